@@ -0,0 +1,101 @@
+//! Indexing benchmarks: `cargo bench --bench indexing`. For the time each source
+//! takes during a real startup, run egg with `RUST_LOG=debug`.
+
+use std::{fs, path::PathBuf};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use egg_cli::bench::{
+    build_index, decode_app_index, encode_app_index, finalize_index, start_menu_urls_in, AppType,
+    ApplicationInfo, IndexOptions,
+};
+
+const SHORTCUTS: usize = 2_000;
+const APPS: usize = 5_000;
+
+/// A Start Menu tree of Steam shortcuts spread over nested vendor folders.
+fn start_menu_fixture() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("egg-bench-start-menu-{}", std::process::id()));
+    for index in 0..SHORTCUTS {
+        let dir = root
+            .join(format!("Vendor {}", index % 40))
+            .join(format!("Suite {}", index % 7));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(format!("Game {index}.url")),
+            format!("[InternetShortcut]\r\nURL=steam://rungameid/{index}\r\n"),
+        )
+        .unwrap();
+    }
+    root
+}
+
+/// An unsorted index with duplicate paths and entries under excluded folders.
+fn synthetic_apps() -> Vec<ApplicationInfo> {
+    (0..APPS)
+        .map(|index| {
+            let folder = match index % 5 {
+                0 => r"C:\Windows\System32",
+                _ => r"C:\Program Files\Vendor",
+            };
+            let name = format!("App {}", (index * 7919) % APPS);
+            ApplicationInfo {
+                id: format!("shell:{index}"),
+                path: format!(r"{folder}\app{}.exe", index % (APPS - 100)),
+                keywords: vec![name.clone()],
+                name,
+                source_path: None,
+                app_type: AppType::Win32,
+                description: None,
+                pinyin_index: None,
+                working_directory: None,
+                arguments: None,
+                icon: None,
+                folded: None,
+            }
+        })
+        .collect()
+}
+
+fn indexing(c: &mut Criterion) {
+    let root = start_menu_fixture();
+    c.bench_function("start menu walk", |b| {
+        b.iter(|| start_menu_urls_in(&root, &[]))
+    });
+    let _ = fs::remove_dir_all(&root);
+
+    let apps = synthetic_apps();
+    let exclusions = vec![
+        r"c:\windows\system32".to_string(),
+        r"c:\windows\syswow64".to_string(),
+        r"c:\windows\winsxs".to_string(),
+    ];
+    c.bench_function("finalize index", |b| {
+        b.iter_batched(
+            || apps.clone(),
+            |mut apps| finalize_index(&mut apps, &exclusions),
+            BatchSize::LargeInput,
+        )
+    });
+
+    // Reading the cached index, as startup does before any source is indexed.
+    let json = serde_json::to_vec(&apps).unwrap();
+    let cached = encode_app_index(&apps).unwrap();
+    c.bench_function("load JSON app cache", |b| {
+        b.iter(|| decode_app_index(&json).unwrap())
+    });
+    c.bench_function("load app cache", |b| {
+        b.iter(|| decode_app_index(&cached).unwrap())
+    });
+
+    // Every source on this machine, as at startup; slow, so fewer samples.
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut cold_start = c.benchmark_group("cold start");
+    cold_start.sample_size(10);
+    cold_start.bench_function("build index", |b| {
+        b.iter(|| runtime.block_on(build_index(IndexOptions::default())))
+    });
+    cold_start.finish();
+}
+
+criterion_group!(benches, indexing);
+criterion_main!(benches);
@@ -0,0 +1,120 @@
+//! Search benchmarks: `cargo bench --bench search`. The same queries run over an
+//! index case-folded at index time, over one whose fields are lowercased during
+//! every search, and through the trigram index large catalogs get.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use egg_cli::bench::{
+    refine_search, AppConfig, AppType, ApplicationInfo, BookmarkEntry, GenerationToken,
+    LaunchHistory, SearchIndexes, TrigramIndex,
+};
+
+const APPS: usize = 5_000;
+const BOOKMARKS: usize = 2_000;
+const QUERIES: [&str; 3] = ["visual", "photo editr", "docs sync"];
+const WORDS: [&str; 16] = [
+    "Visual", "Studio", "Photo", "Editor", "Music", "Player", "Cloud", "Sync", "Game", "Launcher",
+    "Terminal", "Notes", "Mail", "Chat", "Video", "Recorder",
+];
+
+/// Two words picked by `index`, so each pair names a few hundred entries.
+fn words(index: usize) -> String {
+    format!(
+        "{} {}",
+        WORDS[index % WORDS.len()],
+        WORDS[(index / WORDS.len()) % WORDS.len()]
+    )
+}
+
+fn synthetic_apps() -> Vec<ApplicationInfo> {
+    (0..APPS)
+        .map(|index| {
+            let name = format!("{} {index}", words(index));
+            ApplicationInfo {
+                id: format!("shell:{index}"),
+                path: format!(r"C:\Program Files\Vendor {}\app{index}.exe", index % 40),
+                keywords: vec![name.clone(), format!("Vendor {}", index % 40)],
+                name,
+                source_path: None,
+                app_type: AppType::Win32,
+                description: None,
+                pinyin_index: None,
+                working_directory: None,
+                arguments: None,
+                icon: None,
+                folded: None,
+            }
+        })
+        .collect()
+}
+
+fn synthetic_bookmarks() -> Vec<BookmarkEntry> {
+    (0..BOOKMARKS)
+        .map(|index| {
+            let title = format!("{} Docs {index}", words(index * 7));
+            let url = format!("https://doc.rust-lang.org/std/page{index}.html");
+            BookmarkEntry {
+                id: format!("bookmark:{index}"),
+                keywords: vec![title.clone(), url.clone()],
+                title,
+                url,
+                folder_path: Some(format!("Bookmarks Bar/Folder {}", index % 25)),
+                pinyin_index: None,
+                folded: None,
+            }
+        })
+        .collect()
+}
+
+fn searching(c: &mut Criterion) {
+    let config = AppConfig::default();
+    let history = LaunchHistory::default();
+    let apps = synthetic_apps();
+    let bookmarks = synthetic_bookmarks();
+    let mut folded_apps = apps.clone();
+    folded_apps.iter_mut().for_each(ApplicationInfo::fold_case);
+    let mut folded_bookmarks = bookmarks.clone();
+    folded_bookmarks
+        .iter_mut()
+        .for_each(BookmarkEntry::fold_case);
+
+    let trigrams = TrigramIndex::build(&folded_apps, &folded_bookmarks);
+
+    let mut group = c.benchmark_group("search");
+    for (label, indexes) in [
+        (
+            "folded at index time",
+            SearchIndexes::new(&folded_apps, &folded_bookmarks),
+        ),
+        (
+            "lowercased per search",
+            SearchIndexes::new(&apps, &bookmarks),
+        ),
+        (
+            "trigram index",
+            SearchIndexes::new(&folded_apps, &folded_bookmarks).with_trigrams(Some(&trigrams)),
+        ),
+    ] {
+        group.bench_function(label, |b| {
+            b.iter(|| {
+                for query in QUERIES {
+                    refine_search(
+                        query.to_string(),
+                        None,
+                        None,
+                        indexes,
+                        &history,
+                        &config,
+                        &GenerationToken::detached(),
+                    );
+                }
+            })
+        });
+    }
+    group.bench_function("build trigram index", |b| {
+        b.iter(|| TrigramIndex::build(&folded_apps, &folded_bookmarks))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, searching);
+criterion_main!(benches);
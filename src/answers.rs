@@ -0,0 +1,187 @@
+//! Instant answers: queries such as `15kg to lb`, `100 usd to eur` or
+//! `3pm PST in Tokyo` are worked out on the spot and shown in a card above the
+//! results. Each kind of query has its own [`AnswerHandler`]; [`Answers`] asks
+//! them in order and shows the first answer, so another kind is one more
+//! handler in [`Answers::new`].
+
+mod currency;
+mod time_zones;
+mod units;
+
+use chrono::{DateTime, Utc};
+
+use crate::locale::Messages;
+
+pub use currency::ExchangeRates;
+
+/// What the answer card shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    /// The query as understood, e.g. `15 kg`.
+    pub question: String,
+    /// The answer, which Ctrl+Y copies, e.g. `33.0693 lb`; `None` while it
+    /// cannot be worked out yet.
+    pub value: Option<String>,
+    /// Where the answer came from, or why there is none yet.
+    pub note: Option<String>,
+}
+
+/// What handlers may consult besides the query.
+pub struct AnswerContext {
+    pub text: &'static Messages,
+    pub now: DateTime<Utc>,
+    /// `fetch_exchange_rates`: whether exchange rates may be downloaded.
+    pub fetch_rates: bool,
+}
+
+/// Recognizes one kind of query and answers it.
+pub trait AnswerHandler: Send + Sync {
+    /// `None` when `query` is not one this handler understands.
+    fn answer(&self, query: &str, context: &AnswerContext) -> Option<Answer>;
+}
+
+pub struct Answers {
+    handlers: Vec<Box<dyn AnswerHandler>>,
+}
+
+impl Answers {
+    /// Unit, currency and time zone conversion, tried in that order.
+    pub fn new() -> Self {
+        Self {
+            handlers: vec![
+                Box::new(units::UnitConverter),
+                Box::new(currency::CurrencyConverter::default()),
+                Box::new(time_zones::TimeZoneConverter),
+            ],
+        }
+    }
+
+    pub fn answer(&self, query: &str, context: &AnswerContext) -> Option<Answer> {
+        let query = query.trim();
+        if query.is_empty() {
+            return None;
+        }
+        self.handlers
+            .iter()
+            .find_map(|handler| handler.answer(query, context))
+    }
+}
+
+impl Default for Answers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Words that separate what to convert from what to convert it to, most
+/// specific first; `5 in to cm` splits at `to`, not at the inch.
+const SEPARATORS: [&str; 7] = [" to ", " into ", " in ", " as ", "->", "→", "="];
+
+/// Splits `15kg to lb` into `15kg` and `lb`, at the last separator of the first
+/// kind found.
+fn split_conversion(query: &str) -> Option<(&str, &str)> {
+    let lower = query.to_lowercase();
+    // Lowercasing can change byte lengths outside ASCII; only split when it did not.
+    if lower.len() != query.len() {
+        return None;
+    }
+    SEPARATORS.iter().find_map(|separator| {
+        let at = lower.rfind(separator)?;
+        let (from, to) = (query[..at].trim(), query[at + separator.len()..].trim());
+        (!from.is_empty() && !to.is_empty()).then_some((from, to))
+    })
+}
+
+/// Splits `15kg` or `1,500.5 usd` into the amount and what follows it.
+fn split_amount(text: &str) -> Option<(f64, &str)> {
+    let text = text.trim();
+    let end = text
+        .char_indices()
+        .find(|&(index, ch)| {
+            !(ch.is_ascii_digit() || ch == '.' || ch == ',' || (index == 0 && ch == '-'))
+        })
+        .map_or(text.len(), |(index, _)| index);
+    let number: String = text[..end].chars().filter(|&ch| ch != ',').collect();
+    let amount: f64 = number.parse().ok()?;
+    amount.is_finite().then_some((amount, text[end..].trim()))
+}
+
+/// Digits kept by [`format_number`].
+const SIGNIFICANT_DIGITS: i32 = 6;
+
+/// `value` to six significant digits without trailing zeros, switching to
+/// scientific notation for very large and very small numbers.
+fn format_number(value: f64) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return "0".to_string();
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    if !(-6..15).contains(&magnitude) {
+        return format!("{value:.4e}");
+    }
+    let decimals = (SIGNIFICANT_DIGITS - 1 - magnitude).max(0) as usize;
+    let text = format!("{value:.decimals$}");
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::{messages, Language};
+
+    pub(super) fn context() -> AnswerContext {
+        AnswerContext {
+            text: messages(Language::En),
+            now: DateTime::from_timestamp(1_792_152_000, 0).unwrap(),
+            fetch_rates: false,
+        }
+    }
+
+    #[test]
+    fn conversions_split_at_the_separator() {
+        assert_eq!(split_conversion("15kg to lb"), Some(("15kg", "lb")));
+        assert_eq!(split_conversion("5 in to cm"), Some(("5 in", "cm")));
+        assert_eq!(
+            split_conversion("3pm PST in Tokyo"),
+            Some(("3pm PST", "Tokyo"))
+        );
+        assert_eq!(split_conversion("10 km->mi"), Some(("10 km", "mi")));
+        assert_eq!(split_conversion("to lb"), None);
+        assert_eq!(split_conversion("notepad"), None);
+    }
+
+    #[test]
+    fn amounts_and_numbers_read_and_print_plainly() {
+        assert_eq!(split_amount("15kg"), Some((15.0, "kg")));
+        assert_eq!(split_amount("1,500.5 usd"), Some((1500.5, "usd")));
+        assert_eq!(split_amount("-40 f"), Some((-40.0, "f")));
+        assert_eq!(split_amount("kg"), None);
+
+        assert_eq!(format_number(33.069_339_327), "33.0693");
+        assert_eq!(format_number(1_000_000.0), "1000000");
+        assert_eq!(format_number(0.5), "0.5");
+        assert_eq!(format_number(-0.000_000_1), "-1.0000e-7");
+    }
+
+    #[test]
+    fn first_handler_to_understand_the_query_answers() {
+        let answers = Answers::new();
+        let answer = answers.answer("15kg to lb", &context()).unwrap();
+        assert_eq!(answer.question, "15 kg");
+        assert_eq!(answer.value.as_deref(), Some("33.0693 lb"));
+
+        let answer = answers.answer("3pm PST in Tokyo", &context()).unwrap();
+        assert_eq!(answer.value.as_deref(), Some("07:00 JST"));
+
+        assert!(answers.answer("notepad", &context()).is_none());
+        assert!(answers.answer("15kg to usd", &context()).is_none());
+    }
+}
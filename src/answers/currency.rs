@@ -0,0 +1,288 @@
+//! `100 usd to eur`, `50 欧元 in 人民币`: currency conversion at daily reference
+//! rates. Rates are downloaded from open.er-api.com once a day, only with
+//! `fetch_exchange_rates` on, and kept in the cache folder between runs.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use chrono::DateTime;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use super::{format_number, split_amount, split_conversion, Answer, AnswerContext, AnswerHandler};
+use crate::{cache, locale::fill};
+
+const RATES_URL: &str = "https://open.er-api.com/v6/latest/USD";
+const RATES_SOURCE: &str = "open.er-api.com";
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Saved rates older than this are refreshed; they are still used meanwhile.
+const MAX_AGE_SECS: i64 = 24 * 60 * 60;
+
+/// Currencies recognized in queries. Other codes the rates cover are ignored, so
+/// that ordinary three-letter words do not turn into conversions.
+const CODES: [&str; 36] = [
+    "USD", "EUR", "GBP", "JPY", "CNY", "HKD", "TWD", "KRW", "SGD", "INR", "AUD", "NZD", "CAD",
+    "CHF", "SEK", "NOK", "DKK", "PLN", "CZK", "HUF", "RUB", "TRY", "BRL", "MXN", "ARS", "CLP",
+    "ZAR", "AED", "SAR", "ILS", "THB", "MYR", "IDR", "PHP", "VND", "UAH",
+];
+
+/// Names and signs for the common currencies, lowercase.
+const NAMES: [(&str, &str); 22] = [
+    ("$", "USD"),
+    ("dollar", "USD"),
+    ("美元", "USD"),
+    ("€", "EUR"),
+    ("euro", "EUR"),
+    ("欧元", "EUR"),
+    ("£", "GBP"),
+    ("英镑", "GBP"),
+    ("yen", "JPY"),
+    ("日元", "JPY"),
+    ("yuan", "CNY"),
+    ("rmb", "CNY"),
+    ("元", "CNY"),
+    ("人民币", "CNY"),
+    ("港币", "HKD"),
+    ("港元", "HKD"),
+    ("新台币", "TWD"),
+    ("won", "KRW"),
+    ("韩元", "KRW"),
+    ("₩", "KRW"),
+    ("₹", "INR"),
+    ("rupee", "INR"),
+];
+
+fn find_currency(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase();
+    let singular = name.strip_suffix('s').unwrap_or(&name);
+    CODES
+        .into_iter()
+        .find(|code| code.eq_ignore_ascii_case(&name))
+        .or_else(|| {
+            NAMES
+                .into_iter()
+                .find(|(alias, _)| *alias == name || *alias == singular)
+                .map(|(_, code)| code)
+        })
+}
+
+/// Reads `100 usd`, `100usd` and `$100`.
+fn split_money(text: &str) -> Option<(f64, &'static str)> {
+    let text = text.trim();
+    if let Some((amount, rest)) = split_amount(text) {
+        return Some((amount, find_currency(rest)?));
+    }
+    let sign_end = text.find(|ch: char| ch.is_ascii_digit())?;
+    let (amount, rest) = split_amount(&text[sign_end..])?;
+    rest.is_empty()
+        .then_some(())
+        .and_then(|()| find_currency(&text[..sign_end]))
+        .map(|code| (amount, code))
+}
+
+/// Units of each currency per US dollar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    /// When the source last updated them, in Unix seconds.
+    pub updated: i64,
+    /// When egg downloaded them, in Unix seconds.
+    pub fetched: i64,
+    pub rates: HashMap<String, f64>,
+}
+
+impl ExchangeRates {
+    fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        let from = self.rates.get(from).filter(|rate| **rate > 0.0)?;
+        let to = self.rates.get(to)?;
+        Some(amount / from * to)
+    }
+}
+
+#[derive(Deserialize)]
+struct RatesResponse {
+    result: String,
+    time_last_update_unix: i64,
+    rates: HashMap<String, f64>,
+}
+
+fn fetch_rates(now: i64) -> Result<ExchangeRates, String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(TIMEOUT)
+        .user_agent(concat!("egg-cli/", env!("CARGO_PKG_VERSION")))
+        .build();
+    let response = agent
+        .get(RATES_URL)
+        .call()
+        .map_err(|err| format!("无法获取汇率: {err}"))?;
+    let body: RatesResponse = serde_json::from_reader(response.into_reader())
+        .map_err(|err| format!("无法解析汇率: {err}"))?;
+    if body.result != "success" {
+        return Err(format!("汇率服务返回 {}", body.result));
+    }
+    Ok(ExchangeRates {
+        updated: body.time_last_update_unix,
+        fetched: now,
+        rates: body.rates,
+    })
+}
+
+#[derive(Default)]
+struct RateState {
+    rates: Option<ExchangeRates>,
+    /// The saved rates have been read.
+    loaded: bool,
+    fetching: bool,
+    /// A download failed this session; it is not retried until egg restarts.
+    failed: bool,
+}
+
+#[derive(Default)]
+pub(super) struct CurrencyConverter {
+    state: Arc<Mutex<RateState>>,
+}
+
+impl CurrencyConverter {
+    /// Runs `read` on the rates, or says why there are none. Rates are read from
+    /// disk the first time a currency is converted, and downloaded in the
+    /// background when missing or a day old.
+    fn with_rates<R>(
+        &self,
+        context: &AnswerContext,
+        read: impl FnOnce(&ExchangeRates) -> R,
+    ) -> Result<R, &'static str> {
+        let mut state = self.state.lock().unwrap();
+        if !state.loaded {
+            state.loaded = true;
+            state.rates = cache::load_exchange_rates();
+        }
+        let now = context.now.timestamp();
+        let stale = state
+            .rates
+            .as_ref()
+            .is_none_or(|rates| now - rates.fetched > MAX_AGE_SECS);
+        if stale && context.fetch_rates && !state.fetching && !state.failed {
+            state.fetching = true;
+            let shared = self.state.clone();
+            thread::spawn(move || {
+                let fetched = fetch_rates(now);
+                let mut state = shared.lock().unwrap();
+                state.fetching = false;
+                match fetched {
+                    Ok(rates) => {
+                        debug!("fetched {} exchange rates", rates.rates.len());
+                        if let Err(err) = cache::save_exchange_rates(&rates) {
+                            warn!("failed to save exchange rates: {err}");
+                        }
+                        state.rates = Some(rates);
+                    }
+                    Err(err) => {
+                        warn!("{err}");
+                        state.failed = true;
+                    }
+                }
+            });
+        }
+        let text = context.text;
+        match &state.rates {
+            Some(rates) => Ok(read(rates)),
+            None if state.fetching => Err(text.rates_loading),
+            None if !context.fetch_rates => Err(text.rates_disabled),
+            None => Err(text.rates_unavailable),
+        }
+    }
+}
+
+impl AnswerHandler for CurrencyConverter {
+    fn answer(&self, query: &str, context: &AnswerContext) -> Option<Answer> {
+        let (from, to) = split_conversion(query)?;
+        let (amount, from) = split_money(from)?;
+        let to = find_currency(to)?;
+        let question = format!("{} {from}", format_money(amount));
+        let converted = self.with_rates(context, |rates| {
+            (rates.convert(amount, from, to), rates.updated)
+        });
+        let (value, note) = match converted {
+            Ok((converted, updated)) => {
+                let updated = DateTime::from_timestamp(updated, 0)
+                    .map(|at| at.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                let value = converted.map(|converted| format!("{} {to}", format_money(converted)));
+                let note = fill(context.text.rates_source, &[RATES_SOURCE, &updated]);
+                (value, note)
+            }
+            Err(reason) => (None, reason.to_string()),
+        };
+        Some(Answer {
+            question,
+            value,
+            note: Some(note),
+        })
+    }
+}
+
+/// Cents for amounts of a unit or more, significant digits below that.
+fn format_money(amount: f64) -> String {
+    if amount.abs() >= 1.0 {
+        format!("{amount:.2}")
+    } else {
+        format_number(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::context;
+    use super::*;
+
+    fn converter(rates: &[(&str, f64)]) -> CurrencyConverter {
+        let state = RateState {
+            rates: Some(ExchangeRates {
+                updated: 1_792_022_400,
+                fetched: context().now.timestamp(),
+                rates: rates
+                    .iter()
+                    .map(|(code, rate)| (code.to_string(), *rate))
+                    .collect(),
+            }),
+            loaded: true,
+            ..RateState::default()
+        };
+        CurrencyConverter {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    #[test]
+    fn converts_through_the_dollar_rates() {
+        let converter = converter(&[("USD", 1.0), ("EUR", 0.9), ("CNY", 7.2)]);
+        let answer = converter.answer("100 usd to eur", &context()).unwrap();
+        assert_eq!(answer.question, "100.00 USD");
+        assert_eq!(answer.value.as_deref(), Some("90.00 EUR"));
+        assert_eq!(
+            answer.note.as_deref(),
+            Some("Exchange rates from open.er-api.com, updated 2026-10-15")
+        );
+
+        let answer = converter.answer("€45 in 人民币", &context()).unwrap();
+        assert_eq!(answer.value.as_deref(), Some("360.00 CNY"));
+        assert!(converter.answer("100 usd to xyz", &context()).is_none());
+        assert!(converter.answer("100 kg to eur", &context()).is_none());
+    }
+
+    #[test]
+    fn explains_missing_rates_instead_of_fetching_them() {
+        let converter = CurrencyConverter {
+            state: Arc::new(Mutex::new(RateState {
+                loaded: true,
+                ..RateState::default()
+            })),
+        };
+        let answer = converter.answer("100 usd to eur", &context()).unwrap();
+        assert_eq!(answer.value, None);
+        assert_eq!(answer.note.as_deref(), Some(context().text.rates_disabled));
+    }
+}
@@ -0,0 +1,250 @@
+//! `3pm PST in Tokyo`, `15:30 in London`, `now in New York`: a time of day today,
+//! in the given or the local time zone, shown in another. Zones are found by
+//! common abbreviation, IANA name (`Asia/Tokyo`) or the city in that name.
+
+use chrono::{DateTime, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
+
+use super::{split_conversion, Answer, AnswerContext, AnswerHandler};
+use crate::locale::Messages;
+
+/// Abbreviations name the zone that uses them, so `PST` in summer reads as `PDT`.
+const ABBREVIATIONS: [(&str, Tz); 34] = [
+    ("pst", Tz::America__Los_Angeles),
+    ("pdt", Tz::America__Los_Angeles),
+    ("pt", Tz::America__Los_Angeles),
+    ("mst", Tz::America__Denver),
+    ("mdt", Tz::America__Denver),
+    ("mt", Tz::America__Denver),
+    ("cst", Tz::America__Chicago),
+    ("cdt", Tz::America__Chicago),
+    ("ct", Tz::America__Chicago),
+    ("est", Tz::America__New_York),
+    ("edt", Tz::America__New_York),
+    ("et", Tz::America__New_York),
+    ("akst", Tz::America__Anchorage),
+    ("hst", Tz::Pacific__Honolulu),
+    ("utc", Tz::UTC),
+    ("gmt", Tz::UTC),
+    ("bst", Tz::Europe__London),
+    ("cet", Tz::Europe__Paris),
+    ("cest", Tz::Europe__Paris),
+    ("eet", Tz::Europe__Athens),
+    ("eest", Tz::Europe__Athens),
+    ("msk", Tz::Europe__Moscow),
+    ("ist", Tz::Asia__Kolkata),
+    ("sgt", Tz::Asia__Singapore),
+    ("hkt", Tz::Asia__Hong_Kong),
+    ("jst", Tz::Asia__Tokyo),
+    ("kst", Tz::Asia__Seoul),
+    ("aest", Tz::Australia__Sydney),
+    ("aedt", Tz::Australia__Sydney),
+    ("nzst", Tz::Pacific__Auckland),
+    ("nzdt", Tz::Pacific__Auckland),
+    // China Standard Time would clash with US Central, so Beijing goes by name.
+    ("beijing", Tz::Asia__Shanghai),
+    ("san francisco", Tz::America__Los_Angeles),
+    ("seattle", Tz::America__Los_Angeles),
+];
+
+/// Chinese names of the cities people ask about most.
+const CITY_NAMES: [(&str, Tz); 16] = [
+    ("北京", Tz::Asia__Shanghai),
+    ("上海", Tz::Asia__Shanghai),
+    ("香港", Tz::Asia__Hong_Kong),
+    ("台北", Tz::Asia__Taipei),
+    ("东京", Tz::Asia__Tokyo),
+    ("首尔", Tz::Asia__Seoul),
+    ("新加坡", Tz::Asia__Singapore),
+    ("悉尼", Tz::Australia__Sydney),
+    ("伦敦", Tz::Europe__London),
+    ("巴黎", Tz::Europe__Paris),
+    ("柏林", Tz::Europe__Berlin),
+    ("莫斯科", Tz::Europe__Moscow),
+    ("纽约", Tz::America__New_York),
+    ("洛杉矶", Tz::America__Los_Angeles),
+    ("旧金山", Tz::America__Los_Angeles),
+    ("温哥华", Tz::America__Vancouver),
+];
+
+fn find_zone(name: &str) -> Option<Tz> {
+    let name = name.trim().replace('_', " ").to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    let listed = |table: &[(&str, Tz)]| {
+        table
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map(|(_, zone)| *zone)
+    };
+    listed(&ABBREVIATIONS)
+        .or_else(|| listed(&CITY_NAMES))
+        .or_else(|| {
+            TZ_VARIANTS.iter().copied().find(|zone| {
+                let full = zone.name().replace('_', " ");
+                let city = full.rsplit('/').next().unwrap_or(&full);
+                full.eq_ignore_ascii_case(&name) || city.eq_ignore_ascii_case(&name)
+            })
+        })
+}
+
+/// `3pm`, `3:30 pm`, `15:30`, `noon` or `midnight`, followed by the rest of the
+/// text. A bare number is not a time.
+fn split_time(text: &str) -> Option<(NaiveTime, &str)> {
+    let lower = text.to_ascii_lowercase();
+    for (word, time) in [("noon", (12, 0)), ("midnight", (0, 0))] {
+        if let Some(rest) = lower
+            .strip_prefix(word)
+            .filter(|rest| !rest.starts_with(|ch: char| ch.is_alphanumeric()))
+        {
+            let rest = &text[text.len() - rest.len()..];
+            return Some((NaiveTime::from_hms_opt(time.0, time.1, 0)?, rest.trim()));
+        }
+    }
+    let digits_end = lower
+        .find(|ch: char| !(ch.is_ascii_digit() || ch == ':'))
+        .unwrap_or(lower.len());
+    let (clock, after) = lower.split_at(digits_end);
+    let after = after.trim_start();
+    let (meridiem, rest) = if let Some(rest) = after.strip_prefix("am") {
+        (Some(false), rest)
+    } else if let Some(rest) = after.strip_prefix("pm") {
+        (Some(true), rest)
+    } else {
+        (None, after)
+    };
+    // `3 pmx` is a word, not a time.
+    if meridiem.is_some() && rest.starts_with(|ch: char| ch.is_alphanumeric()) {
+        return None;
+    }
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => {
+            (hour.parse::<u32>().ok()?, minute.parse().ok()?)
+        }
+        Some(_) => return None,
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+        None => hour,
+    };
+    let rest = &text[text.len() - rest.len()..];
+    Some((NaiveTime::from_hms_opt(hour, minute, 0)?, rest.trim()))
+}
+
+/// Where a time of day is read: a named zone, or the computer's own.
+enum Zone {
+    Named(Tz),
+    Local,
+}
+
+impl Zone {
+    fn today(&self, now: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Self::Named(zone) => now.with_timezone(zone).date_naive(),
+            Self::Local => now.with_timezone(&Local).date_naive(),
+        }
+    }
+
+    /// The instant `time` names here; `None` in a daylight saving gap.
+    fn instant(&self, time: NaiveDateTime) -> Option<DateTime<Utc>> {
+        fn earliest<T: TimeZone>(result: LocalResult<DateTime<T>>) -> Option<DateTime<Utc>> {
+            result.earliest().map(|at| at.with_timezone(&Utc))
+        }
+        match self {
+            Self::Named(zone) => earliest(zone.from_local_datetime(&time)),
+            Self::Local => earliest(Local.from_local_datetime(&time)),
+        }
+    }
+
+    /// `15:00 PDT`, or `15:00 local time`.
+    fn show(&self, at: DateTime<Utc>, text: &Messages) -> String {
+        match self {
+            Self::Named(zone) => at.with_timezone(zone).format("%H:%M %Z").to_string(),
+            Self::Local => format!(
+                "{} {}",
+                at.with_timezone(&Local).format("%H:%M"),
+                text.local_time
+            ),
+        }
+    }
+}
+
+pub(super) struct TimeZoneConverter;
+
+impl AnswerHandler for TimeZoneConverter {
+    fn answer(&self, query: &str, context: &AnswerContext) -> Option<Answer> {
+        let (from, to) = split_conversion(query)?;
+        let target = find_zone(to)?;
+        let (question, at) = if ["now", "time", "现在", "时间"]
+            .iter()
+            .any(|word| from.eq_ignore_ascii_case(word))
+        {
+            (context.text.time_now.to_string(), context.now)
+        } else {
+            let (time, zone) = split_time(from)?;
+            let zone = if zone.is_empty() {
+                Zone::Local
+            } else {
+                Zone::Named(find_zone(zone)?)
+            };
+            let at = zone.instant(zone.today(context.now).and_time(time))?;
+            (zone.show(at, context.text), at)
+        };
+        let there = at.with_timezone(&target);
+        Some(Answer {
+            question,
+            value: Some(there.format("%H:%M %Z").to_string()),
+            note: Some(format!("{} · {}", target.name(), there.format("%Y-%m-%d"))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::context;
+    use super::*;
+
+    fn convert(query: &str) -> Option<Answer> {
+        TimeZoneConverter.answer(query, &context())
+    }
+
+    #[test]
+    fn times_read_in_twelve_and_twenty_four_hours() {
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        assert_eq!(split_time("3pm PST"), Some((time(15, 0), "PST")));
+        assert_eq!(split_time("12 am"), Some((time(0, 0), "")));
+        assert_eq!(split_time("9:05AM tokyo"), Some((time(9, 5), "tokyo")));
+        assert_eq!(split_time("15:30"), Some((time(15, 30), "")));
+        assert_eq!(split_time("noon utc"), Some((time(12, 0), "utc")));
+        assert_eq!(split_time("15"), None);
+        assert_eq!(split_time("13pm"), None);
+        assert_eq!(split_time("3 pmx"), None);
+    }
+
+    #[test]
+    fn zones_match_abbreviations_names_and_cities() {
+        assert_eq!(find_zone("PST"), Some(Tz::America__Los_Angeles));
+        assert_eq!(find_zone("Tokyo"), Some(Tz::Asia__Tokyo));
+        assert_eq!(find_zone("new york"), Some(Tz::America__New_York));
+        assert_eq!(find_zone("europe/berlin"), Some(Tz::Europe__Berlin));
+        assert_eq!(find_zone("北京"), Some(Tz::Asia__Shanghai));
+        assert_eq!(find_zone("atlantis"), None);
+    }
+
+    #[test]
+    fn converts_a_time_of_day_between_zones() {
+        let answer = convert("3pm PST in Tokyo").unwrap();
+        assert_eq!(answer.question, "15:00 PDT");
+        assert_eq!(answer.value.as_deref(), Some("07:00 JST"));
+        assert_eq!(answer.note.as_deref(), Some("Asia/Tokyo · 2026-10-17"));
+
+        let answer = convert("now in london").unwrap();
+        assert_eq!(answer.value.as_deref(), Some("13:00 BST"));
+        assert!(convert("3pm in atlantis").is_none());
+        assert!(convert("notes in onenote").is_none());
+    }
+}
@@ -0,0 +1,223 @@
+//! `15kg to lb`, `100 f in c`, `2 GiB to MB`: conversions between units of the
+//! same kind. Volumes are US customary.
+
+use super::{format_number, split_amount, split_conversion, Answer, AnswerContext, AnswerHandler};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Temperature,
+    Data,
+    Time,
+    Speed,
+}
+
+struct Unit {
+    symbol: &'static str,
+    /// Lowercase spellings besides the symbol; a trailing `s` is also accepted
+    /// on names longer than two letters.
+    names: &'static [&'static str],
+    dimension: Dimension,
+    /// `(value + offset) * factor` is the value in the dimension's base unit.
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(
+    symbol: &'static str,
+    names: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+) -> Unit {
+    Unit {
+        symbol,
+        names,
+        dimension,
+        factor,
+        offset: 0.0,
+    }
+}
+
+const UNITS: &[Unit] = &[
+    unit(
+        "mm",
+        &["millimeter", "millimetre", "毫米"],
+        Dimension::Length,
+        0.001,
+    ),
+    unit(
+        "cm",
+        &["centimeter", "centimetre", "厘米"],
+        Dimension::Length,
+        0.01,
+    ),
+    unit("m", &["meter", "metre", "米"], Dimension::Length, 1.0),
+    unit(
+        "km",
+        &["kilometer", "kilometre", "公里", "千米"],
+        Dimension::Length,
+        1000.0,
+    ),
+    unit(
+        "in",
+        &["inch", "inches", "\"", "英寸"],
+        Dimension::Length,
+        0.0254,
+    ),
+    unit(
+        "ft",
+        &["foot", "feet", "'", "英尺"],
+        Dimension::Length,
+        0.3048,
+    ),
+    unit("yd", &["yard", "码"], Dimension::Length, 0.9144),
+    unit("mi", &["mile", "英里"], Dimension::Length, 1609.344),
+    unit("nmi", &["nautical mile", "海里"], Dimension::Length, 1852.0),
+    unit("mg", &["milligram", "毫克"], Dimension::Mass, 0.000_001),
+    unit("g", &["gram", "克"], Dimension::Mass, 0.001),
+    unit(
+        "kg",
+        &["kilogram", "kilo", "公斤", "千克"],
+        Dimension::Mass,
+        1.0,
+    ),
+    unit("t", &["tonne", "metric ton", "吨"], Dimension::Mass, 1000.0),
+    unit("oz", &["ounce", "盎司"], Dimension::Mass, 0.028_349_523_125),
+    unit("lb", &["lbs", "pound", "磅"], Dimension::Mass, 0.453_592_37),
+    unit("st", &["stone"], Dimension::Mass, 6.350_293_18),
+    unit("斤", &["jin"], Dimension::Mass, 0.5),
+    unit(
+        "ml",
+        &["milliliter", "millilitre", "毫升"],
+        Dimension::Volume,
+        0.001,
+    ),
+    unit("l", &["liter", "litre", "升"], Dimension::Volume, 1.0),
+    unit(
+        "tsp",
+        &["teaspoon"],
+        Dimension::Volume,
+        0.004_928_921_593_75,
+    ),
+    unit(
+        "tbsp",
+        &["tablespoon"],
+        Dimension::Volume,
+        0.014_786_764_781_25,
+    ),
+    unit(
+        "fl oz",
+        &["floz", "fluid ounce"],
+        Dimension::Volume,
+        0.029_573_529_562_5,
+    ),
+    unit("cup", &[], Dimension::Volume, 0.236_588_236_5),
+    unit("pt", &["pint"], Dimension::Volume, 0.473_176_473),
+    unit("qt", &["quart"], Dimension::Volume, 0.946_352_946),
+    unit("gal", &["gallon", "加仑"], Dimension::Volume, 3.785_411_784),
+    Unit {
+        symbol: "°C",
+        names: &["c", "celsius", "摄氏度"],
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        symbol: "°F",
+        names: &["f", "fahrenheit", "华氏度"],
+        dimension: Dimension::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 459.67,
+    },
+    unit("K", &["kelvin"], Dimension::Temperature, 1.0),
+    unit("bit", &[], Dimension::Data, 0.125),
+    unit("B", &["byte", "字节"], Dimension::Data, 1.0),
+    unit("KB", &["kilobyte"], Dimension::Data, 1e3),
+    unit("MB", &["megabyte"], Dimension::Data, 1e6),
+    unit("GB", &["gigabyte"], Dimension::Data, 1e9),
+    unit("TB", &["terabyte"], Dimension::Data, 1e12),
+    unit("KiB", &["kibibyte"], Dimension::Data, 1024.0),
+    unit("MiB", &["mebibyte"], Dimension::Data, 1_048_576.0),
+    unit("GiB", &["gibibyte"], Dimension::Data, 1_073_741_824.0),
+    unit("TiB", &["tebibyte"], Dimension::Data, 1_099_511_627_776.0),
+    unit("ms", &["millisecond", "毫秒"], Dimension::Time, 0.001),
+    unit("s", &["sec", "second", "秒"], Dimension::Time, 1.0),
+    unit("min", &["minute", "分钟"], Dimension::Time, 60.0),
+    unit("h", &["hr", "hour", "小时"], Dimension::Time, 3600.0),
+    unit("d", &["day", "天"], Dimension::Time, 86_400.0),
+    unit("wk", &["week", "周"], Dimension::Time, 604_800.0),
+    unit("m/s", &["mps"], Dimension::Speed, 1.0),
+    unit("km/h", &["kmh", "kph"], Dimension::Speed, 1.0 / 3.6),
+    unit("mph", &[], Dimension::Speed, 0.447_04),
+    unit("kn", &["knot", "节"], Dimension::Speed, 0.514_444),
+];
+
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    let name = name.trim().trim_start_matches('°').to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    let matches = |unit: &Unit, name: &str| {
+        unit.symbol.to_lowercase().trim_start_matches('°') == name || unit.names.contains(&name)
+    };
+    UNITS.iter().find(|unit| matches(unit, &name)).or_else(|| {
+        let singular = name
+            .strip_suffix('s')
+            .filter(|stem| stem.chars().count() > 2)?;
+        UNITS.iter().find(|unit| matches(unit, singular))
+    })
+}
+
+pub(super) struct UnitConverter;
+
+impl AnswerHandler for UnitConverter {
+    fn answer(&self, query: &str, _: &AnswerContext) -> Option<Answer> {
+        let (from, to) = split_conversion(query)?;
+        let (amount, from) = split_amount(from)?;
+        let (from, to) = (find_unit(from)?, find_unit(to)?);
+        if from.dimension != to.dimension {
+            return None;
+        }
+        let base = (amount + from.offset) * from.factor;
+        let converted = base / to.factor - to.offset;
+        Some(Answer {
+            question: format!("{} {}", format_number(amount), from.symbol),
+            value: Some(format!("{} {}", format_number(converted), to.symbol)),
+            note: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::context;
+    use super::*;
+
+    fn convert(query: &str) -> Option<String> {
+        UnitConverter.answer(query, &context())?.value
+    }
+
+    #[test]
+    fn converts_within_a_dimension() {
+        assert_eq!(convert("15kg to lb").as_deref(), Some("33.0693 lb"));
+        assert_eq!(convert("5 in to cm").as_deref(), Some("12.7 cm"));
+        assert_eq!(convert("10 miles in km").as_deref(), Some("16.0934 km"));
+        assert_eq!(convert("2 GiB to MB").as_deref(), Some("2147.48 MB"));
+        assert_eq!(convert("1 fl oz to ml").as_deref(), Some("29.5735 ml"));
+        assert_eq!(convert("3 公斤 to 斤").as_deref(), Some("6 斤"));
+        assert_eq!(convert("15kg to km"), None);
+        assert_eq!(convert("15 parsecs to km"), None);
+    }
+
+    #[test]
+    fn temperatures_keep_their_offsets() {
+        assert_eq!(convert("100 f to c").as_deref(), Some("37.7778 °C"));
+        assert_eq!(convert("-40 °C in °F").as_deref(), Some("-40 °F"));
+        assert_eq!(
+            convert("0 kelvin to celsius").as_deref(),
+            Some("-273.15 °C")
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! DuckDuckGo-style bangs: `!w rust` searches Wikipedia for `rust`, and `!gh`
+//! alone opens GitHub. The bang may also end the query, as in `rust !w`. The
+//! bundled table is extended, or overridden, by `bangs` in `settings.json`,
+//! which maps a bang to a URL with `{}` where the search terms go.
+
+use crate::config::AppConfig;
+
+/// Bang, site name and search URL.
+const BUNDLED: &[(&str, &str, &str)] = &[
+    ("g", "Google", "https://www.google.com/search?q={}"),
+    ("b", "Bing", "https://www.bing.com/search?q={}"),
+    ("ddg", "DuckDuckGo", "https://duckduckgo.com/?q={}"),
+    (
+        "w",
+        "Wikipedia",
+        "https://en.wikipedia.org/wiki/Special:Search?search={}",
+    ),
+    ("gh", "GitHub", "https://github.com/search?q={}"),
+    (
+        "so",
+        "Stack Overflow",
+        "https://stackoverflow.com/search?q={}",
+    ),
+    (
+        "yt",
+        "YouTube",
+        "https://www.youtube.com/results?search_query={}",
+    ),
+    ("a", "Amazon", "https://www.amazon.com/s?k={}"),
+    ("r", "Reddit", "https://www.reddit.com/search/?q={}"),
+    ("m", "Google Maps", "https://www.google.com/maps/search/{}"),
+    ("mdn", "MDN", "https://developer.mozilla.org/search?q={}"),
+    ("crates", "crates.io", "https://crates.io/search?q={}"),
+    (
+        "docs",
+        "Docs.rs",
+        "https://docs.rs/releases/search?query={}",
+    ),
+    ("npm", "npm", "https://www.npmjs.com/search?q={}"),
+    ("pypi", "PyPI", "https://pypi.org/search/?q={}"),
+    ("bd", "百度", "https://www.baidu.com/s?wd={}"),
+    ("zh", "知乎", "https://www.zhihu.com/search?q={}"),
+    (
+        "bili",
+        "哔哩哔哩",
+        "https://search.bilibili.com/all?keyword={}",
+    ),
+];
+
+/// The bang of a query that starts or ends with one, without its `!`, and the
+/// rest of the query.
+pub fn bang_query(query: &str) -> Option<(&str, &str)> {
+    let query = query.trim();
+    let (bang, terms) = match query.strip_prefix('!') {
+        Some(rest) => rest.split_once(char::is_whitespace).unwrap_or((rest, "")),
+        None => {
+            let (terms, last) = query.rsplit_once(char::is_whitespace)?;
+            (last.strip_prefix('!')?, terms)
+        }
+    };
+    let is_bang = !bang.is_empty()
+        && bang
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || ch == '-' || ch == '_' || ch == '.');
+    is_bang.then(|| (bang, terms.trim()))
+}
+
+/// Where `bang` leads: the site's name and its URL for `terms`, or its front
+/// page when there are none. `None` for bangs neither table knows.
+pub fn resolve_bang(bang: &str, terms: &str, config: &AppConfig) -> Option<(String, String)> {
+    let custom = config.bangs.iter().find(|(name, _)| {
+        name.trim()
+            .trim_start_matches('!')
+            .eq_ignore_ascii_case(bang)
+    });
+    let (site, template) = match custom {
+        Some((_, template)) => (host(template).to_string(), template.trim()),
+        None => BUNDLED
+            .iter()
+            .find(|(name, _, _)| name.eq_ignore_ascii_case(bang))
+            .map(|(_, site, template)| (site.to_string(), *template))?,
+    };
+    let url = if terms.is_empty() {
+        front_page(template).to_string()
+    } else if template.contains("{}") {
+        template.replace("{}", &urlencoding::encode(terms))
+    } else {
+        format!("{template}{}", urlencoding::encode(terms))
+    };
+    Some((site, url))
+}
+
+/// `https://host/` of a URL template.
+fn front_page(template: &str) -> &str {
+    let host_start = template.find("://").map_or(0, |at| at + 3);
+    match template[host_start..].find('/') {
+        Some(slash) => &template[..host_start + slash + 1],
+        None => template,
+    }
+}
+
+fn host(template: &str) -> &str {
+    front_page(template)
+        .split("://")
+        .last()
+        .unwrap_or(template)
+        .trim_end_matches('/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bangs_lead_straight_to_the_site() {
+        assert_eq!(bang_query("!w rust lang"), Some(("w", "rust lang")));
+        assert_eq!(bang_query("rust lang !gh"), Some(("gh", "rust lang")));
+        assert_eq!(bang_query("!gh"), Some(("gh", "")));
+        assert_eq!(bang_query("hello!"), None);
+        assert_eq!(bang_query("! rust"), None);
+
+        let mut config = AppConfig::default();
+        assert_eq!(
+            resolve_bang("W", "rust lang", &config),
+            Some((
+                "Wikipedia".to_string(),
+                "https://en.wikipedia.org/wiki/Special:Search?search=rust%20lang".to_string()
+            ))
+        );
+        assert_eq!(
+            resolve_bang("gh", "", &config),
+            Some(("GitHub".to_string(), "https://github.com/".to_string()))
+        );
+        assert_eq!(resolve_bang("nope", "rust", &config), None);
+
+        config.bangs.insert(
+            "!gh".to_string(),
+            "https://git.example.com/explore?q={}".to_string(),
+        );
+        assert_eq!(
+            resolve_bang("gh", "egg", &config),
+            Some((
+                "git.example.com".to_string(),
+                "https://git.example.com/explore?q=egg".to_string()
+            ))
+        );
+    }
+}
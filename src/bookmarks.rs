@@ -4,13 +4,19 @@ use std::{
 };
 
 use log::{debug, warn};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha1::{Digest, Sha1};
+#[cfg(windows)]
 use winreg::{enums::*, RegKey};
 
-use crate::text_utils::build_pinyin_index;
+use crate::{
+    config::BookmarkBrowser,
+    locale::{messages, Language},
+    text_utils::build_pinyin_index,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BookmarkEntry {
     pub id: String,
     pub title: String,
@@ -18,48 +24,107 @@ pub struct BookmarkEntry {
     pub folder_path: Option<String>,
     pub keywords: Vec<String>,
     pub pinyin_index: Option<String>,
+    /// Lowercase copies of the matched fields; `None` until [`Self::fold_case`].
+    #[serde(skip)]
+    pub folded: Option<FoldedBookmark>,
 }
 
-/// Loads Chromium-based browser bookmark entries from detected profiles.
-pub fn load_chrome_bookmarks() -> Vec<BookmarkEntry> {
-    let mut all_entries = Vec::new();
+impl BookmarkEntry {
+    /// Fills in [`Self::folded`], so searches compare without lowercasing.
+    pub fn fold_case(&mut self) {
+        self.folded = Some(FoldedBookmark {
+            title: self.title.to_ascii_lowercase(),
+            folder_path: self.folder_path.as_deref().map(str::to_ascii_lowercase),
+            url: self.url.to_ascii_lowercase(),
+            keywords: self
+                .keywords
+                .iter()
+                .map(|keyword| keyword.to_ascii_lowercase())
+                .collect(),
+        });
+    }
+}
 
-    for profile in bookmark_profile_dirs() {
-        let display_name = profile.label;
-        let bookmarks_path = profile.dir.join("Bookmarks");
-        if !bookmarks_path.is_file() {
-            continue;
-        }
+/// A bookmark's matched fields lowercased once when it is loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldedBookmark {
+    pub title: String,
+    pub folder_path: Option<String>,
+    pub url: String,
+    pub keywords: Vec<String>,
+}
 
-        match fs::read_to_string(&bookmarks_path) {
-            Ok(content) => match serde_json::from_str::<Value>(&content) {
-                Ok(json) => {
-                    collect_entries_from_file(&json, &display_name, &mut all_entries);
-                }
-                Err(err) => warn!(
-                    "failed to parse Chrome bookmarks {:?}: {err}",
-                    bookmarks_path
-                ),
-            },
-            Err(err) => warn!(
-                "failed to read Chrome bookmarks {:?}: {err}",
-                bookmarks_path
-            ),
-        }
+/// Loads bookmark entries from every profile of the configured Chromium-based browsers.
+/// Root folders such as the bookmarks bar are named in `language`.
+pub fn load_bookmarks(browsers: &[BookmarkBrowser], language: Language) -> Vec<BookmarkEntry> {
+    let mut all_entries = Vec::new();
+
+    for source in bookmark_sources(browsers) {
+        all_entries.extend(load_bookmark_file(&source.path, &source.label, language));
     }
 
-    debug!("loaded {} Chrome bookmark entries", all_entries.len());
+    debug!("loaded {} bookmark entries", all_entries.len());
     all_entries
 }
 
+/// A browser profile's `Bookmarks` file and the label its entry ids are prefixed with.
+pub struct BookmarkSource {
+    pub path: PathBuf,
+    pub label: String,
+}
+
+pub fn bookmark_sources(browsers: &[BookmarkBrowser]) -> Vec<BookmarkSource> {
+    bookmark_profile_dirs(browsers)
+        .into_iter()
+        .map(|profile| BookmarkSource {
+            path: profile.dir.join("Bookmarks"),
+            label: profile.label,
+        })
+        .collect()
+}
+
+/// Parses a single profile's `Bookmarks` file; missing or malformed files yield no entries.
+pub fn load_bookmark_file(path: &Path, label: &str, language: Language) -> Vec<BookmarkEntry> {
+    let mut entries = Vec::new();
+    if !path.is_file() {
+        return entries;
+    }
+    match fs::read_to_string(path) {
+        Ok(content) => match serde_json::from_str::<Value>(&content) {
+            Ok(json) => collect_entries_from_file(&json, label, language, &mut entries),
+            Err(err) => warn!("failed to parse bookmarks {:?}: {err}", path),
+        },
+        Err(err) => warn!("failed to read bookmarks {:?}: {err}", path),
+    }
+    entries
+}
+
+/// Swaps every entry of profile `label` for `entries`, leaving other profiles untouched.
+pub fn replace_profile_entries(
+    index: &mut Vec<BookmarkEntry>,
+    label: &str,
+    entries: Vec<BookmarkEntry>,
+) {
+    let prefix = format!("{label}:");
+    index.retain(|entry| !entry.id.starts_with(&prefix));
+    index.extend(entries);
+}
+
 struct ProfileLocation {
     dir: PathBuf,
     label: String,
 }
 
-fn bookmark_profile_dirs() -> Vec<ProfileLocation> {
+fn bookmark_profile_dirs(browsers: &[BookmarkBrowser]) -> Vec<ProfileLocation> {
     let mut results = Vec::new();
-    for (browser_label, root) in bookmark_user_data_roots() {
+    for (browser_label, root) in bookmark_user_data_roots(browsers) {
+        // Opera keeps a single profile directly in its data folder.
+        if root.join("Bookmarks").is_file() {
+            results.push(ProfileLocation {
+                dir: root.clone(),
+                label: browser_label.clone(),
+            });
+        }
         if let Ok(entries) = fs::read_dir(&root) {
             for entry in entries.flatten() {
                 let path = entry.path();
@@ -83,27 +148,23 @@ fn bookmark_profile_dirs() -> Vec<ProfileLocation> {
     results
 }
 
-fn bookmark_user_data_roots() -> Vec<(String, PathBuf)> {
+fn bookmark_user_data_roots(browsers: &[BookmarkBrowser]) -> Vec<(String, PathBuf)> {
     let mut roots = Vec::new();
-    if let Ok(local_app_data) = env::var("LOCALAPPDATA") {
-        let chrome_default = Path::new(&local_app_data)
-            .join("Google")
-            .join("Chrome")
-            .join("User Data");
-        if chrome_default.is_dir() {
-            roots.push(("Chrome".to_string(), chrome_default));
+    for browser in browsers {
+        let name = browser.name.trim();
+        let dir = expand_env_vars(browser.user_data_dir.trim(), |var| env::var(var).ok());
+        if name.is_empty() || dir.is_empty() || dir.contains('%') {
+            continue;
         }
-
-        let edge_default = Path::new(&local_app_data)
-            .join("Microsoft")
-            .join("Edge")
-            .join("User Data");
-        if edge_default.is_dir() {
-            roots.push(("Edge".to_string(), edge_default));
+        let dir = PathBuf::from(dir);
+        if dir.is_dir() {
+            roots.push((name.to_string(), dir));
         }
     }
 
-    if let Some(exe_path) = chrome_executable_path() {
+    // Portable Chrome installs keep their profiles next to the executable.
+    #[cfg(windows)]
+    if let Some(exe_path) = registered_executable("chrome.exe") {
         if let Some(exe_dir) = exe_path.parent() {
             let mut candidates = Vec::new();
             candidates.push(exe_dir.join("User Data"));
@@ -125,15 +186,36 @@ fn bookmark_user_data_roots() -> Vec<(String, PathBuf)> {
     roots
 }
 
-fn chrome_executable_path() -> Option<PathBuf> {
+/// Replaces `%NAME%` with `lookup(NAME)`, leaving unknown variables as they are.
+pub(crate) fn expand_env_vars(path: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        expanded.push_str(&rest[..start]);
+        match lookup(name) {
+            Some(value) if !name.is_empty() => expanded.push_str(&value),
+            _ => expanded.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Where the `App Paths` registry key says `exe_name` (e.g. `chrome.exe`) is installed.
+#[cfg(windows)]
+pub(crate) fn registered_executable(exe_name: &str) -> Option<PathBuf> {
     let roots = [
         RegKey::predef(HKEY_LOCAL_MACHINE),
         RegKey::predef(HKEY_CURRENT_USER),
     ];
+    let subkey = format!(r"SOFTWARE\Microsoft\Windows\CurrentVersion\App Paths\{exe_name}");
     for root in roots {
-        if let Ok(key) =
-            root.open_subkey(r"SOFTWARE\Microsoft\Windows\CurrentVersion\App Paths\chrome.exe")
-        {
+        if let Ok(key) = root.open_subkey(&subkey) {
             if let Ok(path) = key.get_value::<String, _>("") {
                 let trimmed = path.trim().trim_matches('"');
                 if !trimmed.is_empty() {
@@ -145,14 +227,19 @@ fn chrome_executable_path() -> Option<PathBuf> {
     None
 }
 
-fn collect_entries_from_file(json: &Value, profile_label: &str, acc: &mut Vec<BookmarkEntry>) {
+fn collect_entries_from_file(
+    json: &Value,
+    profile_label: &str,
+    language: Language,
+    acc: &mut Vec<BookmarkEntry>,
+) {
     let Some(roots) = json.get("roots").and_then(|value| value.as_object()) else {
         return;
     };
 
     for (key, node) in roots.iter() {
         let mut path_stack = vec![profile_label.to_string()];
-        if let Some(label) = root_display_label(key) {
+        if let Some(label) = root_display_label(key, language) {
             path_stack.push(label.to_string());
         }
 
@@ -239,24 +326,28 @@ fn collect_node(
             );
 
             let id = derive_bookmark_id(profile_label, node, url);
-            acc.push(BookmarkEntry {
+            let mut entry = BookmarkEntry {
                 id,
                 title: title.to_string(),
                 url: url.to_string(),
                 folder_path,
                 keywords,
                 pinyin_index,
-            });
+                folded: None,
+            };
+            entry.fold_case();
+            acc.push(entry);
         }
         _ => {}
     }
 }
 
-fn root_display_label(key: &str) -> Option<&'static str> {
+fn root_display_label(key: &str, language: Language) -> Option<&'static str> {
+    let text = messages(language);
     match key {
-        "bookmark_bar" => Some("书签栏"),
-        "other" => Some("其他书签"),
-        "synced" => Some("已同步"),
+        "bookmark_bar" => Some(text.bookmark_bar),
+        "other" => Some(text.other_bookmarks),
+        "synced" => Some(text.synced_bookmarks),
         _ => None,
     }
 }
@@ -295,3 +386,37 @@ mod hex {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixture_bookmarks;
+
+    #[test]
+    fn replacing_a_profile_keeps_other_profiles() {
+        let mut index = fixture_bookmarks();
+        let mut updated = index[0].clone();
+        updated.id = "Chrome 默认:new".to_string();
+        updated.title = "Rust Blog".to_string();
+
+        replace_profile_entries(&mut index, "Chrome 默认", vec![updated]);
+
+        let titles: Vec<&str> = index.iter().map(|entry| entry.title.as_str()).collect();
+        assert_eq!(titles, vec!["Meeting Notes", "Rust Blog"]);
+    }
+
+    #[test]
+    fn browser_folders_expand_environment_variables() {
+        let lookup = |name: &str| match name {
+            "LOCALAPPDATA" => Some(r"C:\Users\me\AppData\Local".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            expand_env_vars(r"%LOCALAPPDATA%\Vivaldi\User Data", lookup),
+            r"C:\Users\me\AppData\Local\Vivaldi\User Data"
+        );
+        assert_eq!(expand_env_vars(r"%NOPE%\x", lookup), r"%NOPE%\x");
+        assert_eq!(expand_env_vars("100% sure", lookup), "100% sure");
+        assert_eq!(expand_env_vars("%%", lookup), "%%");
+    }
+}
@@ -0,0 +1,203 @@
+//! egg's own commands, reached by typing `egg <command>`: maintenance plus every
+//! TUI action that has a shortcut, so `egg` doubles as a command palette. The TUI
+//! binds each command's [`BuiltinCommand::shortcut`], keeping keys and palette in step.
+//!
+//! Unlike other results these run inside the TUI instead of being handed to
+//! `execute_action` after it exits.
+
+use crate::locale::Language;
+
+const QUERY_PREFIX: &str = "egg";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinCommand {
+    Reindex,
+    Reload,
+    Settings,
+    Stats,
+    Theme,
+    FullPaths,
+    ToggleApps,
+    ToggleBookmarks,
+    ToggleFiles,
+    Undo,
+    Profile,
+    Quit,
+}
+
+impl BuiltinCommand {
+    pub const ALL: [BuiltinCommand; 12] = [
+        BuiltinCommand::Reindex,
+        BuiltinCommand::Reload,
+        BuiltinCommand::Settings,
+        BuiltinCommand::Stats,
+        BuiltinCommand::Theme,
+        BuiltinCommand::FullPaths,
+        BuiltinCommand::ToggleApps,
+        BuiltinCommand::ToggleBookmarks,
+        BuiltinCommand::ToggleFiles,
+        BuiltinCommand::Undo,
+        BuiltinCommand::Profile,
+        BuiltinCommand::Quit,
+    ];
+
+    /// The word typed after `egg`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Reindex => "reindex",
+            Self::Reload => "reload",
+            Self::Settings => "settings",
+            Self::Stats => "stats",
+            Self::Theme => "theme",
+            Self::FullPaths => "paths",
+            Self::ToggleApps => "apps",
+            Self::ToggleBookmarks => "bookmarks",
+            Self::ToggleFiles => "files",
+            Self::Undo => "undo",
+            Self::Profile => "profile",
+            Self::Quit => "quit",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Reindex => "Rebuild Index",
+            Self::Reload => "Reload Settings",
+            Self::Settings => "Open Settings",
+            Self::Stats => "Resource Usage",
+            Self::Theme => "Edit Theme",
+            Self::FullPaths => "Toggle Full Paths",
+            Self::ToggleApps => "Toggle App Search",
+            Self::ToggleBookmarks => "Toggle Bookmark Search",
+            Self::ToggleFiles => "Toggle File Search",
+            Self::Undo => "Undo",
+            Self::Profile => "Next Ranking Profile",
+            Self::Quit => "Quit egg",
+        }
+    }
+
+    pub fn localized_title(self) -> &'static str {
+        match self {
+            Self::Reindex => "重建索引",
+            Self::Reload => "重新加载配置",
+            Self::Settings => "打开配置文件",
+            Self::Stats => "资源占用",
+            Self::Theme => "编辑主题",
+            Self::FullPaths => "切换完整路径",
+            Self::ToggleApps => "开关应用搜索",
+            Self::ToggleBookmarks => "开关收藏夹搜索",
+            Self::ToggleFiles => "开关文件搜索",
+            Self::Undo => "撤销",
+            Self::Profile => "切换排序方案",
+            Self::Quit => "退出",
+        }
+    }
+
+    /// Title in `language`; searches match both titles either way.
+    pub fn display_title(self, language: Language) -> &'static str {
+        match language {
+            Language::En => self.title(),
+            Language::Zh => self.localized_title(),
+        }
+    }
+
+    pub fn description(self, language: Language) -> &'static str {
+        if language == Language::En {
+            return match self {
+                Self::Reindex => "Rescan apps and bookmarks",
+                Self::Reload => "Read settings.json again",
+                Self::Settings => "Open settings.json in an editor",
+                Self::Stats => "Show egg's memory and CPU usage",
+                Self::Theme => "Preview and change the colors",
+                Self::FullPaths => "Switch between full and compact paths",
+                Self::ToggleApps => "Turn app results on or off for this run",
+                Self::ToggleBookmarks => "Turn bookmark results on or off for this run",
+                Self::ToggleFiles => "Turn file search on or off for this run",
+                Self::Undo => "Undo the last blacklist, hide or launch options change",
+                Self::Profile => "Cycle through the ranking_profile values",
+                Self::Quit => "Close egg",
+            };
+        }
+        match self {
+            Self::Reindex => "重新扫描应用和收藏夹",
+            Self::Reload => "从 settings.json 重新读取配置",
+            Self::Settings => "在编辑器中打开 settings.json",
+            Self::Stats => "显示 egg 的内存和 CPU 占用",
+            Self::Theme => "预览并修改界面配色",
+            Self::FullPaths => "在完整路径和简短路径之间切换",
+            Self::ToggleApps => "本次运行中开关应用结果",
+            Self::ToggleBookmarks => "本次运行中开关收藏夹结果",
+            Self::ToggleFiles => "本次运行中开关文件搜索",
+            Self::Undo => "撤销上一次拉黑、隐藏或启动选项修改",
+            Self::Profile => "依次切换 ranking_profile",
+            Self::Quit => "关闭 egg",
+        }
+    }
+
+    /// The key that runs the command outside the palette, in `blacklist_hotkey` syntax.
+    pub fn shortcut(self) -> Option<&'static str> {
+        match self {
+            Self::Settings => Some("Ctrl+O"),
+            Self::Theme => Some("Ctrl+T"),
+            Self::FullPaths => Some("Ctrl+F"),
+            Self::ToggleApps => Some("F3"),
+            Self::ToggleBookmarks => Some("F4"),
+            Self::ToggleFiles => Some("F5"),
+            Self::Undo => Some("Ctrl+Z"),
+            Self::Quit => Some("Esc"),
+            Self::Reindex | Self::Reload | Self::Stats | Self::Profile => None,
+        }
+    }
+
+    /// Extra words accepted after `egg`, including the pinyin of the localized title.
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Self::Reindex => &["index", "refresh", "rescan", "chongjiansuoyin", "cjsy"],
+            Self::Reload => &["config", "chongxinjiazai", "cxjz"],
+            Self::Settings => &["config", "preferences", "dakaipeizhi", "dkpz"],
+            Self::Stats => &["usage", "memory", "cpu", "ziyuanzhanyong", "zyzy"],
+            Self::Theme => &["colors", "bianjizhuti", "bjzt"],
+            Self::FullPaths => &["subtitles", "qiehuanwanzhengluji", "qhwzlj"],
+            Self::ToggleApps => &["providers", "kaiguanyingyongsousuo", "kgyyss"],
+            Self::ToggleBookmarks => &["providers", "kaiguanshoucangjiasousuo", "kgscjss"],
+            Self::ToggleFiles => &["providers", "kaiguanwenjiansousuo", "kgwjss"],
+            Self::Undo => &["revert", "chexiao", "cx"],
+            Self::Profile => &["ranking", "qiehuanpaixufangan", "qhpxfa"],
+            Self::Quit => &["exit", "close", "tuichu", "tc"],
+        }
+    }
+
+    fn matches(self, term: &str) -> bool {
+        let term = term.to_lowercase();
+        [self.id(), self.title(), self.localized_title()]
+            .into_iter()
+            .chain(self.aliases().iter().copied())
+            .any(|candidate| {
+                let candidate = candidate.to_lowercase();
+                candidate.starts_with(&term)
+                    || candidate
+                        .split_whitespace()
+                        .any(|word| word.starts_with(&term))
+            })
+    }
+}
+
+/// Returns the commands offered for `query`, or `None` when it does not start with `egg`.
+pub fn builtin_matches(query: &str) -> Option<Vec<BuiltinCommand>> {
+    let trimmed = query.trim();
+    let head = trimmed.get(..QUERY_PREFIX.len())?;
+    if !head.eq_ignore_ascii_case(QUERY_PREFIX) {
+        return None;
+    }
+    let rest = &trimmed[QUERY_PREFIX.len()..];
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let term = rest.trim();
+    Some(
+        BuiltinCommand::ALL
+            .into_iter()
+            .filter(|command| term.is_empty() || command.matches(term))
+            .collect(),
+    )
+}
@@ -1,42 +1,339 @@
-use std::{env, fs, path::PathBuf};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use log::{debug, warn};
+use serde::{Deserialize, Serialize};
 
-use crate::models::ApplicationInfo;
+use crate::{
+    answers::ExchangeRates,
+    git_repos::RepoScan,
+    models::ApplicationInfo,
+    query_history::QueryHistory,
+    state::{RecentEntry, RecentList},
+};
 
 const INDEX_CACHE_FILE: &str = "index.json";
+const BINARY_INDEX_CACHE_FILE: &str = "index.bin";
+const RECENT_CACHE_FILE: &str = "recent.json";
+const EXCHANGE_RATES_FILE: &str = "exchange_rates.json";
+const QUERY_HISTORY_FILE: &str = "queries.json";
+const REPO_SCAN_FILE: &str = "git_repos.json";
+const ICON_CACHE_DIR: &str = "icons";
+/// Version of the app cache's layout. Bump it whenever a change to
+/// [`ApplicationInfo`] means older caches would read wrong or not at all; a cache
+/// of another version is dropped and the index rebuilt.
+const INDEX_CACHE_VERSION: u32 = 1;
+/// Start of a binary app cache, followed by [`INDEX_CACHE_VERSION`] as four
+/// little-endian bytes and the zstd-compressed bincode of the apps.
+const BINARY_INDEX_MAGIC: &[u8; 4] = b"EGGI";
+#[cfg(feature = "binary-cache")]
+const ZSTD_LEVEL: i32 = 3;
 
+/// The JSON app cache: `{"version": 1, "apps": [...]}`. Caches written before it
+/// had a version are a bare list of apps.
+#[derive(Serialize)]
+#[cfg_attr(feature = "binary-cache", allow(dead_code))]
+struct IndexCache<'a> {
+    version: u32,
+    apps: &'a [ApplicationInfo],
+}
+
+#[derive(Deserialize)]
+struct CacheHeader {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct VersionedIndex {
+    apps: Vec<ApplicationInfo>,
+}
+
+/// The binary cache when there is one that reads, else the JSON one.
 pub fn load_app_index() -> Option<Vec<ApplicationInfo>> {
-    let path = cache_path()?;
+    let mut apps = [BINARY_INDEX_CACHE_FILE, INDEX_CACHE_FILE]
+        .into_iter()
+        .find_map(|file| {
+            let path = cache_path(file)?;
+            let content = fs::read(&path).ok()?;
+            decode_app_index(&content)
+                .map_err(|err| warn!("ignoring app cache {:?}: {err}", path))
+                .ok()
+        })?;
+    apps.iter_mut().for_each(ApplicationInfo::fold_case);
+    Some(apps)
+}
+
+/// Reads a cache written by [`encode_app_index`], binary or JSON, telling them
+/// apart by [`BINARY_INDEX_MAGIC`].
+pub fn decode_app_index(content: &[u8]) -> Result<Vec<ApplicationInfo>, String> {
+    match content.strip_prefix(BINARY_INDEX_MAGIC) {
+        Some(rest) => decode_binary_index(rest),
+        None => parse_app_index(std::str::from_utf8(content).map_err(|err| err.to_string())?),
+    }
+}
+
+/// The app cache as written by this build: compressed bincode with the
+/// `binary-cache` feature, JSON without.
+pub fn encode_app_index(apps: &[ApplicationInfo]) -> Result<Vec<u8>, String> {
+    #[cfg(feature = "binary-cache")]
+    {
+        let encoded = bincode::serialize(apps).map_err(|err| err.to_string())?;
+        let compressed =
+            zstd::encode_all(encoded.as_slice(), ZSTD_LEVEL).map_err(|err| err.to_string())?;
+        let mut content = BINARY_INDEX_MAGIC.to_vec();
+        content.extend_from_slice(&INDEX_CACHE_VERSION.to_le_bytes());
+        content.extend_from_slice(&compressed);
+        Ok(content)
+    }
+    #[cfg(not(feature = "binary-cache"))]
+    serde_json::to_vec(&IndexCache {
+        version: INDEX_CACHE_VERSION,
+        apps,
+    })
+    .map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "binary-cache")]
+fn decode_binary_index(content: &[u8]) -> Result<Vec<ApplicationInfo>, String> {
+    let (version, compressed) = content.split_first_chunk::<4>().ok_or("缓存文件不完整")?;
+    let version = u32::from_le_bytes(*version);
+    if version != INDEX_CACHE_VERSION {
+        return Err(format!(
+            "written for version {version}, expected {INDEX_CACHE_VERSION}"
+        ));
+    }
+    let encoded = zstd::decode_all(compressed).map_err(|err| err.to_string())?;
+    bincode::deserialize(&encoded).map_err(|err| err.to_string())
+}
+
+#[cfg(not(feature = "binary-cache"))]
+fn decode_binary_index(_content: &[u8]) -> Result<Vec<ApplicationInfo>, String> {
+    Err("built without the binary-cache feature".into())
+}
+
+/// Apps of a JSON cache of this version, or of an unversioned one that still
+/// reads as the current [`ApplicationInfo`].
+fn parse_app_index(content: &str) -> Result<Vec<ApplicationInfo>, String> {
+    match serde_json::from_str::<CacheHeader>(content) {
+        Ok(header) if header.version == INDEX_CACHE_VERSION => {
+            serde_json::from_str::<VersionedIndex>(content)
+                .map(|cache| cache.apps)
+                .map_err(|err| err.to_string())
+        }
+        Ok(header) => Err(format!(
+            "written for version {}, expected {INDEX_CACHE_VERSION}",
+            header.version
+        )),
+        Err(_) => serde_json::from_str(content).map_err(|err| err.to_string()),
+    }
+}
+
+pub fn save_app_index(apps: &[ApplicationInfo]) -> Result<(), String> {
+    let (file, other) = if cfg!(feature = "binary-cache") {
+        (BINARY_INDEX_CACHE_FILE, INDEX_CACHE_FILE)
+    } else {
+        (INDEX_CACHE_FILE, BINARY_INDEX_CACHE_FILE)
+    };
+    let (Some(path), Some(other)) = (cache_path(file), cache_path(other)) else {
+        return Err("无法确定缓存目录".into());
+    };
+    write_atomically(&path, encode_app_index(apps)?)?;
+    // A cache in the other format would be stale once a build switches back.
+    let _ = fs::remove_file(other);
+    debug!("wrote app cache {:?}", path);
+    Ok(())
+}
+
+/// Recent actions saved by an earlier run, newest first.
+pub fn load_recent_actions() -> Vec<RecentEntry> {
+    let Some(path) = cache_path(RECENT_CACHE_FILE) else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&content) {
+        Ok(entries) => entries,
+        Err(err) => {
+            warn!("failed to parse recent actions {:?}: {err}", path);
+            Vec::new()
+        }
+    }
+}
+
+/// Saves the recent list, leaving out actions that cannot outlive this run.
+pub fn save_recent_actions(recent: &RecentList) -> Result<(), String> {
+    let Some(path) = cache_path(RECENT_CACHE_FILE) else {
+        return Err("无法确定缓存目录".into());
+    };
+    let entries: Vec<&RecentEntry> = recent
+        .items()
+        .filter(|entry| entry.action.persists())
+        .collect();
+    let payload = serde_json::to_string(&entries).map_err(|err| err.to_string())?;
+    write_atomically(&path, &payload)?;
+    debug!("wrote recent actions {:?}", path);
+    Ok(())
+}
+
+/// Queries saved by an earlier run, newest first.
+pub fn load_query_history() -> Vec<String> {
+    let Some(path) = cache_path(QUERY_HISTORY_FILE) else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&content) {
+        Ok(queries) => queries,
+        Err(err) => {
+            warn!("failed to parse query history {:?}: {err}", path);
+            Vec::new()
+        }
+    }
+}
+
+pub fn save_query_history(history: &QueryHistory) -> Result<(), String> {
+    let Some(path) = cache_path(QUERY_HISTORY_FILE) else {
+        return Err("无法确定缓存目录".into());
+    };
+    let queries: Vec<&String> = history.items().collect();
+    let payload = serde_json::to_string(&queries).map_err(|err| err.to_string())?;
+    write_atomically(&path, &payload)?;
+    debug!("wrote query history {:?}", path);
+    Ok(())
+}
+
+/// Exchange rates downloaded by an earlier run.
+pub fn load_exchange_rates() -> Option<ExchangeRates> {
+    let path = cache_path(EXCHANGE_RATES_FILE)?;
     let content = fs::read_to_string(&path).ok()?;
     match serde_json::from_str(&content) {
-        Ok(apps) => Some(apps),
+        Ok(rates) => Some(rates),
         Err(err) => {
-            warn!("failed to parse app cache {:?}: {err}", path);
+            warn!("failed to parse exchange rates {:?}: {err}", path);
             None
         }
     }
 }
 
-pub fn save_app_index(apps: &[ApplicationInfo]) -> Result<(), String> {
-    let Some(path) = cache_path() else {
+pub fn save_exchange_rates(rates: &ExchangeRates) -> Result<(), String> {
+    let Some(path) = cache_path(EXCHANGE_RATES_FILE) else {
         return Err("无法确定缓存目录".into());
     };
+    let payload = serde_json::to_string(rates).map_err(|err| err.to_string())?;
+    write_atomically(&path, &payload)?;
+    debug!("wrote exchange rates {:?}", path);
+    Ok(())
+}
+
+/// The folders the last repository scan searched; empty before the first one.
+pub fn load_repo_scan() -> RepoScan {
+    let Some(path) = cache_path(REPO_SCAN_FILE) else {
+        return RepoScan::default();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return RepoScan::default();
+    };
+    match serde_json::from_str(&content) {
+        Ok(scan) => scan,
+        Err(err) => {
+            warn!("failed to parse repository scan {:?}: {err}", path);
+            RepoScan::default()
+        }
+    }
+}
+
+pub fn save_repo_scan(scan: &RepoScan) -> Result<(), String> {
+    let Some(path) = cache_path(REPO_SCAN_FILE) else {
+        return Err("无法确定缓存目录".into());
+    };
+    let payload = serde_json::to_string(scan).map_err(|err| err.to_string())?;
+    write_atomically(&path, &payload)?;
+    debug!("wrote repository scan {:?}", path);
+    Ok(())
+}
+
+/// Where extracted app icons are kept, one file per app.
+pub fn icon_dir() -> Option<PathBuf> {
+    cache_path(ICON_CACHE_DIR)
+}
+
+/// Writes `content` to a temporary file next to `path` and renames it over
+/// `path`, so a crash leaves either the old file or the new one, never half of
+/// one.
+pub fn write_atomically(path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|err| err.to_string())?;
     }
-    let payload = serde_json::to_string(apps).map_err(|err| err.to_string())?;
-    fs::write(&path, payload).map_err(|err| err.to_string())?;
-    debug!("wrote app cache {:?}", path);
-    Ok(())
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp = path.with_file_name(temp_name);
+    let mut file = File::create(&temp).map_err(|err| err.to_string())?;
+    file.write_all(content.as_ref())
+        .and_then(|()| file.sync_all())
+        .map_err(|err| err.to_string())?;
+    drop(file);
+    fs::rename(&temp, path).map_err(|err| err.to_string())
 }
 
-fn cache_path() -> Option<PathBuf> {
-    let base = env::var("LOCALAPPDATA").ok()?;
-    Some(
-        PathBuf::from(base)
-            .join("egg")
-            .join("cache")
-            .join(INDEX_CACHE_FILE),
-    )
+fn cache_path(file: &str) -> Option<PathBuf> {
+    let base = dirs::data_local_dir()?;
+    Some(base.join("egg").join("cache").join(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::app;
+
+    #[test]
+    fn app_cache_is_versioned_and_replaced_whole() {
+        let apps = vec![app("shell:code", "Visual Studio Code", r"C:\Code\Code.exe")];
+        let payload = serde_json::to_string(&IndexCache {
+            version: INDEX_CACHE_VERSION,
+            apps: &apps,
+        })
+        .unwrap();
+        let read = |content: &str| parse_app_index(content).map(|apps| apps.len());
+        assert_eq!(read(&payload), Ok(1));
+        // An unversioned cache still loads while its entries read as apps.
+        let legacy = serde_json::to_string(&apps).unwrap();
+        assert_eq!(read(&legacy), Ok(1));
+        assert!(read(r#"[{"id": "shell:code"}]"#).is_err());
+        assert!(read(r#"{"version": 999, "apps": []}"#).is_err());
+        assert!(read(r#"{"version": 1, "apps": [{"#).is_err());
+
+        let dir = std::env::temp_dir().join("egg-cache-atomic");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join(INDEX_CACHE_FILE);
+        write_atomically(&path, "old").unwrap();
+        write_atomically(&path, &payload).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), payload);
+        assert!(!dir.join("index.json.tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn app_cache_round_trips_and_reads_either_format() {
+        let mut apps = vec![app("shell:code", "Visual Studio Code", r"C:\Code\Code.exe")];
+        apps[0].icon = Some(r"C:\Code\Code.exe,0".into());
+        apps[0].folded = None;
+        let encoded = encode_app_index(&apps).unwrap();
+        assert_eq!(
+            encoded.starts_with(BINARY_INDEX_MAGIC),
+            cfg!(feature = "binary-cache")
+        );
+        assert_eq!(decode_app_index(&encoded), Ok(apps.clone()));
+        let json = serde_json::to_vec(&apps).unwrap();
+        assert_eq!(decode_app_index(&json), Ok(apps));
+
+        let mut other_version = BINARY_INDEX_MAGIC.to_vec();
+        other_version.extend_from_slice(&(INDEX_CACHE_VERSION + 1).to_le_bytes());
+        assert!(decode_app_index(&other_version).is_err());
+        assert!(decode_app_index(b"EGGI\x01").is_err());
+    }
 }
@@ -0,0 +1,98 @@
+//! Command-line arguments. Without a subcommand egg opens the TUI; the
+//! subcommands run once and exit, for scripts and other tools.
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "egg-cli",
+    version,
+    about = "Command-line launcher for Windows applications"
+)]
+pub struct Cli {
+    /// Print what launching a result would run instead of running it.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Search the cached indexes and print the results with their ids.
+    Query {
+        /// What to search for; several words are searched as one query.
+        #[arg(required = true)]
+        text: Vec<String>,
+        /// Print a JSON array instead of tab-separated lines.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rebuild the cached app index that queries read.
+    Reindex,
+    /// Launch a result by the id `query` printed.
+    Run { id: String },
+    /// List the problems egg works around in settings.json.
+    CheckSettings {
+        /// Print the problems as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Keep the indexes loaded and answer JSON-RPC requests on `server_port`.
+    Serve,
+    /// Show what egg recorded about itself.
+    Stats {
+        #[command(subcommand)]
+        report: StatsReport,
+    },
+    /// Sync settings and launch history through `sync_folder` now.
+    Sync,
+    /// Write settings, launch history and recent items to a profile file.
+    ExportProfile { file: PathBuf },
+    /// Read a profile written by `export-profile` into this machine's settings.
+    ImportProfile { file: PathBuf },
+    /// Register egg to start at sign-in as the `startup` setting describes.
+    Startup {
+        #[command(subcommand)]
+        action: Option<StartupAction>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StatsReport {
+    /// Queries that found nothing and launches that needed scrolling.
+    Ranking {
+        /// Delete the recorded telemetry instead.
+        #[arg(long)]
+        purge: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StartupAction {
+    /// Show what is registered instead of registering.
+    Status,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn words_after_query_form_one_search() {
+        Cli::command().debug_assert();
+        let cli = Cli::try_parse_from(["egg-cli", "query", "visual", "studio", "--json"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Query { text, json: true }) if text.join(" ") == "visual studio"
+        ));
+        let cli = Cli::try_parse_from(["egg-cli", "run", "app-notepad", "--dry-run"]).unwrap();
+        assert!(cli.dry_run);
+        assert!(Cli::try_parse_from(["egg-cli", "query"]).is_err());
+        assert!(Cli::try_parse_from(["egg-cli", "run"]).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Hands `query` and `run` to a running `egg-cli serve`, so they search the
+//! indexes it keeps loaded instead of reading them again, and launches count in
+//! the launch history it keeps. Without a server they run in-process as before.
+//!
+//! Only these two subcommands are clients so far. The TUI is not: it loads and
+//! indexes on its own whether or not a server runs, since its previews, actions
+//! menu and background searches work on the results directly, so its startup
+//! does not get faster and it shares nothing with the server.
+
+use std::{
+    fmt,
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpStream},
+    time::Duration,
+};
+
+use log::debug;
+use serde_json::{json, Value};
+
+use crate::{models::SearchResult, server};
+
+/// How long to wait for a server before running in-process.
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(200);
+/// How long a request may take; launches can be retried for a while.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub(crate) enum CallError {
+    /// The server answered with a JSON-RPC error.
+    Rpc { code: i64, message: String },
+    /// The connection failed or the reply was not JSON-RPC.
+    Io(String),
+}
+
+impl fmt::Display for CallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rpc { message, .. } => f.write_str(message),
+            Self::Io(err) => write!(f, "与 egg-cli serve 的连接失败: {err}"),
+        }
+    }
+}
+
+pub(crate) struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    token: String,
+    next_id: u64,
+}
+
+/// The server listening on `port`, if one runs and its token can be read.
+pub(crate) fn connect(port: u16) -> Option<Client> {
+    let token = std::fs::read_to_string(server::token_path()?).ok()?;
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    match Client::connect_to(address, token.trim().to_string()) {
+        Ok(client) => Some(client),
+        Err(err) => {
+            debug!("no server on port {port}: {err}");
+            None
+        }
+    }
+}
+
+impl Client {
+    fn connect_to(address: SocketAddr, token: String) -> std::io::Result<Self> {
+        let writer = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+        writer.set_read_timeout(Some(REPLY_TIMEOUT))?;
+        Ok(Self {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+            token,
+            next_id: 1,
+        })
+    }
+
+    pub(crate) fn search(&mut self, query: &str) -> Result<Vec<SearchResult>, CallError> {
+        let reply = self.call("search", json!({ "query": query }))?;
+        serde_json::from_value(reply["results"].clone())
+            .map_err(|err| CallError::Io(err.to_string()))
+    }
+
+    pub(crate) fn execute(&mut self, id: &str) -> Result<(), CallError> {
+        self.call("execute", json!({ "id": id })).map(|_| ())
+    }
+
+    /// Sends one request and waits for its reply, skipping notifications.
+    fn call(&mut self, method: &str, mut params: Value) -> Result<Value, CallError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        params["token"] = json!(self.token);
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.writer, "{request}").map_err(|err| CallError::Io(err.to_string()))?;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .map_err(|err| CallError::Io(err.to_string()))?;
+            if read == 0 {
+                return Err(CallError::Io("连接已关闭".to_string()));
+            }
+            let reply: Value =
+                serde_json::from_str(&line).map_err(|err| CallError::Io(err.to_string()))?;
+            if reply["id"] != json!(id) {
+                continue;
+            }
+            if let Some(error) = reply.get("error") {
+                return Err(CallError::Rpc {
+                    code: error["code"].as_i64().unwrap_or_default(),
+                    message: error["message"].as_str().unwrap_or_default().to_string(),
+                });
+            }
+            return Ok(reply["result"].clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, sync::Arc, thread};
+
+    use tokio::runtime::Handle;
+
+    use super::*;
+    use crate::{state::AppState, testing::fixture_apps};
+
+    #[tokio::test]
+    async fn searches_and_launches_through_the_server() {
+        let state = Arc::new(AppState::new());
+        *state.app_index.lock().unwrap() = fixture_apps().into();
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let runtime = Handle::current();
+        thread::spawn(move || server::accept(listener, state, "secret".into(), true, runtime));
+
+        let mut client = Client::connect_to(address, "secret".to_string()).unwrap();
+        let results = client.search("wx").unwrap();
+        assert_eq!(results[0].title, "微信");
+        assert!(client.execute(&results[0].id).is_ok());
+        assert!(matches!(
+            client.execute("app-missing"),
+            Err(CallError::Rpc { .. })
+        ));
+
+        let mut stranger = Client::connect_to(address, "guess".to_string()).unwrap();
+        assert!(stranger.search("wx").is_err());
+    }
+}
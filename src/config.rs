@@ -1,21 +1,456 @@
-use std::{fs, path::PathBuf};
+use std::{collections::HashMap, fs, io, ops::RangeInclusive, path::PathBuf};
 
+use log::warn;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    cache,
+    hotkey::parse_hotkey,
+    locale::{fill, Language, Messages},
+    ranking::{RankingProfile, RankingWeights, SourceWeights},
+};
 
 const CONFIG_FILE: &str = "settings.json";
+/// Layout of `settings.json`. Bump it, with a [`MIGRATIONS`] entry, when a setting
+/// is renamed or dropped.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Settings renamed or dropped in a version, applied in order to older files.
+struct Migration {
+    version: u32,
+    renamed: &'static [(&'static str, &'static str)],
+    removed: &'static [&'static str],
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    renamed: &[],
+    // The hotkey of the desktop launcher egg started out as.
+    removed: &["global_hotkey"],
+}];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Layout version of the file, [`CONFIG_VERSION`] once migrated; 0 in files
+    /// written before it was recorded.
+    #[serde(default)]
+    pub version: u32,
     #[serde(default = "default_blacklist_hotkey")]
     pub blacklist_hotkey: String,
     #[serde(default = "default_max_results")]
     pub max_results: u32,
+    /// How long typing must pause before a search runs, in milliseconds.
+    #[serde(default = "default_query_delay_ms")]
+    pub query_delay_ms: u64,
     #[serde(default = "default_enable_app_results")]
     pub enable_app_results: bool,
     #[serde(default = "default_enable_bookmark_results")]
     pub enable_bookmark_results: bool,
+    #[serde(default = "default_enable_system_commands")]
+    pub enable_system_commands: bool,
     #[serde(default = "default_system_tool_exclusions")]
     pub system_tool_exclusions: Vec<String>,
+    /// Indexes the uninstallers Programs and Features lists, offered for queries
+    /// such as `uninstall spotify`.
+    #[serde(default)]
+    pub index_uninstallers: bool,
+    /// Folders searched for git repositories, a few levels deep.
+    #[serde(default)]
+    pub git_roots: Vec<String>,
+    /// Editor git repositories open in; unset means VS Code when installed, and
+    /// the folder otherwise.
+    #[serde(default)]
+    pub git_editor: Option<String>,
+    /// Chromium-based browsers whose bookmarks are indexed.
+    #[serde(default = "default_bookmark_browsers")]
+    pub bookmark_browsers: Vec<BookmarkBrowser>,
+    /// Minutes between background rescans of apps and bookmarks, for changes the
+    /// watcher cannot see; 0 turns them off.
+    #[serde(default = "default_reindex_interval_minutes")]
+    pub reindex_interval_minutes: u64,
+    /// Per-app launch arguments and start folders, keyed by app id.
+    #[serde(default)]
+    pub launch_overrides: HashMap<String, LaunchOverride>,
+    #[serde(default)]
+    pub ranking_profile: RankingProfile,
+    #[serde(default)]
+    pub ranking_weights: RankingWeights,
+    /// Per-source multipliers for apps, bookmarks, typed paths and launch history.
+    #[serde(default)]
+    pub source_weights: SourceWeights,
+    /// When nothing matches, lists apps whose names are a typo or two away from
+    /// the query (`chrmoe` finds Chrome), ranked below any real match.
+    #[serde(default)]
+    pub typo_matches: bool,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Language of result titles and the TUI: `en` or `zh`.
+    #[serde(default)]
+    pub language: Language,
+    /// Words that, followed by a space, limit a query to one source (`b github`).
+    #[serde(default)]
+    pub query_prefixes: QueryPrefixes,
+    /// Sections shown when the query is empty, top to bottom.
+    #[serde(default = "default_dashboard_sections")]
+    pub dashboard_sections: Vec<DashboardSection>,
+    /// Shows raw paths and URLs as subtitles instead of `name · folder` (toggle with Ctrl+F).
+    #[serde(default)]
+    pub full_path_subtitles: bool,
+    /// Shows apps and bookmarks/files side by side on wide terminals.
+    #[serde(default)]
+    pub split_panes: bool,
+    /// Lists results under Applications / Bookmarks / Files / Web headers, each
+    /// section where its best result ranks.
+    #[serde(default)]
+    pub group_results: bool,
+    /// Previews the selected file or folder beside the results on wide terminals.
+    #[serde(default = "default_preview_pane")]
+    pub preview_pane: bool,
+    /// Fetches the title of the selected URL or bookmark to show in its subtitle.
+    /// Off by default, since it contacts the site.
+    #[serde(default)]
+    pub fetch_page_titles: bool,
+    /// Saves queries that led to a launch and completes the input to the newest
+    /// one it starts, accepted with Right or Tab.
+    #[serde(default = "default_remember_queries")]
+    pub remember_queries: bool,
+    /// Shows an answer card for conversions such as `15kg to lb` or `3pm PST in Tokyo`.
+    #[serde(default = "default_instant_answers")]
+    pub instant_answers: bool,
+    /// Downloads daily exchange rates the first time a currency is converted.
+    /// Off by default, since it contacts open.er-api.com.
+    #[serde(default)]
+    pub fetch_exchange_rates: bool,
+    /// Lists query suggestions from `suggestion_engine` under the web search row.
+    /// Off by default, since the query is sent there whenever typing pauses.
+    #[serde(default)]
+    pub web_suggestions: bool,
+    #[serde(default)]
+    pub suggestion_engine: SuggestionEngine,
+    /// Enables `file:` queries answered by the Everything search service.
+    #[serde(default)]
+    pub enable_everything: bool,
+    /// Optional explicit path to `Everything64.dll`.
+    #[serde(default)]
+    pub everything_dll_path: Option<String>,
+    /// Answers `file:` queries from the Windows Search index, or when Everything fails.
+    #[serde(default)]
+    pub enable_windows_search: bool,
+    /// How long a Windows Search query may take before it is abandoned.
+    #[serde(default = "default_windows_search_timeout_ms")]
+    pub windows_search_timeout_ms: u64,
+    /// Runs Flow Launcher plugins from the `plugins` folder next to this file.
+    #[serde(default)]
+    pub enable_plugins: bool,
+    /// Plugins to leave off, by id or name.
+    #[serde(default)]
+    pub disabled_plugins: Vec<String>,
+    /// How long a plugin may take to answer a query.
+    #[serde(default = "default_plugin_timeout_ms")]
+    pub plugin_timeout_ms: u64,
+    /// Working set, in MB, above which egg drops its caches; 0 turns the check off.
+    #[serde(default = "default_memory_limit_mb")]
+    pub memory_limit_mb: u64,
+    /// CPU share of one core that, sustained, moves egg to below-normal priority;
+    /// 0 turns the check off.
+    #[serde(default = "default_background_cpu_percent")]
+    pub background_cpu_percent: u32,
+    /// Shell used for `>` commands.
+    #[serde(default)]
+    pub command_shell: CommandShell,
+    /// Keeps the console window open after a `>` command finishes.
+    #[serde(default = "default_command_keep_open")]
+    pub command_keep_open: bool,
+    /// Records zero-result queries and deep launches locally for `egg stats ranking`.
+    #[serde(default)]
+    pub ranking_telemetry: bool,
+    /// Logs what launching a result would run instead of running it (same as `--dry-run`).
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Shows how the selected result's score was computed, under the result list.
+    #[serde(default)]
+    pub debug_mode: bool,
+    /// Folder shared between machines (OneDrive, Syncthing, ...) that settings and
+    /// launch history are synced through; unset turns sync off.
+    #[serde(default)]
+    pub sync_folder: Option<String>,
+    /// Localhost port `egg-cli serve` answers JSON-RPC requests on.
+    #[serde(default = "default_server_port")]
+    pub server_port: u16,
+    /// Lets `egg-cli serve` clients run shell commands, files, plugins and system
+    /// commands and end processes, not just open what egg indexed.
+    #[serde(default)]
+    pub server_allows_commands: bool,
+    /// Downloads the favicons of bookmarked sites for `egg-cli serve` clients that
+    /// ask for icons. Off by default, since it contacts the sites.
+    #[serde(default)]
+    pub fetch_favicons: bool,
+    /// Extra attempts for a launch that failed in a way that may clear up, such as a
+    /// file an antivirus is still scanning or a network share waking up.
+    #[serde(default = "default_launch_retries")]
+    pub launch_retries: u32,
+    /// Wait before the first retry; it doubles for each further one.
+    #[serde(default = "default_launch_retry_delay_ms")]
+    pub launch_retry_delay_ms: u64,
+    /// Result ids (`app-…`, `bookmark-…`) pinned with Ctrl+D; they rank above other
+    /// matches.
+    #[serde(default)]
+    pub pinned_results: Vec<String>,
+    /// Short names that bring up an app or a command line when typed exactly.
+    #[serde(default)]
+    pub aliases: HashMap<String, AliasTarget>,
+    /// Named groups of apps, bookmarks, links and files launched together when the
+    /// name is typed in full.
+    #[serde(default)]
+    pub workspaces: HashMap<String, Vec<String>>,
+    /// Named pieces of text found by name and copied to the clipboard; `{date}` and
+    /// similar placeholders are filled in when copied.
+    #[serde(default)]
+    pub snippets: HashMap<String, String>,
+    /// `!bang` shortcuts added to, or replacing, the bundled ones, each a search
+    /// URL with `{}` where the terms go.
+    #[serde(default)]
+    pub bangs: HashMap<String, String>,
+    /// Markdown file `note …` appends to; unset means `egg-notes.md` in Documents.
+    #[serde(default)]
+    pub notes_file: Option<String>,
+    /// todo.txt file `todo …` lists and adds tasks to; unset means `todo.txt` in
+    /// Documents.
+    #[serde(default)]
+    pub todo_file: Option<String>,
+    /// Result ids hidden with Ctrl+X; they never show up in results or the dashboard.
+    #[serde(default)]
+    pub hidden_results: Vec<String>,
+    /// Starting egg when you sign in; applied with `egg-cli startup`.
+    #[serde(default)]
+    pub startup: StartupConfig,
+}
+
+/// Query prefixes per source; an empty string disables that prefix.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct QueryPrefixes {
+    pub apps: String,
+    pub bookmarks: String,
+    pub search: String,
+    pub windows: String,
+    pub processes: String,
+}
+
+impl Default for QueryPrefixes {
+    fn default() -> Self {
+        Self {
+            apps: "a".to_string(),
+            bookmarks: "b".to_string(),
+            search: "s".to_string(),
+            windows: "w".to_string(),
+            processes: "kill".to_string(),
+        }
+    }
+}
+
+/// A Chromium-based browser's `User Data` folder (or, for Opera, its profile
+/// folder). `%VAR%` environment variables in the path are expanded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BookmarkBrowser {
+    /// Shown at the start of each bookmark's folder path, with the profile name.
+    pub name: String,
+    pub user_data_dir: String,
+}
+
+impl BookmarkBrowser {
+    fn new(name: &str, user_data_dir: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            user_data_dir: user_data_dir.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct StartupConfig {
+    pub enabled: bool,
+    pub method: StartupMethod,
+    /// Task Scheduler only: seconds to wait after sign-in.
+    pub delay_secs: u32,
+    /// Task Scheduler only: run with highest privileges, without a UAC prompt.
+    pub elevated: bool,
+    /// Task Scheduler only: skip starting while on battery.
+    pub ac_power_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupMethod {
+    /// The `HKCU\...\Run` registry value.
+    #[default]
+    Registry,
+    /// A logon task, which can be delayed, elevated or limited to AC power.
+    TaskScheduler,
+}
+
+impl StartupMethod {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Registry => "registry Run key",
+            Self::TaskScheduler => "Task Scheduler",
+        }
+    }
+}
+
+/// What an alias opens, written as `{ "app": "<app id>" }` or
+/// `{ "command": "<command line>" }`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasTarget {
+    /// An indexed app, by id.
+    App(String),
+    /// A command line, run like a `>` command.
+    Command(String),
+}
+
+/// Launch settings that replace the indexed ones for one app.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LaunchOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandShell {
+    Cmd,
+    #[default]
+    PowerShell,
+    /// PowerShell 7+ (`pwsh.exe`).
+    Pwsh,
+    /// The default shell of the default WSL distribution, started in its home
+    /// folder.
+    Wsl,
+}
+
+impl CommandShell {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Cmd => "cmd",
+            Self::PowerShell => "PowerShell",
+            Self::Pwsh => "pwsh",
+            Self::Wsl => "WSL",
+        }
+    }
+}
+
+/// Where `web_suggestions` come from.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionEngine {
+    Google,
+    Bing,
+    #[default]
+    DuckDuckGo,
+}
+
+impl SuggestionEngine {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Google => "Google",
+            Self::Bing => "Bing",
+            Self::DuckDuckGo => "DuckDuckGo",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DashboardSection {
+    /// Recently launched results.
+    Recent,
+    /// Apps with the highest frecency.
+    Frequent,
+}
+
+impl DashboardSection {
+    pub fn title(self, text: &Messages) -> &'static str {
+        match self {
+            Self::Recent => text.recent_section,
+            Self::Frequent => text.frequent_section,
+        }
+    }
+}
+
+/// TUI colors: a preset, with any slot set as an `#rrggbb` string overriding it.
+/// The theme editor edits the slots in place.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    pub colors: ColorSupport,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub background: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub surface: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub border: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub accent: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub text: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub dim: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub highlight_bg: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub highlight_fg: String,
+}
+
+/// Built-in color schemes.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    /// Solarized dark.
+    Solarized,
+}
+
+impl ThemePreset {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+            Self::Solarized => "solarized",
+        }
+    }
+
+    /// The preset after this one, wrapping around; the theme editor steps through them.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Dark => Self::Light,
+            Self::Light => Self::Solarized,
+            Self::Solarized => Self::Dark,
+        }
+    }
+}
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSupport {
+    /// Read from `COLORTERM` and `TERM`.
+    #[default]
+    Auto,
+    /// 24-bit RGB.
+    Truecolor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// The 16 standard ANSI colors.
+    Ansi,
 }
 
 fn default_system_tool_exclusions() -> Vec<String> {
@@ -29,14 +464,116 @@ fn default_system_tool_exclusions() -> Vec<String> {
     ]
 }
 
+#[cfg(windows)]
+fn default_bookmark_browsers() -> Vec<BookmarkBrowser> {
+    vec![
+        BookmarkBrowser::new("Chrome", r"%LOCALAPPDATA%\Google\Chrome\User Data"),
+        BookmarkBrowser::new("Edge", r"%LOCALAPPDATA%\Microsoft\Edge\User Data"),
+        BookmarkBrowser::new(
+            "Brave",
+            r"%LOCALAPPDATA%\BraveSoftware\Brave-Browser\User Data",
+        ),
+        BookmarkBrowser::new("Vivaldi", r"%LOCALAPPDATA%\Vivaldi\User Data"),
+        BookmarkBrowser::new("Chromium", r"%LOCALAPPDATA%\Chromium\User Data"),
+        BookmarkBrowser::new("Opera", r"%APPDATA%\Opera Software\Opera Stable"),
+        BookmarkBrowser::new("Opera GX", r"%APPDATA%\Opera Software\Opera GX Stable"),
+    ]
+}
+
+#[cfg(target_os = "macos")]
+fn default_bookmark_browsers() -> Vec<BookmarkBrowser> {
+    vec![
+        BookmarkBrowser::new("Chrome", "%HOME%/Library/Application Support/Google/Chrome"),
+        BookmarkBrowser::new("Edge", "%HOME%/Library/Application Support/Microsoft Edge"),
+        BookmarkBrowser::new(
+            "Brave",
+            "%HOME%/Library/Application Support/BraveSoftware/Brave-Browser",
+        ),
+        BookmarkBrowser::new("Vivaldi", "%HOME%/Library/Application Support/Vivaldi"),
+        BookmarkBrowser::new("Chromium", "%HOME%/Library/Application Support/Chromium"),
+        BookmarkBrowser::new(
+            "Opera",
+            "%HOME%/Library/Application Support/com.operasoftware.Opera",
+        ),
+    ]
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn default_bookmark_browsers() -> Vec<BookmarkBrowser> {
+    vec![
+        BookmarkBrowser::new("Chrome", "%HOME%/.config/google-chrome"),
+        BookmarkBrowser::new("Edge", "%HOME%/.config/microsoft-edge"),
+        BookmarkBrowser::new("Brave", "%HOME%/.config/BraveSoftware/Brave-Browser"),
+        BookmarkBrowser::new("Vivaldi", "%HOME%/.config/vivaldi"),
+        BookmarkBrowser::new("Chromium", "%HOME%/.config/chromium"),
+        BookmarkBrowser::new("Opera", "%HOME%/.config/opera"),
+    ]
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             blacklist_hotkey: default_blacklist_hotkey(),
             max_results: default_max_results(),
+            query_delay_ms: default_query_delay_ms(),
             enable_app_results: default_enable_app_results(),
             enable_bookmark_results: default_enable_bookmark_results(),
+            enable_system_commands: default_enable_system_commands(),
             system_tool_exclusions: default_system_tool_exclusions(),
+            index_uninstallers: false,
+            git_roots: Vec::new(),
+            git_editor: None,
+            bookmark_browsers: default_bookmark_browsers(),
+            reindex_interval_minutes: default_reindex_interval_minutes(),
+            launch_overrides: HashMap::new(),
+            ranking_profile: RankingProfile::default(),
+            ranking_weights: RankingWeights::default(),
+            source_weights: SourceWeights::default(),
+            typo_matches: false,
+            theme: ThemeConfig::default(),
+            language: Language::default(),
+            query_prefixes: QueryPrefixes::default(),
+            dashboard_sections: default_dashboard_sections(),
+            full_path_subtitles: false,
+            split_panes: false,
+            group_results: false,
+            preview_pane: default_preview_pane(),
+            fetch_page_titles: false,
+            remember_queries: default_remember_queries(),
+            instant_answers: default_instant_answers(),
+            fetch_exchange_rates: false,
+            web_suggestions: false,
+            suggestion_engine: SuggestionEngine::default(),
+            enable_everything: false,
+            everything_dll_path: None,
+            enable_windows_search: false,
+            windows_search_timeout_ms: default_windows_search_timeout_ms(),
+            enable_plugins: false,
+            disabled_plugins: Vec::new(),
+            plugin_timeout_ms: default_plugin_timeout_ms(),
+            memory_limit_mb: default_memory_limit_mb(),
+            background_cpu_percent: default_background_cpu_percent(),
+            command_shell: CommandShell::default(),
+            command_keep_open: default_command_keep_open(),
+            ranking_telemetry: false,
+            dry_run: false,
+            debug_mode: false,
+            sync_folder: None,
+            server_port: default_server_port(),
+            server_allows_commands: false,
+            fetch_favicons: false,
+            launch_retries: default_launch_retries(),
+            launch_retry_delay_ms: default_launch_retry_delay_ms(),
+            pinned_results: Vec::new(),
+            aliases: HashMap::new(),
+            workspaces: HashMap::new(),
+            snippets: HashMap::new(),
+            bangs: HashMap::new(),
+            notes_file: None,
+            todo_file: None,
+            hidden_results: Vec::new(),
+            startup: StartupConfig::default(),
         }
     }
 }
@@ -45,6 +582,51 @@ const fn default_max_results() -> u32 {
     40
 }
 
+const fn default_query_delay_ms() -> u64 {
+    60
+}
+
+const fn default_windows_search_timeout_ms() -> u64 {
+    400
+}
+
+/// Interpreters start slowly on a cold cache, so plugins get longer than Windows Search.
+const fn default_plugin_timeout_ms() -> u64 {
+    2000
+}
+
+const fn default_reindex_interval_minutes() -> u64 {
+    60
+}
+
+const fn default_memory_limit_mb() -> u64 {
+    256
+}
+
+const fn default_background_cpu_percent() -> u32 {
+    25
+}
+
+const fn default_server_port() -> u16 {
+    47_733
+}
+
+const fn default_launch_retries() -> u32 {
+    2
+}
+
+const fn default_launch_retry_delay_ms() -> u64 {
+    500
+}
+
+const fn default_preview_pane() -> bool {
+    true
+}
+
+const fn default_instant_answers() -> bool {
+    true
+}
+
 const fn default_enable_app_results() -> bool {
     true
 }
@@ -53,11 +635,36 @@ const fn default_enable_bookmark_results() -> bool {
     true
 }
 
+const fn default_enable_system_commands() -> bool {
+    true
+}
+
+const fn default_remember_queries() -> bool {
+    true
+}
+
+const fn default_command_keep_open() -> bool {
+    true
+}
+
+fn default_dashboard_sections() -> Vec<DashboardSection> {
+    vec![DashboardSection::Recent, DashboardSection::Frequent]
+}
+
 fn default_blacklist_hotkey() -> String {
     "Ctrl+B".to_string()
 }
 
 impl AppConfig {
+    /// Weights of the ranking profile, with the history multiplier applied.
+    pub fn ranking(&self) -> RankingWeights {
+        let weights = self.ranking_profile.weights(&self.ranking_weights);
+        RankingWeights {
+            frecency_percent: weights.frecency_percent * self.source_weights.history.max(0) / 100,
+            ..weights
+        }
+    }
+
     pub fn load() -> Self {
         let Some(path) = config_path() else {
             return Self::default();
@@ -67,10 +674,115 @@ impl AppConfig {
             let _ = fs::create_dir_all(parent);
         }
 
+        Self::try_load().unwrap_or_default()
+    }
+
+    /// Like [`AppConfig::load`], but reports a malformed `settings.json` instead of
+    /// falling back to defaults. A missing file still yields the defaults.
+    pub fn try_load() -> Result<Self, String> {
+        let (config, issues) = Self::try_load_checked()?;
+        for issue in &issues {
+            warn!("settings.json: {issue:?}");
+        }
+        Ok(config)
+    }
+
+    /// Like [`AppConfig::try_load`], also returning what was fixed up to load the
+    /// file, for showing to the user.
+    pub fn try_load_checked() -> Result<(Self, Vec<ConfigIssue>), String> {
+        let Some(path) = config_path() else {
+            return Ok((Self::default(), Vec::new()));
+        };
         match fs::read_to_string(&path) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(_) => Self::default(),
+            Ok(content) => Self::parse(&content),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok((Self::default(), Vec::new())),
+            Err(err) => Err(format!("无法读取 settings.json: {err}")),
+        }
+    }
+
+    /// Migrates the file to [`CONFIG_VERSION`], leaves settings whose value does
+    /// not read at their defaults and resets values out of range. Only JSON that
+    /// does not parse at all is an error.
+    fn parse(content: &str) -> Result<(Self, Vec<ConfigIssue>), String> {
+        let value: Value = serde_json::from_str(content)
+            .map_err(|err| format!("settings.json 格式错误: {err}"))?;
+        let Value::Object(mut settings) = value else {
+            return Err("settings.json 格式错误: 应为一个 JSON 对象".into());
+        };
+        let mut issues = migrate(&mut settings);
+        issues.extend(drop_unreadable(&mut settings));
+        let mut config: Self = serde_json::from_value(Value::Object(settings))
+            .map_err(|err| format!("settings.json 格式错误: {err}"))?;
+        issues.extend(config.validate());
+        Ok((config, issues))
+    }
+
+    /// Resets settings outside what egg accepts to their defaults.
+    fn validate(&mut self) -> Vec<ConfigIssue> {
+        let defaults = Self::default();
+        let mut issues = Vec::new();
+        if parse_hotkey(&self.blacklist_hotkey).is_none() {
+            issues.push(ConfigIssue::InvalidHotkey {
+                key: "blacklist_hotkey".into(),
+                value: std::mem::replace(&mut self.blacklist_hotkey, defaults.blacklist_hotkey),
+            });
+        }
+        let mut check = |key: &str, value: u64, range: RangeInclusive<u64>| {
+            let in_range = range.contains(&value);
+            if !in_range {
+                issues.push(ConfigIssue::OutOfRange {
+                    key: key.into(),
+                    value,
+                    min: *range.start(),
+                    max: *range.end(),
+                });
+            }
+            in_range
+        };
+        if !check("max_results", self.max_results.into(), 1..=500) {
+            self.max_results = defaults.max_results;
+        }
+        if !check("query_delay_ms", self.query_delay_ms, 0..=2_000) {
+            self.query_delay_ms = defaults.query_delay_ms;
+        }
+        if !check(
+            "windows_search_timeout_ms",
+            self.windows_search_timeout_ms,
+            50..=10_000,
+        ) {
+            self.windows_search_timeout_ms = defaults.windows_search_timeout_ms;
+        }
+        if !check(
+            "reindex_interval_minutes",
+            self.reindex_interval_minutes,
+            0..=1_440,
+        ) {
+            self.reindex_interval_minutes = defaults.reindex_interval_minutes;
+        }
+        if !check("plugin_timeout_ms", self.plugin_timeout_ms, 100..=30_000) {
+            self.plugin_timeout_ms = defaults.plugin_timeout_ms;
+        }
+        if !check(
+            "background_cpu_percent",
+            self.background_cpu_percent.into(),
+            0..=100,
+        ) {
+            self.background_cpu_percent = defaults.background_cpu_percent;
         }
+        if !check("server_port", self.server_port.into(), 1..=65_535) {
+            self.server_port = defaults.server_port;
+        }
+        if !check("launch_retries", self.launch_retries.into(), 0..=10) {
+            self.launch_retries = defaults.launch_retries;
+        }
+        if !check(
+            "launch_retry_delay_ms",
+            self.launch_retry_delay_ms,
+            0..=10_000,
+        ) {
+            self.launch_retry_delay_ms = defaults.launch_retry_delay_ms;
+        }
+        issues
     }
 
     pub fn save(&self) -> Result<(), String> {
@@ -87,7 +799,15 @@ impl AppConfig {
                 if let (serde_json::Value::Object(mut existing_map), serde_json::Value::Object(new_map)) =
                     (existing_value, new_value.clone())
                 {
-                    existing_map.remove("global_hotkey");
+                    // Old names would be migrated again over the new ones.
+                    for migration in MIGRATIONS {
+                        for (old, _) in migration.renamed {
+                            existing_map.remove(*old);
+                        }
+                        for old in migration.removed {
+                            existing_map.remove(*old);
+                        }
+                    }
                     for (key, val) in new_map {
                         existing_map.insert(key, val);
                     }
@@ -103,10 +823,216 @@ impl AppConfig {
         };
 
         let data = serde_json::to_string_pretty(&merged_value).map_err(|err| err.to_string())?;
-        fs::write(path, data).map_err(|err| err.to_string())
+        cache::write_atomically(&path, data)
+    }
+}
+
+/// Something in `settings.json` egg had to work around to load it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigIssue {
+    /// Written by a newer egg; settings it added are ignored.
+    NewerVersion {
+        version: u32,
+    },
+    /// An old name of a setting, read as the new one.
+    Renamed {
+        from: String,
+        to: String,
+    },
+    /// A setting egg no longer has.
+    Removed {
+        key: String,
+    },
+    /// Not a setting; usually a typo.
+    Unknown {
+        key: String,
+    },
+    /// A value of the wrong type, left at the default.
+    WrongType {
+        key: String,
+        error: String,
+    },
+    OutOfRange {
+        key: String,
+        value: u64,
+        min: u64,
+        max: u64,
+    },
+    InvalidHotkey {
+        key: String,
+        value: String,
+    },
+}
+
+impl ConfigIssue {
+    pub fn describe(&self, text: &Messages) -> String {
+        match self {
+            Self::NewerVersion { version } => {
+                fill(text.settings_from_newer_version, &[&version.to_string()])
+            }
+            Self::Renamed { from, to } => fill(text.setting_renamed, &[from, to]),
+            Self::Removed { key } => fill(text.setting_removed, &[key]),
+            Self::Unknown { key } => fill(text.setting_unknown, &[key]),
+            Self::WrongType { key, error } => fill(text.setting_wrong_type, &[key, error]),
+            Self::OutOfRange {
+                key,
+                value,
+                min,
+                max,
+            } => fill(
+                text.setting_out_of_range,
+                &[key, &min.to_string(), &max.to_string(), &value.to_string()],
+            ),
+            Self::InvalidHotkey { key, value } => fill(text.setting_invalid_hotkey, &[key, value]),
+        }
+    }
+}
+
+/// Applies the [`MIGRATIONS`] newer than the file's version.
+fn migrate(settings: &mut Map<String, Value>) -> Vec<ConfigIssue> {
+    let version = settings
+        .get("version")
+        .and_then(Value::as_u64)
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or(0);
+    let mut issues = Vec::new();
+    if version > CONFIG_VERSION {
+        issues.push(ConfigIssue::NewerVersion { version });
+        return issues;
+    }
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > version)
+    {
+        for &(from, to) in migration.renamed {
+            if let Some(value) = settings.remove(from) {
+                // A setting already under its new name wins.
+                settings.entry(to).or_insert(value);
+                issues.push(ConfigIssue::Renamed {
+                    from: from.into(),
+                    to: to.into(),
+                });
+            }
+        }
+        for &key in migration.removed {
+            if settings.remove(key).is_some() {
+                issues.push(ConfigIssue::Removed { key: key.into() });
+            }
+        }
     }
+    settings.insert("version".into(), CONFIG_VERSION.into());
+    issues
+}
+
+/// Drops the settings whose value does not read, so they take their defaults
+/// instead of failing the whole file, and flags the keys that are not settings.
+fn drop_unreadable(settings: &mut Map<String, Value>) -> Vec<ConfigIssue> {
+    let Ok(Value::Object(known)) = serde_json::to_value(AppConfig::default()) else {
+        return Vec::new();
+    };
+    let mut issues = Vec::new();
+    let whole_file_reads =
+        serde_json::from_value::<AppConfig>(Value::Object(settings.clone())).is_ok();
+    settings.retain(|key, value| {
+        if !known.contains_key(key) {
+            issues.push(ConfigIssue::Unknown { key: key.clone() });
+            return true;
+        }
+        if whole_file_reads {
+            return true;
+        }
+        let alone = Map::from_iter([(key.clone(), value.clone())]);
+        match serde_json::from_value::<AppConfig>(Value::Object(alone)) {
+            Ok(_) => true,
+            Err(err) => {
+                issues.push(ConfigIssue::WrongType {
+                    key: key.clone(),
+                    error: err.to_string(),
+                });
+                false
+            }
+        }
+    });
+    issues
 }
 
 pub fn config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|dir| dir.join("egg-cli").join(CONFIG_FILE))
 }
+
+/// The file a path setting names, or `file_name` in the Documents folder (the home
+/// folder where there is none) while it is unset.
+pub fn documents_file(setting: Option<&str>, file_name: &str) -> Option<PathBuf> {
+    match setting.map(str::trim) {
+        Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => Some(
+            dirs::document_dir()
+                .or_else(dirs::home_dir)?
+                .join(file_name),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fills_missing_fields_and_rejects_broken_json() {
+        let (config, issues) = AppConfig::parse(r#"{ "max_results": 12 }"#).unwrap();
+        assert_eq!(config.max_results, 12);
+        assert_eq!(config.query_delay_ms, default_query_delay_ms());
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(issues.is_empty());
+
+        assert!(AppConfig::parse(r#"{ "max_results": 12, "#).is_err());
+    }
+
+    #[test]
+    fn parse_migrates_and_reports_what_it_fixed() {
+        let (config, issues) = AppConfig::parse(
+            r#"{
+                "global_hotkey": "Alt+Space",
+                "blacklist_hotkey": "Ctrl+",
+                "max_results": 0,
+                "query_delay_ms": "fast",
+                "preview_pane": false,
+                "max_reslts": 10
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.max_results, default_max_results());
+        assert_eq!(config.query_delay_ms, default_query_delay_ms());
+        assert_eq!(config.blacklist_hotkey, default_blacklist_hotkey());
+        assert!(!config.preview_pane);
+        let kinds: Vec<&str> = issues
+            .iter()
+            .map(|issue| match issue {
+                ConfigIssue::Removed { key } => key.as_str(),
+                ConfigIssue::Unknown { key } => key.as_str(),
+                ConfigIssue::WrongType { key, .. } => key.as_str(),
+                ConfigIssue::OutOfRange { key, .. } => key.as_str(),
+                ConfigIssue::InvalidHotkey { key, .. } => key.as_str(),
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "global_hotkey",
+                "max_reslts",
+                "query_delay_ms",
+                "blacklist_hotkey",
+                "max_results"
+            ]
+        );
+        assert_eq!(
+            issues[4].describe(crate::locale::messages(Language::En)),
+            "max_results must be from 1 to 500, not 0; using the default."
+        );
+
+        let (_, issues) = AppConfig::parse(r#"{ "version": 9 }"#).unwrap();
+        assert_eq!(issues, vec![ConfigIssue::NewerVersion { version: 9 }]);
+    }
+}
@@ -0,0 +1,299 @@
+//! Installed apps outside Windows: XDG `.desktop` entries on Linux and `.app`
+//! bundles on macOS. They stand in for the AppsFolder walk and are indexed as
+//! regular programs (`AppType::Win32`).
+
+#[cfg(not(target_os = "macos"))]
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+
+use crate::{
+    models::{AppType, ApplicationInfo},
+    text_utils::build_pinyin_index,
+};
+
+/// Ids of apps found in `.desktop` files.
+#[cfg(not(target_os = "macos"))]
+const DESKTOP_ID_PREFIX: &str = "desktop:";
+/// Ids of macOS app bundles.
+#[cfg(target_os = "macos")]
+const BUNDLE_ID_PREFIX: &str = "bundle:";
+
+#[cfg(target_os = "macos")]
+pub fn enumerate_desktop_apps() -> Result<Vec<ApplicationInfo>, String> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let roots = [
+        PathBuf::from("/Applications"),
+        PathBuf::from("/Applications/Utilities"),
+        PathBuf::from("/System/Applications"),
+        PathBuf::from("/System/Applications/Utilities"),
+        home.join("Applications"),
+    ];
+    let mut apps = Vec::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(&root) else {
+            continue;
+        };
+        apps.extend(
+            entries
+                .flatten()
+                .filter_map(|entry| app_bundle(&entry.path())),
+        );
+    }
+    debug!("found {} app bundles", apps.len());
+    Ok(apps)
+}
+
+/// Entries from every XDG data directory; as with the desktop's own menu, an
+/// entry in `~/.local/share/applications` hides a system one with the same id.
+#[cfg(not(target_os = "macos"))]
+pub fn enumerate_desktop_apps() -> Result<Vec<ApplicationInfo>, String> {
+    let mut seen = HashSet::new();
+    let mut apps = Vec::new();
+    for root in application_dirs() {
+        for file in desktop_files(&root) {
+            let Some(file_id) = desktop_file_id(&root, &file) else {
+                continue;
+            };
+            if !seen.insert(file_id.clone()) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&file) else {
+                continue;
+            };
+            if let Some(app) = parse_desktop_entry(&content, &file_id) {
+                apps.push(app);
+            }
+        }
+    }
+    debug!("found {} desktop entries", apps.len());
+    Ok(apps)
+}
+
+/// `$XDG_DATA_HOME` first, then `$XDG_DATA_DIRS`, each with `applications` appended.
+#[cfg(not(target_os = "macos"))]
+fn application_dirs() -> Vec<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")));
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    data_home
+        .into_iter()
+        .chain(env::split_paths(&data_dirs))
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn desktop_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "desktop") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// The desktop file id: the path below `applications` with `/` turned into `-`.
+#[cfg(not(target_os = "macos"))]
+fn desktop_file_id(root: &Path, file: &Path) -> Option<String> {
+    let relative = file.strip_prefix(root).ok()?.to_str()?;
+    Some(relative.replace('/', "-"))
+}
+
+/// Reads the `[Desktop Entry]` group. Entries that are hidden, not applications or
+/// have nothing to run yield `None`.
+#[cfg(not(target_os = "macos"))]
+fn parse_desktop_entry(content: &str, file_id: &str) -> Option<ApplicationInfo> {
+    let mut in_entry = false;
+    let mut fields = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            fields.entry(key.trim()).or_insert(value.trim());
+        }
+    }
+    let field = |key: &str| fields.get(key).copied().filter(|value| !value.is_empty());
+    if field("Type") != Some("Application")
+        || field("NoDisplay") == Some("true")
+        || field("Hidden") == Some("true")
+    {
+        return None;
+    }
+    let name = field("Name")?.to_string();
+    let (program, arguments) = split_exec(field("Exec")?)?;
+
+    let mut keywords = vec![name.clone()];
+    if let Some(generic) = field("GenericName") {
+        keywords.push(generic.to_string());
+    }
+    if let Some(list) = field("Keywords") {
+        keywords.extend(
+            list.split(';')
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(str::to_string),
+        );
+    }
+    keywords.sort();
+    keywords.dedup();
+    // Chinese names are looked up under the zh_CN key, so they get pinyin too.
+    let pinyin_index = build_pinyin_index(
+        [Some(name.as_str()), field("Name[zh_CN]")]
+            .into_iter()
+            .flatten(),
+    );
+    Some(ApplicationInfo {
+        id: format!("{DESKTOP_ID_PREFIX}{}", file_id.to_ascii_lowercase()),
+        name,
+        path: program,
+        source_path: None,
+        app_type: AppType::Win32,
+        description: field("Comment").map(str::to_string),
+        keywords,
+        pinyin_index,
+        working_directory: field("Path").map(str::to_string),
+        arguments,
+        icon: field("Icon").map(str::to_string),
+        folded: None,
+    })
+}
+
+/// Splits `Exec` into the program and the rest of the command line, dropping the
+/// `%f`/`%u`-style placeholders a launcher fills with files; egg passes none.
+#[cfg(not(target_os = "macos"))]
+fn split_exec(exec: &str) -> Option<(String, Option<String>)> {
+    let mut cleaned = String::with_capacity(exec.len());
+    let mut chars = exec.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            cleaned.push(ch);
+        } else if chars.next() == Some('%') {
+            cleaned.push('%');
+        }
+    }
+    let cleaned = cleaned.trim();
+    let (program, rest) = match cleaned.strip_prefix('"') {
+        Some(quoted) => {
+            let (program, rest) = quoted.split_once('"')?;
+            (program, rest)
+        }
+        None => cleaned.split_once(' ').unwrap_or((cleaned, "")),
+    };
+    if program.is_empty() {
+        return None;
+    }
+    let arguments = rest.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some((
+        program.to_string(),
+        (!arguments.is_empty()).then_some(arguments),
+    ))
+}
+
+/// An `.app` bundle, opened as a whole by Launch Services.
+#[cfg(target_os = "macos")]
+fn app_bundle(path: &Path) -> Option<ApplicationInfo> {
+    if path.extension()? != "app" || !path.is_dir() {
+        return None;
+    }
+    let name = path.file_stem()?.to_str()?.trim().to_string();
+    if name.is_empty() {
+        return None;
+    }
+    let path = path.to_string_lossy().into_owned();
+    Some(ApplicationInfo {
+        id: format!("{BUNDLE_ID_PREFIX}{}", path.to_lowercase()),
+        keywords: vec![name.clone()],
+        pinyin_index: build_pinyin_index([name.as_str()]),
+        name,
+        path,
+        source_path: None,
+        app_type: AppType::Win32,
+        description: None,
+        working_directory: None,
+        arguments: None,
+        icon: None,
+        folded: None,
+    })
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_entries_become_apps() {
+        let content = "\
+[Desktop Entry]
+Type=Application
+Name=Firefox Web Browser
+Name[zh_CN]=火狐浏览器
+GenericName=Web Browser
+Keywords=Internet;WWW;
+Comment=Browse the World Wide Web
+Exec=/usr/lib/firefox/firefox %u --new-window
+Path=/tmp
+Icon=firefox
+
+[Desktop Action new-private-window]
+Name=New Private Window
+Exec=/usr/lib/firefox/firefox --private-window %u
+";
+        let app = parse_desktop_entry(content, "firefox.desktop").unwrap();
+        assert_eq!(app.id, "desktop:firefox.desktop");
+        assert_eq!(app.name, "Firefox Web Browser");
+        assert_eq!(app.path, "/usr/lib/firefox/firefox");
+        assert_eq!(app.arguments.as_deref(), Some("--new-window"));
+        assert_eq!(app.working_directory.as_deref(), Some("/tmp"));
+        assert_eq!(app.icon.as_deref(), Some("firefox"));
+        assert!(app.keywords.contains(&"WWW".to_string()));
+
+        let hidden = content.replace("Type=Application", "Type=Application\nNoDisplay=true");
+        assert!(parse_desktop_entry(&hidden, "firefox.desktop").is_none());
+        let link = content.replace("Type=Application", "Type=Link");
+        assert!(parse_desktop_entry(&link, "firefox.desktop").is_none());
+    }
+
+    #[test]
+    fn exec_lines_lose_field_codes() {
+        assert_eq!(
+            split_exec("\"/opt/My App/app\" --flag %F"),
+            Some(("/opt/My App/app".to_string(), Some("--flag".to_string())))
+        );
+        assert_eq!(split_exec("htop"), Some(("htop".to_string(), None)));
+        assert_eq!(
+            split_exec("printf 100%%"),
+            Some(("printf".to_string(), Some("100%".to_string())))
+        );
+        assert_eq!(split_exec("%U"), None);
+    }
+}
@@ -0,0 +1,577 @@
+//! Emoji and symbols for the `:` provider (`:thumbs up`, `emoji fire`). Names
+//! follow the Unicode short names; keywords add common synonyms and Chinese.
+//! Entries are grouped by kind, faces first, which is also the order an empty
+//! `:` query lists them in.
+
+pub struct Emoji {
+    pub symbol: &'static str,
+    pub name: &'static str,
+    pub keywords: &'static [&'static str],
+}
+
+impl Emoji {
+    /// `U+1F44D`, or `U+1F1FA U+1F1F8` for sequences.
+    pub fn code_points(&self) -> String {
+        self.symbol
+            .chars()
+            .filter(|ch| *ch != '\u{fe0f}' && *ch != '\u{200d}')
+            .map(|ch| format!("U+{:04X}", ch as u32))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+const fn emoji(
+    symbol: &'static str,
+    name: &'static str,
+    keywords: &'static [&'static str],
+) -> Emoji {
+    Emoji {
+        symbol,
+        name,
+        keywords,
+    }
+}
+
+pub const EMOJI: &[Emoji] = &[
+    emoji("😀", "grinning face", &["happy", "smile", "grin", "笑"]),
+    emoji(
+        "😃",
+        "grinning face with big eyes",
+        &["happy", "smile", "开心"],
+    ),
+    emoji(
+        "😄",
+        "grinning face with smiling eyes",
+        &["happy", "smile", "laugh"],
+    ),
+    emoji("😁", "beaming face with smiling eyes", &["grin", "happy"]),
+    emoji("😆", "grinning squinting face", &["laugh", "happy"]),
+    emoji("😅", "grinning face with sweat", &["relief", "nervous"]),
+    emoji(
+        "🤣",
+        "rolling on the floor laughing",
+        &["lol", "laugh", "rofl"],
+    ),
+    emoji(
+        "😂",
+        "face with tears of joy",
+        &["lol", "laugh", "cry", "笑哭"],
+    ),
+    emoji("🙂", "slightly smiling face", &["smile"]),
+    emoji("🙃", "upside-down face", &["sarcasm"]),
+    emoji("😉", "winking face", &["wink"]),
+    emoji(
+        "😊",
+        "smiling face with smiling eyes",
+        &["blush", "smile", "微笑"],
+    ),
+    emoji("😇", "smiling face with halo", &["angel", "innocent"]),
+    emoji("🥰", "smiling face with hearts", &["love", "adore"]),
+    emoji("😍", "smiling face with heart-eyes", &["love", "crush"]),
+    emoji("🤩", "star-struck", &["wow"]),
+    emoji("😘", "face blowing a kiss", &["love"]),
+    emoji("😋", "face savoring food", &["yum", "delicious", "tongue"]),
+    emoji("😛", "face with tongue", &[]),
+    emoji("😜", "winking face with tongue", &["wink", "crazy"]),
+    emoji("🤪", "zany face", &["crazy", "goofy"]),
+    emoji("🤑", "money-mouth face", &["rich"]),
+    emoji("🤗", "hugging face", &["hug", "拥抱"]),
+    emoji("🤭", "face with hand over mouth", &["oops", "giggle"]),
+    emoji("🤫", "shushing face", &["shush", "quiet", "secret"]),
+    emoji("🤔", "thinking face", &["think", "hmm", "思考"]),
+    emoji("🤐", "zipper-mouth face", &["zip", "secret", "quiet"]),
+    emoji(
+        "🤨",
+        "face with raised eyebrow",
+        &["skeptical", "suspicious"],
+    ),
+    emoji("😐", "neutral face", &["meh"]),
+    emoji("😑", "expressionless face", &["meh"]),
+    emoji("😶", "face without mouth", &["speechless", "silent"]),
+    emoji("😏", "smirking face", &["smirk"]),
+    emoji("😒", "unamused face", &["meh"]),
+    emoji("🙄", "face with rolling eyes", &["eye", "roll", "whatever"]),
+    emoji("😬", "grimacing face", &["grimace", "awkward"]),
+    emoji("😌", "relieved face", &["calm"]),
+    emoji("😔", "pensive face", &["sad"]),
+    emoji("😪", "sleepy face", &["tired"]),
+    emoji("🤤", "drooling face", &["drool"]),
+    emoji("😴", "sleeping face", &["sleep", "tired", "zzz", "睡觉"]),
+    emoji("😷", "face with medical mask", &["sick"]),
+    emoji("🤒", "face with thermometer", &["sick", "fever"]),
+    emoji("🤕", "face with head-bandage", &["hurt", "injured"]),
+    emoji("🤢", "nauseated face", &["sick"]),
+    emoji("🤮", "face with open mouth vomiting", &["vomit", "sick"]),
+    emoji("🥵", "overheated face", &["hot", "sweat", "heat"]),
+    emoji("🥶", "freezing face", &["cold"]),
+    emoji("🥴", "woozy face", &["drunk", "dizzy"]),
+    emoji("😵", "dizzy face", &[]),
+    emoji(
+        "🤯",
+        "exploding head",
+        &["mind", "blown", "explode", "shock"],
+    ),
+    emoji(
+        "🥳",
+        "partying face",
+        &["party", "celebrate", "birthday", "庆祝"],
+    ),
+    emoji("😎", "smiling face with sunglasses", &["cool", "酷"]),
+    emoji("🤓", "nerd face", &["geek", "glasses"]),
+    emoji("🧐", "face with monocle", &["inspect"]),
+    emoji("😕", "confused face", &[]),
+    emoji("😟", "worried face", &[]),
+    emoji("🙁", "slightly frowning face", &["frown", "sad"]),
+    emoji("😮", "face with open mouth", &["wow", "surprised"]),
+    emoji("😲", "astonished face", &["shock"]),
+    emoji("😳", "flushed face", &["embarrassed", "blush"]),
+    emoji("🥺", "pleading face", &["puppy", "eyes", "please"]),
+    emoji("🥹", "face holding back tears", &["touched", "grateful"]),
+    emoji("😦", "frowning face with open mouth", &["frown", "shock"]),
+    emoji("😧", "anguished face", &["anguish"]),
+    emoji("😨", "fearful face", &["fear", "scared"]),
+    emoji("😰", "anxious face with sweat", &[]),
+    emoji("😢", "crying face", &["cry", "sad", "tear", "哭"]),
+    emoji("😭", "loudly crying face", &["sob", "cry", "sad", "大哭"]),
+    emoji("😱", "face screaming in fear", &["scream", "shock"]),
+    emoji("😖", "confounded face", &[]),
+    emoji("😣", "persevering face", &["persevere"]),
+    emoji("😞", "disappointed face", &["sad"]),
+    emoji("😓", "downcast face with sweat", &[]),
+    emoji("😩", "weary face", &["tired"]),
+    emoji("😫", "tired face", &["exhausted"]),
+    emoji("🥱", "yawning face", &["yawn", "bored", "tired"]),
+    emoji("😤", "face with steam from nose", &["triumph", "huff"]),
+    emoji("😡", "enraged face", &["angry", "rage", "mad", "生气"]),
+    emoji("😠", "angry face", &["mad"]),
+    emoji(
+        "🤬",
+        "face with symbols on mouth",
+        &["swear", "curse", "angry"],
+    ),
+    emoji("😈", "smiling face with horns", &["devil", "evil"]),
+    emoji("💀", "skull", &["dead"]),
+    emoji("💩", "pile of poo", &["poop"]),
+    emoji("🤡", "clown face", &[]),
+    emoji("👻", "ghost", &["halloween"]),
+    emoji("👽", "alien", &["ufo"]),
+    emoji("🤖", "robot", &["bot"]),
+    emoji("😺", "grinning cat", &["smile"]),
+    emoji("❤️", "red heart", &["love", "爱心"]),
+    emoji("🧡", "orange heart", &[]),
+    emoji("💛", "yellow heart", &[]),
+    emoji("💚", "green heart", &[]),
+    emoji("💙", "blue heart", &[]),
+    emoji("💜", "purple heart", &[]),
+    emoji("🖤", "black heart", &[]),
+    emoji("🤍", "white heart", &[]),
+    emoji("💔", "broken heart", &["sad"]),
+    emoji("💕", "two hearts", &["love"]),
+    emoji("💯", "hundred points", &["perfect", "score"]),
+    emoji("💢", "anger symbol", &[]),
+    emoji("💥", "collision", &["boom", "explosion"]),
+    emoji("💫", "dizzy", &["star"]),
+    emoji("💦", "sweat droplets", &["water", "drops"]),
+    emoji("💨", "dashing away", &["dash", "fast", "wind"]),
+    emoji("💬", "speech balloon", &["chat", "message", "comment"]),
+    emoji("💭", "thought balloon", &["bubble"]),
+    emoji("👋", "waving hand", &["wave", "hello", "hi", "bye", "你好"]),
+    emoji("🤚", "raised back of hand", &[]),
+    emoji("✋", "raised hand", &["stop", "high", "five"]),
+    emoji("🖖", "vulcan salute", &["spock"]),
+    emoji("👌", "ok hand", &["okay", "perfect"]),
+    emoji("🤌", "pinched fingers", &["italian"]),
+    emoji("✌️", "victory hand", &["peace"]),
+    emoji("🤞", "crossed fingers", &["luck", "hope"]),
+    emoji("🤟", "love-you gesture", &[]),
+    emoji("🤘", "sign of the horns", &["rock", "metal"]),
+    emoji("🤙", "call me hand", &["shaka"]),
+    emoji("👈", "backhand index pointing left", &["point"]),
+    emoji("👉", "backhand index pointing right", &["point"]),
+    emoji("👆", "backhand index pointing up", &["point"]),
+    emoji("👇", "backhand index pointing down", &["point"]),
+    emoji("☝️", "index pointing up", &["point"]),
+    emoji(
+        "👍",
+        "thumbs up",
+        &["yes", "like", "good", "ok", "赞", "+1"],
+    ),
+    emoji("👎", "thumbs down", &["no", "dislike", "bad", "-1"]),
+    emoji("✊", "raised fist", &[]),
+    emoji("👊", "oncoming fist", &["punch", "bump"]),
+    emoji(
+        "👏",
+        "clapping hands",
+        &["clap", "applause", "bravo", "鼓掌"],
+    ),
+    emoji("🙌", "raising hands", &["hooray", "celebrate", "raise"]),
+    emoji("👐", "open hands", &[]),
+    emoji("🤝", "handshake", &["deal", "agree"]),
+    emoji(
+        "🙏",
+        "folded hands",
+        &["pray", "please", "thanks", "namaste", "谢谢"],
+    ),
+    emoji("✍️", "writing hand", &["write"]),
+    emoji("💪", "flexed biceps", &["muscle", "strong", "flex", "加油"]),
+    emoji("🧠", "brain", &["smart"]),
+    emoji("👀", "eyes", &["look", "see"]),
+    emoji("👁️", "eye", &["see"]),
+    emoji("👶", "baby", &[]),
+    emoji("🧒", "child", &["kid"]),
+    emoji("👦", "boy", &[]),
+    emoji("👧", "girl", &[]),
+    emoji("🧑", "person", &[]),
+    emoji("👨", "man", &[]),
+    emoji("👩", "woman", &[]),
+    emoji("🧓", "older person", &[]),
+    emoji("👴", "older man", &["old", "grandpa"]),
+    emoji("👵", "older woman", &["old", "grandma"]),
+    emoji("🙋", "person raising hand", &["raise", "question"]),
+    emoji("🤦", "person facepalming", &["facepalm"]),
+    emoji("🤷", "person shrugging", &["shrug", "whatever", "dunno"]),
+    emoji(
+        "🧑‍💻",
+        "technologist",
+        &["developer", "programmer", "coder", "hacker"],
+    ),
+    emoji(
+        "🎉",
+        "party popper",
+        &["tada", "celebrate", "congrats", "恭喜"],
+    ),
+    emoji("🎊", "confetti ball", &["celebrate"]),
+    emoji("🎈", "balloon", &["party"]),
+    emoji("🎁", "wrapped present", &["gift", "birthday", "礼物"]),
+    emoji("🎂", "birthday cake", &["生日"]),
+    emoji("🎄", "christmas tree", &["xmas"]),
+    emoji("🎃", "jack-o-lantern", &["pumpkin", "halloween"]),
+    emoji("🧧", "red envelope", &["hongbao", "红包"]),
+    emoji("🏆", "trophy", &["win", "champion"]),
+    emoji("🥇", "1st place medal", &["gold", "first", "winner"]),
+    emoji("🏅", "sports medal", &[]),
+    emoji("⚽", "soccer ball", &["football"]),
+    emoji("🏀", "basketball", &[]),
+    emoji("🎮", "video game", &["controller", "gaming", "游戏"]),
+    emoji("🎲", "game die", &["dice"]),
+    emoji("🎯", "bullseye", &["target", "goal", "dart"]),
+    emoji("🎵", "musical note", &["music"]),
+    emoji("🎶", "musical notes", &["music", "song"]),
+    emoji("🎧", "headphone", &["headphones", "music"]),
+    emoji("🎤", "microphone", &["karaoke", "sing"]),
+    emoji("🎬", "clapper board", &["movie", "film"]),
+    emoji("📷", "camera", &["photo"]),
+    emoji("📱", "mobile phone", &["smartphone", "手机"]),
+    emoji("💻", "laptop", &["computer", "电脑"]),
+    emoji("🖥️", "desktop computer", &["monitor"]),
+    emoji("⌨️", "keyboard", &["type"]),
+    emoji("🖱️", "computer mouse", &["click"]),
+    emoji("💾", "floppy disk", &["save"]),
+    emoji("💿", "optical disk", &["cd", "disc"]),
+    emoji("📀", "dvd", &["disc"]),
+    emoji("🔋", "battery", &["power"]),
+    emoji("🔌", "electric plug", &["power"]),
+    emoji("💡", "light bulb", &["idea", "灯泡"]),
+    emoji("🔦", "flashlight", &["torch"]),
+    emoji("📚", "books", &["library", "study", "read", "书"]),
+    emoji("📖", "open book", &["read"]),
+    emoji("📝", "memo", &["note", "write", "笔记"]),
+    emoji("✏️", "pencil", &["write", "edit"]),
+    emoji("📌", "pushpin", &["pin"]),
+    emoji("📎", "paperclip", &["attachment"]),
+    emoji("✂️", "scissors", &["cut"]),
+    emoji("📁", "file folder", &["directory"]),
+    emoji("📂", "open file folder", &[]),
+    emoji("📅", "calendar", &["date"]),
+    emoji("📆", "tear-off calendar", &["tear-off"]),
+    emoji(
+        "📈",
+        "chart increasing",
+        &["up", "increase", "growth", "trend"],
+    ),
+    emoji("📉", "chart decreasing", &["down", "decrease"]),
+    emoji("📊", "bar chart", &["stats", "graph"]),
+    emoji("📋", "clipboard", &[]),
+    emoji("📦", "package", &["box", "shipping"]),
+    emoji("📧", "e-mail", &["email", "mail"]),
+    emoji("✉️", "envelope", &["email", "mail", "letter"]),
+    emoji("📨", "incoming envelope", &["mail"]),
+    emoji("📬", "open mailbox with raised flag", &["mail"]),
+    emoji("🔒", "lock", &["locked", "secure"]),
+    emoji("🔓", "unlocked", &["unlock", "open"]),
+    emoji("🔑", "key", &["password"]),
+    emoji("🔨", "hammer", &["tool", "build"]),
+    emoji("🔧", "wrench", &["tool", "fix", "settings"]),
+    emoji("⚙️", "gear", &["settings", "cog"]),
+    emoji("🧰", "toolbox", &["tools"]),
+    emoji("🛠️", "hammer and wrench", &["tools", "build"]),
+    emoji("🧪", "test tube", &["experiment", "lab"]),
+    emoji("🔬", "microscope", &["science"]),
+    emoji("🔭", "telescope", &[]),
+    emoji("📡", "satellite antenna", &["signal"]),
+    emoji("💰", "money bag", &["rich", "钱"]),
+    emoji("💵", "dollar banknote", &["money", "cash"]),
+    emoji("💳", "credit card", &["payment"]),
+    emoji("🛒", "shopping cart", &[]),
+    emoji("🔔", "bell", &["notification"]),
+    emoji("🔕", "bell with slash", &["mute", "silent"]),
+    emoji("📢", "loudspeaker", &["announcement"]),
+    emoji(
+        "🔍",
+        "magnifying glass tilted left",
+        &["search", "magnifier", "find", "zoom", "搜索"],
+    ),
+    emoji("⏰", "alarm clock", &["time"]),
+    emoji("⏳", "hourglass not done", &["wait", "time"]),
+    emoji("⌛", "hourglass done", &["time"]),
+    emoji("⏱️", "stopwatch", &["timer"]),
+    emoji("🕐", "one o’clock", &["clock"]),
+    emoji("🌍", "globe showing europe-africa", &["earth", "world"]),
+    emoji("🌎", "globe showing americas", &["earth", "world"]),
+    emoji(
+        "🌏",
+        "globe showing asia-australia",
+        &["earth", "world", "地球"],
+    ),
+    emoji("🗺️", "world map", &[]),
+    emoji("🏠", "house", &["home", "家"]),
+    emoji("🏢", "office building", &["work"]),
+    emoji("🏫", "school", &[]),
+    emoji("🏥", "hospital", &[]),
+    emoji("⛪", "church", &[]),
+    emoji("🗼", "tokyo tower", &[]),
+    emoji("🗽", "statue of liberty", &["new", "york"]),
+    emoji("🚗", "automobile", &["car", "汽车"]),
+    emoji("🚕", "taxi", &["cab"]),
+    emoji("🚌", "bus", &[]),
+    emoji("🚲", "bicycle", &["bike"]),
+    emoji("🚀", "rocket", &["launch", "ship", "space", "火箭"]),
+    emoji("✈️", "airplane", &["plane", "flight", "travel", "飞机"]),
+    emoji("🚢", "ship", &["boat"]),
+    emoji("🚦", "vertical traffic light", &[]),
+    emoji("🚧", "construction", &["wip", "work", "in", "progress"]),
+    emoji("⛽", "fuel pump", &["gas", "station"]),
+    emoji("☀️", "sun", &["sunny", "weather"]),
+    emoji("🌙", "crescent moon", &["night"]),
+    emoji("⭐", "star", &["favorite"]),
+    emoji("🌟", "glowing star", &["shine"]),
+    emoji("✨", "sparkles", &["shiny", "magic", "new"]),
+    emoji(
+        "⚡",
+        "high voltage",
+        &["lightning", "zap", "electric", "fast"],
+    ),
+    emoji("🔥", "fire", &["hot", "lit", "flame", "火"]),
+    emoji("🌈", "rainbow", &[]),
+    emoji("☁️", "cloud", &["weather"]),
+    emoji("🌧️", "cloud with rain", &["weather"]),
+    emoji("⛄", "snowman without snow", &["winter"]),
+    emoji("❄️", "snowflake", &["cold", "winter", "snow"]),
+    emoji("🌊", "water wave", &["ocean", "sea"]),
+    emoji("💧", "droplet", &["water"]),
+    emoji("🌸", "cherry blossom", &["sakura", "flower", "樱花"]),
+    emoji("🌹", "rose", &["flower"]),
+    emoji("🌻", "sunflower", &[]),
+    emoji("🌲", "evergreen tree", &[]),
+    emoji("🌵", "cactus", &[]),
+    emoji("🍀", "four leaf clover", &["luck"]),
+    emoji("🍁", "maple leaf", &["autumn", "canada"]),
+    emoji("🐶", "dog face", &["puppy", "狗"]),
+    emoji("🐱", "cat face", &["kitten", "猫"]),
+    emoji("🐭", "mouse face", &[]),
+    emoji("🐰", "rabbit face", &["bunny"]),
+    emoji("🦊", "fox", &[]),
+    emoji("🐻", "bear", &[]),
+    emoji("🐼", "panda", &["熊猫"]),
+    emoji("🐨", "koala", &[]),
+    emoji("🐯", "tiger face", &[]),
+    emoji("🦁", "lion", &[]),
+    emoji("🐮", "cow face", &[]),
+    emoji("🐷", "pig face", &[]),
+    emoji("🐸", "frog", &[]),
+    emoji("🐵", "monkey face", &[]),
+    emoji("🙈", "see-no-evil monkey", &[]),
+    emoji("🐔", "chicken", &[]),
+    emoji("🐧", "penguin", &["linux"]),
+    emoji("🐦", "bird", &[]),
+    emoji("🦄", "unicorn", &[]),
+    emoji("🐝", "honeybee", &["bee"]),
+    emoji("🐛", "bug", &["insect"]),
+    emoji("🦋", "butterfly", &[]),
+    emoji("🐢", "turtle", &["slow"]),
+    emoji("🐍", "snake", &["python"]),
+    emoji("🦀", "crab", &["rust", "rustacean"]),
+    emoji("🐙", "octopus", &["github"]),
+    emoji("🐳", "spouting whale", &["docker"]),
+    emoji("🐟", "fish", &[]),
+    emoji("🍎", "red apple", &["fruit", "苹果"]),
+    emoji("🍊", "tangerine", &["orange", "fruit"]),
+    emoji("🍋", "lemon", &[]),
+    emoji("🍌", "banana", &[]),
+    emoji("🍉", "watermelon", &["西瓜"]),
+    emoji("🍇", "grapes", &[]),
+    emoji("🍓", "strawberry", &[]),
+    emoji("🍑", "peach", &[]),
+    emoji("🍒", "cherries", &[]),
+    emoji("🥑", "avocado", &[]),
+    emoji("🍅", "tomato", &[]),
+    emoji("🌶️", "hot pepper", &["chili", "spicy"]),
+    emoji("🌽", "ear of corn", &[]),
+    emoji("🥕", "carrot", &[]),
+    emoji("🍞", "bread", &[]),
+    emoji("🧀", "cheese wedge", &[]),
+    emoji("🍔", "hamburger", &["burger"]),
+    emoji("🍟", "french fries", &[]),
+    emoji("🍕", "pizza", &[]),
+    emoji("🌭", "hot dog", &[]),
+    emoji("🌮", "taco", &[]),
+    emoji("🍜", "steaming bowl", &["noodles", "ramen", "面条"]),
+    emoji("🍣", "sushi", &[]),
+    emoji("🍚", "cooked rice", &["米饭"]),
+    emoji("🥟", "dumpling", &["饺子"]),
+    emoji("🍦", "soft ice cream", &[]),
+    emoji("🍩", "doughnut", &["donut"]),
+    emoji("🍪", "cookie", &[]),
+    emoji("🍫", "chocolate bar", &[]),
+    emoji("🍬", "candy", &[]),
+    emoji("🍿", "popcorn", &[]),
+    emoji("☕", "hot beverage", &["coffee", "tea", "drink", "咖啡"]),
+    emoji("🍵", "teacup without handle", &["tea", "green", "茶"]),
+    emoji("🧋", "bubble tea", &["boba", "奶茶"]),
+    emoji("🍺", "beer mug", &["啤酒"]),
+    emoji("🍻", "clinking beer mugs", &["cheers", "beers"]),
+    emoji("🍷", "wine glass", &[]),
+    emoji("🥂", "clinking glasses", &["champagne", "toast", "cheers"]),
+    emoji("🍹", "tropical drink", &["cocktail"]),
+    emoji("✅", "check mark button", &["done", "yes", "ok", "完成"]),
+    emoji("☑️", "check box with check", &["checkbox", "done"]),
+    emoji("✔️", "check mark", &["done", "tick"]),
+    emoji("❌", "cross mark", &["no", "wrong", "x", "cancel", "错"]),
+    emoji("❎", "cross mark button", &[]),
+    emoji("❓", "red question mark", &[]),
+    emoji("❗", "red exclamation mark", &["important"]),
+    emoji("⚠️", "warning", &["caution", "alert", "警告"]),
+    emoji("⛔", "no entry", &["forbidden"]),
+    emoji("🚫", "prohibited", &["forbidden", "no"]),
+    emoji("♻️", "recycling symbol", &["recycle"]),
+    emoji("🆗", "ok button", &[]),
+    emoji("🆕", "new button", &[]),
+    emoji("🆓", "free button", &[]),
+    emoji("🔴", "red circle", &["dot"]),
+    emoji("🟠", "orange circle", &[]),
+    emoji("🟡", "yellow circle", &[]),
+    emoji("🟢", "green circle", &["dot"]),
+    emoji("🔵", "blue circle", &["dot"]),
+    emoji("🟣", "purple circle", &[]),
+    emoji("⚫", "black circle", &[]),
+    emoji("⚪", "white circle", &[]),
+    emoji("🟥", "red square", &[]),
+    emoji("🟩", "green square", &[]),
+    emoji("🟦", "blue square", &[]),
+    emoji("⬛", "black large square", &[]),
+    emoji("⬜", "white large square", &[]),
+    emoji("🏁", "chequered flag", &["finish", "race"]),
+    emoji("🚩", "triangular flag", &["red"]),
+    emoji("🏳️", "white flag", &["surrender"]),
+    emoji("🏴‍☠️", "pirate flag", &["jolly", "roger"]),
+    emoji("🇨🇳", "flag: china", &["cn", "中国"]),
+    emoji("🇺🇸", "flag: united states", &["usa", "us", "america"]),
+    emoji("🇬🇧", "flag: united kingdom", &["uk", "gb", "britain"]),
+    emoji("🇯🇵", "flag: japan", &["jp", "日本"]),
+    emoji("🇩🇪", "flag: germany", &["de"]),
+    emoji("🇫🇷", "flag: france", &["fr"]),
+    emoji("🇪🇺", "flag: european union", &["eu", "europe"]),
+    emoji("→", "rightwards arrow", &["right"]),
+    emoji("←", "leftwards arrow", &["left"]),
+    emoji("↑", "upwards arrow", &["up"]),
+    emoji("↓", "downwards arrow", &["down"]),
+    emoji("↔", "left right arrow", &[]),
+    emoji("⇒", "rightwards double arrow", &["implies"]),
+    emoji("↩", "leftwards arrow with hook", &["return", "enter"]),
+    emoji("⏎", "return symbol", &["enter", "key"]),
+    emoji(
+        "⌘",
+        "command key",
+        &["cmd", "mac", "place", "of", "interest"],
+    ),
+    emoji("⌥", "option key", &["alt", "mac"]),
+    emoji("⇧", "shift key", &[]),
+    emoji("⌃", "control key", &["ctrl"]),
+    emoji("⎋", "escape key", &["esc"]),
+    emoji("⌫", "backspace", &["delete"]),
+    emoji("✓", "check mark", &["tick"]),
+    emoji("✗", "ballot x", &["cross"]),
+    emoji("•", "bullet", &["dot"]),
+    emoji("·", "middle dot", &["interpunct"]),
+    emoji("…", "horizontal ellipsis", &["dots"]),
+    emoji("—", "em dash", &[]),
+    emoji("–", "en dash", &[]),
+    emoji("°", "degree sign", &["temperature"]),
+    emoji("±", "plus-minus sign", &[]),
+    emoji("×", "multiplication sign", &["multiply", "times"]),
+    emoji("÷", "division sign", &["divide"]),
+    emoji("≈", "almost equal to", &["approximately"]),
+    emoji("≠", "not equal to", &[]),
+    emoji("≤", "less-than or equal to", &[]),
+    emoji("≥", "greater-than or equal to", &[]),
+    emoji("∞", "infinity", &[]),
+    emoji("√", "square root", &[]),
+    emoji("∑", "summation", &["sum", "sigma"]),
+    emoji("π", "greek small letter pi", &[]),
+    emoji("µ", "micro sign", &["mu"]),
+    emoji("Δ", "greek capital letter delta", &["change"]),
+    emoji("λ", "greek small letter lambda", &[]),
+    emoji("Ω", "greek capital letter omega", &["ohm"]),
+    emoji("‰", "per mille sign", &[]),
+    emoji("©", "copyright sign", &[]),
+    emoji("®", "registered sign", &["trademark"]),
+    emoji("™", "trade mark sign", &["trademark", "tm"]),
+    emoji("§", "section sign", &[]),
+    emoji("¶", "pilcrow sign", &["paragraph"]),
+    emoji("€", "euro sign", &["currency", "欧元"]),
+    emoji("£", "pound sign", &["sterling", "currency"]),
+    emoji("¥", "yen sign", &["yuan", "currency", "人民币", "日元"]),
+    emoji("₿", "bitcoin sign", &["btc", "crypto"]),
+    emoji("¢", "cent sign", &["currency"]),
+    emoji("♠", "spade suit", &["cards"]),
+    emoji("♥", "heart suit", &["cards"]),
+    emoji("♦", "diamond suit", &["cards"]),
+    emoji("♣", "club suit", &["cards"]),
+    emoji("★", "black star", &[]),
+    emoji("☆", "white star", &[]),
+    emoji("♪", "eighth note", &["music"]),
+    emoji("☐", "ballot box", &["checkbox"]),
+    emoji("☺", "smiling face", &[]),
+    emoji("☯", "yin yang", &[]),
+    emoji("☮", "peace symbol", &[]),
+    emoji("⚓", "anchor", &[]),
+    emoji("⌚", "watch", &[]),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn symbols_are_listed_once_with_lowercase_names() {
+        let mut seen = HashSet::new();
+        for emoji in EMOJI {
+            assert!(seen.insert(emoji.symbol), "{} twice", emoji.symbol);
+            assert_eq!(emoji.name, emoji.name.to_lowercase());
+            assert!(!emoji.keywords.contains(&emoji.name), "{}", emoji.name);
+        }
+        let thumbs_up = EMOJI
+            .iter()
+            .find(|emoji| emoji.name == "thumbs up")
+            .unwrap();
+        assert_eq!(thumbs_up.code_points(), "U+1F44D");
+        let flag = EMOJI
+            .iter()
+            .find(|emoji| emoji.name == "flag: japan")
+            .unwrap();
+        assert_eq!(flag.code_points(), "U+1F1EF U+1F1F5");
+    }
+}
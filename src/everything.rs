@@ -0,0 +1,153 @@
+//! Optional file search through the Everything SDK (voidtools).
+//!
+//! The SDK DLL is loaded at runtime so egg keeps working when Everything is not
+//! installed; queries are answered by the running Everything service over IPC.
+
+use std::{
+    ffi::OsStr,
+    mem,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+use log::{debug, warn};
+use windows::{
+    core::{s, PCSTR, PCWSTR, PWSTR},
+    Win32::{
+        Foundation::{BOOL, HMODULE},
+        System::LibraryLoader::{GetProcAddress, LoadLibraryW},
+    },
+};
+
+use crate::windows_utils::os_str_to_wide;
+
+const DEFAULT_DLL_NAME: &str = "Everything64.dll";
+const EVERYTHING_ERROR_IPC: u32 = 2;
+const MAX_PATH_CHARS: usize = 32_768;
+
+type RawProc = unsafe extern "system" fn() -> isize;
+type SetSearchW = unsafe extern "system" fn(PCWSTR);
+type SetMax = unsafe extern "system" fn(u32);
+type QueryW = unsafe extern "system" fn(BOOL) -> BOOL;
+type GetNumResults = unsafe extern "system" fn() -> u32;
+type GetResultFullPathNameW = unsafe extern "system" fn(u32, PWSTR, u32) -> u32;
+type GetLastError = unsafe extern "system" fn() -> u32;
+
+struct EverythingApi {
+    set_search: SetSearchW,
+    set_max: SetMax,
+    query: QueryW,
+    get_num_results: GetNumResults,
+    get_result_full_path_name: GetResultFullPathNameW,
+    get_last_error: GetLastError,
+}
+
+static API: OnceLock<Result<EverythingApi, String>> = OnceLock::new();
+/// The SDK keeps query state in globals, so only one query may run at a time.
+static QUERY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Runs `query` against Everything and returns up to `limit` full paths.
+pub fn search(query: &str, limit: u32, dll_path: Option<&str>) -> Result<Vec<String>, String> {
+    let api = API
+        .get_or_init(|| load_api(dll_path))
+        .as_ref()
+        .map_err(Clone::clone)?;
+    let _guard = QUERY_LOCK
+        .lock()
+        .map_err(|_| "Everything 查询锁已损坏".to_string())?;
+
+    let query_wide = os_str_to_wide(OsStr::new(query));
+    let mut results = Vec::new();
+    unsafe {
+        (api.set_search)(PCWSTR(query_wide.as_ptr()));
+        (api.set_max)(limit);
+        if !(api.query)(BOOL::from(true)).as_bool() {
+            let code = (api.get_last_error)();
+            return Err(if code == EVERYTHING_ERROR_IPC {
+                "Everything 未运行".to_string()
+            } else {
+                format!("Everything 查询失败 (错误码 {code})")
+            });
+        }
+
+        let count = (api.get_num_results)();
+        let mut buffer = vec![0u16; MAX_PATH_CHARS];
+        for index in 0..count {
+            let len = (api.get_result_full_path_name)(
+                index,
+                PWSTR(buffer.as_mut_ptr()),
+                buffer.len() as u32,
+            ) as usize;
+            if len == 0 {
+                continue;
+            }
+            results.push(String::from_utf16_lossy(&buffer[..len.min(buffer.len())]));
+        }
+    }
+    debug!(
+        "everything returned {} results for {query:?}",
+        results.len()
+    );
+    Ok(results)
+}
+
+fn load_api(dll_path: Option<&str>) -> Result<EverythingApi, String> {
+    let module = dll_candidates(dll_path)
+        .into_iter()
+        .find_map(|candidate| {
+            let wide = os_str_to_wide(candidate.as_os_str());
+            unsafe { LoadLibraryW(PCWSTR(wide.as_ptr())).ok() }
+        })
+        .ok_or_else(|| {
+            warn!("Everything SDK DLL not found");
+            "未找到 Everything SDK (Everything64.dll)".to_string()
+        })?;
+
+    unsafe {
+        Ok(EverythingApi {
+            set_search: mem::transmute::<RawProc, SetSearchW>(proc(
+                module,
+                s!("Everything_SetSearchW"),
+            )?),
+            set_max: mem::transmute::<RawProc, SetMax>(proc(module, s!("Everything_SetMax"))?),
+            query: mem::transmute::<RawProc, QueryW>(proc(module, s!("Everything_QueryW"))?),
+            get_num_results: mem::transmute::<RawProc, GetNumResults>(proc(
+                module,
+                s!("Everything_GetNumResults"),
+            )?),
+            get_result_full_path_name: mem::transmute::<RawProc, GetResultFullPathNameW>(proc(
+                module,
+                s!("Everything_GetResultFullPathNameW"),
+            )?),
+            get_last_error: mem::transmute::<RawProc, GetLastError>(proc(
+                module,
+                s!("Everything_GetLastError"),
+            )?),
+        })
+    }
+}
+
+unsafe fn proc(module: HMODULE, name: PCSTR) -> Result<RawProc, String> {
+    GetProcAddress(module, name).ok_or_else(|| {
+        let name = name.to_string().unwrap_or_default();
+        format!("Everything SDK 缺少导出函数 {name}")
+    })
+}
+
+fn dll_candidates(configured: Option<&str>) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(path) = configured.map(str::trim).filter(|path| !path.is_empty()) {
+        candidates.push(PathBuf::from(path));
+    }
+    for var in ["ProgramFiles", "ProgramFiles(x86)"] {
+        if let Some(base) = std::env::var_os(var) {
+            candidates.push(
+                PathBuf::from(base)
+                    .join("Everything")
+                    .join(DEFAULT_DLL_NAME),
+            );
+        }
+    }
+    candidates.push(PathBuf::from(DEFAULT_DLL_NAME));
+    candidates
+}
@@ -1,8 +1,20 @@
+use std::{collections::HashMap, path::Path, thread, time::Duration};
+#[cfg(windows)]
 use std::{
     ffi::{OsStr, OsString},
     ptr,
 };
+#[cfg(unix)]
+use std::{
+    os::unix::fs::PermissionsExt,
+    process::{Command, Stdio},
+};
 
+#[cfg(windows)]
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Local;
+use log::info;
+#[cfg(windows)]
 use windows::{
     core::PCWSTR,
     Win32::{
@@ -12,18 +24,672 @@ use windows::{
     },
 };
 
+#[cfg(all(unix, not(target_os = "macos")))]
+use crate::path_commands::find_on_path;
+#[cfg(unix)]
+use crate::unix_utils::{copy_to_clipboard, focus_window, kill_process, run_system_command};
+#[cfg(windows)]
+use crate::windows_utils::{
+    copy_to_clipboard, focus_window, kill_process, os_str_to_wide, run_system_command,
+};
 use crate::{
-    models::ApplicationInfo,
+    config::{AppConfig, CommandShell, LaunchOverride},
+    git_repos::{self, is_git_repo, repo_folder},
+    models::{ActionVariant, AppType, ApplicationInfo},
+    notes,
+    plugins::{self, PluginCall},
+    private_window::{private_browser, PRIVATE_WINDOWS_AVAILABLE},
+    snippets,
     state::PendingAction,
-    windows_utils::os_str_to_wide,
+    system_commands::SystemCommand,
+    todo,
 };
 
-/// Execute a pending action (launch app, open URL, etc.)
-pub fn execute_action(action: &PendingAction, run_as_admin: bool) -> Result<(), String> {
+/// Terminal emulators tried in order; `x-terminal-emulator` is Debian's choice
+/// of the installed ones.
+#[cfg(all(unix, not(target_os = "macos")))]
+const TERMINALS: [&str; 7] = [
+    "x-terminal-emulator",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "kitty",
+    "alacritty",
+    "xterm",
+];
+
+/// ShellExecute results below 33 are errors; these are the ones that can clear up.
+#[cfg(windows)]
+const SE_ERR_FNF: isize = 2;
+#[cfg(windows)]
+const SE_ERR_PNF: isize = 3;
+#[cfg(windows)]
+const SE_ERR_ACCESSDENIED: isize = 5;
+#[cfg(windows)]
+const SE_ERR_OOM: isize = 8;
+#[cfg(windows)]
+const SE_ERR_SHARE: isize = 26;
+#[cfg(windows)]
+const SE_ERR_DDETIMEOUT: isize = 28;
+#[cfg(windows)]
+const SE_ERR_DDEBUSY: isize = 30;
+
+/// How often a launch that failed transiently is tried again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    /// Wait before the first retry; it doubles for each further one.
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            retries: config.launch_retries,
+            delay: Duration::from_millis(config.launch_retry_delay_ms),
+        }
+    }
+
+    /// Wait before retry number `retry`, counting from 1.
+    fn delay_before(&self, retry: u32) -> Duration {
+        self.delay
+            .saturating_mul(1 << retry.saturating_sub(1).min(16))
+    }
+}
+
+/// Execute a pending action (launch app, open URL, etc.) the way `variant` asks.
+///
+/// A launch that fails in a way that may clear up is retried as `retry` allows;
+/// `on_retry` hears about each retry with the error, the retry number and the wait.
+/// With `dry_run` the resolved plan, including every fallback, is printed and
+/// logged instead of launched.
+pub fn execute_action(
+    action: &PendingAction,
+    variant: ActionVariant,
+    dry_run: bool,
+    retry: RetryPolicy,
+    on_retry: impl FnMut(&str, u32, Duration),
+) -> Result<(), String> {
+    let plan = plan_variant(action, variant)?;
+    if dry_run {
+        for line in describe_plan(&plan) {
+            println!("Dry run: {line}");
+            info!("dry run: {line}");
+        }
+        return Ok(());
+    }
+    run_with_retries(retry, || run_plan(&plan), thread::sleep, on_retry)
+}
+
+/// Variants the actions menu offers for `action`, [`ActionVariant::Open`] first.
+pub fn action_variants(action: &PendingAction) -> Vec<ActionVariant> {
+    match action {
+        PendingAction::Application(app) if is_git_repo(app) => vec![
+            ActionVariant::Open,
+            ActionVariant::OpenInTerminal,
+            ActionVariant::OpenRemote,
+            ActionVariant::OpenContainingFolder,
+            ActionVariant::CopyPath,
+        ],
+        // The proxy's arguments name the web app, so they are not editable.
+        PendingAction::Application(app) if app.app_type == AppType::Pwa => vec![
+            ActionVariant::Open,
+            ActionVariant::CopyPath,
+            ActionVariant::EditAlias,
+        ],
+        PendingAction::Application(app) if app.app_type == AppType::Uninstaller => vec![
+            ActionVariant::Open,
+            ActionVariant::RunAsAdmin,
+            ActionVariant::CopyPath,
+        ],
+        // Only real files take arguments; `shell:` targets launch through the shell.
+        PendingAction::Application(app) if should_use_runas(&app.path) => vec![
+            ActionVariant::Open,
+            ActionVariant::RunAsAdmin,
+            ActionVariant::OpenContainingFolder,
+            ActionVariant::CopyPath,
+            ActionVariant::EditAlias,
+            ActionVariant::EditLaunchOptions,
+        ],
+        PendingAction::Application(_) => vec![
+            ActionVariant::Open,
+            ActionVariant::CopyPath,
+            ActionVariant::EditAlias,
+        ],
+        PendingAction::Bookmark(_) | PendingAction::Url(_) if PRIVATE_WINDOWS_AVAILABLE => vec![
+            ActionVariant::Open,
+            ActionVariant::OpenPrivate,
+            ActionVariant::CopyUrl,
+        ],
+        PendingAction::Bookmark(_) | PendingAction::Url(_) | PendingAction::Search(_) => {
+            vec![ActionVariant::Open, ActionVariant::CopyUrl]
+        }
+        PendingAction::File(_) | PendingAction::Path { .. } => vec![
+            ActionVariant::Open,
+            ActionVariant::OpenContainingFolder,
+            ActionVariant::CopyPath,
+        ],
+        PendingAction::Command { .. } => vec![ActionVariant::Open, ActionVariant::RunAsAdmin],
+        PendingAction::Task { .. } => vec![ActionVariant::Open, ActionVariant::CompleteTask],
+        PendingAction::System(_)
+        | PendingAction::Copy(_)
+        | PendingAction::Snippet(_)
+        | PendingAction::AppendToFile { .. }
+        | PendingAction::AddTask { .. }
+        | PendingAction::FocusWindow(_)
+        | PendingAction::KillProcess { .. }
+        | PendingAction::Builtin(_)
+        | PendingAction::Query(_)
+        | PendingAction::Plugin(_)
+        | PendingAction::Workspace { .. } => vec![ActionVariant::Open],
+    }
+}
+
+/// What executing an action amounts to.
+#[derive(Debug, PartialEq)]
+enum Plan {
+    /// Tried in order; each later launch runs only if the previous one failed.
+    ShellExecute(Vec<ShellLaunch>),
+    /// Handed to the default handler for the URL or file.
+    Open(String),
+    System(SystemCommand),
+    /// Placed on the clipboard.
+    Copy(String),
+    /// Written at the end of the file.
+    AppendToFile {
+        path: String,
+        entry: String,
+    },
+    /// The todo.txt task on `line` marked done.
+    CompleteTask {
+        path: String,
+        line: String,
+    },
+    /// Brought to the front.
+    FocusWindow(isize),
+    /// Ended, if `pid` still runs the program `name`.
+    KillProcess {
+        pid: u32,
+        name: String,
+    },
+    /// Sent back to the plugin that produced the result.
+    Plugin(PluginCall),
+    /// Built-in commands run inside the TUI and never reach the executor.
+    InProcess,
+    /// Each one run in turn, whether or not the ones before it worked.
+    Batch(Vec<Plan>),
+}
+
+/// A launch that did not go through.
+#[derive(Debug, PartialEq, Eq)]
+struct LaunchFailure {
+    message: String,
+    /// Whether the same launch may succeed if tried again shortly.
+    transient: bool,
+}
+
+impl From<String> for LaunchFailure {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            transient: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ShellLaunch {
+    target: String,
+    arguments: Option<String>,
+    working_directory: Option<String>,
+    verb: &'static str,
+}
+
+impl ShellLaunch {
+    fn new(
+        target: &str,
+        arguments: Option<&str>,
+        working_directory: Option<&str>,
+        run_as_admin: bool,
+    ) -> Self {
+        let non_empty = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            target: target.to_string(),
+            arguments: non_empty(arguments),
+            working_directory: non_empty(working_directory),
+            verb: if run_as_admin { "runas" } else { "open" },
+        }
+    }
+}
+
+fn plan_variant(action: &PendingAction, variant: ActionVariant) -> Result<Plan, String> {
+    if !action_variants(action).contains(&variant) {
+        return Err("该结果不支持此操作".into());
+    }
+    match variant {
+        ActionVariant::Open => plan_action(action, false),
+        ActionVariant::RunAsAdmin => plan_action(action, true),
+        ActionVariant::OpenContainingFolder => action_path(action)
+            .map(reveal_in_explorer)
+            .ok_or_else(|| "该结果没有文件路径".into()),
+        ActionVariant::CopyPath => action_path(action)
+            .map(|path| Plan::Copy(path.to_string()))
+            .ok_or_else(|| "该结果没有文件路径".into()),
+        ActionVariant::CopyUrl => action_url(action)
+            .map(|url| Plan::Copy(url.to_string()))
+            .ok_or_else(|| "该结果没有网址".into()),
+        ActionVariant::OpenPrivate => {
+            let url = action_url(action).ok_or("该结果没有网址")?;
+            // Bookmark ids start with the profile label, e.g. `Edge 默认:...`.
+            let profile = match action {
+                PendingAction::Bookmark(entry) => entry.id.split_once(':').map(|(label, _)| label),
+                _ => None,
+            };
+            let (browser, flag) = private_browser(profile).ok_or("找不到支持无痕窗口的浏览器")?;
+            Ok(private_window_plan(&browser.to_string_lossy(), flag, url))
+        }
+        ActionVariant::CompleteTask => match action {
+            PendingAction::Task { path, line } => Ok(Plan::CompleteTask {
+                path: path.clone(),
+                line: line.clone(),
+            }),
+            _ => Err("该结果不是任务".into()),
+        },
+        ActionVariant::OpenInTerminal => action_path(action)
+            .ok_or_else(|| "该结果没有文件路径".to_string())
+            .and_then(terminal_plan),
+        ActionVariant::OpenRemote => {
+            let folder = action_path(action).ok_or("该结果没有文件路径")?;
+            git_repos::remote_web_url(Path::new(folder))
+                .map(Plan::Open)
+                .ok_or_else(|| "仓库没有远程地址".into())
+        }
+        ActionVariant::EditLaunchOptions | ActionVariant::EditAlias => Ok(Plan::InProcess),
+    }
+}
+
+/// Replaces an app's indexed arguments and start folder with the user's override, if any.
+pub fn apply_launch_override(
+    action: &mut PendingAction,
+    overrides: &HashMap<String, LaunchOverride>,
+) {
+    let PendingAction::Application(app) = action else {
+        return;
+    };
+    if matches!(app.app_type, AppType::Pwa | AppType::Uninstaller) {
+        return;
+    }
+    if let Some(launch) = overrides.get(&app.id) {
+        // An empty value clears the indexed one.
+        let set = |value: &Option<String>| value.clone().filter(|value| !value.is_empty());
+        app.arguments = set(&launch.arguments);
+        app.working_directory = set(&launch.working_directory);
+    }
+}
+
+fn action_path(action: &PendingAction) -> Option<&str> {
     match action {
-        PendingAction::Application(app) => launch_application(app, run_as_admin),
-        PendingAction::Bookmark(entry) => open_url(&entry.url),
-        PendingAction::Url(url) | PendingAction::Search(url) => open_url(url),
+        // A repository's path is the editor it opens in.
+        PendingAction::Application(app) if is_git_repo(app) => repo_folder(app),
+        PendingAction::Application(app) => Some(app.path.trim()),
+        PendingAction::File(path) | PendingAction::Path { path, .. } => Some(path.trim()),
+        _ => None,
+    }
+}
+
+fn action_url(action: &PendingAction) -> Option<&str> {
+    match action {
+        PendingAction::Bookmark(entry) => Some(&entry.url),
+        PendingAction::Url(url) | PendingAction::Search(url) => Some(url),
+        _ => None,
+    }
+}
+
+fn plan_action(action: &PendingAction, run_as_admin: bool) -> Result<Plan, String> {
+    match action {
+        PendingAction::Application(app) => plan_application(app, run_as_admin),
+        PendingAction::Bookmark(entry) => Ok(Plan::Open(entry.url.clone())),
+        PendingAction::Url(url) | PendingAction::Search(url) => Ok(Plan::Open(url.clone())),
+        PendingAction::File(path) => Ok(Plan::Open(path.clone())),
+        PendingAction::Path { path, reveal } => Ok(if *reveal {
+            reveal_in_explorer(path)
+        } else {
+            Plan::Open(path.clone())
+        }),
+        PendingAction::Command {
+            command,
+            shell,
+            keep_open,
+        } => plan_command(command, *shell, *keep_open, run_as_admin),
+        PendingAction::System(command) => Ok(Plan::System(*command)),
+        PendingAction::Copy(text) => Ok(Plan::Copy(text.clone())),
+        PendingAction::Snippet(body) => Ok(Plan::Copy(snippets::expand(
+            body,
+            Local::now().naive_local(),
+        ))),
+        PendingAction::AppendToFile { path, text } => Ok(Plan::AppendToFile {
+            path: path.clone(),
+            entry: notes::note_entry(text, Local::now().naive_local()),
+        }),
+        PendingAction::AddTask { path, task } => Ok(Plan::AppendToFile {
+            path: path.clone(),
+            entry: todo::new_task_line(task, Local::now().date_naive()),
+        }),
+        PendingAction::Task { path, .. } => Ok(Plan::Open(path.clone())),
+        PendingAction::FocusWindow(id) => Ok(Plan::FocusWindow(*id)),
+        PendingAction::KillProcess { pid, name, .. } => Ok(Plan::KillProcess {
+            pid: *pid,
+            name: name.clone(),
+        }),
+        PendingAction::Plugin(call) => Ok(Plan::Plugin(call.clone())),
+        PendingAction::Builtin(_) | PendingAction::Query(_) => Ok(Plan::InProcess),
+        PendingAction::Workspace { members, .. } => members
+            .iter()
+            .map(|member| plan_action(member, false))
+            .collect::<Result<_, _>>()
+            .map(Plan::Batch),
+    }
+}
+
+/// Starts `browser` with its private-window `flag` and `url`.
+fn private_window_plan(browser: &str, flag: &str, url: &str) -> Plan {
+    #[cfg(windows)]
+    let url = format!("\"{}\"", url.replace('"', "%22"));
+    #[cfg(unix)]
+    let url = sh_quote(url);
+    Plan::ShellExecute(vec![ShellLaunch::new(
+        browser,
+        Some(&format!("{flag} {url}")),
+        None,
+        false,
+    )])
+}
+
+/// Opens Explorer on the folder holding `path`, with `path` selected.
+#[cfg(windows)]
+fn reveal_in_explorer(path: &str) -> Plan {
+    Plan::ShellExecute(vec![ShellLaunch::new(
+        "explorer.exe",
+        Some(&format!("/select,\"{path}\"")),
+        None,
+        false,
+    )])
+}
+
+/// Selects `path` in Finder.
+#[cfg(target_os = "macos")]
+fn reveal_in_explorer(path: &str) -> Plan {
+    Plan::ShellExecute(vec![ShellLaunch::new(
+        "open",
+        Some(&format!("-R {}", sh_quote(path))),
+        None,
+        false,
+    )])
+}
+
+/// File managers have no common way to select a file, so its folder is opened.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn reveal_in_explorer(path: &str) -> Plan {
+    let folder = Path::new(path)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map_or_else(
+            || path.to_string(),
+            |parent| parent.to_string_lossy().into_owned(),
+        );
+    Plan::Open(folder)
+}
+
+/// Opens Windows Terminal in `folder`, or PowerShell where it is not installed.
+#[cfg(windows)]
+fn terminal_plan(folder: &str) -> Result<Plan, String> {
+    Ok(Plan::ShellExecute(vec![
+        ShellLaunch::new(
+            "wt.exe",
+            Some(&format!("-d {}", quote_argument(folder))),
+            None,
+            false,
+        ),
+        ShellLaunch::new("powershell.exe", None, Some(folder), false),
+    ]))
+}
+
+/// Opens Terminal in `folder`.
+#[cfg(target_os = "macos")]
+fn terminal_plan(folder: &str) -> Result<Plan, String> {
+    Ok(Plan::ShellExecute(vec![ShellLaunch::new(
+        "open",
+        Some(&format!("-a Terminal {}", sh_quote(folder))),
+        None,
+        false,
+    )]))
+}
+
+/// Starts the first terminal emulator on `$PATH` in `folder`.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn terminal_plan(folder: &str) -> Result<Plan, String> {
+    let terminal = TERMINALS
+        .iter()
+        .find_map(|name| find_on_path(name))
+        .ok_or("找不到终端程序")?;
+    Ok(Plan::ShellExecute(vec![ShellLaunch::new(
+        &terminal.to_string_lossy(),
+        None,
+        Some(folder),
+        false,
+    )]))
+}
+
+/// Makes the first attempt and then up to `policy.retries` more while the failure
+/// stays transient, waiting longer before each.
+fn run_with_retries(
+    policy: RetryPolicy,
+    mut attempt: impl FnMut() -> Result<(), LaunchFailure>,
+    mut wait: impl FnMut(Duration),
+    mut on_retry: impl FnMut(&str, u32, Duration),
+) -> Result<(), String> {
+    let mut retry = 0;
+    loop {
+        match attempt() {
+            Ok(()) => return Ok(()),
+            Err(failure) if failure.transient && retry < policy.retries => {
+                retry += 1;
+                let delay = policy.delay_before(retry);
+                info!(
+                    "launch failed ({}), retry {retry} in {delay:?}",
+                    failure.message
+                );
+                on_retry(&failure.message, retry, delay);
+                wait(delay);
+            }
+            Err(failure) if retry > 0 => {
+                return Err(format!("{} (已重试 {retry} 次)", failure.message))
+            }
+            Err(failure) => return Err(failure.message),
+        }
+    }
+}
+
+fn run_plan(plan: &Plan) -> Result<(), LaunchFailure> {
+    match plan {
+        Plan::ShellExecute(launches) => {
+            let mut first_error = None;
+            for launch in launches {
+                match start(launch) {
+                    Ok(()) => return Ok(()),
+                    Err(failure) => {
+                        first_error.get_or_insert(failure);
+                    }
+                }
+            }
+            Err(first_error.unwrap_or_else(|| String::from("目标程序无效").into()))
+        }
+        Plan::Open(target) => open_url(target).map_err(LaunchFailure::from),
+        Plan::System(command) => run_system_command(*command).map_err(LaunchFailure::from),
+        Plan::Copy(text) => copy_to_clipboard(text).map_err(LaunchFailure::from),
+        Plan::AppendToFile { path, entry } => {
+            notes::append_to_file(Path::new(path), entry).map_err(LaunchFailure::from)
+        }
+        Plan::CompleteTask { path, line } => {
+            todo::complete_task(Path::new(path), line, Local::now().date_naive())
+                .map_err(LaunchFailure::from)
+        }
+        Plan::FocusWindow(id) => focus_window(*id).map_err(LaunchFailure::from),
+        Plan::KillProcess { pid, name } => kill_process(*pid, name).map_err(LaunchFailure::from),
+        Plan::Plugin(call) => plugins::call(call).map_err(LaunchFailure::from),
+        Plan::InProcess => Ok(()),
+        Plan::Batch(plans) => {
+            let failures: Vec<String> = plans
+                .iter()
+                .filter_map(|plan| run_plan(plan).err())
+                .map(|failure| failure.message)
+                .collect();
+            if failures.is_empty() {
+                Ok(())
+            } else {
+                // Retrying would start the members that worked a second time.
+                Err(format!("{} 项启动失败: {}", failures.len(), failures.join("; ")).into())
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+fn start(launch: &ShellLaunch) -> Result<(), LaunchFailure> {
+    shell_execute_raw(launch).map_err(|code| LaunchFailure {
+        message: format!("无法启动程序 (ShellExecute 错误码 {code})"),
+        transient: is_transient_shell_error(launch, code),
+    })
+}
+
+/// Documents, folders and app bundles go to their default handler. Programs run
+/// through `sh`, which splits their arguments the way a terminal would.
+#[cfg(unix)]
+fn start(launch: &ShellLaunch) -> Result<(), LaunchFailure> {
+    if launch.verb == "runas" {
+        return Err(String::from("以管理员身份运行仅支持 Windows").into());
+    }
+    if launch.arguments.is_none() && !is_program(&launch.target) {
+        return open::that(&launch.target)
+            .map_err(|err| format!("无法打开 {}: {err}", launch.target).into());
+    }
+    let script = format!(
+        "exec \"$0\" {}",
+        launch.arguments.as_deref().unwrap_or_default()
+    );
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(script)
+        .arg(&launch.target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(dir) = &launch.working_directory {
+        command.current_dir(dir);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("无法启动程序: {err}"))?;
+    // Reaped in the background so finished programs do not linger as zombies.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Bare command names (as in `.desktop` files) and files with an execute bit.
+#[cfg(unix)]
+fn is_program(target: &str) -> bool {
+    !target.contains('/')
+        || Path::new(target)
+            .metadata()
+            .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(unix)]
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// `value` as a single program argument. A trailing backslash is doubled so it
+/// does not escape the closing quote.
+#[cfg(windows)]
+pub(crate) fn quote_argument(value: &str) -> String {
+    let escape = if value.ends_with('\\') { "\\" } else { "" };
+    format!("\"{value}{escape}\"")
+}
+
+#[cfg(unix)]
+pub(crate) fn quote_argument(value: &str) -> String {
+    sh_quote(value)
+}
+
+/// Whether a ShellExecute error code may clear up on its own. Access denied is
+/// also what a cancelled UAC prompt reports, so it only counts for plain launches;
+/// missing files only count on network shares, which report them while waking up.
+#[cfg(windows)]
+fn is_transient_shell_error(launch: &ShellLaunch, code: isize) -> bool {
+    match code {
+        0 | SE_ERR_OOM | SE_ERR_SHARE | SE_ERR_DDETIMEOUT | SE_ERR_DDEBUSY => true,
+        SE_ERR_ACCESSDENIED => launch.verb != "runas",
+        SE_ERR_FNF | SE_ERR_PNF => launch.target.starts_with(r"\\"),
+        _ => false,
+    }
+}
+
+fn describe_plan(plan: &Plan) -> Vec<String> {
+    match plan {
+        Plan::ShellExecute(launches) => launches
+            .iter()
+            .enumerate()
+            .map(|(index, launch)| {
+                let step = if index == 0 { "launch" } else { "fallback" };
+                format!(
+                    "{}. {step}: ShellExecute verb={} target={:?} args={} cwd={}",
+                    index + 1,
+                    launch.verb,
+                    launch.target,
+                    launch
+                        .arguments
+                        .as_deref()
+                        .map_or("(none)".to_string(), |value| format!("{value:?}")),
+                    launch
+                        .working_directory
+                        .as_deref()
+                        .map_or("(none)".to_string(), |value| format!("{value:?}")),
+                )
+            })
+            .collect(),
+        Plan::Open(target) => vec![format!("open {target:?} with its default handler")],
+        Plan::System(command) => vec![format!("system command {}", command.id())],
+        Plan::Copy(text) => vec![format!("copy {text:?} to the clipboard")],
+        Plan::AppendToFile { path, entry } => vec![format!("append {entry:?} to {path:?}")],
+        Plan::CompleteTask { path, line } => vec![format!("mark {line:?} done in {path:?}")],
+        Plan::FocusWindow(id) => vec![format!("bring window {id:#x} to the front")],
+        Plan::KillProcess { pid, name } => vec![format!("end process {pid} ({name})")],
+        Plan::Plugin(call) => vec![match &call.request {
+            Some(request) => format!(
+                "plugin {}: {:?} with parameters {}",
+                call.plugin.name,
+                request.method,
+                serde_json::Value::from(request.parameters.clone())
+            ),
+            None => format!("plugin {}: result has no action", call.plugin.name),
+        }],
+        Plan::InProcess => vec!["handled inside egg, nothing to launch".to_string()],
+        Plan::Batch(plans) => plans
+            .iter()
+            .enumerate()
+            .flat_map(|(index, plan)| {
+                describe_plan(plan)
+                    .into_iter()
+                    .map(move |line| format!("member {}: {line}", index + 1))
+            })
+            .collect(),
     }
 }
 
@@ -31,7 +697,79 @@ fn open_url(target: &str) -> Result<(), String> {
     open::that(target).map_err(|err| err.to_string())
 }
 
-fn launch_application(app: &ApplicationInfo, run_as_admin: bool) -> Result<(), String> {
+fn plan_command(
+    command: &str,
+    shell: CommandShell,
+    keep_open: bool,
+    run_as_admin: bool,
+) -> Result<Plan, String> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err("命令为空".into());
+    }
+    let (program, arguments) = shell_invocation(command, shell, keep_open);
+    let home = dirs::home_dir().map(|dir| dir.to_string_lossy().into_owned());
+    Ok(Plan::ShellExecute(vec![ShellLaunch::new(
+        program,
+        Some(&arguments),
+        home.as_deref(),
+        run_as_admin,
+    )]))
+}
+
+/// Builds the shell executable and argument string for a `>` command.
+#[cfg(windows)]
+fn shell_invocation(command: &str, shell: CommandShell, keep_open: bool) -> (&'static str, String) {
+    match shell {
+        CommandShell::Cmd => {
+            let switch = if keep_open { "/K" } else { "/C" };
+            ("cmd.exe", format!("{switch} {command}"))
+        }
+        // WSL hands everything after `--` to the distribution's shell; keeping
+        // the window open starts an interactive shell once the command is done.
+        CommandShell::Wsl => {
+            let then = if keep_open {
+                "; exec \"${SHELL:-sh}\""
+            } else {
+                ""
+            };
+            ("wsl.exe", format!("--cd ~ -- {command}{then}"))
+        }
+        CommandShell::PowerShell | CommandShell::Pwsh => {
+            let program = if shell == CommandShell::Pwsh {
+                "pwsh.exe"
+            } else {
+                "powershell.exe"
+            };
+            // -EncodedCommand sidesteps command-line quoting: base64 of the UTF-16LE script.
+            let utf16: Vec<u8> = command
+                .encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect();
+            let no_exit = if keep_open { " -NoExit" } else { "" };
+            (
+                program,
+                format!(
+                    "-NoLogo{no_exit} -EncodedCommand {}",
+                    STANDARD.encode(utf16)
+                ),
+            )
+        }
+    }
+}
+
+/// `>` commands run through `sh -c`; the shell setting and keeping a window open
+/// only apply on Windows.
+#[cfg(unix)]
+fn shell_invocation(
+    command: &str,
+    _shell: CommandShell,
+    _keep_open: bool,
+) -> (&'static str, String) {
+    ("sh", format!("-c {}", sh_quote(command)))
+}
+
+fn plan_application(app: &ApplicationInfo, run_as_admin: bool) -> Result<Plan, String> {
     let target = app.path.trim();
     if target.is_empty() {
         return Err("目标程序无效".into());
@@ -41,16 +779,26 @@ fn launch_application(app: &ApplicationInfo, run_as_admin: bool) -> Result<(), S
     let working_directory = app.working_directory.as_deref();
     let allow_runas = run_as_admin && should_use_runas(target);
 
-    match shell_execute_raw(target, arguments, working_directory, allow_runas) {
-        Ok(_) => Ok(()),
-        Err(err) => {
-            if let Some(source) = app.source_path.as_deref() {
-                shell_execute_raw(source, arguments, working_directory, allow_runas).or(Err(err))
-            } else {
-                Err(err)
-            }
-        }
+    let mut launches = vec![ShellLaunch::new(
+        target,
+        arguments,
+        working_directory,
+        allow_runas,
+    )];
+    if let Some(source) = app.source_path.as_deref() {
+        // A web app's shortcut already holds the proxy's arguments.
+        let source_arguments = match app.app_type {
+            AppType::Pwa => None,
+            _ => arguments,
+        };
+        launches.push(ShellLaunch::new(
+            source,
+            source_arguments,
+            working_directory,
+            allow_runas,
+        ));
     }
+    Ok(Plan::ShellExecute(launches))
 }
 
 fn should_use_runas(target: &str) -> bool {
@@ -61,42 +809,28 @@ fn should_use_runas(target: &str) -> bool {
     !(lower.starts_with("shell:") || lower.contains("://"))
 }
 
-fn shell_execute_raw(
-    target: &str,
-    arguments: Option<&str>,
-    working_directory: Option<&str>,
-    run_as_admin: bool,
-) -> Result<(), String> {
-    let target_os = OsString::from(target);
-    let argument_os = arguments
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .map(OsString::from);
-    let working_dir_os = working_directory
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .map(OsString::from);
-
-    let verb = if run_as_admin {
-        OsStr::new("runas")
-    } else {
-        OsStr::new("open")
-    };
+/// Returns the ShellExecute error code on failure.
+#[cfg(windows)]
+fn shell_execute_raw(launch: &ShellLaunch) -> Result<(), isize> {
+    let target_os = OsString::from(&launch.target);
+    let argument_os = launch.arguments.as_ref().map(OsString::from);
+    let working_dir_os = launch.working_directory.as_ref().map(OsString::from);
 
     shell_execute_internal(
         target_os.as_os_str(),
         argument_os.as_deref(),
         working_dir_os.as_deref(),
-        verb,
+        OsStr::new(launch.verb),
     )
 }
 
+#[cfg(windows)]
 fn shell_execute_internal(
     target: &OsStr,
     arguments: Option<&OsStr>,
     working_directory: Option<&OsStr>,
     verb: &OsStr,
-) -> Result<(), String> {
+) -> Result<(), isize> {
     let file_buffer = os_str_to_wide(target);
     let arg_buffer = arguments.map(os_str_to_wide);
     let dir_buffer = working_directory.map(os_str_to_wide);
@@ -123,12 +857,315 @@ fn shell_execute_internal(
         )
     };
 
-    if result.0 as isize <= 32 {
-        Err(format!(
-            "无法启动程序 (ShellExecute 错误码 {})",
-            result.0 as isize
-        ))
+    let code = result.0 as isize;
+    if code <= 32 {
+        Err(code)
     } else {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::app;
+
+    #[test]
+    fn launch_overrides_replace_arguments_and_start_folder() {
+        let mut game = app("game", "Game", r"D:\Games\game.exe");
+        game.arguments = Some("-windowed".to_string());
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "game".to_string(),
+            LaunchOverride {
+                arguments: Some("-novid -high".to_string()),
+                working_directory: Some(r"D:\Games".to_string()),
+            },
+        );
+
+        let mut action = PendingAction::Application(game);
+        apply_launch_override(&mut action, &overrides);
+        let plan = plan_variant(&action, ActionVariant::Open).unwrap();
+        assert_eq!(
+            describe_plan(&plan),
+            vec![
+                r#"1. launch: ShellExecute verb=open target="D:\\Games\\game.exe" args="-novid -high" cwd="D:\\Games""#,
+            ]
+        );
+
+        let mut other = PendingAction::Application(app("other", "Other", r"C:\other.exe"));
+        apply_launch_override(&mut other, &overrides);
+        assert!(matches!(other, PendingAction::Application(app) if app.arguments.is_none()));
+
+        // Cleared fields launch with neither the indexed arguments nor a start folder.
+        let mut game = app("game", "Game", r"D:\Games\game.exe");
+        game.arguments = Some("-windowed".to_string());
+        overrides.insert(
+            "game".to_string(),
+            LaunchOverride {
+                arguments: Some(String::new()),
+                working_directory: Some(String::new()),
+            },
+        );
+        let mut action = PendingAction::Application(game);
+        apply_launch_override(&mut action, &overrides);
+        assert!(
+            matches!(action, PendingAction::Application(app) if app.arguments.is_none() && app.working_directory.is_none())
+        );
+    }
+
+    #[test]
+    fn dry_run_lists_the_fallback_chain() {
+        let mut shortcut = app("code", "Visual Studio Code", r"C:\Code\Code.exe");
+        shortcut.source_path = Some(r"C:\Start\Code.lnk".to_string());
+        shortcut.arguments = Some("  ".to_string());
+        shortcut.working_directory = Some(r"C:\Code".to_string());
+
+        let action = PendingAction::Application(shortcut);
+        let plan = plan_variant(&action, ActionVariant::RunAsAdmin).unwrap();
+        assert_eq!(
+            describe_plan(&plan),
+            vec![
+                r#"1. launch: ShellExecute verb=runas target="C:\\Code\\Code.exe" args=(none) cwd="C:\\Code""#,
+                r#"2. fallback: ShellExecute verb=runas target="C:\\Start\\Code.lnk" args=(none) cwd="C:\\Code""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn web_apps_start_through_their_proxy() {
+        let mut youtube = app("pwa:edge:agimn", "YouTube", r"C:\Edge\msedge_proxy.exe");
+        youtube.app_type = AppType::Pwa;
+        youtube.source_path = Some(r"C:\Start\YouTube.lnk".to_string());
+        youtube.arguments = Some("--profile-directory=Default --app-id=agimn".to_string());
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            youtube.id.clone(),
+            LaunchOverride {
+                arguments: None,
+                working_directory: None,
+            },
+        );
+
+        let mut action = PendingAction::Application(youtube);
+        apply_launch_override(&mut action, &overrides);
+        assert!(!action_variants(&action).contains(&ActionVariant::EditLaunchOptions));
+        let plan = plan_variant(&action, ActionVariant::Open).unwrap();
+        assert_eq!(
+            describe_plan(&plan),
+            vec![
+                r#"1. launch: ShellExecute verb=open target="C:\\Edge\\msedge_proxy.exe" args="--profile-directory=Default --app-id=agimn" cwd=(none)"#,
+                r#"2. fallback: ShellExecute verb=open target="C:\\Start\\YouTube.lnk" args=(none) cwd=(none)"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn variants_resolve_to_paths_and_urls() {
+        let file = PendingAction::File(r"D:\docs\report.docx".to_string());
+        #[cfg(windows)]
+        assert_eq!(
+            describe_plan(&plan_variant(&file, ActionVariant::OpenContainingFolder).unwrap()),
+            vec![
+                r#"1. launch: ShellExecute verb=open target="explorer.exe" args="/select,\"D:\\docs\\report.docx\"" cwd=(none)"#
+            ]
+        );
+        let reveal = PendingAction::Path {
+            path: r"D:\docs\report.docx".to_string(),
+            reveal: true,
+        };
+        assert_eq!(
+            plan_variant(&reveal, ActionVariant::Open).unwrap(),
+            plan_variant(&file, ActionVariant::OpenContainingFolder).unwrap()
+        );
+        let url = PendingAction::Url("https://example.com".to_string());
+        assert_eq!(
+            plan_variant(&url, ActionVariant::CopyUrl).unwrap(),
+            Plan::Copy("https://example.com".to_string())
+        );
+        assert!(plan_variant(&url, ActionVariant::RunAsAdmin).is_err());
+        #[cfg(windows)]
+        assert_eq!(
+            describe_plan(&private_window_plan(
+                "msedge.exe",
+                "--inprivate",
+                "https://example.com/?q=\"a\"",
+            )),
+            vec![
+                r#"1. launch: ShellExecute verb=open target="msedge.exe" args="--inprivate \"https://example.com/?q=%22a%22\"" cwd=(none)"#
+            ]
+        );
+
+        let uwp = PendingAction::Application(app("calc", "Calculator", "shell:AppsFolder\\Calc"));
+        assert_eq!(
+            action_variants(&uwp),
+            vec![
+                ActionVariant::Open,
+                ActionVariant::CopyPath,
+                ActionVariant::EditAlias
+            ]
+        );
+    }
+
+    #[test]
+    fn repositories_open_in_the_editor_and_offer_their_folder() {
+        let folder = std::env::temp_dir().join(format!("egg-repo-test-{}", std::process::id()));
+        let folder_path = folder.to_string_lossy().into_owned();
+        let mut repo = app(
+            &format!("repo:{}", folder_path.to_lowercase()),
+            "egg",
+            r"C:\Code\Code.exe",
+        );
+        repo.working_directory = Some(folder_path.clone());
+        let action = PendingAction::Application(repo);
+        assert!(action_variants(&action).contains(&ActionVariant::OpenInTerminal));
+        assert_eq!(
+            plan_variant(&action, ActionVariant::CopyPath).unwrap(),
+            Plan::Copy(folder_path.clone())
+        );
+        #[cfg(windows)]
+        assert!(matches!(
+            plan_variant(&action, ActionVariant::OpenInTerminal).unwrap(),
+            Plan::ShellExecute(launches) if launches[1].working_directory.as_deref() == Some(folder_path.as_str())
+        ));
+
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(folder.join(".git")).unwrap();
+        assert!(plan_variant(&action, ActionVariant::OpenRemote).is_err());
+        fs::write(
+            folder.join(".git").join("config"),
+            "[remote \"origin\"]\n\turl = git@github.com:me/egg.git\n",
+        )
+        .unwrap();
+        assert_eq!(
+            plan_variant(&action, ActionVariant::OpenRemote).unwrap(),
+            Plan::Open("https://github.com/me/egg".to_string())
+        );
+        let _ = fs::remove_dir_all(&folder);
+
+        let plain = PendingAction::Application(app("code", "Code", r"C:\Code\Code.exe"));
+        assert!(plan_variant(&plain, ActionVariant::OpenRemote).is_err());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn shell_invocation_respects_shell_and_keep_open() {
+        assert_eq!(
+            shell_invocation("dir /b", CommandShell::Cmd, true),
+            ("cmd.exe", "/K dir /b".to_string())
+        );
+        assert_eq!(
+            shell_invocation("dir /b", CommandShell::Cmd, false),
+            ("cmd.exe", "/C dir /b".to_string())
+        );
+        // "ls" as UTF-16LE is 6c 00 73 00.
+        assert_eq!(
+            shell_invocation("ls", CommandShell::PowerShell, true),
+            (
+                "powershell.exe",
+                "-NoLogo -NoExit -EncodedCommand bABzAA==".to_string()
+            )
+        );
+        assert_eq!(
+            shell_invocation("ls", CommandShell::Pwsh, false).1,
+            "-NoLogo -EncodedCommand bABzAA=="
+        );
+        assert_eq!(
+            shell_invocation("uname -a", CommandShell::Wsl, true),
+            (
+                "wsl.exe",
+                "--cd ~ -- uname -a; exec \"${SHELL:-sh}\"".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn transient_failures_are_retried_with_backoff() {
+        let policy = RetryPolicy {
+            retries: 2,
+            delay: Duration::from_millis(500),
+        };
+        let busy = || LaunchFailure {
+            message: "busy".to_string(),
+            transient: true,
+        };
+
+        let mut attempts = 0;
+        let mut waits = Vec::new();
+        let mut notices = Vec::new();
+        let result = run_with_retries(
+            policy,
+            || {
+                attempts += 1;
+                if attempts < 3 {
+                    Err(busy())
+                } else {
+                    Ok(())
+                }
+            },
+            |delay| waits.push(delay),
+            |message, retry, _| notices.push(format!("{message} {retry}")),
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            waits,
+            vec![Duration::from_millis(500), Duration::from_millis(1000)]
+        );
+        assert_eq!(notices, vec!["busy 1", "busy 2"]);
+
+        let mut attempts = 0;
+        let result = run_with_retries(
+            policy,
+            || {
+                attempts += 1;
+                Err(busy())
+            },
+            |_| {},
+            |_, _, _| {},
+        );
+        assert_eq!(attempts, 3);
+        assert_eq!(result, Err("busy (已重试 2 次)".to_string()));
+
+        let mut attempts = 0;
+        let result = run_with_retries(
+            policy,
+            || {
+                attempts += 1;
+                Err(LaunchFailure::from("missing".to_string()))
+            },
+            |_| {},
+            |_, _, _| {},
+        );
+        assert_eq!(attempts, 1);
+        assert_eq!(result, Err("missing".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_commands_run_through_sh() {
+        assert_eq!(
+            shell_invocation("echo 'hi'", CommandShell::Cmd, true),
+            ("sh", r"-c 'echo '\''hi'\'''".to_string())
+        );
+        assert!(is_program("htop"));
+        assert!(is_program("/bin/sh"));
+        assert!(!is_program("/home/me/report.pdf"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn only_recoverable_shell_errors_count_as_transient() {
+        let local = ShellLaunch::new(r"C:\Tools\app.exe", None, None, false);
+        let share = ShellLaunch::new(r"\\nas\tools\app.exe", None, None, false);
+        let elevated = ShellLaunch::new(r"C:\Tools\app.exe", None, None, true);
+
+        assert!(is_transient_shell_error(&local, SE_ERR_SHARE));
+        assert!(is_transient_shell_error(&local, SE_ERR_ACCESSDENIED));
+        assert!(!is_transient_shell_error(&elevated, SE_ERR_ACCESSDENIED));
+        assert!(!is_transient_shell_error(&local, SE_ERR_FNF));
+        assert!(is_transient_shell_error(&share, SE_ERR_FNF));
+        assert!(!is_transient_shell_error(&local, 31));
+    }
+}
@@ -0,0 +1,451 @@
+//! Reads what the preview pane shows for a file: the first lines of text files,
+//! the size of images (read from their headers), or a folder's entries. Reads are
+//! capped so a huge file costs no more than a small one.
+
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, MAIN_SEPARATOR},
+};
+
+/// Bytes read from the start of a file.
+const MAX_READ_BYTES: usize = 64 * 1024;
+const MAX_LINES: usize = 200;
+const MAX_FOLDER_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilePreview {
+    Text {
+        lines: Vec<String>,
+        /// The file goes on past the lines read.
+        truncated: bool,
+        language: Option<Language>,
+    },
+    Image {
+        format: &'static str,
+        width: u32,
+        height: u32,
+        size: u64,
+    },
+    Folder {
+        /// Names, folders first with a trailing path separator (`\` on Windows).
+        entries: Vec<String>,
+        total: usize,
+    },
+    Binary {
+        size: u64,
+    },
+    Unavailable(String),
+}
+
+pub fn load_preview(path: &Path) -> FilePreview {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) => return FilePreview::Unavailable(err.to_string()),
+    };
+    if metadata.is_dir() {
+        return folder_preview(path);
+    }
+    let mut head = Vec::new();
+    let read = File::open(path).and_then(|file| {
+        file.take(MAX_READ_BYTES as u64 + 1)
+            .read_to_end(&mut head)
+            .map(|_| ())
+    });
+    if let Err(err) = read {
+        return FilePreview::Unavailable(err.to_string());
+    }
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    preview_bytes(&head, extension, metadata.len())
+}
+
+/// Builds the preview from the first bytes of a file of `size` bytes.
+pub fn preview_bytes(head: &[u8], extension: &str, size: u64) -> FilePreview {
+    if let Some((format, width, height)) = image_dimensions(head) {
+        return FilePreview::Image {
+            format,
+            width,
+            height,
+            size,
+        };
+    }
+    let Some(text) = decode_text(&head[..head.len().min(MAX_READ_BYTES)]) else {
+        return FilePreview::Binary { size };
+    };
+    let mut lines: Vec<String> = text
+        .lines()
+        .take(MAX_LINES + 1)
+        .map(|line| line.replace('\t', "    "))
+        .collect();
+    let truncated = lines.len() > MAX_LINES || head.len() > MAX_READ_BYTES;
+    lines.truncate(MAX_LINES);
+    FilePreview::Text {
+        lines,
+        truncated,
+        language: Language::for_extension(extension),
+    }
+}
+
+fn folder_preview(path: &Path) -> FilePreview {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(err) => return FilePreview::Unavailable(err.to_string()),
+    };
+    let mut names: Vec<(bool, String)> = entries
+        .flatten()
+        .map(|entry| {
+            let is_dir = entry.file_type().is_ok_and(|kind| kind.is_dir());
+            (!is_dir, entry.file_name().to_string_lossy().into_owned())
+        })
+        .collect();
+    names.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.to_lowercase().cmp(&b.1.to_lowercase()))
+    });
+    let total = names.len();
+    let entries = names
+        .into_iter()
+        .take(MAX_FOLDER_ENTRIES)
+        .map(|(is_file, name)| {
+            if is_file {
+                name
+            } else {
+                format!("{name}{MAIN_SEPARATOR}")
+            }
+        })
+        .collect();
+    FilePreview::Folder { entries, total }
+}
+
+/// UTF-8 (with or without BOM) or UTF-16 with a BOM; `None` for binary data.
+fn decode_text(bytes: &[u8]) -> Option<String> {
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return Some(decode_utf16(rest, u16::from_le_bytes));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return Some(decode_utf16(rest, u16::from_be_bytes));
+    }
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    if bytes.contains(&0) {
+        return None;
+    }
+    // A read cut mid-character leaves a partial sequence at the end; lossy decoding
+    // turns it into one replacement character instead of rejecting the file.
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| unit([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Format and pixel size from an image header.
+fn image_dimensions(bytes: &[u8]) -> Option<(&'static str, u32, u32)> {
+    let be32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+    let le16 = |at: usize| {
+        Some(u32::from(u16::from_le_bytes(
+            bytes.get(at..at + 2)?.try_into().ok()?,
+        )))
+    };
+    let le32 = |at: usize| Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(("PNG", be32(16)?, be32(20)?));
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some(("GIF", le16(6)?, le16(8)?));
+    }
+    // A known info header size keeps text starting with "BM" from passing as a bitmap.
+    if bytes.starts_with(b"BM") && matches!(le32(14), Some(40 | 52 | 56 | 108 | 124)) {
+        // Height is negative for top-down bitmaps.
+        return Some(("BMP", le32(18)?, (le32(22)? as i32).unsigned_abs()));
+    }
+    if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        let (width, height) = match bytes.get(12..16)? {
+            b"VP8X" => ((le32(24)? & 0xFF_FFFF) + 1, (le32(27)? & 0xFF_FFFF) + 1),
+            b"VP8L" => {
+                let bits = le32(21)?;
+                ((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1)
+            }
+            b"VP8 " => (le16(26)? & 0x3FFF, le16(28)? & 0x3FFF),
+            _ => return None,
+        };
+        return Some(("WebP", width, height));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        return jpeg_dimensions(bytes).map(|(width, height)| ("JPEG", width, height));
+    }
+    None
+}
+
+/// Walks JPEG segments up to the first start-of-frame marker.
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut at = 2;
+    loop {
+        if *bytes.get(at)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(at + 1)?;
+        let length = usize::from(u16::from_be_bytes([
+            *bytes.get(at + 2)?,
+            *bytes.get(at + 3)?,
+        ]));
+        let is_frame = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_frame {
+            let height = u16::from_be_bytes([*bytes.get(at + 5)?, *bytes.get(at + 6)?]);
+            let width = u16::from_be_bytes([*bytes.get(at + 7)?, *bytes.get(at + 8)?]);
+            return Some((u32::from(width), u32::from(height)));
+        }
+        at += 2 + length;
+    }
+}
+
+/// How a preview line is split for coloring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    String,
+    Number,
+    Comment,
+}
+
+/// Just enough of a language to color keywords, strings and line comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    line_comment: &'static str,
+    keywords: &'static [&'static str],
+}
+
+const C_LIKE_KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "class",
+    "const",
+    "continue",
+    "else",
+    "enum",
+    "false",
+    "for",
+    "if",
+    "import",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "private",
+    "public",
+    "return",
+    "static",
+    "struct",
+    "switch",
+    "this",
+    "true",
+    "var",
+    "void",
+    "while",
+    "function",
+    "async",
+    "await",
+    "export",
+    "from",
+    "type",
+    "package",
+    "func",
+];
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "else", "enum", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while",
+];
+const PYTHON_KEYWORDS: &[&str] = &[
+    "and", "as", "async", "await", "class", "def", "elif", "else", "except", "False", "finally",
+    "for", "from", "if", "import", "in", "is", "lambda", "None", "not", "or", "pass", "raise",
+    "return", "True", "try", "while", "with", "yield",
+];
+const SHELL_KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "for", "in", "do", "done", "while", "case", "esac",
+    "function", "return", "param", "foreach", "true", "false",
+];
+
+impl Language {
+    pub fn for_extension(extension: &str) -> Option<Self> {
+        let (line_comment, keywords): (&str, &[&str]) =
+            match extension.to_ascii_lowercase().as_str() {
+                "rs" => ("//", RUST_KEYWORDS),
+                "c" | "h" | "cpp" | "hpp" | "cc" | "cs" | "java" | "js" | "jsx" | "ts" | "tsx"
+                | "go" | "kt" | "swift" => ("//", C_LIKE_KEYWORDS),
+                "py" | "pyw" => ("#", PYTHON_KEYWORDS),
+                "sh" | "bash" | "ps1" | "psm1" => ("#", SHELL_KEYWORDS),
+                "toml" | "yaml" | "yml" | "conf" => ("#", &["true", "false"]),
+                "ini" | "reg" => (";", &[]),
+                "json" => ("", &["true", "false", "null"]),
+                "bat" | "cmd" => ("::", &["echo", "set", "if", "else", "goto", "call", "exit"]),
+                "sql" => (
+                    "--",
+                    &[
+                        "select", "from", "where", "insert", "update", "delete", "join",
+                    ],
+                ),
+                _ => return None,
+            };
+        Some(Self {
+            line_comment,
+            keywords,
+        })
+    }
+
+    /// Splits `line` into colored runs.
+    pub fn highlight<'a>(&self, line: &'a str) -> Vec<(TokenKind, &'a str)> {
+        let mut tokens = Vec::new();
+        let mut rest = line;
+        while !rest.is_empty() {
+            if !self.line_comment.is_empty() && rest.starts_with(self.line_comment) {
+                tokens.push((TokenKind::Comment, rest));
+                break;
+            }
+            let first = rest.chars().next().unwrap_or_default();
+            let len = if first == '"' || first == '\'' {
+                let close = rest[1..].find(first).map_or(rest.len(), |end| end + 2);
+                tokens.push((TokenKind::String, &rest[..close]));
+                close
+            } else if first.is_alphanumeric() || first == '_' {
+                let end = rest
+                    .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                    .unwrap_or(rest.len());
+                let word = &rest[..end];
+                let kind = if first.is_ascii_digit() {
+                    TokenKind::Number
+                } else if self.keywords.contains(&word) {
+                    TokenKind::Keyword
+                } else {
+                    TokenKind::Plain
+                };
+                tokens.push((kind, word));
+                end
+            } else {
+                let end = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(at, ch)| {
+                        ch.is_alphanumeric()
+                            || ch == '_'
+                            || ch == '"'
+                            || ch == '\''
+                            || (!self.line_comment.is_empty()
+                                && rest[at..].starts_with(self.line_comment))
+                    })
+                    .map_or(rest.len(), |(at, _)| at);
+                tokens.push((TokenKind::Plain, &rest[..end]));
+                end
+            };
+            rest = &rest[len..];
+        }
+        tokens
+    }
+}
+
+/// `2.3 MB`-style sizes for the preview header.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_image_sizes_from_headers() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend(1920u32.to_be_bytes());
+        png.extend(1080u32.to_be_bytes());
+        assert_eq!(
+            preview_bytes(&png, "png", 2048),
+            FilePreview::Image {
+                format: "PNG",
+                width: 1920,
+                height: 1080,
+                size: 2048
+            }
+        );
+
+        let gif = b"GIF89a\x40\x01\xf0\x00";
+        assert_eq!(image_dimensions(gif), Some(("GIF", 320, 240)));
+
+        // SOI, an APP0 segment of length 4, then SOF0 with 480x640.
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01,
+            0xE0, 0x02, 0x80,
+        ];
+        assert_eq!(image_dimensions(&jpeg), Some(("JPEG", 640, 480)));
+    }
+
+    #[test]
+    fn text_is_decoded_and_binary_is_not_shown() {
+        let FilePreview::Text {
+            lines,
+            truncated,
+            language,
+        } = preview_bytes(b"\xEF\xBB\xBFfn main() {\n\tlet x = 1;\n}", "rs", 30)
+        else {
+            panic!("expected text");
+        };
+        assert_eq!(lines, vec!["fn main() {", "    let x = 1;", "}"]);
+        assert!(!truncated);
+        assert!(language.is_some());
+
+        let utf16: Vec<u8> = [0xFF, 0xFE, b'h', 0, b'i', 0].to_vec();
+        assert!(matches!(
+            preview_bytes(&utf16, "txt", 6),
+            FilePreview::Text { lines, .. } if lines == vec!["hi"]
+        ));
+        assert_eq!(
+            preview_bytes(b"MZ\x90\0\x03", "exe", 5),
+            FilePreview::Binary { size: 5 }
+        );
+        assert_eq!(format_size(2_400_000), "2.3 MB");
+    }
+
+    #[test]
+    fn highlights_keywords_strings_and_comments() {
+        let rust = Language::for_extension("RS").unwrap();
+        let tokens = rust.highlight(r#"let name = "egg"; // 42"#);
+        let kinds: Vec<TokenKind> = tokens.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Keyword,
+                TokenKind::Plain,
+                TokenKind::Plain,
+                TokenKind::Plain,
+                TokenKind::String,
+                TokenKind::Plain,
+                TokenKind::Comment,
+            ]
+        );
+        assert_eq!(tokens[4].1, "\"egg\"");
+        assert_eq!(tokens[6].1, "// 42");
+        assert_eq!(
+            tokens.iter().map(|(_, text)| *text).collect::<String>(),
+            r#"let name = "egg"; // 42"#
+        );
+        assert!(Language::for_extension("docx").is_none());
+    }
+}
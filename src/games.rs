@@ -0,0 +1,436 @@
+//! Installed Steam and Epic Games Store games, read from the launchers' own
+//! manifests so games show up with their store names even without a Start Menu
+//! shortcut. Games launch through the launcher's protocol URL.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, warn};
+use serde::Deserialize;
+#[cfg(windows)]
+use winreg::{enums::*, RegKey};
+
+use crate::{
+    models::{AppType, ApplicationInfo},
+    text_utils::build_pinyin_index,
+};
+
+/// Ids of games found in launcher manifests.
+pub const GAME_ID_PREFIX: &str = "game:";
+/// Steam's shared redistributables "app", installed alongside most games.
+const STEAM_REDIST_APP_ID: &str = "228980";
+
+/// All installed Steam and Epic games.
+pub fn enumerate_games() -> Vec<ApplicationInfo> {
+    let mut games = Vec::new();
+    if let Some(root) = steam_root() {
+        let steam = steam_games(&root);
+        debug!("indexed {} steam games", steam.len());
+        games.extend(steam);
+    }
+    if let Some(dir) = epic_manifest_dir() {
+        let epic = epic_games(&dir);
+        debug!("indexed {} epic games", epic.len());
+        games.extend(epic);
+    }
+    games
+}
+
+pub fn is_game_entry(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(GAME_ID_PREFIX)
+}
+
+#[cfg(windows)]
+fn steam_root() -> Option<PathBuf> {
+    let registered = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(r"Software\Valve\Steam")
+        .and_then(|key| key.get_value::<String, _>("SteamPath"))
+        .ok()
+        .map(|path| PathBuf::from(path.trim()));
+    let default = env::var_os("ProgramFiles(x86)").map(|dir| PathBuf::from(dir).join("Steam"));
+    [registered, default]
+        .into_iter()
+        .flatten()
+        .find(|root| root.join("steamapps").is_dir())
+}
+
+/// Steam's folder on macOS, or on Linux the `~/.steam/steam` link Steam keeps to
+/// wherever it is installed.
+#[cfg(not(windows))]
+fn steam_root() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    [
+        home.join("Library/Application Support/Steam"),
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+    ]
+    .into_iter()
+    .find(|root| root.join("steamapps").is_dir())
+}
+
+fn steam_games(root: &Path) -> Vec<ApplicationInfo> {
+    let mut libraries = vec![root.to_path_buf()];
+    let folders = root.join("steamapps").join("libraryfolders.vdf");
+    match fs::read_to_string(&folders) {
+        Ok(content) => libraries.extend(steam_library_paths(&content)),
+        Err(err) => warn!("failed to read {}: {err}", folders.display()),
+    }
+    libraries.sort();
+    libraries.dedup_by(|a, b| {
+        a.to_string_lossy()
+            .eq_ignore_ascii_case(&b.to_string_lossy())
+    });
+
+    let mut games = Vec::new();
+    for library in libraries {
+        let Ok(entries) = fs::read_dir(library.join("steamapps")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if !(file_name.starts_with("appmanifest_") && file_name.ends_with(".acf")) {
+                continue;
+            }
+            if let Some(game) = fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|content| steam_game(&content))
+            {
+                games.push(game);
+            }
+        }
+    }
+    games
+}
+
+/// Library folders listed in `libraryfolders.vdf`.
+fn steam_library_paths(content: &str) -> Vec<PathBuf> {
+    let Some(vdf) = parse_vdf(content) else {
+        return Vec::new();
+    };
+    let Some(Vdf::Section(root)) = vdf.get("libraryfolders") else {
+        return Vec::new();
+    };
+    root.iter()
+        .filter_map(|(_, folder)| match folder {
+            // Older files list bare paths; newer ones nest them under "path".
+            Vdf::Value(path) => Some(path.as_str()),
+            Vdf::Section(_) => match folder.get("path") {
+                Some(Vdf::Value(path)) => Some(path.as_str()),
+                _ => None,
+            },
+        })
+        .filter(|path| !path.trim().is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Reads one `appmanifest_*.acf`.
+fn steam_game(content: &str) -> Option<ApplicationInfo> {
+    let vdf = parse_vdf(content)?;
+    let state = vdf.get("AppState")?;
+    let field = |key: &str| match state.get(key) {
+        Some(Vdf::Value(value)) => Some(value.trim()).filter(|value| !value.is_empty()),
+        _ => None,
+    };
+    let app_id = field("appid")?;
+    let name = field("name")?;
+    if app_id == STEAM_REDIST_APP_ID {
+        return None;
+    }
+    Some(game_info(
+        format!("{GAME_ID_PREFIX}steam:{app_id}"),
+        name,
+        format!("steam://rungameid/{app_id}"),
+        "Steam",
+        field("installdir"),
+    ))
+}
+
+fn epic_manifest_dir() -> Option<PathBuf> {
+    let program_data = env::var_os("ProgramData")?;
+    let dir = PathBuf::from(program_data)
+        .join("Epic")
+        .join("EpicGamesLauncher")
+        .join("Data")
+        .join("Manifests");
+    dir.is_dir().then_some(dir)
+}
+
+fn epic_games(dir: &Path) -> Vec<ApplicationInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("item"))
+        })
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            epic_game(&content).or_else(|| {
+                debug!("skipping epic manifest {}", path.display());
+                None
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EpicManifest {
+    display_name: String,
+    app_name: String,
+    catalog_namespace: String,
+    catalog_item_id: String,
+    #[serde(default)]
+    install_location: String,
+    #[serde(default, rename = "bIsIncompleteInstall")]
+    is_incomplete_install: bool,
+    #[serde(default)]
+    app_categories: Vec<String>,
+}
+
+/// Reads one Epic `.item` manifest; DLC and unfinished installs are skipped.
+fn epic_game(content: &str) -> Option<ApplicationInfo> {
+    let manifest: EpicManifest = serde_json::from_str(content).ok()?;
+    let is_addon = manifest
+        .app_categories
+        .iter()
+        .any(|category| category.eq_ignore_ascii_case("addons"));
+    if manifest.is_incomplete_install || is_addon || manifest.display_name.trim().is_empty() {
+        return None;
+    }
+    let url = format!(
+        "com.epicgames.launcher://apps/{}%3A{}%3A{}?action=launch&silent=true",
+        urlencoding::encode(&manifest.catalog_namespace),
+        urlencoding::encode(&manifest.catalog_item_id),
+        urlencoding::encode(&manifest.app_name),
+    );
+    let folder = Path::new(&manifest.install_location)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+    Some(game_info(
+        format!("{GAME_ID_PREFIX}epic:{}", manifest.app_name),
+        manifest.display_name.trim(),
+        url,
+        "Epic Games",
+        folder.as_deref(),
+    ))
+}
+
+fn game_info(
+    id: String,
+    name: &str,
+    url: String,
+    launcher: &str,
+    folder: Option<&str>,
+) -> ApplicationInfo {
+    let mut keywords = vec![name.to_string(), launcher.to_string(), "game".to_string()];
+    keywords.extend(folder.map(str::to_string));
+    keywords.sort();
+    keywords.dedup();
+    let description = format!("{launcher} game");
+    ApplicationInfo {
+        id,
+        name: name.to_string(),
+        path: url,
+        source_path: None,
+        app_type: AppType::Win32,
+        pinyin_index: build_pinyin_index([name]),
+        description: Some(description),
+        keywords,
+        working_directory: None,
+        arguments: None,
+        icon: None,
+        folded: None,
+    }
+}
+
+/// A node of Valve's KeyValues text format, as used by `.vdf` and `.acf` files.
+#[derive(Debug, PartialEq, Eq)]
+enum Vdf {
+    Value(String),
+    Section(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    /// Child `key` of a section; keys compare case-insensitively like Steam does.
+    fn get(&self, key: &str) -> Option<&Vdf> {
+        match self {
+            Self::Section(entries) => entries
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value),
+            Self::Value(_) => None,
+        }
+    }
+}
+
+/// Parses KeyValues text into a root section; `None` on unbalanced braces.
+fn parse_vdf(content: &str) -> Option<Vdf> {
+    let mut tokens = vdf_tokens(content).into_iter();
+    let root = parse_section(&mut tokens)?;
+    tokens.next().is_none().then_some(Vdf::Section(root))
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Open,
+    Close,
+}
+
+fn parse_section(tokens: &mut impl Iterator<Item = Token>) -> Option<Vec<(String, Vdf)>> {
+    let mut entries = Vec::new();
+    loop {
+        let key = match tokens.next() {
+            Some(Token::Text(key)) => key,
+            Some(Token::Close) | None => return Some(entries),
+            Some(Token::Open) => return None,
+        };
+        let value = match tokens.next()? {
+            Token::Text(value) => Vdf::Value(value),
+            Token::Open => Vdf::Section(parse_section(tokens)?),
+            Token::Close => return None,
+        };
+        entries.push((key, value));
+    }
+}
+
+fn vdf_tokens(content: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '"' => {
+                let mut text = String::new();
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(escaped) => text.push(escaped),
+                            None => break,
+                        },
+                        _ => text.push(ch),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        break;
+                    }
+                }
+            }
+            ch if ch.is_whitespace() => {}
+            _ => {
+                let mut text = ch.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '{' | '}' | '"') {
+                        break;
+                    }
+                    text.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Text(text));
+            }
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_steam_libraries_and_app_manifests() {
+        let folders = r#"
+"libraryfolders"
+{
+    "0"
+    {
+        "path"      "C:\\Program Files (x86)\\Steam"
+        "apps"
+        {
+            "228980"    "171"
+        }
+    }
+    "1"
+    {
+        "path"      "D:\\SteamLibrary"
+    }
+}
+"#;
+        assert_eq!(
+            steam_library_paths(folders),
+            vec![
+                PathBuf::from(r"C:\Program Files (x86)\Steam"),
+                PathBuf::from(r"D:\SteamLibrary"),
+            ]
+        );
+
+        let manifest = r#"
+"AppState"
+{
+    "appid"     "620"
+    "Universe"  "1"
+    "name"      "Portal 2"
+    "StateFlags"    "4"
+    "installdir"    "Portal 2"
+    "UserConfig"
+    {
+        "language"  "english"
+    }
+}
+"#;
+        let game = steam_game(manifest).unwrap();
+        assert_eq!(game.id, "game:steam:620");
+        assert_eq!(game.name, "Portal 2");
+        assert_eq!(game.path, "steam://rungameid/620");
+        assert!(game.keywords.contains(&"Steam".to_string()));
+
+        let redist = manifest.replace("\"620\"", "\"228980\"");
+        assert!(steam_game(&redist).is_none());
+        assert!(steam_game("\"AppState\" { \"appid\" \"620\"").is_none());
+    }
+
+    #[test]
+    fn reads_epic_manifests_and_skips_dlc() {
+        let manifest = r#"{
+            "FormatVersion": 0,
+            "bIsIncompleteInstall": false,
+            "DisplayName": "Hades",
+            "InstallLocation": "C:\\Program Files\\Epic Games\\Hades",
+            "AppName": "Min",
+            "CatalogNamespace": "min",
+            "CatalogItemId": "fb39e3a7c7f14e6b8b2a9a3e0d1a6e5c",
+            "AppCategories": ["public", "games", "applications"]
+        }"#;
+        let game = epic_game(manifest).unwrap();
+        assert_eq!(game.id, "game:epic:Min");
+        assert_eq!(
+            game.path,
+            "com.epicgames.launcher://apps/min%3Afb39e3a7c7f14e6b8b2a9a3e0d1a6e5c%3AMin?action=launch&silent=true"
+        );
+        assert!(game.keywords.contains(&"Hades".to_string()));
+
+        let dlc = manifest.replace(r#""games","#, r#""addons","#);
+        assert!(epic_game(&dlc).is_none());
+        let partial = manifest.replace(
+            r#""bIsIncompleteInstall": false"#,
+            r#""bIsIncompleteInstall": true"#,
+        );
+        assert!(epic_game(&partial).is_none());
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+
+const HISTORY_FILE: &str = "launch_history.json";
+const MAX_ENTRIES: usize = 500;
+/// Launch counts beyond this stop increasing the frecency score.
+const MAX_COUNTED_LAUNCHES: u32 = 20;
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LaunchRecord {
+    pub count: u32,
+    /// Unix timestamp (seconds) of the most recent launch.
+    pub last_launched: u64,
+}
+
+/// Per-item launch counts and timestamps, keyed by search result id.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchHistory {
+    entries: HashMap<String, LaunchRecord>,
+}
+
+impl LaunchHistory {
+    pub fn load() -> Self {
+        let Some(path) = history_path() else {
+            return Self::default();
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_json::from_str(&content) {
+            Ok(history) => history,
+            Err(err) => {
+                warn!("failed to parse launch history {:?}: {err}", path);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = history_path() else {
+            return Err("无法确定数据目录".into());
+        };
+        let payload = serde_json::to_string(self).map_err(|err| err.to_string())?;
+        cache::write_atomically(&path, payload)?;
+        debug!("wrote launch history {:?}", path);
+        Ok(())
+    }
+
+    pub fn record(&mut self, id: &str, now: u64) {
+        let entry = self.entries.entry(id.to_string()).or_insert(LaunchRecord {
+            count: 0,
+            last_launched: now,
+        });
+        entry.count = entry.count.saturating_add(1);
+        entry.last_launched = now;
+        self.prune();
+    }
+
+    /// Number of items with a recorded launch.
+    pub fn item_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Frequency weighted by how recently the item was last launched.
+    pub fn frecency(&self, id: &str, now: u64) -> i64 {
+        let Some(record) = self.entries.get(id) else {
+            return 0;
+        };
+        let age_days = now.saturating_sub(record.last_launched) / DAY_SECS;
+        let recency = match age_days {
+            0..=3 => 100,
+            4..=13 => 70,
+            14..=30 => 50,
+            31..=90 => 30,
+            _ => 10,
+        };
+        i64::from(record.count.min(MAX_COUNTED_LAUNCHES)) * recency / 4
+    }
+
+    /// Folds in launches recorded elsewhere (see `sync.rs`): the newer record of
+    /// each item wins, keeping the higher count.
+    pub fn merge(&mut self, other: &LaunchHistory) {
+        for (id, theirs) in &other.entries {
+            let merged = match self.entries.get(id) {
+                Some(ours) if ours.last_launched >= theirs.last_launched => LaunchRecord {
+                    count: ours.count.max(theirs.count),
+                    ..*ours
+                },
+                Some(ours) => LaunchRecord {
+                    count: ours.count.max(theirs.count),
+                    ..*theirs
+                },
+                None => *theirs,
+            };
+            self.entries.insert(id.clone(), merged);
+        }
+        self.prune();
+    }
+
+    fn prune(&mut self) {
+        if self.entries.len() <= MAX_ENTRIES {
+            return;
+        }
+        let mut by_age: Vec<(String, u64)> = self
+            .entries
+            .iter()
+            .map(|(id, record)| (id.clone(), record.last_launched))
+            .collect();
+        by_age.sort_by_key(|(_, last)| *last);
+        let excess = self.entries.len() - MAX_ENTRIES;
+        for (id, _) in by_age.into_iter().take(excess) {
+            self.entries.remove(&id);
+        }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+fn history_path() -> Option<PathBuf> {
+    let base = dirs::data_local_dir()?;
+    Some(base.join("egg").join("data").join(HISTORY_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frecency_grows_with_launches_and_decays_with_age() {
+        let now = 100 * DAY_SECS;
+        let mut history = LaunchHistory::default();
+        assert_eq!(history.frecency("app-a", now), 0);
+
+        history.record("app-a", now);
+        let once = history.frecency("app-a", now);
+        history.record("app-a", now);
+        let twice = history.frecency("app-a", now);
+        assert!(twice > once);
+
+        assert!(history.frecency("app-a", now + 20 * DAY_SECS) < twice);
+        assert!(history.frecency("app-a", now + 200 * DAY_SECS) > 0);
+    }
+
+    #[test]
+    fn merge_keeps_the_newest_launch_and_highest_count() {
+        let mut home = LaunchHistory::default();
+        home.record("code", 100);
+        home.record("code", 200);
+        home.record("notes", 50);
+        let mut work = LaunchHistory::default();
+        work.record("code", 300);
+        work.record("chrome", 10);
+
+        home.merge(&work);
+        assert_eq!(
+            home.entries["code"],
+            LaunchRecord {
+                count: 2,
+                last_launched: 300
+            }
+        );
+        assert_eq!(home.entries["notes"].last_launched, 50);
+        assert_eq!(home.entries["chrome"].count, 1);
+    }
+
+    #[test]
+    fn prune_drops_oldest_entries() {
+        let mut history = LaunchHistory::default();
+        for index in 0..=MAX_ENTRIES as u64 {
+            history.record(&format!("app-{index}"), index);
+        }
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert!(!history.entries.contains_key("app-0"));
+        assert!(history.entries.contains_key(&format!("app-{MAX_ENTRIES}")));
+    }
+}
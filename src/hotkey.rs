@@ -0,0 +1,78 @@
+//! Key combinations written as text, such as `Ctrl+B` or `Alt+Shift+F4`, as in
+//! `blacklist_hotkey` and the command shortcuts.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct HotkeySpec {
+    modifiers: KeyModifiers,
+    code: KeyCode,
+}
+
+pub fn parse_hotkey(input: &str) -> Option<HotkeySpec> {
+    let mut modifiers = KeyModifiers::empty();
+    let mut code = None;
+
+    for token in input.split('+').map(|value| value.trim()) {
+        if token.is_empty() {
+            continue;
+        }
+        let token_upper = token.to_ascii_uppercase();
+        match token_upper.as_str() {
+            "ALT" => modifiers.insert(KeyModifiers::ALT),
+            "CTRL" | "CONTROL" => modifiers.insert(KeyModifiers::CONTROL),
+            "SHIFT" => modifiers.insert(KeyModifiers::SHIFT),
+            _ => {
+                if code.is_some() {
+                    return None;
+                }
+                code = parse_key_code(&token_upper);
+            }
+        }
+    }
+
+    code.map(|code| HotkeySpec { modifiers, code })
+}
+
+fn parse_key_code(token: &str) -> Option<KeyCode> {
+    if token.len() == 1 {
+        let ch = token.chars().next().unwrap();
+        let normalized = ch.to_ascii_lowercase();
+        if normalized.is_ascii_alphanumeric() || normalized == ' ' {
+            return Some(KeyCode::Char(normalized));
+        }
+    }
+
+    match token {
+        "SPACE" => Some(KeyCode::Char(' ')),
+        "ENTER" | "RETURN" => Some(KeyCode::Enter),
+        "TAB" => Some(KeyCode::Tab),
+        "ESC" | "ESCAPE" => Some(KeyCode::Esc),
+        "BACKSPACE" => Some(KeyCode::Backspace),
+        "LEFT" => Some(KeyCode::Left),
+        "RIGHT" => Some(KeyCode::Right),
+        "UP" => Some(KeyCode::Up),
+        "DOWN" => Some(KeyCode::Down),
+        _ => token
+            .strip_prefix('F')
+            .and_then(|number| number.parse().ok())
+            .filter(|number| (1..=12).contains(number))
+            .map(KeyCode::F),
+    }
+}
+
+pub fn hotkey_matches(event: KeyEvent, spec: &HotkeySpec) -> bool {
+    let mut event_mods = event.modifiers;
+    let mut spec_mods = spec.modifiers;
+    let mut event_code = event.code;
+    let mut spec_code = spec.code;
+
+    if let (KeyCode::Char(event_char), KeyCode::Char(spec_char)) = (event.code, spec.code) {
+        event_mods.remove(KeyModifiers::SHIFT);
+        spec_mods.remove(KeyModifiers::SHIFT);
+        event_code = KeyCode::Char(event_char.to_ascii_lowercase());
+        spec_code = KeyCode::Char(spec_char.to_ascii_lowercase());
+    }
+
+    event_mods == spec_mods && event_code == spec_code
+}
@@ -0,0 +1,273 @@
+//! App icons and site favicons for clients of `egg-cli serve` that show them.
+//! Each icon is extracted or downloaded once into `<cache dir>/icons`, in a file
+//! named after the app id or site, and messages carry that file's path. Delete
+//! the folder to extract them again.
+
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use log::{debug, warn};
+
+use crate::{cache, models::ApplicationInfo, page_titles::page_url};
+
+/// Formats a cached icon may be stored in.
+const EXTENSIONS: [&str; 4] = ["ico", "png", "svg", "xpm"];
+/// Favicons past this size are not cached.
+const MAX_FAVICON_BYTES: u64 = 256 * 1024;
+const FAVICON_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The icon of `app` if it was extracted before.
+pub fn cached_icon(app: &ApplicationInfo) -> Option<PathBuf> {
+    cached(&app.id)
+}
+
+/// The cached icon of `app`, extracting it first if needed. Extraction goes
+/// through the shell and can take a while, so callers run it off the request path.
+pub fn icon_file(app: &ApplicationInfo) -> Option<PathBuf> {
+    if let Some(cached) = cached_icon(app) {
+        return Some(cached);
+    }
+    let (bytes, extension) = extract(app)?;
+    store(&app.id, &bytes, extension)
+}
+
+/// The `scheme://host` of a web address, whose favicon stands for its bookmarks.
+pub fn site_of(url: &str) -> Option<String> {
+    let page = page_url(url)?;
+    let (scheme, rest) = page.split_once("://")?;
+    let host = rest
+        .split(['/', '?', '#'])
+        .next()
+        .filter(|host| !host.is_empty())?;
+    Some(format!(
+        "{}://{}",
+        scheme.to_ascii_lowercase(),
+        host.to_ascii_lowercase()
+    ))
+}
+
+/// The favicon of `site` if it was downloaded before.
+pub fn cached_favicon(site: &str) -> Option<PathBuf> {
+    cached(&favicon_key(site))
+}
+
+/// The cached favicon of `site`, downloading its `/favicon.ico` first if needed.
+/// Like [`icon_file`], this can take a while.
+pub fn favicon_file(site: &str) -> Option<PathBuf> {
+    if let Some(cached) = cached_favicon(site) {
+        return Some(cached);
+    }
+    let bytes = fetch_favicon(site)
+        .map_err(|err| debug!("no favicon for {site}: {err}"))
+        .ok()?;
+    // Sites without a favicon often answer with an HTML page instead.
+    let extension = image_kind(&bytes)?;
+    store(&favicon_key(site), &bytes, extension)
+}
+
+fn favicon_key(site: &str) -> String {
+    format!("favicon:{site}")
+}
+
+fn fetch_favicon(site: &str) -> Result<Vec<u8>, String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(FAVICON_TIMEOUT)
+        .redirects(5)
+        .user_agent(concat!("egg-cli/", env!("CARGO_PKG_VERSION")))
+        .build();
+    let response = agent
+        .get(&format!("{site}/favicon.ico"))
+        .call()
+        .map_err(|err| err.to_string())?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_FAVICON_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|err| err.to_string())?;
+    if bytes.len() as u64 > MAX_FAVICON_BYTES {
+        return Err("favicon too large".to_string());
+    }
+    Ok(bytes)
+}
+
+/// The extension for an `.ico` or PNG image, by its first bytes.
+fn image_kind(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0, 0, 1, 0]) {
+        Some("ico")
+    } else if bytes.starts_with(b"\x89PNG") {
+        Some("png")
+    } else {
+        None
+    }
+}
+
+fn cached(id: &str) -> Option<PathBuf> {
+    let dir = cache::icon_dir()?;
+    let stem = cache_key(id);
+    EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{stem}.{extension}")))
+        .find(|path| path.is_file())
+}
+
+fn store(id: &str, bytes: &[u8], extension: &str) -> Option<PathBuf> {
+    let dir = cache::icon_dir()?;
+    let path = dir.join(format!("{}.{extension}", cache_key(id)));
+    // Written whole or not at all, since `cached` serves whatever is there.
+    if let Err(err) = cache::write_atomically(&path, bytes) {
+        warn!("failed to cache the icon of {id}: {err}");
+        return None;
+    }
+    Some(path)
+}
+
+/// App ids hold paths and colons, so files are named by a hash of the id.
+fn cache_key(id: &str) -> String {
+    // FNV-1a, which unlike the std hasher stays the same across Rust releases.
+    let hash = id.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(windows)]
+fn extract(app: &ApplicationInfo) -> Option<(Vec<u8>, &'static str)> {
+    use crate::windows_utils::{file_icon, shell_icon};
+
+    // Image files, such as a terminal profile's icon, are cached as they are.
+    if let Some(icon) = app.icon.as_deref() {
+        if let Some(extension) = image_extension(Path::new(icon)) {
+            return Some((fs::read(icon).ok()?, extension));
+        }
+    }
+    let bytes = match app.icon.as_deref().and_then(|icon| icon.rsplit_once(',')) {
+        Some((file, index)) => file_icon(file, index.trim().parse().unwrap_or(0)),
+        None => shell_icon(app.source_path.as_deref().unwrap_or(&app.path)),
+    }?;
+    Some((bytes, "ico"))
+}
+
+#[cfg(not(windows))]
+fn extract(app: &ApplicationInfo) -> Option<(Vec<u8>, &'static str)> {
+    let path = theme_icon(app.icon.as_deref()?)?;
+    let extension = image_extension(&path)?;
+    Some((fs::read(&path).ok()?, extension))
+}
+
+/// The extension of `path` when it is one of the [`EXTENSIONS`] icons are kept in.
+fn image_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    EXTENSIONS.into_iter().find(|known| *known == extension)
+}
+
+/// Sizes tried in the hicolor theme, largest bitmap first.
+#[cfg(not(windows))]
+const THEME_SIZES: [&str; 6] = ["256x256", "128x128", "64x64", "48x48", "32x32", "scalable"];
+
+/// A desktop entry's `Icon=`: an absolute path, or a name looked up in the
+/// hicolor theme and then `pixmaps`.
+#[cfg(not(windows))]
+fn theme_icon(icon: &str) -> Option<PathBuf> {
+    let icon = icon.trim();
+    if Path::new(icon).is_absolute() {
+        return Path::new(icon).is_file().then(|| PathBuf::from(icon));
+    }
+    let data_dirs = dirs::data_dir().into_iter().chain(
+        std::env::var("XDG_DATA_DIRS")
+            .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string())
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .collect::<Vec<_>>(),
+    );
+    let mut folders = Vec::new();
+    for data_dir in data_dirs {
+        for size in THEME_SIZES {
+            folders.push(data_dir.join("icons/hicolor").join(size).join("apps"));
+        }
+        folders.push(data_dir.join("pixmaps"));
+    }
+    folders.iter().find_map(|folder| find_icon_in(folder, icon))
+}
+
+#[cfg(not(windows))]
+fn find_icon_in(folder: &Path, name: &str) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|extension| folder.join(format!("{name}.{extension}")))
+        .find(|path| path.is_file())
+}
+
+/// An `.ico` file holding one 32-bit image, from BGRA rows bottom row first.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn encode_ico(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    const HEADER_SIZE: u32 = 6 + 16;
+    const INFO_SIZE: u32 = 40;
+    // The AND mask is one bit per pixel, rows padded to four bytes; alpha makes
+    // it unused, so it stays clear.
+    let mask_size = width.div_ceil(32) * 4 * height;
+    let image_size = INFO_SIZE + pixels.len() as u32 + mask_size;
+    // Sizes of 256 and up are written as 0.
+    let dimension = |value: u32| u8::try_from(value).unwrap_or(0);
+
+    let mut ico = Vec::with_capacity((HEADER_SIZE + image_size) as usize);
+    ico.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    ico.extend_from_slice(&[dimension(width), dimension(height), 0, 0]);
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&32u16.to_le_bytes());
+    ico.extend_from_slice(&image_size.to_le_bytes());
+    ico.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+
+    ico.extend_from_slice(&INFO_SIZE.to_le_bytes());
+    ico.extend_from_slice(&(width as i32).to_le_bytes());
+    // The height counts the colour and mask rows together.
+    ico.extend_from_slice(&(height as i32 * 2).to_le_bytes());
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&32u16.to_le_bytes());
+    ico.extend_from_slice(&[0; 24]);
+    ico.extend_from_slice(pixels);
+    ico.resize((HEADER_SIZE + image_size) as usize, 0);
+    ico
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icons_are_cached_under_a_stable_name() {
+        assert_eq!(cache_key("shell:wechat"), cache_key("shell:wechat"));
+        assert_ne!(cache_key("shell:wechat"), cache_key("shell:wechat2"));
+        assert_eq!(cache_key(""), "cbf29ce484222325");
+
+        let ico = encode_ico(2, 2, &[255; 16]);
+        assert_eq!(&ico[..6], &[0, 0, 1, 0, 1, 0]);
+        assert_eq!((ico[6], ico[7]), (2, 2));
+        // Header, bitmap info, four pixels and two padded mask rows.
+        assert_eq!(ico.len(), 22 + 40 + 16 + 8);
+        assert_eq!(u32::from_le_bytes(ico[14..18].try_into().unwrap()), 64);
+        assert_eq!(i32::from_le_bytes(ico[30..34].try_into().unwrap()), 4);
+    }
+
+    #[test]
+    fn favicons_are_kept_per_site() {
+        assert_eq!(
+            site_of("https://GitHub.com/rust-lang/rust?tab=readme").as_deref(),
+            Some("https://github.com")
+        );
+        assert_eq!(
+            site_of("example.com").as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(site_of("mailto:someone@example.com"), None);
+
+        assert_eq!(image_kind(&encode_ico(1, 1, &[0; 4])), Some("ico"));
+        assert_eq!(image_kind(b"\x89PNG\r\n\x1a\n"), Some("png"));
+        assert_eq!(image_kind(b"<!doctype html>"), None);
+    }
+}
@@ -0,0 +1,165 @@
+//! What changed between two versions of the app or bookmark index, matched up
+//! by id. A refresh that replaces an index only drops the cached searches the
+//! change can reach: those listing a removed or changed entry, those a new or
+//! changed entry could appear in, and those built from the whole index.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    bookmarks::BookmarkEntry,
+    config::AppConfig,
+    models::ApplicationInfo,
+    search_core::{could_match, reads_whole_index},
+    state::{CacheProvider, CachedSearch},
+};
+
+/// An entry of the app or bookmark index.
+pub trait IndexEntry: Clone + PartialEq {
+    /// The data searches over this index read.
+    const PROVIDER: CacheProvider;
+
+    fn id(&self) -> &str;
+
+    /// The id of the search result listing this entry.
+    fn result_id(&self) -> String;
+
+    /// Whether a search for `query` could list one of `entries`.
+    fn could_match(query: &str, entries: &[Self], config: &AppConfig) -> bool;
+}
+
+impl IndexEntry for ApplicationInfo {
+    const PROVIDER: CacheProvider = CacheProvider::Apps;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn result_id(&self) -> String {
+        format!("app-{}", self.id)
+    }
+
+    fn could_match(query: &str, entries: &[Self], config: &AppConfig) -> bool {
+        could_match(query, entries, &[], config)
+    }
+}
+
+impl IndexEntry for BookmarkEntry {
+    const PROVIDER: CacheProvider = CacheProvider::Bookmarks;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn result_id(&self) -> String {
+        format!("bookmark-{}", self.id)
+    }
+
+    fn could_match(query: &str, entries: &[Self], config: &AppConfig) -> bool {
+        could_match(query, &[], entries, config)
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexDiff<T> {
+    /// Added entries and the new versions of changed ones.
+    pub fresh: Vec<T>,
+    /// Result ids of removed and changed entries.
+    pub stale_ids: HashSet<String>,
+}
+
+impl<T: IndexEntry> IndexDiff<T> {
+    pub fn between(old: &[T], new: &[T]) -> Self {
+        let old_by_id: HashMap<&str, &T> = old.iter().map(|entry| (entry.id(), entry)).collect();
+        let new_ids: HashSet<&str> = new.iter().map(IndexEntry::id).collect();
+        let mut fresh = Vec::new();
+        let mut stale_ids: HashSet<String> = old
+            .iter()
+            .filter(|entry| !new_ids.contains(entry.id()))
+            .map(IndexEntry::result_id)
+            .collect();
+        for entry in new {
+            match old_by_id.get(entry.id()) {
+                Some(previous) if *previous == entry => {}
+                Some(_) => {
+                    stale_ids.insert(entry.result_id());
+                    fresh.push(entry.clone());
+                }
+                None => fresh.push(entry.clone()),
+            }
+        }
+        Self { fresh, stale_ids }
+    }
+
+    /// Whether no entry was added, removed or changed; the order may still differ.
+    pub fn is_empty(&self) -> bool {
+        self.fresh.is_empty() && self.stale_ids.is_empty()
+    }
+
+    /// Whether `search`, cached under `config`, could come out differently now.
+    pub fn affects(&self, search: &CachedSearch, config: &AppConfig) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        search
+            .results
+            .iter()
+            .any(|result| reads_whole_index(result) || self.stale_ids.contains(&result.id))
+            || T::could_match(&search.query, &self.fresh, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixture_apps;
+
+    fn cached(query: &str, result_ids: &[&str]) -> CachedSearch {
+        CachedSearch {
+            query: query.to_string(),
+            results: result_ids
+                .iter()
+                .map(|id| crate::models::SearchResult {
+                    id: id.to_string(),
+                    title: id.to_string(),
+                    subtitle: String::new(),
+                    score: 0,
+                    action_id: "app".to_string(),
+                    match_source: None,
+                })
+                .collect(),
+            pending_actions: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn only_searches_a_change_can_reach_are_affected() {
+        let old = fixture_apps();
+        let chrome = old.iter().find(|app| app.name == "Google Chrome").unwrap();
+        let chrome_id = chrome.result_id();
+        let mut new: Vec<ApplicationInfo> = old
+            .iter()
+            .filter(|app| app.name != "Google Chrome")
+            .cloned()
+            .collect();
+        new.reverse();
+        let mut blender = new[0].clone();
+        blender.id = "blender".to_string();
+        blender.name = "Blender".to_string();
+        blender.keywords.clear();
+        blender.pinyin_index = None;
+        blender.folded = None;
+        new.push(blender);
+
+        let config = AppConfig::default();
+        let diff = IndexDiff::between(&old, &new);
+        assert_eq!(diff.fresh.len(), 1);
+        assert_eq!(diff.stale_ids, HashSet::from([chrome_id.clone()]));
+        assert!(diff.affects(&cached("chrome", &[&chrome_id]), &config));
+        assert!(diff.affects(&cached("blend", &[]), &config));
+        assert!(!diff.affects(&cached("zzqx", &["app-other"]), &config));
+
+        // Reordering alone affects nothing.
+        let reordered: Vec<_> = old.iter().rev().cloned().collect();
+        assert!(IndexDiff::between(&old, &reordered).is_empty());
+    }
+}
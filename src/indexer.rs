@@ -1,12 +1,22 @@
 use std::{
     collections::HashSet,
-    env, fs,
+    env,
+    fmt::Display,
+    fs,
+    future::Future,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use log::{debug, warn};
+#[cfg(windows)]
 use windows::{
-    core::{Error as WinError, Result as WinResult, PWSTR},
+    core::{Error as WinError, Result as WinResult, HSTRING, PWSTR},
+    Management::Deployment::PackageManager,
     Win32::{
         Foundation::{HANDLE, RPC_E_CHANGED_MODE},
         System::{
@@ -20,62 +30,397 @@ use windows::{
     },
 };
 
+#[cfg(not(windows))]
+use crate::desktop_apps::enumerate_desktop_apps;
 use crate::{
+    config::AppConfig,
+    games::{enumerate_games, is_game_entry},
+    git_repos::{enumerate_git_repos, is_git_repo},
+    logging::span,
     models::{AppType, ApplicationInfo},
+    path_commands::{drop_shadowed_path_commands, enumerate_path_commands, is_path_command},
+    recent_documents::{enumerate_recent_documents, is_recent_document},
+    recent_projects::{enumerate_recent_projects, is_recent_project},
+    terminal_profiles::{enumerate_terminal_profiles, is_terminal_profile},
     text_utils::build_pinyin_index,
+    uninstallers::{enumerate_uninstallers, is_uninstall_entry},
+    web_apps::{drop_shell_duplicates, enumerate_web_apps, is_web_app},
+    wsl::{enumerate_wsl_distros, is_wsl_distro},
 };
 
-/// Build the application index by enumerating the AppsFolder shell items.
-pub async fn build_index(exclusion_paths: Vec<String>) -> Vec<ApplicationInfo> {
-    let (shell_task, start_menu_task) = tokio::join!(
-        tokio::task::spawn_blocking(enumerate_shell_apps),
-        tokio::task::spawn_blocking(enumerate_start_menu_urls),
-    );
-    let mut results = match shell_task {
-        Ok(Ok(apps)) => apps,
-        Ok(Err(err)) => {
-            warn!("shell apps index failed: {err}");
-            Vec::new()
-        }
-        Err(err) => {
-            warn!("shell apps index task failed: {err}");
-            Vec::new()
+#[cfg(windows)]
+const APPS_FOLDER_PREFIX: &str = r"shell:AppsFolder\";
+/// Ids of apps reported by PackageManager rather than the AppsFolder walk.
+const PACKAGED_ID_PREFIX: &str = "uwp:";
+
+/// What [`build_index`] takes from the settings.
+#[derive(Debug, Clone, Default)]
+pub struct IndexOptions {
+    pub exclusion_paths: Vec<String>,
+    /// Adds the uninstallers from the registry.
+    pub uninstallers: bool,
+    /// Folders searched for git repositories.
+    pub git_roots: Vec<String>,
+    pub git_editor: Option<String>,
+}
+
+impl IndexOptions {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            exclusion_paths: config.system_tool_exclusions.clone(),
+            uninstallers: config.index_uninstallers,
+            git_roots: config.git_roots.clone(),
+            git_editor: config.git_editor.clone(),
         }
-    };
-    debug!("indexed {} shell apps", results.len());
+    }
+}
+
+/// A step of [`build_index_with_progress`], for showing how far a reindex got.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexProgress {
+    Started,
+    /// A source finished with `count` entries (none when it failed); `done` of
+    /// the build's `sources` have finished.
+    Source {
+        source: &'static str,
+        count: usize,
+        done: usize,
+        sources: usize,
+    },
+    /// The index is built, with `total` entries after dropping duplicates.
+    Finished {
+        total: usize,
+        duration: Duration,
+    },
+}
+
+/// Counts the sources of one build and reports each as it finishes.
+#[derive(Clone)]
+struct SourceProgress {
+    report: Arc<dyn Fn(IndexProgress) + Send + Sync>,
+    sources: Arc<AtomicUsize>,
+    done: Arc<AtomicUsize>,
+}
 
-    let start_menu = match start_menu_task {
-        Ok(apps) => apps,
-        Err(err) => {
-            warn!("start menu index task failed: {err}");
-            Vec::new()
+impl SourceProgress {
+    fn new(report: impl Fn(IndexProgress) + Send + Sync + 'static) -> Self {
+        Self {
+            report: Arc::new(report),
+            sources: Arc::new(AtomicUsize::new(0)),
+            done: Arc::new(AtomicUsize::new(0)),
         }
-    };
-    debug!("indexed {} start menu urls", start_menu.len());
-    results.extend(start_menu);
+    }
 
-    let mut seen: HashSet<String> = HashSet::new();
-    results.retain(|app| seen.insert(app.path.to_ascii_lowercase()));
-    results.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    fn finished(&self, source: &'static str, count: usize) {
+        (self.report)(IndexProgress::Source {
+            source,
+            count,
+            done: self.done.fetch_add(1, Ordering::Relaxed) + 1,
+            sources: self.sources.load(Ordering::Relaxed),
+        });
+    }
+}
 
-    results.retain(|app| !is_system_tool(app, &exclusion_paths));
+pub async fn build_index(options: IndexOptions) -> Vec<ApplicationInfo> {
+    build_index_with_progress(options, |_| {}).await
+}
 
+/// Build the application index from installed packages, the AppsFolder shell
+/// items, Chrome and Edge web apps, Steam and Epic games, Start Menu `.url`
+/// shortcuts, `%PATH%`, WSL distributions, Windows Terminal profiles, git
+/// repositories, IDE projects, recent documents and, when enabled, uninstallers. Outside Windows `.desktop` entries or app bundles
+/// take the place of packages and AppsFolder. Each source is reported to
+/// `progress` as it finishes.
+pub async fn build_index_with_progress(
+    options: IndexOptions,
+    progress: impl Fn(IndexProgress) + Send + Sync + 'static,
+) -> Vec<ApplicationInfo> {
+    let IndexOptions {
+        exclusion_paths,
+        uninstallers,
+        git_roots,
+        git_editor,
+    } = options;
+    let _span = span("build app index");
+    let started = Instant::now();
+    let progress = SourceProgress::new(progress);
+    (progress.report)(IndexProgress::Started);
+    let startup_dirs = startup_directories();
+    // One task per Start Menu root: the per-user and all-users trees are walked side by side.
+    let start_menu_tasks: Vec<_> = start_menu_roots()
+        .into_iter()
+        .map(|root| {
+            let startup_dirs = startup_dirs.clone();
+            index_source(&progress, "start menu urls", move || {
+                start_menu_urls_in(&root, &startup_dirs)
+            })
+        })
+        .collect();
+    let uninstall_task =
+        uninstallers.then(|| index_source(&progress, "uninstallers", enumerate_uninstallers));
+    let (
+        packaged,
+        mut shell_apps,
+        web_apps,
+        games,
+        path_commands,
+        wsl_distros,
+        terminal_profiles,
+        repos,
+        recent_documents,
+        projects,
+    ) = tokio::join!(
+        index_source(&progress, "packaged apps", enumerate_packaged_apps),
+        index_source(&progress, "shell apps", enumerate_shell_apps),
+        index_source(&progress, "web apps", enumerate_web_apps),
+        index_source(&progress, "games", enumerate_games),
+        index_source(&progress, "executables on PATH", enumerate_path_commands),
+        index_source(&progress, "wsl distributions", enumerate_wsl_distros),
+        index_source(&progress, "terminal profiles", enumerate_terminal_profiles),
+        index_source(&progress, "git repositories", move || {
+            enumerate_git_repos(&git_roots, git_editor.as_deref())
+        }),
+        index_source(&progress, "recent documents", enumerate_recent_documents),
+        index_source(&progress, "recent projects", enumerate_recent_projects),
+    );
+    let mut results = packaged;
+    drop_packaged_duplicates(&mut shell_apps, &results);
+    drop_shell_duplicates(&mut shell_apps, &web_apps);
+    results.extend(shell_apps);
+    results.extend(web_apps);
+    // Ahead of the Start Menu so a game's store name wins over its shortcut's.
+    results.extend(games);
+    // Awaited in root order, so the index comes out the same on every run.
+    for task in start_menu_tasks {
+        results.extend(task.await);
+    }
+    results.extend(path_commands);
+    results.extend(wsl_distros);
+    results.extend(terminal_profiles);
+    // Ahead of the IDE projects, so a repository that is also a recent project
+    // keeps the repository's actions.
+    results.extend(repos);
+    results.extend(projects);
+    if let Some(task) = uninstall_task {
+        results.extend(task.await);
+    }
+    // Last, so a document that is also an indexed app keeps the app entry.
+    results.extend(recent_documents);
+
+    let finalize_started = Instant::now();
+    finalize_index(&mut results, &exclusion_paths);
+    debug!(
+        "finalized {} index entries in {:.1?}",
+        results.len(),
+        finalize_started.elapsed()
+    );
+    (progress.report)(IndexProgress::Finished {
+        total: results.len(),
+        duration: started.elapsed(),
+    });
     results
 }
 
-fn is_system_tool(app: &ApplicationInfo, exclusion_paths: &[String]) -> bool {
+/// Starts one index source on the blocking pool right away; the returned future
+/// logs how many entries it found and how long it took, and reports it to
+/// `progress`. A failed source contributes nothing.
+fn index_source<F, T>(
+    progress: &SourceProgress,
+    source: &'static str,
+    enumerate: F,
+) -> impl Future<Output = Vec<ApplicationInfo>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: SourceOutput + Send + 'static,
+{
+    // Every source is started before any is awaited, so the count is complete
+    // by the time the first one reports.
+    progress.sources.fetch_add(1, Ordering::Relaxed);
+    let progress = progress.clone();
+    let task = tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        (enumerate().into_result(), started.elapsed())
+    });
+    async move {
+        let apps = match task.await {
+            Ok((Ok(apps), elapsed)) => {
+                debug!("indexed {} {source} in {elapsed:.1?}", apps.len());
+                apps
+            }
+            Ok((Err(err), elapsed)) => {
+                warn!("{source} index failed after {elapsed:.1?}: {err}");
+                Vec::new()
+            }
+            Err(err) => {
+                warn!("{source} index task failed: {err}");
+                Vec::new()
+            }
+        };
+        progress.finished(source, apps.len());
+        apps
+    }
+}
+
+/// What an index source returns: its entries, or an error for sources that can fail.
+trait SourceOutput {
+    fn into_result(self) -> Result<Vec<ApplicationInfo>, String>;
+}
+
+impl SourceOutput for Vec<ApplicationInfo> {
+    fn into_result(self) -> Result<Vec<ApplicationInfo>, String> {
+        Ok(self)
+    }
+}
+
+impl<E: Display> SourceOutput for Result<Vec<ApplicationInfo>, E> {
+    fn into_result(self) -> Result<Vec<ApplicationInfo>, String> {
+        self.map_err(|err| err.to_string())
+    }
+}
+
+/// Dedups by path and arguments (web apps all start the same proxy), sorts by name and drops excluded system tools. `%PATH%`
+/// executables and uninstallers (`MsiExec.exe`) skip the exclusions, which would
+/// otherwise hide all of System32. The apps left are case-folded for searching.
+pub fn finalize_index(results: &mut Vec<ApplicationInfo>, exclusion_paths: &[String]) {
+    let mut seen: HashSet<(String, Option<String>)> = HashSet::new();
+    results.retain(|app| seen.insert((app.path.to_ascii_lowercase(), app.arguments.clone())));
+    drop_shadowed_path_commands(results);
+    results.sort_by_cached_key(|app| app.name.to_lowercase());
+    let exclusions: Vec<String> = exclusion_paths
+        .iter()
+        .map(|path| path.trim().to_ascii_lowercase())
+        .filter(|path| !path.is_empty())
+        .collect();
+    results.retain(|app| {
+        is_path_command(app) || is_uninstall_entry(app) || !is_system_tool(app, &exclusions)
+    });
+    for app in results.iter_mut() {
+        app.fold_case();
+    }
+}
+
+/// Start Menu folders whose changes affect the index.
+pub fn watched_start_menu_roots() -> Vec<PathBuf> {
+    start_menu_roots()
+}
+
+/// Re-enumerates AppsFolder. The shell has no per-shortcut query, so `.lnk`
+/// changes are handled by diffing this list against the current index.
+pub async fn enumerate_shell_index() -> Vec<ApplicationInfo> {
+    index_source(
+        &SourceProgress::new(|_| {}),
+        "shell apps",
+        enumerate_shell_apps,
+    )
+    .await
+}
+
+/// Reads one `.url` shortcut; `None` when it was deleted, is a Startup item or is unsupported.
+pub fn load_url_shortcut(path: &Path) -> Option<ApplicationInfo> {
+    if !path.is_file()
+        || startup_directories()
+            .iter()
+            .any(|startup| path.starts_with(startup))
+    {
+        return None;
+    }
+    internet_shortcut_to_application(path)
+}
+
+fn is_packaged_entry(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(PACKAGED_ID_PREFIX)
+}
+
+/// Drops AppsFolder items for apps PackageManager already reported; the shell
+/// may give their AppUserModelId with or without the `shell:AppsFolder\` prefix.
+fn drop_packaged_duplicates(shell_apps: &mut Vec<ApplicationInfo>, packaged: &[ApplicationInfo]) {
+    let last_segment = |path: &str| {
+        path.rsplit('\\')
+            .next()
+            .unwrap_or(path)
+            .to_ascii_lowercase()
+    };
+    let packaged_ids: HashSet<String> = packaged
+        .iter()
+        .filter(|app| is_packaged_entry(app))
+        .map(|app| last_segment(&app.path))
+        .collect();
+    shell_apps.retain(|app| !packaged_ids.contains(&last_segment(&app.path)));
+}
+
+fn is_url_shortcut_entry(app: &ApplicationInfo) -> bool {
+    app.source_path
+        .as_deref()
+        .is_some_and(|source| source.to_ascii_lowercase().ends_with(".url"))
+}
+
+/// Applies re-read `.url` shortcuts to the index. Returns whether anything changed.
+pub fn merge_url_shortcuts(
+    apps: &mut Vec<ApplicationInfo>,
+    changes: Vec<(PathBuf, Option<ApplicationInfo>)>,
+    exclusion_paths: &[String],
+) -> bool {
+    let before = apps.clone();
+    for (path, replacement) in changes {
+        let source = path.to_string_lossy().to_ascii_lowercase();
+        apps.retain(|app| {
+            app.source_path
+                .as_deref()
+                .is_none_or(|existing| existing.to_ascii_lowercase() != source)
+        });
+        apps.extend(replacement);
+    }
+    finalize_index(apps, exclusion_paths);
+    *apps != before
+}
+
+/// Replaces the AppsFolder-derived part of the index, keeping `.url`, web app,
+/// game, `%PATH%`, WSL, terminal profile, recent document, repository, project, uninstaller and packaged entries. Returns whether anything changed.
+pub fn merge_shell_apps(
+    apps: &mut Vec<ApplicationInfo>,
+    mut shell_apps: Vec<ApplicationInfo>,
+    exclusion_paths: &[String],
+) -> bool {
+    let before = apps.clone();
+    let mut merged: Vec<ApplicationInfo> = apps
+        .drain(..)
+        .filter(|app| {
+            is_url_shortcut_entry(app)
+                || is_packaged_entry(app)
+                || is_web_app(app)
+                || is_game_entry(app)
+                || is_path_command(app)
+                || is_recent_document(app)
+                || is_recent_project(app)
+                || is_git_repo(app)
+                || is_wsl_distro(app)
+                || is_terminal_profile(app)
+                || is_uninstall_entry(app)
+        })
+        .collect();
+    drop_packaged_duplicates(&mut shell_apps, &merged);
+    let web_apps: Vec<ApplicationInfo> = merged
+        .iter()
+        .filter(|app| is_web_app(app))
+        .cloned()
+        .collect();
+    drop_shell_duplicates(&mut shell_apps, &web_apps);
+    merged.extend(shell_apps);
+    finalize_index(&mut merged, exclusion_paths);
+    *apps = merged;
+    *apps != before
+}
+
+/// `exclusions` are trimmed, lowercased and non-empty.
+fn is_system_tool(app: &ApplicationInfo, exclusions: &[String]) -> bool {
     let path_to_check = app.source_path.as_ref().unwrap_or(&app.path);
     let path_lower = path_to_check.to_ascii_lowercase();
 
-    for sys_path in exclusion_paths {
-        let sys_path_lower = sys_path.trim().to_ascii_lowercase();
-        if sys_path_lower.is_empty() {
-            continue;
-        }
-        if path_lower.starts_with(&sys_path_lower) {
+    for sys_path_lower in exclusions.iter().map(String::as_str) {
+        if path_lower.starts_with(sys_path_lower) {
             return true;
         }
-        if sys_path_lower.starts_with('{') && path_lower.contains(&sys_path_lower) {
+        if sys_path_lower.starts_with('{') && path_lower.contains(sys_path_lower) {
             return true;
         }
         if sys_path_lower.starts_with("shell:appsfolder\\") {
@@ -84,10 +429,10 @@ fn is_system_tool(app: &ApplicationInfo, exclusion_paths: &[String]) -> bool {
                 return true;
             }
         }
-        if !looks_like_file_path(&sys_path_lower)
+        if !looks_like_file_path(sys_path_lower)
             && !sys_path_lower.starts_with("shell:")
             && !sys_path_lower.contains("://")
-            && path_lower.contains(&sys_path_lower)
+            && path_lower.contains(sys_path_lower)
         {
             return true;
         }
@@ -103,49 +448,42 @@ fn looks_like_file_path(path: &str) -> bool {
 
 const SUPPORTED_URL_PROTOCOLS: &[&str] = &["steam://", "com.epicgames.launcher://apps/"];
 
-fn enumerate_start_menu_urls() -> Vec<ApplicationInfo> {
-    let startup_dirs = startup_directories();
+/// `.url` shortcuts anywhere below `root`, skipping the Startup folders.
+pub fn start_menu_urls_in(root: &Path, startup_dirs: &[PathBuf]) -> Vec<ApplicationInfo> {
     let mut applications = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
 
-    for root in start_menu_roots() {
-        if !root.is_dir() {
-            continue;
-        }
-
-        let mut stack = vec![root];
-        while let Some(dir) = stack.pop() {
-            let entries = match fs::read_dir(&dir) {
-                Ok(entries) => entries,
-                Err(_) => continue,
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
             };
 
-            for entry in entries.flatten() {
-                let path = entry.path();
-                let Ok(file_type) = entry.file_type() else {
-                    continue;
-                };
-
-                if file_type.is_dir() {
-                    stack.push(path);
-                    continue;
-                }
+            if file_type.is_dir() {
+                stack.push(path);
+                continue;
+            }
 
-                if !file_type.is_file() {
-                    continue;
-                }
+            if !file_type.is_file() {
+                continue;
+            }
 
-                if startup_dirs.iter().any(|startup| path.starts_with(startup)) {
-                    continue;
-                }
+            if startup_dirs.iter().any(|startup| path.starts_with(startup)) {
+                continue;
+            }
 
-                if path
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .is_some_and(|ext| ext.eq_ignore_ascii_case("url"))
-                {
-                    if let Some(app) = internet_shortcut_to_application(&path) {
-                        applications.push(app);
-                    }
+            if path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("url"))
+            {
+                if let Some(app) = internet_shortcut_to_application(&path) {
+                    applications.push(app);
                 }
             }
         }
@@ -210,6 +548,8 @@ fn internet_shortcut_to_application(path: &Path) -> Option<ApplicationInfo> {
         pinyin_index,
         working_directory: None,
         arguments: None,
+        icon: shortcut.icon,
+        folded: None,
     })
 }
 
@@ -245,6 +585,8 @@ fn startup_directories() -> Vec<PathBuf> {
 struct InternetShortcutInfo {
     url: String,
     description: Option<String>,
+    /// `IconFile,IconIndex`.
+    icon: Option<String>,
 }
 
 fn parse_internet_shortcut(path: &Path) -> Option<InternetShortcutInfo> {
@@ -257,6 +599,8 @@ fn parse_internet_shortcut(path: &Path) -> Option<InternetShortcutInfo> {
     let mut in_section = false;
     let mut url = None;
     let mut description = None;
+    let mut icon_file = None;
+    let mut icon_index = 0;
 
     for raw_line in content.lines() {
         let line = raw_line.trim();
@@ -288,12 +632,19 @@ fn parse_internet_shortcut(path: &Path) -> Option<InternetShortcutInfo> {
             "description" | "comment" => {
                 description = Some(cleaned_value.to_string());
             }
+            "iconfile" => icon_file = Some(cleaned_value.to_string()),
+            "iconindex" => icon_index = cleaned_value.parse().unwrap_or(0),
             _ => {}
         }
     }
 
     let url = url?;
-    Some(InternetShortcutInfo { url, description })
+    let icon = icon_file.map(|file| format!("{file},{icon_index}"));
+    Some(InternetShortcutInfo {
+        url,
+        description,
+        icon,
+    })
 }
 
 fn decode_shortcut_contents(bytes: &[u8]) -> Option<String> {
@@ -323,6 +674,7 @@ fn decode_utf16(data: &[u8], little_endian: bool) -> String {
     String::from_utf16_lossy(&units)
 }
 
+#[cfg(windows)]
 fn is_blacklisted_shell_item(name: &str, parsing_name: &str) -> bool {
     if looks_like_uninstaller(name) || looks_like_uninstaller(parsing_name) {
         return true;
@@ -353,12 +705,15 @@ fn is_blacklisted_shell_item(name: &str, parsing_name: &str) -> bool {
     false
 }
 
-struct ComInitGuard {
+/// Initializes COM (multithreaded) for the current thread until dropped.
+#[cfg(windows)]
+pub(crate) struct ComInitGuard {
     initialized: bool,
 }
 
+#[cfg(windows)]
 impl ComInitGuard {
-    unsafe fn new() -> WinResult<Self> {
+    pub(crate) unsafe fn new() -> WinResult<Self> {
         let hr = CoInitializeEx(None, COINIT_MULTITHREADED);
         if hr.is_ok() {
             Ok(Self { initialized: true })
@@ -370,6 +725,7 @@ impl ComInitGuard {
     }
 }
 
+#[cfg(windows)]
 impl Drop for ComInitGuard {
     fn drop(&mut self) {
         if self.initialized {
@@ -380,8 +736,10 @@ impl Drop for ComInitGuard {
     }
 }
 
+#[cfg(windows)]
 struct CoTaskMemGuard(PWSTR);
 
+#[cfg(windows)]
 impl Drop for CoTaskMemGuard {
     fn drop(&mut self) {
         if self.0.is_null() {
@@ -393,6 +751,7 @@ impl Drop for CoTaskMemGuard {
     }
 }
 
+#[cfg(windows)]
 fn enumerate_shell_apps() -> WinResult<Vec<ApplicationInfo>> {
     unsafe {
         let _com_guard = ComInitGuard::new()?;
@@ -450,6 +809,8 @@ fn enumerate_shell_apps() -> WinResult<Vec<ApplicationInfo>> {
                 pinyin_index,
                 working_directory: None,
                 arguments: None,
+                icon: None,
+                folded: None,
             });
         }
 
@@ -457,6 +818,86 @@ fn enumerate_shell_apps() -> WinResult<Vec<ApplicationInfo>> {
     }
 }
 
+#[cfg(not(windows))]
+fn enumerate_shell_apps() -> Result<Vec<ApplicationInfo>, String> {
+    enumerate_desktop_apps()
+}
+
+/// Store (packaged) apps of the current user, launched through their AppUserModelId.
+#[cfg(windows)]
+fn enumerate_packaged_apps() -> WinResult<Vec<ApplicationInfo>> {
+    let _com_guard = unsafe { ComInitGuard::new()? };
+    let manager = PackageManager::new()?;
+
+    let mut applications = Vec::new();
+    // An empty security id means the current user.
+    for package in manager.FindPackagesByUserSecurityId(&HSTRING::new())? {
+        if package.IsFramework().unwrap_or(true) || package.IsResourcePackage().unwrap_or(true) {
+            continue;
+        }
+        let Ok(entries) = package
+            .GetAppListEntriesAsync()
+            .and_then(|operation| operation.get())
+        else {
+            continue;
+        };
+        let publisher = package
+            .PublisherDisplayName()
+            .ok()
+            .map(|value| value.to_string())
+            .filter(|value| !value.trim().is_empty());
+
+        for entry in entries {
+            let Ok(app_user_model_id) = entry.AppUserModelId().map(|id| id.to_string()) else {
+                continue;
+            };
+            let Some(name) = entry
+                .DisplayInfo()
+                .and_then(|info| info.DisplayName())
+                .ok()
+                .map(|name| name.to_string().trim().to_string())
+                .filter(|name| !name.is_empty())
+            else {
+                continue;
+            };
+            if looks_like_uninstaller(&name) {
+                continue;
+            }
+
+            let mut keywords = vec![name.clone(), app_user_model_id.clone()];
+            keywords.sort();
+            keywords.dedup();
+            let pinyin_index = build_pinyin_index([name.as_str()]);
+
+            applications.push(ApplicationInfo {
+                id: format!(
+                    "{PACKAGED_ID_PREFIX}{}",
+                    app_user_model_id.to_ascii_lowercase()
+                ),
+                name,
+                path: format!("{APPS_FOLDER_PREFIX}{app_user_model_id}"),
+                source_path: None,
+                app_type: AppType::Uwp,
+                description: publisher.clone(),
+                keywords,
+                pinyin_index,
+                working_directory: None,
+                arguments: None,
+                icon: None,
+                folded: None,
+            });
+        }
+    }
+
+    Ok(applications)
+}
+
+#[cfg(not(windows))]
+fn enumerate_packaged_apps() -> Result<Vec<ApplicationInfo>, String> {
+    Ok(Vec::new())
+}
+
+#[cfg(windows)]
 fn shell_item_display_name(item: &IShellItem, sigdn: SIGDN) -> Option<String> {
     let display = unsafe { item.GetDisplayName(sigdn).ok()? };
     if display.is_null() {
@@ -472,6 +913,7 @@ fn shell_item_display_name(item: &IShellItem, sigdn: SIGDN) -> Option<String> {
     }
 }
 
+#[cfg(windows)]
 fn is_shell_item_hidden(item: &IShellItem) -> bool {
     match unsafe { item.GetAttributes(SFGAO_HIDDEN) } {
         Ok(attributes) => attributes.contains(SFGAO_HIDDEN),
@@ -479,6 +921,7 @@ fn is_shell_item_hidden(item: &IShellItem) -> bool {
     }
 }
 
+#[cfg(windows)]
 fn infer_shell_app_type(parsing_name: &str) -> AppType {
     let lower = parsing_name.to_ascii_lowercase();
     if lower.starts_with("shell:appsfolder\\") && lower.contains('!') {
@@ -488,7 +931,143 @@ fn infer_shell_app_type(parsing_name: &str) -> AppType {
     }
 }
 
+#[cfg(windows)]
 fn looks_like_uninstaller(value: &str) -> bool {
     let lower = value.to_ascii_lowercase();
     lower.contains("unins") || lower.contains("uninstall")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::app;
+
+    fn url_app(name: &str, source: &str) -> ApplicationInfo {
+        let mut entry = app(&format!("url:{name}"), name, &format!("steam://run/{name}"));
+        entry.source_path = Some(source.to_string());
+        entry
+    }
+
+    #[tokio::test]
+    async fn sources_report_as_they_finish() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let progress = SourceProgress::new(move |step| sender.send(step).unwrap());
+        let found = index_source(&progress, "fixture apps", || {
+            vec![app("shell:code", "Visual Studio Code", r"C:\Code\Code.exe")]
+        });
+        let failed = index_source(&progress, "broken source", || {
+            Err::<Vec<ApplicationInfo>, String>("no access".into())
+        });
+        assert_eq!(found.await.len(), 1);
+        assert!(failed.await.is_empty());
+        let steps: Vec<IndexProgress> = receiver.try_iter().collect();
+        assert_eq!(
+            steps,
+            vec![
+                IndexProgress::Source {
+                    source: "fixture apps",
+                    count: 1,
+                    done: 1,
+                    sources: 2
+                },
+                IndexProgress::Source {
+                    source: "broken source",
+                    count: 0,
+                    done: 2,
+                    sources: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn url_changes_touch_only_their_shortcut() {
+        let mut apps = vec![
+            app("shell:code", "Visual Studio Code", r"C:\Code\Code.exe"),
+            url_app("Portal", r"C:\Start\Portal.url"),
+            url_app("Celeste", r"C:\Start\Celeste.url"),
+        ];
+        let renamed = url_app("Portal 2", r"C:\Start\Portal.url");
+        let changes = vec![
+            (PathBuf::from(r"C:\Start\Portal.url"), Some(renamed)),
+            (PathBuf::from(r"C:\Start\Celeste.url"), None),
+        ];
+        assert!(merge_url_shortcuts(&mut apps, changes, &[]));
+        let names: Vec<&str> = apps.iter().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, vec!["Portal 2", "Visual Studio Code"]);
+
+        let unchanged = vec![(
+            PathBuf::from(r"C:\Start\Portal.url"),
+            Some(url_app("Portal 2", r"C:\Start\Portal.url")),
+        )];
+        assert!(!merge_url_shortcuts(&mut apps, unchanged, &[]));
+    }
+
+    #[test]
+    fn shell_duplicates_of_packaged_apps_are_dropped() {
+        let mut calculator = app(
+            "uwp:microsoft.windowscalculator_8wekyb3d8bbwe!app",
+            "Calculator",
+            r"shell:AppsFolder\Microsoft.WindowsCalculator_8wekyb3d8bbwe!App",
+        );
+        calculator.app_type = AppType::Uwp;
+        let mut apps = vec![calculator];
+        let shell = vec![
+            app(
+                "shell:microsoft.windowscalculator_8wekyb3d8bbwe!app",
+                "Calculator",
+                "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App",
+            ),
+            app("shell:code", "Visual Studio Code", r"C:\Code\Code.exe"),
+        ];
+
+        assert!(merge_shell_apps(&mut apps, shell, &[]));
+        let ids: Vec<&str> = apps.iter().map(|app| app.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "uwp:microsoft.windowscalculator_8wekyb3d8bbwe!app",
+                "shell:code"
+            ]
+        );
+        assert_eq!(apps[0].app_type, AppType::Uwp);
+    }
+
+    #[test]
+    fn shell_merge_keeps_url_entries() {
+        let mut apps = vec![
+            url_app("Portal", r"C:\Start\Portal.url"),
+            app("shell:code", "Visual Studio Code", r"C:\Code\Code.exe"),
+        ];
+        let shell = vec![
+            app("shell:code", "Visual Studio Code", r"C:\Code\Code.exe"),
+            app("shell:notepad", "Notepad", r"C:\Windows\notepad.exe"),
+            app("shell:terminal", "Terminal", r"C:\Apps\wt.exe"),
+        ];
+        let exclusions = vec![r"c:\windows".to_string()];
+        let mut same = apps.clone();
+        assert!(!merge_shell_apps(&mut same, vec![apps[1].clone()], &[]));
+        assert!(merge_shell_apps(&mut apps, shell, &exclusions));
+        let names: Vec<&str> = apps.iter().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, vec!["Portal", "Terminal", "Visual Studio Code"]);
+    }
+
+    #[test]
+    fn start_menu_walk_skips_startup_folders() {
+        let root = std::env::temp_dir().join("egg-indexer-start-menu");
+        let _ = fs::remove_dir_all(&root);
+        let startup = root.join("Startup");
+        let suite = root.join("Vendor").join("Suite");
+        fs::create_dir_all(&startup).unwrap();
+        fs::create_dir_all(&suite).unwrap();
+        let shortcut = "[InternetShortcut]\r\nURL=steam://rungameid/1\r\n";
+        fs::write(suite.join("Game.url"), shortcut).unwrap();
+        fs::write(suite.join("Readme.txt"), shortcut).unwrap();
+        fs::write(startup.join("Autorun.url"), shortcut).unwrap();
+
+        let apps = start_menu_urls_in(&root, &[startup]);
+        let _ = fs::remove_dir_all(&root);
+        let names: Vec<&str> = apps.iter().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, vec!["Game"]);
+    }
+}
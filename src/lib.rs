@@ -0,0 +1,620 @@
+//! The launcher itself; `main.rs` only starts [`run`] on a Tokio runtime. Being a
+//! library also lets `benches/` reach the indexer and search through [`bench`].
+
+mod answers;
+mod bangs;
+mod bookmarks;
+mod builtin_commands;
+mod cache;
+mod cli;
+mod client;
+mod config;
+#[cfg(not(windows))]
+mod desktop_apps;
+mod emoji;
+#[cfg(windows)]
+mod everything;
+mod execute;
+mod file_preview;
+mod games;
+mod git_repos;
+mod history;
+mod hotkey;
+mod icons;
+mod index_diff;
+mod indexer;
+mod locale;
+mod logging;
+mod models;
+mod notes;
+mod page_titles;
+mod path_commands;
+mod plugins;
+mod private_window;
+mod profile;
+mod provider;
+mod query_history;
+mod query_syntax;
+mod ranking;
+mod recent_documents;
+mod recent_projects;
+mod resources;
+mod scheduler;
+mod search_core;
+mod server;
+mod session_cache;
+mod settings_pages;
+mod snippets;
+#[cfg(windows)]
+mod startup;
+mod state;
+mod sync;
+mod system_commands;
+mod telemetry;
+mod terminal_profiles;
+#[cfg(test)]
+mod testing;
+mod text_utils;
+mod theme;
+mod todo;
+mod trigram_index;
+mod tui;
+mod uninstallers;
+#[cfg(not(windows))]
+mod unix_utils;
+mod watcher;
+mod web_apps;
+mod web_suggestions;
+#[cfg(windows)]
+mod windows_search;
+#[cfg(windows)]
+mod windows_utils;
+mod workspaces;
+mod wsl;
+
+/// What `benches/` measures; not a stable API.
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::{
+        bookmarks::BookmarkEntry,
+        cache::{decode_app_index, encode_app_index},
+        config::AppConfig,
+        history::LaunchHistory,
+        indexer::{build_index, finalize_index, start_menu_urls_in, IndexOptions},
+        models::{AppType, ApplicationInfo},
+        search_core::{refine_search, SearchIndexes},
+        state::GenerationToken,
+        trigram_index::TrigramIndex,
+    };
+}
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use log::{debug, info, warn};
+
+use crate::{
+    bookmarks::BookmarkEntry,
+    cli::{Cli, Command, StatsReport},
+    client::CallError,
+    config::{AppConfig, ConfigIssue},
+    execute::{apply_launch_override, execute_action, RetryPolicy},
+    history::{unix_now, LaunchHistory},
+    index_diff::IndexDiff,
+    indexer::{build_index, IndexOptions},
+    models::{ActionVariant, ApplicationInfo, SearchResult},
+    state::{AppState, GenerationToken, PendingAction, RecentEntry},
+    telemetry::RankingTelemetry,
+    tui::run_tui,
+};
+
+/// Loads settings and indexes, runs the TUI and then the chosen action.
+pub async fn run() -> Result<()> {
+    logging::init();
+
+    let cli = Cli::parse();
+    let dry_run_flag = cli.dry_run;
+    if let Some(command) = cli.command {
+        std::process::exit(run_subcommand(command, dry_run_flag).await);
+    }
+
+    println!("egg-cli v0.1.0 starting...");
+
+    let mut config = match AppConfig::try_load_checked() {
+        Ok((config, issues)) => {
+            print_config_issues(&config, &issues);
+            config
+        }
+        Err(err) => {
+            eprintln!("Error: {err}");
+            AppConfig::default()
+        }
+    };
+    logging::set_debug(config.debug_mode);
+    debug!("Loaded configuration");
+    if let Some(folder) = sync::sync_folder(&config) {
+        match sync::sync_with(&folder, &config) {
+            Ok(outcome) => {
+                if !outcome.pulled.is_empty() {
+                    println!(
+                        "Synced {} settings from {}.",
+                        outcome.pulled.len(),
+                        folder.display()
+                    );
+                }
+                config = outcome.config;
+            }
+            Err(err) => eprintln!("Sync skipped: {err}"),
+        }
+    }
+    if dry_run_flag || config.dry_run {
+        println!("Dry run: launching a result only logs what would run.");
+    }
+
+    let state = load_state(&config).await;
+
+    println!(
+        "\nReady! Indexed {} apps and {} bookmarks.",
+        state.app_index.lock().unwrap().len(),
+        state.bookmark_index.lock().unwrap().len()
+    );
+    println!("Starting TUI...\n");
+
+    let _index_watcher = watcher::spawn_index_watcher(state.clone());
+    let _resource_guard = resources::spawn_resource_guard(state.clone());
+    let _reindex_scheduler = scheduler::spawn_reindex_scheduler(state.clone());
+
+    let pending = run_tui(state.clone())?;
+    let batch = pending.len() > 1;
+    for (result, action, variant) in pending {
+        // One failure does not stop the rest of a batch.
+        match launch(&state, &result, action, variant, dry_run_flag) {
+            Err(err) if batch => eprintln!("Error: {}: {err}", result.title),
+            Err(err) => eprintln!("Error: {err}"),
+            Ok(()) => {}
+        }
+    }
+
+    // Push this session's launches and settings changes to the other machines.
+    let config = state.config.lock().unwrap().clone();
+    if let Some(folder) = sync::sync_folder(&config) {
+        if let Err(err) = sync::sync_with(&folder, &config) {
+            warn!("sync on exit failed: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Shared state with the saved history and recent list and fresh indexes, starting
+/// from the cached app index.
+async fn load_state(config: &AppConfig) -> Arc<AppState> {
+    let state = Arc::new(AppState::new());
+    {
+        let mut config_guard = state.config.lock().unwrap();
+        *config_guard = config.clone();
+    }
+
+    {
+        let mut history_guard = state.launch_history.lock().unwrap();
+        *history_guard = Arc::new(LaunchHistory::load());
+    }
+    {
+        let mut recent_guard = state.recent_actions.lock().unwrap();
+        recent_guard.restore(cache::load_recent_actions());
+    }
+    if config.remember_queries {
+        let mut query_guard = state.query_history.lock().unwrap();
+        query_guard.restore(cache::load_query_history());
+    }
+
+    if let Some(cached_apps) = cache::load_app_index() {
+        if !cached_apps.is_empty() {
+            info!("Loaded {} cached applications", cached_apps.len());
+            let mut app_index = state.app_index.lock().unwrap();
+            *app_index = Arc::new(cached_apps);
+        }
+    }
+
+    println!("Building application index...");
+    println!("Loading bookmarks...");
+    let index_options = IndexOptions::from_config(config);
+    let bookmark_browsers = config.bookmark_browsers.clone();
+    let language = config.language;
+    let (apps_task, bookmarks_task) = tokio::join!(
+        tokio::spawn(async move { build_index(index_options).await }),
+        tokio::task::spawn_blocking(move || bookmarks::load_bookmarks(
+            &bookmark_browsers,
+            language
+        )),
+    );
+    let apps = match apps_task {
+        Ok(apps) => apps,
+        Err(err) => {
+            warn!("app index task failed: {err}");
+            Vec::new()
+        }
+    };
+    let bookmarks = match bookmarks_task {
+        Ok(bookmarks) => bookmarks,
+        Err(err) => {
+            warn!("bookmark index task failed: {err}");
+            Vec::new()
+        }
+    };
+    info!("Indexed {} applications", apps.len());
+    info!("Loaded {} bookmarks", bookmarks.len());
+
+    store_app_index(&state, apps);
+    store_bookmark_index(&state, bookmarks);
+    state
+}
+
+/// Replaces the app index with a fresh scan and caches it; an empty scan, as
+/// from a failed task, keeps the old index. Only the cached searches the
+/// difference can affect are dropped. Returns whether the index changed.
+fn store_app_index(state: &AppState, apps: Vec<ApplicationInfo>) -> bool {
+    if apps.is_empty() {
+        return false;
+    }
+    let mut app_index = state.app_index.lock().unwrap();
+    if **app_index == apps {
+        return false;
+    }
+    let _ = cache::save_app_index(&apps);
+    let diff = IndexDiff::between(&app_index, &apps);
+    *app_index = Arc::new(apps);
+    drop(app_index);
+    state.invalidate_changes(&diff);
+    true
+}
+
+/// Replaces the bookmark index when it changed, dropping only the cached
+/// searches the difference can affect. Returns whether it changed.
+fn store_bookmark_index(state: &AppState, bookmarks: Vec<BookmarkEntry>) -> bool {
+    let mut bookmark_index = state.bookmark_index.lock().unwrap();
+    if **bookmark_index == bookmarks {
+        return false;
+    }
+    let diff = IndexDiff::between(&bookmark_index, &bookmarks);
+    *bookmark_index = Arc::new(bookmarks);
+    drop(bookmark_index);
+    state.invalidate_changes(&diff);
+    true
+}
+
+/// Runs the chosen action with the current launch settings.
+fn launch(
+    state: &AppState,
+    result: &SearchResult,
+    mut action: PendingAction,
+    variant: ActionVariant,
+    dry_run_flag: bool,
+) -> Result<(), String> {
+    // Copying a path or opening its folder does not count as using the item.
+    if variant.launches() && action.remembered() {
+        remember_launch(state, result, &action);
+    }
+    // Settings may have been reloaded or edited inside the TUI.
+    let config = state.config.lock().unwrap().clone();
+    apply_launch_override(&mut action, &config.launch_overrides);
+    let dry_run = dry_run_flag || config.dry_run;
+    let retry = RetryPolicy::from_config(&config);
+    let _span = logging::span("launch");
+    execute_action(&action, variant, dry_run, retry, |err, attempt, delay| {
+        eprintln!(
+            "Launch failed: {err}. Retrying in {} ms ({attempt}/{})...",
+            delay.as_millis(),
+            retry.retries
+        );
+    })
+}
+
+/// Adds the launched item to the recent list and, for scored items, the launch history.
+fn remember_launch(state: &AppState, result: &SearchResult, action: &PendingAction) {
+    if let Ok(mut recent_guard) = state.recent_actions.lock() {
+        recent_guard.insert(RecentEntry {
+            // The match hint describes the query that found it, not the recent list.
+            result: SearchResult {
+                match_source: None,
+                ..result.clone()
+            },
+            action: action.clone(),
+        });
+        if let Err(err) = cache::save_recent_actions(&recent_guard) {
+            warn!("failed to save recent actions: {err}");
+        }
+    }
+    if matches!(
+        action,
+        PendingAction::Application(_)
+            | PendingAction::Bookmark(_)
+            | PendingAction::System(_)
+            | PendingAction::Copy(_)
+            | PendingAction::Snippet(_)
+    ) {
+        if let Ok(mut history_guard) = state.launch_history.lock() {
+            let history = Arc::make_mut(&mut history_guard);
+            history.record(&result.id, unix_now());
+            if let Err(err) = history.save() {
+                warn!("failed to save launch history: {err}");
+            }
+        }
+    }
+}
+
+/// The cached app index and the bookmarks, for searching without the TUI. Apps
+/// installed since the last run show up after `egg-cli reindex`.
+fn cached_indexes(config: &AppConfig) -> (Vec<ApplicationInfo>, Vec<BookmarkEntry>) {
+    let apps = cache::load_app_index().unwrap_or_default();
+    let bookmarks = bookmarks::load_bookmarks(&config.bookmark_browsers, config.language);
+    (apps, bookmarks)
+}
+
+/// One line per problem egg worked around while loading `settings.json`.
+fn print_config_issues(config: &AppConfig, issues: &[ConfigIssue]) {
+    let text = locale::messages(config.language);
+    for issue in issues {
+        println!("settings.json: {}", issue.describe(text));
+    }
+}
+
+/// Runs a one-shot subcommand and returns its exit code.
+async fn run_subcommand(command: Command, dry_run_flag: bool) -> i32 {
+    match command {
+        Command::Stats {
+            report: StatsReport::Ranking { purge: false },
+        } => {
+            if !AppConfig::load().ranking_telemetry {
+                println!("Ranking telemetry is off. Set \"ranking_telemetry\": true in settings.json to collect it.");
+            }
+            print!("{}", RankingTelemetry::load().report());
+            0
+        }
+        Command::Stats {
+            report: StatsReport::Ranking { purge: true },
+        } => match RankingTelemetry::purge() {
+            Ok(true) => {
+                println!("Ranking telemetry deleted.");
+                0
+            }
+            Ok(false) => {
+                println!("No ranking telemetry to delete.");
+                0
+            }
+            Err(err) => {
+                eprintln!("Error: {err}");
+                1
+            }
+        },
+        Command::Sync => {
+            let config = AppConfig::load();
+            let Some(folder) = sync::sync_folder(&config) else {
+                println!("Sync is off. Set \"sync_folder\" in settings.json to a folder shared between your machines.");
+                return 0;
+            };
+            match sync::sync_with(&folder, &config) {
+                Ok(outcome) if outcome.pulled.is_empty() => {
+                    println!(
+                        "Synced with {}; nothing new from other machines.",
+                        folder.display()
+                    );
+                    0
+                }
+                Ok(outcome) => {
+                    println!(
+                        "Synced with {}; updated {}.",
+                        folder.display(),
+                        outcome.pulled.join(", ")
+                    );
+                    0
+                }
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    1
+                }
+            }
+        }
+        Command::Query { text, json } => {
+            let config = AppConfig::load();
+            let query = text.join(" ");
+            let served = client::connect(config.server_port).and_then(|mut client| {
+                client
+                    .search(&query)
+                    .map_err(|err| debug!("server search failed: {err}"))
+                    .ok()
+            });
+            let results = served.unwrap_or_else(|| {
+                let (apps, bookmarks) = cached_indexes(&config);
+                let (results, _, _) = search_core::refine_search(
+                    query,
+                    None,
+                    None,
+                    search_core::SearchIndexes::new(&apps, &bookmarks),
+                    &LaunchHistory::load(),
+                    &config,
+                    &GenerationToken::detached(),
+                );
+                results
+            });
+            if json {
+                match serde_json::to_string_pretty(&results) {
+                    Ok(json) => println!("{json}"),
+                    Err(err) => {
+                        eprintln!("Error: {err}");
+                        return 1;
+                    }
+                }
+            } else {
+                for result in &results {
+                    println!("{}\t{}\t{}", result.id, result.title, result.subtitle);
+                }
+            }
+            0
+        }
+        Command::CheckSettings { json } => match AppConfig::try_load_checked() {
+            Ok((_, issues)) if json => {
+                match serde_json::to_string_pretty(&issues) {
+                    Ok(json) => println!("{json}"),
+                    Err(err) => {
+                        eprintln!("Error: {err}");
+                        return 1;
+                    }
+                }
+                i32::from(!issues.is_empty())
+            }
+            Ok((_, issues)) if issues.is_empty() => {
+                println!("settings.json is fine.");
+                0
+            }
+            Ok((config, issues)) => {
+                print_config_issues(&config, &issues);
+                1
+            }
+            Err(err) => {
+                eprintln!("Error: {err}");
+                1
+            }
+        },
+        Command::Reindex => {
+            let config = AppConfig::load();
+            let started = std::time::Instant::now();
+            let apps = build_index(IndexOptions::from_config(&config)).await;
+            match cache::save_app_index(&apps) {
+                Ok(()) => {
+                    println!(
+                        "Indexed {} applications in {:.1}s.",
+                        apps.len(),
+                        started.elapsed().as_secs_f64()
+                    );
+                    0
+                }
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    1
+                }
+            }
+        }
+        Command::Run { id } => {
+            let config = AppConfig::load();
+            // A dry run prints what it would run, which a server would print to its own console.
+            let client = (!dry_run_flag)
+                .then(|| client::connect(config.server_port))
+                .flatten();
+            if let Some(mut client) = client {
+                match client.execute(&id) {
+                    Ok(()) => return 0,
+                    // This process may run commands even when the server's clients may not.
+                    Err(CallError::Rpc {
+                        code: server::COMMANDS_REFUSED,
+                        ..
+                    }) => {}
+                    Err(err) => {
+                        eprintln!("Error: {err}");
+                        return 1;
+                    }
+                }
+            }
+            let state = AppState::new();
+            let (apps, bookmarks) = cached_indexes(&config);
+            let recent = cache::load_recent_actions();
+            // Results without a stable id, such as web searches, can still be
+            // run again from the recent list.
+            let found = search_core::result_for_id(&id, &apps, &bookmarks, &config).or_else(|| {
+                recent
+                    .iter()
+                    .find(|entry| entry.result.id == id)
+                    .map(|entry| (entry.result.clone(), entry.action.clone()))
+            });
+            state.recent_actions.lock().unwrap().restore(recent);
+            *state.launch_history.lock().unwrap() = Arc::new(LaunchHistory::load());
+            *state.config.lock().unwrap() = config;
+            let Some((result, action)) = found else {
+                eprintln!("No result with id {id}. Run `egg-cli query <text>` to list ids.");
+                return 1;
+            };
+            match launch(&state, &result, action, ActionVariant::Open, dry_run_flag) {
+                Ok(()) => 0,
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    1
+                }
+            }
+        }
+        Command::Serve => {
+            let config = AppConfig::load();
+            let state = load_state(&config).await;
+            let _index_watcher = watcher::spawn_index_watcher(state.clone());
+            let _reindex_scheduler = scheduler::spawn_reindex_scheduler(state.clone());
+            let runtime = tokio::runtime::Handle::current();
+            let port = config.server_port;
+            let served = tokio::task::spawn_blocking(move || {
+                server::serve(state, port, dry_run_flag, runtime)
+            })
+            .await
+            .map_err(|err| err.to_string())
+            .and_then(|served| served);
+            match served {
+                Ok(()) => 0,
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    1
+                }
+            }
+        }
+        Command::ExportProfile { file } => match profile::export(&file) {
+            Ok(summary) => {
+                println!(
+                    "Exported settings, {} launch history items and {} recent items to {}.",
+                    summary.launches,
+                    summary.recent,
+                    file.display()
+                );
+                0
+            }
+            Err(err) => {
+                eprintln!("Error: {err}");
+                1
+            }
+        },
+        Command::ImportProfile { file } => match profile::import(&file) {
+            Ok(summary) => {
+                println!(
+                    "Imported settings, {} launch history items and {} recent items from {}.",
+                    summary.launches,
+                    summary.recent,
+                    file.display()
+                );
+                0
+            }
+            Err(err) => {
+                eprintln!("Error: {err}");
+                1
+            }
+        },
+        #[cfg(windows)]
+        Command::Startup { action: None } => match startup::apply(&AppConfig::load().startup) {
+            Ok(()) => {
+                println!("{}", startup::status().summary());
+                0
+            }
+            Err(err) => {
+                eprintln!("Error: {err}");
+                1
+            }
+        },
+        #[cfg(windows)]
+        Command::Startup {
+            action: Some(cli::StartupAction::Status),
+        } => {
+            println!("{}", startup::status().summary());
+            0
+        }
+        #[cfg(not(windows))]
+        Command::Startup { .. } => {
+            eprintln!("Starting at sign-in is only supported on Windows.");
+            1
+        }
+    }
+}
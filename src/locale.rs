@@ -0,0 +1,748 @@
+//! User-facing text in each supported language, picked with the `language` setting.
+//! Messages are templates whose `{}` placeholders [`fill`] replaces in order.
+//! Names of settings, operators and keys stay untranslated so they match what is typed.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    #[default]
+    En,
+    Zh,
+}
+
+/// Every translated message, one field per message.
+pub struct Messages {
+    // Search results.
+    pub open_url: &'static str,
+    pub search_with: &'static str,
+    pub web_search: &'static str,
+    pub did_you_mean: &'static str,
+    pub web_suggestion: &'static str,
+    pub suggestion_hint: &'static str,
+    pub typo_match: &'static str,
+    pub search_files: &'static str,
+    pub run_command: &'static str,
+    pub command_alias: &'static str,
+    pub open_workspace: &'static str,
+    pub workspace_members: &'static str,
+    pub workspace_missing: &'static str,
+    pub save_note: &'static str,
+    pub note_file: &'static str,
+    pub add_task: &'static str,
+    pub task_added: &'static str,
+    pub keep_window_open: &'static str,
+    pub bookmarks: &'static str,
+    pub open_folder: &'static str,
+    pub open_file: &'static str,
+    pub reveal_path: &'static str,
+    // Bookmark root folders.
+    pub bookmark_bar: &'static str,
+    pub other_bookmarks: &'static str,
+    pub synced_bookmarks: &'static str,
+    // Query diagnostics and `?` help.
+    pub unknown_prefix: &'static str,
+    pub unclosed_quote: &'static str,
+    pub file_search_disabled: &'static str,
+    pub missing_text: &'static str,
+    pub help_run_in_shell: &'static str,
+    pub help_run_as_admin: &'static str,
+    pub help_wildcards: &'static str,
+    pub help_by_extension: &'static str,
+    pub help_in_folder: &'static str,
+    pub help_by_kind: &'static str,
+    pub help_apps_only: &'static str,
+    pub help_bookmarks_only: &'static str,
+    pub help_web_only: &'static str,
+    pub help_windows_only: &'static str,
+    pub help_processes_only: &'static str,
+    pub help_files: &'static str,
+    pub help_emoji: &'static str,
+    pub help_shell_command: &'static str,
+    pub help_wsl_command: &'static str,
+    pub help_egg_commands: &'static str,
+    pub help_bang: &'static str,
+    pub help_plugin: &'static str,
+    pub help_exact_phrase: &'static str,
+    // Result kinds, shown before each subtitle.
+    pub kind_app: &'static str,
+    pub kind_uwp: &'static str,
+    pub kind_pwa: &'static str,
+    pub kind_uninstall: &'static str,
+    pub kind_repo: &'static str,
+    pub kind_bookmark: &'static str,
+    pub kind_url: &'static str,
+    pub kind_search: &'static str,
+    pub kind_file: &'static str,
+    pub kind_command: &'static str,
+    pub kind_system: &'static str,
+    pub kind_settings: &'static str,
+    pub kind_workspace: &'static str,
+    pub kind_snippet: &'static str,
+    pub kind_note: &'static str,
+    pub kind_todo: &'static str,
+    pub kind_suggest: &'static str,
+    pub kind_help: &'static str,
+    pub kind_plugin: &'static str,
+    pub kind_emoji: &'static str,
+    pub kind_window: &'static str,
+    pub kind_process: &'static str,
+    pub kind_other: &'static str,
+    // Actions menu.
+    pub action_open: &'static str,
+    pub action_run_as_admin: &'static str,
+    pub action_open_containing_folder: &'static str,
+    pub action_copy_path: &'static str,
+    pub action_copy_url: &'static str,
+    pub action_open_private: &'static str,
+    pub action_complete_task: &'static str,
+    pub action_open_in_terminal: &'static str,
+    pub action_open_remote: &'static str,
+    pub action_edit_launch_options: &'static str,
+    pub action_edit_alias: &'static str,
+    // Screen layout.
+    pub header_search: &'static str,
+    pub provider_apps: &'static str,
+    pub provider_bookmarks: &'static str,
+    pub provider_files: &'static str,
+    pub mode_web_search: &'static str,
+    pub mode_windows: &'static str,
+    pub mode_processes: &'static str,
+    pub header_home: &'static str,
+    pub header_results: &'static str,
+    pub provider_on: &'static str,
+    pub provider_off: &'static str,
+    pub placeholder: &'static str,
+    pub search_title: &'static str,
+    pub results_title: &'static str,
+    pub searching: &'static str,
+    pub no_recent_items: &'static str,
+    pub no_results: &'static str,
+    pub no_matches: &'static str,
+    pub apps_pane: &'static str,
+    pub links_pane: &'static str,
+    pub recent_section: &'static str,
+    pub frequent_section: &'static str,
+    pub applications_group: &'static str,
+    pub bookmarks_group: &'static str,
+    pub files_group: &'static str,
+    pub web_group: &'static str,
+    pub other_group: &'static str,
+    // Footer hints.
+    pub any_key: &'static str,
+    pub hint_restore_session: &'static str,
+    pub hint_dismiss: &'static str,
+    pub hint_next: &'static str,
+    pub hint_back: &'static str,
+    pub hint_cancel: &'static str,
+    pub hint_save: &'static str,
+    pub hint_discard: &'static str,
+    pub hint_run: &'static str,
+    pub hint_run_as_admin: &'static str,
+    pub hint_quit: &'static str,
+    pub hint_move: &'static str,
+    pub hint_slot: &'static str,
+    pub hint_reset_slot: &'static str,
+    pub hint_pane: &'static str,
+    pub hint_actions: &'static str,
+    pub hint_delete: &'static str,
+    pub hint_settings: &'static str,
+    pub hint_theme: &'static str,
+    pub hint_undo: &'static str,
+    pub hint_copy_answer: &'static str,
+    pub hint_complete: &'static str,
+    pub hint_run_marked: &'static str,
+    pub hint_clear_marks: &'static str,
+    // Status messages.
+    pub session_restored: &'static str,
+    pub provider_toggled: &'static str,
+    pub file_search_not_set_up: &'static str,
+    pub action_unavailable: &'static str,
+    pub no_other_actions: &'static str,
+    pub showing_full_paths: &'static str,
+    pub showing_compact_paths: &'static str,
+    pub settings_not_saved: &'static str,
+    pub nothing_to_pin: &'static str,
+    pub cannot_pin: &'static str,
+    pub pinned: &'static str,
+    pub unpinned: &'static str,
+    pub cannot_mark: &'static str,
+    pub marked_count: &'static str,
+    pub marks_cleared: &'static str,
+    pub nothing_to_blacklist: &'static str,
+    pub selection_unresolved: &'static str,
+    pub cannot_blacklist: &'static str,
+    pub app_without_path: &'static str,
+    pub already_blacklisted: &'static str,
+    pub blacklisted: &'static str,
+    pub nothing_to_hide: &'static str,
+    pub cannot_hide: &'static str,
+    pub hidden: &'static str,
+    pub nothing_to_undo: &'static str,
+    pub undone: &'static str,
+    pub unblacklisted: &'static str,
+    pub shown_again: &'static str,
+    pub pinned_again: &'static str,
+    pub alias_restored: &'static str,
+    pub launch_options_restored: &'static str,
+    pub file_search_unavailable: &'static str,
+    pub plugin_unavailable: &'static str,
+    pub window_list_unavailable: &'static str,
+    pub process_list_unavailable: &'static str,
+    pub process_subtitle: &'static str,
+    pub process_elevated_subtitle: &'static str,
+    pub confirm_end_process: &'static str,
+    pub reindexing: &'static str,
+    pub reindex_progress: &'static str,
+    pub reindex_finished: &'static str,
+    pub settings_reloaded: &'static str,
+    pub settings_not_reloaded: &'static str,
+    pub settings_reloaded_with_issues: &'static str,
+    pub settings_from_newer_version: &'static str,
+    pub setting_renamed: &'static str,
+    pub setting_removed: &'static str,
+    pub setting_unknown: &'static str,
+    pub setting_wrong_type: &'static str,
+    pub setting_out_of_range: &'static str,
+    pub setting_invalid_hotkey: &'static str,
+    pub settings_opened: &'static str,
+    pub ranking_profile: &'static str,
+    // Launch options and alias prompts.
+    pub launch_options_title: &'static str,
+    pub arguments_label: &'static str,
+    pub start_folder_label: &'static str,
+    pub arguments_keys: &'static str,
+    pub start_folder_keys: &'static str,
+    pub launch_options_unchanged: &'static str,
+    pub launch_options_saved: &'static str,
+    pub alias_title: &'static str,
+    pub alias_label: &'static str,
+    pub alias_keys: &'static str,
+    pub alias_unchanged: &'static str,
+    pub alias_removed: &'static str,
+    pub alias_saved: &'static str,
+    // Theme editor.
+    pub theme_title: &'static str,
+    pub theme_keys: &'static str,
+    pub theme_preview_title: &'static str,
+    pub sample_result: &'static str,
+    pub selected_result: &'static str,
+    pub not_a_color: &'static str,
+    pub theme_discarded: &'static str,
+    pub theme_saved: &'static str,
+    // File preview.
+    pub preview_loading: &'static str,
+    pub preview_image: &'static str,
+    pub preview_more_entries: &'static str,
+    pub preview_empty_folder: &'static str,
+    pub preview_unsupported: &'static str,
+    pub preview_failed: &'static str,
+    // Instant answers.
+    pub answer_title: &'static str,
+    pub answer_copied: &'static str,
+    pub answer_not_copied: &'static str,
+    pub time_now: &'static str,
+    pub local_time: &'static str,
+    pub rates_source: &'static str,
+    pub rates_loading: &'static str,
+    pub rates_disabled: &'static str,
+    pub rates_unavailable: &'static str,
+}
+
+const EN: Messages = Messages {
+    open_url: "Open URL: {}",
+    search_with: "Search {} for: {}",
+    web_search: "{} search",
+    did_you_mean: "Did you mean: {}",
+    web_suggestion: "{} suggestion",
+    suggestion_hint: "Spelling suggestion · Enter replaces the query",
+    typo_match: "did you mean?",
+    search_files: "Search files for: {}",
+    run_command: "Run command: {}",
+    command_alias: "Alias {} · {}",
+    open_workspace: "Open workspace {}",
+    workspace_members: "Workspace · {}",
+    workspace_missing: "not found: {}",
+    save_note: "Save note: {}",
+    note_file: "Appends to {}",
+    add_task: "Add task: {}",
+    task_added: "added {}",
+    keep_window_open: "{} · keep window open",
+    bookmarks: "Bookmarks",
+    open_folder: "Open folder: {}",
+    open_file: "Open file: {}",
+    reveal_path: "Show in folder: {}",
+    bookmark_bar: "Bookmarks bar",
+    other_bookmarks: "Other bookmarks",
+    synced_bookmarks: "Mobile bookmarks",
+    unknown_prefix: "unknown prefix '{}'",
+    unclosed_quote: "unclosed quote",
+    file_search_disabled: "file: needs enable_everything or enable_windows_search",
+    missing_text: "type something after {}",
+    help_run_in_shell: "runs the command in {}",
+    help_run_as_admin: "runs the command as administrator",
+    help_wildcards: "Everything: wildcards",
+    help_by_extension: "Everything: by extension",
+    help_in_folder: "Everything: inside a folder",
+    help_by_kind: "Windows Search: by kind (document, email, picture...)",
+    help_apps_only: "apps and system actions only",
+    help_bookmarks_only: "bookmarks only",
+    help_web_only: "web search only",
+    help_windows_only: "switch to an open window",
+    help_processes_only: "end a running process",
+    help_files: "files through Everything or Windows Search",
+    help_emoji: "emoji and symbols; Enter copies one",
+    help_shell_command: "runs a shell command",
+    help_wsl_command: "runs a command in the default WSL distribution",
+    help_egg_commands: "egg's own commands",
+    help_bang: "searches a site directly, e.g. !w for Wikipedia",
+    help_plugin: "plugin: {}",
+    help_exact_phrase: "keeps words together",
+    kind_app: "app",
+    kind_uwp: "uwp",
+    kind_pwa: "web app",
+    kind_uninstall: "uninstall",
+    kind_repo: "repo",
+    kind_bookmark: "bookmark",
+    kind_url: "url",
+    kind_search: "search",
+    kind_file: "file",
+    kind_command: "command",
+    kind_system: "system",
+    kind_settings: "settings",
+    kind_workspace: "workspace",
+    kind_snippet: "snippet",
+    kind_note: "note",
+    kind_todo: "todo",
+    kind_suggest: "did you mean",
+    kind_help: "syntax",
+    kind_plugin: "plugin",
+    kind_emoji: "emoji",
+    kind_window: "window",
+    kind_process: "process",
+    kind_other: "Other",
+    action_open: "Open",
+    action_run_as_admin: "Run as administrator",
+    action_open_containing_folder: "Open containing folder",
+    action_copy_path: "Copy path",
+    action_copy_url: "Copy URL",
+    action_open_private: "Open in private window",
+    action_complete_task: "Mark done",
+    action_open_in_terminal: "Open in terminal",
+    action_open_remote: "Open remote in browser",
+    action_edit_launch_options: "Edit arguments and start folder",
+    action_edit_alias: "Set alias",
+    header_search: "search",
+    provider_apps: "apps",
+    provider_bookmarks: "bookmarks",
+    provider_files: "files",
+    mode_web_search: "web search",
+    mode_windows: "windows",
+    mode_processes: "processes",
+    header_home: "home",
+    header_results: "results",
+    provider_on: "on",
+    provider_off: "off",
+    placeholder: "Type to search... (? for help)",
+    search_title: "Search",
+    results_title: "Results",
+    searching: "searching...",
+    no_recent_items: "No recent items. Type to search.",
+    no_results: "No results. Try another query.",
+    no_matches: "No matches.",
+    apps_pane: "Apps",
+    links_pane: "Bookmarks & Files",
+    recent_section: "Recent",
+    frequent_section: "Frequent apps",
+    applications_group: "Applications",
+    bookmarks_group: "Bookmarks",
+    files_group: "Files",
+    web_group: "Web",
+    other_group: "Other",
+    any_key: "any key",
+    hint_restore_session: "restore the session that crashed",
+    hint_dismiss: "dismiss",
+    hint_next: "next",
+    hint_back: "back",
+    hint_cancel: "cancel",
+    hint_save: "save",
+    hint_discard: "discard",
+    hint_run: "run",
+    hint_run_as_admin: "run as admin",
+    hint_quit: "quit",
+    hint_move: "move",
+    hint_slot: "slot",
+    hint_reset_slot: "reset slot",
+    hint_pane: "pane",
+    hint_actions: "actions",
+    hint_delete: "delete",
+    hint_settings: "settings",
+    hint_theme: "theme",
+    hint_undo: "undo",
+    hint_copy_answer: "copy answer",
+    hint_complete: "complete",
+    hint_run_marked: "run {} marked",
+    hint_clear_marks: "clear marks",
+    session_restored: "Previous session restored.",
+    provider_toggled: "Searching {}: {} for this session.",
+    file_search_not_set_up:
+        "File search is not set up; enable Everything or Windows Search in settings.json.",
+    action_unavailable: "{} is not available for this result.",
+    no_other_actions: "No other actions for this result.",
+    showing_full_paths: "Showing full paths.",
+    showing_compact_paths: "Showing compact paths.",
+    settings_not_saved: "Failed to save settings.",
+    nothing_to_pin: "No selection to pin.",
+    cannot_pin: "Only apps and bookmarks can be pinned.",
+    pinned: "Pinned {}.",
+    unpinned: "Unpinned {}.",
+    cannot_mark: "Only apps, bookmarks, links and files can be launched together.",
+    marked_count: "{} marked; Enter launches them all.",
+    marks_cleared: "Marks cleared.",
+    nothing_to_blacklist: "No selection to blacklist.",
+    selection_unresolved: "Unable to resolve selection.",
+    cannot_blacklist: "Only apps can be blacklisted.",
+    app_without_path: "Selected app has no path.",
+    already_blacklisted: "Already in blacklist: {}",
+    blacklisted: "Added to blacklist: {} (Ctrl+Z to undo)",
+    nothing_to_hide: "No selection to hide.",
+    cannot_hide: "Only apps and bookmarks can be hidden.",
+    hidden: "Hidden: {} (Ctrl+Z to undo)",
+    nothing_to_undo: "Nothing to undo.",
+    undone: "Undone: {}.",
+    unblacklisted: "{} is no longer blacklisted",
+    shown_again: "{} is shown again",
+    pinned_again: "{} is pinned again",
+    alias_restored: "aliases before changing {} restored",
+    launch_options_restored: "launch options for {} restored",
+    file_search_unavailable: "File search unavailable: {}",
+    plugin_unavailable: "Plugin unavailable: {}",
+    window_list_unavailable: "Cannot list windows: {}",
+    process_list_unavailable: "Cannot list processes: {}",
+    process_subtitle: "PID {}",
+    process_elevated_subtitle: "PID {} · administrator",
+    confirm_end_process: "{} runs as administrator. Press Enter again to end it.",
+    reindexing: "Reindexing apps and bookmarks...",
+    reindex_progress: "Reindexing {}/{}: {} ({})",
+    reindex_finished: "Reindexed {} apps in {}s.",
+    settings_reloaded: "Settings reloaded.",
+    settings_not_reloaded: "Settings not reloaded: {}",
+    settings_reloaded_with_issues: "Settings reloaded: {}",
+    settings_from_newer_version:
+        "settings.json is from a newer egg (version {}); settings this one lacks are ignored.",
+    setting_renamed: "{} is now called {}.",
+    setting_removed: "{} is no longer used.",
+    setting_unknown: "{} is not a setting.",
+    setting_wrong_type: "{} could not be read ({}); using the default.",
+    setting_out_of_range: "{} must be from {} to {}, not {}; using the default.",
+    setting_invalid_hotkey: "{}: \"{}\" is not a key combination like Ctrl+B; using the default.",
+    settings_opened: "Opened settings.json.",
+    ranking_profile: "Ranking profile: {}.",
+    launch_options_title: "Launch options · {}",
+    arguments_label: "Arguments",
+    start_folder_label: "Start folder",
+    arguments_keys: "Enter: next  Ctrl+U: clear  Esc: cancel",
+    start_folder_keys: "Enter: save  Shift+Tab: back  Ctrl+U: clear  Esc: cancel",
+    launch_options_unchanged: "Launch options unchanged.",
+    launch_options_saved: "Launch options saved for {}.",
+    alias_title: "Alias · {}",
+    alias_label: "Alias",
+    alias_keys: "Enter: save  Ctrl+U: clear  Esc: cancel",
+    alias_unchanged: "Alias unchanged.",
+    alias_removed: "Alias removed from {}.",
+    alias_saved: "Type {} to open {}.",
+    theme_title: "Theme",
+    theme_keys: "Type #rrggbb or r,g,b. Ctrl+R resets the slot, Ctrl+T switches the preset.",
+    theme_preview_title: "Preview",
+    sample_result: "Sample result",
+    selected_result: "Selected result",
+    not_a_color: "Not a color: {}",
+    theme_discarded: "Theme changes discarded.",
+    theme_saved: "Theme saved.",
+    preview_loading: "Loading...",
+    preview_image: "{} image",
+    preview_more_entries: "... and {} more",
+    preview_empty_folder: "Empty folder",
+    preview_unsupported: "No preview for this file ({})",
+    preview_failed: "Cannot preview: {}",
+    answer_title: "Answer",
+    answer_copied: "Copied {}.",
+    answer_not_copied: "Could not copy: {}",
+    time_now: "Now",
+    local_time: "local time",
+    rates_source: "Exchange rates from {}, updated {}",
+    rates_loading: "Fetching exchange rates...",
+    rates_disabled: "Set \"fetch_exchange_rates\": true to convert currencies.",
+    rates_unavailable: "Exchange rates are unavailable right now.",
+};
+
+const ZH: Messages = Messages {
+    open_url: "打开网址: {}",
+    search_with: "在 {} 上搜索: {}",
+    web_search: "{} 搜索",
+    did_you_mean: "你是不是要找: {}",
+    web_suggestion: "{} 搜索建议",
+    suggestion_hint: "拼写建议 · 回车替换查询",
+    typo_match: "你是不是要找?",
+    search_files: "在文件中搜索: {}",
+    run_command: "运行命令: {}",
+    command_alias: "别名 {} · {}",
+    open_workspace: "打开工作区 {}",
+    workspace_members: "工作区 · {}",
+    workspace_missing: "未找到: {}",
+    save_note: "保存笔记: {}",
+    note_file: "追加到 {}",
+    add_task: "添加任务: {}",
+    task_added: "添加于 {}",
+    keep_window_open: "{} · 保持窗口",
+    bookmarks: "收藏夹",
+    open_folder: "打开文件夹: {}",
+    open_file: "打开文件: {}",
+    reveal_path: "在文件夹中显示: {}",
+    bookmark_bar: "书签栏",
+    other_bookmarks: "其他书签",
+    synced_bookmarks: "已同步",
+    unknown_prefix: "未知前缀 '{}'",
+    unclosed_quote: "引号没有闭合",
+    file_search_disabled: "file: 需要开启 enable_everything 或 enable_windows_search",
+    missing_text: "请在 {} 后输入内容",
+    help_run_in_shell: "在 {} 中运行命令",
+    help_run_as_admin: "以管理员身份运行命令",
+    help_wildcards: "Everything: 通配符",
+    help_by_extension: "Everything: 按扩展名",
+    help_in_folder: "Everything: 在文件夹内",
+    help_by_kind: "Windows Search: 按类型 (document、email、picture...)",
+    help_apps_only: "只搜索应用和系统操作",
+    help_bookmarks_only: "只搜索收藏夹",
+    help_web_only: "只进行网页搜索",
+    help_windows_only: "切换到已打开的窗口",
+    help_processes_only: "结束正在运行的进程",
+    help_files: "通过 Everything 或 Windows Search 搜索文件",
+    help_emoji: "表情和符号，回车复制",
+    help_shell_command: "运行 shell 命令",
+    help_wsl_command: "在默认的 WSL 发行版中运行命令",
+    help_egg_commands: "egg 自己的命令",
+    help_bang: "直接搜索某个网站，如 !w 搜索维基百科",
+    help_plugin: "插件: {}",
+    help_exact_phrase: "词语连在一起匹配",
+    kind_app: "应用",
+    kind_uwp: "商店应用",
+    kind_pwa: "网页应用",
+    kind_uninstall: "卸载",
+    kind_repo: "仓库",
+    kind_bookmark: "收藏夹",
+    kind_url: "网址",
+    kind_search: "搜索",
+    kind_file: "文件",
+    kind_command: "命令",
+    kind_system: "系统",
+    kind_settings: "设置页",
+    kind_workspace: "工作区",
+    kind_snippet: "片段",
+    kind_note: "笔记",
+    kind_todo: "待办",
+    kind_suggest: "建议",
+    kind_help: "语法",
+    kind_plugin: "插件",
+    kind_emoji: "表情",
+    kind_window: "窗口",
+    kind_process: "进程",
+    kind_other: "其他",
+    action_open: "打开",
+    action_run_as_admin: "以管理员身份运行",
+    action_open_containing_folder: "打开所在文件夹",
+    action_copy_path: "复制路径",
+    action_copy_url: "复制网址",
+    action_open_private: "在无痕窗口中打开",
+    action_complete_task: "标记为完成",
+    action_open_in_terminal: "在终端中打开",
+    action_open_remote: "在浏览器中打开远程仓库",
+    action_edit_launch_options: "修改启动参数和起始文件夹",
+    action_edit_alias: "设置别名",
+    header_search: "搜索",
+    provider_apps: "应用",
+    provider_bookmarks: "收藏夹",
+    provider_files: "文件",
+    mode_web_search: "网页搜索",
+    mode_windows: "窗口",
+    mode_processes: "进程",
+    header_home: "主页",
+    header_results: "结果",
+    provider_on: "开",
+    provider_off: "关",
+    placeholder: "输入以搜索... (? 查看帮助)",
+    search_title: "搜索",
+    results_title: "结果",
+    searching: "搜索中...",
+    no_recent_items: "没有最近使用的项目，输入以搜索。",
+    no_results: "没有结果，换个关键词试试。",
+    no_matches: "没有匹配项。",
+    apps_pane: "应用",
+    links_pane: "收藏夹和文件",
+    recent_section: "最近使用",
+    frequent_section: "常用应用",
+    applications_group: "应用",
+    bookmarks_group: "书签",
+    files_group: "文件",
+    web_group: "网页",
+    other_group: "其他",
+    any_key: "任意键",
+    hint_restore_session: "恢复崩溃前的会话",
+    hint_dismiss: "忽略",
+    hint_next: "下一步",
+    hint_back: "返回",
+    hint_cancel: "取消",
+    hint_save: "保存",
+    hint_discard: "放弃",
+    hint_run: "运行",
+    hint_run_as_admin: "以管理员身份运行",
+    hint_quit: "退出",
+    hint_move: "移动",
+    hint_slot: "颜色",
+    hint_reset_slot: "重置颜色",
+    hint_pane: "切换窗格",
+    hint_actions: "操作",
+    hint_delete: "删除",
+    hint_settings: "配置",
+    hint_theme: "主题",
+    hint_undo: "撤销",
+    hint_copy_answer: "复制答案",
+    hint_complete: "补全",
+    hint_run_marked: "运行 {} 个标记项",
+    hint_clear_marks: "清除标记",
+    session_restored: "已恢复上次的会话。",
+    provider_toggled: "本次运行中搜索 {}: {}。",
+    file_search_not_set_up:
+        "文件搜索尚未设置，请在 settings.json 中开启 Everything 或 Windows Search。",
+    action_unavailable: "此结果不支持“{}”。",
+    no_other_actions: "此结果没有其他操作。",
+    showing_full_paths: "显示完整路径。",
+    showing_compact_paths: "显示简短路径。",
+    settings_not_saved: "无法保存配置。",
+    nothing_to_pin: "没有可置顶的选中项。",
+    cannot_pin: "只能置顶应用和收藏夹。",
+    pinned: "已置顶 {}。",
+    unpinned: "已取消置顶 {}。",
+    cannot_mark: "只能一起启动应用、收藏夹、链接和文件。",
+    marked_count: "已标记 {} 项，按 Enter 全部启动。",
+    marks_cleared: "已清除标记。",
+    nothing_to_blacklist: "没有可拉黑的选中项。",
+    selection_unresolved: "无法识别选中项。",
+    cannot_blacklist: "只能拉黑应用。",
+    app_without_path: "选中的应用没有路径。",
+    already_blacklisted: "已在黑名单中: {}",
+    blacklisted: "已加入黑名单: {} (Ctrl+Z 撤销)",
+    nothing_to_hide: "没有可隐藏的选中项。",
+    cannot_hide: "只能隐藏应用和收藏夹。",
+    hidden: "已隐藏: {} (Ctrl+Z 撤销)",
+    nothing_to_undo: "没有可撤销的操作。",
+    undone: "已撤销: {}。",
+    unblacklisted: "{} 已移出黑名单",
+    shown_again: "{} 已重新显示",
+    pinned_again: "{} 已重新置顶",
+    alias_restored: "修改 {} 前的别名已恢复",
+    launch_options_restored: "{} 的启动选项已恢复",
+    file_search_unavailable: "文件搜索不可用: {}",
+    plugin_unavailable: "插件不可用: {}",
+    window_list_unavailable: "无法列出窗口: {}",
+    process_list_unavailable: "无法列出进程: {}",
+    process_subtitle: "PID {}",
+    process_elevated_subtitle: "PID {} · 管理员",
+    confirm_end_process: "{} 以管理员身份运行，再按一次回车结束它。",
+    reindexing: "正在重建应用和收藏夹索引...",
+    reindex_progress: "正在重建索引 {}/{}: {} ({})",
+    reindex_finished: "索引已重建，共 {} 个应用，用时 {} 秒。",
+    settings_reloaded: "已重新加载配置。",
+    settings_not_reloaded: "配置没有重新加载: {}",
+    settings_reloaded_with_issues: "已重新加载配置: {}",
+    settings_from_newer_version:
+        "settings.json 来自更新的 egg (版本 {})，这个版本没有的设置会被忽略。",
+    setting_renamed: "{} 已改名为 {}。",
+    setting_removed: "{} 已不再使用。",
+    setting_unknown: "{} 不是一个设置项。",
+    setting_wrong_type: "无法读取 {} ({})，已使用默认值。",
+    setting_out_of_range: "{} 应在 {} 到 {} 之间，而不是 {}，已使用默认值。",
+    setting_invalid_hotkey: "{}: \"{}\" 不是 Ctrl+B 这样的组合键，已使用默认值。",
+    settings_opened: "已打开 settings.json。",
+    ranking_profile: "排序方案: {}。",
+    launch_options_title: "启动选项 · {}",
+    arguments_label: "参数",
+    start_folder_label: "起始文件夹",
+    arguments_keys: "Enter: 下一步  Ctrl+U: 清空  Esc: 取消",
+    start_folder_keys: "Enter: 保存  Shift+Tab: 返回  Ctrl+U: 清空  Esc: 取消",
+    launch_options_unchanged: "启动选项没有改变。",
+    launch_options_saved: "已保存 {} 的启动选项。",
+    alias_title: "别名 · {}",
+    alias_label: "别名",
+    alias_keys: "Enter: 保存  Ctrl+U: 清空  Esc: 取消",
+    alias_unchanged: "别名没有改变。",
+    alias_removed: "已移除 {} 的别名。",
+    alias_saved: "输入 {} 即可打开 {}。",
+    theme_title: "主题",
+    theme_keys: "输入 #rrggbb 或 r,g,b。Ctrl+R 重置当前颜色，Ctrl+T 切换预设。",
+    theme_preview_title: "预览",
+    sample_result: "示例结果",
+    selected_result: "选中的结果",
+    not_a_color: "不是有效的颜色: {}",
+    theme_discarded: "已放弃主题修改。",
+    theme_saved: "主题已保存。",
+    preview_loading: "加载中...",
+    preview_image: "{} 图片",
+    preview_more_entries: "... 还有 {} 项",
+    preview_empty_folder: "空文件夹",
+    preview_unsupported: "无法预览此文件 ({})",
+    preview_failed: "无法预览: {}",
+    answer_title: "答案",
+    answer_copied: "已复制 {}。",
+    answer_not_copied: "无法复制: {}",
+    time_now: "现在",
+    local_time: "本地时间",
+    rates_source: "汇率来自 {}，更新于 {}",
+    rates_loading: "正在获取汇率...",
+    rates_disabled: "将 \"fetch_exchange_rates\" 设为 true 即可换算货币。",
+    rates_unavailable: "暂时无法获取汇率。",
+};
+
+pub fn messages(language: Language) -> &'static Messages {
+    match language {
+        Language::En => &EN,
+        Language::Zh => &ZH,
+    }
+}
+
+/// Replaces each `{}` in `template` with the next of `values`. Placeholders left
+/// over once `values` runs out are kept as they are.
+pub fn fill(template: &str, values: &[&str]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut values = values.iter();
+    let mut rest = template;
+    while let Some(at) = rest.find("{}") {
+        filled.push_str(&rest[..at]);
+        match values.next() {
+            Some(value) => filled.push_str(value),
+            None => filled.push_str("{}"),
+        }
+        rest = &rest[at + 2..];
+    }
+    filled.push_str(rest);
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_filled_in_order() {
+        assert_eq!(
+            fill(messages(Language::En).search_with, &["Bing", "rust {}"]),
+            "Search Bing for: rust {}"
+        );
+        assert_eq!(
+            fill(messages(Language::Zh).search_with, &["Bing", "rust"]),
+            "在 Bing 上搜索: rust"
+        );
+        assert_eq!(fill("{} and {}", &["one"]), "one and {}");
+    }
+}
@@ -0,0 +1,196 @@
+//! Log file under `%LOCALAPPDATA%\egg\logs` (`~/.local/share/egg/logs` elsewhere)
+//! for attaching to bug reports. `egg.log` moves to `egg.1.log`, and that to
+//! `egg.2.log`, once it grows past [`MAX_LOG_BYTES`]; older logs are dropped.
+//!
+//! `RUST_LOG` sets the level when given; otherwise it is debug with
+//! `debug_mode` on and info with it off. Other crates only log warnings and
+//! info. [`span`] times a step (indexing, a search, a launch) at debug level.
+
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Instant,
+};
+
+use log::{debug, Level, LevelFilter, Log, Metadata, Record};
+
+pub const LOG_FILE: &str = "egg.log";
+/// Size at which the log is rotated.
+pub const MAX_LOG_BYTES: u64 = 1024 * 1024;
+/// Logs kept, the current one included.
+const KEPT_LOGS: usize = 3;
+/// Module paths of this crate start with it.
+const OWN_TARGET: &str = "egg_cli";
+
+pub fn logs_dir() -> Option<PathBuf> {
+    Some(dirs::data_local_dir()?.join("egg").join("logs"))
+}
+
+/// Starts logging to the log file at info level, or the `RUST_LOG` level. Without
+/// a writable logs folder nothing is logged.
+pub fn init() {
+    let Some(file) = logs_dir().and_then(|dir| LogFile::open(&dir, MAX_LOG_BYTES).ok()) else {
+        return;
+    };
+    let logger = Box::leak(Box::new(FileLogger {
+        file: Mutex::new(file),
+    }));
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(env_level().unwrap_or(LevelFilter::Info));
+    }
+}
+
+/// Follows `debug_mode`, unless `RUST_LOG` picked the level.
+pub fn set_debug(enabled: bool) {
+    if env_level().is_none() {
+        log::set_max_level(if enabled {
+            LevelFilter::Debug
+        } else {
+            LevelFilter::Info
+        });
+    }
+}
+
+fn env_level() -> Option<LevelFilter> {
+    env::var("RUST_LOG").ok()?.trim().parse().ok()
+}
+
+/// Logs how long it lived at debug level when dropped.
+#[must_use = "the span is timed until it is dropped"]
+pub struct Span {
+    name: &'static str,
+    started: Instant,
+}
+
+pub fn span(name: &'static str) -> Span {
+    Span {
+        name,
+        started: Instant::now(),
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        debug!("{} took {:.1?}", self.name, self.started.elapsed());
+    }
+}
+
+struct FileLogger {
+    file: Mutex<LogFile>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && (metadata.target().starts_with(OWN_TARGET) || metadata.level() <= Level::Info)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_line(&line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+struct LogFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+}
+
+impl LogFile {
+    fn open(dir: &Path, max_bytes: u64) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = append_to(&dir.join(LOG_FILE))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file,
+            size,
+            max_bytes,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        for kept in (1..KEPT_LOGS).rev() {
+            let older = self.dir.join(rotated_name(kept));
+            let newer = self.dir.join(rotated_name(kept - 1));
+            if newer.exists() {
+                fs::rename(&newer, &older)?;
+            }
+        }
+        self.file = append_to(&self.dir.join(LOG_FILE))?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn append_to(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// `egg.log` for 0, `egg.1.log` for 1 and so on.
+fn rotated_name(index: usize) -> String {
+    match index {
+        0 => LOG_FILE.to_string(),
+        _ => format!("egg.{index}.log"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_logs_rotate_and_the_oldest_is_dropped() {
+        let dir = env::temp_dir().join("egg-logging-rotate");
+        let _ = fs::remove_dir_all(&dir);
+        let mut log = LogFile::open(&dir, 10).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            log.write_line(line).unwrap();
+        }
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap_or_default();
+        assert_eq!(read("egg.log"), "fourth\n");
+        assert_eq!(read("egg.1.log"), "third\n");
+        assert_eq!(read("egg.2.log"), "second\n");
+        assert!(!dir.join("egg.3.log").exists());
+
+        // Reopening appends to the current log.
+        drop(log);
+        LogFile::open(&dir, 100)
+            .unwrap()
+            .write_line("fifth\n")
+            .unwrap();
+        assert_eq!(read("egg.log"), "fourth\nfifth\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
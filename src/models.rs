@@ -1,11 +1,19 @@
 use serde::{Deserialize, Serialize};
 
+use crate::locale::Messages;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum AppType {
     Win32,
     Uwp,
+    /// A Chrome or Edge web app, started through the browser's app proxy.
+    Pwa,
+    /// An uninstaller from Programs and Features.
+    Uninstaller,
 }
 
+/// Every field is serialized, even when empty: the binary app cache reads fields
+/// by position, not by name.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ApplicationInfo {
     pub id: String,
@@ -19,13 +27,183 @@ pub struct ApplicationInfo {
     pub pinyin_index: Option<String>,
     pub working_directory: Option<String>,
     pub arguments: Option<String>,
+    /// Where the icon comes from when it is not the item itself: `file,index` from
+    /// an internet shortcut, or a desktop entry's `Icon=` name or path. The pixels
+    /// are kept in the icon cache, never in the index.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Lowercase copies of the matched fields; `None` until [`Self::fold_case`].
+    /// Derived, so never cached.
+    #[serde(skip)]
+    pub folded: Option<FoldedApp>,
+}
+
+impl ApplicationInfo {
+    /// Fills in [`Self::folded`], so searches compare without lowercasing.
+    pub fn fold_case(&mut self) {
+        self.folded = Some(FoldedApp {
+            name: self.name.to_ascii_lowercase(),
+            keywords: self
+                .keywords
+                .iter()
+                .map(|keyword| keyword.to_ascii_lowercase())
+                .collect(),
+        });
+    }
+}
+
+/// An app's name and keywords lowercased once at index time. Pinyin is built
+/// lowercase and needs no copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldedApp {
+    pub name: String,
+    pub keywords: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Which indexed field contributed most to a match.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchSource {
+    Name,
+    Keyword,
+    PinyinFull,
+    PinyinInitials,
+    Folder,
+    Url,
+    /// A user-defined alias, typed in full.
+    Alias,
+    /// Words of the name a few edits from the query, tried when nothing matched.
+    Typo,
+}
+
+impl MatchSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Keyword => "keyword",
+            Self::PinyinFull => "pinyin",
+            Self::PinyinInitials => "initials",
+            Self::Folder => "folder",
+            Self::Url => "url",
+            Self::Alias => "alias",
+            Self::Typo => "typo",
+        }
+    }
+}
+
+/// Ways to act on a result; the actions menu lists the ones a result supports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ActionVariant {
+    /// What Enter does.
+    #[default]
+    Open,
+    RunAsAdmin,
+    OpenContainingFolder,
+    CopyPath,
+    CopyUrl,
+    /// Opens the link in an incognito or InPrivate window, leaving it out of
+    /// egg's history too.
+    OpenPrivate,
+    /// Edits the app's launch arguments and start folder inside the TUI.
+    EditLaunchOptions,
+    /// Sets the short name that brings the app up.
+    EditAlias,
+    /// Marks a todo.txt task done.
+    CompleteTask,
+    /// Opens a terminal in a git repository.
+    OpenInTerminal,
+    /// Opens the web page of a git repository's remote.
+    OpenRemote,
+}
+
+impl ActionVariant {
+    pub fn label(self, text: &Messages) -> &'static str {
+        match self {
+            Self::Open => text.action_open,
+            Self::RunAsAdmin => text.action_run_as_admin,
+            Self::OpenContainingFolder => text.action_open_containing_folder,
+            Self::CopyPath => text.action_copy_path,
+            Self::CopyUrl => text.action_copy_url,
+            Self::OpenPrivate => text.action_open_private,
+            Self::EditLaunchOptions => text.action_edit_launch_options,
+            Self::EditAlias => text.action_edit_alias,
+            Self::CompleteTask => text.action_complete_task,
+            Self::OpenInTerminal => text.action_open_in_terminal,
+            Self::OpenRemote => text.action_open_remote,
+        }
+    }
+
+    /// Whether the variant starts the item, as opposed to acting on its path or URL.
+    pub fn launches(self) -> bool {
+        matches!(self, Self::Open | Self::RunAsAdmin)
+    }
+}
+
+/// An item returned by the Windows Search index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct IndexedItem {
+    /// `System.ItemNameDisplay`.
+    pub name: String,
+    /// `System.ItemPathDisplay`; empty for items that are not files.
+    pub path: String,
+    /// `System.ItemUrl`, e.g. `file:C:/...` or `mapi://...` for emails.
+    pub url: String,
+}
+
+/// A top-level window offered by the window switcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    /// The window handle (`HWND`, or the X11 window id), as an integer.
+    pub id: isize,
+    pub title: String,
+    /// The owning program's file name, e.g. `chrome.exe`; empty when unknown.
+    pub process: String,
+}
+
+/// A running process offered by the `kill` prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// The program's file name, e.g. `chrome.exe`.
+    pub name: String,
+    /// Runs as administrator (root on Linux and macOS), so ending it asks first.
+    pub elevated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub id: String,
     pub title: String,
     pub subtitle: String,
     pub score: i64,
     pub action_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_source: Option<MatchSource>,
+}
+
+/// The section a result is listed under when `group_results` is on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultCategory {
+    /// Apps, projects, repositories and other things that launch a program.
+    Applications,
+    Bookmarks,
+    Files,
+    /// URLs, web searches and suggestions.
+    Web,
+    /// Commands, snippets, notes, windows and everything else.
+    Other,
+}
+
+impl ResultCategory {
+    pub fn title(self, text: &Messages) -> &'static str {
+        match self {
+            Self::Applications => text.applications_group,
+            Self::Bookmarks => text.bookmarks_group,
+            Self::Files => text.files_group,
+            Self::Web => text.web_group,
+            Self::Other => text.other_group,
+        }
+    }
 }
@@ -0,0 +1,86 @@
+//! Quick notes: `note <text>` appends the text to a Markdown file as a list item
+//! stamped with the local time. The file is `notes_file` in `settings.json`, or
+//! `egg-notes.md` in the Documents folder.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDateTime;
+
+use crate::config::{documents_file, AppConfig};
+
+const NOTE_KEYWORD: &str = "note";
+const DEFAULT_NOTES_FILE: &str = "egg-notes.md";
+
+/// Returns the note when `query` starts with `note ` and has text after it.
+pub fn note_query(query: &str) -> Option<&str> {
+    let (head, rest) = query.trim_start().split_once(char::is_whitespace)?;
+    let note = rest.trim();
+    (head.eq_ignore_ascii_case(NOTE_KEYWORD) && !note.is_empty()).then_some(note)
+}
+
+/// The file notes are appended to.
+pub fn notes_path(config: &AppConfig) -> Option<PathBuf> {
+    documents_file(config.notes_file.as_deref(), DEFAULT_NOTES_FILE)
+}
+
+/// The line a note is saved as.
+pub fn note_entry(text: &str, now: NaiveDateTime) -> String {
+    format!("- {} {}\n", now.format("%Y-%m-%d %H:%M"), text.trim())
+}
+
+/// Appends `entry` to the file, creating it and its folder if needed. A last
+/// line without a line break gets one first.
+pub fn append_to_file(path: &Path, entry: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|err| format!("无法创建目录: {err}"))?;
+    }
+    let needs_break = fs::read(path)
+        .map(|bytes| bytes.last().is_some_and(|byte| *byte != b'\n'))
+        .unwrap_or(false);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| format!("无法打开文件: {err}"))?;
+    let text = if needs_break {
+        format!("\n{entry}")
+    } else {
+        entry.to_string()
+    };
+    file.write_all(text.as_bytes())
+        .map_err(|err| format!("无法写入文件: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn notes_are_appended_with_a_timestamp() {
+        assert_eq!(note_query("note  buy milk "), Some("buy milk"));
+        assert_eq!(note_query("Note call Bob"), Some("call Bob"));
+        assert_eq!(note_query("note"), None);
+        assert_eq!(note_query("notepad notes"), None);
+
+        let now = NaiveDate::from_ymd_opt(2024, 3, 8)
+            .unwrap()
+            .and_hms_opt(9, 5, 0)
+            .unwrap();
+        let dir = std::env::temp_dir().join(format!("egg-notes-test-{}", std::process::id()));
+        let path = dir.join("inbox").join("notes.md");
+        let _ = fs::remove_dir_all(&dir);
+        append_to_file(&path, &note_entry("buy milk", now)).unwrap();
+        fs::write(&path, "- 2024-03-08 09:05 buy milk").unwrap();
+        append_to_file(&path, &note_entry("call Bob", now)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "- 2024-03-08 09:05 buy milk\n- 2024-03-08 09:05 call Bob\n"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
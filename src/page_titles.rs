@@ -0,0 +1,165 @@
+//! Looks up the `<title>` of web pages for URL and bookmark results. Only the
+//! start of the page is downloaded, requests give up after a few seconds, and
+//! looked-up titles (including pages without one) are kept for the session.
+
+use std::{io::Read, time::Duration};
+
+use crate::session_cache::SessionCache;
+
+/// Titles are nearly always in the `<head>`, well within this many bytes.
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+const MAX_TITLE_CHARS: usize = 120;
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The address to fetch for `url`: web addresses as they are, bare host names such
+/// as `example.com` over https. Other schemes (`mailto:`, `file:`) have no page.
+pub fn page_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let scheme_end = url.find("://");
+    match scheme_end {
+        Some(end) => {
+            let scheme = &url[..end];
+            (scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+                .then(|| url.to_string())
+        }
+        None if !url.is_empty() && !url.contains(':') && !url.contains(char::is_whitespace) => {
+            Some(format!("https://{url}"))
+        }
+        None => None,
+    }
+}
+
+/// Downloads the start of `url` and returns its title, or `None` for pages that
+/// are not HTML or have no title.
+pub fn fetch_title(url: &str) -> Result<Option<String>, String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(TIMEOUT)
+        .redirects(5)
+        .user_agent(concat!("egg-cli/", env!("CARGO_PKG_VERSION")))
+        .build();
+    let response = agent
+        .get(url)
+        .call()
+        .map_err(|err| format!("无法获取网页标题: {err}"))?;
+    if !response.content_type().contains("html") {
+        return Ok(None);
+    }
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_end(&mut body)
+        .map_err(|err| format!("无法读取网页内容: {err}"))?;
+    Ok(extract_title(&String::from_utf8_lossy(&body)))
+}
+
+/// The text of the first `<title>` element, with entities decoded and whitespace
+/// collapsed.
+pub fn extract_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut from = 0;
+    let open = loop {
+        let at = from + lower[from..].find("<title")?;
+        // Skip look-alikes such as `<titlebar>`.
+        match lower[at + 6..].chars().next() {
+            Some('>') | Some(' ') | Some('\t') | Some('\n') | Some('\r') => break at,
+            _ => from = at + 6,
+        }
+    };
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(&html[start..end])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    Some(match title.char_indices().nth(MAX_TITLE_CHARS) {
+        Some((cut, _)) => format!("{}…", &title[..cut]),
+        None => title,
+    })
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest
+            .find(';')
+            .filter(|semi| *semi <= 10)
+            .and_then(|semi| Some((entity_char(&rest[1..semi])?, semi)));
+        match entity {
+            Some((ch, semi)) => {
+                decoded.push(ch);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn entity_char(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let value = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(value)
+        }
+    }
+}
+
+/// Titles looked up this session, keyed by page URL. `None` records a page that
+/// had no title or could not be fetched, so it is not fetched again.
+pub type TitleCache = SessionCache<String, Option<String>, 256>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_web_pages_are_fetched() {
+        assert_eq!(
+            page_url("https://docs.rs/ratatui").as_deref(),
+            Some("https://docs.rs/ratatui")
+        );
+        assert_eq!(
+            page_url("example.com").as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(page_url("mailto:me@example.com"), None);
+        assert_eq!(page_url("file:///C:/notes.txt"), None);
+    }
+
+    #[test]
+    fn titles_are_decoded_and_collapsed() {
+        let html = "<html><head><titlebar>x</titlebar>\n<TITLE lang=\"en\">\n  Rust &amp; \
+                    Ratatui &#8212; Docs&#x21;  </TITLE></head>";
+        assert_eq!(
+            extract_title(html).as_deref(),
+            Some("Rust & Ratatui — Docs!")
+        );
+        assert_eq!(extract_title("<title>  </title>"), None);
+        assert_eq!(extract_title("<p>no title</p>"), None);
+        assert_eq!(
+            extract_title("<title>AT&T &bogus; &</title>").as_deref(),
+            Some("AT&T &bogus; &")
+        );
+    }
+}
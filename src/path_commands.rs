@@ -0,0 +1,209 @@
+//! Executables on `%PATH%`, so `ping` or `notepad` can be run directly even
+//! without a Start Menu shortcut. Windows picks them by `%PATHEXT%`; elsewhere
+//! any file with an execute bit counts.
+
+#[cfg(not(windows))]
+use std::path::PathBuf;
+use std::{collections::HashSet, env, ffi::OsString, fs, path::Path};
+
+use crate::{
+    git_repos::is_git_repo,
+    models::{AppType, ApplicationInfo},
+    recent_documents::is_recent_document,
+    recent_projects::is_recent_project,
+    terminal_profiles::is_terminal_profile,
+    wsl::is_wsl_distro,
+};
+
+/// Ids of executables found on `%PATH%`.
+pub const PATH_ID_PREFIX: &str = "path:";
+/// Used when `%PATHEXT%` is unset.
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+/// Every executable on `%PATH%`; like the shell, the first directory wins when a
+/// name appears more than once.
+pub fn enumerate_path_commands() -> Vec<ApplicationInfo> {
+    let Some(path) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let extensions = executable_extensions(env::var_os("PATHEXT"));
+    let mut seen = HashSet::new();
+    let mut commands = Vec::new();
+    for dir in env::split_paths(&path) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut files: Vec<_> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+            .map(|entry| entry.path())
+            .collect();
+        files.sort();
+        for file in files {
+            if let Some(command) = path_command(&file, &extensions) {
+                if seen.insert(command.id.clone()) {
+                    commands.push(command);
+                }
+            }
+        }
+    }
+    commands
+}
+
+pub fn is_path_command(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(PATH_ID_PREFIX)
+}
+
+/// The first file named `name` in a `$PATH` folder.
+#[cfg(not(windows))]
+pub fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Drops `%PATH%` executables that another app already launches, matched by
+/// path or by file name (`code` when VS Code is indexed as `...\Code.exe`).
+/// Recent documents, git repositories, IDE projects, WSL distributions and
+/// terminal profiles do not count; `ping.txt` must not hide `ping`, a project
+/// opened in VS Code must not hide `code`, and a distribution must not hide `wsl`.
+pub fn drop_shadowed_path_commands(apps: &mut Vec<ApplicationInfo>) {
+    let file_stem = |path: &str| {
+        let name = path.rsplit(['\\', '/']).next().unwrap_or(path);
+        let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+        stem.to_ascii_lowercase()
+    };
+    let mut launched = HashSet::new();
+    for app in apps.iter().filter(|app| {
+        !is_path_command(app)
+            && !is_recent_document(app)
+            && !is_recent_project(app)
+            && !is_git_repo(app)
+            && !is_wsl_distro(app)
+            && !is_terminal_profile(app)
+    }) {
+        launched.insert(app.path.to_ascii_lowercase());
+        launched.insert(file_stem(&app.path));
+        if let Some(source) = &app.source_path {
+            launched.insert(source.to_ascii_lowercase());
+        }
+    }
+    apps.retain(|app| {
+        !is_path_command(app)
+            || !(launched.contains(&app.path.to_ascii_lowercase())
+                || launched.contains(&file_stem(&app.path)))
+    });
+}
+
+fn executable_extensions(pathext: Option<OsString>) -> Vec<String> {
+    let pathext = pathext
+        .map(|value| value.to_string_lossy().into_owned())
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PATHEXT.to_string());
+    pathext
+        .split(';')
+        .map(|ext| ext.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+fn path_command(file: &Path, extensions: &[String]) -> Option<ApplicationInfo> {
+    if !is_executable(file, extensions) {
+        return None;
+    }
+    // Outside Windows the extension is part of the command (`python3.12`).
+    let stem = if cfg!(windows) {
+        file.file_stem()
+    } else {
+        file.file_name()
+    }?
+    .to_str()?
+    .trim();
+    if stem.is_empty() {
+        return None;
+    }
+    let file_name = file.file_name()?.to_string_lossy().into_owned();
+    Some(ApplicationInfo {
+        id: format!("{PATH_ID_PREFIX}{}", stem.to_ascii_lowercase()),
+        name: stem.to_string(),
+        path: file.to_string_lossy().into_owned(),
+        source_path: None,
+        app_type: AppType::Win32,
+        description: Some(format!("{file_name} on PATH")),
+        keywords: vec![file_name],
+        pinyin_index: None,
+        working_directory: None,
+        arguments: None,
+        icon: None,
+        folded: None,
+    })
+}
+
+#[cfg(windows)]
+fn is_executable(file: &Path, extensions: &[String]) -> bool {
+    file.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext.to_ascii_lowercase()))
+}
+
+#[cfg(unix)]
+fn is_executable(file: &Path, _extensions: &[String]) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(file).is_ok_and(|meta| meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::app;
+
+    #[cfg(windows)]
+    #[test]
+    fn pathext_picks_executables() {
+        let extensions = executable_extensions(Some(OsString::from(".COM;.EXE; .Cmd;;")));
+        assert_eq!(extensions, vec!["com", "exe", "cmd"]);
+        assert_eq!(executable_extensions(None).len(), 4);
+
+        let ping = path_command(Path::new("C:/Windows/System32/PING.EXE"), &extensions).unwrap();
+        assert_eq!(ping.id, "path:ping");
+        assert_eq!(ping.name, "PING");
+        assert_eq!(ping.keywords, vec!["PING.EXE"]);
+        assert!(path_command(Path::new("C:/Windows/System32/kernel32.dll"), &extensions).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute_bit_picks_executables() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let sh = path_command(Path::new("/bin/sh"), &[]).unwrap();
+        assert_eq!(sh.id, "path:sh");
+        assert_eq!(sh.name, "sh");
+
+        let notes = std::env::temp_dir().join("egg-path-commands-notes.txt");
+        fs::write(&notes, "notes").unwrap();
+        fs::set_permissions(&notes, fs::Permissions::from_mode(0o644)).unwrap();
+        let found = path_command(&notes, &[]);
+        let _ = fs::remove_file(&notes);
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn shortcuts_shadow_path_commands() {
+        let mut apps = vec![
+            app("shell:code", "Visual Studio Code", r"C:\VS Code\Code.exe"),
+            app("shell:notepad", "Notepad", r"{1AC14E77}\notepad.exe"),
+            app("path:code", "code", r"C:\VS Code\bin\code.cmd"),
+            app(
+                "path:notepad",
+                "notepad",
+                r"C:\Windows\System32\notepad.exe",
+            ),
+            app("path:ping", "PING", r"C:\Windows\System32\PING.EXE"),
+        ];
+        drop_shadowed_path_commands(&mut apps);
+        let ids: Vec<&str> = apps.iter().map(|app| app.id.as_str()).collect();
+        assert_eq!(ids, vec!["shell:code", "shell:notepad", "path:ping"]);
+    }
+}
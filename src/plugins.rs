@@ -0,0 +1,515 @@
+//! Flow Launcher JSON-RPC plugins run as external providers.
+//!
+//! Each plugin lives in its own folder under `<config dir>/egg-cli/plugins` with a
+//! Flow `plugin.json` manifest. A query that starts with a plugin's action keyword
+//! runs the plugin once with a `query` request; picking a result runs it again with
+//! the result's `JsonRPCAction`. Only the original (v1) protocol is spoken, so
+//! Python, Node and executable plugins work while .NET and `_v2` plugins are skipped.
+//!
+//! Plugin processes are contained rather than trusted: they get no console, no stdin,
+//! a trimmed environment and a job object that caps their memory and ends every
+//! process they started once the reply is read or the timeout passes. Outside
+//! Windows a process group stands in for the job, without the memory cap.
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+#[cfg(windows)]
+use std::os::windows::{io::AsRawHandle, process::CommandExt};
+use std::{
+    ffi::OsStr,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_json::{json, Value};
+#[cfg(windows)]
+use windows::{
+    core::PCWSTR,
+    Win32::{
+        Foundation::{CloseHandle, HANDLE},
+        System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+            JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+        },
+    },
+};
+
+use crate::{config::config_path, state::GenerationToken};
+
+const MANIFEST_FILE: &str = "plugin.json";
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+/// Per-process memory cap inside a plugin's job.
+#[cfg(windows)]
+const PROCESS_MEMORY_LIMIT: usize = 512 * 1024 * 1024;
+/// Replies past this size are cut off and fail to parse.
+const MAX_REPLY_BYTES: u64 = 4 * 1024 * 1024;
+/// Picking a result may open windows or files, so actions get longer than queries.
+pub const ACTION_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often a query waiting on its plugin checks whether it was superseded.
+const STALE_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+/// Environment variables a plugin inherits; everything else is dropped.
+const PASSED_ENV: &[&str] = &[
+    "APPDATA",
+    "COMSPEC",
+    "HOME",
+    "HOMEDRIVE",
+    "HOMEPATH",
+    "LANG",
+    "LOCALAPPDATA",
+    "NUMBER_OF_PROCESSORS",
+    "PATH",
+    "PATHEXT",
+    "PROCESSOR_ARCHITECTURE",
+    "PROGRAMDATA",
+    "PROGRAMFILES",
+    "PROGRAMFILES(X86)",
+    "SYSTEMDRIVE",
+    "SYSTEMROOT",
+    "TEMP",
+    "TMP",
+    "TMPDIR",
+    "USER",
+    "USERNAME",
+    "USERPROFILE",
+    "WINDIR",
+];
+
+/// The parts of a Flow Launcher `plugin.json` egg uses.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PluginManifest {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub action_keyword: String,
+    #[serde(default)]
+    pub action_keywords: Vec<String>,
+    pub language: String,
+    pub execute_file_name: String,
+    /// Folder holding the manifest; plugins run from here.
+    #[serde(skip)]
+    pub directory: PathBuf,
+}
+
+impl PluginManifest {
+    fn keywords(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.action_keyword.as_str())
+            .chain(self.action_keywords.iter().map(String::as_str))
+            .map(str::trim)
+            // `*` plugins answer every query in Flow; egg only runs plugins on request.
+            .filter(|keyword| !keyword.is_empty() && *keyword != "*")
+    }
+
+    /// Whether `disabled_plugins` lists this plugin by id or name.
+    pub fn is_disabled(&self, disabled: &[String]) -> bool {
+        disabled.iter().any(|entry| {
+            let entry = entry.trim();
+            entry.eq_ignore_ascii_case(&self.id) || entry.eq_ignore_ascii_case(&self.name)
+        })
+    }
+
+    /// Program and leading arguments that run the plugin's entry file.
+    fn launcher(&self) -> Result<(PathBuf, Vec<PathBuf>), String> {
+        let entry = self.directory.join(&self.execute_file_name);
+        match self.language.to_ascii_lowercase().as_str() {
+            "python" => Ok((PathBuf::from("python"), vec![entry])),
+            "javascript" | "typescript" => Ok((PathBuf::from("node"), vec![entry])),
+            "executable" => Ok((entry, Vec::new())),
+            other => Err(format!("插件 {} 使用不支持的语言: {other}", self.name)),
+        }
+    }
+}
+
+/// One row of a plugin's `query` reply.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct PluginResult {
+    #[serde(rename = "Title", alias = "title")]
+    pub title: String,
+    #[serde(rename = "SubTitle", alias = "subTitle", alias = "subtitle", default)]
+    pub subtitle: String,
+    #[serde(rename = "Score", alias = "score", default)]
+    pub score: i64,
+    #[serde(rename = "JsonRPCAction", alias = "jsonRPCAction", default)]
+    pub action: Option<PluginRequest>,
+}
+
+/// A JSON-RPC request sent to a plugin, either the query or a result's action.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct PluginRequest {
+    pub method: String,
+    #[serde(default)]
+    pub parameters: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct QueryReply {
+    #[serde(default)]
+    result: Vec<PluginResult>,
+}
+
+/// A picked plugin result, run through the plugin that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginCall {
+    pub plugin: PluginManifest,
+    /// `None` for informational rows, which do nothing when picked.
+    pub request: Option<PluginRequest>,
+}
+
+pub fn plugins_dir() -> Option<PathBuf> {
+    config_path().and_then(|path| path.parent().map(|dir| dir.join("plugins")))
+}
+
+/// Reads every plugin manifest under `dir`, skipping plugins egg cannot run.
+pub fn discover(dir: &Path) -> Vec<PluginManifest> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut plugins: Vec<PluginManifest> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let directory = entry.path();
+            let content = fs::read_to_string(directory.join(MANIFEST_FILE)).ok()?;
+            match parse_manifest(&content, directory.clone()) {
+                Ok(plugin) => Some(plugin),
+                Err(err) => {
+                    warn!("skipping plugin in {}: {err}", directory.display());
+                    None
+                }
+            }
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+fn parse_manifest(content: &str, directory: PathBuf) -> Result<PluginManifest, String> {
+    let mut plugin: PluginManifest = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|err| format!("plugin.json 格式错误: {err}"))?;
+    plugin.directory = directory;
+    plugin.launcher()?;
+    Ok(plugin)
+}
+
+/// Finds the enabled plugin whose action keyword starts `query`, and the rest of the query.
+pub fn match_keyword<'a>(
+    query: &'a str,
+    plugins: &'a [PluginManifest],
+    disabled: &[String],
+) -> Option<(&'a PluginManifest, &'a str)> {
+    let (head, rest) = query.trim_start().split_once(char::is_whitespace)?;
+    plugins
+        .iter()
+        .filter(|plugin| !plugin.is_disabled(disabled))
+        .find(|plugin| plugin.keywords().any(|keyword| keyword == head))
+        .map(|plugin| (plugin, rest.trim()))
+}
+
+/// Asks `plugin` for results for `search`, ending it early once `token` goes
+/// stale.
+pub fn query(
+    plugin: &PluginManifest,
+    search: &str,
+    timeout: Duration,
+    token: &GenerationToken,
+) -> Result<Vec<PluginResult>, String> {
+    let request = PluginRequest {
+        method: "query".to_string(),
+        parameters: vec![Value::String(search.to_string())],
+    };
+    let reply = run(plugin, &request, timeout, token)?;
+    let reply = parse_query_reply(&reply).map_err(|err| format!("{}: {err}", plugin.name))?;
+    debug!(
+        "plugin {} returned {} results for {search:?}",
+        plugin.id,
+        reply.len()
+    );
+    Ok(reply)
+}
+
+fn parse_query_reply(reply: &str) -> Result<Vec<PluginResult>, String> {
+    let reply: QueryReply = serde_json::from_str(json_payload(reply))
+        .map_err(|err| format!("插件返回的结果无法解析: {err}"))?;
+    Ok(reply.result)
+}
+
+/// Runs a result's action. Requests the plugin sends back to Flow, such as
+/// changing the query, have no egg equivalent and are only logged.
+pub fn call(call: &PluginCall) -> Result<(), String> {
+    let Some(request) = &call.request else {
+        return Ok(());
+    };
+    let reply = run(
+        &call.plugin,
+        request,
+        ACTION_TIMEOUT,
+        &GenerationToken::detached(),
+    )?;
+    let payload = json_payload(&reply);
+    if !payload.is_empty() {
+        debug!("plugin {} action reply ignored: {payload}", call.plugin.id);
+    }
+    Ok(())
+}
+
+/// Plugins may print log lines before their JSON; the reply is the last line that
+/// looks like an object.
+fn json_payload(reply: &str) -> &str {
+    let reply = reply.trim();
+    if reply.starts_with('{') {
+        return reply;
+    }
+    reply
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| line.starts_with('{'))
+        .unwrap_or(reply)
+}
+
+/// The command line that sends `request` to `plugin`, as (program, arguments).
+fn command_line(
+    plugin: &PluginManifest,
+    request: &PluginRequest,
+) -> Result<(PathBuf, Vec<String>), String> {
+    let (program, leading) = plugin.launcher()?;
+    let mut arguments: Vec<String> = leading
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    arguments
+        .push(json!({ "method": request.method, "parameters": request.parameters }).to_string());
+    Ok((program, arguments))
+}
+
+fn run(
+    plugin: &PluginManifest,
+    request: &PluginRequest,
+    timeout: Duration,
+    token: &GenerationToken,
+) -> Result<String, String> {
+    let (program, arguments) = command_line(plugin, request)?;
+    let mut command = Command::new(&program);
+    command
+        .args(&arguments)
+        .current_dir(&plugin.directory)
+        .env_clear()
+        .envs(std::env::vars_os().filter(|(key, _)| is_passed_env(key)))
+        .env("PYTHONIOENCODING", "utf-8")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+    #[cfg(unix)]
+    command.process_group(0);
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("无法启动插件 {}: {err}", plugin.name))?;
+    // Anything the plugin spawns before this point escapes the job; plugins start
+    // helpers only after reading their request, so the window is not a concern.
+    let job = match PluginJob::confine(&child) {
+        Ok(job) => job,
+        Err(err) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("无法限制插件 {} 的进程: {err}", plugin.name));
+        }
+    };
+
+    let reply = read_reply(&mut child, timeout, token);
+    // Closing the job ends the plugin and anything it left running.
+    drop(job);
+    let _ = child.wait();
+    reply.map_err(|err| format!("插件 {} {err}", plugin.name))
+}
+
+fn read_reply(
+    child: &mut Child,
+    timeout: Duration,
+    token: &GenerationToken,
+) -> Result<String, String> {
+    let mut stdout = child.stdout.take().ok_or("没有输出")?;
+    let (sender, receiver) = mpsc::sync_channel(1);
+    thread::Builder::new()
+        .name("plugin-reply".to_string())
+        .spawn(move || {
+            let mut reply = Vec::new();
+            let read = (&mut stdout).take(MAX_REPLY_BYTES).read_to_end(&mut reply);
+            let _ = sender.send(read.map(|_| reply));
+        })
+        .map_err(|err| format!("无法读取输出: {err}"))?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        let wait = deadline
+            .saturating_duration_since(Instant::now())
+            .min(STALE_CHECK_INTERVAL);
+        match receiver.recv_timeout(wait) {
+            Ok(Ok(reply)) => return Ok(String::from_utf8_lossy(&reply).into_owned()),
+            Ok(Err(err)) => return Err(format!("输出读取失败: {err}")),
+            Err(RecvTimeoutError::Timeout) if token.is_stale() => return Err("已取消".to_string()),
+            Err(RecvTimeoutError::Timeout) if Instant::now() < deadline => {}
+            Err(_) => return Err(format!("超时 ({} ms)", timeout.as_millis())),
+        }
+    }
+}
+
+fn is_passed_env(key: &OsStr) -> bool {
+    let key = key.to_string_lossy();
+    PASSED_ENV.iter().any(|name| key.eq_ignore_ascii_case(name))
+}
+
+/// Job object holding one plugin process; closing it kills every process inside.
+#[cfg(windows)]
+struct PluginJob(HANDLE);
+
+#[cfg(windows)]
+impl PluginJob {
+    fn confine(child: &Child) -> windows::core::Result<Self> {
+        unsafe {
+            let job = Self(CreateJobObjectW(None, PCWSTR::null())?);
+            let mut limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE
+                | JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION
+                | JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+            limits.ProcessMemoryLimit = PROCESS_MEMORY_LIMIT;
+            SetInformationJobObject(
+                job.0,
+                JobObjectExtendedLimitInformation,
+                &limits as *const _ as *const _,
+                std::mem::size_of_val(&limits) as u32,
+            )?;
+            AssignProcessToJobObject(job.0, HANDLE(child.as_raw_handle()))?;
+            Ok(job)
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for PluginJob {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.0);
+        }
+    }
+}
+
+/// The process group a plugin was started in; dropping it kills the group.
+#[cfg(unix)]
+struct PluginJob(libc::pid_t);
+
+#[cfg(unix)]
+impl PluginJob {
+    fn confine(child: &Child) -> std::io::Result<Self> {
+        libc::pid_t::try_from(child.id())
+            .map(Self)
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidInput))
+    }
+}
+
+#[cfg(unix)]
+impl Drop for PluginJob {
+    fn drop(&mut self) {
+        // The group id is the plugin's pid, as it was started with `process_group(0)`.
+        unsafe {
+            libc::kill(-self.0, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(keyword: &str, language: &str) -> PluginManifest {
+        parse_manifest(
+            &format!(
+                r#"{{
+                    "ID": "2f4e6a1c",
+                    "ActionKeyword": "{keyword}",
+                    "Name": "Wiki",
+                    "Author": "someone",
+                    "Version": "1.0.0",
+                    "Language": "{language}",
+                    "Website": "https://example.com",
+                    "ExecuteFileName": "main.py"
+                }}"#
+            ),
+            PathBuf::from(r"C:\plugins\Wiki"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_flow_manifests_and_rejects_unsupported_languages() {
+        let plugin = manifest("wiki", "python");
+        assert_eq!(plugin.id, "2f4e6a1c");
+        assert_eq!(plugin.directory, PathBuf::from(r"C:\plugins\Wiki"));
+
+        let (program, arguments) = command_line(
+            &plugin,
+            &PluginRequest {
+                method: "query".to_string(),
+                parameters: vec![json!("rust lang")],
+            },
+        )
+        .unwrap();
+        assert_eq!(program, PathBuf::from("python"));
+        assert_eq!(
+            arguments.last().unwrap(),
+            r#"{"method":"query","parameters":["rust lang"]}"#
+        );
+
+        let content = r#"{"ID":"x","Name":"Dotnet","Language":"csharp","ExecuteFileName":"x.dll"}"#;
+        assert!(parse_manifest(content, PathBuf::new()).is_err());
+        let content = r#"{"ID":"x","Name":"New","Language":"python_v2","ExecuteFileName":"x.py"}"#;
+        assert!(parse_manifest(content, PathBuf::new()).is_err());
+    }
+
+    #[test]
+    fn keywords_pick_enabled_plugins_only() {
+        let plugins = vec![manifest("wiki", "python"), manifest("*", "python")];
+        let (plugin, rest) = match_keyword("wiki  rust lang", &plugins, &[]).unwrap();
+        assert_eq!(plugin.name, "Wiki");
+        assert_eq!(rest, "rust lang");
+
+        assert!(match_keyword("wiki", &plugins, &[]).is_none());
+        assert!(match_keyword("wikipedia rust", &plugins, &[]).is_none());
+        assert!(match_keyword("anything goes", &plugins, &[]).is_none());
+        assert!(match_keyword("wiki rust", &plugins, &["wiki".to_string()]).is_none());
+        assert!(match_keyword("wiki rust", &plugins, &["2F4E6A1C".to_string()]).is_none());
+    }
+
+    #[test]
+    fn query_replies_tolerate_log_lines_and_missing_fields() {
+        let reply = "loading cache\r\n{\"result\": [\
+            {\"Title\": \"Rust\", \"SubTitle\": \"Programming language\", \"IcoPath\": \"a.png\",\
+             \"JsonRPCAction\": {\"method\": \"open_url\", \"parameters\": [\"https://rust-lang.org\"]},\
+             \"score\": 40},\
+            {\"Title\": \"Rust (fungus)\"}]}\r\n";
+        let results = parse_query_reply(reply).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].score, 40);
+        assert_eq!(
+            results[0].action,
+            Some(PluginRequest {
+                method: "open_url".to_string(),
+                parameters: vec![json!("https://rust-lang.org")],
+            })
+        );
+        assert_eq!(results[1].subtitle, "");
+        assert!(results[1].action.is_none());
+
+        assert!(parse_query_reply("Traceback (most recent call last):").is_err());
+    }
+}
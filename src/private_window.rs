@@ -0,0 +1,196 @@
+//! Opening links in a private window (Incognito, InPrivate). The browser a
+//! bookmark came from is preferred, then the default browser, then any installed
+//! browser that has a private mode.
+
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::process::Command;
+use std::{collections::HashSet, path::PathBuf};
+
+#[cfg(windows)]
+use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+#[cfg(windows)]
+use crate::bookmarks::registered_executable;
+#[cfg(not(windows))]
+use crate::path_commands::find_on_path;
+
+/// Browsers are found through the registry on Windows and `$PATH` on Linux;
+/// macOS keeps them in app bundles, which are not looked up.
+pub const PRIVATE_WINDOWS_AVAILABLE: bool = cfg!(not(target_os = "macos"));
+
+struct PrivateBrowser {
+    /// Matches the start of a bookmark profile label, e.g. `Edge 默认`.
+    name: &'static str,
+    /// The program's file name on Windows.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    executable: &'static str,
+    /// The command on Linux.
+    command: &'static str,
+    /// Opens the URL that follows in a private window.
+    flag: &'static str,
+    /// Start of the ProgId Windows records for the default browser.
+    prog_id: &'static str,
+}
+
+const fn browser(
+    name: &'static str,
+    executable: &'static str,
+    command: &'static str,
+    flag: &'static str,
+    prog_id: &'static str,
+) -> PrivateBrowser {
+    PrivateBrowser {
+        name,
+        executable,
+        command,
+        flag,
+        prog_id,
+    }
+}
+
+const PRIVATE_BROWSERS: &[PrivateBrowser] = &[
+    browser(
+        "Chrome",
+        "chrome.exe",
+        "google-chrome",
+        "--incognito",
+        "ChromeHTML",
+    ),
+    browser(
+        "Edge",
+        "msedge.exe",
+        "microsoft-edge",
+        "--inprivate",
+        "MSEdgeHTM",
+    ),
+    browser(
+        "Brave",
+        "brave.exe",
+        "brave-browser",
+        "--incognito",
+        "BraveHTML",
+    ),
+    browser(
+        "Vivaldi",
+        "vivaldi.exe",
+        "vivaldi",
+        "--incognito",
+        "VivaldiHTM",
+    ),
+    browser("Opera", "opera.exe", "opera", "--private", "Opera"),
+    browser(
+        "Firefox",
+        "firefox.exe",
+        "firefox",
+        "-private-window",
+        "FirefoxURL",
+    ),
+];
+
+/// The installed browser to open a private window with and its private flag.
+/// `profile` is the label of the bookmark's browser profile, if any.
+pub fn private_browser(profile: Option<&str>) -> Option<(PathBuf, &'static str)> {
+    let default = default_browser_id();
+    candidates(profile, default.as_deref())
+        .into_iter()
+        .find_map(|browser| Some((locate(browser)?, browser.flag)))
+}
+
+/// Browsers to try, best first: the profile's, the default one, then the rest.
+fn candidates(profile: Option<&str>, default_id: Option<&str>) -> Vec<&'static PrivateBrowser> {
+    let starts_with = |text: &str, prefix: &str| {
+        text.get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    };
+    let from_profile = profile.and_then(|label| {
+        PRIVATE_BROWSERS
+            .iter()
+            .find(|browser| starts_with(label, browser.name))
+    });
+    let default = default_id.and_then(|id| {
+        PRIVATE_BROWSERS
+            .iter()
+            .find(|browser| starts_with(id, browser.prog_id) || starts_with(id, browser.command))
+    });
+    let mut seen = HashSet::new();
+    from_profile
+        .into_iter()
+        .chain(default)
+        .chain(PRIVATE_BROWSERS)
+        .filter(|browser| seen.insert(browser.name))
+        .collect()
+}
+
+/// The ProgId `https` links open with, e.g. `ChromeHTML`.
+#[cfg(windows)]
+fn default_browser_id() -> Option<String> {
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(
+            r"Software\Microsoft\Windows\Shell\Associations\UrlAssociations\https\UserChoice",
+        )
+        .ok()?
+        .get_value("ProgId")
+        .ok()
+}
+
+/// The desktop file of the default browser, e.g. `firefox.desktop`.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn default_browser_id() -> Option<String> {
+    let output = Command::new("xdg-settings")
+        .args(["get", "default-web-browser"])
+        .output()
+        .ok()?;
+    let id = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !id.is_empty()).then_some(id)
+}
+
+#[cfg(target_os = "macos")]
+fn default_browser_id() -> Option<String> {
+    None
+}
+
+#[cfg(windows)]
+fn locate(browser: &PrivateBrowser) -> Option<PathBuf> {
+    registered_executable(browser.executable).filter(|path| path.is_file())
+}
+
+#[cfg(not(windows))]
+fn locate(browser: &PrivateBrowser) -> Option<PathBuf> {
+    find_on_path(browser.command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(browsers: Vec<&PrivateBrowser>) -> Vec<&str> {
+        browsers
+            .iter()
+            .take(3)
+            .map(|browser| browser.name)
+            .collect()
+    }
+
+    #[test]
+    fn the_bookmark_browser_comes_first_then_the_default() {
+        assert_eq!(
+            names(candidates(
+                Some("Edge 默认"),
+                Some("FirefoxURL-308046B0AF4A39CB")
+            )),
+            vec!["Edge", "Firefox", "Chrome"]
+        );
+        assert_eq!(
+            names(candidates(None, Some("brave-browser.desktop"))),
+            vec!["Brave", "Chrome", "Edge"]
+        );
+        assert_eq!(
+            names(candidates(Some("Opera GX"), Some("ChromeHTML"))),
+            vec!["Opera", "Chrome", "Edge"]
+        );
+        assert_eq!(
+            candidates(Some("Chromium"), None).len(),
+            PRIVATE_BROWSERS.len()
+        );
+    }
+}
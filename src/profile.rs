@@ -0,0 +1,149 @@
+//! Portable profiles, for moving egg to another machine: `egg-cli
+//! export-profile <file>` bundles the settings (aliases, pins and launch options
+//! included), launch history and recent list into one JSON file, and
+//! `egg-cli import-profile <file>` takes them in. Indexes are left out; the new
+//! machine builds its own.
+
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache,
+    config::AppConfig,
+    history::{unix_now, LaunchHistory},
+    state::{RecentEntry, RecentList, RECENT_CAPACITY},
+};
+
+/// Bumped when a profile stops being readable by older versions.
+const PROFILE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct Profile {
+    pub version: u32,
+    /// When it was exported, in Unix seconds.
+    pub exported: u64,
+    pub settings: AppConfig,
+    #[serde(default)]
+    pub history: LaunchHistory,
+    /// Newest first.
+    #[serde(default)]
+    pub recent: Vec<RecentEntry>,
+}
+
+/// What an import or export covered, for the summary line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProfileSummary {
+    pub launches: usize,
+    pub recent: usize,
+}
+
+/// Writes this machine's profile to `path`.
+pub fn export(path: &Path) -> Result<ProfileSummary, String> {
+    let profile = Profile {
+        version: PROFILE_VERSION,
+        exported: unix_now(),
+        settings: AppConfig::try_load()?,
+        history: LaunchHistory::load(),
+        recent: cache::load_recent_actions(),
+    };
+    let content = serde_json::to_string_pretty(&profile).map_err(|err| err.to_string())?;
+    fs::write(path, content).map_err(|err| format!("无法写入 {}: {err}", path.display()))?;
+    Ok(profile.summary())
+}
+
+/// Replaces the settings with the profile's and merges its launch history and
+/// recent list into this machine's.
+pub fn import(path: &Path) -> Result<ProfileSummary, String> {
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("无法读取 {}: {err}", path.display()))?;
+    let profile = Profile::parse(&content)?;
+    let summary = profile.summary();
+
+    let config = profile.settings_for(&AppConfig::try_load()?);
+    config.save()?;
+
+    let mut history = LaunchHistory::load();
+    history.merge(&profile.history);
+    history.save()?;
+
+    let mut recent = RecentList::new(RECENT_CAPACITY);
+    recent.restore(cache::load_recent_actions());
+    recent.restore(profile.recent);
+    cache::save_recent_actions(&recent)?;
+    Ok(summary)
+}
+
+impl Profile {
+    fn parse(content: &str) -> Result<Self, String> {
+        let profile: Self =
+            serde_json::from_str(content).map_err(|err| format!("档案格式错误: {err}"))?;
+        if profile.version > PROFILE_VERSION {
+            return Err(format!(
+                "档案版本 {} 比这个 egg 支持的更新，请先升级 egg",
+                profile.version
+            ));
+        }
+        Ok(profile)
+    }
+
+    /// The profile's settings, keeping those that belong to this machine.
+    fn settings_for(&self, current: &AppConfig) -> AppConfig {
+        AppConfig {
+            sync_folder: current.sync_folder.clone(),
+            ..self.settings.clone()
+        }
+    }
+
+    fn summary(&self) -> ProfileSummary {
+        ProfileSummary {
+            launches: self.history.item_count(),
+            recent: self.recent.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_round_trip_and_keep_local_settings() {
+        let mut history = LaunchHistory::default();
+        history.record("app-notepad", 1_792_000_000);
+        let profile = Profile {
+            version: PROFILE_VERSION,
+            exported: 1_792_000_000,
+            settings: AppConfig {
+                max_results: 25,
+                sync_folder: Some("D:\\Sync".to_string()),
+                pinned_results: vec!["app-notepad".to_string()],
+                ..AppConfig::default()
+            },
+            history,
+            recent: Vec::new(),
+        };
+        let content = serde_json::to_string(&profile).unwrap();
+        let parsed = Profile::parse(&content).unwrap();
+        assert_eq!(
+            parsed.summary(),
+            ProfileSummary {
+                launches: 1,
+                recent: 0
+            }
+        );
+
+        let here = AppConfig {
+            sync_folder: Some("/home/me/Sync".to_string()),
+            ..AppConfig::default()
+        };
+        let settings = parsed.settings_for(&here);
+        assert_eq!(settings.max_results, 25);
+        assert_eq!(settings.pinned_results, vec!["app-notepad".to_string()]);
+        assert_eq!(settings.sync_folder.as_deref(), Some("/home/me/Sync"));
+
+        let newer = content.replacen("\"version\":1", "\"version\":2", 1);
+        assert!(Profile::parse(&newer).is_err());
+        assert!(Profile::parse("{}").is_err());
+    }
+}
@@ -0,0 +1,55 @@
+//! Guards for search providers that call into other processes or services.
+
+use std::{sync::mpsc, thread, time::Duration};
+
+/// Runs `task` on a worker thread and gives up after `timeout`.
+///
+/// A timed-out task keeps running in the background; its result is dropped.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn with_timeout<T, F>(provider: &str, timeout: Duration, task: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::sync_channel(1);
+    thread::Builder::new()
+        .name(format!("{provider}-query"))
+        .spawn(move || {
+            let _ = sender.send(task());
+        })
+        .map_err(|err| format!("无法启动 {provider} 查询线程: {err}"))?;
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            Err(format!("{provider} 查询超时 ({} ms)", timeout.as_millis()))
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(format!("{provider} 查询线程异常退出")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_results_that_arrive_in_time() {
+        let result = with_timeout("test", Duration::from_secs(5), || Ok(42));
+        assert_eq!(result, Ok(42));
+    }
+
+    #[test]
+    fn slow_tasks_time_out() {
+        let result: Result<(), String> = with_timeout("test", Duration::from_millis(10), || {
+            thread::sleep(Duration::from_millis(500));
+            Ok(())
+        });
+        assert!(result.unwrap_err().contains("超时"));
+    }
+
+    #[test]
+    fn panicking_tasks_report_an_error() {
+        let result: Result<(), String> =
+            with_timeout("test", Duration::from_secs(5), || panic!("boom"));
+        assert!(result.is_err());
+    }
+}
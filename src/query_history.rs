@@ -0,0 +1,76 @@
+//! Queries that led to a launch, newest first, kept in the cache folder. The TUI
+//! completes the input to the newest one it starts, shown as ghost text.
+
+use std::collections::VecDeque;
+
+/// Queries kept; the oldest drop off.
+pub const QUERY_HISTORY_CAPACITY: usize = 200;
+
+#[derive(Debug, Default)]
+pub struct QueryHistory {
+    queries: VecDeque<String>,
+}
+
+impl QueryHistory {
+    /// Takes in queries saved by an earlier run, newest first.
+    pub fn restore(&mut self, queries: Vec<String>) {
+        for query in queries.into_iter().rev() {
+            self.record(&query);
+        }
+    }
+
+    /// Moves `query` to the front, trimmed; an earlier copy differing only in
+    /// case is replaced.
+    pub fn record(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        let lower = query.to_lowercase();
+        self.queries
+            .retain(|existing| existing.to_lowercase() != lower);
+        self.queries.push_front(query.to_string());
+        self.queries.truncate(QUERY_HISTORY_CAPACITY);
+    }
+
+    /// The newest query that `input` starts (ignoring case) and is shorter than.
+    pub fn complete(&self, input: &str) -> Option<&str> {
+        if input.trim().is_empty() {
+            return None;
+        }
+        let lower = input.to_lowercase();
+        self.queries
+            .iter()
+            .find(|query| query.len() > input.len() && query.to_lowercase().starts_with(&lower))
+            .map(String::as_str)
+    }
+
+    /// Newest first.
+    pub fn items(&self) -> impl Iterator<Item = &String> {
+        self.queries.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_to_the_newest_matching_query() {
+        let mut history = QueryHistory::default();
+        history.restore(vec!["visual studio".to_string(), "vim".to_string()]);
+        assert_eq!(history.complete("vi"), Some("visual studio"));
+        history.record("  VIM ");
+        assert_eq!(history.complete("vi"), Some("VIM"));
+        assert_eq!(history.items().count(), 2);
+        assert_eq!(history.complete("Visual"), Some("visual studio"));
+        assert_eq!(history.complete("visual studio"), None);
+        assert_eq!(history.complete(" "), None);
+
+        for index in 0..QUERY_HISTORY_CAPACITY {
+            history.record(&format!("query {index}"));
+        }
+        assert_eq!(history.items().count(), QUERY_HISTORY_CAPACITY);
+        assert_eq!(history.complete("vi"), None);
+    }
+}
@@ -0,0 +1,312 @@
+//! Query syntax checks and help. The search itself is forgiving, so problems are
+//! reported as diagnostics shown under the input rather than as failed searches,
+//! and a trailing `?` lists the operators that apply where the cursor is.
+
+use std::collections::HashMap;
+
+use crate::{
+    config::AppConfig,
+    locale::{fill, messages, Messages},
+    models::SearchResult,
+    plugins::PluginManifest,
+    search_core::{command_query, emoji_query, file_query, split_mode_prefix, QueryMode},
+    state::PendingAction,
+    wsl::WSL_AVAILABLE,
+};
+
+/// Something in the query that will not do what it looks like.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// A `word:` head that is not a known prefix, e.g. a typo of `file:`.
+    UnknownPrefix(String),
+    /// An odd number of `"`; the phrase runs to the end of the query.
+    UnclosedQuote,
+    /// `file:` while neither Everything nor Windows Search is enabled.
+    FileSearchDisabled,
+    /// An operator with nothing after it yet.
+    MissingText(&'static str),
+}
+
+impl Diagnostic {
+    pub fn message(&self, text: &Messages) -> String {
+        match self {
+            Self::UnknownPrefix(prefix) => fill(text.unknown_prefix, &[prefix]),
+            Self::UnclosedQuote => text.unclosed_quote.to_string(),
+            Self::FileSearchDisabled => text.file_search_disabled.to_string(),
+            Self::MissingText(operator) => fill(text.missing_text, &[operator]),
+        }
+    }
+
+    /// Errors change what the query does; the rest are reminders.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::MissingText(_))
+    }
+}
+
+/// Checks `query` against the prefixes and operators egg understands.
+pub fn diagnose(query: &str, config: &AppConfig) -> Vec<Diagnostic> {
+    let query = query.trim();
+    let mut diagnostics = Vec::new();
+    if query.is_empty() || help_request(query).is_some() {
+        return diagnostics;
+    }
+    if let Some(command) = command_query(query) {
+        if command.is_empty() {
+            diagnostics.push(Diagnostic::MissingText(">"));
+        }
+        return diagnostics;
+    }
+    if let Some(files) = file_query(query) {
+        if !config.enable_everything && !config.enable_windows_search {
+            diagnostics.push(Diagnostic::FileSearchDisabled);
+        } else if files.is_empty() {
+            diagnostics.push(Diagnostic::MissingText("file:"));
+        }
+        return diagnostics;
+    }
+    if emoji_query(query).is_some() {
+        return diagnostics;
+    }
+    if let Some(prefix) = unknown_prefix(query) {
+        diagnostics.push(Diagnostic::UnknownPrefix(prefix.to_string()));
+    }
+    if query.matches('"').count() % 2 == 1 {
+        diagnostics.push(Diagnostic::UnclosedQuote);
+    }
+    diagnostics
+}
+
+/// A leading `word:` that egg would otherwise search for literally. Drive letters,
+/// URLs (`https://`) and `ms-settings:`-style URIs with a target are left alone.
+fn unknown_prefix(query: &str) -> Option<&str> {
+    let head = query.split_whitespace().next()?;
+    let (name, rest) = head.split_once(':')?;
+    let looks_like_prefix = name.len() > 1
+        && rest.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    looks_like_prefix.then_some(head)
+}
+
+/// The query before a trailing ` ?`, when help was asked for. The space before
+/// the `?` is kept so `b ?` still reads as the bookmarks prefix. A `?` ending a
+/// shell command belongs to the command.
+pub fn help_request(query: &str) -> Option<&str> {
+    let base = query.trim().strip_suffix('?')?;
+    if command_query(base).is_some_and(|command| !command.is_empty()) {
+        return None;
+    }
+    (base.is_empty() || base.ends_with(char::is_whitespace)).then_some(base)
+}
+
+/// One line of help: what to type, what it does, and the query picking it leaves.
+struct Operator {
+    syntax: String,
+    description: String,
+    insert: String,
+}
+
+impl Operator {
+    fn new(syntax: impl Into<String>, description: impl Into<String>, insert: &str) -> Self {
+        Self {
+            syntax: syntax.into(),
+            description: description.into(),
+            insert: insert.to_string(),
+        }
+    }
+}
+
+/// Operators that apply to `base`, the query before the `?`.
+fn operators(base: &str, config: &AppConfig, plugins: &[PluginManifest]) -> Vec<Operator> {
+    let text = messages(config.language);
+    if command_query(base).is_some() {
+        return vec![
+            Operator::new(
+                "> <command>",
+                fill(text.help_run_in_shell, &[config.command_shell.label()]),
+                "> ",
+            ),
+            Operator::new("Ctrl+Enter", text.help_run_as_admin, base),
+        ];
+    }
+    if file_query(base).is_some() {
+        let mut rows = Vec::new();
+        if config.enable_everything {
+            rows.push(Operator::new("*.docx", text.help_wildcards, "file: *."));
+            rows.push(Operator::new(
+                "ext:pdf;docx",
+                text.help_by_extension,
+                "file: ext:",
+            ));
+            rows.push(Operator::new(
+                r"C:\work\ report",
+                text.help_in_folder,
+                "file: ",
+            ));
+        }
+        if config.enable_windows_search {
+            rows.push(Operator::new(
+                "kind:document",
+                text.help_by_kind,
+                "file: kind:",
+            ));
+        }
+        if rows.is_empty() {
+            rows.push(Operator::new(
+                "file: <name>",
+                Diagnostic::FileSearchDisabled.message(text),
+                base,
+            ));
+        }
+        return rows;
+    }
+
+    let prefixes = &config.query_prefixes;
+    let mut rows = Vec::new();
+    if split_mode_prefix(base, prefixes).0 == QueryMode::All {
+        let mut prefix_row = |prefix: &str, description: &str| {
+            if !prefix.is_empty() {
+                rows.push(Operator::new(
+                    format!("{prefix} <text>"),
+                    description,
+                    &format!("{prefix} "),
+                ));
+            }
+        };
+        prefix_row(&prefixes.apps, text.help_apps_only);
+        prefix_row(&prefixes.bookmarks, text.help_bookmarks_only);
+        prefix_row(&prefixes.search, text.help_web_only);
+        prefix_row(&prefixes.windows, text.help_windows_only);
+        prefix_row(&prefixes.processes, text.help_processes_only);
+        rows.push(Operator::new("file: <name>", text.help_files, "file: "));
+        rows.push(Operator::new(":<name>", text.help_emoji, ":"));
+        rows.push(Operator::new("> <command>", text.help_shell_command, "> "));
+        if WSL_AVAILABLE {
+            rows.push(Operator::new(
+                "wsl <command>",
+                text.help_wsl_command,
+                "wsl ",
+            ));
+        }
+        rows.push(Operator::new("egg", text.help_egg_commands, "egg"));
+        rows.push(Operator::new("!w <text>", text.help_bang, "!"));
+        if config.enable_plugins {
+            for plugin in plugins
+                .iter()
+                .filter(|plugin| !plugin.is_disabled(&config.disabled_plugins))
+            {
+                let keyword = plugin.action_keyword.trim();
+                if !keyword.is_empty() && keyword != "*" {
+                    rows.push(Operator::new(
+                        format!("{keyword} <text>"),
+                        fill(text.help_plugin, &[&plugin.name]),
+                        &format!("{keyword} "),
+                    ));
+                }
+            }
+        }
+    }
+    rows.push(Operator::new(
+        "\"exact phrase\"",
+        text.help_exact_phrase,
+        &format!("{base}\""),
+    ));
+    rows
+}
+
+/// Help rows for `base`; picking one puts its operator in the search box.
+pub fn help_results(
+    base: &str,
+    config: &AppConfig,
+    plugins: &[PluginManifest],
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let mut results = Vec::new();
+    let mut pending_actions = HashMap::new();
+    for (index, operator) in operators(base, config, plugins).into_iter().enumerate() {
+        let result_id = format!("help-{index}");
+        pending_actions.insert(result_id.clone(), PendingAction::Query(operator.insert));
+        results.push(SearchResult {
+            id: result_id,
+            title: operator.syntax,
+            subtitle: operator.description,
+            score: -(index as i64),
+            action_id: "help".to_string(),
+            match_source: None,
+        });
+    }
+    (results, pending_actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_unknown_prefixes_and_unclosed_quotes() {
+        let config = AppConfig::default();
+        assert_eq!(
+            diagnose("xy: notes", &config),
+            vec![Diagnostic::UnknownPrefix("xy:".to_string())]
+        );
+        assert_eq!(
+            diagnose("\"visual studio", &config),
+            vec![Diagnostic::UnclosedQuote]
+        );
+        assert_eq!(
+            diagnose("file: report", &config),
+            vec![Diagnostic::FileSearchDisabled]
+        );
+        assert_eq!(diagnose(">", &config), vec![Diagnostic::MissingText(">")]);
+        assert!(!Diagnostic::MissingText(">").is_error());
+
+        for fine in [
+            "c: drive",
+            "https://example.com",
+            "ms-settings:display",
+            "\"visual studio\" code",
+            "> echo \"hi",
+            "b github",
+        ] {
+            assert!(diagnose(fine, &config).is_empty(), "{fine}");
+        }
+    }
+
+    #[test]
+    fn question_mark_lists_operators_for_the_mode() {
+        assert_eq!(help_request("?"), Some(""));
+        assert_eq!(help_request("b ?"), Some("b "));
+        assert_eq!(help_request("what is rust?"), None);
+        assert_eq!(help_request("> dir ?"), None);
+
+        let config = AppConfig::default();
+        let (results, actions) = help_results("", &config, &[]);
+        let titles: Vec<&str> = results.iter().map(|row| row.title.as_str()).collect();
+        let mut expected = vec![
+            "a <text>",
+            "b <text>",
+            "s <text>",
+            "w <text>",
+            "kill <text>",
+            "file: <name>",
+            ":<name>",
+            "> <command>",
+        ];
+        if WSL_AVAILABLE {
+            expected.push("wsl <command>");
+        }
+        expected.extend(["egg", "!w <text>", "\"exact phrase\""]);
+        assert_eq!(titles, expected);
+        assert!(matches!(
+            actions.get(&results[1].id),
+            Some(PendingAction::Query(query)) if query == "b "
+        ));
+
+        let (results, actions) = help_results("b ", &config, &[]);
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Query(query)) if query == "b \""
+        ));
+    }
+}
@@ -0,0 +1,429 @@
+use serde::{Deserialize, Serialize};
+
+/// Named ranking presets selectable via `ranking_profile` in settings.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RankingProfile {
+    #[default]
+    Balanced,
+    /// Rewards exact and prefix hits so a few keystrokes reliably land on the same item.
+    KeyboardHeavy,
+    /// Flattens match-quality bonuses so looser fuzzy matches surface more often.
+    Discovery,
+    /// Lets frequently and recently launched items dominate whenever they match at all.
+    Recency,
+    /// Uses `ranking_weights` from settings verbatim.
+    Custom,
+}
+
+impl RankingProfile {
+    const ALL: [RankingProfile; 5] = [
+        Self::Balanced,
+        Self::KeyboardHeavy,
+        Self::Discovery,
+        Self::Recency,
+        Self::Custom,
+    ];
+
+    /// The profile after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|profile| *profile == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// The name used in settings.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Balanced => "balanced",
+            Self::KeyboardHeavy => "keyboard-heavy",
+            Self::Discovery => "discovery",
+            Self::Recency => "recency",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn weights(self, custom: &RankingWeights) -> RankingWeights {
+        match self {
+            Self::Balanced => RankingWeights::default(),
+            Self::KeyboardHeavy => RankingWeights {
+                exact_bonus: 260,
+                prefix_bonus: 160,
+                contains_bonus: 20,
+                token_exact_bonus: 60,
+                token_prefix_bonus: 40,
+                token_contains_bonus: 4,
+                fuzzy_percent: 60,
+                length_penalty_divisor: 3,
+                frecency_percent: 15,
+                source_percent: full_share(),
+            },
+            Self::Discovery => RankingWeights {
+                exact_bonus: 90,
+                prefix_bonus: 40,
+                contains_bonus: 30,
+                token_exact_bonus: 15,
+                token_prefix_bonus: 10,
+                token_contains_bonus: 8,
+                fuzzy_percent: 150,
+                length_penalty_divisor: 12,
+                frecency_percent: 10,
+                source_percent: full_share(),
+            },
+            Self::Recency => RankingWeights {
+                frecency_percent: 300,
+                ..RankingWeights::default()
+            },
+            Self::Custom => custom.clone(),
+        }
+    }
+}
+
+/// Tunable constants used by `search_core` when scoring a candidate.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RankingWeights {
+    /// Bonus when the whole query equals a primary field.
+    pub exact_bonus: i64,
+    /// Bonus when a primary field starts with the whole query.
+    pub prefix_bonus: i64,
+    /// Bonus when a primary field contains the whole query.
+    pub contains_bonus: i64,
+    pub token_exact_bonus: i64,
+    pub token_prefix_bonus: i64,
+    pub token_contains_bonus: i64,
+    /// Scale applied to the raw fuzzy matcher score, in percent.
+    pub fuzzy_percent: i64,
+    /// One point is subtracted per this many unmatched characters in a field.
+    pub length_penalty_divisor: i64,
+    /// Share of the launch-history frecency score added to a match, in percent.
+    pub frecency_percent: i64,
+    /// Share of the match points kept for the candidate's source, in percent. Set
+    /// from `source_weights` with [`RankingWeights::for_source`], never from settings.
+    #[serde(skip, default = "full_share")]
+    pub source_percent: i64,
+}
+
+impl RankingWeights {
+    /// These weights for a candidate whose source keeps `percent` of its points.
+    pub fn for_source(&self, percent: i64) -> Self {
+        Self {
+            source_percent: percent.max(0),
+            ..self.clone()
+        }
+    }
+}
+
+fn full_share() -> i64 {
+    100
+}
+
+/// Multipliers for each source's scores, in percent, applied on top of the
+/// ranking profile. At `"bookmarks": 50` a bookmark needs twice the match points
+/// of an app to outrank it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SourceWeights {
+    pub apps: i64,
+    pub bookmarks: i64,
+    /// Typed paths that exist.
+    pub files: i64,
+    /// The launch-history share of every score, whatever its source.
+    pub history: i64,
+}
+
+impl Default for SourceWeights {
+    fn default() -> Self {
+        Self {
+            apps: 100,
+            bookmarks: 100,
+            files: 100,
+            history: 100,
+        }
+    }
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            exact_bonus: 140,
+            prefix_bonus: 70,
+            contains_bonus: 30,
+            token_exact_bonus: 30,
+            token_prefix_bonus: 18,
+            token_contains_bonus: 8,
+            fuzzy_percent: 100,
+            length_penalty_divisor: 6,
+            frecency_percent: 30,
+            source_percent: full_share(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bookmarks::BookmarkEntry,
+        config::AppConfig,
+        history::{unix_now, LaunchHistory},
+        models::{ApplicationInfo, SearchResult},
+        search_core::search,
+        testing::{app, bookmark},
+    };
+
+    /// Expected leading titles (web search row excluded) per profile and query.
+    const GOLDEN: &[(RankingProfile, &str, &[&str])] = &[
+        (
+            RankingProfile::Balanced,
+            "notes",
+            &["Notes", "Sticky Notes"],
+        ),
+        (
+            RankingProfile::Balanced,
+            "vsc",
+            &["VSCodium", "Visual Studio Code"],
+        ),
+        (
+            RankingProfile::Balanced,
+            "studio",
+            &["Studio One 6", "Android Studio", "Visual Studio Code"],
+        ),
+        (
+            RankingProfile::Balanced,
+            "tm",
+            &["Task Manager", "Windows Terminal"],
+        ),
+        (RankingProfile::KeyboardHeavy, "note", &["Notes"]),
+        (RankingProfile::KeyboardHeavy, "stu", &["Studio One 6"]),
+        (RankingProfile::KeyboardHeavy, "vsc", &["VSCodium"]),
+        (
+            RankingProfile::KeyboardHeavy,
+            "tm",
+            &["Task Manager", "Windows Terminal"],
+        ),
+        (
+            RankingProfile::Discovery,
+            "note",
+            &["Notes", "Sticky Notes", "OneNote"],
+        ),
+        (
+            RankingProfile::Discovery,
+            "tm",
+            &["Task Manager", "Terminal Tips"],
+        ),
+        (RankingProfile::Discovery, "ndroid", &["Android Studio"]),
+        (RankingProfile::Recency, "paint", &["Paint", "Paint.NET"]),
+        (RankingProfile::Recency, "sn", &["Sticky Notes"]),
+    ];
+
+    fn corpus() -> (Vec<ApplicationInfo>, Vec<BookmarkEntry>) {
+        let apps = vec![
+            app("shell:code", "Visual Studio Code", r"C:\VS Code\Code.exe"),
+            app("shell:codium", "VSCodium", r"C:\VSCodium\VSCodium.exe"),
+            app("shell:term", "Windows Terminal", r"C:\WindowsApps\wt.exe"),
+            app("shell:teams", "Microsoft Teams", r"C:\Teams\ms-teams.exe"),
+            app("shell:tm", "Task Manager", r"C:\Windows\taskmgr.exe"),
+            app("shell:paint", "Paint", r"C:\Windows\mspaint.exe"),
+            app(
+                "shell:paintnet",
+                "Paint.NET",
+                r"C:\Paint.NET\paintdotnet.exe",
+            ),
+            app("shell:onenote", "OneNote", r"C:\Office\ONENOTE.EXE"),
+            app("shell:sticky", "Sticky Notes", r"shell:AppsFolder\Sticky"),
+            app("shell:notes", "Notes", r"C:\Notes\notes.exe"),
+            app(
+                "shell:studioone",
+                "Studio One 6",
+                r"C:\PreSonus\Studio One.exe",
+            ),
+            app(
+                "shell:android",
+                "Android Studio",
+                r"C:\Android\studio64.exe",
+            ),
+        ];
+        let bookmarks = vec![
+            bookmark(
+                "b:paint",
+                "Paint by Numbers Online",
+                "https://paint.example.com",
+                None,
+            ),
+            bookmark(
+                "b:term",
+                "Terminal Tips",
+                "https://terminal.example.com",
+                None,
+            ),
+        ];
+        (apps, bookmarks)
+    }
+
+    fn ranked(config: &AppConfig, query: &str, history: &LaunchHistory) -> Vec<SearchResult> {
+        let (apps, bookmarks) = corpus();
+        let (results, _) = search(query.to_string(), None, &apps, &bookmarks, history, config);
+        results
+            .into_iter()
+            .filter(|result| result.action_id != "search")
+            .collect()
+    }
+
+    fn with_profile(profile: RankingProfile) -> AppConfig {
+        AppConfig {
+            ranking_profile: profile,
+            ..AppConfig::default()
+        }
+    }
+
+    fn score_of(results: &[SearchResult], title: &str) -> i64 {
+        results
+            .iter()
+            .find(|result| result.title == title)
+            .map(|result| result.score)
+            .unwrap_or_else(|| panic!("{title} missing from results"))
+    }
+
+    #[test]
+    fn golden_queries_rank_as_expected() {
+        for (profile, query, expected) in GOLDEN {
+            let titles: Vec<String> =
+                ranked(&with_profile(*profile), query, &LaunchHistory::default())
+                    .into_iter()
+                    .map(|result| result.title)
+                    .collect();
+            assert!(
+                titles.len() >= expected.len() && titles[..expected.len()] == **expected,
+                "{profile:?} `{query}`: expected {expected:?} first, got {titles:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keyboard_heavy_widens_prefix_lead_over_discovery() {
+        let lead = |profile| {
+            let results = ranked(&with_profile(profile), "stu", &LaunchHistory::default());
+            score_of(&results, "Studio One 6") - score_of(&results, "Android Studio")
+        };
+        assert!(lead(RankingProfile::KeyboardHeavy) > lead(RankingProfile::Balanced));
+        assert!(lead(RankingProfile::Balanced) > lead(RankingProfile::Discovery));
+    }
+
+    #[test]
+    fn recency_profile_promotes_recent_launches() {
+        let mut history = LaunchHistory::default();
+        history.record("app-shell:android", unix_now());
+        let top = |profile| {
+            ranked(&with_profile(profile), "studio", &history)[0]
+                .title
+                .clone()
+        };
+        assert_eq!(top(RankingProfile::Balanced), "Studio One 6");
+        assert_eq!(top(RankingProfile::KeyboardHeavy), "Studio One 6");
+        assert_eq!(top(RankingProfile::Recency), "Android Studio");
+    }
+
+    #[test]
+    fn frequent_launches_float_to_top_by_default() {
+        let mut history = LaunchHistory::default();
+        let now = unix_now();
+        for _ in 0..10 {
+            history.record("app-shell:android", now);
+        }
+        let results = ranked(&AppConfig::default(), "studio", &history);
+        assert_eq!(results[0].title, "Android Studio");
+    }
+
+    #[test]
+    fn custom_profile_uses_configured_weights() {
+        let config = AppConfig {
+            ranking_profile: RankingProfile::Custom,
+            ranking_weights: RankingWeights {
+                prefix_bonus: 0,
+                token_prefix_bonus: 0,
+                ..RankingWeights::default()
+            },
+            ..AppConfig::default()
+        };
+        let custom = ranked(&config, "stu", &LaunchHistory::default());
+        let balanced = ranked(&AppConfig::default(), "stu", &LaunchHistory::default());
+        assert!(score_of(&custom, "Studio One 6") < score_of(&balanced, "Studio One 6"));
+        assert_eq!(
+            score_of(&custom, "Android Studio"),
+            score_of(&balanced, "Android Studio")
+        );
+    }
+
+    #[test]
+    fn source_weights_scale_each_source() {
+        let discovery = with_profile(RankingProfile::Discovery);
+        let halved = AppConfig {
+            source_weights: SourceWeights {
+                bookmarks: 50,
+                ..SourceWeights::default()
+            },
+            ..discovery.clone()
+        };
+        let before = ranked(&discovery, "tm", &LaunchHistory::default());
+        let after = ranked(&halved, "tm", &LaunchHistory::default());
+        assert_eq!(before[1].title, "Terminal Tips");
+        assert_eq!(after[1].title, "Windows Terminal");
+        assert_eq!(
+            score_of(&after, "Terminal Tips"),
+            score_of(&before, "Terminal Tips") / 2
+        );
+        assert_eq!(
+            score_of(&after, "Task Manager"),
+            score_of(&before, "Task Manager")
+        );
+
+        // Without history the launch-history share drops out, so the better
+        // match wins again.
+        let mut history = LaunchHistory::default();
+        history.record("app-shell:android", unix_now());
+        let forgetful = AppConfig {
+            source_weights: SourceWeights {
+                history: 0,
+                ..SourceWeights::default()
+            },
+            ..with_profile(RankingProfile::Recency)
+        };
+        assert_eq!(
+            ranked(&forgetful, "studio", &history)[0].title,
+            "Studio One 6"
+        );
+
+        // Custom weights read from settings keep their full share.
+        let custom: RankingWeights = serde_json::from_str(r#"{"exact_bonus": 10}"#).unwrap();
+        assert_eq!(custom.source_percent, 100);
+    }
+
+    #[test]
+    fn profiles_cycle_through_every_preset() {
+        let mut profile = RankingProfile::default();
+        let mut seen = Vec::new();
+        for _ in 0..RankingProfile::ALL.len() {
+            seen.push(profile.label());
+            profile = profile.next();
+        }
+        assert_eq!(profile, RankingProfile::default());
+        assert_eq!(
+            seen,
+            [
+                "balanced",
+                "keyboard-heavy",
+                "discovery",
+                "recency",
+                "custom"
+            ]
+        );
+        // Labels are the names settings.json uses.
+        assert_eq!(
+            serde_json::to_value(RankingProfile::KeyboardHeavy).unwrap(),
+            "keyboard-heavy"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Recently opened documents, from the shortcuts Windows keeps in
+//! `%APPDATA%\Microsoft\Windows\Recent`. Other systems have no such folder, so
+//! there are no recent documents there.
+
+#[cfg(windows)]
+use std::{
+    cmp::Reverse,
+    env, fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+#[cfg(windows)]
+use log::warn;
+
+use crate::models::ApplicationInfo;
+#[cfg(windows)]
+use crate::{indexer::ComInitGuard, models::AppType, windows_utils::resolve_shell_link};
+
+/// Ids of recent documents.
+pub const RECENT_ID_PREFIX: &str = "recent:";
+/// Only the newest shortcuts are resolved; the folder keeps hundreds.
+#[cfg(windows)]
+const MAX_RECENT: usize = 200;
+
+/// Files behind the newest Recent shortcuts, skipping shortcuts to folders,
+/// network shares and files that no longer exist.
+#[cfg(windows)]
+pub fn enumerate_recent_documents() -> Vec<ApplicationInfo> {
+    let Some(dir) = recent_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut links: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"))
+        })
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+            Some((modified, path))
+        })
+        .collect();
+    links.sort_by_key(|(modified, _)| Reverse(*modified));
+    links.truncate(MAX_RECENT);
+
+    let _com_guard = match unsafe { ComInitGuard::new() } {
+        Ok(guard) => guard,
+        Err(err) => {
+            warn!("recent documents skipped, COM unavailable: {err}");
+            return Vec::new();
+        }
+    };
+    links
+        .iter()
+        .filter_map(|(_, link)| resolve_shell_link(link))
+        .filter(|target| is_local(target) && Path::new(target).is_file())
+        .filter_map(|target| recent_document(&target))
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn enumerate_recent_documents() -> Vec<ApplicationInfo> {
+    Vec::new()
+}
+
+pub fn is_recent_document(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(RECENT_ID_PREFIX)
+}
+
+#[cfg(windows)]
+fn recent_dir() -> Option<PathBuf> {
+    let appdata = env::var_os("APPDATA")?;
+    Some(
+        PathBuf::from(appdata)
+            .join("Microsoft")
+            .join("Windows")
+            .join("Recent"),
+    )
+}
+
+/// Checking a share that is offline can stall for seconds, so only local drives count.
+#[cfg(windows)]
+fn is_local(target: &str) -> bool {
+    !target.starts_with(r"\\")
+}
+
+#[cfg(windows)]
+fn recent_document(target: &str) -> Option<ApplicationInfo> {
+    let (folder, file_name) = target.rsplit_once(['\\', '/'])?;
+    let name = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(stem, _)| stem)
+        .trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(ApplicationInfo {
+        id: format!("{RECENT_ID_PREFIX}{}", target.to_lowercase()),
+        name: name.to_string(),
+        path: target.to_string(),
+        source_path: None,
+        app_type: AppType::Win32,
+        description: Some(format!("Recent document · {folder}")),
+        keywords: vec![file_name.to_string()],
+        pinyin_index: None,
+        working_directory: None,
+        arguments: None,
+        icon: None,
+        folded: None,
+    })
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_targets_become_searchable_documents() {
+        let doc = recent_document(r"C:\Users\me\Documents\Q3 Report.docx").unwrap();
+        assert_eq!(doc.id, r"recent:c:\users\me\documents\q3 report.docx");
+        assert_eq!(doc.name, "Q3 Report");
+        assert_eq!(doc.keywords, vec!["Q3 Report.docx"]);
+        assert_eq!(
+            doc.description.as_deref(),
+            Some(r"Recent document · C:\Users\me\Documents")
+        );
+        assert!(is_recent_document(&doc));
+
+        assert!(recent_document("no-folder.txt").is_none());
+        assert!(is_local(r"D:\notes.txt"));
+        assert!(!is_local(r"\\nas\share\notes.txt"));
+    }
+}
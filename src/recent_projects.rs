@@ -0,0 +1,100 @@
+//! Recent projects of VS Code and JetBrains IDEs, read from the history each IDE
+//! keeps in its config folder. A project opens in the IDE it was opened in, so
+//! `egg` can stand in for the IDE's own welcome screen. Each IDE family has its
+//! own parser module; projects whose folder is gone are skipped.
+
+mod jetbrains;
+mod vscode;
+
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+use crate::{
+    execute::quote_argument,
+    models::{AppType, ApplicationInfo},
+};
+
+/// Ids of recent projects, followed by the IDE and the project path.
+pub const PROJECT_ID_PREFIX: &str = "project:";
+
+/// Recent projects of every IDE found.
+pub fn enumerate_recent_projects() -> Vec<ApplicationInfo> {
+    let mut projects = vscode::recent_projects();
+    debug!("indexed {} vs code projects", projects.len());
+    let jetbrains = jetbrains::recent_projects();
+    debug!("indexed {} jetbrains projects", jetbrains.len());
+    projects.extend(jetbrains);
+    projects
+}
+
+/// The first VS Code edition installed, which folders open in by default.
+pub fn default_editor() -> Option<PathBuf> {
+    vscode::installed_editor()
+}
+
+pub fn is_recent_project(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(PROJECT_ID_PREFIX)
+}
+
+/// An index entry that opens `project`, a folder or workspace file, in `ide`.
+fn project_entry(ide: &str, executable: &Path, project: &str) -> Option<ApplicationInfo> {
+    let file_name = project
+        .trim_end_matches(['\\', '/'])
+        .rsplit(['\\', '/'])
+        .next()?;
+    let name = file_name
+        .strip_suffix(".code-workspace")
+        .unwrap_or(file_name)
+        .trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(ApplicationInfo {
+        id: format!(
+            "{PROJECT_ID_PREFIX}{}:{}",
+            ide.to_ascii_lowercase(),
+            project.to_lowercase()
+        ),
+        name: name.to_string(),
+        path: executable.to_string_lossy().into_owned(),
+        source_path: None,
+        app_type: AppType::Win32,
+        description: Some(format!("{ide} project · {project}")),
+        keywords: vec![ide.to_string(), "project".to_string()],
+        pinyin_index: None,
+        working_directory: None,
+        arguments: Some(quote_argument(project)),
+        icon: None,
+        folded: None,
+    })
+}
+
+/// Checking a share that is offline can stall for seconds, so only local
+/// projects count.
+fn exists_locally(project: &str) -> bool {
+    !project.starts_with(r"\\") && Path::new(project).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projects_open_in_their_ide() {
+        let code = Path::new("/usr/bin/code");
+        let project = project_entry("VS Code", code, "/home/me/src/egg/").unwrap();
+        assert_eq!(project.id, "project:vs code:/home/me/src/egg/");
+        assert_eq!(project.name, "egg");
+        assert_eq!(project.path, "/usr/bin/code");
+        assert_eq!(
+            project.description.as_deref(),
+            Some("VS Code project · /home/me/src/egg/")
+        );
+        assert!(is_recent_project(&project));
+
+        let workspace = project_entry("VS Code", code, r"D:\work\site.code-workspace").unwrap();
+        assert_eq!(workspace.name, "site");
+        assert!(project_entry("VS Code", code, "/").is_none());
+    }
+}
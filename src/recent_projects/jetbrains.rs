@@ -0,0 +1,267 @@
+//! Recent projects of JetBrains IDEs, listed in `options/recentProjects.xml` of
+//! each IDE's config folder (`JetBrains/PyCharm2024.1`, ...). Only the newest
+//! version of each IDE is read. Newer versions key the projects of an
+//! `additionalInfo` map, older ones list them under `recentPaths`.
+
+#[cfg(windows)]
+use std::{env, path::Path};
+use std::{fs, path::PathBuf};
+
+use super::{exists_locally, project_entry};
+use crate::models::ApplicationInfo;
+#[cfg(not(windows))]
+use crate::path_commands::find_on_path;
+
+struct Product {
+    name: &'static str,
+    /// The config folder's name before the version, e.g. `PyCharmCE`.
+    config_prefix: &'static str,
+    /// The install folder's name before the version on Windows, as the installer
+    /// and the Toolbox App name it.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    install_prefix: &'static str,
+    /// The launcher in `bin`, or the command on `$PATH` and in the Toolbox App's
+    /// scripts folder elsewhere.
+    launcher: &'static str,
+}
+
+const fn product(
+    name: &'static str,
+    config_prefix: &'static str,
+    install_prefix: &'static str,
+    launcher: &'static str,
+) -> Product {
+    Product {
+        name,
+        config_prefix,
+        install_prefix,
+        launcher,
+    }
+}
+
+const PRODUCTS: &[Product] = &[
+    product("IntelliJ IDEA", "IntelliJIdea", "IntelliJ IDEA", "idea"),
+    product(
+        "IntelliJ IDEA Community",
+        "IdeaIC",
+        "IntelliJ IDEA Community Edition",
+        "idea",
+    ),
+    product("PyCharm", "PyCharm", "PyCharm", "pycharm"),
+    product(
+        "PyCharm Community",
+        "PyCharmCE",
+        "PyCharm Community Edition",
+        "pycharm",
+    ),
+    product("WebStorm", "WebStorm", "WebStorm", "webstorm"),
+    product("PhpStorm", "PhpStorm", "PhpStorm", "phpstorm"),
+    product("CLion", "CLion", "CLion", "clion"),
+    product("GoLand", "GoLand", "GoLand", "goland"),
+    product("Rider", "Rider", "JetBrains Rider", "rider"),
+    product("RubyMine", "RubyMine", "RubyMine", "rubymine"),
+    product("RustRover", "RustRover", "RustRover", "rustrover"),
+    product("DataGrip", "DataGrip", "DataGrip", "datagrip"),
+];
+
+pub(super) fn recent_projects() -> Vec<ApplicationInfo> {
+    let Some(config_root) = dirs::config_dir().map(|dir| dir.join("JetBrains")) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&config_root) else {
+        return Vec::new();
+    };
+    let config_dirs: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    let home = dirs::home_dir()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut projects = Vec::new();
+    for product in PRODUCTS {
+        let Some(config_dir) = newest_version(&config_dirs, product.config_prefix) else {
+            continue;
+        };
+        let history = config_root
+            .join(config_dir)
+            .join("options")
+            .join("recentProjects.xml");
+        let Ok(content) = fs::read_to_string(&history) else {
+            continue;
+        };
+        let Some(executable) = locate(product) else {
+            continue;
+        };
+        projects.extend(
+            recent_paths(&content, &home)
+                .iter()
+                .filter(|project| exists_locally(project))
+                .filter_map(|project| project_entry(product.name, &executable, project)),
+        );
+    }
+    projects
+}
+
+/// The newest of the folders named `prefix` and a version, e.g. `GoLand2024.1`.
+fn newest_version<'a>(names: &'a [String], prefix: &str) -> Option<&'a str> {
+    names
+        .iter()
+        .filter(|name| {
+            name.strip_prefix(prefix)
+                .is_some_and(|version| version.starts_with(|ch: char| ch.is_ascii_digit()))
+        })
+        .max_by_key(|name| version_key(&name[prefix.len()..]))
+        .map(String::as_str)
+}
+
+fn version_key(version: &str) -> Vec<u32> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// The newest install under `%LOCALAPPDATA%\Programs` or `%ProgramFiles%\JetBrains`.
+#[cfg(windows)]
+fn locate(product: &Product) -> Option<PathBuf> {
+    let user = env::var_os("LOCALAPPDATA").map(|dir| Path::new(&dir).join("Programs"));
+    let machine = env::var_os("ProgramFiles").map(|dir| Path::new(&dir).join("JetBrains"));
+    let launcher = format!("{}64.exe", product.launcher);
+    let mut installs: Vec<PathBuf> = [user, machine]
+        .into_iter()
+        .flatten()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| is_install_of(name, product.install_prefix))
+        })
+        .map(|entry| entry.path().join("bin").join(&launcher))
+        .filter(|path| path.is_file())
+        .collect();
+    installs.sort();
+    installs.pop()
+}
+
+/// Whether `folder` is `prefix` alone, with a version, or with the edition the
+/// Toolbox App adds; `IntelliJ IDEA Community Edition` is not `IntelliJ IDEA`.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn is_install_of(folder: &str, prefix: &str) -> bool {
+    folder.strip_prefix(prefix).is_some_and(|rest| {
+        let rest = rest.trim_start();
+        rest.is_empty()
+            || rest.starts_with(|ch: char| ch.is_ascii_digit())
+            || rest == "Ultimate"
+            || rest == "Professional"
+    })
+}
+
+#[cfg(not(windows))]
+fn locate(product: &Product) -> Option<PathBuf> {
+    find_on_path(product.launcher).or_else(|| {
+        let scripts = dirs::data_dir()?.join("JetBrains/Toolbox/scripts");
+        Some(scripts.join(product.launcher)).filter(|path| path.is_file())
+    })
+}
+
+/// Project paths in `recentProjects.xml`, with `$USER_HOME$` expanded.
+fn recent_paths(content: &str, home: &str) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    // Map keys in newer versions, `<option value="..."/>` list items in older ones.
+    for marker in ["<entry key=\"", "<option value=\""] {
+        for (at, _) in content.match_indices(marker) {
+            let Some((value, _)) = content[at + marker.len()..].split_once('"') else {
+                continue;
+            };
+            let path = unescape(value).replace("$USER_HOME$", home);
+            let path = if cfg!(windows) {
+                path.replace('/', "\\")
+            } else {
+                path
+            };
+            if !path.is_empty() && !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_projects_xml_lists_project_folders() {
+        let newer = r#"<application>
+  <component name="RecentProjectsManager">
+    <option name="additionalInfo">
+      <map>
+        <entry key="$USER_HOME$/PycharmProjects/scraper">
+          <value>
+            <RecentProjectMetaInfo frameTitle="scraper – main.py">
+              <option name="build" value="PY-241.14494.241" />
+            </RecentProjectMetaInfo>
+          </value>
+        </entry>
+        <entry key="/srv/R&amp;D/tools">
+          <value><RecentProjectMetaInfo /></value>
+        </entry>
+      </map>
+    </option>
+    <option name="lastProjectLocation" value="$USER_HOME$/PycharmProjects" />
+  </component>
+</application>"#;
+        let older = r#"<option name="recentPaths">
+      <list>
+        <option value="$USER_HOME$/IdeaProjects/shop" />
+      </list>
+    </option>"#;
+        let separated = |path: &str| {
+            if cfg!(windows) {
+                path.replace('/', "\\")
+            } else {
+                path.to_string()
+            }
+        };
+        assert_eq!(
+            recent_paths(newer, "/home/me"),
+            vec![
+                separated("/home/me/PycharmProjects/scraper"),
+                separated("/srv/R&D/tools")
+            ]
+        );
+        assert_eq!(
+            recent_paths(older, "/home/me"),
+            vec![separated("/home/me/IdeaProjects/shop")]
+        );
+
+        let dirs = [
+            "PyCharm2023.3",
+            "PyCharm2024.1",
+            "PyCharmCE2024.2",
+            "PyCharm2023.10",
+        ]
+        .map(String::from);
+        assert_eq!(newest_version(&dirs, "PyCharm"), Some("PyCharm2024.1"));
+        assert_eq!(newest_version(&dirs, "PyCharmCE"), Some("PyCharmCE2024.2"));
+        assert!(is_install_of("IntelliJ IDEA 2024.1.2", "IntelliJ IDEA"));
+        assert!(is_install_of("IntelliJ IDEA Ultimate", "IntelliJ IDEA"));
+        assert!(!is_install_of(
+            "IntelliJ IDEA Community Edition",
+            "IntelliJ IDEA"
+        ));
+    }
+}
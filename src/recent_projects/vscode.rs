@@ -0,0 +1,197 @@
+//! VS Code's recently opened folders and workspaces, listed in the
+//! `User/globalStorage/storage.json` of its user data folder. Older versions keep
+//! them in `openedPathsList`; newer ones still record the open windows, backups
+//! and per-folder profiles there. Remote folders are skipped.
+
+#[cfg(windows)]
+use std::{env, path::Path};
+use std::{fs, path::PathBuf};
+
+use serde_json::Value;
+
+use super::{exists_locally, project_entry};
+use crate::models::ApplicationInfo;
+#[cfg(not(windows))]
+use crate::path_commands::find_on_path;
+
+struct Edition {
+    name: &'static str,
+    /// The user data folder under the config folder.
+    data_dir: &'static str,
+    /// The install folder on Windows, under `%LOCALAPPDATA%\Programs` or
+    /// `%ProgramFiles%`.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    install_dir: &'static str,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    executable: &'static str,
+    /// The command on `$PATH` elsewhere.
+    #[cfg_attr(windows, allow(dead_code))]
+    command: &'static str,
+}
+
+const EDITIONS: [Edition; 3] = [
+    Edition {
+        name: "VS Code",
+        data_dir: "Code",
+        install_dir: "Microsoft VS Code",
+        executable: "Code.exe",
+        command: "code",
+    },
+    Edition {
+        name: "VS Code Insiders",
+        data_dir: "Code - Insiders",
+        install_dir: "Microsoft VS Code Insiders",
+        executable: "Code - Insiders.exe",
+        command: "code-insiders",
+    },
+    Edition {
+        name: "VSCodium",
+        data_dir: "VSCodium",
+        install_dir: "VSCodium",
+        executable: "VSCodium.exe",
+        command: "codium",
+    },
+];
+
+pub(super) fn recent_projects() -> Vec<ApplicationInfo> {
+    let Some(config_dir) = dirs::config_dir() else {
+        return Vec::new();
+    };
+    let mut projects = Vec::new();
+    for edition in &EDITIONS {
+        let storage = config_dir
+            .join(edition.data_dir)
+            .join("User")
+            .join("globalStorage")
+            .join("storage.json");
+        let Ok(content) = fs::read_to_string(&storage) else {
+            continue;
+        };
+        let Some(executable) = locate(edition) else {
+            continue;
+        };
+        projects.extend(
+            recent_paths(&content)
+                .iter()
+                .filter(|project| exists_locally(project))
+                .filter_map(|project| project_entry(edition.name, &executable, project)),
+        );
+    }
+    projects
+}
+
+pub(super) fn installed_editor() -> Option<PathBuf> {
+    EDITIONS.iter().find_map(locate)
+}
+
+#[cfg(windows)]
+fn locate(edition: &Edition) -> Option<PathBuf> {
+    let user = env::var_os("LOCALAPPDATA").map(|dir| Path::new(&dir).join("Programs"));
+    let machine = env::var_os("ProgramFiles").map(PathBuf::from);
+    [user, machine]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join(edition.install_dir).join(edition.executable))
+        .find(|path| path.is_file())
+}
+
+#[cfg(not(windows))]
+fn locate(edition: &Edition) -> Option<PathBuf> {
+    find_on_path(edition.command)
+}
+
+/// Local folders and workspace files named in `storage.json`, first seen first.
+fn recent_paths(content: &str) -> Vec<String> {
+    let Ok(storage) = serde_json::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+    let mut uris: Vec<&str> = Vec::new();
+    let list = &storage["openedPathsList"];
+    for entry in list["entries"]
+        .as_array()
+        .into_iter()
+        .chain(list["workspaces3"].as_array())
+        .flatten()
+    {
+        let uri = entry
+            .as_str()
+            .or_else(|| entry["folderUri"].as_str())
+            .or_else(|| entry["workspace"]["configPath"].as_str())
+            .or_else(|| entry["configURIPath"].as_str());
+        uris.extend(uri);
+    }
+    let windows = &storage["windowsState"];
+    uris.extend(windows["lastActiveWindow"]["folder"].as_str());
+    for window in windows["openedWindows"].as_array().into_iter().flatten() {
+        uris.extend(window["folder"].as_str());
+    }
+    for folder in storage["backupWorkspaces"]["folders"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        uris.extend(folder["folderUri"].as_str());
+    }
+    if let Some(profiles) = storage["profileAssociations"]["workspaces"].as_object() {
+        uris.extend(profiles.keys().map(String::as_str));
+    }
+
+    let mut paths: Vec<String> = Vec::new();
+    for path in uris.into_iter().filter_map(file_uri_path) {
+        if !paths.iter().any(|seen| seen.eq_ignore_ascii_case(&path)) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// The local path of a `file:///` URI; `None` for other schemes and for shares.
+fn file_uri_path(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix("file:///")?;
+    let path = urlencoding::decode(rest).ok()?;
+    // `file:///c%3A/Users/...` on Windows, `file:///home/...` elsewhere.
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let mut drive = path.replace('/', "\\");
+        drive[..1].make_ascii_uppercase();
+        Some(drive)
+    } else {
+        Some(format!("/{path}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_json_lists_local_folders_and_workspaces() {
+        let storage = r#"{
+            "openedPathsList": {
+                "entries": [
+                    { "folderUri": "file:///c%3A/Users/me/src/egg" },
+                    { "fileUri": "file:///c%3A/Users/me/notes.md" },
+                    { "workspace": { "configPath": "file:///d%3A/work/site.code-workspace" } },
+                    { "folderUri": "vscode-remote://wsl%2Bubuntu/home/me/api" }
+                ]
+            },
+            "windowsState": {
+                "lastActiveWindow": { "folder": "file:///home/me/My%20Project" },
+                "openedWindows": [{ "folder": "file:///C%3A/Users/me/src/egg" }]
+            },
+            "profileAssociations": {
+                "workspaces": { "file:///home/me/dotfiles": "__default__profile__" }
+            }
+        }"#;
+        assert_eq!(
+            recent_paths(storage),
+            vec![
+                r"C:\Users\me\src\egg",
+                r"D:\work\site.code-workspace",
+                "/home/me/My Project",
+                "/home/me/dotfiles",
+            ]
+        );
+        assert!(recent_paths("not json").is_empty());
+    }
+}
@@ -0,0 +1,409 @@
+//! Keeps egg lightweight while it sits open: polls its own working set and CPU
+//! time, drops caches when memory passes `memory_limit_mb`, and lowers its
+//! priority while CPU use stays above `background_cpu_percent`.
+
+use std::{
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use tokio::task::JoinHandle;
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::FILETIME,
+    System::{
+        ProcessStatus::{
+            EmptyWorkingSet, GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+            PROCESS_MEMORY_COUNTERS_EX,
+        },
+        Threading::{
+            GetCurrentProcess, GetProcessTimes, SetPriorityClass, BELOW_NORMAL_PRIORITY_CLASS,
+            NORMAL_PRIORITY_CLASS,
+        },
+    },
+};
+
+use crate::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// Polls in a row over the CPU limit before the priority is lowered, so a burst of
+/// typing never counts as background load.
+const SUSTAINED_POLLS: u32 = 3;
+/// How far the working set must grow after a trim that left it over the limit
+/// before caches are dropped again.
+const RETRY_GROWTH: u64 = 16 * MB;
+const MB: u64 = 1024 * 1024;
+
+/// One reading of the process counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub working_set: u64,
+    pub peak_working_set: u64,
+    pub private_bytes: u64,
+    /// Kernel plus user time since the process started.
+    pub cpu_time: Duration,
+}
+
+/// What the guard last saw and did, for `egg stats`.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceStatus {
+    pub usage: ResourceUsage,
+    pub cpu_percent: f64,
+    /// How many times caches were dropped for going over the memory limit.
+    pub trims: u32,
+    /// Whether egg is running at below-normal priority.
+    pub throttled: bool,
+}
+
+impl ResourceStatus {
+    pub fn summary(&self, cached_searches: usize, memory_limit_mb: u64) -> String {
+        let limit = if memory_limit_mb == 0 {
+            "no limit".to_string()
+        } else {
+            format!("limit {memory_limit_mb} MB")
+        };
+        let mut summary = format!(
+            "Memory {} MB (peak {} MB, {limit}) · CPU {:.1}% · {cached_searches} cached searches",
+            self.usage.working_set / MB,
+            self.usage.peak_working_set / MB,
+            self.cpu_percent,
+        );
+        if self.trims > 0 {
+            summary.push_str(&format!(" · trimmed {}x", self.trims));
+        }
+        if self.throttled {
+            summary.push_str(" · low priority");
+        }
+        summary
+    }
+}
+
+/// Reads the working set and CPU time of this process.
+#[cfg(windows)]
+pub fn current_usage() -> Option<ResourceUsage> {
+    unsafe {
+        let process = GetCurrentProcess();
+        let mut counters = PROCESS_MEMORY_COUNTERS_EX {
+            cb: mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
+            ..Default::default()
+        };
+        GetProcessMemoryInfo(
+            process,
+            &mut counters as *mut PROCESS_MEMORY_COUNTERS_EX as *mut PROCESS_MEMORY_COUNTERS,
+            counters.cb,
+        )
+        .ok()?;
+
+        let mut created = FILETIME::default();
+        let mut exited = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user).ok()?;
+
+        Some(ResourceUsage {
+            working_set: counters.WorkingSetSize as u64,
+            peak_working_set: counters.PeakWorkingSetSize as u64,
+            private_bytes: counters.PrivateUsage as u64,
+            cpu_time: filetime_duration(kernel) + filetime_duration(user),
+        })
+    }
+}
+
+/// Reads the resident set and CPU time of this process. Only Linux reports the
+/// current resident set (in `/proc/self/statm`); elsewhere the peak stands in.
+#[cfg(unix)]
+pub fn current_usage() -> Option<ResourceUsage> {
+    let mut usage = unsafe { mem::zeroed::<libc::rusage>() };
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return None;
+    }
+    let timeval = |time: libc::timeval| {
+        Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+    };
+    // `ru_maxrss` is in kilobytes on Linux and in bytes on macOS.
+    let peak = if cfg!(target_os = "macos") {
+        usage.ru_maxrss as u64
+    } else {
+        usage.ru_maxrss as u64 * 1024
+    };
+    let resident = std::fs::read_to_string("/proc/self/statm")
+        .ok()
+        .and_then(|statm| statm.split_whitespace().nth(1)?.parse::<u64>().ok())
+        .map(|pages| pages * unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64)
+        .unwrap_or(peak);
+    Some(ResourceUsage {
+        working_set: resident,
+        peak_working_set: peak.max(resident),
+        private_bytes: resident,
+        cpu_time: timeval(usage.ru_utime) + timeval(usage.ru_stime),
+    })
+}
+
+/// Hands unused pages back to the system; returns whether any were released.
+#[cfg(windows)]
+fn release_working_set() -> bool {
+    unsafe { EmptyWorkingSet(GetCurrentProcess()) }.is_ok()
+}
+
+/// There is no call to shrink a process's resident set outside Windows.
+#[cfg(unix)]
+fn release_working_set() -> bool {
+    false
+}
+
+#[cfg(windows)]
+fn set_low_priority(low: bool) -> Result<(), String> {
+    let class = if low {
+        BELOW_NORMAL_PRIORITY_CLASS
+    } else {
+        NORMAL_PRIORITY_CLASS
+    };
+    unsafe { SetPriorityClass(GetCurrentProcess(), class) }.map_err(|err| err.to_string())
+}
+
+#[cfg(unix)]
+fn set_low_priority(low: bool) -> Result<(), String> {
+    let nice = if low { 10 } else { 0 };
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error().to_string())
+    }
+}
+
+/// Starts polling; the task runs until the runtime shuts down.
+pub fn spawn_resource_guard(state: Arc<AppState>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut previous = current_usage();
+        let mut last_poll = Instant::now();
+        let mut busy_polls = 0;
+        let mut backoff = TrimBackoff::default();
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let Some(usage) = current_usage() else {
+                continue;
+            };
+            let cpu = previous
+                .map(|before| cpu_percent(before.cpu_time, usage.cpu_time, last_poll.elapsed()))
+                .unwrap_or_default();
+            previous = Some(usage);
+            last_poll = Instant::now();
+
+            let (memory_limit_mb, cpu_limit) = {
+                let config = state.config.lock().unwrap();
+                (config.memory_limit_mb, config.background_cpu_percent)
+            };
+
+            let mut trimmed = false;
+            if backoff.should_trim(&usage, memory_limit_mb) {
+                let evicted = trim_caches(&state);
+                let released = release_working_set();
+                info!(
+                    "working set {} MB is over the {memory_limit_mb} MB limit; dropped {evicted} cached searches{}",
+                    usage.working_set / MB,
+                    if released { " and trimmed the working set" } else { "" },
+                );
+                let after = current_usage().unwrap_or(usage);
+                if backoff.trimmed(&after, memory_limit_mb) {
+                    info!(
+                        "working set still {} MB after trimming; not trimming again until it grows",
+                        after.working_set / MB,
+                    );
+                }
+                trimmed = true;
+            }
+
+            busy_polls = if cpu_limit > 0 && cpu > f64::from(cpu_limit) {
+                busy_polls + 1
+            } else {
+                0
+            };
+            let throttle = busy_polls >= SUSTAINED_POLLS;
+
+            let mut status = state.resources.lock().unwrap();
+            // Outside Windows an unprivileged process may not raise its priority
+            // again, so once lowered it stays that way.
+            if throttle != status.throttled && (throttle || cfg!(windows)) {
+                match set_low_priority(throttle) {
+                    Ok(()) => {
+                        status.throttled = throttle;
+                        if throttle {
+                            info!("CPU at {cpu:.1}% for {SUSTAINED_POLLS} polls; lowered priority");
+                        } else {
+                            info!("CPU back to {cpu:.1}%; restored normal priority");
+                        }
+                    }
+                    Err(err) => warn!("failed to change process priority: {err}"),
+                }
+            }
+            status.usage = usage;
+            status.cpu_percent = cpu;
+            if trimmed {
+                status.trims += 1;
+            }
+        }
+    })
+}
+
+fn over_memory_limit(usage: &ResourceUsage, memory_limit_mb: u64) -> bool {
+    memory_limit_mb > 0 && usage.working_set > memory_limit_mb * MB
+}
+
+/// Holds off trimming after a trim that left the working set over the limit, as
+/// when the indexes alone are bigger, so the search cache is not thrown away on
+/// every poll for nothing.
+#[derive(Debug, Default)]
+struct TrimBackoff {
+    /// The limit and working set after the trim that did not help.
+    held: Option<(u64, u64)>,
+}
+
+impl TrimBackoff {
+    /// Whether to trim now: over the limit, and not held since the last futile
+    /// trim unless the working set has grown or the limit changed since.
+    fn should_trim(&mut self, usage: &ResourceUsage, memory_limit_mb: u64) -> bool {
+        if !over_memory_limit(usage, memory_limit_mb) {
+            self.held = None;
+            return false;
+        }
+        match self.held {
+            Some((limit, working_set))
+                if limit == memory_limit_mb && usage.working_set < working_set + RETRY_GROWTH =>
+            {
+                false
+            }
+            _ => {
+                self.held = None;
+                true
+            }
+        }
+    }
+
+    /// Records the usage read right after a trim; returns whether it was futile.
+    fn trimmed(&mut self, after: &ResourceUsage, memory_limit_mb: u64) -> bool {
+        let futile = over_memory_limit(after, memory_limit_mb);
+        if futile {
+            self.held = Some((memory_limit_mb, after.working_set));
+        }
+        futile
+    }
+}
+
+/// Drops what egg can rebuild on demand and returns how many searches were cached.
+/// The indexes themselves stay, since searching without them means rescanning.
+fn trim_caches(state: &AppState) -> usize {
+    state
+        .search_cache
+        .lock()
+        .map(|mut cache| cache.clear())
+        .unwrap_or_default()
+}
+
+/// CPU used between two readings as a share of one core.
+fn cpu_percent(before: Duration, after: Duration, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        return 0.0;
+    }
+    after.saturating_sub(before).as_secs_f64() / elapsed.as_secs_f64() * 100.0
+}
+
+/// FILETIME durations count 100 ns ticks.
+#[cfg(windows)]
+fn filetime_duration(time: FILETIME) -> Duration {
+    let ticks = (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime);
+    Duration::from_nanos(ticks.saturating_mul(100))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::state::CachedSearch;
+
+    #[test]
+    fn limits_and_cpu_share() {
+        let usage = ResourceUsage {
+            working_set: 300 * MB,
+            ..Default::default()
+        };
+        assert!(over_memory_limit(&usage, 256));
+        assert!(!over_memory_limit(&usage, 512));
+        assert!(!over_memory_limit(&usage, 0));
+
+        let percent = cpu_percent(
+            Duration::from_millis(500),
+            Duration::from_millis(3000),
+            Duration::from_secs(10),
+        );
+        assert!((percent - 25.0).abs() < 1e-9);
+        assert_eq!(
+            cpu_percent(Duration::ZERO, Duration::ZERO, Duration::ZERO),
+            0.0
+        );
+        #[cfg(windows)]
+        assert_eq!(
+            filetime_duration(FILETIME {
+                dwLowDateTime: 10_000_000,
+                dwHighDateTime: 0,
+            }),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn futile_trims_wait_for_the_working_set_to_grow() {
+        let at = |mb: u64| ResourceUsage {
+            working_set: mb * MB,
+            ..Default::default()
+        };
+        let mut backoff = TrimBackoff::default();
+        assert!(backoff.should_trim(&at(300), 256));
+        assert!(backoff.trimmed(&at(290), 256));
+        assert!(!backoff.should_trim(&at(300), 256));
+        assert!(backoff.should_trim(&at(310), 256));
+        assert!(!backoff.trimmed(&at(200), 256));
+
+        // A new limit, or dropping under the old one, clears the hold.
+        assert!(backoff.trimmed(&at(290), 256));
+        assert!(backoff.should_trim(&at(290), 200));
+        assert!(backoff.trimmed(&at(290), 256));
+        assert!(!backoff.should_trim(&at(250), 256));
+        assert!(backoff.should_trim(&at(290), 256));
+    }
+
+    #[test]
+    fn trimming_empties_the_search_cache() {
+        let state = AppState::new();
+        {
+            let mut cache = state.search_cache.lock().unwrap();
+            let stamp = cache.stamp();
+            for key in ["a", "b"] {
+                let value = CachedSearch {
+                    query: key.to_string(),
+                    results: Vec::new(),
+                    pending_actions: HashMap::new(),
+                };
+                cache.insert(key.to_string(), value, Vec::new(), stamp);
+            }
+        }
+        assert_eq!(trim_caches(&state), 2);
+        assert_eq!(state.search_cache.lock().unwrap().len(), 0);
+
+        let status = ResourceStatus {
+            usage: ResourceUsage {
+                working_set: 48 * MB,
+                peak_working_set: 61 * MB,
+                ..Default::default()
+            },
+            trims: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            status.summary(0, 256),
+            "Memory 48 MB (peak 61 MB, limit 256 MB) · CPU 0.0% · 0 cached searches · trimmed 1x"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Rescans apps and bookmarks every `reindex_interval_minutes` in the background,
+//! for changes the index watcher cannot see: programs added to `PATH`, new WSL
+//! distributions, registry entries and the like. Each wait is stretched by up
+//! to a tenth at random, so several egg processes do not rescan at once. Only
+//! an index that changed is replaced, and only its cached searches are dropped.
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+
+use log::{debug, info, warn};
+use tokio::task::JoinHandle;
+
+use crate::{
+    bookmarks,
+    indexer::{build_index, IndexOptions},
+    logging::span,
+    state::AppState,
+};
+
+/// How often a switched-off scheduler checks whether it was switched on.
+const IDLE_POLL: Duration = Duration::from_secs(60);
+
+/// Starts the schedule; the task runs until the runtime shuts down.
+pub fn spawn_reindex_scheduler(state: Arc<AppState>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let minutes = state.config.lock().unwrap().reindex_interval_minutes;
+            if minutes == 0 {
+                tokio::time::sleep(IDLE_POLL).await;
+                continue;
+            }
+            let wait = jittered(Duration::from_secs(minutes * 60), random_seed());
+            debug!("next scheduled reindex in {wait:.0?}");
+            tokio::time::sleep(wait).await;
+            // Turned off while waiting.
+            if state.config.lock().unwrap().reindex_interval_minutes == 0 {
+                continue;
+            }
+            reindex(&state).await;
+        }
+    })
+}
+
+async fn reindex(state: &AppState) {
+    let _span = span("scheduled reindex");
+    let (options, browsers, language) = {
+        let config = state.config.lock().unwrap();
+        (
+            IndexOptions::from_config(&config),
+            config.bookmark_browsers.clone(),
+            config.language,
+        )
+    };
+    let apps = build_index(options).await;
+    let app_count = apps.len();
+    let apps_changed = crate::store_app_index(state, apps);
+    let bookmarks =
+        tokio::task::spawn_blocking(move || bookmarks::load_bookmarks(&browsers, language)).await;
+    let (bookmarks_changed, bookmark_count) = match bookmarks {
+        Ok(bookmarks) => {
+            let count = bookmarks.len();
+            (crate::store_bookmark_index(state, bookmarks), count)
+        }
+        Err(err) => {
+            warn!("scheduled bookmark reindex failed: {err}");
+            (false, 0)
+        }
+    };
+    let mut updated = Vec::new();
+    if apps_changed {
+        updated.push(format!("{app_count} apps"));
+    }
+    if bookmarks_changed {
+        updated.push(format!("{bookmark_count} bookmarks"));
+    }
+    if !updated.is_empty() {
+        info!(
+            "scheduled reindex updated the index: {}",
+            updated.join(", ")
+        );
+    }
+}
+
+/// `interval` plus up to a tenth of it, picked by `seed`.
+fn jittered(interval: Duration, seed: u64) -> Duration {
+    let spread = interval.as_millis() / 10;
+    let extra = match spread {
+        0 => 0,
+        spread => u128::from(seed) % (spread + 1),
+    };
+    interval + Duration::from_millis(u64::try_from(extra).unwrap_or(0))
+}
+
+/// A fresh random number; std seeds every `RandomState` from the OS.
+fn random_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waits_are_stretched_by_at_most_a_tenth() {
+        let hour = Duration::from_secs(3_600);
+        assert_eq!(jittered(hour, 0), hour);
+        assert_eq!(jittered(hour, 360_000), hour + Duration::from_secs(360));
+        for _ in 0..100 {
+            let wait = jittered(hour, random_seed());
+            assert!(wait >= hour && wait <= hour + Duration::from_secs(360));
+        }
+        assert_eq!(
+            jittered(Duration::from_millis(5), 7),
+            Duration::from_millis(5)
+        );
+    }
+}
@@ -1,24 +1,57 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, cmp::Reverse, collections::HashMap, fs};
 
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 
 use crate::{
+    bangs::{bang_query, resolve_bang},
     bookmarks::BookmarkEntry,
-    config::AppConfig,
-    models::{AppType, ApplicationInfo, SearchResult},
-    state::PendingAction,
+    builtin_commands::builtin_matches,
+    config::{AliasTarget, AppConfig, CommandShell, QueryPrefixes, SuggestionEngine},
+    emoji::{Emoji, EMOJI},
+    git_repos::is_git_repo,
+    history::{unix_now, LaunchHistory},
+    locale::{fill, messages, Messages},
+    models::{
+        AppType, ApplicationInfo, IndexedItem, MatchSource, ProcessInfo, ResultCategory,
+        SearchResult, WindowInfo,
+    },
+    notes::{note_query, notes_path},
+    plugins::{PluginCall, PluginManifest, PluginResult},
+    ranking::RankingWeights,
+    settings_pages::{SettingsPage, SETTINGS_PAGES, SETTINGS_PAGES_AVAILABLE},
+    snippets,
+    state::{GenerationToken, PendingAction},
+    system_commands::SystemCommand,
+    todo::{open_tasks, todo_path, todo_query},
+    trigram_index::TrigramIndex,
+    uninstallers::asks_for_uninstaller,
+    workspaces::{find_workspace, resolve_members},
+    wsl::{wsl_query, WSL_AVAILABLE},
 };
 
 const MIN_RESULT_LIMIT: u32 = 10;
 const MAX_RESULT_LIMIT: u32 = 60;
+/// Built-in `egg` commands are pinned above every scored match.
+const BUILTIN_SCORE: i64 = 10_000;
+/// Added to pinned results, lifting them above every other match but below
+/// builtin commands.
+const PINNED_BOOST: i64 = 5_000;
+/// Score of an alias typed in full: above pinned matches, below builtin commands.
+const ALIAS_SCORE: i64 = 8_000;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum QueryMode {
+/// Which sources a query searches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueryMode {
+    #[default]
     All,
     Bookmark,
     Application,
     Search,
+    /// Open windows, answered by the window switcher instead of the indexes.
+    Window,
+    /// Running processes, to end one.
+    Process,
 }
 
 impl QueryMode {
@@ -31,10 +64,24 @@ impl QueryMode {
             Some("bookmark") | Some("bookmarks") | Some("b") => Self::Bookmark,
             Some("app") | Some("apps") | Some("application") | Some("r") => Self::Application,
             Some("search") | Some("s") => Self::Search,
+            Some("window") | Some("windows") | Some("w") => Self::Window,
+            Some("process") | Some("processes") | Some("kill") => Self::Process,
             _ => Self::All,
         }
     }
 
+    /// Shown in the TUI header while a prefix limits the query.
+    pub fn label(self, text: &Messages) -> Option<&'static str> {
+        match self {
+            Self::All => None,
+            Self::Bookmark => Some(text.provider_bookmarks),
+            Self::Application => Some(text.provider_apps),
+            Self::Search => Some(text.mode_web_search),
+            Self::Window => Some(text.mode_windows),
+            Self::Process => Some(text.mode_processes),
+        }
+    }
+
     fn allows_bookmarks(&self) -> bool {
         matches!(self, Self::All | Self::Bookmark)
     }
@@ -48,92 +95,405 @@ impl QueryMode {
     }
 }
 
+/// Splits a leading source prefix (`b github`) off `query`. The prefix only counts
+/// when followed by whitespace, so typing `b` alone still searches for "b".
+pub fn split_mode_prefix<'a>(query: &'a str, prefixes: &QueryPrefixes) -> (QueryMode, &'a str) {
+    let query = query.trim_start();
+    let Some((head, rest)) = query.split_once(char::is_whitespace) else {
+        return (QueryMode::All, query);
+    };
+    [
+        (&prefixes.apps, QueryMode::Application),
+        (&prefixes.bookmarks, QueryMode::Bookmark),
+        (&prefixes.search, QueryMode::Search),
+        (&prefixes.windows, QueryMode::Window),
+        (&prefixes.processes, QueryMode::Process),
+    ]
+    .into_iter()
+    .find(|(prefix, _)| !prefix.is_empty() && head.eq_ignore_ascii_case(prefix))
+    .map_or((QueryMode::All, query), |(_, mode)| {
+        (mode, rest.trim_start())
+    })
+}
+
+/// Index entries a query matched, before `max_results` cut them down. Every
+/// entry a longer query matches is among them, so [`refine_search`] re-scores
+/// only these instead of scanning the whole index.
+///
+/// Positions are only meaningful for the indexes they were taken from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Candidates {
+    tokens: Vec<String>,
+    /// Positions in the app index; `None` when apps were not searched.
+    apps: Option<Vec<usize>>,
+    /// Positions in the bookmark index; `None` when bookmarks were not searched.
+    bookmarks: Option<Vec<usize>>,
+    /// Found among the trigram index's hits only. A longer query can match
+    /// entries outside them, so these are not reused.
+    from_trigrams: bool,
+}
+
+impl Candidates {
+    /// Whether `tokens` only extend the query these were found for: earlier
+    /// tokens unchanged, the last one grown, and maybe new tokens after it.
+    /// A fuzzy match for a longer token is also one for its prefix.
+    fn narrowed_by(&self, tokens: &[&str]) -> bool {
+        let Some((last, earlier)) = self.tokens.split_last() else {
+            return false;
+        };
+        tokens.len() > earlier.len()
+            && earlier.iter().zip(tokens).all(|(old, new)| old == new)
+            && tokens[earlier.len()].starts_with(last.as_str())
+    }
+}
+
+/// Positions to scan, `None` for all: the prior candidates narrowed to the
+/// trigram hits, so a refined search scans what a fresh one would.
+fn subset<'a>(
+    prior: Option<&'a [usize]>,
+    trigrams: Option<&'a [usize]>,
+) -> Option<Cow<'a, [usize]>> {
+    match (prior, trigrams) {
+        (Some(prior), Some(trigrams)) => Some(Cow::Owned(
+            prior
+                .iter()
+                .copied()
+                .filter(|position| trigrams.binary_search(position).is_ok())
+                .collect(),
+        )),
+        (prior, trigrams) => prior.or(trigrams).map(Cow::Borrowed),
+    }
+}
+
+/// `index` entries with their positions; only those at `subset` when given.
+fn scan<'a, T>(
+    index: &'a [T],
+    subset: Option<&'a [usize]>,
+) -> Box<dyn Iterator<Item = (usize, &'a T)> + 'a> {
+    match subset {
+        Some(positions) => Box::new(
+            positions
+                .iter()
+                .filter_map(|&position| index.get(position).map(|entry| (position, entry))),
+        ),
+        None => Box::new(index.iter().enumerate()),
+    }
+}
+
+/// The indexes a search reads.
+#[derive(Clone, Copy)]
+pub struct SearchIndexes<'a> {
+    pub apps: &'a [ApplicationInfo],
+    pub bookmarks: &'a [BookmarkEntry],
+    /// Built over `apps` and `bookmarks` for large catalogs.
+    pub trigrams: Option<&'a TrigramIndex>,
+}
+
+impl<'a> SearchIndexes<'a> {
+    pub fn new(apps: &'a [ApplicationInfo], bookmarks: &'a [BookmarkEntry]) -> Self {
+        Self {
+            apps,
+            bookmarks,
+            trigrams: None,
+        }
+    }
+
+    pub fn with_trigrams(self, trigrams: Option<&'a TrigramIndex>) -> Self {
+        Self { trigrams, ..self }
+    }
+}
+
 /// Core search function - extracted from submit_query command
-/// Returns (results, pending_actions)
-pub fn search(
+/// Returns (results, pending_actions, candidates)
+///
+/// Without an explicit `mode`, a prefix from `config.query_prefixes` picks one.
+/// Scans the entries the trigram index finds, or the full indexes without one or
+/// when it finds fewer than `max_results`. When the query extends the one `prior`
+/// was found for in a full scan, only those of them are re-scored, so the results
+/// are the same either way. The returned candidates go with the next query and
+/// are `None` when nothing was scanned. Once `token` goes stale the scan stops
+/// and nothing is returned.
+pub fn refine_search(
     query: String,
     mode: Option<String>,
-    app_index: &[ApplicationInfo],
-    bookmark_index: &[BookmarkEntry],
+    prior: Option<&Candidates>,
+    indexes: SearchIndexes<'_>,
+    history: &LaunchHistory,
     config: &AppConfig,
-) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    token: &GenerationToken,
+) -> (
+    Vec<SearchResult>,
+    HashMap<String, PendingAction>,
+    Option<Candidates>,
+) {
     let trimmed = query.trim();
     if trimmed.is_empty() {
-        return (Vec::new(), HashMap::new());
+        return (Vec::new(), HashMap::new(), None);
     }
+    if let Some(command) = command_query(trimmed) {
+        let (results, pending_actions) = command_results(command, config.command_shell, config);
+        return (results, pending_actions, None);
+    }
+    if let Some(command) = wsl_query(trimmed).filter(|_| WSL_AVAILABLE) {
+        let (results, pending_actions) = command_results(command, CommandShell::Wsl, config);
+        return (results, pending_actions, None);
+    }
+    if let Some(search) = emoji_query(trimmed) {
+        let (results, pending_actions) = emoji_results(search, history, config);
+        return (results, pending_actions, None);
+    }
+    if let Some(note) = note_query(trimmed) {
+        let (results, pending_actions) = note_results(note, config);
+        return (results, pending_actions, None);
+    }
+    if let Some(search) = todo_query(trimmed) {
+        let (results, pending_actions) = todo_results(search, config);
+        return (results, pending_actions, None);
+    }
+    if let Some((result, action)) = bang_result(trimmed, config) {
+        let pending_actions = HashMap::from([(result.id.clone(), action)]);
+        return (vec![result], pending_actions, None);
+    }
+    let (query_mode, trimmed) = match mode {
+        Some(mode) => (QueryMode::from_option(Some(mode)), trimmed),
+        None => split_mode_prefix(trimmed, &config.query_prefixes),
+    };
     let tokens = tokenize_query(trimmed);
     if tokens.is_empty() {
-        return (Vec::new(), HashMap::new());
+        return (Vec::new(), HashMap::new(), None);
     }
+    let prior = prior.filter(|prior| !prior.from_trigrams && prior.narrowed_by(&tokens));
+    let SearchIndexes {
+        apps: app_index,
+        bookmarks: bookmark_index,
+        trigrams,
+    } = indexes;
+    let mut candidates = Candidates {
+        tokens: tokens.iter().map(|token| token.to_string()).collect(),
+        apps: None,
+        bookmarks: None,
+        from_trigrams: false,
+    };
+    // Quotes only group tokens; whole-query bonuses compare against the bare text.
+    let match_query = trimmed.replace('"', "");
+    let scoring = ScoringQuery::new(&match_query, &tokens);
 
-    let query_mode = QueryMode::from_option(mode);
+    let weights = config.ranking();
+    let text = messages(config.language);
+    let now = unix_now();
     let include_apps = config.enable_app_results;
     let include_bookmarks = config.enable_bookmark_results;
     let mut result_limit = config.max_results.clamp(MIN_RESULT_LIMIT, MAX_RESULT_LIMIT) as usize;
     if result_limit == 0 {
         result_limit = MIN_RESULT_LIMIT as usize;
     }
+    // Fuzzy subsequence matches such as `ntpd` share no trigram with the entry,
+    // so when the index finds too few entries to fill the list, scan them all.
+    let (trigram_apps, trigram_bookmarks) = trigrams
+        .and_then(|trigrams| trigrams.candidates(&tokens))
+        .filter(|(apps, bookmarks)| apps.len() + bookmarks.len() >= result_limit)
+        .unzip();
+    candidates.from_trigrams = trigram_apps.is_some();
 
     let mut results = Vec::new();
     let mut counter = 0usize;
     let mut pending_actions: HashMap<String, PendingAction> = HashMap::new();
 
-    if is_url_like(trimmed) {
+    if let Some(path) = typed_path(trimmed) {
+        // Pasted paths contain dots, so they must not fall through to the URL check.
+        if let Ok(metadata) = fs::metadata(path) {
+            let files_percent = config.source_weights.files.max(0);
+            for (result, action) in path_results(path, metadata.is_dir(), text) {
+                pending_actions.insert(result.id.clone(), action);
+                results.push(SearchResult {
+                    score: result.score * files_percent / 100,
+                    ..result
+                });
+                counter += 1;
+            }
+        }
+    } else if is_url_like(trimmed) {
         let result_id = format!("url-{counter}");
         pending_actions.insert(result_id.clone(), PendingAction::Url(trimmed.to_string()));
         results.push(SearchResult {
             id: result_id,
-            title: format!("打开网址: {trimmed}"),
+            title: fill(text.open_url, &[trimmed]),
             subtitle: trimmed.to_string(),
             score: 200,
             action_id: "url".to_string(),
+            match_source: None,
+        });
+        counter += 1;
+    }
+
+    for (index, command) in builtin_matches(trimmed)
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+    {
+        let result_id = format!("builtin-{}", command.id());
+        pending_actions.insert(result_id.clone(), PendingAction::Builtin(command));
+        let subtitle = match command.shortcut() {
+            Some(key) => format!(
+                "egg {} · {key} · {}",
+                command.id(),
+                command.description(config.language)
+            ),
+            None => format!(
+                "egg {} · {}",
+                command.id(),
+                command.description(config.language)
+            ),
+        };
+        results.push(SearchResult {
+            id: result_id,
+            title: command.display_title(config.language).to_string(),
+            subtitle,
+            score: BUILTIN_SCORE - index as i64,
+            action_id: "builtin".to_string(),
+            match_source: None,
         });
         counter += 1;
     }
 
+    let mut aliased = None;
+    if query_mode.allows_applications() {
+        if let Some((result, action)) = alias_result(trimmed, app_index, config) {
+            aliased = Some(result.id.clone());
+            pending_actions.insert(result.id.clone(), action);
+            results.push(result);
+            counter += 1;
+        }
+        if let Some((result, action)) = workspace_result(trimmed, app_index, bookmark_index, config)
+        {
+            pending_actions.insert(result.id.clone(), action);
+            results.push(result);
+            counter += 1;
+        }
+    }
+
     let matcher = SkimMatcherV2::default();
 
     if query_mode.allows_applications() && include_apps {
-        for app in app_index.iter() {
-            if let Some(score) = match_application(&matcher, app, trimmed, &tokens) {
+        let app_weights = weights.for_source(config.source_weights.apps);
+        let wants_uninstallers = asks_for_uninstaller(&tokens);
+        let mut matched = Vec::new();
+        let subset = subset(
+            prior.and_then(|prior| prior.apps.as_deref()),
+            trigram_apps.as_deref(),
+        );
+        for (position, app) in scan(app_index, subset.as_deref()) {
+            if token.is_stale() {
+                return (Vec::new(), HashMap::new(), None);
+            }
+            let result_id = format!("app-{}", app.id);
+            if is_hidden(config, &result_id)
+                || (!wants_uninstallers && app.app_type == AppType::Uninstaller)
+            {
+                continue;
+            }
+            // The alias row stands in for its target, which a longer query may match.
+            if aliased.as_ref() == Some(&result_id) {
+                matched.push(position);
+                continue;
+            }
+            let frecency = history.frecency(&result_id, now);
+            if let Some((score, source)) =
+                match_application(&matcher, &app_weights, app, &scoring, frecency)
+            {
                 counter += 1;
-                let result_id = format!("app-{}", app.id);
+                matched.push(position);
+                let score = score + pin_boost(config, &result_id);
                 pending_actions.insert(result_id.clone(), PendingAction::Application(app.clone()));
-                let subtitle = app
-                    .path
-                    .clone();
-                results.push(SearchResult {
-                    id: result_id,
-                    title: app.name.clone(),
-                    subtitle,
-                    score,
-                    action_id: match app.app_type {
-                        AppType::Win32 => "app".to_string(),
-                        AppType::Uwp => "uwp".to_string(),
-                    },
-                });
+                results.push(application_result(app, result_id, score, Some(source)));
             }
         }
+        candidates.apps = Some(matched);
     }
 
     if query_mode.allows_bookmarks() && include_bookmarks {
-        for bookmark in bookmark_index.iter() {
-            if let Some(score) = match_bookmark(&matcher, bookmark, trimmed, &tokens) {
+        let mut matched = Vec::new();
+        let subset = subset(
+            prior.and_then(|prior| prior.bookmarks.as_deref()),
+            trigram_bookmarks.as_deref(),
+        );
+        let bookmark_weights = weights.for_source(config.source_weights.bookmarks);
+        for (position, bookmark) in scan(bookmark_index, subset.as_deref()) {
+            if token.is_stale() {
+                return (Vec::new(), HashMap::new(), None);
+            }
+            let result_id = format!("bookmark-{}", bookmark.id);
+            if is_hidden(config, &result_id) {
+                continue;
+            }
+            let frecency = history.frecency(&result_id, now);
+            if let Some((score, source)) =
+                match_bookmark(&matcher, &bookmark_weights, bookmark, &scoring, frecency)
+            {
                 counter += 1;
-                let subtitle = match &bookmark.folder_path {
-                    Some(path) => format!("收藏夹 · {path} · {}", bookmark.url),
-                    None => format!("收藏夹 · {}", bookmark.url),
-                };
-                let result_id = format!("bookmark-{}", bookmark.id);
+                matched.push(position);
+                let score = score + pin_boost(config, &result_id);
                 pending_actions
                     .insert(result_id.clone(), PendingAction::Bookmark(bookmark.clone()));
-                results.push(SearchResult {
-                    id: result_id,
-                    title: bookmark.title.clone(),
-                    subtitle,
+                results.push(bookmark_result(
+                    bookmark,
+                    result_id,
                     score,
-                    action_id: "bookmark".to_string(),
-                });
+                    Some(source),
+                    text,
+                ));
+            }
+        }
+        candidates.bookmarks = Some(matched);
+    }
+
+    if query_mode.allows_applications() && config.enable_system_commands {
+        for command in SystemCommand::ALL {
+            let result_id = format!("system-{}", command.id());
+            let frecency = history.frecency(&result_id, now);
+            if let Some((score, source)) =
+                match_system_command(&matcher, &weights, command, &scoring, frecency)
+            {
+                counter += 1;
+                pending_actions.insert(result_id.clone(), PendingAction::System(command));
+                results.push(system_result(command, score, Some(source), config));
+            }
+        }
+    }
+
+    if query_mode.allows_applications() && config.enable_system_commands && SETTINGS_PAGES_AVAILABLE
+    {
+        for page in SETTINGS_PAGES {
+            let result_id = format!("settings-{}", page.id());
+            if is_hidden(config, &result_id) {
+                continue;
+            }
+            let frecency = history.frecency(&result_id, now);
+            if let Some((score, source)) =
+                match_settings_page(&matcher, &weights, page, &scoring, frecency)
+            {
+                counter += 1;
+                let score = score + pin_boost(config, &result_id);
+                pending_actions.insert(result_id.clone(), PendingAction::Url(page.target.into()));
+                results.push(settings_result(page, score, Some(source), config));
+            }
+        }
+    }
+
+    if query_mode == QueryMode::All {
+        for (name, body) in &config.snippets {
+            let result_id = format!("snippet-{name}");
+            if is_hidden(config, &result_id) {
+                continue;
+            }
+            let frecency = history.frecency(&result_id, now);
+            if let Some((score, source)) =
+                match_snippet(&matcher, &weights, name, &scoring, frecency)
+            {
+                counter += 1;
+                let score = score + pin_boost(config, &result_id);
+                pending_actions.insert(result_id.clone(), PendingAction::Snippet(body.clone()));
+                results.push(snippet_result(name, body, score, Some(source)));
             }
         }
     }
@@ -145,200 +505,3078 @@ pub fn search(
         results.truncate(result_limit);
     }
 
-    if query_mode.allows_web_search() {
-        let search_id = format!("search-{counter}");
-        let search_url = format!(
-            "https://google.com/search?q={}",
-            urlencoding::encode(trimmed)
+    if results.is_empty() && config.typo_matches && query_mode.allows_applications() && include_apps
+    {
+        for (result, action) in typo_matches(&tokens, app_index, history, config, now)
+            .into_iter()
+            .take(result_limit.saturating_sub(1).max(1))
+        {
+            counter += 1;
+            pending_actions.insert(result.id.clone(), action);
+            results.push(result);
+        }
+    }
+
+    if results.is_empty() && query_mode == QueryMode::All {
+        append_fallbacks(
+            &mut results,
+            &mut pending_actions,
+            trimmed,
+            &tokens,
+            app_index,
+            bookmark_index,
+            config,
         );
-        pending_actions.insert(search_id.clone(), PendingAction::Search(search_url.clone()));
+    } else if query_mode.allows_web_search() {
+        let (result, action) = web_search_result(WEB_SEARCH_ENGINES[0], trimmed, counter, text);
+        pending_actions.insert(result.id.clone(), action);
         results.push(SearchResult {
-            id: search_id,
-            title: format!("在 Google 上搜索: {trimmed}"),
-            subtitle: String::from("Google 搜索"),
             score: i64::MIN,
-            action_id: "search".to_string(),
+            ..result
         });
     }
 
+    if config.group_results {
+        group_by_category(&mut results);
+    }
+    (results, pending_actions, Some(candidates))
+}
+
+/// The section a result with `action_id` is listed under.
+pub fn result_category(action_id: &str) -> ResultCategory {
+    match action_id {
+        "app" | "uwp" | "pwa" | "uninstall" | "repo" | "workspace" => ResultCategory::Applications,
+        "bookmark" => ResultCategory::Bookmarks,
+        "file" => ResultCategory::Files,
+        "url" | "search" | "suggest" => ResultCategory::Web,
+        _ => ResultCategory::Other,
+    }
+}
+
+/// Gathers results of the same category together. Categories keep the order of
+/// their best-ranked result, and results keep their rank within a category, so
+/// the top result stays first.
+pub fn group_by_category(results: &mut [SearchResult]) {
+    let mut order: Vec<ResultCategory> = Vec::new();
+    for result in results.iter() {
+        let category = result_category(&result.action_id);
+        if !order.contains(&category) {
+            order.push(category);
+        }
+    }
+    results.sort_by_key(|result| {
+        let category = result_category(&result.action_id);
+        order.iter().position(|known| *known == category)
+    });
+}
+
+/// [`refine_search`] with no earlier candidates, scanning the full indexes.
+#[cfg(test)]
+pub fn search(
+    query: String,
+    mode: Option<String>,
+    app_index: &[ApplicationInfo],
+    bookmark_index: &[BookmarkEntry],
+    history: &LaunchHistory,
+    config: &AppConfig,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let (results, pending_actions, _) = refine_search(
+        query,
+        mode,
+        None,
+        SearchIndexes::new(app_index, bookmark_index),
+        history,
+        config,
+        &GenerationToken::detached(),
+    );
     (results, pending_actions)
 }
 
-fn is_url_like(input: &str) -> bool {
-    input.starts_with("http://")
-        || input.starts_with("https://")
-        || input.contains('.') && input.split_whitespace().count() == 1
+/// Name and query URL prefix; the first engine is the one offered on every search.
+const WEB_SEARCH_ENGINES: [(&str, &str); 3] = [
+    ("Google", "https://google.com/search?q="),
+    ("Bing", "https://www.bing.com/search?q="),
+    ("DuckDuckGo", "https://duckduckgo.com/?q="),
+];
+
+fn web_search_result(
+    (name, url_prefix): (&str, &str),
+    query: &str,
+    counter: usize,
+    text: &Messages,
+) -> (SearchResult, PendingAction) {
+    let search_url = format!("{url_prefix}{}", urlencoding::encode(query));
+    let result = SearchResult {
+        id: format!("search-{counter}"),
+        title: fill(text.search_with, &[name, query]),
+        subtitle: fill(text.web_search, &[name]),
+        score: 0,
+        action_id: "search".to_string(),
+        match_source: None,
+    };
+    (result, PendingAction::Search(search_url))
 }
 
-fn match_application(
-    matcher: &SkimMatcherV2,
-    app: &ApplicationInfo,
+/// The row for a query with a known bang, going straight to the site instead
+/// of the default web search.
+fn bang_result(query: &str, config: &AppConfig) -> Option<(SearchResult, PendingAction)> {
+    let (bang, terms) = bang_query(query)?;
+    let (site, url) = resolve_bang(bang, terms, config)?;
+    let text = messages(config.language);
+    let title = if terms.is_empty() {
+        fill(text.open_url, &[&url])
+    } else {
+        fill(text.search_with, &[&site, terms])
+    };
+    let result = SearchResult {
+        id: format!("bang-{}", bang.to_lowercase()),
+        title,
+        subtitle: url.clone(),
+        score: BUILTIN_SCORE,
+        action_id: "search".to_string(),
+        match_source: None,
+    };
+    Some((result, PendingAction::Search(url)))
+}
+
+/// A row for a query suggestion listed under the web search row, searching
+/// `engine` for it.
+pub fn web_suggestion_result(
+    engine: SuggestionEngine,
+    suggestion: &str,
+    index: usize,
+    text: &Messages,
+) -> (SearchResult, PendingAction) {
+    let url_prefix = WEB_SEARCH_ENGINES
+        .iter()
+        .find(|(name, _)| *name == engine.label())
+        .map_or(WEB_SEARCH_ENGINES[0].1, |(_, url_prefix)| *url_prefix);
+    let result = SearchResult {
+        id: format!("web-suggestion-{index}"),
+        title: suggestion.to_string(),
+        subtitle: fill(text.web_suggestion, &[engine.label()]),
+        score: i64::MIN,
+        action_id: "search".to_string(),
+        match_source: None,
+    };
+    let search_url = format!("{url_prefix}{}", urlencoding::encode(suggestion));
+    (result, PendingAction::Search(search_url))
+}
+
+const FALLBACK_ID_PREFIX: &str = "fallback-";
+
+/// Whether `result` is a zero-result fallback row rather than a provider hit.
+pub fn is_fallback(result: &SearchResult) -> bool {
+    result.id.starts_with(FALLBACK_ID_PREFIX)
+}
+
+/// Whether `result` was built from the whole app and bookmark indexes, like the
+/// spelling suggestion or a workspace's member list, so any change to them can
+/// change it.
+pub fn reads_whole_index(result: &SearchResult) -> bool {
+    is_fallback(result) || result.action_id == "workspace"
+}
+
+/// Whether a search for `query` could list one of `apps` or `bookmarks`: as a
+/// fuzzy or typo match, an alias target or a workspace member. Errs towards yes;
+/// hidden entries and uninstallers count as matches too.
+pub fn could_match(
     query: &str,
-    tokens: &[&str],
-) -> Option<i64> {
-    let mut fields = Vec::new();
-    fields.push(Field::new(&app.name, 120, true));
-    for keyword in &app.keywords {
-        if keyword.is_empty() {
-            continue;
-        }
-        fields.push(Field::new(keyword.as_str(), 70, false));
+    apps: &[ApplicationInfo],
+    bookmarks: &[BookmarkEntry],
+    config: &AppConfig,
+) -> bool {
+    if apps.is_empty() && bookmarks.is_empty() {
+        return false;
     }
-    if let Some(pinyin_index) = &app.pinyin_index {
-        for entry in pinyin_index.split_whitespace() {
-            let (full, initials) = split_pinyin_entry(entry);
-            if let Some(full) = full {
-                fields.push(Field::new(full, 85, false));
-            }
-            if let Some(initials) = initials {
-                fields.push(Field::new(initials, 95, false));
+    let (query_mode, trimmed) = split_mode_prefix(query.trim(), &config.query_prefixes);
+    let tokens = tokenize_query(trimmed);
+    if tokens.is_empty() {
+        return false;
+    }
+    if find_workspace(trimmed, config).is_some() {
+        return true;
+    }
+    let match_query = trimmed.replace('"', "");
+    let scoring = ScoringQuery::new(&match_query, &tokens);
+    let weights = config.ranking();
+    let matcher = SkimMatcherV2::default();
+
+    if query_mode.allows_applications() && config.enable_app_results {
+        if let Some((_, AliasTarget::App(app_id))) = find_alias(trimmed, config) {
+            if apps.iter().any(|app| app.id == *app_id) {
+                return true;
             }
         }
+        let app_weights = weights.for_source(config.source_weights.apps);
+        if apps
+            .iter()
+            .any(|app| match_application(&matcher, &app_weights, app, &scoring, 0).is_some())
+        {
+            return true;
+        }
+        if config.typo_matches
+            && !typo_matches(&tokens, apps, &LaunchHistory::default(), config, 0).is_empty()
+        {
+            return true;
+        }
     }
-
-    score_fields(matcher, query, tokens, &fields)
+    if query_mode.allows_bookmarks() && config.enable_bookmark_results {
+        let bookmark_weights = weights.for_source(config.source_weights.bookmarks);
+        return bookmarks.iter().any(|bookmark| {
+            match_bookmark(&matcher, &bookmark_weights, bookmark, &scoring, 0).is_some()
+        });
+    }
+    false
 }
 
-fn match_bookmark(
-    matcher: &SkimMatcherV2,
-    bookmark: &BookmarkEntry,
+/// Rows offered when nothing matched, in order: a spelling suggestion, every web
+/// search engine, and a file search when a file provider is enabled.
+fn append_fallbacks(
+    results: &mut Vec<SearchResult>,
+    pending_actions: &mut HashMap<String, PendingAction>,
     query: &str,
     tokens: &[&str],
-) -> Option<i64> {
-    let mut fields = Vec::new();
-    fields.push(Field::new(&bookmark.title, 110, true));
-    if let Some(path) = &bookmark.folder_path {
-        fields.push(Field::new(path.as_str(), 65, false));
+    app_index: &[ApplicationInfo],
+    bookmark_index: &[BookmarkEntry],
+    config: &AppConfig,
+) {
+    let text = messages(config.language);
+    let mut rows = Vec::new();
+    if let Some(suggestion) = spelling_suggestion(tokens, app_index, bookmark_index) {
+        rows.push((
+            SearchResult {
+                id: format!("{FALLBACK_ID_PREFIX}suggestion"),
+                title: fill(text.did_you_mean, &[&suggestion]),
+                subtitle: text.suggestion_hint.to_string(),
+                score: 0,
+                action_id: "suggest".to_string(),
+                match_source: None,
+            },
+            PendingAction::Query(suggestion),
+        ));
     }
-    fields.push(Field::new(&bookmark.url, 45, false));
-    for keyword in &bookmark.keywords {
-        if keyword.is_empty() {
-            continue;
-        }
-        fields.push(Field::new(keyword.as_str(), 55, false));
+    for (index, engine) in WEB_SEARCH_ENGINES.into_iter().enumerate() {
+        rows.push(web_search_result(engine, query, index, text));
     }
-    if let Some(pinyin_index) = &bookmark.pinyin_index {
-        for entry in pinyin_index.split_whitespace() {
-            let (full, initials) = split_pinyin_entry(entry);
-            if let Some(full) = full {
-                fields.push(Field::new(full, 80, false));
-            }
-            if let Some(initials) = initials {
-                fields.push(Field::new(initials, 90, false));
-            }
-        }
+    if config.enable_everything || config.enable_windows_search {
+        rows.push((
+            SearchResult {
+                id: format!("{FALLBACK_ID_PREFIX}files"),
+                title: fill(text.search_files, &[query]),
+                subtitle: format!("{FILE_PREFIX} {query}"),
+                score: 0,
+                action_id: "file".to_string(),
+                match_source: None,
+            },
+            PendingAction::Query(format!("{FILE_PREFIX} {query}")),
+        ));
+    }
+    for (position, (result, action)) in rows.into_iter().enumerate() {
+        pending_actions.insert(result.id.clone(), action);
+        results.push(SearchResult {
+            score: -(position as i64),
+            ..result
+        });
     }
-
-    score_fields(matcher, query, tokens, &fields)
 }
 
-fn split_pinyin_entry(entry: &str) -> (Option<&str>, Option<&str>) {
-    if let Some((full, initials)) = entry.split_once('|') {
-        (
-            if full.is_empty() { None } else { Some(full) },
-            if initials.is_empty() {
-                None
-            } else {
-                Some(initials)
-            },
+/// Rewrites misspelled tokens to the closest word from app names and bookmark
+/// titles. Returns `None` when no token needed correcting.
+fn spelling_suggestion(
+    tokens: &[&str],
+    app_index: &[ApplicationInfo],
+    bookmark_index: &[BookmarkEntry],
+) -> Option<String> {
+    let mut vocabulary: Vec<String> = app_index
+        .iter()
+        .map(|app| app.name.as_str())
+        .chain(
+            bookmark_index
+                .iter()
+                .map(|bookmark| bookmark.title.as_str()),
         )
-    } else if entry.is_empty() {
-        (None, None)
+        .flat_map(|text| text.split(|ch: char| !ch.is_alphanumeric()))
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect();
+    vocabulary.sort();
+    vocabulary.dedup();
+
+    let mut corrected = false;
+    let words: Vec<String> = tokens
+        .iter()
+        .map(|token| {
+            let token = token.to_lowercase();
+            let len = token.chars().count();
+            if len < 3 || vocabulary.binary_search(&token).is_ok() {
+                return token;
+            }
+            let max_distance = typo_budget(len);
+            let best = vocabulary
+                .iter()
+                .map(|word| (edit_distance(&token, word), word))
+                .filter(|(distance, _)| *distance <= max_distance)
+                .min_by_key(|(distance, word)| (*distance, word.len()));
+            match best {
+                Some((_, word)) => {
+                    corrected = true;
+                    word.clone()
+                }
+                None => token,
+            }
+        })
+        .collect();
+    corrected.then(|| words.join(" "))
+}
+
+/// Edits a misspelled word of `len` characters may be from the intended one.
+fn typo_budget(len: usize) -> usize {
+    if len <= 4 {
+        1
     } else {
-        (Some(entry), None)
+        2
     }
 }
 
-fn update_best(best: &mut Option<i64>, candidate: i64) {
-    if best.is_none_or(|current| candidate > current) {
-        *best = Some(candidate);
+/// Score of a typo match with no edits, below an ordinary name match.
+const TYPO_BASE_SCORE: i64 = 100;
+/// Points a typo match loses per edit.
+const TYPO_PENALTY: i64 = 30;
+
+/// Apps whose name has a word within [`typo_budget`] edits of every token, for
+/// `typo_matches` when the fuzzy pass found nothing. A token may also be a
+/// misspelled start of a word, as in `chrmo`.
+fn typo_matches(
+    tokens: &[&str],
+    app_index: &[ApplicationInfo],
+    history: &LaunchHistory,
+    config: &AppConfig,
+    now: u64,
+) -> Vec<(SearchResult, PendingAction)> {
+    let weights = config.ranking().for_source(config.source_weights.apps);
+    let tokens: Vec<String> = tokens.iter().map(|token| token.to_lowercase()).collect();
+    if tokens.iter().any(|token| token.chars().count() < 3) {
+        return Vec::new();
+    }
+    let mut matches = Vec::new();
+    for app in app_index {
+        let result_id = format!("app-{}", app.id);
+        if app.app_type == AppType::Uninstaller || is_hidden(config, &result_id) {
+            continue;
+        }
+        let name = app.name.to_lowercase();
+        let words: Vec<&str> = name
+            .split(|ch: char| !ch.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let mut edits = 0;
+        for token in &tokens {
+            let Some(distance) = words
+                .iter()
+                .filter_map(|word| word_distance(token, word))
+                .min()
+            else {
+                edits = usize::MAX;
+                break;
+            };
+            edits += distance;
+        }
+        if edits == usize::MAX {
+            continue;
+        }
+        let points = (TYPO_BASE_SCORE - TYPO_PENALTY * edits as i64) * weights.source_percent / 100
+            + history.frecency(&result_id, now) * weights.frecency_percent / 100
+            + pin_boost(config, &result_id);
+        matches.push((
+            application_result(app, result_id, points, Some(MatchSource::Typo)),
+            PendingAction::Application(app.clone()),
+        ));
     }
+    matches.sort_by_key(|(result, _)| Reverse(result.score));
+    matches
 }
 
-#[derive(Clone, Copy)]
-struct Field<'a> {
-    text: &'a str,
-    weight: i64,
-    full_query_boost: bool,
+/// Edits between `token` and `word`, or the start of `word` as long as the
+/// token; `None` beyond the token's budget.
+fn word_distance(token: &str, word: &str) -> Option<usize> {
+    let len = token.chars().count();
+    let budget = typo_budget(len);
+    let word_len = word.chars().count();
+    let whole = (word_len.abs_diff(len) <= budget).then(|| edit_distance(token, word));
+    let start = (word_len > len).then(|| {
+        let start: String = word.chars().take(len).collect();
+        edit_distance(token, &start)
+    });
+    whole
+        .into_iter()
+        .chain(start)
+        .min()
+        .filter(|distance| *distance <= budget)
 }
 
-impl<'a> Field<'a> {
-    fn new(text: &'a str, weight: i64, full_query_boost: bool) -> Self {
-        Self {
-            text,
-            weight,
-            full_query_boost,
+/// Optimal string alignment distance: edits plus adjacent transpositions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
         }
     }
+    rows[a.len()][b.len()]
 }
 
-fn tokenize_query(query: &str) -> Vec<&str> {
-    query
-        .split_whitespace()
-        .filter(|value| !value.is_empty())
-        .collect()
+/// Apps with launch history, highest frecency first, for the empty-query dashboard.
+pub fn frequent_apps(
+    app_index: &[ApplicationInfo],
+    history: &LaunchHistory,
+    limit: usize,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let now = unix_now();
+    let mut scored: Vec<(i64, &ApplicationInfo)> = app_index
+        .iter()
+        .map(|app| (history.frecency(&format!("app-{}", app.id), now), app))
+        .filter(|(frecency, _)| *frecency > 0)
+        .collect();
+    scored.sort_by_key(|(frecency, _)| Reverse(*frecency));
+
+    let mut results = Vec::new();
+    let mut pending_actions = HashMap::new();
+    for (frecency, app) in scored.into_iter().take(limit) {
+        let result_id = format!("app-{}", app.id);
+        pending_actions.insert(result_id.clone(), PendingAction::Application(app.clone()));
+        results.push(application_result(app, result_id, frecency, None));
+    }
+    (results, pending_actions)
 }
 
-fn score_fields(
-    matcher: &SkimMatcherV2,
+/// The alias `query` spells out in full, ignoring case.
+fn find_alias<'a>(query: &str, config: &'a AppConfig) -> Option<(&'a str, &'a AliasTarget)> {
+    config
+        .aliases
+        .iter()
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(query))
+        .map(|(name, target)| (name.as_str(), target))
+}
+
+/// The result for an alias typed in full. App aliases whose app is no longer
+/// indexed yield nothing.
+fn alias_result(
     query: &str,
-    tokens: &[&str],
-    fields: &[Field<'_>],
-) -> Option<i64> {
-    let mut total = 0i64;
-    for token in tokens {
-        let mut best: Option<i64> = None;
-        for field in fields {
-            if let Some(score) = score_token(matcher, field, token) {
-                best = Some(best.map_or(score, |current| current.max(score)));
-            }
+    app_index: &[ApplicationInfo],
+    config: &AppConfig,
+) -> Option<(SearchResult, PendingAction)> {
+    let (name, target) = find_alias(query, config)?;
+    match target {
+        AliasTarget::App(app_id) => {
+            let app = app_index.iter().find(|app| app.id == *app_id)?;
+            let result = application_result(
+                app,
+                format!("app-{}", app.id),
+                ALIAS_SCORE,
+                Some(MatchSource::Alias),
+            );
+            Some((result, PendingAction::Application(app.clone())))
+        }
+        AliasTarget::Command(command) => {
+            let text = messages(config.language);
+            let result = SearchResult {
+                id: format!("alias-{name}"),
+                title: fill(text.run_command, &[command]),
+                subtitle: fill(text.command_alias, &[name, config.command_shell.label()]),
+                score: ALIAS_SCORE,
+                action_id: "command".to_string(),
+                match_source: Some(MatchSource::Alias),
+            };
+            let action = PendingAction::Command {
+                command: command.clone(),
+                shell: config.command_shell,
+                keep_open: config.command_keep_open,
+            };
+            Some((result, action))
         }
-        let Some(best_score) = best else {
-            return None;
-        };
-        total += best_score;
     }
+}
 
-    let query_lower = query.to_ascii_lowercase();
-    let mut bonus = None;
-    for field in fields.iter().filter(|field| field.full_query_boost) {
-        let field_lower = field.text.to_ascii_lowercase();
-        let score = if field_lower == query_lower {
-            140
-        } else if field_lower.starts_with(&query_lower) {
-            70
-        } else if field_lower.contains(&query_lower) {
-            30
-        } else {
-            0
-        };
-        if score > 0 {
-            update_best(&mut bonus, score + field.weight);
-        }
+/// The result for a workspace typed in full, listing the members it launches.
+/// Workspaces whose members all went missing yield nothing.
+fn workspace_result(
+    query: &str,
+    app_index: &[ApplicationInfo],
+    bookmark_index: &[BookmarkEntry],
+    config: &AppConfig,
+) -> Option<(SearchResult, PendingAction)> {
+    let (name, members) = find_workspace(query, config)?;
+    let (resolved, missing) = resolve_members(members, app_index, bookmark_index);
+    if resolved.is_empty() {
+        return None;
     }
-    if let Some(extra) = bonus {
-        total += extra;
+    let text = messages(config.language);
+    let labels: Vec<&str> = resolved.iter().map(|(label, _)| label.as_str()).collect();
+    let mut subtitle = fill(text.workspace_members, &[&labels.join(", ")]);
+    if !missing.is_empty() {
+        subtitle.push_str(" · ");
+        subtitle.push_str(&fill(text.workspace_missing, &[&missing.join(", ")]));
     }
-
-    Some(total)
+    let result = SearchResult {
+        id: format!("workspace-{name}"),
+        title: fill(text.open_workspace, &[name]),
+        subtitle,
+        score: ALIAS_SCORE,
+        action_id: "workspace".to_string(),
+        match_source: None,
+    };
+    let action = PendingAction::Workspace {
+        name: name.to_string(),
+        members: resolved.into_iter().map(|(_, action)| action).collect(),
+    };
+    Some((result, action))
 }
 
-fn score_token(matcher: &SkimMatcherV2, field: &Field<'_>, token: &str) -> Option<i64> {
-    let fuzzy = matcher.fuzzy_match(field.text, token)?;
-    let token_lower = token.to_ascii_lowercase();
-    let field_lower = field.text.to_ascii_lowercase();
-    let mut score = fuzzy + field.weight;
+fn is_hidden(config: &AppConfig, result_id: &str) -> bool {
+    config.hidden_results.iter().any(|id| id == result_id)
+}
 
-    if field_lower == token_lower {
-        score += 30;
-    } else if field_lower.starts_with(&token_lower) {
-        score += 18;
-    } else if field_lower.contains(&token_lower) {
-        score += 8;
+fn pin_boost(config: &AppConfig, result_id: &str) -> i64 {
+    if config.pinned_results.iter().any(|id| id == result_id) {
+        PINNED_BOOST
+    } else {
+        0
     }
+}
 
-    let field_len = field.text.chars().count();
-    let token_len = token.chars().count();
-    let length_penalty = field_len.saturating_sub(token_len) as i64 / 6;
-    Some(score - length_penalty)
+fn application_result(
+    app: &ApplicationInfo,
+    id: String,
+    score: i64,
+    match_source: Option<MatchSource>,
+) -> SearchResult {
+    SearchResult {
+        id,
+        title: app.name.clone(),
+        // Web apps all share the proxy's path, and many uninstallers run
+        // `MsiExec.exe`; their start URL or command line tells them apart. A
+        // repository's path is its editor's.
+        subtitle: match app.app_type {
+            _ if is_git_repo(app) => app
+                .working_directory
+                .clone()
+                .unwrap_or_else(|| app.path.clone()),
+            AppType::Pwa | AppType::Uninstaller => {
+                app.description.clone().unwrap_or_else(|| app.path.clone())
+            }
+            _ => app.path.clone(),
+        },
+        score,
+        action_id: match app.app_type {
+            _ if is_git_repo(app) => "repo".to_string(),
+            AppType::Win32 => "app".to_string(),
+            AppType::Uwp => "uwp".to_string(),
+            AppType::Pwa => "pwa".to_string(),
+            AppType::Uninstaller => "uninstall".to_string(),
+        },
+        match_source,
+    }
+}
+
+fn bookmark_result(
+    bookmark: &BookmarkEntry,
+    id: String,
+    score: i64,
+    match_source: Option<MatchSource>,
+    text: &Messages,
+) -> SearchResult {
+    let subtitle = match &bookmark.folder_path {
+        Some(path) => format!("{} · {path} · {}", text.bookmarks, bookmark.url),
+        None => format!("{} · {}", text.bookmarks, bookmark.url),
+    };
+    SearchResult {
+        id,
+        title: bookmark.title.clone(),
+        subtitle,
+        score,
+        action_id: "bookmark".to_string(),
+        match_source,
+    }
+}
+
+fn system_result(
+    command: SystemCommand,
+    score: i64,
+    match_source: Option<MatchSource>,
+    config: &AppConfig,
+) -> SearchResult {
+    SearchResult {
+        id: format!("system-{}", command.id()),
+        title: command.display_title(config.language).to_string(),
+        subtitle: command.description(config.language).to_string(),
+        score,
+        action_id: "system".to_string(),
+        match_source,
+    }
+}
+
+fn settings_result(
+    page: &SettingsPage,
+    score: i64,
+    match_source: Option<MatchSource>,
+    config: &AppConfig,
+) -> SearchResult {
+    SearchResult {
+        id: format!("settings-{}", page.id()),
+        title: page.display_title(config.language).to_string(),
+        subtitle: page.description(config.language),
+        score,
+        action_id: "settings".to_string(),
+        match_source,
+    }
+}
+
+fn snippet_result(
+    name: &str,
+    body: &str,
+    score: i64,
+    match_source: Option<MatchSource>,
+) -> SearchResult {
+    SearchResult {
+        id: format!("snippet-{name}"),
+        title: name.to_string(),
+        subtitle: snippets::preview(body),
+        score,
+        action_id: "snippet".to_string(),
+        match_source,
+    }
+}
+
+/// The app, bookmark, system command, settings page or snippet a search result id
+/// names, for launching a result by id without searching again.
+pub fn result_for_id(
+    id: &str,
+    app_index: &[ApplicationInfo],
+    bookmark_index: &[BookmarkEntry],
+    config: &AppConfig,
+) -> Option<(SearchResult, PendingAction)> {
+    if let Some(app_id) = id.strip_prefix("app-") {
+        let app = app_index.iter().find(|app| app.id == app_id)?;
+        let result = application_result(app, id.to_string(), 0, None);
+        return Some((result, PendingAction::Application(app.clone())));
+    }
+    if let Some(bookmark_id) = id.strip_prefix("bookmark-") {
+        let bookmark = bookmark_index
+            .iter()
+            .find(|bookmark| bookmark.id == bookmark_id)?;
+        let text = messages(config.language);
+        let result = bookmark_result(bookmark, id.to_string(), 0, None, text);
+        return Some((result, PendingAction::Bookmark(bookmark.clone())));
+    }
+    if let Some(page_id) = id.strip_prefix("settings-") {
+        let page = SETTINGS_PAGES.iter().find(|page| page.id() == page_id)?;
+        let result = settings_result(page, 0, None, config);
+        return Some((result, PendingAction::Url(page.target.to_string())));
+    }
+    if let Some(name) = id.strip_prefix("snippet-") {
+        let body = config.snippets.get(name)?;
+        let result = snippet_result(name, body, 0, None);
+        return Some((result, PendingAction::Snippet(body.clone())));
+    }
+    let command = SystemCommand::ALL
+        .into_iter()
+        .find(|command| id.strip_prefix("system-") == Some(command.id()))?;
+    Some((
+        system_result(command, 0, None, config),
+        PendingAction::System(command),
+    ))
+}
+
+const COMMAND_PREFIX: char = '>';
+const FILE_PREFIX: &str = "file:";
+const EMOJI_PREFIX: char = ':';
+const EMOJI_KEYWORD: &str = "emoji";
+
+/// Returns the shell command when `query` starts with `>`.
+pub fn command_query(query: &str) -> Option<&str> {
+    query
+        .trim_start()
+        .strip_prefix(COMMAND_PREFIX)
+        .map(str::trim)
+}
+
+/// The single row that runs `command` in `shell`.
+fn command_results(
+    command: &str,
+    shell: CommandShell,
+    config: &AppConfig,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    if command.is_empty() {
+        return (Vec::new(), HashMap::new());
+    }
+    let result_id = "command-0".to_string();
+    let mut pending_actions = HashMap::new();
+    pending_actions.insert(
+        result_id.clone(),
+        PendingAction::Command {
+            command: command.to_string(),
+            shell,
+            keep_open: config.command_keep_open,
+        },
+    );
+    let text = messages(config.language);
+    let subtitle = if config.command_keep_open {
+        fill(text.keep_window_open, &[shell.label()])
+    } else {
+        shell.label().to_string()
+    };
+    let results = vec![SearchResult {
+        id: result_id,
+        title: fill(text.run_command, &[command]),
+        subtitle,
+        score: 0,
+        action_id: "command".to_string(),
+        match_source: None,
+    }];
+    (results, pending_actions)
+}
+
+/// The single row that saves `note` to the notes file.
+fn note_results(
+    note: &str,
+    config: &AppConfig,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let Some(path) = notes_path(config) else {
+        return (Vec::new(), HashMap::new());
+    };
+    let path = path.to_string_lossy().into_owned();
+    let text = messages(config.language);
+    let result = SearchResult {
+        id: "note-0".to_string(),
+        title: fill(text.save_note, &[note]),
+        subtitle: fill(text.note_file, &[&path]),
+        score: 0,
+        action_id: "note".to_string(),
+        match_source: None,
+    };
+    let action = PendingAction::AppendToFile {
+        path,
+        text: note.to_string(),
+    };
+    let pending_actions = HashMap::from([(result.id.clone(), action)]);
+    (vec![result], pending_actions)
+}
+
+/// Open tasks containing every token of `search`, highest priority first, after
+/// a row adding `search` as a task unless it is one already.
+fn todo_results(
+    search: &str,
+    config: &AppConfig,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let mut results = Vec::new();
+    let mut pending_actions = HashMap::new();
+    let Some(path) = todo_path(config) else {
+        return (results, pending_actions);
+    };
+    let contents = fs::read_to_string(&path).unwrap_or_default();
+    let path = path.to_string_lossy().into_owned();
+    let text = messages(config.language);
+    let tokens: Vec<String> = tokenize_query(search)
+        .iter()
+        .map(|token| token.replace('"', "").to_lowercase())
+        .collect();
+    let mut tasks: Vec<_> = open_tasks(&contents)
+        .into_iter()
+        .filter(|task| {
+            let line = task.line.to_lowercase();
+            tokens.iter().all(|token| line.contains(token))
+        })
+        .collect();
+    // Stable, so tasks of equal priority keep the file's order.
+    tasks.sort_by_key(|task| (task.priority.is_none(), task.priority));
+
+    let exists = tasks
+        .iter()
+        .any(|task| task.description.eq_ignore_ascii_case(search));
+    if !search.is_empty() && !exists {
+        let result = SearchResult {
+            id: "todo-add".to_string(),
+            title: fill(text.add_task, &[search]),
+            subtitle: fill(text.note_file, &[&path]),
+            score: 0,
+            action_id: "todo".to_string(),
+            match_source: None,
+        };
+        let action = PendingAction::AddTask {
+            path: path.clone(),
+            task: search.to_string(),
+        };
+        pending_actions.insert(result.id.clone(), action);
+        results.push(result);
+    }
+    let limit = config.max_results.clamp(MIN_RESULT_LIMIT, MAX_RESULT_LIMIT) as usize;
+    for (index, task) in tasks.into_iter().take(limit).enumerate() {
+        let mut details = Vec::new();
+        if let Some(priority) = task.priority {
+            details.push(format!("({priority})"));
+        }
+        if let Some(created) = task.created {
+            details.push(fill(text.task_added, &[created]));
+        }
+        let result = SearchResult {
+            id: format!("todo-{index}"),
+            title: task.description.to_string(),
+            subtitle: details.join(" · "),
+            score: 0,
+            action_id: "todo".to_string(),
+            match_source: None,
+        };
+        let action = PendingAction::Task {
+            path: path.clone(),
+            line: task.line.to_string(),
+        };
+        pending_actions.insert(result.id.clone(), action);
+        results.push(result);
+    }
+    (results, pending_actions)
+}
+
+/// Returns the Everything query when `query` uses the `file:` prefix.
+pub fn file_query(query: &str) -> Option<&str> {
+    let trimmed = query.trim_start();
+    let head = trimmed.get(..FILE_PREFIX.len())?;
+    if !head.eq_ignore_ascii_case(FILE_PREFIX) {
+        return None;
+    }
+    Some(trimmed[FILE_PREFIX.len()..].trim())
+}
+
+/// Returns the emoji search when `query` starts with `:` or `emoji `. A closing
+/// colon, as in `:fire:`, is dropped.
+pub fn emoji_query(query: &str) -> Option<&str> {
+    let query = query.trim_start();
+    let search = match query.strip_prefix(EMOJI_PREFIX) {
+        Some(search) => search,
+        None => {
+            let (head, rest) = query.split_once(char::is_whitespace)?;
+            if !head.eq_ignore_ascii_case(EMOJI_KEYWORD) {
+                return None;
+            }
+            rest
+        }
+    };
+    Some(search.trim().trim_end_matches(EMOJI_PREFIX).trim_end())
+}
+
+/// Emoji whose name or keywords have a word starting with every token of
+/// `search`, best first. An empty search lists the start of the table.
+fn emoji_results(
+    search: &str,
+    history: &LaunchHistory,
+    config: &AppConfig,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let limit = config.max_results.clamp(MIN_RESULT_LIMIT, MAX_RESULT_LIMIT) as usize;
+    let tokens = tokenize_query(search);
+    let scoring = ScoringQuery::new(search, &tokens);
+    let matcher = SkimMatcherV2::default();
+    let weights = config.ranking();
+    let now = unix_now();
+    let mut scored: Vec<(i64, &Emoji)> = EMOJI
+        .iter()
+        .filter_map(|emoji| {
+            if tokens.is_empty() {
+                return Some((0, emoji));
+            }
+            let fields = emoji_fields(emoji);
+            let starts_words = tokens.iter().all(|token| {
+                let token = token.to_lowercase();
+                fields.iter().any(|field| {
+                    field
+                        .text
+                        .split([' ', '-'])
+                        .any(|word| word.starts_with(&token))
+                })
+            });
+            if !starts_words {
+                return None;
+            }
+            let frecency = history.frecency(&emoji_result_id(emoji), now);
+            score_fields(&matcher, &weights, &scoring, &fields, frecency)
+                .map(|(score, _)| (score, emoji))
+        })
+        .collect();
+    // Stable, so equal scores keep the table's order.
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored.truncate(limit);
+
+    let mut results = Vec::with_capacity(scored.len());
+    let mut pending_actions = HashMap::with_capacity(scored.len());
+    for (score, emoji) in scored {
+        let result_id = emoji_result_id(emoji);
+        pending_actions.insert(
+            result_id.clone(),
+            PendingAction::Copy(emoji.symbol.to_string()),
+        );
+        let subtitle = if emoji.keywords.is_empty() {
+            emoji.code_points()
+        } else {
+            format!("{} · {}", emoji.code_points(), emoji.keywords.join(", "))
+        };
+        results.push(SearchResult {
+            id: result_id,
+            title: format!("{}  {}", emoji.symbol, emoji.name),
+            subtitle,
+            score,
+            action_id: "emoji".to_string(),
+            match_source: None,
+        });
+    }
+    (results, pending_actions)
+}
+
+fn emoji_result_id(emoji: &Emoji) -> String {
+    format!("emoji-{}", emoji.symbol)
+}
+
+fn emoji_fields(emoji: &Emoji) -> Vec<Field<'static>> {
+    let mut fields = vec![Field::new(emoji.name, 120, true, MatchSource::Name)];
+    for keyword in emoji.keywords {
+        fields.push(Field::new(keyword, 70, false, MatchSource::Keyword));
+    }
+    fields
+}
+
+/// Open windows whose title or program name matches every token of `search`,
+/// best first; all of them, front to back, when `search` is empty.
+pub fn window_results(
+    windows: &[WindowInfo],
+    search: &str,
+    config: &AppConfig,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let tokens = tokenize_query(search);
+    let scoring = ScoringQuery::new(search, &tokens);
+    let matcher = SkimMatcherV2::default();
+    let weights = config.ranking();
+    let mut scored: Vec<(i64, &WindowInfo)> = windows
+        .iter()
+        .filter_map(|window| {
+            if tokens.is_empty() {
+                return Some((0, window));
+            }
+            let fields = [
+                Field::new(&window.title, 120, true, MatchSource::Name),
+                Field::new(&window.process, 90, true, MatchSource::Keyword),
+            ];
+            score_fields(&matcher, &weights, &scoring, &fields, 0).map(|(score, _)| (score, window))
+        })
+        .collect();
+    // Stable, so equal scores stay in front-to-back order.
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+
+    let mut results = Vec::with_capacity(scored.len());
+    let mut pending_actions = HashMap::with_capacity(scored.len());
+    for (score, window) in scored {
+        let result_id = format!("window-{}", window.id);
+        pending_actions.insert(result_id.clone(), PendingAction::FocusWindow(window.id));
+        results.push(SearchResult {
+            id: result_id,
+            title: window.title.clone(),
+            subtitle: window.process.clone(),
+            score,
+            action_id: "window".to_string(),
+            match_source: None,
+        });
+    }
+    (results, pending_actions)
+}
+
+/// Running processes whose program name or pid matches every token of
+/// `search`, best first; all of them by name when `search` is empty.
+pub fn process_results(
+    processes: &[ProcessInfo],
+    search: &str,
+    config: &AppConfig,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let text = messages(config.language);
+    let tokens = tokenize_query(search);
+    let scoring = ScoringQuery::new(search, &tokens);
+    let matcher = SkimMatcherV2::default();
+    let weights = config.ranking();
+    let limit = config.max_results.clamp(MIN_RESULT_LIMIT, MAX_RESULT_LIMIT) as usize;
+    let mut sorted: Vec<&ProcessInfo> = processes.iter().collect();
+    sorted.sort_by_cached_key(|process| (process.name.to_lowercase(), process.pid));
+    let mut scored: Vec<(i64, &ProcessInfo)> = sorted
+        .into_iter()
+        .filter_map(|process| {
+            if tokens.is_empty() {
+                return Some((0, process));
+            }
+            let pid = process.pid.to_string();
+            let fields = [
+                Field::new(&process.name, 120, true, MatchSource::Name),
+                Field::new(&pid, 60, false, MatchSource::Keyword),
+            ];
+            score_fields(&matcher, &weights, &scoring, &fields, 0)
+                .map(|(score, _)| (score, process))
+        })
+        .collect();
+    // Stable, so equal scores stay in name order.
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored.truncate(limit);
+
+    let mut results = Vec::with_capacity(scored.len());
+    let mut pending_actions = HashMap::with_capacity(scored.len());
+    for (score, process) in scored {
+        let result_id = format!("process-{}", process.pid);
+        pending_actions.insert(
+            result_id.clone(),
+            PendingAction::KillProcess {
+                pid: process.pid,
+                name: process.name.clone(),
+                elevated: process.elevated,
+            },
+        );
+        let pid = process.pid.to_string();
+        let subtitle = if process.elevated {
+            fill(text.process_elevated_subtitle, &[&pid])
+        } else {
+            fill(text.process_subtitle, &[&pid])
+        };
+        results.push(SearchResult {
+            id: result_id,
+            title: process.name.clone(),
+            subtitle,
+            score,
+            action_id: "process".to_string(),
+            match_source: None,
+        });
+    }
+    (results, pending_actions)
+}
+
+/// Turns Everything hits into results, keeping Everything's own ordering.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn file_results(paths: &[String]) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let mut results = Vec::with_capacity(paths.len());
+    let mut pending_actions = HashMap::with_capacity(paths.len());
+    for (index, path) in paths.iter().enumerate() {
+        let result_id = format!("file-{path}");
+        let title = path
+            .trim_end_matches(['\\', '/'])
+            .rsplit(['\\', '/'])
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(path)
+            .to_string();
+        pending_actions.insert(result_id.clone(), PendingAction::File(path.clone()));
+        results.push(SearchResult {
+            id: result_id,
+            title,
+            subtitle: path.clone(),
+            score: -(index as i64),
+            action_id: "file".to_string(),
+            match_source: None,
+        });
+    }
+    (results, pending_actions)
+}
+
+/// Turns Windows Search hits into results, keeping the index's ordering. Files open
+/// like Everything hits; other items such as emails open through their URL.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn indexed_results(
+    items: &[IndexedItem],
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let mut results = Vec::with_capacity(items.len());
+    let mut pending_actions = HashMap::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let is_file = !item.path.is_empty()
+            && item
+                .url
+                .get(..5)
+                .is_some_and(|scheme| scheme.eq_ignore_ascii_case("file:"));
+        let (result_id, action, subtitle, action_id) = if is_file {
+            (
+                format!("file-{}", item.path),
+                PendingAction::File(item.path.clone()),
+                item.path.clone(),
+                "file",
+            )
+        } else if !item.url.is_empty() {
+            (
+                format!("indexed-{}", item.url),
+                PendingAction::Url(item.url.clone()),
+                item.path.clone(),
+                "url",
+            )
+        } else {
+            continue;
+        };
+        if pending_actions.contains_key(&result_id) {
+            continue;
+        }
+        let title = if item.name.trim().is_empty() {
+            subtitle.clone()
+        } else {
+            item.name.clone()
+        };
+        pending_actions.insert(result_id.clone(), action);
+        results.push(SearchResult {
+            id: result_id,
+            title,
+            subtitle,
+            score: -(index as i64),
+            action_id: action_id.to_string(),
+            match_source: None,
+        });
+    }
+    (results, pending_actions)
+}
+
+/// Turns a plugin's reply into results, highest plugin score first.
+pub fn plugin_results(
+    plugin: &PluginManifest,
+    replies: &[PluginResult],
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let mut ordered: Vec<&PluginResult> = replies.iter().collect();
+    ordered.sort_by_key(|reply| Reverse(reply.score));
+    let mut results = Vec::with_capacity(ordered.len());
+    let mut pending_actions = HashMap::with_capacity(ordered.len());
+    for (index, reply) in ordered.into_iter().enumerate() {
+        let result_id = format!("plugin-{}-{index}", plugin.id);
+        pending_actions.insert(
+            result_id.clone(),
+            PendingAction::Plugin(PluginCall {
+                plugin: plugin.clone(),
+                request: reply.action.clone(),
+            }),
+        );
+        results.push(SearchResult {
+            id: result_id,
+            title: reply.title.clone(),
+            subtitle: reply.subtitle.clone(),
+            score: -(index as i64),
+            action_id: "plugin".to_string(),
+            match_source: None,
+        });
+    }
+    (results, pending_actions)
+}
+
+/// The path in `query` when it is an absolute Windows path (`C:\...`, `D:/...` or
+/// `\\server\share`), without the quotes Explorer's "Copy as path" adds.
+pub fn typed_path(query: &str) -> Option<&str> {
+    let query = query.trim();
+    let path = query
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(query)
+        .trim();
+    let bytes = path.as_bytes();
+    let drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+    let unc = path.len() > 2 && path.starts_with(r"\\");
+    (drive || unc).then_some(path)
+}
+
+/// Rows for an existing typed path: open it, then show it in its folder.
+fn path_results(path: &str, is_dir: bool, text: &Messages) -> Vec<(SearchResult, PendingAction)> {
+    let name = path
+        .trim_end_matches(['\\', '/'])
+        .rsplit(['\\', '/'])
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(path);
+    let open_title = if is_dir {
+        text.open_folder
+    } else {
+        text.open_file
+    };
+    [
+        (fill(open_title, &[name]), false),
+        (fill(text.reveal_path, &[name]), true),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(index, (title, reveal))| {
+        let verb = if reveal { "reveal" } else { "open" };
+        (
+            SearchResult {
+                id: format!("path-{verb}-{path}"),
+                title,
+                subtitle: path.to_string(),
+                score: 200 - index as i64,
+                action_id: "file".to_string(),
+                match_source: None,
+            },
+            PendingAction::Path {
+                path: path.to_string(),
+                reveal,
+            },
+        )
+    })
+    .collect()
+}
+
+fn is_url_like(input: &str) -> bool {
+    input.starts_with("http://")
+        || input.starts_with("https://")
+        || input.contains('.') && input.split_whitespace().count() == 1
+}
+
+fn match_application(
+    matcher: &SkimMatcherV2,
+    weights: &RankingWeights,
+    app: &ApplicationInfo,
+    query: &ScoringQuery<'_>,
+    frecency: i64,
+) -> Option<(i64, MatchSource)> {
+    let fields = application_fields(app);
+    score_fields(matcher, weights, query, &fields, frecency)
+}
+
+fn application_fields(app: &ApplicationInfo) -> Vec<Field<'_>> {
+    let folded = app.folded.as_ref();
+    let mut fields = Vec::new();
+    fields.push(
+        Field::new(&app.name, 120, true, MatchSource::Name)
+            .folded(folded.map(|folded| folded.name.as_str())),
+    );
+    for (index, keyword) in app.keywords.iter().enumerate() {
+        if keyword.is_empty() {
+            continue;
+        }
+        let lower = folded.and_then(|folded| folded.keywords.get(index));
+        fields.push(
+            Field::new(keyword.as_str(), 70, false, MatchSource::Keyword)
+                .folded(lower.map(String::as_str)),
+        );
+    }
+    if let Some(pinyin_index) = &app.pinyin_index {
+        for entry in pinyin_index.split_whitespace() {
+            // `build_pinyin_index` writes it lowercase already.
+            let (full, initials) = split_pinyin_entry(entry);
+            if let Some(full) = full {
+                fields
+                    .push(Field::new(full, 85, false, MatchSource::PinyinFull).folded(Some(full)));
+            }
+            if let Some(initials) = initials {
+                fields.push(
+                    Field::new(initials, 95, false, MatchSource::PinyinInitials)
+                        .folded(Some(initials)),
+                );
+            }
+        }
+    }
+    fields
+}
+
+fn match_bookmark(
+    matcher: &SkimMatcherV2,
+    weights: &RankingWeights,
+    bookmark: &BookmarkEntry,
+    query: &ScoringQuery<'_>,
+    frecency: i64,
+) -> Option<(i64, MatchSource)> {
+    let fields = bookmark_fields(bookmark);
+    score_fields(matcher, weights, query, &fields, frecency)
+}
+
+fn bookmark_fields(bookmark: &BookmarkEntry) -> Vec<Field<'_>> {
+    let folded = bookmark.folded.as_ref();
+    let mut fields = Vec::new();
+    fields.push(
+        Field::new(&bookmark.title, 110, true, MatchSource::Name)
+            .folded(folded.map(|folded| folded.title.as_str())),
+    );
+    if let Some(path) = &bookmark.folder_path {
+        fields.push(
+            Field::new(path.as_str(), 65, false, MatchSource::Folder)
+                .folded(folded.and_then(|folded| folded.folder_path.as_deref())),
+        );
+    }
+    fields.push(
+        Field::new(&bookmark.url, 45, false, MatchSource::Url)
+            .folded(folded.map(|folded| folded.url.as_str())),
+    );
+    for (index, keyword) in bookmark.keywords.iter().enumerate() {
+        if keyword.is_empty() {
+            continue;
+        }
+        let lower = folded.and_then(|folded| folded.keywords.get(index));
+        fields.push(
+            Field::new(keyword.as_str(), 55, false, MatchSource::Keyword)
+                .folded(lower.map(String::as_str)),
+        );
+    }
+    if let Some(pinyin_index) = &bookmark.pinyin_index {
+        for entry in pinyin_index.split_whitespace() {
+            // `build_pinyin_index` writes it lowercase already.
+            let (full, initials) = split_pinyin_entry(entry);
+            if let Some(full) = full {
+                fields
+                    .push(Field::new(full, 80, false, MatchSource::PinyinFull).folded(Some(full)));
+            }
+            if let Some(initials) = initials {
+                fields.push(
+                    Field::new(initials, 90, false, MatchSource::PinyinInitials)
+                        .folded(Some(initials)),
+                );
+            }
+        }
+    }
+    fields
+}
+
+/// System commands can be destructive, so a purely fuzzy hit is not enough:
+/// the query must also prefix a word of one of the command's names or keywords.
+fn match_system_command(
+    matcher: &SkimMatcherV2,
+    weights: &RankingWeights,
+    command: SystemCommand,
+    query: &ScoringQuery<'_>,
+    frecency: i64,
+) -> Option<(i64, MatchSource)> {
+    let fields = system_command_fields(command);
+    let query_lower = query.text.to_lowercase();
+    let prefixes_a_word = fields.iter().any(|field| {
+        let text = field.text.to_lowercase();
+        text.starts_with(&query_lower)
+            || text
+                .split_whitespace()
+                .any(|word| word.starts_with(&query_lower))
+    });
+    if !prefixes_a_word {
+        return None;
+    }
+
+    score_fields(matcher, weights, query, &fields, frecency)
+}
+
+/// Snippet names are short and chosen by hand, so every token must start one of
+/// their words; fuzzy matches would bring them up for unrelated queries.
+fn match_snippet(
+    matcher: &SkimMatcherV2,
+    weights: &RankingWeights,
+    name: &str,
+    query: &ScoringQuery<'_>,
+    frecency: i64,
+) -> Option<(i64, MatchSource)> {
+    let name_lower = name.to_lowercase();
+    let starts_words = query.tokens.iter().all(|token| {
+        let token = token.text.to_lowercase();
+        name_lower
+            .split(|ch: char| ch.is_whitespace() || ch == '-' || ch == '_')
+            .any(|word| word.starts_with(&token))
+    });
+    if !starts_words {
+        return None;
+    }
+    let fields = [Field::new(name, 120, true, MatchSource::Name)];
+    score_fields(matcher, weights, query, &fields, frecency)
+}
+
+fn system_command_fields(command: SystemCommand) -> Vec<Field<'static>> {
+    let (full, initials) = split_pinyin_entry(command.pinyin_index());
+    let mut fields = vec![
+        Field::new(command.title(), 120, true, MatchSource::Name),
+        Field::new(command.localized_title(), 120, true, MatchSource::Name),
+    ];
+    for keyword in command.keywords() {
+        fields.push(Field::new(keyword, 70, false, MatchSource::Keyword));
+    }
+    if let Some(full) = full {
+        fields.push(Field::new(full, 85, false, MatchSource::PinyinFull));
+    }
+    if let Some(initials) = initials {
+        fields.push(Field::new(initials, 95, false, MatchSource::PinyinInitials));
+    }
+    fields
+}
+
+/// There are dozens of settings pages with overlapping words, so each query word
+/// must start a word of the page's names or keywords (CJK words may sit
+/// anywhere), and `settings` alone does not list them all.
+fn match_settings_page(
+    matcher: &SkimMatcherV2,
+    weights: &RankingWeights,
+    page: &SettingsPage,
+    query: &ScoringQuery<'_>,
+    frecency: i64,
+) -> Option<(i64, MatchSource)> {
+    let fields = settings_page_fields(page);
+    let texts: Vec<(String, bool)> = fields
+        .iter()
+        .map(|field| {
+            let generic = page.kind_keywords().contains(&field.text);
+            (field.text.to_lowercase(), generic)
+        })
+        .collect();
+    let starts_a_word = |token: &str, include_generic: bool| {
+        let token = token.to_lowercase();
+        texts
+            .iter()
+            .filter(|(_, generic)| include_generic || !generic)
+            .any(|(text, _)| {
+                (!token.is_ascii() && text.contains(&token))
+                    || text
+                        .split(|ch: char| ch.is_whitespace() || ch == '-')
+                        .any(|word| word.starts_with(&token))
+            })
+    };
+    if !query
+        .tokens
+        .iter()
+        .all(|token| starts_a_word(token.text, true))
+        || !query
+            .tokens
+            .iter()
+            .any(|token| starts_a_word(token.text, false))
+    {
+        return None;
+    }
+
+    score_fields(matcher, weights, query, &fields, frecency)
+}
+
+fn settings_page_fields(page: &SettingsPage) -> Vec<Field<'static>> {
+    let mut fields = vec![
+        Field::new(page.title, 120, true, MatchSource::Name),
+        Field::new(page.localized_title, 120, true, MatchSource::Name),
+    ];
+    for keyword in page.keywords {
+        fields.push(Field::new(keyword, 70, false, MatchSource::Keyword));
+    }
+    for keyword in page.kind_keywords() {
+        fields.push(Field::new(keyword, 40, false, MatchSource::Keyword));
+    }
+    if let Some(entry) = page.pinyin_index() {
+        let (full, initials) = split_pinyin_entry(entry);
+        if let Some(full) = full {
+            fields.push(Field::new(full, 85, false, MatchSource::PinyinFull));
+        }
+        if let Some(initials) = initials {
+            fields.push(Field::new(initials, 95, false, MatchSource::PinyinInitials));
+        }
+    }
+    fields
+}
+
+fn split_pinyin_entry(entry: &str) -> (Option<&str>, Option<&str>) {
+    if let Some((full, initials)) = entry.split_once('|') {
+        (
+            if full.is_empty() { None } else { Some(full) },
+            if initials.is_empty() {
+                None
+            } else {
+                Some(initials)
+            },
+        )
+    } else if entry.is_empty() {
+        (None, None)
+    } else {
+        (Some(entry), None)
+    }
+}
+
+fn update_best(best: &mut Option<(i64, usize)>, candidate: i64, index: usize) {
+    if best.is_none_or(|(current, _)| candidate > current) {
+        *best = Some((candidate, index));
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Field<'a> {
+    text: &'a str,
+    /// `text` lowercased ahead of time, for indexed items.
+    lower: Option<&'a str>,
+    weight: i64,
+    full_query_boost: bool,
+    source: MatchSource,
+}
+
+impl<'a> Field<'a> {
+    fn new(text: &'a str, weight: i64, full_query_boost: bool, source: MatchSource) -> Self {
+        Self {
+            text,
+            lower: None,
+            weight,
+            full_query_boost,
+            source,
+        }
+    }
+
+    /// The field with its precomputed lowercase text, when there is one.
+    fn folded(self, lower: Option<&'a str>) -> Self {
+        Self { lower, ..self }
+    }
+
+    fn lower(&self) -> Cow<'a, str> {
+        match self.lower {
+            Some(lower) => Cow::Borrowed(lower),
+            None => Cow::Owned(self.text.to_ascii_lowercase()),
+        }
+    }
+}
+
+/// A query as scoring sees it, lowercased once per search rather than once per
+/// field: the text whole-query bonuses compare against, and its tokens.
+struct ScoringQuery<'a> {
+    text: &'a str,
+    lower: String,
+    tokens: Vec<ScoringToken<'a>>,
+}
+
+struct ScoringToken<'a> {
+    /// As typed, since the fuzzy matcher treats capitals as case-sensitive.
+    text: &'a str,
+    lower: String,
+}
+
+impl<'a> ScoringQuery<'a> {
+    fn new(text: &'a str, tokens: &[&'a str]) -> Self {
+        Self {
+            text,
+            lower: text.to_ascii_lowercase(),
+            tokens: tokens
+                .iter()
+                .map(|token| ScoringToken {
+                    text: token,
+                    lower: token.to_ascii_lowercase(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Splits on whitespace, keeping `"quoted phrases"` whole. An unclosed quote runs
+/// to the end of the query.
+fn tokenize_query(query: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = query;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return tokens;
+        }
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let (phrase, after) = quoted.split_once('"').unwrap_or((quoted, ""));
+            let phrase = phrase.trim();
+            if !phrase.is_empty() {
+                tokens.push(phrase);
+            }
+            rest = after;
+        } else {
+            let end = rest
+                .find(|ch: char| ch.is_whitespace() || ch == '"')
+                .unwrap_or(rest.len());
+            tokens.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+}
+
+fn score_fields(
+    matcher: &SkimMatcherV2,
+    weights: &RankingWeights,
+    query: &ScoringQuery<'_>,
+    fields: &[Field<'_>],
+    frecency: i64,
+) -> Option<(i64, MatchSource)> {
+    score_fields_traced(matcher, weights, query, fields, frecency, None)
+}
+
+/// [`score_fields`], also writing each step into `trace` when given.
+fn score_fields_traced(
+    matcher: &SkimMatcherV2,
+    weights: &RankingWeights,
+    query: &ScoringQuery<'_>,
+    fields: &[Field<'_>],
+    frecency: i64,
+    mut trace: Option<&mut ScoreBreakdown>,
+) -> Option<(i64, MatchSource)> {
+    let mut total = 0i64;
+    // Points credited to each field, used to report which one won the match.
+    let mut contributions = vec![0i64; fields.len()];
+    for token in &query.tokens {
+        let mut best: Option<(i64, usize)> = None;
+        let mut best_points = None;
+        for (index, field) in fields.iter().enumerate() {
+            if let Some(points) = score_token(matcher, weights, field, token) {
+                let before = best;
+                update_best(&mut best, points.total(), index);
+                if best != before {
+                    best_points = Some(points);
+                }
+            }
+        }
+        let (best_score, index) = best?;
+        total += best_score;
+        contributions[index] += best_score;
+        if let (Some(trace), Some(points)) = (trace.as_deref_mut(), best_points) {
+            trace.tokens.push(TokenScore {
+                token: token.text.to_string(),
+                field: fields[index].text.to_string(),
+                source: fields[index].source,
+                points,
+            });
+        }
+    }
+
+    let mut bonus = None;
+    for (index, field) in fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.full_query_boost)
+    {
+        let score = match_kind(&field.lower(), &query.lower).map_or(0, |kind| match kind {
+            MatchKind::Exact => weights.exact_bonus,
+            MatchKind::Prefix => weights.prefix_bonus,
+            MatchKind::Contains => weights.contains_bonus,
+        });
+        if score > 0 {
+            update_best(&mut bonus, score + field.weight, index);
+        }
+    }
+    if let Some((extra, index)) = bonus {
+        total += extra;
+        contributions[index] += extra;
+    }
+    total = total * weights.source_percent / 100;
+    let frecency_points = frecency * weights.frecency_percent / 100;
+    total += frecency_points;
+    if let Some(trace) = trace {
+        trace.query_bonus = bonus.and_then(|(extra, index)| {
+            let field = fields[index];
+            let kind = match_kind(&field.lower(), &query.lower)?;
+            Some(QueryBonus {
+                field: field.text.to_string(),
+                source: field.source,
+                kind,
+                bonus: extra - field.weight,
+                weight: field.weight,
+            })
+        });
+        trace.source_percent = weights.source_percent;
+        trace.frecency = frecency;
+        trace.frecency_points = frecency_points;
+        trace.total = total;
+    }
+
+    let winner = contributions
+        .iter()
+        .enumerate()
+        .fold(0, |best, (index, score)| {
+            if *score > contributions[best] {
+                index
+            } else {
+                best
+            }
+        });
+    Some((total, source_of(fields, winner)))
+}
+
+/// Keywords often repeat the name, URL or folder; credit the dedicated field instead.
+fn source_of(fields: &[Field<'_>], index: usize) -> MatchSource {
+    let winner = fields[index];
+    if winner.source != MatchSource::Keyword {
+        return winner.source;
+    }
+    fields
+        .iter()
+        .find(|field| {
+            field.source != MatchSource::Keyword && field.text.eq_ignore_ascii_case(winner.text)
+        })
+        .map_or(MatchSource::Keyword, |field| field.source)
+}
+
+/// How a field contains a query or token, for the match bonuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Contains,
+}
+
+impl MatchKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Exact => "exact",
+            Self::Prefix => "prefix",
+            Self::Contains => "contains",
+        }
+    }
+}
+
+fn match_kind(field_lower: &str, query_lower: &str) -> Option<MatchKind> {
+    if field_lower == query_lower {
+        Some(MatchKind::Exact)
+    } else if field_lower.starts_with(query_lower) {
+        Some(MatchKind::Prefix)
+    } else if field_lower.contains(query_lower) {
+        Some(MatchKind::Contains)
+    } else {
+        None
+    }
+}
+
+/// The parts of one token's score against one field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenPoints {
+    /// Fuzzy matcher score after `fuzzy_percent`.
+    pub fuzzy: i64,
+    pub weight: i64,
+    pub kind: Option<MatchKind>,
+    pub bonus: i64,
+    pub length_penalty: i64,
+}
+
+impl TokenPoints {
+    pub fn total(&self) -> i64 {
+        self.fuzzy + self.weight + self.bonus - self.length_penalty
+    }
+}
+
+fn score_token(
+    matcher: &SkimMatcherV2,
+    weights: &RankingWeights,
+    field: &Field<'_>,
+    token: &ScoringToken<'_>,
+) -> Option<TokenPoints> {
+    let fuzzy = matcher.fuzzy_match(field.text, token.text)?;
+    let kind = match_kind(&field.lower(), &token.lower);
+    let bonus = kind.map_or(0, |kind| match kind {
+        MatchKind::Exact => weights.token_exact_bonus,
+        MatchKind::Prefix => weights.token_prefix_bonus,
+        MatchKind::Contains => weights.token_contains_bonus,
+    });
+
+    let field_len = field.text.chars().count();
+    let token_len = token.text.chars().count();
+    let length_penalty =
+        field_len.saturating_sub(token_len) as i64 / weights.length_penalty_divisor.max(1);
+    Some(TokenPoints {
+        fuzzy: fuzzy * weights.fuzzy_percent / 100,
+        weight: field.weight,
+        kind,
+        bonus,
+        length_penalty,
+    })
+}
+
+/// How a result's score was put together; shown with `debug_mode`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    /// The field each query token scored best against.
+    pub tokens: Vec<TokenScore>,
+    /// Bonus for the whole query matching a primary field.
+    pub query_bonus: Option<QueryBonus>,
+    /// Share of the match points kept for the result's source, from `source_weights`.
+    pub source_percent: i64,
+    /// Launch-history score before `frecency_percent`.
+    pub frecency: i64,
+    pub frecency_points: i64,
+    pub pin_boost: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenScore {
+    pub token: String,
+    pub field: String,
+    pub source: MatchSource,
+    pub points: TokenPoints,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryBonus {
+    pub field: String,
+    pub source: MatchSource,
+    pub kind: MatchKind,
+    pub bonus: i64,
+    pub weight: i64,
+}
+
+impl ScoreBreakdown {
+    /// One line per step, e.g. `"code" → name "Visual Studio Code": fuzzy 68 + weight 120 ...`.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for token in &self.tokens {
+            let points = token.points;
+            let mut line = format!(
+                "\"{}\" → {} \"{}\": fuzzy {} + weight {}",
+                token.token,
+                token.source.label(),
+                token.field,
+                points.fuzzy,
+                points.weight
+            );
+            if let Some(kind) = points.kind {
+                line.push_str(&format!(" + {} {}", kind.label(), points.bonus));
+            }
+            if points.length_penalty > 0 {
+                line.push_str(&format!(" − length {}", points.length_penalty));
+            }
+            line.push_str(&format!(" = {}", points.total()));
+            lines.push(line);
+        }
+        if let Some(bonus) = &self.query_bonus {
+            lines.push(format!(
+                "whole query {} on {} \"{}\": {} + weight {} = {}",
+                bonus.kind.label(),
+                bonus.source.label(),
+                bonus.field,
+                bonus.bonus,
+                bonus.weight,
+                bonus.bonus + bonus.weight
+            ));
+        }
+        if self.source_percent != 100 {
+            lines.push(format!("source weight {}%", self.source_percent));
+        }
+        if self.frecency > 0 {
+            lines.push(format!(
+                "frecency {} → {}",
+                self.frecency, self.frecency_points
+            ));
+        }
+        if self.pin_boost > 0 {
+            lines.push(format!("pinned + {}", self.pin_boost));
+        }
+        lines.push(format!("total {}", self.total));
+        lines
+    }
+}
+
+/// Re-scores the item behind `action` for `query` the way [`search`] did and
+/// records every step. Only fuzzy-matched items (apps, bookmarks, system
+/// commands and settings pages) have a breakdown; other results carry fixed scores.
+pub fn explain_score(
+    query: &str,
+    action: &PendingAction,
+    history: &LaunchHistory,
+    config: &AppConfig,
+) -> Option<ScoreBreakdown> {
+    let (_, trimmed) = split_mode_prefix(query.trim(), &config.query_prefixes);
+    let tokens = tokenize_query(trimmed);
+    if tokens.is_empty() || command_query(query.trim()).is_some() {
+        return None;
+    }
+    if let (Some((_, AliasTarget::App(app_id))), PendingAction::Application(app)) =
+        (find_alias(trimmed, config), action)
+    {
+        if *app_id == app.id {
+            return None;
+        }
+    }
+    let match_query = trimmed.replace('"', "");
+    let (result_id, fields) = match action {
+        PendingAction::Application(app) => (format!("app-{}", app.id), application_fields(app)),
+        PendingAction::Bookmark(bookmark) => (
+            format!("bookmark-{}", bookmark.id),
+            bookmark_fields(bookmark),
+        ),
+        PendingAction::System(command) => (
+            format!("system-{}", command.id()),
+            system_command_fields(*command),
+        ),
+        PendingAction::Url(url) => {
+            let page = SETTINGS_PAGES.iter().find(|page| page.target == url)?;
+            (
+                format!("settings-{}", page.id()),
+                settings_page_fields(page),
+            )
+        }
+        _ => return None,
+    };
+    let frecency = history.frecency(&result_id, unix_now());
+    let mut breakdown = ScoreBreakdown::default();
+    let source_percent = match action {
+        PendingAction::Application(_) => config.source_weights.apps,
+        PendingAction::Bookmark(_) => config.source_weights.bookmarks,
+        _ => 100,
+    };
+    score_fields_traced(
+        &SkimMatcherV2::default(),
+        &config.ranking().for_source(source_percent),
+        &ScoringQuery::new(&match_query, &tokens),
+        &fields,
+        frecency,
+        Some(&mut breakdown),
+    )?;
+    breakdown.pin_boost = pin_boost(config, &result_id);
+    breakdown.total += breakdown.pin_boost;
+    Some(breakdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin_commands::BuiltinCommand;
+    use crate::locale::Language;
+    use crate::state::QueryGenerations;
+    use crate::testing::{app, fixture_apps, fixture_bookmarks};
+
+    fn run(query: &str, mode: Option<&str>) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+        search(
+            query.to_string(),
+            mode.map(str::to_string),
+            &fixture_apps(),
+            &fixture_bookmarks(),
+            &LaunchHistory::default(),
+            &AppConfig::default(),
+        )
+    }
+
+    fn titles(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|result| result.title.as_str()).collect()
+    }
+
+    #[test]
+    fn longer_queries_rescore_only_the_previous_matches() {
+        let (apps, bookmarks) = (fixture_apps(), fixture_bookmarks());
+        let (history, config) = (LaunchHistory::default(), AppConfig::default());
+        let refine = |query: &str, prior: Option<&Candidates>| {
+            let (results, _, candidates) = refine_search(
+                query.to_string(),
+                None,
+                prior,
+                SearchIndexes::new(&apps, &bookmarks),
+                &history,
+                &config,
+                &GenerationToken::detached(),
+            );
+            let ranked: Vec<(String, i64)> = results
+                .into_iter()
+                .map(|result| (result.id, result.score))
+                .collect();
+            (ranked, candidates)
+        };
+
+        let (_, candidates) = refine("no", None);
+        let candidates = candidates.unwrap();
+        for query in ["not", "note", "no te"] {
+            assert_eq!(refine(query, Some(&candidates)), refine(query, None));
+        }
+
+        // Nothing outside the prior candidates is scanned again...
+        let nothing = Candidates {
+            tokens: vec!["no".to_string()],
+            apps: Some(Vec::new()),
+            bookmarks: Some(Vec::new()),
+            from_trigrams: false,
+        };
+        let (ranked, _) = refine("note", Some(&nothing));
+        assert!(ranked
+            .iter()
+            .all(|(id, _)| !id.starts_with("app-") && !id.starts_with("bookmark-")));
+        // ...unless the query no longer extends theirs.
+        let (ranked, _) = refine("calc", Some(&nothing));
+        assert_eq!(ranked, refine("calc", None).0);
+    }
+
+    #[test]
+    fn blank_query_returns_nothing() {
+        let (results, actions) = run("   ", None);
+        assert!(results.is_empty());
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn exact_name_outranks_partial_matches() {
+        let (results, _) = run("notion", None);
+        assert_eq!(titles(&results)[0], "Notion");
+    }
+
+    #[test]
+    fn pinyin_initials_match_chinese_names() {
+        let (results, actions) = run("wx", None);
+        assert_eq!(titles(&results)[0], "微信");
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Application(app)) if app.id == "shell:wechat"
+        ));
+    }
+
+    #[test]
+    fn uwp_apps_use_uwp_action_id() {
+        let (results, _) = run("calculator", None);
+        assert_eq!(results[0].action_id, "uwp");
+    }
+
+    #[test]
+    fn web_search_is_always_last() {
+        let (results, actions) = run("github", None);
+        let last = results.last().unwrap();
+        assert_eq!(last.action_id, "search");
+        assert!(matches!(
+            actions.get(&last.id),
+            Some(PendingAction::Search(url)) if url == "https://google.com/search?q=github"
+        ));
+    }
+
+    #[test]
+    fn misspelled_query_suggests_the_closest_name() {
+        let (results, actions) = run("chrmoe", None);
+        assert_eq!(results[0].action_id, "suggest");
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Query(query)) if query == "chrome"
+        ));
+        assert!(is_fallback(&results[0]));
+
+        assert_eq!(edit_distance("chrmoe", "chrome"), 1);
+        assert_eq!(edit_distance("notpad", "notepad"), 1);
+        assert_eq!(edit_distance("zzz", "chrome"), 6);
+    }
+
+    #[test]
+    fn typo_matches_find_apps_when_nothing_else_does() {
+        let config = AppConfig {
+            typo_matches: true,
+            ..AppConfig::default()
+        };
+        let search_with = |query: &str| {
+            search(
+                query.to_string(),
+                None,
+                &fixture_apps(),
+                &fixture_bookmarks(),
+                &LaunchHistory::default(),
+                &config,
+            )
+        };
+        let (results, actions) = search_with("chrmoe");
+        assert_eq!(results[0].title, "Google Chrome");
+        assert_eq!(results[0].match_source, Some(MatchSource::Typo));
+        assert_eq!(results[0].score, TYPO_BASE_SCORE - TYPO_PENALTY);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Application(app)) if app.name == "Google Chrome"
+        ));
+        assert_eq!(results.last().unwrap().action_id, "search");
+        // A misspelled start of a name matches too.
+        assert_eq!(search_with("gogle chrm").0[0].title, "Google Chrome");
+
+        // Only a query nothing matched is retried, and only when turned on.
+        assert!(search_with("chrome")
+            .0
+            .iter()
+            .all(|result| result.match_source != Some(MatchSource::Typo)));
+        assert_eq!(run("chrmoe", None).0[0].action_id, "suggest");
+        assert_eq!(word_distance("xyzzy", "chrome"), None);
+    }
+
+    #[test]
+    fn a_superseded_search_stops_scanning() {
+        let (apps, bookmarks) = (fixture_apps(), fixture_bookmarks());
+        let (history, config) = (LaunchHistory::default(), AppConfig::default());
+        let generations = QueryGenerations::default();
+        let run = |token: &GenerationToken| {
+            let indexes = SearchIndexes::new(&apps, &bookmarks);
+            refine_search(
+                "notepad".to_string(),
+                None,
+                None,
+                indexes,
+                &history,
+                &config,
+                token,
+            )
+        };
+        let token = generations.advance();
+        assert_eq!(run(&token).0[0].title, "Notepad");
+
+        generations.advance();
+        assert!(token.is_stale());
+        let (results, actions, candidates) = run(&token);
+        assert!(results.is_empty() && actions.is_empty() && candidates.is_none());
+    }
+
+    #[test]
+    fn trigram_index_finds_what_a_full_scan_does() {
+        let (apps, bookmarks) = (fixture_apps(), fixture_bookmarks());
+        let (history, config) = (LaunchHistory::default(), AppConfig::default());
+        let trigrams = TrigramIndex::build(&apps, &bookmarks);
+        let ranked = |query: &str, trigrams: Option<&TrigramIndex>| -> Vec<(String, i64)> {
+            let indexes = SearchIndexes::new(&apps, &bookmarks).with_trigrams(trigrams);
+            let token = GenerationToken::detached();
+            refine_search(
+                query.to_string(),
+                None,
+                None,
+                indexes,
+                &history,
+                &config,
+                &token,
+            )
+            .0
+            .into_iter()
+            .map(|result| (result.id, result.score))
+            .collect()
+        };
+        for query in ["chrome", "vsc", "visual code", "meeting", "no", "wx"] {
+            assert_eq!(
+                ranked(query, Some(&trigrams)),
+                ranked(query, None),
+                "{query}"
+            );
+        }
+        // A subsequence sharing no trigram leaves too few candidates, so the
+        // whole catalog is scanned and the fuzzy match still turns up.
+        let found =
+            |ranked: Vec<(String, i64)>| ranked.iter().any(|(id, _)| id == "app-shell:notepad");
+        assert!(found(ranked("ntpd", None)));
+        assert!(found(ranked("ntpd", Some(&trigrams))));
+    }
+
+    #[test]
+    fn refined_searches_match_fresh_ones_with_the_trigram_index() {
+        let mut apps = fixture_apps();
+        for index in 0..MIN_RESULT_LIMIT {
+            let id = format!("notes{index}");
+            apps.push(app(&id, &format!("Notes {index}"), &format!("/apps/{id}")));
+        }
+        // Matches `not pad` without sharing a trigram with `not`.
+        apps.push(app("nightowl", "Nightowl Task Pad", "/apps/nightowl"));
+        let bookmarks = fixture_bookmarks();
+        let trigrams = TrigramIndex::build(&apps, &bookmarks);
+        let history = LaunchHistory::default();
+        let config = AppConfig {
+            max_results: MIN_RESULT_LIMIT,
+            ..AppConfig::default()
+        };
+        let refine = |query: &str, prior: Option<&Candidates>| {
+            let (results, _, candidates) = refine_search(
+                query.to_string(),
+                None,
+                prior,
+                SearchIndexes::new(&apps, &bookmarks).with_trigrams(Some(&trigrams)),
+                &history,
+                &config,
+                &GenerationToken::detached(),
+            );
+            let ranked: Vec<(String, i64)> = results
+                .into_iter()
+                .map(|result| (result.id, result.score))
+                .collect();
+            (ranked, candidates)
+        };
+
+        // `no` is scanned in full, `not` only among its trigram hits, and `not pad`
+        // finds too few of those and is scanned in full again.
+        for (typed, from_trigrams) in [("no", false), ("not", true)] {
+            let (_, candidates) = refine(typed, None);
+            let candidates = candidates.unwrap();
+            assert_eq!(candidates.from_trigrams, from_trigrams);
+            for query in ["not", "note", "not pad"] {
+                assert_eq!(
+                    refine(query, Some(&candidates)).0,
+                    refine(query, None).0,
+                    "{typed} -> {query}"
+                );
+            }
+        }
+        let (ranked, _) = refine("not pad", None);
+        assert!(ranked.iter().any(|(id, _)| id == "app-nightowl"));
+    }
+
+    #[test]
+    fn case_folded_index_scores_like_lowercasing_per_search() {
+        let mut apps = fixture_apps();
+        let mut bookmarks = fixture_bookmarks();
+        apps.iter_mut().for_each(|app| app.folded = None);
+        bookmarks
+            .iter_mut()
+            .for_each(|bookmark| bookmark.folded = None);
+        for query in ["CHROME", "vs code", "wx", "Meeting", "rust blog"] {
+            let folded = run(query, None).0;
+            let (unfolded, _) = search(
+                query.to_string(),
+                None,
+                &apps,
+                &bookmarks,
+                &LaunchHistory::default(),
+                &AppConfig::default(),
+            );
+            let scored = |results: &[SearchResult]| -> Vec<(String, i64)> {
+                results
+                    .iter()
+                    .map(|result| (result.id.clone(), result.score))
+                    .collect()
+            };
+            assert_eq!(scored(&folded), scored(&unfolded), "{query}");
+        }
+    }
+
+    #[test]
+    fn zero_result_fallbacks_offer_every_engine_and_files() {
+        let (results, _) = run("qqqzzz", None);
+        assert_eq!(
+            titles(&results),
+            vec![
+                "Search Google for: qqqzzz",
+                "Search Bing for: qqqzzz",
+                "Search DuckDuckGo for: qqqzzz",
+            ]
+        );
+
+        let config = AppConfig {
+            enable_everything: true,
+            ..AppConfig::default()
+        };
+        let (results, actions) = search(
+            "qqqzzz".to_string(),
+            None,
+            &fixture_apps(),
+            &fixture_bookmarks(),
+            &LaunchHistory::default(),
+            &config,
+        );
+        let files = results.last().unwrap();
+        assert_eq!(files.title, "Search files for: qqqzzz");
+        assert!(matches!(
+            actions.get(&files.id),
+            Some(PendingAction::Query(query)) if query == "file: qqqzzz"
+        ));
+        assert!(results.windows(2).all(|pair| pair[0].score > pair[1].score));
+
+        // A prefix asks for one source, so an empty answer stays empty.
+        assert!(run("b qqqzzz", None).0.is_empty());
+    }
+
+    #[test]
+    fn url_like_query_offers_direct_open() {
+        let (results, actions) = run("example.com", None);
+        let url_row = results
+            .iter()
+            .find(|result| result.action_id == "url")
+            .expect("url row");
+        assert_eq!(url_row.title, "Open URL: example.com");
+        assert!(matches!(
+            actions.get(&url_row.id),
+            Some(PendingAction::Url(url)) if url == "example.com"
+        ));
+    }
+
+    #[test]
+    fn result_titles_follow_the_language_setting() {
+        let config = AppConfig {
+            language: Language::Zh,
+            ..AppConfig::default()
+        };
+        let titles_for = |query: &str| {
+            let (results, _) = search(
+                query.to_string(),
+                None,
+                &fixture_apps(),
+                &fixture_bookmarks(),
+                &LaunchHistory::default(),
+                &config,
+            );
+            results
+                .into_iter()
+                .map(|result| result.title)
+                .collect::<Vec<_>>()
+        };
+        assert!(titles_for("example.com").contains(&"打开网址: example.com".to_string()));
+        assert_eq!(titles_for("lock")[0], "锁定");
+        // Both languages still match, whichever one is shown.
+        assert_eq!(titles_for("shut down")[0], "关机");
+    }
+
+    #[test]
+    fn grouping_keeps_categories_in_order_of_their_best_result() {
+        let result = |title: &str, action_id: &str| SearchResult {
+            id: title.to_string(),
+            title: title.to_string(),
+            subtitle: String::new(),
+            score: 0,
+            action_id: action_id.to_string(),
+            match_source: None,
+        };
+        let mut results = vec![
+            result("GitHub Desktop", "app"),
+            result("GitHub", "bookmark"),
+            result("github.com", "url"),
+            result("GitHub CLI", "app"),
+            result("GitHub Issues", "bookmark"),
+            result("Lock", "system"),
+            result("Search Google for: github", "search"),
+        ];
+        group_by_category(&mut results);
+        assert_eq!(
+            titles(&results),
+            vec![
+                "GitHub Desktop",
+                "GitHub CLI",
+                "GitHub",
+                "GitHub Issues",
+                "github.com",
+                "Search Google for: github",
+                "Lock",
+            ]
+        );
+        assert_eq!(result_category("repo"), ResultCategory::Applications);
+        assert_eq!(result_category("snippet"), ResultCategory::Other);
+    }
+
+    #[test]
+    fn mode_restricts_sources() {
+        let (results, _) = run("notes", Some("bookmark"));
+        assert!(results
+            .iter()
+            .all(|result| matches!(result.action_id.as_str(), "bookmark" | "search")));
+        assert_eq!(titles(&results)[0], "Meeting Notes");
+
+        let (results, _) = run("notes", Some("search"));
+        assert_eq!(titles(&results), vec!["Search Google for: notes"]);
+    }
+
+    #[test]
+    fn disabled_sources_are_skipped() {
+        let config = AppConfig {
+            enable_bookmark_results: false,
+            ..AppConfig::default()
+        };
+        let (results, _) = search(
+            "github".to_string(),
+            None,
+            &fixture_apps(),
+            &fixture_bookmarks(),
+            &LaunchHistory::default(),
+            &config,
+        );
+        assert!(results.iter().all(|result| result.action_id != "bookmark"));
+    }
+
+    #[test]
+    fn file_prefix_maps_everything_hits_to_file_actions() {
+        assert_eq!(file_query("file: report.docx"), Some("report.docx"));
+        assert_eq!(file_query("FILE:*.rs"), Some("*.rs"));
+        assert_eq!(file_query("files"), None);
+
+        let paths = vec![
+            r"C:\Users\egg\report.docx".to_string(),
+            r"D:\Projects\".to_string(),
+        ];
+        let (results, actions) = file_results(&paths);
+        assert_eq!(titles(&results), vec!["report.docx", "Projects"]);
+        assert!(results[0].score > results[1].score);
+        assert!(matches!(
+            actions.get(&results[1].id),
+            Some(PendingAction::File(path)) if path == r"D:\Projects\"
+        ));
+    }
+
+    #[test]
+    fn typed_paths_offer_open_and_reveal() {
+        assert_eq!(
+            typed_path(r#" "C:\Users\me\report.pdf" "#),
+            Some(r"C:\Users\me\report.pdf")
+        );
+        assert_eq!(typed_path("d:/projects"), Some("d:/projects"));
+        assert_eq!(typed_path(r"\\nas\share"), Some(r"\\nas\share"));
+        for not_a_path in ["c: drive", "report.pdf", "https://example.com", r"\\"] {
+            assert_eq!(typed_path(not_a_path), None, "{not_a_path}");
+        }
+
+        let text = messages(Language::En);
+        let rows = path_results(r"D:\projects\", true, text);
+        let titles: Vec<&str> = rows
+            .iter()
+            .map(|(result, _)| result.title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            vec!["Open folder: projects", "Show in folder: projects"]
+        );
+        assert!(matches!(
+            &rows[1].1,
+            PendingAction::Path { path, reveal: true } if path == r"D:\projects\"
+        ));
+        assert_eq!(
+            path_results(r"C:\a.txt", false, text)[0].0.title,
+            "Open file: a.txt"
+        );
+    }
+
+    #[test]
+    fn indexed_files_open_by_path_and_other_items_by_url() {
+        let item = |name: &str, path: &str, url: &str| IndexedItem {
+            name: name.to_string(),
+            path: path.to_string(),
+            url: url.to_string(),
+        };
+        let items = vec![
+            item(
+                "report.docx",
+                r"C:\Users\egg\report.docx",
+                "file:C:/Users/egg/report.docx",
+            ),
+            item("Quarterly numbers", "", "mapi://{S-1-5-21}/Inbox/Quarterly"),
+            item("", "", ""),
+        ];
+        let (results, actions) = indexed_results(&items);
+        assert_eq!(titles(&results), vec!["report.docx", "Quarterly numbers"]);
+        assert_eq!(results[0].action_id, "file");
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::File(path)) if path == r"C:\Users\egg\report.docx"
+        ));
+        assert_eq!(results[1].action_id, "url");
+        assert!(matches!(
+            actions.get(&results[1].id),
+            Some(PendingAction::Url(url)) if url.starts_with("mapi://")
+        ));
+    }
+
+    #[test]
+    fn plugin_results_follow_the_plugin_score() {
+        let plugin = PluginManifest {
+            id: "wiki".to_string(),
+            name: "Wiki".to_string(),
+            action_keyword: "wiki".to_string(),
+            action_keywords: Vec::new(),
+            language: "python".to_string(),
+            execute_file_name: "main.py".to_string(),
+            directory: Default::default(),
+        };
+        let reply = |title: &str, score: i64| PluginResult {
+            title: title.to_string(),
+            subtitle: String::new(),
+            score,
+            action: None,
+        };
+        let replies = vec![reply("Rust (fungus)", 10), reply("Rust", 90)];
+        let (results, actions) = plugin_results(&plugin, &replies);
+        assert_eq!(titles(&results), vec!["Rust", "Rust (fungus)"]);
+        assert!(results.iter().all(|result| result.action_id == "plugin"));
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Plugin(call)) if call.plugin.id == "wiki" && call.request.is_none()
+        ));
+    }
+
+    #[test]
+    fn quotes_keep_phrases_together() {
+        assert_eq!(
+            tokenize_query(r#"open "visual studio" now"#),
+            vec!["open", "visual studio", "now"]
+        );
+        assert_eq!(tokenize_query(r#"say"hi there"#), vec!["say", "hi there"]);
+        assert_eq!(tokenize_query(r#""  ""#), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn prefixes_limit_the_query_to_one_source() {
+        let prefixes = QueryPrefixes::default();
+        assert_eq!(
+            split_mode_prefix("b  github", &prefixes),
+            (QueryMode::Bookmark, "github")
+        );
+        assert_eq!(
+            split_mode_prefix("A code", &prefixes),
+            (QueryMode::Application, "code")
+        );
+        assert_eq!(split_mode_prefix("b", &prefixes), (QueryMode::All, "b"));
+        assert_eq!(
+            split_mode_prefix("bing maps", &prefixes),
+            (QueryMode::All, "bing maps")
+        );
+        let custom = QueryPrefixes {
+            bookmarks: "bm".to_string(),
+            ..QueryPrefixes::default()
+        };
+        assert_eq!(
+            split_mode_prefix("bm rust", &custom),
+            (QueryMode::Bookmark, "rust")
+        );
+        assert_eq!(
+            split_mode_prefix("b rust", &custom),
+            (QueryMode::All, "b rust")
+        );
+
+        let action_ids = |query: &str| {
+            run(query, None)
+                .0
+                .into_iter()
+                .map(|result| result.action_id)
+                .collect::<Vec<_>>()
+        };
+        let all = action_ids("chrome");
+        assert!(all.contains(&"app".to_string()) && all.contains(&"bookmark".to_string()));
+        assert!(action_ids("b chrome").iter().all(|id| id == "bookmark"));
+        assert!(action_ids("a chrome").iter().all(|id| id == "app"));
+        assert_eq!(action_ids("s chrome"), vec!["search"]);
+    }
+
+    #[test]
+    fn reports_field_that_won_the_match() {
+        let source_of = |query: &str, title: &str| {
+            run(query, None)
+                .0
+                .into_iter()
+                .find(|result| result.title == title)
+                .and_then(|result| result.match_source)
+        };
+        assert_eq!(
+            source_of("chrome", "Google Chrome"),
+            Some(MatchSource::Name)
+        );
+        assert_eq!(source_of("wx", "微信"), Some(MatchSource::PinyinInitials));
+        assert_eq!(source_of("weixin", "微信"), Some(MatchSource::PinyinFull));
+        assert_eq!(
+            source_of("rust-lang", "Rust Documentation"),
+            Some(MatchSource::Url)
+        );
+    }
+
+    #[test]
+    fn score_breakdown_adds_up_to_the_search_score() {
+        let (results, actions) = run("chrome", None);
+        let result = results
+            .iter()
+            .find(|result| result.title == "Google Chrome")
+            .unwrap();
+        let breakdown = explain_score(
+            "chrome",
+            &actions[&result.id],
+            &LaunchHistory::default(),
+            &AppConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(breakdown.total, result.score);
+        assert_eq!(breakdown.tokens.len(), 1);
+        assert_eq!(breakdown.tokens[0].field, "Google Chrome");
+        assert_eq!(
+            breakdown.lines().last().unwrap(),
+            &format!("total {}", result.score)
+        );
+    }
+
+    #[test]
+    fn pinned_results_rank_first_when_they_match() {
+        let config = AppConfig {
+            pinned_results: vec!["bookmark-Edge 默认:notes".to_string()],
+            ..AppConfig::default()
+        };
+        let (results, actions) = search(
+            "no".to_string(),
+            None,
+            &fixture_apps(),
+            &fixture_bookmarks(),
+            &LaunchHistory::default(),
+            &config,
+        );
+        assert_eq!(titles(&results)[0], "Meeting Notes");
+        let breakdown = explain_score(
+            "no",
+            &actions[&results[0].id],
+            &LaunchHistory::default(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(breakdown.pin_boost, PINNED_BOOST);
+        assert_eq!(breakdown.total, results[0].score);
+
+        // A pin does not make an unrelated query match.
+        let (results, _) = search(
+            "chrome".to_string(),
+            None,
+            &fixture_apps(),
+            &fixture_bookmarks(),
+            &LaunchHistory::default(),
+            &config,
+        );
+        assert!(!titles(&results).contains(&"Meeting Notes"));
+    }
+
+    #[test]
+    fn hidden_results_are_left_out() {
+        let config = AppConfig {
+            hidden_results: vec![
+                "app-shell:notepad".to_string(),
+                "bookmark-Edge 默认:notes".to_string(),
+            ],
+            ..AppConfig::default()
+        };
+        let (results, _) = search(
+            "no".to_string(),
+            None,
+            &fixture_apps(),
+            &fixture_bookmarks(),
+            &LaunchHistory::default(),
+            &config,
+        );
+        let titles = titles(&results);
+        assert!(titles.contains(&"Notion"));
+        assert!(!titles.contains(&"Notepad"));
+        assert!(!titles.contains(&"Meeting Notes"));
+    }
+
+    #[test]
+    fn aliases_rank_their_target_first() {
+        let config = AppConfig {
+            aliases: HashMap::from([
+                (
+                    "np".to_string(),
+                    AliasTarget::App("shell:notepad".to_string()),
+                ),
+                (
+                    "ip".to_string(),
+                    AliasTarget::Command("ipconfig /all".to_string()),
+                ),
+            ]),
+            ..AppConfig::default()
+        };
+        let search_with = |query: &str| {
+            search(
+                query.to_string(),
+                None,
+                &fixture_apps(),
+                &fixture_bookmarks(),
+                &LaunchHistory::default(),
+                &config,
+            )
+        };
+
+        let (results, _) = search_with("NP");
+        assert_eq!(results[0].title, "Notepad");
+        assert_eq!(results[0].match_source, Some(MatchSource::Alias));
+        assert_eq!(
+            titles(&results).iter().filter(|t| **t == "Notepad").count(),
+            1
+        );
+
+        let (results, actions) = search_with("ip");
+        assert_eq!(results[0].title, "Run command: ipconfig /all");
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Command { command, .. }) if command == "ipconfig /all"
+        ));
+
+        // Only the whole alias counts.
+        let (results, _) = search_with("ipc");
+        assert!(results
+            .iter()
+            .all(|result| result.match_source != Some(MatchSource::Alias)));
+    }
+
+    #[test]
+    fn workspaces_launch_their_members_together() {
+        let members = ["Visual Studio Code", "Teams", "GitHub"].map(String::from);
+        let config = AppConfig {
+            workspaces: HashMap::from([("work".to_string(), members.to_vec())]),
+            ..AppConfig::default()
+        };
+        let (results, actions) = search(
+            "Work".to_string(),
+            None,
+            &fixture_apps(),
+            &fixture_bookmarks(),
+            &LaunchHistory::default(),
+            &config,
+        );
+        assert_eq!(results[0].title, "Open workspace work");
+        assert_eq!(
+            results[0].subtitle,
+            "Workspace · Visual Studio Code, GitHub · not found: Teams"
+        );
+        let Some(PendingAction::Workspace { members, .. }) = actions.get(&results[0].id) else {
+            panic!("expected a workspace action");
+        };
+        assert!(matches!(
+            members.as_slice(),
+            [PendingAction::Application(_), PendingAction::Bookmark(_)]
+        ));
+    }
+
+    #[test]
+    fn snippets_are_found_by_the_words_of_their_name() {
+        let config = AppConfig {
+            snippets: HashMap::from([
+                (
+                    "home address".to_string(),
+                    "1 Main St\nSpringfield".to_string(),
+                ),
+                ("sig".to_string(), "Best,\nAda ({date})".to_string()),
+            ]),
+            ..AppConfig::default()
+        };
+        let search_snippets = |query: &str| {
+            search(
+                query.to_string(),
+                None,
+                &fixture_apps(),
+                &fixture_bookmarks(),
+                &LaunchHistory::default(),
+                &config,
+            )
+        };
+        let (results, actions) = search_snippets("addr");
+        assert_eq!(results[0].title, "home address");
+        assert_eq!(results[0].subtitle, "1 Main St Springfield");
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Snippet(body)) if body == "1 Main St\nSpringfield"
+        ));
+        let (results, _) = search_snippets("hdr");
+        assert!(results.iter().all(|result| result.action_id != "snippet"));
+        let (results, _) = search_snippets("b sig");
+        assert!(results.iter().all(|result| result.action_id != "snippet"));
+        assert!(result_for_id("snippet-sig", &[], &[], &config).is_some());
+    }
+
+    #[test]
+    fn note_prefix_yields_a_single_save_row() {
+        let config = AppConfig {
+            notes_file: Some("/home/me/inbox.md".to_string()),
+            ..AppConfig::default()
+        };
+        let (results, actions) = search(
+            "note  buy milk".to_string(),
+            None,
+            &fixture_apps(),
+            &fixture_bookmarks(),
+            &LaunchHistory::default(),
+            &config,
+        );
+        assert_eq!(titles(&results), vec!["Save note: buy milk"]);
+        assert_eq!(results[0].subtitle, "Appends to /home/me/inbox.md");
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::AppendToFile { path, text })
+                if path == "/home/me/inbox.md" && text == "buy milk"
+        ));
+    }
+
+    #[test]
+    fn todo_prefix_lists_open_tasks_and_offers_to_add_one() {
+        let path = std::env::temp_dir().join(format!("egg-todo-search-{}.txt", std::process::id()));
+        fs::write(
+            &path,
+            "2024-03-01 water plants\n(B) pay rent\nx 2024-03-02 buy milk\n(A) 2024-03-05 call plumber\n",
+        )
+        .unwrap();
+        let config = AppConfig {
+            todo_file: Some(path.to_string_lossy().into_owned()),
+            ..AppConfig::default()
+        };
+        let todo = |query: &str| {
+            search(
+                query.to_string(),
+                None,
+                &fixture_apps(),
+                &fixture_bookmarks(),
+                &LaunchHistory::default(),
+                &config,
+            )
+        };
+        let (results, _) = todo("todo");
+        assert_eq!(
+            titles(&results),
+            vec!["call plumber", "pay rent", "water plants"]
+        );
+        assert_eq!(results[0].subtitle, "(A) · added 2024-03-05");
+
+        let (results, actions) = todo("todo buy milk");
+        assert_eq!(titles(&results), vec!["Add task: buy milk"]);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::AddTask { task, .. }) if task == "buy milk"
+        ));
+        let (results, actions) = todo("todo pay rent");
+        assert_eq!(titles(&results), vec!["pay rent"]);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Task { line, .. }) if line == "(B) pay rent"
+        ));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn command_prefix_yields_single_command_row() {
+        let (results, actions) = run(">  Get-Process chrome ", None);
+        assert_eq!(titles(&results), vec!["Run command: Get-Process chrome"]);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Command { command, keep_open: true, .. })
+                if command == "Get-Process chrome"
+        ));
+        assert!(run(">", None).0.is_empty());
+    }
+
+    #[test]
+    fn wsl_prefix_runs_the_command_in_wsl() {
+        let (results, actions) = run("wsl uname -a", None);
+        if WSL_AVAILABLE {
+            assert_eq!(titles(&results), vec!["Run command: uname -a"]);
+            assert_eq!(results[0].subtitle, "WSL · keep window open");
+            assert!(matches!(
+                actions.get(&results[0].id),
+                Some(PendingAction::Command { command, shell: CommandShell::Wsl, .. })
+                    if command == "uname -a"
+            ));
+        } else {
+            assert!(actions
+                .values()
+                .all(|action| !matches!(action, PendingAction::Command { .. })));
+        }
+    }
+
+    #[test]
+    fn window_prefix_matches_titles_and_programs() {
+        let config = AppConfig::default();
+        assert_eq!(
+            split_mode_prefix("w inbox", &config.query_prefixes),
+            (QueryMode::Window, "inbox")
+        );
+        let window = |id, title: &str, process: &str| WindowInfo {
+            id,
+            title: title.to_string(),
+            process: process.to_string(),
+        };
+        let windows = [
+            window(0x10, "Inbox - Outlook", "OUTLOOK.EXE"),
+            window(0x20, "egg - Visual Studio Code", "Code.exe"),
+            window(0x30, "Untitled - Notepad", "notepad.exe"),
+        ];
+
+        let (results, actions) = window_results(&windows, "code", &config);
+        assert_eq!(titles(&results), vec!["egg - Visual Studio Code"]);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::FocusWindow(0x20))
+        ));
+        assert_eq!(
+            titles(&window_results(&windows, "notepad.exe", &config).0),
+            vec!["Untitled - Notepad"]
+        );
+        // An empty search lists every window, front to back.
+        assert_eq!(window_results(&windows, "", &config).0.len(), 3);
+        assert!(!PendingAction::FocusWindow(0x20).persists());
+    }
+
+    #[test]
+    fn kill_prefix_lists_processes_to_end() {
+        let config = AppConfig::default();
+        assert_eq!(
+            split_mode_prefix("kill chrome", &config.query_prefixes),
+            (QueryMode::Process, "chrome")
+        );
+        let process = |pid, name: &str, elevated| ProcessInfo {
+            pid,
+            name: name.to_string(),
+            elevated,
+        };
+        let processes = [
+            process(4242, "notepad.exe", false),
+            process(812, "chrome.exe", false),
+            process(96, "svchost.exe", true),
+        ];
+
+        let (results, actions) = process_results(&processes, "chrome", &config);
+        assert_eq!(titles(&results), vec!["chrome.exe"]);
+        assert_eq!(results[0].subtitle, "PID 812");
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::KillProcess {
+                pid: 812,
+                elevated: false,
+                ..
+            })
+        ));
+        let (results, actions) = process_results(&processes, "4242", &config);
+        assert_eq!(titles(&results), vec!["notepad.exe"]);
+        assert!(!actions[&results[0].id].needs_confirmation());
+
+        // An empty search lists every process by name.
+        let (results, actions) = process_results(&processes, "", &config);
+        assert_eq!(
+            titles(&results),
+            vec!["chrome.exe", "notepad.exe", "svchost.exe"]
+        );
+        assert_eq!(results[2].subtitle, "PID 96 · administrator");
+        assert!(actions[&results[2].id].needs_confirmation());
+        assert!(!actions[&results[2].id].persists());
+    }
+
+    #[test]
+    fn emoji_prefix_finds_symbols_to_copy() {
+        assert_eq!(emoji_query(":fire:"), Some("fire"));
+        assert_eq!(emoji_query("Emoji thumbs up"), Some("thumbs up"));
+        assert_eq!(emoji_query("emojis"), None);
+
+        let (results, actions) = run(":thumbs up", None);
+        assert_eq!(results[0].title, "👍  thumbs up");
+        assert!(results.iter().all(|result| result.action_id == "emoji"));
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Copy(symbol)) if symbol == "👍"
+        ));
+        assert_eq!(run("emoji 赞", None).0[0].title, "👍  thumbs up");
+        assert_eq!(run(":rust", None).0[0].title, "🦀  crab");
+        // Tokens must start a word, so loose fuzzy hits stay out.
+        assert!(run(":hrt", None).0.is_empty());
+        assert_eq!(run(":", None).0[0].title, "😀  grinning face");
+    }
+
+    #[test]
+    fn system_commands_need_a_word_prefix() {
+        let system_titles = |query: &str| -> Vec<String> {
+            run(query, None)
+                .0
+                .into_iter()
+                .filter(|result| result.action_id == "system")
+                .map(|result| result.title)
+                .collect()
+        };
+        assert_eq!(system_titles("lock"), vec!["Lock"]);
+        assert_eq!(system_titles("关机"), vec!["Shut Down"]);
+        assert_eq!(system_titles("gj"), vec!["Shut Down"]);
+        assert_eq!(system_titles("bin"), vec!["Empty Recycle Bin"]);
+        // Fuzzy-only hits such as "sdn" (Shut DowN) must not offer a shutdown.
+        assert!(system_titles("sdn").is_empty());
+
+        let (results, actions) = run("reboot", None);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::System(SystemCommand::Restart))
+        ));
+    }
+
+    #[test]
+    fn uninstallers_only_show_for_uninstall_queries() {
+        let mut apps = fixture_apps();
+        let mut uninstaller = crate::testing::app(
+            "uninstall:wechat",
+            "微信",
+            r"C:\Program Files\Tencent\WeChat\Uninstall.exe",
+        );
+        uninstaller.app_type = AppType::Uninstaller;
+        uninstaller
+            .keywords
+            .extend(["uninstall".to_string(), "卸载".to_string()]);
+        uninstaller.description = Some(r"C:\Program Files\Tencent\WeChat\Uninstall.exe /S".into());
+        apps.push(uninstaller);
+        let action_ids = |query: &str| -> Vec<String> {
+            search(
+                query.to_string(),
+                None,
+                &apps,
+                &[],
+                &LaunchHistory::default(),
+                &AppConfig::default(),
+            )
+            .0
+            .into_iter()
+            .filter(|result| result.action_id != "search")
+            .map(|result| result.action_id)
+            .collect()
+        };
+        assert_eq!(action_ids("微信"), vec!["app"]);
+        assert_eq!(action_ids("uninstall 微信"), vec!["uninstall"]);
+        assert_eq!(action_ids("卸载 微信"), vec!["uninstall"]);
+    }
+
+    #[test]
+    fn settings_pages_need_a_word_of_their_own() {
+        let pages = |query: &str| -> Vec<&str> {
+            let tokens = tokenize_query(query);
+            let scoring = ScoringQuery::new(query, &tokens);
+            SETTINGS_PAGES
+                .iter()
+                .filter(|page| {
+                    match_settings_page(
+                        &SkimMatcherV2::default(),
+                        &RankingWeights::default(),
+                        page,
+                        &scoring,
+                        0,
+                    )
+                    .is_some()
+                })
+                .map(|page| page.id())
+                .collect()
+        };
+        assert_eq!(pages("bluetooth settings"), vec!["bluetooth"]);
+        assert_eq!(pages("蓝牙"), vec!["bluetooth"]);
+        assert_eq!(pages("环境变量"), vec!["sysdm"]);
+        assert_eq!(pages("wifi"), vec!["network-wifi"]);
+        assert!(pages("wifi control panel").is_empty());
+        assert!(pages("settings").is_empty());
+        assert!(pages("blth").is_empty());
+
+        let config = AppConfig::default();
+        let (result, action) = result_for_id("settings-ncpa", &[], &[], &config).unwrap();
+        assert_eq!(result.title, "Network Connections");
+        assert_eq!(result.action_id, "settings");
+        assert!(matches!(action, PendingAction::Url(target) if target == "ncpa.cpl"));
+    }
+
+    #[test]
+    fn egg_prefix_lists_builtin_commands() {
+        let builtin_titles = |query: &str| -> Vec<String> {
+            run(query, None)
+                .0
+                .into_iter()
+                .filter(|result| result.action_id == "builtin")
+                .map(|result| result.title)
+                .collect()
+        };
+        assert_eq!(builtin_titles("egg").len(), BuiltinCommand::ALL.len());
+        assert_eq!(builtin_titles("egg rei"), vec!["Rebuild Index"]);
+        assert_eq!(builtin_titles("EGG tc"), vec!["Quit egg"]);
+        assert!(builtin_titles("eggplant").is_empty());
+
+        let (results, actions) = run("egg reload", None);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Builtin(BuiltinCommand::Reload))
+        ));
+    }
+
+    #[test]
+    fn known_bangs_replace_the_search() {
+        let (results, actions) = run("!gh egg launcher", None);
+        assert_eq!(titles(&results), vec!["Search GitHub for: egg launcher"]);
+        assert!(matches!(
+            actions.get(&results[0].id),
+            Some(PendingAction::Search(url)) if url == "https://github.com/search?q=egg%20launcher"
+        ));
+
+        // Unknown bangs are searched for like any other text.
+        let (results, _) = run("!nope github", None);
+        assert!(!results.iter().any(|result| result.id.starts_with("bang-")));
+    }
+
+    #[test]
+    fn results_are_found_again_by_id() {
+        let (apps, bookmarks, config) = (fixture_apps(), fixture_bookmarks(), AppConfig::default());
+        let (results, _) = run("github", None);
+        let (result, action) = result_for_id(&results[0].id, &apps, &bookmarks, &config).unwrap();
+        assert_eq!(result.title, "GitHub");
+        assert!(matches!(action, PendingAction::Bookmark(_)));
+
+        let (result, _) = result_for_id("app-shell:wechat", &apps, &bookmarks, &config).unwrap();
+        assert_eq!(result.title, "微信");
+        let (_, action) = result_for_id("system-lock", &apps, &bookmarks, &config).unwrap();
+        assert!(matches!(action, PendingAction::System(SystemCommand::Lock)));
+        assert!(result_for_id("app-missing", &apps, &bookmarks, &config).is_none());
+        assert!(result_for_id("search-0", &apps, &bookmarks, &config).is_none());
+    }
 }
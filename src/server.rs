@@ -0,0 +1,696 @@
+//! `egg-cli serve`: answers JSON-RPC 2.0 requests on a localhost TCP port, so
+//! AutoHotkey scripts, Stream Deck plugins and other tools can search egg's
+//! index and launch results without the TUI. Each request and each reply is one
+//! line of JSON. The indexes stay loaded, and the index watcher keeps them fresh.
+//!
+//! - `search {"query": "..."}` returns the results the TUI would list, each with
+//!   the `category` it is grouped under when `group_results` is on. With
+//!   `"icons": true`, apps whose icon is cached get its file path. The other
+//!   icons are extracted in the background and arrive afterwards as
+//!   `icon_ready {"id": "...", "icon": "..."}` notifications, so a search never
+//!   waits on the shell.
+//! - `execute {"id": "..."}` launches a result from the connection's last search,
+//!   or an app, bookmark or system command by id.
+//! - `reindex` rescans the apps and returns how many were found and how long it
+//!   took, in `duration_ms`. While it runs, `reindex_started`,
+//!   `reindex_progress {"source": "...", "count": 12, "done": 3, "sources": 12}`
+//!   and `reindex_finished {"total": 480, "duration_ms": 1250}` notifications
+//!   report each index source as it finishes.
+//!
+//! Only 127.0.0.1 is bound. A line that is not JSON ends the connection, so a web
+//! page posting to the port cannot get a request past its HTTP headers. Every
+//! request carries the per-install `token` from a file only the user can read,
+//! so other users and sandboxed programs on the machine cannot drive egg; a
+//! wrong one ends the connection. Shell commands and ending processes stay
+//! refused unless `server_allows_commands` is on.
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, info, warn};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::runtime::Handle;
+#[cfg(windows)]
+use windows::{
+    core::{w, HSTRING},
+    Win32::{
+        Foundation::{LocalFree, HLOCAL},
+        Security::{
+            Authorization::{
+                ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+            },
+            SetFileSecurityW, DACL_SECURITY_INFORMATION, PROTECTED_DACL_SECURITY_INFORMATION,
+            PSECURITY_DESCRIPTOR,
+        },
+    },
+};
+
+use crate::{
+    icons::{cached_favicon, cached_icon, favicon_file, icon_file, site_of},
+    indexer::{build_index_with_progress, IndexOptions, IndexProgress},
+    logging::span,
+    models::{ActionVariant, ApplicationInfo, SearchResult},
+    search_core::{refine_search, result_category, result_for_id, SearchIndexes},
+    state::{AppState, GenerationToken, PendingAction, QueryGenerations},
+};
+
+/// Requests past this size end the connection.
+const MAX_REQUEST_BYTES: u64 = 64 * 1024;
+const TOKEN_FILE: &str = "server_token";
+/// Random bytes in the token, which is written out in hex.
+const TOKEN_BYTES: usize = 32;
+/// Results whose missing icons a search queues, about a page of a client's list;
+/// later ones only get icons that are already cached.
+const ICON_PREFETCH: usize = 20;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// A request egg understood but could not carry out.
+const SERVER_ERROR: i64 = -32000;
+const UNAUTHORIZED: i64 = -32001;
+/// `execute` named a shell command or process while `server_allows_commands` is off.
+pub(crate) const COMMANDS_REFUSED: i64 = -32002;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    /// Missing for notifications, which get no reply.
+    #[serde(default)]
+    id: Option<Value>,
+}
+
+/// Listens until the process is stopped. `runtime` runs the async indexer for
+/// `reindex` from the connection threads.
+pub fn serve(
+    state: Arc<AppState>,
+    port: u16,
+    dry_run: bool,
+    runtime: Handle,
+) -> Result<(), String> {
+    let token_path = token_path().ok_or("无法确定数据目录")?;
+    let token: Arc<str> = load_or_create_token(&token_path)?.into();
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|err| format!("无法监听端口 {port}: {err}"))?;
+    info!("serving JSON-RPC on 127.0.0.1:{port}");
+    println!("Listening for JSON-RPC requests on 127.0.0.1:{port}. Press Ctrl+C to stop.");
+    println!("Requests must carry the token in {}.", token_path.display());
+    accept(listener, state, token, dry_run, runtime);
+    Ok(())
+}
+
+/// Answers each connection to `listener` on its own thread.
+pub(crate) fn accept(
+    listener: TcpListener,
+    state: Arc<AppState>,
+    token: Arc<str>,
+    dry_run: bool,
+    runtime: Handle,
+) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                warn!("failed to accept a connection: {err}");
+                continue;
+            }
+        };
+        thread::spawn({
+            let (state, token, runtime) = (state.clone(), token.clone(), runtime.clone());
+            move || {
+                let closed = stream.try_clone().and_then(|writer| {
+                    Session::new(state, token, dry_run, runtime, writer).handle(stream)
+                });
+                if let Err(err) = closed {
+                    debug!("connection closed: {err}");
+                }
+            }
+        });
+    }
+}
+
+/// One connection. `execute` can name any result its last `search` returned, as
+/// well as apps, bookmarks and recent results by their lasting ids.
+struct Session<W> {
+    state: Arc<AppState>,
+    /// What each request's `token` param must be.
+    token: Arc<str>,
+    dry_run: bool,
+    runtime: Handle,
+    pending_actions: HashMap<String, (SearchResult, PendingAction)>,
+    /// Replies and notifications share the connection.
+    writer: Arc<Mutex<W>>,
+    /// Started by the first icon that is not cached yet.
+    icon_queue: Option<mpsc::Sender<(GenerationToken, String, IconSource)>>,
+    /// Result ids of the last search whose icons were queued, so each is extracted once.
+    icons_queued: HashSet<String>,
+    searches: QueryGenerations,
+    /// Icons queued for an older search are dropped unextracted.
+    search_token: GenerationToken,
+}
+
+impl<W: Write + Send + 'static> Session<W> {
+    fn new(
+        state: Arc<AppState>,
+        token: Arc<str>,
+        dry_run: bool,
+        runtime: Handle,
+        writer: W,
+    ) -> Self {
+        Self {
+            state,
+            token,
+            dry_run,
+            runtime,
+            pending_actions: HashMap::new(),
+            writer: Arc::new(Mutex::new(writer)),
+            icon_queue: None,
+            icons_queued: HashSet::new(),
+            searches: QueryGenerations::default(),
+            search_token: GenerationToken::detached(),
+        }
+    }
+
+    fn handle(&mut self, stream: impl Read) -> io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = (&mut reader).take(MAX_REQUEST_BYTES).read_line(&mut line)?;
+            if read == 0 {
+                return Ok(());
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (reply, keep_open) = self.answer(&line);
+            if let Some(reply) = reply {
+                writeln!(self.writer.lock().unwrap(), "{reply}")?;
+            }
+            if !keep_open {
+                return Ok(());
+            }
+        }
+    }
+
+    /// The reply to one line, if it needs one, and whether to keep reading.
+    fn answer(&mut self, line: &str) -> (Option<Value>, bool) {
+        let request: Request = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(err) => {
+                let reply = error_reply(Value::Null, PARSE_ERROR, &err.to_string());
+                return (Some(reply), false);
+            }
+        };
+        let id = request.id.clone();
+        if !self.authorized(&request.params) {
+            let reply = id.map(|id| error_reply(id, UNAUTHORIZED, "missing or wrong token"));
+            return (reply, false);
+        }
+        let outcome = if request.jsonrpc == "2.0" {
+            self.call(&request.method, &request.params)
+        } else {
+            Err((INVALID_REQUEST, "jsonrpc must be \"2.0\"".to_string()))
+        };
+        let reply = id.map(|id| match outcome {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_reply(id, code, &message),
+        });
+        (reply, true)
+    }
+
+    fn authorized(&self, params: &Value) -> bool {
+        let sent = params
+            .get("token")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        same_secret(sent.as_bytes(), self.token.as_bytes())
+    }
+
+    fn call(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "search" => {
+                let query = string_param(params, "query")?;
+                let icons = params.get("icons").and_then(Value::as_bool) == Some(true);
+                Ok(json!({ "results": self.search(query, icons) }))
+            }
+            "execute" => {
+                let id = string_param(params, "id")?;
+                self.execute(id).map(|()| Value::Null)
+            }
+            "reindex" => Ok(self.reindex()),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method {method}"))),
+        }
+    }
+
+    fn search(&mut self, query: &str, icons: bool) -> Vec<Value> {
+        let _span = span("search");
+        self.search_token = self.searches.advance();
+        self.icons_queued.clear();
+        let stamp = self.state.search_cache.lock().unwrap().stamp();
+        let config = self.state.search_config();
+        let apps = self.state.app_index.lock().unwrap().clone();
+        let bookmarks = self.state.bookmark_index.lock().unwrap().clone();
+        let history = self.state.launch_history.lock().unwrap().clone();
+        let trigrams = self.state.trigram_index(stamp, &apps, &bookmarks);
+        let (results, mut pending_actions, _) = refine_search(
+            query.to_string(),
+            None,
+            None,
+            SearchIndexes::new(&apps, &bookmarks).with_trigrams(trigrams.as_deref()),
+            &history,
+            &config,
+            &GenerationToken::detached(),
+        );
+        self.pending_actions = results
+            .iter()
+            .filter_map(|result| {
+                let action = pending_actions.remove(&result.id)?;
+                Some((result.id.clone(), (result.clone(), action)))
+            })
+            .collect();
+        results
+            .iter()
+            .enumerate()
+            .map(|(index, result)| {
+                let mut reply = json!(result);
+                reply["category"] = json!(result_category(&result.action_id));
+                let source = match self.pending_actions.get(&result.id) {
+                    Some((_, PendingAction::Application(app))) if icons => {
+                        Some(IconSource::App(Box::new(app.clone())))
+                    }
+                    Some((_, PendingAction::Bookmark(bookmark))) if icons => {
+                        site_of(&bookmark.url).map(IconSource::Site)
+                    }
+                    _ => None,
+                };
+                let icon = source.and_then(|source| {
+                    let fetch = match source {
+                        IconSource::App(_) => true,
+                        IconSource::Site(_) => config.fetch_favicons,
+                    };
+                    if fetch && index < ICON_PREFETCH {
+                        self.icon(&result.id, source)
+                    } else {
+                        source.cached()
+                    }
+                });
+                if let Some(icon) = icon {
+                    reply["icon"] = json!(icon);
+                }
+                reply
+            })
+            .collect()
+    }
+
+    /// The cached icon, or `None` after queueing its extraction.
+    fn icon(&mut self, result_id: &str, source: IconSource) -> Option<PathBuf> {
+        if let Some(icon) = source.cached() {
+            return Some(icon);
+        }
+        if self.icons_queued.insert(result_id.to_string()) {
+            let queue = self
+                .icon_queue
+                .get_or_insert_with(|| spawn_icon_worker(self.writer.clone()));
+            let _ = queue.send((self.search_token.clone(), result_id.to_string(), source));
+        }
+        None
+    }
+
+    fn execute(&self, id: &str) -> Result<(), (i64, String)> {
+        let found = self
+            .pending_actions
+            .get(id)
+            .cloned()
+            .or_else(|| {
+                let config = self.state.search_config();
+                let apps = self.state.app_index.lock().unwrap();
+                let bookmarks = self.state.bookmark_index.lock().unwrap();
+                result_for_id(id, &apps, &bookmarks, &config)
+            })
+            .or_else(|| {
+                let recent = self.state.recent_actions.lock().unwrap();
+                let entry = recent.items().find(|entry| entry.result.id == id)?;
+                Some((entry.result.clone(), entry.action.clone()))
+            });
+        let Some((result, action)) = found else {
+            return Err((SERVER_ERROR, format!("没有 id 为 {id} 的结果")));
+        };
+        if runs_commands(&action) && !self.state.config.lock().unwrap().server_allows_commands {
+            let message = "运行程序、插件、命令和系统操作需要开启 server_allows_commands";
+            return Err((COMMANDS_REFUSED, message.to_string()));
+        }
+        crate::launch(
+            &self.state,
+            &result,
+            action,
+            ActionVariant::Open,
+            self.dry_run,
+        )
+        .map_err(|err| (SERVER_ERROR, err))
+    }
+
+    fn reindex(&self) -> Value {
+        let options = IndexOptions::from_config(&self.state.config.lock().unwrap());
+        let writer = self.writer.clone();
+        let started = Instant::now();
+        let apps = self
+            .runtime
+            .block_on(build_index_with_progress(options, move |step| {
+                let _ = writeln!(writer.lock().unwrap(), "{}", progress_notification(&step));
+            }));
+        crate::store_app_index(&self.state, apps);
+        json!({
+            "apps": self.state.app_index.lock().unwrap().len(),
+            "duration_ms": millis(started.elapsed()),
+        })
+    }
+}
+
+/// Whether `action` could run a program, a plugin or a system command, which a
+/// client could turn into running anything as the user. Actions not listed as
+/// harmless here count as running commands.
+fn runs_commands(action: &PendingAction) -> bool {
+    match action {
+        PendingAction::Application(_)
+        | PendingAction::Bookmark(_)
+        | PendingAction::Search(_)
+        | PendingAction::Copy(_)
+        | PendingAction::Snippet(_)
+        | PendingAction::FocusWindow(_)
+        | PendingAction::Query(_)
+        | PendingAction::AppendToFile { .. }
+        | PendingAction::AddTask { .. }
+        | PendingAction::Task { .. }
+        | PendingAction::Path { reveal: true, .. } => false,
+        // Typed text such as `setup.exe` is opened like a URL too.
+        PendingAction::Url(url) => !(url.starts_with("https://") || url.starts_with("http://")),
+        PendingAction::Workspace { members, .. } => members.iter().any(runs_commands),
+        _ => true,
+    }
+}
+
+/// Compares without stopping at the first difference, so the time a guess
+/// takes does not tell how much of it was right.
+fn same_secret(sent: &[u8], expected: &[u8]) -> bool {
+    sent.len() == expected.len()
+        && sent
+            .iter()
+            .zip(expected)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+pub(crate) fn token_path() -> Option<PathBuf> {
+    let base = dirs::data_local_dir()?;
+    Some(base.join("egg").join("data").join(TOKEN_FILE))
+}
+
+/// The token in `path`, or a new random one written there.
+fn load_or_create_token(path: &Path) -> Result<String, String> {
+    if let Ok(token) = fs::read_to_string(path) {
+        let token = token.trim();
+        if token.len() == TOKEN_BYTES * 2 {
+            return Ok(token.to_string());
+        }
+    }
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::getrandom(&mut bytes).map_err(|err| format!("无法生成令牌: {err}"))?;
+    let token: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    write_private(path, &token).map_err(|err| format!("无法写入令牌文件: {err}"))?;
+    Ok(token)
+}
+
+/// Writes `content` to a new file only the current user can open.
+fn write_private(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    #[cfg(windows)]
+    restrict_to_owner(path)?;
+    file.write_all(content.as_bytes())
+}
+
+/// Replaces the permissions `path` inherited with full access for its owner alone.
+#[cfg(windows)]
+fn restrict_to_owner(path: &Path) -> io::Result<()> {
+    unsafe {
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        // `P` stops inheritance; `OW` is whoever owns the file.
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            w!("D:P(A;;FA;;;OW)"),
+            SDDL_REVISION_1,
+            &mut descriptor,
+            None,
+        )?;
+        let applied = SetFileSecurityW(
+            &HSTRING::from(path),
+            DACL_SECURITY_INFORMATION | PROTECTED_DACL_SECURITY_INFORMATION,
+            descriptor,
+        );
+        let _ = LocalFree(HLOCAL(descriptor.0));
+        if applied.as_bool() {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+/// Where a result's icon comes from.
+enum IconSource {
+    App(Box<ApplicationInfo>),
+    /// The `scheme://host` of a bookmark, whose favicon it gets.
+    Site(String),
+}
+
+impl IconSource {
+    fn cached(&self) -> Option<PathBuf> {
+        match self {
+            Self::App(app) => cached_icon(app),
+            Self::Site(site) => cached_favicon(site),
+        }
+    }
+
+    fn fetch(&self) -> Option<PathBuf> {
+        match self {
+            Self::App(app) => icon_file(app),
+            Self::Site(site) => favicon_file(site),
+        }
+    }
+}
+
+/// Extracts queued icons and downloads queued favicons one at a time and
+/// announces each, skipping those a newer search made stale. Sites without a
+/// favicon are not asked again on the same connection. It stops once the
+/// connection is gone and the queue is empty.
+fn spawn_icon_worker<W: Write + Send + 'static>(
+    writer: Arc<Mutex<W>>,
+) -> mpsc::Sender<(GenerationToken, String, IconSource)> {
+    let (sender, receiver) = mpsc::channel::<(GenerationToken, String, IconSource)>();
+    thread::spawn(move || {
+        let mut without_favicon = HashSet::new();
+        for (search, id, source) in receiver {
+            if search.is_stale() {
+                continue;
+            }
+            if let IconSource::Site(site) = &source {
+                if without_favicon.contains(site) {
+                    continue;
+                }
+            }
+            let Some(icon) = source.fetch() else {
+                if let IconSource::Site(site) = source {
+                    without_favicon.insert(site);
+                }
+                continue;
+            };
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": "icon_ready",
+                "params": { "id": id, "icon": icon },
+            });
+            if writeln!(writer.lock().unwrap(), "{notification}").is_err() {
+                return;
+            }
+        }
+    });
+    sender
+}
+
+fn progress_notification(step: &IndexProgress) -> Value {
+    let (method, params) = match step {
+        IndexProgress::Started => ("reindex_started", json!({})),
+        IndexProgress::Source {
+            source,
+            count,
+            done,
+            sources,
+        } => (
+            "reindex_progress",
+            json!({ "source": source, "count": count, "done": done, "sources": sources }),
+        ),
+        IndexProgress::Finished { total, duration } => (
+            "reindex_finished",
+            json!({ "total": total, "duration_ms": millis(*duration) }),
+        ),
+    };
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn string_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, (i64, String)> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| (INVALID_PARAMS, format!("missing string param {name}")))
+}
+
+fn error_reply(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        plugins::{PluginCall, PluginManifest},
+        system_commands::SystemCommand,
+        testing::fixture_apps,
+    };
+
+    #[tokio::test]
+    async fn answers_requests_line_by_line() {
+        let state = Arc::new(AppState::new());
+        *state.app_index.lock().unwrap() = fixture_apps().into();
+        let mut session = Session::new(state, "secret".into(), true, Handle::current(), Vec::new());
+
+        let (reply, keep_open) = session.answer(
+            r#"{"jsonrpc":"2.0","id":1,"method":"search","params":{"token":"secret","query":"wx"}}"#,
+        );
+        let reply = reply.unwrap();
+        assert!(keep_open);
+        assert_eq!(reply["id"], 1);
+        assert_eq!(reply["result"]["results"][0]["title"], "微信");
+        assert_eq!(reply["result"]["results"][0]["category"], "applications");
+        assert!(session.pending_actions.contains_key("app-shell:wechat"));
+
+        let (reply, _) = session
+            .answer(r#"{"jsonrpc":"2.0","id":2,"method":"launch","params":{"token":"secret"}}"#);
+        assert_eq!(reply.unwrap()["error"]["code"], METHOD_NOT_FOUND);
+        let (reply, _) = session
+            .answer(r#"{"jsonrpc":"2.0","id":3,"method":"execute","params":{"token":"secret"}}"#);
+        assert_eq!(reply.unwrap()["error"]["code"], INVALID_PARAMS);
+        let (reply, _) =
+            session.answer(r#"{"jsonrpc":"2.0","method":"search","params":{"token":"secret"}}"#);
+        assert!(reply.is_none());
+
+        // Shell commands need server_allows_commands.
+        let (reply, _) = session.answer(
+            r#"{"jsonrpc":"2.0","id":4,"method":"search","params":{"token":"secret","query":">calc"}}"#,
+        );
+        let command_id = reply.unwrap()["result"]["results"][0]["id"].clone();
+        assert!(matches!(
+            session.pending_actions.get(command_id.as_str().unwrap()),
+            Some((_, PendingAction::Command { .. }))
+        ));
+        let execute = json!({"jsonrpc": "2.0", "id": 5, "method": "execute",
+            "params": {"token": "secret", "id": command_id}});
+        let (reply, keep_open) = session.answer(&execute.to_string());
+        assert_eq!(reply.unwrap()["error"]["code"], COMMANDS_REFUSED);
+        assert!(keep_open);
+        // So do programs, plugins and system commands reached any other way.
+        let plugin = PluginManifest {
+            id: "wiki".to_string(),
+            name: "Wiki".to_string(),
+            action_keyword: "wiki".to_string(),
+            action_keywords: Vec::new(),
+            language: "python".to_string(),
+            execute_file_name: "main.py".to_string(),
+            directory: Default::default(),
+        };
+        let refused = [
+            PendingAction::Path {
+                path: "C:\\Users\\me\\Downloads\\setup.exe".to_string(),
+                reveal: false,
+            },
+            PendingAction::Url("setup.exe".to_string()),
+            PendingAction::Plugin(PluginCall {
+                plugin,
+                request: None,
+            }),
+            PendingAction::System(SystemCommand::Shutdown),
+        ];
+        for (index, action) in refused.into_iter().enumerate() {
+            let id = format!("refused-{index}");
+            let result = SearchResult {
+                id: id.clone(),
+                title: id.clone(),
+                subtitle: String::new(),
+                score: 0,
+                action_id: String::new(),
+                match_source: None,
+            };
+            session.pending_actions.insert(id.clone(), (result, action));
+            assert_eq!(session.execute(&id).unwrap_err().0, COMMANDS_REFUSED);
+        }
+
+        // Icons that are not cached yet are queued once and announced later.
+        let app = crate::testing::app("shell:missing", "Missing", "/nowhere/missing");
+        assert_eq!(
+            session.icon("app-shell:missing", IconSource::App(Box::new(app.clone()))),
+            None
+        );
+        assert_eq!(
+            session.icon("app-shell:missing", IconSource::App(Box::new(app))),
+            None
+        );
+        assert_eq!(session.icons_queued.len(), 1);
+        assert!(session.icon_queue.is_some());
+        // A new search makes the icons queued for the last one stale.
+        let queued_for = session.search_token.clone();
+        session.answer(
+            r#"{"jsonrpc":"2.0","id":7,"method":"search","params":{"token":"secret","query":"wx"}}"#,
+        );
+        assert!(queued_for.is_stale());
+        assert!(session.icons_queued.is_empty());
+
+        let (reply, keep_open) = session.answer("POST / HTTP/1.1");
+        assert_eq!(reply.unwrap()["error"]["code"], PARSE_ERROR);
+        assert!(!keep_open);
+        let (reply, keep_open) = session
+            .answer(r#"{"jsonrpc":"2.0","id":6,"method":"search","params":{"token":"guess"}}"#);
+        assert_eq!(reply.unwrap()["error"]["code"], UNAUTHORIZED);
+        assert!(!keep_open);
+    }
+}
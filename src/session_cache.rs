@@ -0,0 +1,62 @@
+//! A small in-memory cache for lookups made during one session, such as page
+//! titles and query suggestions. Once `CAPACITY` entries are kept, the oldest
+//! are dropped first.
+
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+pub struct SessionCache<K, V, const CAPACITY: usize> {
+    values: HashMap<K, V>,
+    /// Keys in the order they were first inserted.
+    order: VecDeque<K>,
+}
+
+impl<K, V, const CAPACITY: usize> Default for SessionCache<K, V, CAPACITY> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V, const CAPACITY: usize> SessionCache<K, V, CAPACITY> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.values.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.values.insert(key.clone(), value).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.values.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_oldest_entries_go_first() {
+        let mut cache = SessionCache::<String, u32, 2>::default();
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        cache.insert("a".to_string(), 3);
+        cache.insert("c".to_string(), 4);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(&2));
+        assert_eq!(cache.get("c"), Some(&4));
+    }
+}
@@ -0,0 +1,446 @@
+//! Windows Settings pages (`ms-settings:` links) and classic Control Panel applets
+//! (`.cpl`) offered as search results, so `bluetooth settings` or `环境变量` opens
+//! the page directly. Both open through the shell like a URL.
+
+use std::sync::OnceLock;
+
+use crate::{locale::Language, text_utils::build_pinyin_index};
+
+/// Only Windows has these pages.
+pub const SETTINGS_PAGES_AVAILABLE: bool = cfg!(windows);
+
+pub struct SettingsPage {
+    /// The `ms-settings:` link or `.cpl` file name.
+    pub target: &'static str,
+    pub title: &'static str,
+    pub localized_title: &'static str,
+    pub keywords: &'static [&'static str],
+}
+
+impl SettingsPage {
+    /// Stable identifier used in result ids and launch history.
+    pub fn id(&self) -> &'static str {
+        let target = self.target;
+        target
+            .strip_prefix("ms-settings:")
+            .or_else(|| target.strip_suffix(".cpl"))
+            .unwrap_or(target)
+    }
+
+    pub fn is_control_panel(&self) -> bool {
+        self.target.ends_with(".cpl")
+    }
+
+    /// Title in `language`; searches match both titles either way.
+    pub fn display_title(&self, language: Language) -> &'static str {
+        match language {
+            Language::En => self.title,
+            Language::Zh => self.localized_title,
+        }
+    }
+
+    /// Where the page lives, e.g. `Windows Settings · ms-settings:bluetooth`.
+    pub fn description(&self, language: Language) -> String {
+        let source = match (self.is_control_panel(), language) {
+            (false, Language::En) => "Windows Settings",
+            (false, Language::Zh) => "Windows 设置",
+            (true, Language::En) => "Control Panel",
+            (true, Language::Zh) => "控制面板",
+        };
+        format!("{source} · {}", self.target)
+    }
+
+    /// Words every page of its kind answers to, so `wifi settings` finds Wi-Fi.
+    pub fn kind_keywords(&self) -> &'static [&'static str] {
+        if self.is_control_panel() {
+            &["control panel", "控制面板"]
+        } else {
+            &["settings", "设置"]
+        }
+    }
+
+    /// Pinyin for the localized title in the `full|initials` index format.
+    pub fn pinyin_index(&self) -> Option<&'static str> {
+        static INDEXES: OnceLock<Vec<Option<String>>> = OnceLock::new();
+        let indexes = INDEXES.get_or_init(|| {
+            SETTINGS_PAGES
+                .iter()
+                .map(|page| build_pinyin_index([page.localized_title]))
+                .collect()
+        });
+        let position = SETTINGS_PAGES
+            .iter()
+            .position(|page| page.target == self.target)?;
+        indexes[position].as_deref()
+    }
+}
+
+const fn page(
+    target: &'static str,
+    title: &'static str,
+    localized_title: &'static str,
+    keywords: &'static [&'static str],
+) -> SettingsPage {
+    SettingsPage {
+        target,
+        title,
+        localized_title,
+        keywords,
+    }
+}
+
+pub const SETTINGS_PAGES: &[SettingsPage] = &[
+    page(
+        "ms-settings:display",
+        "Display",
+        "显示",
+        &[
+            "screen",
+            "resolution",
+            "brightness",
+            "scale",
+            "monitor",
+            "分辨率",
+            "亮度",
+            "屏幕",
+        ],
+    ),
+    page(
+        "ms-settings:nightlight",
+        "Night light",
+        "夜间模式",
+        &["blue light", "护眼"],
+    ),
+    page(
+        "ms-settings:sound",
+        "Sound",
+        "声音",
+        &["audio", "volume", "speaker", "microphone", "音量", "麦克风"],
+    ),
+    page(
+        "ms-settings:notifications",
+        "Notifications",
+        "通知",
+        &["focus", "do not disturb", "勿扰"],
+    ),
+    page(
+        "ms-settings:powersleep",
+        "Power & sleep",
+        "电源和睡眠",
+        &["screen timeout", "power mode", "息屏"],
+    ),
+    page(
+        "ms-settings:batterysaver",
+        "Battery",
+        "电池",
+        &["battery saver", "节电"],
+    ),
+    page(
+        "ms-settings:storagesense",
+        "Storage",
+        "存储",
+        &["disk", "space", "cleanup", "磁盘", "清理"],
+    ),
+    page(
+        "ms-settings:multitasking",
+        "Multitasking",
+        "多任务处理",
+        &["snap", "virtual desktops", "alt tab", "分屏"],
+    ),
+    page(
+        "ms-settings:clipboard",
+        "Clipboard",
+        "剪贴板",
+        &["clipboard history", "剪贴板历史"],
+    ),
+    page(
+        "ms-settings:about",
+        "About",
+        "关于",
+        &[
+            "system info",
+            "pc name",
+            "version",
+            "specs",
+            "系统信息",
+            "电脑名称",
+        ],
+    ),
+    page(
+        "ms-settings:bluetooth",
+        "Bluetooth & devices",
+        "蓝牙和其他设备",
+        &["bluetooth", "devices", "pair", "蓝牙", "配对"],
+    ),
+    page(
+        "ms-settings:printers",
+        "Printers & scanners",
+        "打印机和扫描仪",
+        &["printer", "scanner", "打印"],
+    ),
+    page(
+        "ms-settings:mousetouchpad",
+        "Mouse",
+        "鼠标",
+        &["pointer", "cursor", "scroll", "指针"],
+    ),
+    page(
+        "ms-settings:devices-touchpad",
+        "Touchpad",
+        "触摸板",
+        &["trackpad", "gestures", "手势"],
+    ),
+    page(
+        "ms-settings:network-status",
+        "Network & internet",
+        "网络和 Internet",
+        &["network", "internet", "网络", "联网"],
+    ),
+    page(
+        "ms-settings:network-wifi",
+        "Wi-Fi",
+        "WLAN",
+        &["wifi", "wireless", "无线"],
+    ),
+    page(
+        "ms-settings:network-vpn",
+        "VPN",
+        "VPN",
+        &["virtual private network"],
+    ),
+    page(
+        "ms-settings:network-proxy",
+        "Proxy",
+        "代理",
+        &["proxy server", "代理服务器"],
+    ),
+    page(
+        "ms-settings:personalization-background",
+        "Background",
+        "背景",
+        &["wallpaper", "desktop background", "壁纸", "桌面背景"],
+    ),
+    page(
+        "ms-settings:colors",
+        "Colors",
+        "颜色",
+        &[
+            "dark mode",
+            "light mode",
+            "accent color",
+            "深色模式",
+            "主题色",
+        ],
+    ),
+    page("ms-settings:themes", "Themes", "主题", &["theme", "icons"]),
+    page(
+        "ms-settings:taskbar",
+        "Taskbar",
+        "任务栏",
+        &["tray", "start button", "托盘"],
+    ),
+    page(
+        "ms-settings:appsfeatures",
+        "Installed apps",
+        "已安装的应用",
+        &["uninstall", "programs", "apps & features", "卸载", "程序"],
+    ),
+    page(
+        "ms-settings:defaultapps",
+        "Default apps",
+        "默认应用",
+        &["default browser", "file associations", "默认浏览器"],
+    ),
+    page(
+        "ms-settings:startupapps",
+        "Startup apps",
+        "启动应用",
+        &["autostart", "startup", "开机启动", "自启动"],
+    ),
+    page(
+        "ms-settings:yourinfo",
+        "Your info",
+        "账户信息",
+        &["account", "microsoft account", "账户"],
+    ),
+    page(
+        "ms-settings:signinoptions",
+        "Sign-in options",
+        "登录选项",
+        &["password", "pin", "windows hello", "密码"],
+    ),
+    page(
+        "ms-settings:dateandtime",
+        "Date & time",
+        "日期和时间",
+        &["time zone", "clock", "时区", "时间"],
+    ),
+    page(
+        "ms-settings:regionlanguage",
+        "Language & region",
+        "语言和区域",
+        &[
+            "language",
+            "region",
+            "input method",
+            "keyboard",
+            "语言",
+            "输入法",
+        ],
+    ),
+    page(
+        "ms-settings:gaming-gamebar",
+        "Game Bar",
+        "游戏栏",
+        &["xbox game bar", "screen recording", "录屏"],
+    ),
+    page(
+        "ms-settings:easeofaccess",
+        "Accessibility",
+        "辅助功能",
+        &[
+            "ease of access",
+            "text size",
+            "narrator",
+            "magnifier",
+            "无障碍",
+        ],
+    ),
+    page(
+        "ms-settings:privacy",
+        "Privacy & security",
+        "隐私和安全性",
+        &["permissions", "camera", "location", "隐私", "权限"],
+    ),
+    page(
+        "ms-settings:windowsupdate",
+        "Windows Update",
+        "Windows 更新",
+        &["update", "updates", "patch", "更新", "升级"],
+    ),
+    page(
+        "ms-settings:windowsdefender",
+        "Windows Security",
+        "Windows 安全中心",
+        &["antivirus", "defender", "virus", "杀毒"],
+    ),
+    page(
+        "ms-settings:recovery",
+        "Recovery",
+        "恢复",
+        &["reset this pc", "重置"],
+    ),
+    page(
+        "ms-settings:developers",
+        "For developers",
+        "开发者选项",
+        &["developer mode", "开发者模式"],
+    ),
+    page(
+        "ncpa.cpl",
+        "Network Connections",
+        "网络连接",
+        &["adapter", "ethernet", "ip address", "网卡", "适配器"],
+    ),
+    page(
+        "appwiz.cpl",
+        "Programs and Features",
+        "程序和功能",
+        &["uninstall", "add remove programs", "卸载程序"],
+    ),
+    page(
+        "sysdm.cpl",
+        "System Properties",
+        "系统属性",
+        &[
+            "environment variables",
+            "computer name",
+            "remote desktop",
+            "环境变量",
+        ],
+    ),
+    page(
+        "mmsys.cpl",
+        "Sound Devices",
+        "声音设备",
+        &[
+            "playback",
+            "recording",
+            "audio devices",
+            "播放设备",
+            "录制设备",
+        ],
+    ),
+    page(
+        "powercfg.cpl",
+        "Power Options",
+        "电源选项",
+        &["power plan", "电源计划"],
+    ),
+    page(
+        "main.cpl",
+        "Mouse Properties",
+        "鼠标属性",
+        &["pointer", "double click", "指针"],
+    ),
+    page(
+        "inetcpl.cpl",
+        "Internet Options",
+        "Internet 选项",
+        &["internet explorer", "certificates", "证书"],
+    ),
+    page(
+        "firewall.cpl",
+        "Windows Defender Firewall",
+        "Windows Defender 防火墙",
+        &["firewall", "防火墙"],
+    ),
+    page(
+        "intl.cpl",
+        "Region Formats",
+        "区域格式",
+        &["date format", "number format", "日期格式"],
+    ),
+    page(
+        "joy.cpl",
+        "Game Controllers",
+        "游戏控制器",
+        &["joystick", "gamepad", "手柄"],
+    ),
+    page(
+        "hdwwiz.cpl",
+        "Device Manager",
+        "设备管理器",
+        &["drivers", "hardware", "驱动"],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn pages_have_unique_ids() {
+        let ids: HashSet<&str> = SETTINGS_PAGES.iter().map(SettingsPage::id).collect();
+        assert_eq!(ids.len(), SETTINGS_PAGES.len());
+
+        let bluetooth = SETTINGS_PAGES
+            .iter()
+            .find(|page| page.id() == "bluetooth")
+            .unwrap();
+        assert_eq!(
+            bluetooth.description(Language::En),
+            "Windows Settings · ms-settings:bluetooth"
+        );
+        let firewall = SETTINGS_PAGES
+            .iter()
+            .find(|page| page.id() == "firewall")
+            .unwrap();
+        assert!(firewall.is_control_panel());
+        assert_eq!(
+            firewall.description(Language::Zh),
+            "控制面板 · firewall.cpl"
+        );
+    }
+}
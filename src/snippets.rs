@@ -0,0 +1,52 @@
+//! Snippets: named pieces of text under `snippets` in `settings.json`, such as an
+//! address or a canned reply. They are found by name and Enter copies them to the
+//! clipboard, with `{date}`, `{time}`, `{datetime}` and `{weekday}` filled in at
+//! that moment. Other braces are left alone.
+
+use chrono::NaiveDateTime;
+
+/// Placeholders and the `chrono` formats that replace them.
+const PLACEHOLDERS: [(&str, &str); 4] = [
+    ("{date}", "%Y-%m-%d"),
+    ("{time}", "%H:%M"),
+    ("{datetime}", "%Y-%m-%d %H:%M"),
+    ("{weekday}", "%A"),
+];
+
+/// `body` with its placeholders filled in for `now`, in local time.
+pub fn expand(body: &str, now: NaiveDateTime) -> String {
+    PLACEHOLDERS
+        .iter()
+        .fold(body.to_string(), |text, (placeholder, format)| {
+            if text.contains(placeholder) {
+                text.replace(placeholder, &now.format(format).to_string())
+            } else {
+                text
+            }
+        })
+}
+
+/// `body` on one line, for the result subtitle.
+pub fn preview(body: &str) -> String {
+    body.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn placeholders_are_filled_in_when_copied() {
+        let now = NaiveDate::from_ymd_opt(2024, 3, 8)
+            .unwrap()
+            .and_hms_opt(9, 5, 0)
+            .unwrap();
+        assert_eq!(
+            expand("Sent {date} at {time} ({weekday}), {unknown}", now),
+            "Sent 2024-03-08 at 09:05 (Friday), {unknown}"
+        );
+        assert_eq!(expand("{datetime}", now), "2024-03-08 09:05");
+        assert_eq!(preview("Hi,\n\n  Thanks!\r\n"), "Hi, Thanks!");
+    }
+}
@@ -0,0 +1,258 @@
+//! Starting egg when the user signs in. The registry Run key is the simple way;
+//! a Task Scheduler logon task can also wait after sign-in, run elevated without a
+//! UAC prompt and skip starting on battery. `egg-cli startup` applies the
+//! `startup` settings and removes whatever the other method registered.
+
+use std::{env, io, path::Path};
+
+use log::info;
+use windows::{
+    core::{Interface, BSTR, VARIANT},
+    Win32::{
+        Foundation::{E_ACCESSDENIED, VARIANT_BOOL},
+        System::{
+            Com::{CoCreateInstance, CLSCTX_INPROC_SERVER},
+            TaskScheduler::{
+                IExecAction, ILogonTrigger, ITaskFolder, ITaskService, TaskScheduler,
+                TASK_ACTION_EXEC, TASK_CREATE_OR_UPDATE, TASK_INSTANCES_IGNORE_NEW,
+                TASK_LOGON_INTERACTIVE_TOKEN, TASK_RUNLEVEL_HIGHEST, TASK_RUNLEVEL_LUA,
+                TASK_TRIGGER_LOGON,
+            },
+        },
+    },
+};
+use winreg::{enums::*, RegKey};
+
+use crate::{
+    config::{StartupConfig, StartupMethod},
+    indexer::ComInitGuard,
+};
+
+const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+const RUN_VALUE: &str = "egg";
+/// Registered in the root folder of the Task Scheduler library.
+const TASK_NAME: &str = "egg";
+/// `ERROR_FILE_NOT_FOUND` as an HRESULT, returned for a task that does not exist.
+const TASK_NOT_FOUND: i32 = 0x8007_0002_u32 as i32;
+
+/// Where egg is currently registered to start.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StartupStatus {
+    /// The command in the Run key.
+    pub registry: Option<String>,
+    pub task: bool,
+}
+
+impl StartupStatus {
+    pub fn summary(&self) -> String {
+        match (&self.registry, self.task) {
+            (None, false) => "egg does not start when you sign in.".to_string(),
+            (Some(command), false) => format!("Starts from the registry Run key: {command}"),
+            (None, true) => "Starts from the Task Scheduler task \"egg\".".to_string(),
+            (Some(command), true) => format!(
+                "Starts from both the registry Run key ({command}) and the Task Scheduler task \"egg\"."
+            ),
+        }
+    }
+}
+
+/// Registers egg with the configured method and removes the other one, or removes
+/// both when startup is off.
+pub fn apply(config: &StartupConfig) -> Result<(), String> {
+    let exe = env::current_exe().map_err(|err| format!("无法确定程序路径: {err}"))?;
+    match (config.enabled, config.method) {
+        (false, _) => {
+            remove_run_value()?;
+            remove_task()?;
+        }
+        (true, StartupMethod::Registry) => {
+            remove_task()?;
+            set_run_value(&run_command(&exe))?;
+        }
+        (true, StartupMethod::TaskScheduler) => {
+            remove_run_value()?;
+            register_task(config, &exe)?;
+        }
+    }
+    info!(
+        "startup {} via {}",
+        if config.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        },
+        config.method.label()
+    );
+    Ok(())
+}
+
+pub fn status() -> StartupStatus {
+    StartupStatus {
+        registry: RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey(RUN_KEY)
+            .and_then(|key| key.get_value::<String, _>(RUN_VALUE))
+            .ok(),
+        task: unsafe { task_exists() }.unwrap_or(false),
+    }
+}
+
+/// The Run key value: the quoted path to this executable.
+fn run_command(exe: &Path) -> String {
+    format!("\"{}\"", exe.display())
+}
+
+/// Task Scheduler takes delays as ISO 8601 durations.
+fn iso_duration(secs: u32) -> String {
+    let (minutes, secs) = (secs / 60, secs % 60);
+    match (minutes, secs) {
+        (0, secs) => format!("PT{secs}S"),
+        (minutes, 0) => format!("PT{minutes}M"),
+        (minutes, secs) => format!("PT{minutes}M{secs}S"),
+    }
+}
+
+fn set_run_value(command: &str) -> Result<(), String> {
+    let (key, _) = RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(RUN_KEY)
+        .map_err(|err| format!("无法打开启动项注册表: {err}"))?;
+    key.set_value(RUN_VALUE, &command.to_string())
+        .map_err(|err| format!("无法写入启动项: {err}"))
+}
+
+fn remove_run_value() -> Result<(), String> {
+    let Ok((key, _)) = RegKey::predef(HKEY_CURRENT_USER).create_subkey(RUN_KEY) else {
+        return Ok(());
+    };
+    match key.delete_value(RUN_VALUE) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(format!("无法删除启动项: {err}")),
+        _ => Ok(()),
+    }
+}
+
+unsafe fn connect() -> windows::core::Result<ITaskService> {
+    let service: ITaskService = CoCreateInstance(&TaskScheduler, None, CLSCTX_INPROC_SERVER)?;
+    service.Connect(
+        &VARIANT::default(),
+        &VARIANT::default(),
+        &VARIANT::default(),
+        &VARIANT::default(),
+    )?;
+    Ok(service)
+}
+
+unsafe fn root_folder(service: &ITaskService) -> windows::core::Result<ITaskFolder> {
+    service.GetFolder(&BSTR::from("\\"))
+}
+
+unsafe fn task_exists() -> windows::core::Result<bool> {
+    let _com = ComInitGuard::new()?;
+    match root_folder(&connect()?)?.GetTask(&BSTR::from(TASK_NAME)) {
+        Ok(_) => Ok(true),
+        Err(err) if err.code().0 == TASK_NOT_FOUND => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+fn register_task(config: &StartupConfig, exe: &Path) -> Result<(), String> {
+    unsafe { register_task_inner(config, exe) }.map_err(|err| {
+        if err.code() == E_ACCESSDENIED {
+            "注册提权启动任务需要以管理员身份运行".to_string()
+        } else {
+            format!("无法注册启动任务: {err}")
+        }
+    })
+}
+
+unsafe fn register_task_inner(config: &StartupConfig, exe: &Path) -> windows::core::Result<()> {
+    let _com = ComInitGuard::new()?;
+    let service = connect()?;
+    let task = service.NewTask(0)?;
+    task.RegistrationInfo()?
+        .SetDescription(&BSTR::from("Starts egg when you sign in."))?;
+
+    let trigger: ILogonTrigger = task.Triggers()?.Create(TASK_TRIGGER_LOGON)?.cast()?;
+    // Without a user the task would start at every user's sign-in.
+    if let (Ok(domain), Ok(user)) = (env::var("USERDOMAIN"), env::var("USERNAME")) {
+        trigger.SetUserId(&BSTR::from(format!("{domain}\\{user}")))?;
+    }
+    if config.delay_secs > 0 {
+        trigger.SetDelay(&BSTR::from(iso_duration(config.delay_secs)))?;
+    }
+
+    let principal = task.Principal()?;
+    principal.SetLogonType(TASK_LOGON_INTERACTIVE_TOKEN)?;
+    principal.SetRunLevel(if config.elevated {
+        TASK_RUNLEVEL_HIGHEST
+    } else {
+        TASK_RUNLEVEL_LUA
+    })?;
+
+    let settings = task.Settings()?;
+    settings.SetDisallowStartIfOnBatteries(VARIANT_BOOL::from(config.ac_power_only))?;
+    settings.SetStopIfGoingOnBatteries(VARIANT_BOOL::from(false))?;
+    // egg stays open until closed, so the default three-day limit must not apply.
+    settings.SetExecutionTimeLimit(&BSTR::from("PT0S"))?;
+    settings.SetMultipleInstances(TASK_INSTANCES_IGNORE_NEW)?;
+
+    let action: IExecAction = task.Actions()?.Create(TASK_ACTION_EXEC)?.cast()?;
+    action.SetPath(&BSTR::from(exe.to_string_lossy().as_ref()))?;
+    if let Some(folder) = exe.parent() {
+        action.SetWorkingDirectory(&BSTR::from(folder.to_string_lossy().as_ref()))?;
+    }
+
+    root_folder(&service)?.RegisterTaskDefinition(
+        &BSTR::from(TASK_NAME),
+        &task,
+        TASK_CREATE_OR_UPDATE.0,
+        &VARIANT::default(),
+        &VARIANT::default(),
+        TASK_LOGON_INTERACTIVE_TOKEN,
+        &VARIANT::default(),
+    )?;
+    Ok(())
+}
+
+fn remove_task() -> Result<(), String> {
+    let removed = unsafe {
+        ComInitGuard::new().and_then(|_com| {
+            match root_folder(&connect()?)?.DeleteTask(&BSTR::from(TASK_NAME), 0) {
+                Err(err) if err.code().0 == TASK_NOT_FOUND => Ok(()),
+                result => result,
+            }
+        })
+    };
+    removed.map_err(|err| {
+        if err.code() == E_ACCESSDENIED {
+            "删除提权启动任务需要以管理员身份运行".to_string()
+        } else {
+            format!("无法删除启动任务: {err}")
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn commands_and_delays() {
+        assert_eq!(
+            run_command(&PathBuf::from(r"C:\Tools\egg\egg-cli.exe")),
+            r#""C:\Tools\egg\egg-cli.exe""#
+        );
+        assert_eq!(iso_duration(30), "PT30S");
+        assert_eq!(iso_duration(120), "PT2M");
+        assert_eq!(iso_duration(90), "PT1M30S");
+
+        let status = StartupStatus {
+            registry: None,
+            task: true,
+        };
+        assert_eq!(
+            status.summary(),
+            "Starts from the Task Scheduler task \"egg\"."
+        );
+    }
+}
@@ -1,50 +1,366 @@
 use std::{
     collections::{HashMap, VecDeque},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     bookmarks::BookmarkEntry,
-    config::AppConfig,
+    builtin_commands::BuiltinCommand,
+    config::{AppConfig, CommandShell},
+    history::LaunchHistory,
+    index_diff::{IndexDiff, IndexEntry},
+    locale::{messages, Messages},
     models::{ApplicationInfo, SearchResult},
+    plugins::{self, PluginCall, PluginManifest},
+    query_history::QueryHistory,
+    resources::ResourceStatus,
+    search_core::Candidates,
+    system_commands::SystemCommand,
+    trigram_index::TrigramIndex,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PendingAction {
     Application(ApplicationInfo),
     Bookmark(BookmarkEntry),
     Url(String),
     Search(String),
+    /// A file or folder path returned by Everything.
+    File(String),
+    /// A file or folder path typed into the search box; `reveal` selects it in
+    /// Explorer instead of opening it.
+    Path {
+        path: String,
+        reveal: bool,
+    },
+    /// A `>` shell command.
+    Command {
+        command: String,
+        shell: CommandShell,
+        keep_open: bool,
+    },
+    System(SystemCommand),
+    /// Text put on the clipboard, e.g. an emoji.
+    Copy(String),
+    /// A snippet's text, copied with its placeholders filled in.
+    Snippet(String),
+    /// A running window to bring to the front, by its `WindowInfo::id`.
+    #[serde(skip)]
+    FocusWindow(isize),
+    /// A running process to end; `name` guards against the pid being reused.
+    #[serde(skip)]
+    KillProcess {
+        pid: u32,
+        name: String,
+        elevated: bool,
+    },
+    /// An `egg` maintenance command, run inside the TUI.
+    #[serde(skip)]
+    Builtin(BuiltinCommand),
+    /// Replaces the query in the search box, e.g. with a spelling suggestion.
+    #[serde(skip)]
+    Query(String),
+    /// A Flow Launcher plugin result, acted on by the plugin itself.
+    #[serde(skip)]
+    Plugin(PluginCall),
+    /// A `note` saved as a timestamped line at the end of the Markdown file `path`.
+    AppendToFile {
+        path: String,
+        text: String,
+    },
+    /// A task to add to the todo.txt file `path`, dated today.
+    AddTask {
+        path: String,
+        task: String,
+    },
+    /// An open task of the todo.txt file `path`; Enter opens the file.
+    Task {
+        path: String,
+        line: String,
+    },
+    /// The members of a workspace, launched one after another.
+    Workspace {
+        name: String,
+        members: Vec<PendingAction>,
+    },
+}
+
+impl PendingAction {
+    /// Whether the action can be saved and run again after a restart. Plugin
+    /// results only make sense to the plugin process that produced them, and
+    /// window ids and pids to the windows and processes running now.
+    pub fn persists(&self) -> bool {
+        !matches!(
+            self,
+            Self::Builtin(_)
+                | Self::Query(_)
+                | Self::Plugin(_)
+                | Self::FocusWindow(_)
+                | Self::KillProcess { .. }
+        )
+    }
+
+    /// Whether a launch goes into the recent list and the remembered queries.
+    /// Notes and tasks are written down once, not brought up again.
+    pub fn remembered(&self) -> bool {
+        !matches!(
+            self,
+            Self::AppendToFile { .. } | Self::AddTask { .. } | Self::Task { .. }
+        )
+    }
+
+    /// Whether Enter has to be pressed twice: the action is destructive enough
+    /// to ask first.
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(self, Self::KillProcess { elevated: true, .. })
+    }
 }
 
 #[derive(Clone)]
 pub struct AppState {
-    pub app_index: Arc<Mutex<Vec<ApplicationInfo>>>,
-    pub bookmark_index: Arc<Mutex<Vec<BookmarkEntry>>>,
+    /// The indexes and launch history are snapshots: searches clone the inner
+    /// `Arc`, and changes replace it (or copy it first while a search holds it).
+    pub app_index: Arc<Mutex<Arc<Vec<ApplicationInfo>>>>,
+    pub bookmark_index: Arc<Mutex<Arc<Vec<BookmarkEntry>>>>,
     pub config: Arc<Mutex<AppConfig>>,
     pub search_cache: Arc<Mutex<SearchCache>>,
     pub recent_actions: Arc<Mutex<RecentList>>,
+    pub launch_history: Arc<Mutex<Arc<LaunchHistory>>>,
+    /// Queries that led to a launch, for autocompletion.
+    pub query_history: Arc<Mutex<QueryHistory>>,
+    pub resources: Arc<Mutex<ResourceStatus>>,
+    /// Providers switched on or off with F3-F5; lasts until egg exits.
+    pub provider_overrides: Arc<Mutex<ProviderOverrides>>,
+    /// Counts queries, so searches for older input can tell they are stale.
+    pub query_generations: QueryGenerations,
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
-            app_index: Arc::new(Mutex::new(Vec::new())),
-            bookmark_index: Arc::new(Mutex::new(Vec::new())),
+            app_index: Arc::default(),
+            bookmark_index: Arc::default(),
             config: Arc::new(Mutex::new(AppConfig::default())),
             search_cache: Arc::new(Mutex::new(SearchCache::new(8))),
-            recent_actions: Arc::new(Mutex::new(RecentList::new(12))),
+            recent_actions: Arc::new(Mutex::new(RecentList::new(RECENT_CAPACITY))),
+            launch_history: Arc::default(),
+            query_history: Arc::new(Mutex::new(QueryHistory::default())),
+            resources: Arc::new(Mutex::new(ResourceStatus::default())),
+            provider_overrides: Arc::new(Mutex::new(ProviderOverrides::default())),
+            query_generations: QueryGenerations::default(),
+        }
+    }
+
+    /// The config searches run with: the saved settings with this session's
+    /// provider overrides on top.
+    pub fn search_config(&self) -> AppConfig {
+        let mut config = self.config.lock().unwrap().clone();
+        self.provider_overrides.lock().unwrap().apply(&mut config);
+        config
+    }
+
+    /// The trigram index of indexes snapshotted after `stamp`, built on the first
+    /// search after they change; `None` for catalogs small enough to scan.
+    pub fn trigram_index(
+        &self,
+        stamp: CacheStamp,
+        apps: &[ApplicationInfo],
+        bookmarks: &[BookmarkEntry],
+    ) -> Option<Arc<TrigramIndex>> {
+        if let Some(trigrams) = self.search_cache.lock().unwrap().trigrams(stamp) {
+            return Some(trigrams);
+        }
+        let trigrams = Arc::new(TrigramIndex::for_catalog(apps, bookmarks)?);
+        self.search_cache
+            .lock()
+            .unwrap()
+            .set_trigrams(trigrams.clone(), stamp);
+        Some(trigrams)
+    }
+
+    /// The manifests in the plugins folder, read on first use and again only
+    /// after the settings or the folder change.
+    pub fn plugins(&self) -> Arc<[PluginManifest]> {
+        let stamp = {
+            let cache = self.search_cache.lock().unwrap();
+            if let Some(plugins) = cache.plugins() {
+                return plugins;
+            }
+            cache.stamp()
+        };
+        let plugins: Arc<[PluginManifest]> = plugins::plugins_dir()
+            .map(|dir| plugins::discover(&dir))
+            .unwrap_or_default()
+            .into();
+        self.search_cache
+            .lock()
+            .unwrap()
+            .set_plugins(plugins.clone(), stamp);
+        plugins
+    }
+
+    /// Drops the cached searches an index change described by `diff` can affect.
+    pub fn invalidate_changes<T: IndexEntry>(&self, diff: &IndexDiff<T>) {
+        let config = self.search_config();
+        if let Ok(mut cache_guard) = self.search_cache.lock() {
+            cache_guard.invalidate_where(T::PROVIDER, |search| diff.affects(search, &config));
+        }
+    }
+}
+
+/// Generation of the newest query. Every search carries the token of the query
+/// it runs for and stops once a newer query takes over.
+#[derive(Debug, Clone, Default)]
+pub struct QueryGenerations(Arc<AtomicU64>);
+
+impl QueryGenerations {
+    /// Starts a new query, making every earlier token stale.
+    pub fn advance(&self) -> GenerationToken {
+        let generation = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+        GenerationToken {
+            generation,
+            latest: Some(self.0.clone()),
+        }
+    }
+
+    /// Makes every token stale, e.g. when the input was cleared.
+    pub fn cancel(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.0.load(Ordering::SeqCst) == generation
+    }
+}
+
+/// The generation a search runs for.
+#[derive(Debug, Clone)]
+pub struct GenerationToken {
+    generation: u64,
+    /// `None` for searches nothing can supersede.
+    latest: Option<Arc<AtomicU64>>,
+}
+
+impl GenerationToken {
+    /// A token that never goes stale, for one-off searches.
+    pub fn detached() -> Self {
+        Self {
+            generation: 0,
+            latest: None,
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether a newer query started or the search was cancelled.
+    pub fn is_stale(&self) -> bool {
+        self.latest
+            .as_ref()
+            .is_some_and(|latest| latest.load(Ordering::SeqCst) != self.generation)
+    }
+}
+
+/// A result source that can be switched for the session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchProvider {
+    Apps,
+    Bookmarks,
+    /// Everything or Windows Search, whichever is configured.
+    Files,
+}
+
+impl SearchProvider {
+    pub const ALL: [SearchProvider; 3] = [Self::Apps, Self::Bookmarks, Self::Files];
+
+    pub fn label(self, text: &Messages) -> &'static str {
+        match self {
+            Self::Apps => text.provider_apps,
+            Self::Bookmarks => text.provider_bookmarks,
+            Self::Files => text.provider_files,
+        }
+    }
+
+    fn enabled_in(self, config: &AppConfig) -> bool {
+        match self {
+            Self::Apps => config.enable_app_results,
+            Self::Bookmarks => config.enable_bookmark_results,
+            Self::Files => config.enable_everything || config.enable_windows_search,
+        }
+    }
+}
+
+/// Session-only provider switches layered over the config; never saved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProviderOverrides {
+    apps: Option<bool>,
+    bookmarks: Option<bool>,
+    files: Option<bool>,
+}
+
+impl ProviderOverrides {
+    fn slot(&mut self, provider: SearchProvider) -> &mut Option<bool> {
+        match provider {
+            SearchProvider::Apps => &mut self.apps,
+            SearchProvider::Bookmarks => &mut self.bookmarks,
+            SearchProvider::Files => &mut self.files,
+        }
+    }
+
+    /// The override for `provider`, if it differs from the config.
+    pub fn get(&self, provider: SearchProvider) -> Option<bool> {
+        match provider {
+            SearchProvider::Apps => self.apps,
+            SearchProvider::Bookmarks => self.bookmarks,
+            SearchProvider::Files => self.files,
+        }
+    }
+
+    /// Flips `provider` relative to `config` and returns whether it is now on.
+    /// File search can only be switched off, since turning it on needs a backend
+    /// picked in the config.
+    pub fn toggle(&mut self, provider: SearchProvider, config: &AppConfig) -> Result<bool, String> {
+        let configured = provider.enabled_in(config);
+        let enabled = !self.slot(provider).unwrap_or(configured);
+        if provider == SearchProvider::Files && enabled && !configured {
+            return Err(messages(config.language).file_search_not_set_up.into());
+        }
+        *self.slot(provider) = (enabled != configured).then_some(enabled);
+        Ok(enabled)
+    }
+
+    pub fn apply(&self, config: &mut AppConfig) {
+        if let Some(enabled) = self.apps {
+            config.enable_app_results = enabled;
+        }
+        if let Some(enabled) = self.bookmarks {
+            config.enable_bookmark_results = enabled;
+        }
+        if self.files == Some(false) {
+            config.enable_everything = false;
+            config.enable_windows_search = false;
         }
     }
 }
 
 #[derive(Clone)]
 pub struct CachedSearch {
+    /// The query as typed, for telling whether an index change reaches it.
+    pub query: String,
     pub results: Vec<SearchResult>,
     pub pending_actions: HashMap<String, PendingAction>,
 }
 
-#[derive(Clone)]
+/// Entries kept in the recent list.
+pub const RECENT_CAPACITY: usize = 12;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RecentEntry {
     pub result: SearchResult,
     pub action: PendingAction,
@@ -79,11 +395,44 @@ impl RecentList {
         self.entries.iter()
     }
 
-    pub fn retain<F>(&mut self, mut keep: F)
-    where
-        F: FnMut(&RecentEntry) -> bool,
-    {
-        self.entries.retain(|entry| keep(entry));
+    /// Puts back entries saved by an earlier run, newest first, keeping any
+    /// already in the list in front.
+    pub fn restore(&mut self, saved: Vec<RecentEntry>) {
+        for entry in saved {
+            if !entry.action.persists()
+                || self
+                    .entries
+                    .iter()
+                    .any(|item| item.result.id == entry.result.id)
+            {
+                continue;
+            }
+            self.entries.push_back(entry);
+        }
+        self.evict_if_needed();
+    }
+
+    /// Removes the entry for `id`, returning it with its position for [`Self::put_back`].
+    pub fn take(&mut self, id: &str) -> Option<(usize, RecentEntry)> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.result.id == id)?;
+        self.entries.remove(index).map(|entry| (index, entry))
+    }
+
+    /// Reinserts an entry removed by [`Self::take`] where it was, unless it has been
+    /// launched again since.
+    pub fn put_back(&mut self, index: usize, entry: RecentEntry) {
+        if self
+            .entries
+            .iter()
+            .any(|item| item.result.id == entry.result.id)
+        {
+            return;
+        }
+        self.entries.insert(index.min(self.entries.len()), entry);
+        self.evict_if_needed();
     }
 
     fn evict_if_needed(&mut self) {
@@ -93,10 +442,52 @@ impl RecentList {
     }
 }
 
+/// Data a cached search was computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheProvider {
+    Apps,
+    Bookmarks,
+    Config,
+}
+
+impl CacheProvider {
+    fn index(self) -> usize {
+        match self {
+            Self::Apps => 0,
+            Self::Bookmarks => 1,
+            Self::Config => 2,
+        }
+    }
+}
+
+/// Provider generations seen before a search read its inputs.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheStamp([u64; 3]);
+
+impl CacheStamp {
+    /// Whether the app and bookmark generations, the first two, are `generations`'.
+    fn same_indexes(&self, generations: &[u64; 3]) -> bool {
+        self.0[..2] == generations[..2]
+    }
+}
+
+struct CacheEntry {
+    value: CachedSearch,
+    providers: Vec<CacheProvider>,
+}
+
 pub struct SearchCache {
     capacity: usize,
-    entries: HashMap<String, CachedSearch>,
+    entries: HashMap<String, CacheEntry>,
     order: VecDeque<String>,
+    generations: [u64; 3],
+    /// What the last search matched, for the next keystroke to narrow.
+    candidates: Option<Candidates>,
+    /// The trigram index of the app and bookmark indexes, with the stamp it was
+    /// built under.
+    trigrams: Option<(CacheStamp, Arc<TrigramIndex>)>,
+    /// The plugin manifests, until the config is invalidated.
+    plugins: Option<Arc<[PluginManifest]>>,
 }
 
 impl SearchCache {
@@ -105,32 +496,141 @@ impl SearchCache {
             capacity: capacity.max(1),
             entries: HashMap::new(),
             order: VecDeque::new(),
+            generations: [0; 3],
+            candidates: None,
+            trigrams: None,
+            plugins: None,
         }
     }
 
     pub fn get(&mut self, key: &str) -> Option<CachedSearch> {
-        let entry = self.entries.get(key).cloned();
+        let entry = self.entries.get(key).map(|entry| entry.value.clone());
         if entry.is_some() {
             self.promote(key);
         }
         entry
     }
 
-    pub fn insert(&mut self, key: String, value: CachedSearch) {
+    /// Take before snapshotting the indexes a search reads, and pass to `insert`.
+    pub fn stamp(&self) -> CacheStamp {
+        CacheStamp(self.generations)
+    }
+
+    /// Caches a search that read `providers`, unless one of them was invalidated
+    /// after `stamp` was taken.
+    pub fn insert(
+        &mut self,
+        key: String,
+        value: CachedSearch,
+        providers: Vec<CacheProvider>,
+        stamp: CacheStamp,
+    ) {
+        if providers
+            .iter()
+            .any(|provider| stamp.0[provider.index()] != self.generations[provider.index()])
+        {
+            return;
+        }
+
+        let entry = CacheEntry { value, providers };
         if self.entries.contains_key(&key) {
-            self.entries.insert(key.clone(), value);
+            self.entries.insert(key.clone(), entry);
             self.promote(&key);
             return;
         }
 
-        self.entries.insert(key.clone(), value);
+        self.entries.insert(key.clone(), entry);
         self.order.push_back(key);
         self.evict_if_needed();
     }
 
-    pub fn clear(&mut self) {
+    /// The last search's candidates, unless a provider was invalidated after
+    /// `stamp` and they may point into an older index.
+    pub fn candidates(&self, stamp: CacheStamp) -> Option<Candidates> {
+        if stamp.0 == self.generations {
+            self.candidates.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Keeps the candidates a search found, unless a provider was invalidated
+    /// after `stamp` was taken.
+    pub fn set_candidates(&mut self, candidates: Option<Candidates>, stamp: CacheStamp) {
+        if stamp.0 == self.generations {
+            self.candidates = candidates;
+        }
+    }
+
+    /// The trigram index built for the indexes `stamp` was taken with, unless
+    /// the apps or bookmarks changed since and it may point into an older index
+    /// than the caller snapshotted.
+    pub fn trigrams(&self, stamp: CacheStamp) -> Option<Arc<TrigramIndex>> {
+        if !stamp.same_indexes(&self.generations) {
+            return None;
+        }
+        self.trigrams
+            .as_ref()
+            .filter(|(built, _)| built.same_indexes(&stamp.0))
+            .map(|(_, trigrams)| trigrams.clone())
+    }
+
+    /// Keeps a trigram index built from indexes snapshotted after `stamp`,
+    /// unless the apps or bookmarks changed since.
+    pub fn set_trigrams(&mut self, trigrams: Arc<TrigramIndex>, stamp: CacheStamp) {
+        if stamp.same_indexes(&self.generations) {
+            self.trigrams = Some((stamp, trigrams));
+        }
+    }
+
+    pub fn plugins(&self) -> Option<Arc<[PluginManifest]>> {
+        self.plugins.clone()
+    }
+
+    /// Keeps the plugin manifests read after `stamp` was taken, unless the
+    /// config was invalidated since.
+    pub fn set_plugins(&mut self, plugins: Arc<[PluginManifest]>, stamp: CacheStamp) {
+        let config = CacheProvider::Config.index();
+        if stamp.0[config] == self.generations[config] {
+            self.plugins = Some(plugins);
+        }
+    }
+
+    /// Drops every cached search that read `provider`.
+    pub fn invalidate(&mut self, provider: CacheProvider) {
+        self.invalidate_where(provider, |_| true);
+    }
+
+    /// Drops the cached searches that read `provider` and that `affected` picks.
+    /// Searches still running and the last candidates may have read the old
+    /// data, so they are not kept either way.
+    pub fn invalidate_where(
+        &mut self,
+        provider: CacheProvider,
+        affected: impl Fn(&CachedSearch) -> bool,
+    ) {
+        self.generations[provider.index()] += 1;
+        self.candidates = None;
+        if provider == CacheProvider::Config {
+            self.plugins = None;
+        }
+        self.entries
+            .retain(|_, entry| !entry.providers.contains(&provider) || !affected(&entry.value));
+        self.order.retain(|key| self.entries.contains_key(key));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Drops every cached search without invalidating providers, and returns how
+    /// many there were.
+    pub fn clear(&mut self) -> usize {
+        let cleared = self.entries.len();
         self.entries.clear();
         self.order.clear();
+        self.candidates = None;
+        cleared
     }
 
     fn promote(&mut self, key: &str) {
@@ -148,3 +648,191 @@ impl SearchCache {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(title: &str) -> CachedSearch {
+        CachedSearch {
+            query: title.to_string(),
+            results: vec![SearchResult {
+                id: title.to_string(),
+                title: title.to_string(),
+                subtitle: String::new(),
+                score: 0,
+                action_id: "app".to_string(),
+                match_source: None,
+            }],
+            pending_actions: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn invalidate_drops_only_dependent_entries() {
+        let mut cache = SearchCache::new(8);
+        let stamp = cache.stamp();
+        cache.insert(
+            "apps".to_string(),
+            cached("apps"),
+            vec![CacheProvider::Config, CacheProvider::Apps],
+            stamp,
+        );
+        cache.insert(
+            "all".to_string(),
+            cached("all"),
+            vec![
+                CacheProvider::Config,
+                CacheProvider::Apps,
+                CacheProvider::Bookmarks,
+            ],
+            stamp,
+        );
+
+        cache.invalidate(CacheProvider::Bookmarks);
+        assert!(cache.get("apps").is_some());
+        assert!(cache.get("all").is_none());
+
+        cache.invalidate(CacheProvider::Config);
+        assert!(cache.get("apps").is_none());
+    }
+
+    #[test]
+    fn candidates_do_not_outlive_an_index_change() {
+        let mut cache = SearchCache::new(8);
+        let stamp = cache.stamp();
+        cache.set_candidates(Some(Candidates::default()), stamp);
+        assert!(cache.candidates(stamp).is_some());
+
+        cache.invalidate(CacheProvider::Bookmarks);
+        assert!(cache.candidates(cache.stamp()).is_none());
+        cache.set_candidates(Some(Candidates::default()), stamp);
+        assert!(cache.candidates(cache.stamp()).is_none());
+    }
+
+    #[test]
+    fn trigram_index_lasts_until_the_indexes_change() {
+        let mut cache = SearchCache::new(8);
+        let stamp = cache.stamp();
+        cache.set_trigrams(Arc::new(TrigramIndex::default()), stamp);
+        cache.invalidate(CacheProvider::Config);
+        assert!(cache.trigrams(cache.stamp()).is_some());
+
+        cache.invalidate(CacheProvider::Apps);
+        assert!(cache.trigrams(cache.stamp()).is_none());
+        cache.set_trigrams(Arc::new(TrigramIndex::default()), stamp);
+        assert!(cache.trigrams(cache.stamp()).is_none());
+
+        // A stamp taken before the indexes changed gets no index either.
+        let stamp = cache.stamp();
+        cache.set_trigrams(Arc::new(TrigramIndex::default()), stamp);
+        cache.invalidate(CacheProvider::Bookmarks);
+        assert!(cache.trigrams(stamp).is_none());
+    }
+
+    #[test]
+    fn plugins_are_kept_until_the_config_changes() {
+        let mut cache = SearchCache::new(8);
+        let stamp = cache.stamp();
+        cache.set_plugins(Arc::from(Vec::new()), stamp);
+        cache.invalidate(CacheProvider::Apps);
+        assert!(cache.plugins().is_some());
+
+        cache.invalidate(CacheProvider::Config);
+        assert!(cache.plugins().is_none());
+        cache.set_plugins(Arc::from(Vec::new()), stamp);
+        assert!(cache.plugins().is_none());
+    }
+
+    #[test]
+    fn insert_skips_results_computed_before_invalidation() {
+        let mut cache = SearchCache::new(8);
+        let stamp = cache.stamp();
+        cache.invalidate(CacheProvider::Apps);
+        cache.insert(
+            "stale".to_string(),
+            cached("stale"),
+            vec![CacheProvider::Apps],
+            stamp,
+        );
+        assert!(cache.get("stale").is_none());
+
+        // An unrelated invalidation does not make the search stale.
+        let stamp = cache.stamp();
+        cache.invalidate(CacheProvider::Bookmarks);
+        cache.insert(
+            "fresh".to_string(),
+            cached("fresh"),
+            vec![CacheProvider::Apps],
+            stamp,
+        );
+        assert!(cache.get("fresh").is_some());
+    }
+
+    #[test]
+    fn recent_entries_survive_a_round_trip() {
+        let entry = |id: &str, action: PendingAction| RecentEntry {
+            result: cached(id).results.remove(0),
+            action,
+        };
+        let mut recent = RecentList::new(12);
+        recent.insert(entry("lock", PendingAction::System(SystemCommand::Lock)));
+        recent.insert(entry(
+            "spelling",
+            PendingAction::Query("replaced".to_string()),
+        ));
+        recent.insert(entry(
+            "docs",
+            PendingAction::Url("https://docs.rs".to_string()),
+        ));
+
+        let saved: Vec<&RecentEntry> = recent
+            .items()
+            .filter(|entry| entry.action.persists())
+            .collect();
+        let json = serde_json::to_string(&saved).unwrap();
+        let loaded: Vec<RecentEntry> = serde_json::from_str(&json).unwrap();
+
+        let mut restored = RecentList::new(2);
+        restored.insert(entry(
+            "docs",
+            PendingAction::Url("https://docs.rs".to_string()),
+        ));
+        restored.restore(loaded);
+        let ids: Vec<_> = restored
+            .items()
+            .map(|item| item.result.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["docs", "lock"]);
+        assert!(matches!(
+            restored.items().nth(1).unwrap().action,
+            PendingAction::System(SystemCommand::Lock)
+        ));
+    }
+
+    #[test]
+    fn taken_entries_go_back_where_they_were() {
+        let entry = |id: &str| RecentEntry {
+            result: cached(id).results.remove(0),
+            action: PendingAction::Url(format!("https://{id}.example")),
+        };
+        let mut recent = RecentList::new(12);
+        for id in ["c", "b", "a"] {
+            recent.insert(entry(id));
+        }
+        let (index, taken) = recent.take("b").unwrap();
+        assert_eq!(index, 1);
+        assert!(recent.take("missing").is_none());
+
+        recent.put_back(index, taken.clone());
+        let ids: Vec<_> = recent.items().map(|item| item.result.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+
+        // Launched again in the meantime: the newer entry stays at the front.
+        let (index, taken) = recent.take("c").unwrap();
+        recent.insert(entry("c"));
+        recent.put_back(index, taken);
+        let ids: Vec<_> = recent.items().map(|item| item.result.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "a", "b"]);
+    }
+}
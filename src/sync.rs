@@ -0,0 +1,291 @@
+//! Opt-in sync of settings and launch history through a folder the user already
+//! syncs between machines (`sync_folder`). Each setting carries the time it last
+//! changed, so the newest edit of a key wins; a list edited on both machines since
+//! the last sync is merged instead. Launch history merges per item.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    cache::write_atomically,
+    config::AppConfig,
+    history::{unix_now, LaunchHistory},
+};
+
+const SYNC_FILE: &str = "egg-sync.json";
+/// What the last sync agreed on, to tell local edits from untouched values.
+const SYNC_STATE_FILE: &str = "sync_state.json";
+/// Settings that only make sense on this machine; `version` is the layout of
+/// this machine's file.
+const LOCAL_ONLY_KEYS: &[&str] = &["sync_folder", "version"];
+
+/// A setting and the time (Unix seconds) it last changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Stamped {
+    value: Value,
+    modified: u64,
+}
+
+type Stamps = HashMap<String, Stamped>;
+
+/// Contents of `egg-sync.json` in the shared folder.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SharedState {
+    #[serde(default)]
+    settings: Stamps,
+    #[serde(default)]
+    history: LaunchHistory,
+}
+
+#[derive(Debug)]
+pub struct SyncOutcome {
+    /// The merged settings, already saved to `settings.json`.
+    pub config: AppConfig,
+    /// Settings taken from another machine.
+    pub pulled: Vec<String>,
+}
+
+/// The configured sync folder, if any.
+pub fn sync_folder(config: &AppConfig) -> Option<PathBuf> {
+    let folder = config.sync_folder.as_deref()?.trim();
+    (!folder.is_empty()).then(|| PathBuf::from(folder))
+}
+
+/// Merges local settings and launch history with the copy in `folder` and writes
+/// the result to both sides.
+pub fn sync_with(folder: &Path, config: &AppConfig) -> Result<SyncOutcome, String> {
+    if !folder.is_dir() {
+        return Err(format!("同步文件夹不存在: {}", folder.display()));
+    }
+    let shared_path = folder.join(SYNC_FILE);
+    // A shared file that does not parse may be mid-sync; leave it alone rather
+    // than overwrite another machine's changes.
+    let shared = match fs::read_to_string(&shared_path) {
+        Ok(content) => serde_json::from_str::<SharedState>(&content)
+            .map_err(|err| format!("{SYNC_FILE} 格式错误: {err}"))?,
+        Err(_) => SharedState::default(),
+    };
+    let known = load_sync_state();
+
+    let Value::Object(local) = serde_json::to_value(config).map_err(|err| err.to_string())? else {
+        return Err("无法序列化设置".into());
+    };
+    let (merged, stamps, pulled) = merge_settings(&local, &known, &shared.settings, unix_now());
+    let merged_config: AppConfig = serde_json::from_value(Value::Object(merged))
+        .map_err(|err| format!("同步的设置无效: {err}"))?;
+    if !pulled.is_empty() {
+        merged_config.save()?;
+    }
+
+    let mut history = LaunchHistory::load();
+    history.merge(&shared.history);
+    history.save()?;
+
+    let shared = SharedState {
+        settings: stamps.clone(),
+        history,
+    };
+    write_atomically(
+        &shared_path,
+        &serde_json::to_string_pretty(&shared).map_err(|err| err.to_string())?,
+    )?;
+    debug!("wrote {:?}", shared_path);
+    save_sync_state(&stamps)?;
+    info!(
+        "synced with {:?}; {} settings came from another machine",
+        folder,
+        pulled.len()
+    );
+    Ok(SyncOutcome {
+        config: merged_config,
+        pulled,
+    })
+}
+
+/// Merges `local` settings with the `shared` copy. `known` holds what the last
+/// sync wrote, so a key counts as edited here when its value moved away from it.
+/// Returns the merged settings, their stamps and the keys taken from `shared`.
+fn merge_settings(
+    local: &Map<String, Value>,
+    known: &Stamps,
+    shared: &Stamps,
+    now: u64,
+) -> (Map<String, Value>, Stamps, Vec<String>) {
+    let mut merged = local.clone();
+    let mut stamps = Stamps::new();
+    let mut pulled = Vec::new();
+    // A machine joining the sync takes the shared settings over its own defaults.
+    let first_sync = known.is_empty();
+    let keys: BTreeSet<&String> = local.keys().chain(shared.keys()).collect();
+    for key in keys {
+        if LOCAL_ONLY_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let last = known.get(key);
+        let ours = local.get(key).map(|value| match last {
+            Some(last) if last.value == *value => last.clone(),
+            _ => Stamped {
+                value: value.clone(),
+                modified: if first_sync { 0 } else { now },
+            },
+        });
+        let theirs = shared.get(key);
+        let winner = match (ours, theirs) {
+            (Some(ours), Some(theirs)) if ours.value == theirs.value => Stamped {
+                modified: ours.modified.max(theirs.modified),
+                ..ours
+            },
+            (Some(ours), Some(theirs)) => {
+                let (edited_here, edited_there) = match last {
+                    Some(last) => (last.value != ours.value, last.modified < theirs.modified),
+                    None => (!first_sync, true),
+                };
+                match (&ours.value, &theirs.value) {
+                    (Value::Array(a), Value::Array(b)) if edited_here && edited_there => Stamped {
+                        value: Value::Array(union(a, b)),
+                        modified: now,
+                    },
+                    _ if theirs.modified > ours.modified => theirs.clone(),
+                    _ => ours,
+                }
+            }
+            (Some(ours), None) => ours,
+            (None, Some(theirs)) => theirs.clone(),
+            (None, None) => continue,
+        };
+        if local.get(key) != Some(&winner.value) {
+            pulled.push(key.clone());
+            merged.insert(key.clone(), winner.value.clone());
+        }
+        stamps.insert(key.clone(), winner);
+    }
+    (merged, stamps, pulled)
+}
+
+/// Items of `a`, then those only in `b`.
+fn union(a: &[Value], b: &[Value]) -> Vec<Value> {
+    let mut merged = a.to_vec();
+    for item in b {
+        if !merged.contains(item) {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+/// The stamps of the last sync; none when the state file is missing or unreadable.
+fn load_sync_state() -> Stamps {
+    sync_state_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_sync_state(stamps: &Stamps) -> Result<(), String> {
+    let Some(path) = sync_state_path() else {
+        return Err("无法确定数据目录".into());
+    };
+    let payload = serde_json::to_string(stamps).map_err(|err| err.to_string())?;
+    write_atomically(&path, &payload)
+}
+
+fn sync_state_path() -> Option<PathBuf> {
+    let base = dirs::data_local_dir()?;
+    Some(base.join("egg").join("data").join(SYNC_STATE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn stamped(value: Value, modified: u64) -> Stamped {
+        Stamped { value, modified }
+    }
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn newest_edit_of_each_setting_wins() {
+        let local =
+            object(json!({ "max_results": 20, "query_delay_ms": 80, "sync_folder": "D:\\Sync" }));
+        let known = Stamps::from([
+            ("max_results".to_string(), stamped(json!(40), 100)),
+            ("query_delay_ms".to_string(), stamped(json!(80), 100)),
+        ]);
+        let shared = Stamps::from([
+            ("max_results".to_string(), stamped(json!(30), 150)),
+            ("query_delay_ms".to_string(), stamped(json!(120), 150)),
+            ("sync_folder".to_string(), stamped(json!("E:\\Other"), 150)),
+        ]);
+
+        let (merged, stamps, pulled) = merge_settings(&local, &known, &shared, 200);
+        // Edited here at 200, after the other machine's edit at 150.
+        assert_eq!(merged["max_results"], json!(20));
+        // Untouched here, edited there.
+        assert_eq!(merged["query_delay_ms"], json!(120));
+        assert_eq!(pulled, vec!["query_delay_ms"]);
+        assert_eq!(merged["sync_folder"], json!("D:\\Sync"));
+        assert!(!stamps.contains_key("sync_folder"));
+        assert_eq!(stamps["max_results"].modified, 200);
+    }
+
+    #[test]
+    fn first_sync_takes_the_shared_settings() {
+        let local =
+            object(json!({ "max_results": 40, "dashboard_sections": ["recent", "frequent"] }));
+        let shared = Stamps::from([
+            ("max_results".to_string(), stamped(json!(25), 150)),
+            (
+                "dashboard_sections".to_string(),
+                stamped(json!(["frequent"]), 150),
+            ),
+        ]);
+
+        let (merged, _, pulled) = merge_settings(&local, &Stamps::new(), &shared, 200);
+        assert_eq!(merged["max_results"], json!(25));
+        assert_eq!(merged["dashboard_sections"], json!(["frequent"]));
+        assert_eq!(pulled.len(), 2);
+    }
+
+    #[test]
+    fn lists_edited_on_both_machines_are_merged() {
+        let local =
+            object(json!({ "disabled_plugins": ["a", "b"], "system_tool_exclusions": ["x"] }));
+        let known = Stamps::from([
+            ("disabled_plugins".to_string(), stamped(json!(["a"]), 100)),
+            (
+                "system_tool_exclusions".to_string(),
+                stamped(json!(["x", "y"]), 100),
+            ),
+        ]);
+        let shared = Stamps::from([
+            (
+                "disabled_plugins".to_string(),
+                stamped(json!(["a", "c"]), 150),
+            ),
+            (
+                "system_tool_exclusions".to_string(),
+                stamped(json!(["x", "y"]), 100),
+            ),
+        ]);
+
+        let (merged, _, _) = merge_settings(&local, &known, &shared, 200);
+        assert_eq!(merged["disabled_plugins"], json!(["a", "b", "c"]));
+        // Only removed here, so the removal sticks.
+        assert_eq!(merged["system_tool_exclusions"], json!(["x"]));
+    }
+}
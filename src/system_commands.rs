@@ -0,0 +1,123 @@
+//! Built-in system actions (lock, sleep, shutdown, ...) offered as search results.
+
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Language;
+
+/// A system action; executed by `run_system_command` in `windows_utils` or `unix_utils`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemCommand {
+    Lock,
+    Sleep,
+    Shutdown,
+    Restart,
+    SignOut,
+    EmptyRecycleBin,
+    OpenSettings,
+}
+
+impl SystemCommand {
+    pub const ALL: [SystemCommand; 7] = [
+        SystemCommand::Lock,
+        SystemCommand::Sleep,
+        SystemCommand::Shutdown,
+        SystemCommand::Restart,
+        SystemCommand::SignOut,
+        SystemCommand::EmptyRecycleBin,
+        SystemCommand::OpenSettings,
+    ];
+
+    /// Stable identifier used in result ids and launch history.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Lock => "lock",
+            Self::Sleep => "sleep",
+            Self::Shutdown => "shutdown",
+            Self::Restart => "restart",
+            Self::SignOut => "sign-out",
+            Self::EmptyRecycleBin => "empty-recycle-bin",
+            Self::OpenSettings => "settings",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Lock => "Lock",
+            Self::Sleep => "Sleep",
+            Self::Shutdown => "Shut Down",
+            Self::Restart => "Restart",
+            Self::SignOut => "Sign Out",
+            Self::EmptyRecycleBin => "Empty Recycle Bin",
+            Self::OpenSettings => "Settings",
+        }
+    }
+
+    pub fn localized_title(self) -> &'static str {
+        match self {
+            Self::Lock => "锁定",
+            Self::Sleep => "睡眠",
+            Self::Shutdown => "关机",
+            Self::Restart => "重启",
+            Self::SignOut => "注销",
+            Self::EmptyRecycleBin => "清空回收站",
+            Self::OpenSettings => "设置",
+        }
+    }
+
+    /// Title in `language`; searches match both titles either way.
+    pub fn display_title(self, language: Language) -> &'static str {
+        match language {
+            Language::En => self.title(),
+            Language::Zh => self.localized_title(),
+        }
+    }
+
+    pub fn description(self, language: Language) -> &'static str {
+        if language == Language::En {
+            return match self {
+                Self::Lock => "Lock this PC",
+                Self::Sleep => "Put the PC to sleep",
+                Self::Shutdown => "Turn off the PC",
+                Self::Restart => "Restart the PC",
+                Self::SignOut => "Sign out the current user",
+                Self::EmptyRecycleBin => "Permanently delete the files in the Recycle Bin",
+                Self::OpenSettings => "Open system settings",
+            };
+        }
+        match self {
+            Self::Lock => "锁定此电脑",
+            Self::Sleep => "让电脑进入睡眠",
+            Self::Shutdown => "关闭电脑",
+            Self::Restart => "重新启动电脑",
+            Self::SignOut => "注销当前用户",
+            Self::EmptyRecycleBin => "永久删除回收站中的文件",
+            Self::OpenSettings => "打开系统设置",
+        }
+    }
+
+    pub fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Lock => &["lock screen", "lock computer", "win+l", "锁屏"],
+            Self::Sleep => &["suspend", "standby", "休眠", "待机"],
+            Self::Shutdown => &["shutdown", "power off", "turn off", "关闭电脑"],
+            Self::Restart => &["reboot", "restart computer", "重新启动"],
+            Self::SignOut => &["log off", "logout", "sign off", "退出登录"],
+            Self::EmptyRecycleBin => &["trash", "recycle", "回收站"],
+            Self::OpenSettings => &["control panel", "preferences", "ms-settings", "控制面板"],
+        }
+    }
+
+    /// Pinyin for the localized title in the `full|initials` index format.
+    pub fn pinyin_index(self) -> &'static str {
+        match self {
+            Self::Lock => "suoding|sd",
+            Self::Sleep => "shuimian|sm",
+            Self::Shutdown => "guanji|gj",
+            Self::Restart => "chongqi|cq",
+            Self::SignOut => "zhuxiao|zx",
+            Self::EmptyRecycleBin => "qingkonghuishouzhan|qkhsz",
+            Self::OpenSettings => "shezhi|sz",
+        }
+    }
+}
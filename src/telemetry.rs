@@ -0,0 +1,244 @@
+//! Local-only ranking telemetry: queries that found nothing and launches that
+//! needed scrolling. Nothing is recorded unless `ranking_telemetry` is enabled.
+
+use std::{collections::HashMap, fmt::Write as _, fs, path::PathBuf};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+
+const TELEMETRY_FILE: &str = "ranking_telemetry.json";
+const MAX_ENTRIES: usize = 200;
+/// Launches from this zero-based position or deeper count as "had to scroll".
+pub const DEEP_POSITION: usize = 5;
+const REPORT_ROWS: usize = 15;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QueryStat {
+    pub count: u32,
+    pub last_seen: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeepLaunch {
+    pub query: String,
+    pub result_id: String,
+    pub title: String,
+    pub count: u32,
+    /// Sum of zero-based positions over all `count` launches.
+    pub position_total: u64,
+    pub last_seen: u64,
+}
+
+/// How a TUI session ended, as far as ranking is concerned.
+pub struct SessionOutcome<'a> {
+    pub query: &'a str,
+    /// Matches excluding the web search fallback row.
+    pub local_results: usize,
+    /// Zero-based position, id and title of the launched result.
+    pub launched: Option<(usize, &'a str, &'a str)>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RankingTelemetry {
+    #[serde(default)]
+    no_results: HashMap<String, QueryStat>,
+    #[serde(default)]
+    deep_launches: Vec<DeepLaunch>,
+}
+
+impl RankingTelemetry {
+    pub fn load() -> Self {
+        let Some(path) = telemetry_path() else {
+            return Self::default();
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_json::from_str(&content) {
+            Ok(telemetry) => telemetry,
+            Err(err) => {
+                warn!("failed to parse ranking telemetry {:?}: {err}", path);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = telemetry_path() else {
+            return Err("无法确定数据目录".into());
+        };
+        let payload = serde_json::to_string(self).map_err(|err| err.to_string())?;
+        cache::write_atomically(&path, payload)?;
+        debug!("wrote ranking telemetry {:?}", path);
+        Ok(())
+    }
+
+    /// Deletes the telemetry file. Returns `false` when there was nothing to delete.
+    pub fn purge() -> Result<bool, String> {
+        let Some(path) = telemetry_path() else {
+            return Err("无法确定数据目录".into());
+        };
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.no_results.is_empty() && self.deep_launches.is_empty()
+    }
+
+    /// Records a finished session; returns whether anything was worth keeping.
+    pub fn observe(&mut self, outcome: &SessionOutcome<'_>, now: u64) -> bool {
+        let query = outcome.query.trim();
+        if query.is_empty() {
+            return false;
+        }
+        let key = query.to_lowercase();
+
+        if outcome.local_results == 0 {
+            let stat = self.no_results.entry(key).or_insert(QueryStat {
+                count: 0,
+                last_seen: now,
+            });
+            stat.count = stat.count.saturating_add(1);
+            stat.last_seen = now;
+            self.prune();
+            return true;
+        }
+
+        let Some((position, result_id, title)) = outcome.launched else {
+            return false;
+        };
+        if position < DEEP_POSITION {
+            return false;
+        }
+        match self
+            .deep_launches
+            .iter_mut()
+            .find(|entry| entry.query == key && entry.result_id == result_id)
+        {
+            Some(entry) => {
+                entry.count = entry.count.saturating_add(1);
+                entry.position_total += position as u64;
+                entry.title = title.to_string();
+                entry.last_seen = now;
+            }
+            None => self.deep_launches.push(DeepLaunch {
+                query: key,
+                result_id: result_id.to_string(),
+                title: title.to_string(),
+                count: 1,
+                position_total: position as u64,
+                last_seen: now,
+            }),
+        }
+        self.prune();
+        true
+    }
+
+    /// Human-readable summary for `egg stats ranking`.
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        if self.is_empty() {
+            out.push_str("No ranking telemetry recorded yet.\n");
+            return out;
+        }
+
+        let mut no_results: Vec<(&String, &QueryStat)> = self.no_results.iter().collect();
+        no_results.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+        let _ = writeln!(out, "Queries with no results ({}):", no_results.len());
+        for (query, stat) in no_results.iter().take(REPORT_ROWS) {
+            let _ = writeln!(out, "  {:>4}x  {query}", stat.count);
+        }
+
+        let mut deep: Vec<&DeepLaunch> = self.deep_launches.iter().collect();
+        deep.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.query.cmp(&b.query)));
+        let _ = writeln!(
+            out,
+            "\nLaunches below position {DEEP_POSITION} ({}):",
+            deep.len()
+        );
+        for entry in deep.iter().take(REPORT_ROWS) {
+            let average = entry.position_total as f64 / f64::from(entry.count.max(1)) + 1.0;
+            let _ = writeln!(
+                out,
+                "  {:>4}x  {} -> {} (avg position {average:.1})",
+                entry.count, entry.query, entry.title
+            );
+        }
+        out.push_str(
+            "\nConsider adding keywords for frequent misses or switching `ranking_profile`.\n",
+        );
+        out
+    }
+
+    fn prune(&mut self) {
+        if self.no_results.len() > MAX_ENTRIES {
+            let mut by_age: Vec<(String, u64)> = self
+                .no_results
+                .iter()
+                .map(|(query, stat)| (query.clone(), stat.last_seen))
+                .collect();
+            by_age.sort_by_key(|(_, last)| *last);
+            let excess = self.no_results.len() - MAX_ENTRIES;
+            for (query, _) in by_age.into_iter().take(excess) {
+                self.no_results.remove(&query);
+            }
+        }
+        if self.deep_launches.len() > MAX_ENTRIES {
+            self.deep_launches
+                .sort_by_key(|entry| std::cmp::Reverse(entry.last_seen));
+            self.deep_launches.truncate(MAX_ENTRIES);
+        }
+    }
+}
+
+fn telemetry_path() -> Option<PathBuf> {
+    let base = dirs::data_local_dir()?;
+    Some(base.join("egg").join("data").join(TELEMETRY_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome<'a>(
+        query: &'a str,
+        local_results: usize,
+        launched: Option<(usize, &'a str, &'a str)>,
+    ) -> SessionOutcome<'a> {
+        SessionOutcome {
+            query,
+            local_results,
+            launched,
+        }
+    }
+
+    #[test]
+    fn records_only_misses_and_deep_launches() {
+        let mut telemetry = RankingTelemetry::default();
+        assert!(!telemetry.observe(&outcome("", 0, None), 1));
+        assert!(!telemetry.observe(&outcome("code", 4, Some((0, "app-code", "Code"))), 1));
+        assert!(!telemetry.observe(&outcome("code", 9, None), 1));
+        assert!(telemetry.is_empty());
+
+        assert!(telemetry.observe(&outcome("Fooapp", 0, None), 1));
+        assert!(telemetry.observe(&outcome("fooapp ", 0, None), 2));
+        assert_eq!(telemetry.no_results["fooapp"].count, 2);
+
+        let launch = Some((6, "app-term", "Windows Terminal"));
+        assert!(telemetry.observe(&outcome("term", 8, launch), 3));
+        let launch = Some((8, "app-term", "Windows Terminal"));
+        assert!(telemetry.observe(&outcome("term", 8, launch), 4));
+        assert_eq!(telemetry.deep_launches.len(), 1);
+        assert_eq!(telemetry.deep_launches[0].count, 2);
+
+        let report = telemetry.report();
+        assert!(report.contains("2x  fooapp"));
+        assert!(report.contains("term -> Windows Terminal (avg position 8.0)"));
+    }
+}
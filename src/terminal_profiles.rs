@@ -0,0 +1,258 @@
+//! Windows Terminal profiles, read from the `settings.json` of the Store, Preview
+//! and unpackaged installs. Each visible profile is indexed by name and opens
+//! Windows Terminal with it (`wt -p "Ubuntu"`); the profile's icon becomes the
+//! result's icon, and its command line and source add keywords. The file allows
+//! comments and trailing commas, which are stripped before parsing.
+
+use std::{env, fs};
+
+use serde_json::Value;
+
+use crate::{
+    bookmarks::expand_env_vars,
+    execute::quote_argument,
+    models::{AppType, ApplicationInfo},
+};
+
+/// Ids of Windows Terminal profiles, followed by the profile name.
+pub const TERMINAL_PROFILE_ID_PREFIX: &str = "wt:";
+
+/// Folders under `%LOCALAPPDATA%` holding `settings.json`, Store install first.
+const SETTINGS_DIRS: [&str; 3] = [
+    r"Packages\Microsoft.WindowsTerminal_8wekyb3d8bbwe\LocalState",
+    r"Packages\Microsoft.WindowsTerminalPreview_8wekyb3d8bbwe\LocalState",
+    r"Microsoft\Windows Terminal",
+];
+
+/// Profiles of every Windows Terminal install, once per name.
+pub fn enumerate_terminal_profiles() -> Vec<ApplicationInfo> {
+    let Some(local_dir) = dirs::data_local_dir() else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<ApplicationInfo> = Vec::new();
+    for dir in SETTINGS_DIRS {
+        let Ok(content) = fs::read_to_string(local_dir.join(dir).join("settings.json")) else {
+            continue;
+        };
+        for profile in terminal_profiles(&content) {
+            if !profiles.iter().any(|known| known.id == profile.id) {
+                profiles.push(profile);
+            }
+        }
+    }
+    profiles
+}
+
+pub fn is_terminal_profile(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(TERMINAL_PROFILE_ID_PREFIX)
+}
+
+/// The visible profiles of a `settings.json`. `profiles` is either the list
+/// itself or an object with `defaults` and `list`.
+fn terminal_profiles(content: &str) -> Vec<ApplicationInfo> {
+    let Ok(settings) = serde_json::from_str::<Value>(&strip_comments(content)) else {
+        return Vec::new();
+    };
+    let profiles = &settings["profiles"];
+    profiles
+        .as_array()
+        .or_else(|| profiles["list"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(profile_entry)
+        .collect()
+}
+
+fn profile_entry(profile: &Value) -> Option<ApplicationInfo> {
+    if profile["hidden"].as_bool() == Some(true) {
+        return None;
+    }
+    let name = profile["name"].as_str()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let expand = |value: &str| expand_env_vars(value.trim(), |var| env::var(var).ok());
+    let commandline = profile["commandline"]
+        .as_str()
+        .map(str::trim)
+        .filter(|commandline| !commandline.is_empty());
+    let program = commandline.map(|commandline| expand(program_of(commandline)));
+    // `Windows.Terminal.Wsl` and the like, for profiles Windows Terminal generates.
+    let source = profile["source"].as_str();
+
+    let mut keywords = vec!["terminal".to_string()];
+    if let Some(program) = &program {
+        let file_name = program.rsplit(['\\', '/']).next().unwrap_or(program);
+        let stem = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem);
+        keywords.push(stem.to_lowercase());
+    }
+    keywords.extend(
+        source
+            .and_then(|source| source.rsplit('.').next())
+            .map(str::to_lowercase),
+    );
+
+    // Icons can also be `ms-appx:///` package resources or an emoji, which have
+    // no file to read; the program's own icon stands in for those.
+    let icon = profile["icon"]
+        .as_str()
+        .map(expand)
+        .filter(|icon| is_file_path(icon))
+        .or_else(|| {
+            program
+                .as_deref()
+                .filter(|program| is_file_path(program))
+                .map(|program| format!("{program},0"))
+        });
+
+    Some(ApplicationInfo {
+        id: format!("{TERMINAL_PROFILE_ID_PREFIX}{}", name.to_lowercase()),
+        name: name.to_string(),
+        path: "wt.exe".to_string(),
+        source_path: None,
+        app_type: AppType::Win32,
+        description: Some(format!(
+            "Windows Terminal profile · {}",
+            commandline.or(source).unwrap_or(name)
+        )),
+        keywords,
+        pinyin_index: None,
+        working_directory: None,
+        // `;` separates Windows Terminal commands, so one in a name is escaped.
+        arguments: Some(format!("-p {}", quote_argument(&name.replace(';', "\\;")))),
+        icon,
+        folded: None,
+    })
+}
+
+/// The program a command line starts, without its quotes.
+fn program_of(commandline: &str) -> &str {
+    match commandline.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().unwrap_or(rest),
+        None => commandline.split_whitespace().next().unwrap_or(commandline),
+    }
+}
+
+fn is_file_path(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    (bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+        || value.starts_with(r"\\")
+}
+
+/// `text` as strict JSON: `//` and `/* */` comments and commas before a closing
+/// bracket are dropped, except inside strings.
+fn strip_comments(text: &str) -> String {
+    let mut json = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(ch) = chars.next() {
+        if in_string {
+            json.push(ch);
+            match ch {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            '"' => {
+                in_string = true;
+                json.push(ch);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '}' | ']' => {
+                let content = json.trim_end().len();
+                if json[..content].ends_with(',') {
+                    json.truncate(content - 1);
+                }
+                json.push(ch);
+            }
+            _ => json.push(ch),
+        }
+    }
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_json_profiles_become_launchable_results() {
+        let settings = r#"// This file was initially generated by Windows Terminal
+{
+    "$schema": "https://aka.ms/terminal-profiles-schema",
+    /* The profile opened by default. */
+    "defaultProfile": "{61c54bbd-c2c6-5271-96e7-009a87ff44bf}",
+    "profiles": {
+        "defaults": {},
+        "list": [
+            {
+                "name": "Windows PowerShell",
+                "commandline": "%SystemRoot%\\System32\\WindowsPowerShell\\v1.0\\powershell.exe",
+                "hidden": false,
+            },
+            {
+                "name": "Ubuntu; dev",
+                "source": "Windows.Terminal.Wsl",
+                "icon": "ms-appx:///ProfileIcons/{9acb9455-ca41-5af7-950f-6bca1bc9722f}.png"
+            },
+            {
+                "name": "Git Bash",
+                "commandline": "\"C:\\Program Files\\Git\\bin\\bash.exe\" -i -l",
+                "icon": "C:\\Program Files\\Git\\mingw64\\share\\git\\git-for-windows.ico",
+            },
+            { "name": "Azure Cloud Shell", "source": "Windows.Terminal.Azure", "hidden": true },
+        ],
+    },
+}"#;
+        let profiles = terminal_profiles(settings);
+        let names: Vec<&str> = profiles.iter().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, vec!["Windows PowerShell", "Ubuntu; dev", "Git Bash"]);
+
+        let powershell = &profiles[0];
+        assert_eq!(powershell.id, "wt:windows powershell");
+        assert_eq!(powershell.path, "wt.exe");
+        assert!(is_terminal_profile(powershell));
+        assert_eq!(powershell.keywords, vec!["terminal", "powershell"]);
+
+        let ubuntu = &profiles[1];
+        assert_eq!(
+            ubuntu.arguments,
+            Some(format!("-p {}", quote_argument(r"Ubuntu\; dev")))
+        );
+        assert_eq!(ubuntu.keywords, vec!["terminal", "wsl"]);
+        assert_eq!(ubuntu.icon, None);
+
+        let git_bash = &profiles[2];
+        assert_eq!(
+            git_bash.icon.as_deref(),
+            Some(r"C:\Program Files\Git\mingw64\share\git\git-for-windows.ico")
+        );
+        assert_eq!(git_bash.keywords, vec!["terminal", "bash"]);
+        assert_eq!(
+            git_bash.description.as_deref(),
+            Some(r#"Windows Terminal profile · "C:\Program Files\Git\bin\bash.exe" -i -l"#)
+        );
+
+        assert_eq!(
+            strip_comments(r#"{"url": "https://x//y", "a": [1, /* two */ 2,],}"#),
+            r#"{"url": "https://x//y", "a": [1,  2]}"#
+        );
+    }
+}
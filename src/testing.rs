@@ -0,0 +1,216 @@
+//! Test-only fixtures and a headless TUI driver.
+//!
+//! The driver feeds key events through the same `handle_key_event` /
+//! `refresh_results` path the real terminal loop uses and renders frames into a
+//! ratatui [`TestBackend`], so tests can assert on both state and screen output.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, buffer::Buffer, text::Span, Terminal};
+
+use crate::{
+    bookmarks::BookmarkEntry,
+    models::{AppType, ApplicationInfo, SearchResult},
+    state::{AppState, PendingAction, RecentEntry},
+    tui::{handle_key_event, refresh_results, render_ui, TuiState},
+};
+
+/// An app as the indexer leaves it, case-folded for searching.
+pub(crate) fn app(id: &str, name: &str, path: &str) -> ApplicationInfo {
+    let mut app = ApplicationInfo {
+        id: id.to_string(),
+        name: name.to_string(),
+        path: path.to_string(),
+        source_path: None,
+        app_type: AppType::Win32,
+        description: None,
+        keywords: vec![name.to_string()],
+        pinyin_index: None,
+        working_directory: None,
+        arguments: None,
+        icon: None,
+        folded: None,
+    };
+    app.fold_case();
+    app
+}
+
+pub(crate) fn bookmark(id: &str, title: &str, url: &str, folder: Option<&str>) -> BookmarkEntry {
+    let mut bookmark = BookmarkEntry {
+        id: id.to_string(),
+        title: title.to_string(),
+        url: url.to_string(),
+        folder_path: folder.map(str::to_string),
+        keywords: vec![title.to_string(), url.to_string()],
+        pinyin_index: None,
+        folded: None,
+    };
+    bookmark.fold_case();
+    bookmark
+}
+
+/// A small, stable catalog covering Win32, UWP and pinyin-indexed entries.
+pub(crate) fn fixture_apps() -> Vec<ApplicationInfo> {
+    let mut wechat = app(
+        "shell:wechat",
+        "微信",
+        r"C:\Program Files\Tencent\WeChat\WeChat.exe",
+    );
+    wechat.pinyin_index = Some("weixin|wx".to_string());
+
+    let mut calculator = app(
+        "shell:calculator",
+        "Calculator",
+        r"shell:AppsFolder\Microsoft.WindowsCalculator_8wekyb3d8bbwe!App",
+    );
+    calculator.app_type = AppType::Uwp;
+
+    vec![
+        app(
+            "shell:chrome",
+            "Google Chrome",
+            r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+        ),
+        app(
+            "shell:code",
+            "Visual Studio Code",
+            r"C:\Users\egg\AppData\Local\Programs\Microsoft VS Code\Code.exe",
+        ),
+        app("shell:notepad", "Notepad", r"C:\Windows\notepad.exe"),
+        app(
+            "shell:notion",
+            "Notion",
+            r"C:\Users\egg\AppData\Local\Programs\Notion\Notion.exe",
+        ),
+        wechat,
+        calculator,
+    ]
+}
+
+pub(crate) fn fixture_bookmarks() -> Vec<BookmarkEntry> {
+    vec![
+        bookmark(
+            "Chrome 默认:rust",
+            "Rust Documentation",
+            "https://doc.rust-lang.org/",
+            Some("Chrome 默认 / 书签栏 / Dev"),
+        ),
+        bookmark(
+            "Chrome 默认:github",
+            "GitHub",
+            "https://github.com/",
+            Some("Chrome 默认 / 书签栏"),
+        ),
+        bookmark(
+            "Edge 默认:notes",
+            "Meeting Notes",
+            "https://notes.example.com/meetings",
+            None,
+        ),
+    ]
+}
+
+/// Builds an [`AppState`] populated with the fixture catalog.
+pub(crate) fn fixture_state() -> AppState {
+    let state = AppState::new();
+    *state.app_index.lock().unwrap() = fixture_apps().into();
+    *state.bookmark_index.lock().unwrap() = fixture_bookmarks().into();
+    state
+}
+
+pub(crate) fn push_recent(state: &AppState, result: SearchResult, action: PendingAction) {
+    state
+        .recent_actions
+        .lock()
+        .unwrap()
+        .insert(RecentEntry { result, action });
+}
+
+/// Drives the TUI without a real terminal.
+pub(crate) struct TuiDriver {
+    pub(crate) app_state: AppState,
+    pub(crate) ui_state: TuiState,
+    terminal: Terminal<TestBackend>,
+}
+
+impl TuiDriver {
+    pub(crate) fn new(app_state: AppState) -> Self {
+        Self::with_size(app_state, 80, 24)
+    }
+
+    pub(crate) fn with_size(app_state: AppState, width: u16, height: u16) -> Self {
+        let terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let mut ui_state = TuiState::new();
+        refresh_results(&mut ui_state, &app_state);
+        Self {
+            app_state,
+            ui_state,
+            terminal,
+        }
+    }
+
+    pub(crate) fn press(&mut self, code: KeyCode) {
+        self.press_with(code, KeyModifiers::NONE);
+    }
+
+    pub(crate) fn press_with(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        handle_key_event(
+            KeyEvent::new(code, modifiers),
+            &mut self.ui_state,
+            &self.app_state,
+        );
+    }
+
+    pub(crate) fn type_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            self.press(KeyCode::Char(ch));
+        }
+    }
+
+    pub(crate) fn result_titles(&self) -> Vec<String> {
+        self.ui_state
+            .results
+            .iter()
+            .map(|result| result.title.clone())
+            .collect()
+    }
+
+    pub(crate) fn selected_title(&self) -> Option<String> {
+        let index = self.ui_state.list_state.selected()?;
+        self.ui_state
+            .results
+            .get(index)
+            .map(|result| result.title.clone())
+    }
+
+    /// Renders one frame and returns the screen as one string per row.
+    pub(crate) fn render(&mut self) -> Vec<String> {
+        let app_state = &self.app_state;
+        let ui_state = &mut self.ui_state;
+        self.terminal
+            .draw(|frame| render_ui(frame, ui_state, app_state))
+            .unwrap();
+        buffer_lines(self.terminal.backend().buffer())
+    }
+
+    pub(crate) fn screen_contains(&mut self, needle: &str) -> bool {
+        self.render().iter().any(|line| line.contains(needle))
+    }
+}
+
+/// Screen rows as text. The cells covered by a wide character are skipped, so
+/// Chinese text reads as it was written.
+fn buffer_lines(buffer: &Buffer) -> Vec<String> {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let mut line = String::new();
+            let mut x = area.left();
+            while x < area.right() {
+                let symbol = buffer.get(x, y).symbol();
+                line.push_str(symbol);
+                x += Span::raw(symbol).width().max(1) as u16;
+            }
+            line.trim_end().to_string()
+        })
+        .collect()
+}
@@ -59,3 +59,71 @@ fn build_single_index(source: &str) -> Option<String> {
         Some(joined)
     }
 }
+
+/// Shortens a path or URL for display: `name · parent folder` for paths, and the
+/// address without its scheme for URLs.
+pub fn compact_path(path: &str) -> String {
+    if let Some((_, rest)) = path.split_once("://") {
+        return rest.trim_end_matches('/').to_string();
+    }
+    let trimmed = path.trim_end_matches(['\\', '/']);
+    let mut parts = trimmed.rsplit(['\\', '/']);
+    let name = parts.next().unwrap_or_default();
+    match parts.next().filter(|folder| !folder.is_empty()) {
+        Some(folder) if !name.is_empty() => format!("{name} · {folder}"),
+        _ => path.to_string(),
+    }
+}
+
+/// Cuts `text` to at most `max_chars` characters by replacing its middle with `…`,
+/// keeping the last path segment (usually the file name) whenever it fits.
+pub fn truncate_middle(text: &str, max_chars: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= max_chars {
+        return text.to_string();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+    let budget = max_chars - 1;
+    let name_len = chars
+        .iter()
+        .rev()
+        .position(|ch| matches!(ch, '\\' | '/'))
+        .map_or(chars.len(), |offset| offset + 1);
+    let tail_len = name_len.min(budget);
+    let head_len = budget - tail_len;
+    let mut truncated: String = chars[..head_len].iter().collect();
+    truncated.push('…');
+    truncated.extend(&chars[chars.len() - tail_len..]);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_path_keeps_name_and_folder() {
+        assert_eq!(
+            compact_path(r"C:\Program Files\Microsoft VS Code\Code.exe"),
+            "Code.exe · Microsoft VS Code"
+        );
+        assert_eq!(compact_path(r"D:\Projects\"), "Projects · D:");
+        assert_eq!(
+            compact_path("https://example.com/docs/"),
+            "example.com/docs"
+        );
+        assert_eq!(compact_path("notes.txt"), "notes.txt");
+    }
+
+    #[test]
+    fn truncate_middle_keeps_the_file_name() {
+        let path = r"C:\Users\egg\Documents\Reports\2024\report.docx";
+        assert_eq!(truncate_middle(path, 100), path);
+        assert_eq!(truncate_middle(path, 20), r"C:\User…\report.docx");
+        assert_eq!(truncate_middle(path, 20).chars().count(), 20);
+        assert_eq!(truncate_middle(path, 8), "…rt.docx");
+        assert_eq!(truncate_middle(path, 0), "");
+    }
+}
@@ -0,0 +1,462 @@
+use std::{env, str::FromStr, sync::OnceLock};
+
+use ratatui::{buffer::Buffer, style::Color};
+
+use crate::config::{ColorSupport, ThemeConfig, ThemePreset};
+
+/// Editable color slots, in the order the theme editor lists them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ThemeSlot {
+    Background,
+    Surface,
+    Border,
+    Accent,
+    Text,
+    Dim,
+    HighlightBg,
+    HighlightFg,
+}
+
+impl ThemeSlot {
+    pub(crate) const ALL: [ThemeSlot; 8] = [
+        ThemeSlot::Background,
+        ThemeSlot::Surface,
+        ThemeSlot::Border,
+        ThemeSlot::Accent,
+        ThemeSlot::Text,
+        ThemeSlot::Dim,
+        ThemeSlot::HighlightBg,
+        ThemeSlot::HighlightFg,
+    ];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Background => "background",
+            Self::Surface => "surface",
+            Self::Border => "border",
+            Self::Accent => "accent",
+            Self::Text => "text",
+            Self::Dim => "dim",
+            Self::HighlightBg => "highlight_bg",
+            Self::HighlightFg => "highlight_fg",
+        }
+    }
+
+    pub(crate) fn value(self, config: &ThemeConfig) -> &str {
+        match self {
+            Self::Background => &config.background,
+            Self::Surface => &config.surface,
+            Self::Border => &config.border,
+            Self::Accent => &config.accent,
+            Self::Text => &config.text,
+            Self::Dim => &config.dim,
+            Self::HighlightBg => &config.highlight_bg,
+            Self::HighlightFg => &config.highlight_fg,
+        }
+    }
+
+    pub(crate) fn value_mut(self, config: &mut ThemeConfig) -> &mut String {
+        match self {
+            Self::Background => &mut config.background,
+            Self::Surface => &mut config.surface,
+            Self::Border => &mut config.border,
+            Self::Accent => &mut config.accent,
+            Self::Text => &mut config.text,
+            Self::Dim => &mut config.dim,
+            Self::HighlightBg => &mut config.highlight_bg,
+            Self::HighlightFg => &mut config.highlight_fg,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Theme {
+    pub(crate) background: Color,
+    pub(crate) surface: Color,
+    pub(crate) border: Color,
+    pub(crate) accent: Color,
+    pub(crate) text: Color,
+    pub(crate) dim: Color,
+    pub(crate) highlight_bg: Color,
+    pub(crate) highlight_fg: Color,
+}
+
+impl Theme {
+    /// The preset's colors, as designed for 24-bit terminals.
+    pub(crate) fn preset(preset: ThemePreset) -> Self {
+        let rgb = |[r, g, b]: [u8; 3]| Color::Rgb(r, g, b);
+        let [background, surface, border, accent, text, dim, highlight_bg, highlight_fg] =
+            match preset {
+                ThemePreset::Dark => [
+                    [18, 20, 23],
+                    [28, 31, 36],
+                    [58, 62, 70],
+                    [242, 193, 78],
+                    [232, 230, 227],
+                    [148, 153, 160],
+                    [45, 93, 124],
+                    [250, 250, 250],
+                ],
+                ThemePreset::Light => [
+                    [246, 246, 244],
+                    [255, 255, 255],
+                    [208, 212, 218],
+                    [183, 121, 31],
+                    [31, 35, 40],
+                    [106, 115, 125],
+                    [207, 227, 245],
+                    [11, 31, 51],
+                ],
+                ThemePreset::Solarized => [
+                    [0, 43, 54],
+                    [7, 54, 66],
+                    [88, 110, 117],
+                    [181, 137, 0],
+                    [147, 161, 161],
+                    [101, 123, 131],
+                    [38, 139, 210],
+                    [253, 246, 227],
+                ],
+            }
+            .map(rgb);
+        Self {
+            background,
+            surface,
+            border,
+            accent,
+            text,
+            dim,
+            highlight_bg,
+            highlight_fg,
+        }
+    }
+
+    /// The preset for 16-color terminals. Nearest colors would turn the dark
+    /// backgrounds and borders all black, so each preset picks its own, leaving
+    /// the background to the terminal.
+    fn ansi_preset(preset: ThemePreset) -> Self {
+        let (border, accent, highlight_bg, highlight_fg) = match preset {
+            ThemePreset::Dark => (Color::DarkGray, Color::Yellow, Color::Blue, Color::White),
+            ThemePreset::Light => (Color::Gray, Color::Blue, Color::Cyan, Color::Black),
+            ThemePreset::Solarized => (Color::DarkGray, Color::Yellow, Color::Blue, Color::White),
+        };
+        Self {
+            background: Color::Reset,
+            surface: Color::Reset,
+            border,
+            accent,
+            text: Color::Reset,
+            dim: Color::DarkGray,
+            highlight_bg,
+            highlight_fg,
+        }
+    }
+
+    /// Builds a theme from the settings: the preset for the terminal's colors,
+    /// with every slot that parses as a color replacing the preset's.
+    pub(crate) fn from_config(config: &ThemeConfig) -> Self {
+        let support = config.colors.resolve();
+        let mut theme = match support {
+            ColorSupport::Ansi => Self::ansi_preset(config.preset),
+            _ => Self::preset(config.preset),
+        };
+        for slot in ThemeSlot::ALL {
+            if let Some(color) = parse_color(slot.value(config)) {
+                *theme.slot_mut(slot) = downgrade(color, support);
+            }
+        }
+        theme
+    }
+
+    pub(crate) fn color(&self, slot: ThemeSlot) -> Color {
+        match slot {
+            ThemeSlot::Background => self.background,
+            ThemeSlot::Surface => self.surface,
+            ThemeSlot::Border => self.border,
+            ThemeSlot::Accent => self.accent,
+            ThemeSlot::Text => self.text,
+            ThemeSlot::Dim => self.dim,
+            ThemeSlot::HighlightBg => self.highlight_bg,
+            ThemeSlot::HighlightFg => self.highlight_fg,
+        }
+    }
+
+    fn slot_mut(&mut self, slot: ThemeSlot) -> &mut Color {
+        match slot {
+            ThemeSlot::Background => &mut self.background,
+            ThemeSlot::Surface => &mut self.surface,
+            ThemeSlot::Border => &mut self.border,
+            ThemeSlot::Accent => &mut self.accent,
+            ThemeSlot::Text => &mut self.text,
+            ThemeSlot::Dim => &mut self.dim,
+            ThemeSlot::HighlightBg => &mut self.highlight_bg,
+            ThemeSlot::HighlightFg => &mut self.highlight_fg,
+        }
+    }
+}
+
+/// Parses `#rrggbb`, `rrggbb`, `#rgb`, `r,g,b`, `rgb(r, g, b)` or the name of
+/// one of the 16 ANSI colors, e.g. `darkgray`.
+pub(crate) fn parse_color(input: &str) -> Option<Color> {
+    let value = input.trim();
+    if value.chars().all(|ch| ch.is_ascii_alphabetic()) && !value.is_empty() {
+        return Color::from_str(value)
+            .ok()
+            .filter(|color| !matches!(color, Color::Reset | Color::Rgb(..)));
+    }
+    if let Some(inner) = value
+        .strip_prefix("rgb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return parse_rgb_triplet(inner);
+    }
+    if value.contains(',') {
+        return parse_rgb_triplet(value);
+    }
+
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        6 => {
+            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+            Some(Color::Rgb(r, g, b))
+        }
+        3 => {
+            let mut channels = hex
+                .chars()
+                .map(|ch| ch.to_digit(16).map(|digit| digit as u8 * 17));
+            let r = channels.next()??;
+            let g = channels.next()??;
+            let b = channels.next()??;
+            Some(Color::Rgb(r, g, b))
+        }
+        _ => None,
+    }
+}
+
+fn parse_rgb_triplet(value: &str) -> Option<Color> {
+    let channels: Vec<u8> = value
+        .split(',')
+        .map(|part| part.trim().parse::<u8>().ok())
+        .collect::<Option<_>>()?;
+    match channels.as_slice() {
+        [r, g, b] => Some(Color::Rgb(*r, *g, *b)),
+        _ => None,
+    }
+}
+
+pub(crate) fn format_color(color: Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("#{r:02x}{g:02x}{b:02x}"),
+        other => other.to_string(),
+    }
+}
+
+impl ColorSupport {
+    /// `Auto` settled from the environment, read once per run.
+    pub(crate) fn resolve(self) -> Self {
+        static DETECTED: OnceLock<ColorSupport> = OnceLock::new();
+        match self {
+            Self::Auto => *DETECTED.get_or_init(|| {
+                detect_color_support(
+                    env::var("COLORTERM").ok().as_deref(),
+                    env::var("TERM").ok().as_deref(),
+                    env::var_os("WT_SESSION").is_some(),
+                )
+            }),
+            support => support,
+        }
+    }
+}
+
+/// Windows Terminal and the Windows 10 console both take 24-bit colors; other
+/// terminals advertise it in `COLORTERM`, or 256 colors in `TERM`.
+fn detect_color_support(
+    colorterm: Option<&str>,
+    term: Option<&str>,
+    windows_terminal: bool,
+) -> ColorSupport {
+    if cfg!(windows) || windows_terminal || matches!(colorterm, Some("truecolor" | "24bit")) {
+        ColorSupport::Truecolor
+    } else if term.is_some_and(|term| term.contains("256color")) {
+        ColorSupport::Ansi256
+    } else {
+        ColorSupport::Ansi
+    }
+}
+
+/// `color` as the terminal can show it: RGB colors become the nearest palette
+/// entry on terminals without 24-bit color.
+pub(crate) fn downgrade(color: Color, support: ColorSupport) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    match support {
+        ColorSupport::Auto | ColorSupport::Truecolor => color,
+        ColorSupport::Ansi256 => Color::Indexed(nearest_256(r, g, b)),
+        ColorSupport::Ansi => nearest_ansi(r, g, b),
+    }
+}
+
+/// Downgrades every cell of a drawn frame, so that colors picked outside the
+/// theme, such as result badges, still show.
+pub(crate) fn downgrade_buffer(buffer: &mut Buffer, support: ColorSupport) {
+    if matches!(support, ColorSupport::Auto | ColorSupport::Truecolor) {
+        return;
+    }
+    for cell in &mut buffer.content {
+        cell.fg = downgrade(cell.fg, support);
+        cell.bg = downgrade(cell.bg, support);
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+    channel(r1, r2) + channel(g1, g2) + channel(b1, b2)
+}
+
+/// The closer of the xterm color cube entry and gray ramp entry.
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |value: u8| match value {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (value - 35) / 40,
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (
+        LEVELS[ri as usize],
+        LEVELS[gi as usize],
+        LEVELS[bi as usize],
+    );
+    let average = ((u16::from(r) + u16::from(g) + u16::from(b)) / 3) as u8;
+    let gray_index = (average.saturating_sub(3) / 10).min(23);
+    let gray = 8 + gray_index * 10;
+    if distance((r, g, b), (gray, gray, gray)) < distance((r, g, b), cube) {
+        232 + gray_index
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+/// The nearest of the 16 ANSI colors, as xterm draws them.
+fn nearest_ansi(r: u8, g: u8, b: u8) -> Color {
+    const PALETTE: [(Color, (u8, u8, u8)); 16] = [
+        (Color::Black, (0, 0, 0)),
+        (Color::Red, (205, 0, 0)),
+        (Color::Green, (0, 205, 0)),
+        (Color::Yellow, (205, 205, 0)),
+        (Color::Blue, (0, 0, 238)),
+        (Color::Magenta, (205, 0, 205)),
+        (Color::Cyan, (0, 205, 205)),
+        (Color::Gray, (229, 229, 229)),
+        (Color::DarkGray, (127, 127, 127)),
+        (Color::LightRed, (255, 0, 0)),
+        (Color::LightGreen, (0, 255, 0)),
+        (Color::LightYellow, (255, 255, 0)),
+        (Color::LightBlue, (92, 92, 255)),
+        (Color::LightMagenta, (255, 0, 255)),
+        (Color::LightCyan, (0, 255, 255)),
+        (Color::White, (255, 255, 255)),
+    ];
+    PALETTE
+        .into_iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map_or(Color::Reset, |(color, _)| color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_supported_color_notations() {
+        assert_eq!(parse_color("#f2c14e"), Some(Color::Rgb(242, 193, 78)));
+        assert_eq!(parse_color("F2C14E"), Some(Color::Rgb(242, 193, 78)));
+        assert_eq!(parse_color("#fff"), Some(Color::Rgb(255, 255, 255)));
+        assert_eq!(parse_color("12, 34, 56"), Some(Color::Rgb(12, 34, 56)));
+        assert_eq!(parse_color("rgb(1,2,3)"), Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("256,0,0"), None);
+        assert_eq!(parse_color("teal"), None);
+    }
+
+    #[test]
+    fn slots_override_the_preset() {
+        let config = ThemeConfig {
+            preset: ThemePreset::Light,
+            colors: ColorSupport::Truecolor,
+            accent: "#ff0000".to_string(),
+            border: "not a color".to_string(),
+            ..ThemeConfig::default()
+        };
+        let light = Theme::preset(ThemePreset::Light);
+        let theme = Theme::from_config(&config);
+        assert_eq!(theme.accent, Color::Rgb(255, 0, 0));
+        assert_eq!(theme.border, light.border);
+        assert_eq!(theme.text, light.text);
+        assert_eq!(
+            Theme::from_config(&ThemeConfig {
+                colors: ColorSupport::Truecolor,
+                ..ThemeConfig::default()
+            })
+            .background,
+            Color::Rgb(18, 20, 23)
+        );
+        for preset in [
+            ThemePreset::Dark,
+            ThemePreset::Light,
+            ThemePreset::Solarized,
+        ] {
+            let theme = Theme::preset(preset);
+            for slot in ThemeSlot::ALL {
+                let color = theme.color(slot);
+                assert_eq!(parse_color(&format_color(color)), Some(color), "{slot:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn terminals_without_truecolor_get_palette_colors() {
+        assert_eq!(
+            detect_color_support(Some("truecolor"), Some("xterm"), false),
+            ColorSupport::Truecolor
+        );
+        if !cfg!(windows) {
+            assert_eq!(
+                detect_color_support(None, Some("xterm-256color"), false),
+                ColorSupport::Ansi256
+            );
+            assert_eq!(
+                detect_color_support(None, Some("linux"), false),
+                ColorSupport::Ansi
+            );
+        }
+
+        let accent = Color::Rgb(242, 193, 78);
+        assert_eq!(downgrade(accent, ColorSupport::Truecolor), accent);
+        assert_eq!(
+            downgrade(accent, ColorSupport::Ansi256),
+            Color::Indexed(215)
+        );
+        assert_eq!(downgrade(accent, ColorSupport::Ansi), Color::Yellow);
+        assert_eq!(
+            downgrade(Color::Rgb(18, 20, 23), ColorSupport::Ansi256),
+            Color::Indexed(233)
+        );
+        assert_eq!(downgrade(Color::Blue, ColorSupport::Ansi), Color::Blue);
+
+        let config = ThemeConfig {
+            colors: ColorSupport::Ansi,
+            ..ThemeConfig::default()
+        };
+        let theme = Theme::from_config(&config);
+        assert_eq!(theme.background, Color::Reset);
+        assert_eq!(theme.border, Color::DarkGray);
+        assert_eq!(parse_color("DarkGray"), Some(Color::DarkGray));
+    }
+}
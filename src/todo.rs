@@ -0,0 +1,175 @@
+//! Tasks in a todo.txt file, `todo_file` in `settings.json` or `todo.txt` in the
+//! Documents folder. `todo` lists the open tasks, `todo <text>` searches them and
+//! offers to add the text as a task, and the actions menu marks a task done.
+//! Lines follow the todo.txt format: an optional `(A)` priority and creation
+//! date before the text, and `x` with the completion date in front once done.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+
+use crate::{
+    cache,
+    config::{documents_file, AppConfig},
+};
+
+const TODO_KEYWORD: &str = "todo";
+const DEFAULT_TODO_FILE: &str = "todo.txt";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Returns the task search when `query` is `todo` or starts with `todo `.
+pub fn todo_query(query: &str) -> Option<&str> {
+    let query = query.trim();
+    if query.eq_ignore_ascii_case(TODO_KEYWORD) {
+        return Some("");
+    }
+    let (head, rest) = query.split_once(char::is_whitespace)?;
+    head.eq_ignore_ascii_case(TODO_KEYWORD)
+        .then_some(rest.trim())
+}
+
+/// The todo.txt file tasks are read from and written to.
+pub fn todo_path(config: &AppConfig) -> Option<PathBuf> {
+    documents_file(config.todo_file.as_deref(), DEFAULT_TODO_FILE)
+}
+
+/// An open task.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Task<'a> {
+    /// The whole line, which finds the task again when it is marked done.
+    pub line: &'a str,
+    pub priority: Option<char>,
+    pub created: Option<&'a str>,
+    /// The text after the priority and date, with its `+project` and `@context` tags.
+    pub description: &'a str,
+}
+
+/// The open tasks of a todo.txt file, in file order.
+pub fn open_tasks(contents: &str) -> Vec<Task<'_>> {
+    contents.lines().filter_map(parse_task).collect()
+}
+
+fn parse_task(line: &str) -> Option<Task<'_>> {
+    let line = line.trim_end();
+    if line.trim_start().is_empty() || line.starts_with("x ") {
+        return None;
+    }
+    let priority = priority(line);
+    let mut rest = if priority.is_some() {
+        line[4..].trim_start()
+    } else {
+        line
+    };
+    let created = rest
+        .split_whitespace()
+        .next()
+        .filter(|word| NaiveDate::parse_from_str(word, DATE_FORMAT).is_ok());
+    if let Some(date) = created {
+        rest = rest[date.len()..].trim_start();
+    }
+    Some(Task {
+        line,
+        priority,
+        created,
+        description: rest,
+    })
+}
+
+/// The priority of a line starting with `(A) `.
+fn priority(line: &str) -> Option<char> {
+    match line.as_bytes() {
+        [b'(', letter, b')', b' ', ..] if letter.is_ascii_uppercase() => Some(*letter as char),
+        _ => None,
+    }
+}
+
+/// The line a new task is saved as, dated `today`. A priority typed first stays
+/// in front of the date.
+pub fn new_task_line(text: &str, today: NaiveDate) -> String {
+    let text = text.trim();
+    let date = today.format(DATE_FORMAT);
+    match priority(text) {
+        Some(_) => format!("{} {date} {}\n", &text[..3], text[4..].trim_start()),
+        None => format!("{date} {text}\n"),
+    }
+}
+
+/// Marks the open task on `line` done. Its priority is dropped, as todo.txt keeps
+/// priorities on open tasks only.
+pub fn complete_task(path: &Path, line: &str, today: NaiveDate) -> Result<(), String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("无法读取任务文件: {err}"))?;
+    let mut found = false;
+    let lines: Vec<String> = contents
+        .lines()
+        .map(|current| match parse_task(current) {
+            Some(task) if !found && task.line == line => {
+                found = true;
+                let undone = if task.priority.is_some() {
+                    task.line[4..].trim_start()
+                } else {
+                    task.line
+                };
+                format!("x {} {undone}", today.format(DATE_FORMAT))
+            }
+            _ => current.to_string(),
+        })
+        .collect();
+    if !found {
+        return Err("任务已不在任务文件中".into());
+    }
+    let newline = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut updated = lines.join(newline);
+    if contents.ends_with('\n') {
+        updated.push_str(newline);
+    }
+    cache::write_atomically(path, updated).map_err(|err| format!("无法写入任务文件: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tasks_are_parsed_added_and_completed_in_todo_txt_format() {
+        assert_eq!(todo_query("TODO"), Some(""));
+        assert_eq!(todo_query("todo  buy milk"), Some("buy milk"));
+        assert_eq!(todo_query("todoist"), None);
+
+        let contents =
+            "(A) 2024-03-01 Call Mom +family\n\nx 2024-03-02 Pay rent\n(b) water plants\n";
+        let tasks = open_tasks(contents);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].priority, Some('A'));
+        assert_eq!(tasks[0].created, Some("2024-03-01"));
+        assert_eq!(tasks[0].description, "Call Mom +family");
+        assert_eq!(tasks[1].priority, None);
+        assert_eq!(tasks[1].description, "(b) water plants");
+
+        let today = NaiveDate::from_ymd_opt(2024, 3, 8).unwrap();
+        assert_eq!(
+            new_task_line("buy milk @store", today),
+            "2024-03-08 buy milk @store\n"
+        );
+        assert_eq!(
+            new_task_line("(B) file taxes", today),
+            "(B) 2024-03-08 file taxes\n"
+        );
+
+        let path = std::env::temp_dir().join(format!("egg-todo-test-{}.txt", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        complete_task(&path, "(A) 2024-03-01 Call Mom +family", today).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "x 2024-03-08 2024-03-01 Call Mom +family\n\nx 2024-03-02 Pay rent\n(b) water plants\n"
+        );
+        assert!(complete_task(&path, "(A) 2024-03-01 Call Mom +family", today).is_err());
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,154 @@
+//! Trigram index over the app and bookmark indexes. In large catalogs a search
+//! only scores the entries sharing a trigram with each query token, instead of
+//! running the fuzzy matcher over every entry on every keystroke.
+//!
+//! Trigrams come from the lowercase name, keywords, pinyin and (for bookmarks)
+//! folder and URL, plus the initials of the name's words, so `vsc` still finds
+//! Visual Studio Code. Tokens shorter than three characters leave the entries
+//! unfiltered, and so does a query finding fewer entries than fill the result
+//! list, since fuzzy subsequences share no trigram with what they match.
+
+use std::collections::HashMap;
+
+use crate::{bookmarks::BookmarkEntry, models::ApplicationInfo};
+
+/// Catalogs smaller than this are scanned whole; the index would not pay off.
+pub const TRIGRAM_MIN_ENTRIES: usize = 2_000;
+
+#[derive(Debug, Default)]
+pub struct TrigramIndex {
+    /// Positions in the app index having each trigram, ascending.
+    apps: HashMap<u64, Vec<usize>>,
+    bookmarks: HashMap<u64, Vec<usize>>,
+}
+
+impl TrigramIndex {
+    /// The index of a catalog big enough to need one.
+    pub fn for_catalog(apps: &[ApplicationInfo], bookmarks: &[BookmarkEntry]) -> Option<Self> {
+        (apps.len() + bookmarks.len() >= TRIGRAM_MIN_ENTRIES).then(|| Self::build(apps, bookmarks))
+    }
+
+    pub fn build(apps: &[ApplicationInfo], bookmarks: &[BookmarkEntry]) -> Self {
+        let mut index = Self::default();
+        for (position, app) in apps.iter().enumerate() {
+            let mut texts = vec![app.name.as_str()];
+            texts.extend(app.keywords.iter().map(String::as_str));
+            texts.extend(app.pinyin_index.as_deref());
+            add_entry(&mut index.apps, position, &texts, &app.name);
+        }
+        for (position, bookmark) in bookmarks.iter().enumerate() {
+            let mut texts = vec![bookmark.title.as_str(), bookmark.url.as_str()];
+            texts.extend(bookmark.folder_path.as_deref());
+            texts.extend(bookmark.keywords.iter().map(String::as_str));
+            texts.extend(bookmark.pinyin_index.as_deref());
+            add_entry(&mut index.bookmarks, position, &texts, &bookmark.title);
+        }
+        index
+    }
+
+    /// Positions of the apps and bookmarks sharing a trigram with every token
+    /// of three or more characters; `None` when no token is that long.
+    pub fn candidates(&self, tokens: &[&str]) -> Option<(Vec<usize>, Vec<usize>)> {
+        let queried: Vec<Vec<u64>> = tokens
+            .iter()
+            .map(|token| trigrams(&token.to_lowercase()))
+            .filter(|trigrams| !trigrams.is_empty())
+            .collect();
+        if queried.is_empty() {
+            return None;
+        }
+        Some((
+            matching(&self.apps, &queried),
+            matching(&self.bookmarks, &queried),
+        ))
+    }
+}
+
+fn add_entry(postings: &mut HashMap<u64, Vec<usize>>, position: usize, texts: &[&str], name: &str) {
+    let mut entry_trigrams: Vec<u64> = texts
+        .iter()
+        .flat_map(|text| trigrams(&text.to_lowercase()))
+        .chain(trigrams(&initials(name)))
+        .collect();
+    entry_trigrams.sort_unstable();
+    entry_trigrams.dedup();
+    for trigram in entry_trigrams {
+        postings.entry(trigram).or_default().push(position);
+    }
+}
+
+/// Entries sharing at least one trigram with each token's trigrams, ascending.
+fn matching(postings: &HashMap<u64, Vec<usize>>, queried: &[Vec<u64>]) -> Vec<usize> {
+    let mut result: Option<Vec<usize>> = None;
+    for token_trigrams in queried {
+        let mut positions: Vec<usize> = token_trigrams
+            .iter()
+            .filter_map(|trigram| postings.get(trigram))
+            .flatten()
+            .copied()
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        if let Some(previous) = &result {
+            positions.retain(|position| previous.binary_search(position).is_ok());
+        }
+        if positions.is_empty() {
+            return positions;
+        }
+        result = Some(positions);
+    }
+    result.unwrap_or_default()
+}
+
+/// Every run of three characters, packed 21 bits per character.
+fn trigrams(text: &str) -> Vec<u64> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .windows(3)
+        .map(|window| {
+            window
+                .iter()
+                .fold(0u64, |packed, &ch| (packed << 21) | u64::from(ch))
+        })
+        .collect()
+}
+
+/// The lowercase first letters of a name's words: `vsc` for Visual Studio Code.
+fn initials(name: &str) -> String {
+    name.split(|ch: char| ch.is_whitespace() || ch == '-' || ch == '_' || ch == '.')
+        .filter_map(|word| word.chars().next())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixture_apps, fixture_bookmarks};
+
+    #[test]
+    fn candidates_share_a_trigram_with_every_long_token() {
+        let apps = fixture_apps();
+        let bookmarks = fixture_bookmarks();
+        let index = TrigramIndex::build(&apps, &bookmarks);
+        let names = |tokens: &[&str]| -> Vec<&str> {
+            let (app_positions, _) = index.candidates(tokens).unwrap();
+            app_positions
+                .iter()
+                .map(|&position| apps[position].name.as_str())
+                .collect()
+        };
+
+        assert_eq!(names(&["CHROME"]), vec!["Google Chrome"]);
+        // Initials and a misspelling sharing one trigram are kept.
+        assert!(names(&["vsc"]).contains(&"Visual Studio Code"));
+        assert!(names(&["chrme"]).contains(&"Google Chrome"));
+        assert!(names(&["google", "qqq"]).is_empty());
+        assert_eq!(index.candidates(&["vs", "c"]), None);
+
+        let (_, bookmark_positions) = index.candidates(&["meeting"]).unwrap();
+        assert_eq!(bookmark_positions.len(), 1);
+        assert_eq!(bookmarks[bookmark_positions[0]].title, "Meeting Notes");
+        assert!(TrigramIndex::for_catalog(&apps, &bookmarks).is_none());
+    }
+}
@@ -1,8 +1,11 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     process::Command,
-    sync::Arc,
+    sync::{
+        mpsc::{self, Receiver},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
@@ -13,21 +16,98 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use log::warn;
 use ratatui::{
     backend::CrosstermBackend,
     prelude::*,
-    widgets::{Block, BorderType, Borders, List, ListItem, ListState, Padding, Paragraph, Wrap},
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Borders, List, ListItem, ListState, Padding, Paragraph, Wrap,
+    },
 };
 
+#[cfg(unix)]
+use crate::unix_utils::{list_processes, list_windows};
+#[cfg(windows)]
+use crate::windows_utils::{list_processes, list_windows};
 use crate::{
+    answers::{Answer, Answers},
+    bookmarks,
+    builtin_commands::BuiltinCommand,
     cache,
-    config::config_path,
-    indexer::build_index,
-    models::SearchResult,
-    search_core as core,
-    state::{AppState, CachedSearch, PendingAction},
+    config::{config_path, AppConfig},
+    execute::action_variants,
+    history::unix_now,
+    hotkey::{hotkey_matches, parse_hotkey},
+    index_diff::IndexDiff,
+    indexer::{build_index_with_progress, IndexOptions, IndexProgress},
+    locale::{fill, messages, Language, Messages},
+    logging::{self, span},
+    models::{ActionVariant, MatchSource, SearchResult},
+    plugins::{self, PluginManifest},
+    query_syntax::{self, Diagnostic},
+    resources::current_usage,
+    search_core::{self as core, QueryMode},
+    state::{
+        AppState, CacheProvider, CachedSearch, GenerationToken, PendingAction, ProviderOverrides,
+        RecentEntry, SearchProvider,
+    },
+    telemetry::{RankingTelemetry, SessionOutcome},
+    text_utils::{compact_path, truncate_middle},
+    theme::{downgrade_buffer, Theme},
+};
+#[cfg(windows)]
+use crate::{everything, provider, windows_search};
+
+mod actions_menu;
+mod alias_editor;
+mod answer_card;
+mod autocomplete;
+mod background;
+mod batch;
+mod dashboard;
+mod launch_options;
+mod page_titles;
+mod preview;
+mod result_groups;
+mod score_details;
+mod search_pipeline;
+mod session;
+mod split_panes;
+mod theme_editor;
+mod undo;
+mod web_suggestions;
+
+use actions_menu::{
+    handle_actions_menu_key_event, open_actions_menu, render_actions_menu, ActionsMenu,
+};
+use alias_editor::{handle_alias_editor_key_event, render_alias_editor, AliasEditor};
+use answer_card::{copy_answer, render_answer_card, sync_answer, CARD_HEIGHT};
+use autocomplete::{
+    accept_completion, completion_suffix, recall_query, remember_query, sync_completion,
+    HistoryBrowse,
+};
+use batch::{clear_marks, is_marked, launch_marked, toggle_mark, MARK_MARKER};
+use dashboard::{dashboard_rows, load_dashboard, Dashboard};
+use launch_options::{handle_launch_options_key_event, render_launch_options, LaunchOptionsEditor};
+use page_titles::{sync_page_titles, PageTitles};
+use preview::{render_preview, sync_preview, PreviewPane};
+use result_groups::{grouped_rows, ResultGroups};
+use score_details::{details_height, render_score_details, score_details};
+use search_pipeline::{SearchKind, SearchOutcome, SearchPipeline};
+use session::{
+    clear_session, restore_session, unfinished_session, SessionRecorder, SessionSnapshot,
+};
+use split_panes::{
+    move_selection_in_pane, page_selection_in_pane, render_split_results, switch_pane, SplitPanes,
 };
 
+use theme_editor::{
+    handle_theme_editor_key_event, open_theme_editor, render_theme_editor, ThemeEditor,
+};
+use undo::{undo_last, UndoEntry, UndoStack};
+use web_suggestions::{sync_web_suggestions, WebSuggestions};
+
 struct TerminalRestore;
 
 impl Drop for TerminalRestore {
@@ -38,21 +118,65 @@ impl Drop for TerminalRestore {
     }
 }
 
-struct TuiState {
-    input: String,
-    cursor: usize,
-    results: Vec<SearchResult>,
-    pending_actions: HashMap<String, PendingAction>,
-    list_state: ListState,
-    should_quit: bool,
-    pending_action: Option<PendingAction>,
-    pending_result: Option<SearchResult>,
-    status_message: Option<String>,
+pub(crate) struct TuiState {
+    pub(crate) input: String,
+    pub(crate) cursor: usize,
+    pub(crate) results: Vec<SearchResult>,
+    pub(crate) pending_actions: HashMap<String, PendingAction>,
+    pub(crate) list_state: ListState,
+    pub(crate) should_quit: bool,
+    pub(crate) pending_action: Option<PendingAction>,
+    pub(crate) pending_result: Option<SearchResult>,
+    /// How to run `pending_action`, picked in the actions menu.
+    pub(crate) pending_variant: ActionVariant,
+    /// Results marked with Ctrl+Space, in the order they were marked.
+    pub(crate) marked: Vec<(SearchResult, PendingAction)>,
+    /// The marked results, launched together when the TUI closes.
+    pub(crate) pending_batch: Vec<(SearchResult, PendingAction)>,
+    pub(crate) status_message: Option<String>,
     status_deadline: Option<Instant>,
+    pub(crate) theme_editor: Option<ThemeEditor>,
+    pub(crate) actions_menu: Option<ActionsMenu>,
+    pub(crate) launch_options: Option<LaunchOptionsEditor>,
+    pub(crate) alias_editor: Option<AliasEditor>,
+    /// Id of the result whose destructive action waits for a second Enter.
+    pub(crate) confirming: Option<String>,
+    /// Results that fit in the list at once, measured each frame; PageUp and
+    /// PageDown move this far.
+    pub(crate) page_size: usize,
+    /// Background search worker; without it searches run inline (as in tests).
+    search_pipeline: Option<SearchPipeline>,
+    /// A background search for the current input has not come back yet.
+    pub(crate) searching: bool,
+    /// Steps of the app reindex started with `egg reindex`, shown in the status line.
+    reindex_progress: Option<Receiver<IndexProgress>>,
+    pub(crate) split: SplitPanes,
+    pub(crate) dashboard: Dashboard,
+    pub(crate) groups: ResultGroups,
+    /// Subtitles show raw paths instead of `name · folder`; synced from the config each frame.
+    pub(crate) full_paths: bool,
+    /// Ids of pinned results; synced from the config each frame.
+    pub(crate) pinned: HashSet<String>,
+    /// Language of the UI text; synced from the config each frame and key press.
+    pub(crate) language: Language,
+    /// Left by a session that ended unexpectedly; Ctrl+R restores it, any other key dismisses it.
+    pub(crate) session_offer: Option<SessionSnapshot>,
+    pub(crate) preview: PreviewPane,
+    pub(crate) page_titles: PageTitles,
+    pub(crate) web_suggestions: WebSuggestions,
+    /// Blacklisting and launch option changes made this session, for Ctrl+Z.
+    pub(crate) undo: UndoStack,
+    pub(crate) answers: Answers,
+    /// Instant answer for the input, shown above the results; refreshed each frame.
+    pub(crate) answer: Option<Answer>,
+    /// Remembered query the input completes to, shown as ghost text; refreshed each frame.
+    pub(crate) completion: Option<String>,
+    /// Remembered query recalled with Alt+Up or Alt+Down, if browsing them.
+    pub(crate) history_browse: Option<HistoryBrowse>,
 }
 
 impl TuiState {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             input: String::new(),
             cursor: 0,
@@ -62,15 +186,50 @@ impl TuiState {
             should_quit: false,
             pending_action: None,
             pending_result: None,
+            pending_variant: ActionVariant::Open,
+            marked: Vec::new(),
+            pending_batch: Vec::new(),
             status_message: None,
             status_deadline: None,
+            theme_editor: None,
+            actions_menu: None,
+            launch_options: None,
+            alias_editor: None,
+            confirming: None,
+            page_size: 1,
+            search_pipeline: None,
+            searching: false,
+            reindex_progress: None,
+            split: SplitPanes::default(),
+            dashboard: Dashboard::default(),
+            groups: ResultGroups::default(),
+            full_paths: false,
+            pinned: HashSet::new(),
+            language: Language::default(),
+            session_offer: None,
+            preview: PreviewPane::default(),
+            page_titles: PageTitles::default(),
+            web_suggestions: WebSuggestions::default(),
+            undo: UndoStack::default(),
+            answers: Answers::new(),
+            answer: None,
+            completion: None,
+            history_browse: None,
         }
     }
+
+    pub(crate) fn text(&self) -> &'static Messages {
+        messages(self.language)
+    }
 }
 
 const STATUS_MESSAGE_TTL: Duration = Duration::from_secs(2);
 
-pub(crate) fn run_tui(state: Arc<AppState>) -> Result<Option<(SearchResult, PendingAction)>> {
+/// Runs the TUI until it closes, returning what to launch: the picked result, the
+/// marked ones, or nothing.
+pub(crate) fn run_tui(
+    state: Arc<AppState>,
+) -> Result<Vec<(SearchResult, PendingAction, ActionVariant)>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
@@ -79,10 +238,19 @@ pub(crate) fn run_tui(state: Arc<AppState>) -> Result<Option<(SearchResult, Pend
     let _restore = TerminalRestore;
 
     let mut ui_state = TuiState::new();
+    ui_state.search_pipeline = Some(SearchPipeline::spawn((*state).clone()));
+    ui_state.preview.loader.enabled = true;
+    ui_state.page_titles.loader.enabled = true;
+    ui_state.web_suggestions.loader.enabled = true;
+    ui_state.session_offer = unfinished_session();
     refresh_results(&mut ui_state, &state);
+    let mut session = SessionRecorder::new();
 
     loop {
+        poll_search_results(&mut ui_state);
+        poll_reindex_progress(&mut ui_state);
         terminal.draw(|frame| render_ui(frame, &mut ui_state, &state))?;
+        session.record(&ui_state);
 
         if ui_state.should_quit {
             break;
@@ -96,16 +264,107 @@ pub(crate) fn run_tui(state: Arc<AppState>) -> Result<Option<(SearchResult, Pend
     }
 
     terminal.show_cursor()?;
-    Ok(ui_state
-        .pending_action
-        .zip(ui_state.pending_result)
-        .map(|(action, result)| (result, action)))
+    clear_session();
+    let remembered = match &ui_state.pending_action {
+        Some(action) => {
+            action.remembered() && ui_state.pending_variant != ActionVariant::OpenPrivate
+        }
+        None => !ui_state.pending_batch.is_empty(),
+    };
+    if remembered && state.config.lock().unwrap().remember_queries {
+        remember_query(&state, &ui_state.input);
+    }
+    if state.config.lock().unwrap().ranking_telemetry {
+        record_ranking_telemetry(&ui_state);
+    }
+    let variant = ui_state.pending_variant;
+    Ok(match ui_state.pending_action.zip(ui_state.pending_result) {
+        Some((action, result)) => vec![(result, action, variant)],
+        None => ui_state
+            .pending_batch
+            .into_iter()
+            .map(|(result, action)| (result, action, ActionVariant::Open))
+            .collect(),
+    })
+}
+
+fn record_ranking_telemetry(ui_state: &TuiState) {
+    let Some(outcome) = session_outcome(ui_state) else {
+        return;
+    };
+    let mut telemetry = RankingTelemetry::load();
+    if telemetry.observe(&outcome, unix_now()) {
+        if let Err(err) = telemetry.save() {
+            log::warn!("failed to save ranking telemetry: {err}");
+        }
+    }
+}
+
+/// Summarizes the session for telemetry; `>` and `file:` queries are not ranked, so they are skipped.
+fn session_outcome(ui_state: &TuiState) -> Option<SessionOutcome<'_>> {
+    let query = ui_state.input.trim();
+    if query.is_empty() || core::command_query(query).is_some() || core::file_query(query).is_some()
+    {
+        return None;
+    }
+    let local_results = ui_state
+        .results
+        .iter()
+        .filter(|result| {
+            !matches!(result.action_id.as_str(), "search" | "url") && !core::is_fallback(result)
+        })
+        .count();
+    let launched = ui_state.pending_result.as_ref().and_then(|launched| {
+        let position = ui_state
+            .results
+            .iter()
+            .position(|result| result.id == launched.id)?;
+        Some((position, launched.id.as_str(), launched.title.as_str()))
+    });
+    Some(SessionOutcome {
+        query,
+        local_results,
+        launched,
+    })
 }
 
-fn handle_key_event(key: KeyEvent, ui_state: &mut TuiState, app_state: &AppState) {
+pub(crate) fn handle_key_event(key: KeyEvent, ui_state: &mut TuiState, app_state: &AppState) {
     if key.kind == KeyEventKind::Release {
         return;
     }
+    ui_state.language = app_state.config.lock().unwrap().language;
+    // Any key but Enter calls off a confirmation.
+    if key.code != KeyCode::Enter {
+        ui_state.confirming = None;
+    }
+
+    if let Some(snapshot) = ui_state.session_offer.take() {
+        if key.code == KeyCode::Char('r') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            restore_session(ui_state, app_state, snapshot);
+            set_status_message(ui_state, ui_state.text().session_restored);
+            return;
+        }
+    }
+
+    if ui_state.theme_editor.is_some() {
+        handle_theme_editor_key_event(key, ui_state, app_state);
+        return;
+    }
+
+    if ui_state.launch_options.is_some() {
+        handle_launch_options_key_event(key, ui_state, app_state);
+        return;
+    }
+
+    if ui_state.alias_editor.is_some() {
+        handle_alias_editor_key_event(key, ui_state, app_state);
+        return;
+    }
+
+    if ui_state.actions_menu.is_some() {
+        handle_actions_menu_key_event(key, ui_state, app_state);
+        return;
+    }
 
     handle_search_key_event(key, ui_state, app_state);
 }
@@ -116,18 +375,30 @@ fn handle_search_key_event(key: KeyEvent, ui_state: &mut TuiState, app_state: &A
         return;
     }
 
+    if key.code == KeyCode::Esc && clear_marks(ui_state) {
+        return;
+    }
+    if let Some(command) = shortcut_command(key) {
+        perform_builtin_command(command, ui_state, app_state);
+        return;
+    }
+
     if key.modifiers.contains(KeyModifiers::CONTROL) {
         match key.code {
             KeyCode::Char('c') => {
                 ui_state.should_quit = true;
             }
-            KeyCode::Char('o') => open_settings_in_editor(app_state),
+            KeyCode::Char('d') => toggle_pin_selected(ui_state, app_state),
+            KeyCode::Char('x') => hide_selected(ui_state, app_state),
+            KeyCode::Char('y') => copy_answer(ui_state),
             KeyCode::Char('n') => move_selection(ui_state, 1),
             KeyCode::Char('p') => move_selection(ui_state, -1),
             KeyCode::Char('w') => {
                 delete_prev_word(ui_state);
                 refresh_results(ui_state, app_state);
             }
+            KeyCode::Char(' ') => toggle_mark(ui_state, app_state),
+            KeyCode::Enter => launch_selected(ui_state, app_state, ActionVariant::RunAsAdmin),
             KeyCode::Left => move_cursor(ui_state, -1),
             KeyCode::Right => move_cursor(ui_state, 1),
             _ => {}
@@ -135,21 +406,30 @@ fn handle_search_key_event(key: KeyEvent, ui_state: &mut TuiState, app_state: &A
         return;
     }
 
+    if matches!(key.code, KeyCode::Right | KeyCode::Tab) && accept_completion(ui_state, app_state) {
+        return;
+    }
+
+    if let Some(index) = launch_shortcut(key) {
+        launch_nth(ui_state, app_state, index);
+        return;
+    }
+
+    let recalls = key.modifiers.contains(KeyModifiers::ALT) || ui_state.results.is_empty();
     match key.code {
-        KeyCode::Esc => ui_state.should_quit = true,
-        KeyCode::Enter => {
-            if let Some(index) = ui_state.list_state.selected() {
-                if let Some(result) = ui_state.results.get(index).cloned() {
-                    if let Some(action) = ui_state.pending_actions.get(&result.id).cloned() {
-                        ui_state.pending_action = Some(action);
-                        ui_state.pending_result = Some(result);
-                        ui_state.should_quit = true;
-                    }
-                }
-            }
+        KeyCode::Enter if key.modifiers.contains(KeyModifiers::SHIFT) => {
+            open_actions_menu(ui_state, app_state)
         }
+        KeyCode::Enter if launch_marked(ui_state) => {}
+        KeyCode::Enter => launch_selected(ui_state, app_state, ActionVariant::Open),
+        KeyCode::Up if recalls => recall_query(ui_state, app_state, 1),
+        KeyCode::Down if recalls => recall_query(ui_state, app_state, -1),
         KeyCode::Up => move_selection(ui_state, -1),
         KeyCode::Down => move_selection(ui_state, 1),
+        KeyCode::PageUp => page_selection(ui_state, -1),
+        KeyCode::PageDown => page_selection(ui_state, 1),
+        KeyCode::Tab | KeyCode::BackTab if ui_state.split.enabled => switch_pane(ui_state),
+        KeyCode::Tab => open_actions_menu(ui_state, app_state),
         KeyCode::Home => ui_state.cursor = 0,
         KeyCode::End => ui_state.cursor = ui_state.input.chars().count(),
         KeyCode::Left => move_cursor(ui_state, -1),
@@ -174,66 +454,439 @@ fn handle_search_key_event(key: KeyEvent, ui_state: &mut TuiState, app_state: &A
     }
 }
 
-fn refresh_results(ui_state: &mut TuiState, app_state: &AppState) {
+/// Switches a provider on or off until egg exits; `settings.json` is left alone.
+fn toggle_provider(ui_state: &mut TuiState, app_state: &AppState, provider: SearchProvider) {
+    let config = app_state.config.lock().unwrap().clone();
+    let toggled = app_state
+        .provider_overrides
+        .lock()
+        .unwrap()
+        .toggle(provider, &config);
+    match toggled {
+        Ok(enabled) => {
+            let text = ui_state.text();
+            let state = if enabled {
+                text.provider_on
+            } else {
+                text.provider_off
+            };
+            set_status_message(
+                ui_state,
+                fill(text.provider_toggled, &[provider.label(text), state]),
+            );
+            refresh_results(ui_state, app_state);
+        }
+        Err(err) => set_status_message(ui_state, err),
+    }
+}
+
+fn launch_selected(ui_state: &mut TuiState, app_state: &AppState, variant: ActionVariant) {
+    if ui_state.searching {
+        finish_pending_search(ui_state, app_state);
+    }
+    let Some(result) = ui_state
+        .list_state
+        .selected()
+        .and_then(|index| ui_state.results.get(index))
+        .cloned()
+    else {
+        return;
+    };
+    let Some(action) = ui_state.pending_actions.get(&result.id).cloned() else {
+        return;
+    };
+    match action {
+        PendingAction::Builtin(command) => {
+            run_builtin_command(command, ui_state, app_state);
+            return;
+        }
+        PendingAction::Query(query) => {
+            ui_state.cursor = query.chars().count();
+            ui_state.input = query;
+            refresh_results(ui_state, app_state);
+            return;
+        }
+        PendingAction::Plugin(call) if call.request.is_none() => return,
+        _ => {}
+    }
+    if !action_variants(&action).contains(&variant) {
+        let text = ui_state.text();
+        set_status_message(
+            ui_state,
+            fill(text.action_unavailable, &[variant.label(text)]),
+        );
+        return;
+    }
+    if !confirmed(ui_state, &result, &action) {
+        return;
+    }
+    ui_state.pending_variant = variant;
+    ui_state.pending_action = Some(action);
+    ui_state.pending_result = Some(result);
+    ui_state.should_quit = true;
+}
+
+/// Whether `action` may run now. Destructive actions ask first: the first Enter
+/// only explains what will happen, and a second Enter on the same result runs it.
+fn confirmed(ui_state: &mut TuiState, result: &SearchResult, action: &PendingAction) -> bool {
+    if !action.needs_confirmation() || ui_state.confirming.as_deref() == Some(&result.id) {
+        ui_state.confirming = None;
+        return true;
+    }
+    ui_state.confirming = Some(result.id.clone());
+    let message = fill(ui_state.text().confirm_end_process, &[&result.title]);
+    set_status_message(ui_state, message);
+    false
+}
+
+fn toggle_full_paths(ui_state: &mut TuiState, app_state: &AppState) {
+    let mut config = app_state.config.lock().unwrap();
+    config.full_path_subtitles = !config.full_path_subtitles;
+    ui_state.full_paths = config.full_path_subtitles;
+    let saved = config.save();
+    drop(config);
+    let text = ui_state.text();
+    match saved {
+        Ok(()) if ui_state.full_paths => set_status_message(ui_state, text.showing_full_paths),
+        Ok(()) => set_status_message(ui_state, text.showing_compact_paths),
+        Err(_) => set_status_message(ui_state, text.settings_not_saved),
+    }
+}
+
+/// Pins the selected app or bookmark so it ranks first whenever it matches, or
+/// unpins it.
+fn toggle_pin_selected(ui_state: &mut TuiState, app_state: &AppState) {
+    let text = ui_state.text();
+    let Some(result) = ui_state
+        .list_state
+        .selected()
+        .and_then(|index| ui_state.results.get(index))
+        .cloned()
+    else {
+        set_status_message(ui_state, text.nothing_to_pin);
+        return;
+    };
+    if !matches!(
+        ui_state.pending_actions.get(&result.id),
+        Some(PendingAction::Application(_) | PendingAction::Bookmark(_))
+    ) {
+        set_status_message(ui_state, text.cannot_pin);
+        return;
+    }
+
+    let mut config = app_state.config.lock().unwrap();
+    let previous = config.pinned_results.clone();
+    let unpinned_at = config.pinned_results.iter().position(|id| *id == result.id);
+    match unpinned_at {
+        Some(index) => {
+            config.pinned_results.remove(index);
+        }
+        None => config.pinned_results.push(result.id.clone()),
+    }
+    if config.save().is_err() {
+        config.pinned_results = previous;
+        drop(config);
+        set_status_message(ui_state, text.settings_not_saved);
+        return;
+    }
+    ui_state.pinned = config.pinned_results.iter().cloned().collect();
+    drop(config);
+    if let Some(index) = unpinned_at {
+        ui_state.undo.push(UndoEntry::Pin {
+            result_id: result.id.clone(),
+            title: result.title.clone(),
+            index,
+        });
+    }
+
+    if let Ok(mut cache_guard) = app_state.search_cache.lock() {
+        cache_guard.invalidate(CacheProvider::Config);
+    }
+    refresh_results(ui_state, app_state);
+    let message = if unpinned_at.is_none() {
+        text.pinned
+    } else {
+        text.unpinned
+    };
+    set_status_message(ui_state, fill(message, &[&result.title]));
+}
+
+/// Whether Ctrl+Enter can elevate the selected result.
+fn selected_can_elevate(ui_state: &TuiState) -> bool {
+    ui_state
+        .list_state
+        .selected()
+        .and_then(|index| ui_state.results.get(index))
+        .and_then(|result| ui_state.pending_actions.get(&result.id))
+        .is_some_and(|action| action_variants(action).contains(&ActionVariant::RunAsAdmin))
+}
+
+pub(crate) fn refresh_results(ui_state: &mut TuiState, app_state: &AppState) {
     let trimmed = ui_state.input.trim();
     if trimmed.is_empty() {
-        let recent_guard = app_state.recent_actions.lock().unwrap();
-        ui_state.results = recent_guard
-            .items()
-            .map(|entry| entry.result.clone())
-            .collect();
-        ui_state.pending_actions = recent_guard
-            .items()
-            .map(|entry| (entry.result.id.clone(), entry.action.clone()))
-            .collect();
-        reset_selection(ui_state);
+        cancel_pending_search(ui_state);
+        load_dashboard(ui_state, app_state);
         return;
     }
 
-    let config_snapshot = app_state.config.lock().unwrap().clone();
-    let app_index = app_state.app_index.lock().unwrap().clone();
-    let bookmark_index = app_state.bookmark_index.lock().unwrap().clone();
-    let cache_key = format!(
-        "{}|{}|{}|{}",
-        trimmed,
-        config_snapshot.enable_app_results,
-        config_snapshot.enable_bookmark_results,
-        config_snapshot.max_results
-    );
+    let config_snapshot = app_state.search_config();
+    if let Some(base) = query_syntax::help_request(trimmed) {
+        let plugins = if config_snapshot.enable_plugins {
+            app_state.plugins()
+        } else {
+            Arc::from(Vec::new())
+        };
+        let (results, pending_actions) =
+            query_syntax::help_results(base, &config_snapshot, &plugins);
+        cancel_pending_search(ui_state);
+        ui_state.results = results;
+        ui_state.pending_actions = pending_actions;
+        reset_selection(ui_state);
+        return;
+    }
+    if let Some(file_query) = core::file_query(trimmed)
+        .filter(|_| config_snapshot.enable_everything || config_snapshot.enable_windows_search)
+    {
+        if file_query.is_empty() {
+            cancel_pending_search(ui_state);
+            ui_state.results.clear();
+            ui_state.pending_actions.clear();
+            reset_selection(ui_state);
+        } else {
+            start_search(
+                ui_state,
+                app_state,
+                SearchKind::Files,
+                file_query.to_string(),
+            );
+        }
+        return;
+    }
+    match core::split_mode_prefix(trimmed, &config_snapshot.query_prefixes) {
+        (QueryMode::Window, search) => {
+            let search = search.to_string();
+            cancel_pending_search(ui_state);
+            refresh_window_results(ui_state, &search, &config_snapshot);
+            return;
+        }
+        (QueryMode::Process, search) => {
+            let search = search.to_string();
+            cancel_pending_search(ui_state);
+            refresh_process_results(ui_state, &search, &config_snapshot);
+            return;
+        }
+        _ => {}
+    }
+    if config_snapshot.enable_plugins
+        && core::command_query(trimmed).is_none()
+        && core::split_mode_prefix(trimmed, &config_snapshot.query_prefixes).0 == QueryMode::All
+    {
+        let plugins = app_state.plugins();
+        if let Some((plugin, search)) =
+            plugins::match_keyword(trimmed, &plugins, &config_snapshot.disabled_plugins)
+        {
+            let (plugin, search) = (plugin.clone(), search.to_string());
+            start_search(ui_state, app_state, SearchKind::Plugin(plugin), search);
+            return;
+        }
+    }
 
+    let cache_key = search_cache_key(trimmed, &config_snapshot);
     if let Ok(mut cache_guard) = app_state.search_cache.lock() {
         if let Some(cached) = cache_guard.get(&cache_key) {
-            ui_state.results = cached.results.clone();
-            ui_state.pending_actions = cached.pending_actions.clone();
+            let cached = cached.clone();
+            drop(cache_guard);
+            cancel_pending_search(ui_state);
+            ui_state.results = cached.results;
+            ui_state.pending_actions = cached.pending_actions;
             reset_selection(ui_state);
             return;
         }
     }
 
-    let (results, pending_actions) = core::search(
-        trimmed.to_string(),
+    start_search(ui_state, app_state, SearchKind::Index, trimmed.to_string());
+}
+
+/// Hands `query` to the background pipeline, or runs it right away without one
+/// (as in tests).
+fn start_search(ui_state: &mut TuiState, app_state: &AppState, kind: SearchKind, query: String) {
+    if let Some(pipeline) = ui_state.search_pipeline.as_ref() {
+        pipeline.submit(kind, query);
+        ui_state.searching = true;
+        return;
+    }
+    let outcome = kind.run(&query, app_state, &GenerationToken::detached());
+    show_search_outcome(ui_state, outcome);
+}
+
+fn show_search_outcome(ui_state: &mut TuiState, outcome: SearchOutcome) {
+    ui_state.results = outcome.results;
+    ui_state.pending_actions = outcome.pending_actions;
+    if let Some(message) = outcome.failure {
+        set_status_message(ui_state, message);
+    }
+    reset_selection(ui_state);
+}
+
+/// Applies the newest finished background search, if any.
+fn poll_search_results(ui_state: &mut TuiState) {
+    let Some(response) = ui_state
+        .search_pipeline
+        .as_mut()
+        .and_then(|pipeline| pipeline.try_recv())
+    else {
+        return;
+    };
+    ui_state.searching = false;
+    show_search_outcome(ui_state, response.outcome);
+}
+
+/// Shows how far `egg reindex` got, until the app index is built.
+fn poll_reindex_progress(ui_state: &mut TuiState) {
+    let Some(receiver) = ui_state.reindex_progress.as_ref() else {
+        return;
+    };
+    let text = ui_state.text();
+    let mut message = None;
+    let mut finished = false;
+    for step in receiver.try_iter() {
+        match step {
+            IndexProgress::Started => {}
+            IndexProgress::Source {
+                source,
+                count,
+                done,
+                sources,
+            } => {
+                message = Some(fill(
+                    text.reindex_progress,
+                    &[
+                        &done.to_string(),
+                        &sources.to_string(),
+                        source,
+                        &count.to_string(),
+                    ],
+                ));
+            }
+            IndexProgress::Finished { total, duration } => {
+                message = Some(fill(
+                    text.reindex_finished,
+                    &[
+                        &total.to_string(),
+                        &format!("{:.1}", duration.as_secs_f64()),
+                    ],
+                ));
+                finished = true;
+            }
+        }
+    }
+    if finished {
+        ui_state.reindex_progress = None;
+    }
+    if let Some(message) = message {
+        set_status_message(ui_state, message);
+    }
+}
+
+/// Runs the pending search inline so Enter never acts on results for older input.
+fn finish_pending_search(ui_state: &mut TuiState, app_state: &AppState) {
+    cancel_pending_search(ui_state);
+    let query = ui_state.input.trim().to_string();
+    let (results, pending_actions) = run_search(&query, app_state, &GenerationToken::detached());
+    ui_state.results = results;
+    ui_state.pending_actions = pending_actions;
+    reset_selection(ui_state);
+}
+
+fn cancel_pending_search(ui_state: &mut TuiState) {
+    if let Some(pipeline) = ui_state.search_pipeline.as_ref() {
+        pipeline.cancel();
+    }
+    ui_state.searching = false;
+}
+
+fn search_cache_key(query: &str, config: &AppConfig) -> String {
+    format!(
+        "{}|{}|{}|{}|{}|{:?}|{}",
+        query,
+        config.enable_app_results,
+        config.enable_bookmark_results,
+        config.enable_system_commands,
+        config.max_results,
+        config.ranking_profile,
+        config.group_results
+    )
+}
+
+/// Providers whose data can change the results of `query`.
+fn search_providers(query: &str, config: &AppConfig) -> Vec<CacheProvider> {
+    let mut providers = vec![CacheProvider::Config];
+    if core::command_query(query).is_some() || core::emoji_query(query).is_some() {
+        return providers;
+    }
+    if config.enable_app_results {
+        providers.push(CacheProvider::Apps);
+    }
+    if config.enable_bookmark_results {
+        providers.push(CacheProvider::Bookmarks);
+    }
+    providers
+}
+
+/// Runs a search against snapshots of the indexes and caches the outcome. When
+/// the query extends the previous one, only that search's matches are re-scored.
+/// A search `token` went stale during is cut short and not cached.
+fn run_search(
+    query: &str,
+    app_state: &AppState,
+    token: &GenerationToken,
+) -> (Vec<SearchResult>, HashMap<String, PendingAction>) {
+    let _span = span("search");
+    let stamp = app_state.search_cache.lock().unwrap().stamp();
+    let config_snapshot = app_state.search_config();
+    let app_index = app_state.app_index.lock().unwrap().clone();
+    let bookmark_index = app_state.bookmark_index.lock().unwrap().clone();
+    let history = app_state.launch_history.lock().unwrap().clone();
+    // Both are checked against the generations after the snapshots, so neither
+    // candidates nor a trigram index from an older index are used.
+    let prior = app_state.search_cache.lock().unwrap().candidates(stamp);
+    let trigrams = app_state.trigram_index(stamp, &app_index, &bookmark_index);
+
+    let (results, pending_actions, candidates) = core::refine_search(
+        query.to_string(),
         None,
-        &app_index,
-        &bookmark_index,
+        prior.as_ref(),
+        core::SearchIndexes::new(&app_index, &bookmark_index).with_trigrams(trigrams.as_deref()),
+        &history,
         &config_snapshot,
+        token,
     );
+    if token.is_stale() {
+        return (results, pending_actions);
+    }
 
     if let Ok(mut cache_guard) = app_state.search_cache.lock() {
         cache_guard.insert(
-            cache_key,
+            search_cache_key(query, &config_snapshot),
             CachedSearch {
+                query: query.to_string(),
                 results: results.clone(),
                 pending_actions: pending_actions.clone(),
             },
+            search_providers(query, &config_snapshot),
+            stamp,
         );
+        cache_guard.set_candidates(candidates, stamp);
     }
-
-    ui_state.results = results;
-    ui_state.pending_actions = pending_actions;
-    reset_selection(ui_state);
+    (results, pending_actions)
 }
 
 fn reset_selection(ui_state: &mut TuiState) {
+    ui_state.split.forget_selection();
+    ui_state.groups.forget_selection();
+    ui_state.dashboard.clear();
+    // New results are shown from the top.
+    *ui_state.list_state.offset_mut() = 0;
     if ui_state.results.is_empty() {
         ui_state.list_state.select(None);
     } else {
@@ -242,6 +895,10 @@ fn reset_selection(ui_state: &mut TuiState) {
 }
 
 fn move_selection(ui_state: &mut TuiState, delta: isize) {
+    if ui_state.split.enabled {
+        move_selection_in_pane(ui_state, delta);
+        return;
+    }
     let len = ui_state.results.len();
     if len == 0 {
         ui_state.list_state.select(None);
@@ -264,6 +921,21 @@ fn move_selection(ui_state: &mut TuiState, delta: isize) {
     ui_state.list_state.select(Some(next));
 }
 
+/// Moves a page of results at a time, stopping at the first and last result.
+fn page_selection(ui_state: &mut TuiState, pages: isize) {
+    let delta = pages * ui_state.page_size.max(1) as isize;
+    if ui_state.split.enabled {
+        page_selection_in_pane(ui_state, delta);
+        return;
+    }
+    let len = ui_state.results.len();
+    let Some(current) = ui_state.list_state.selected().filter(|_| len > 0) else {
+        return;
+    };
+    let next = (current as isize + delta).clamp(0, len as isize - 1) as usize;
+    ui_state.list_state.select(Some(next));
+}
+
 fn move_cursor(ui_state: &mut TuiState, delta: isize) {
     let len = ui_state.input.chars().count();
     if delta < 0 {
@@ -351,35 +1023,14 @@ fn update_status_message(ui_state: &mut TuiState) {
     }
 }
 
-#[derive(Clone, Copy)]
-struct Theme {
-    background: Color,
-    surface: Color,
-    border: Color,
-    accent: Color,
-    text: Color,
-    dim: Color,
-    highlight_bg: Color,
-    highlight_fg: Color,
-}
-
-impl Theme {
-    fn new() -> Self {
-        Self {
-            background: Color::Rgb(18, 20, 23),
-            surface: Color::Rgb(28, 31, 36),
-            border: Color::Rgb(58, 62, 70),
-            accent: Color::Rgb(242, 193, 78),
-            text: Color::Rgb(232, 230, 227),
-            dim: Color::Rgb(148, 153, 160),
-            highlight_bg: Color::Rgb(45, 93, 124),
-            highlight_fg: Color::Rgb(250, 250, 250),
+pub(crate) fn render_ui(frame: &mut Frame, ui_state: &mut TuiState, app_state: &AppState) {
+    let (theme, colors) = match &ui_state.theme_editor {
+        Some(editor) => (editor.theme(), editor.draft.colors),
+        None => {
+            let config = app_state.config.lock().unwrap();
+            (Theme::from_config(&config.theme), config.theme.colors)
         }
-    }
-}
-
-fn render_ui(frame: &mut Frame, ui_state: &mut TuiState, _app_state: &AppState) {
-    let theme = Theme::new();
+    };
     update_status_message(ui_state);
     let area = frame.size();
     frame.render_widget(
@@ -399,22 +1050,103 @@ fn render_ui(frame: &mut Frame, ui_state: &mut TuiState, _app_state: &AppState)
 
     let header_area = layout[0];
     let input_area = layout[1];
-    let list_area = layout[2];
+    let mut list_area = layout[2];
     let footer_area = layout[3];
+    let config = app_state.config.lock().unwrap();
+    let debug_mode = config.debug_mode && ui_state.theme_editor.is_none();
+    ui_state.split.enabled = config.split_panes
+        && ui_state.theme_editor.is_none()
+        && list_area.width >= split_panes::MIN_WIDTH;
+    ui_state.groups.enabled = config.group_results && !ui_state.split.enabled;
+    ui_state.full_paths = config.full_path_subtitles;
+    ui_state.language = config.language;
+    ui_state.pinned = config.pinned_results.iter().cloned().collect();
+    let preview_enabled = config.preview_pane
+        && ui_state.theme_editor.is_none()
+        && !ui_state.split.enabled
+        && list_area.width >= preview::MIN_WIDTH;
+    let fetch_page_titles = config.fetch_page_titles;
+    let suggestion_engine = config.web_suggestions.then_some(config.suggestion_engine);
+    let (query_mode, _) = core::split_mode_prefix(&ui_state.input, &config.query_prefixes);
+    let (_, web_query) = core::split_mode_prefix(ui_state.input.trim(), &config.query_prefixes);
+    let web_query = web_query.to_string();
+    let diagnostic = query_syntax::diagnose(&ui_state.input, &config)
+        .into_iter()
+        .next();
+    sync_answer(ui_state, &config);
+    let remember_queries = config.remember_queries;
+    drop(config);
+    sync_completion(ui_state, app_state, remember_queries);
+    let overrides = *app_state.provider_overrides.lock().unwrap();
+    sync_preview(ui_state, preview_enabled);
+    sync_page_titles(ui_state, fetch_page_titles);
+    sync_web_suggestions(ui_state, suggestion_engine, &web_query);
+    if let Some(answer) = &ui_state.answer {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(CARD_HEIGHT), Constraint::Min(3)])
+            .split(list_area);
+        render_answer_card(frame, rows[0], answer, theme, ui_state.text());
+        list_area = rows[1];
+    }
+    if let Some(lines) = debug_mode
+        .then(|| score_details(ui_state, app_state))
+        .flatten()
+    {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(details_height(&lines)),
+            ])
+            .split(list_area);
+        render_score_details(frame, rows[1], &lines, theme);
+        list_area = rows[0];
+    }
 
-    render_header(frame, header_area, ui_state, theme);
-    render_input(frame, input_area, ui_state, theme);
-    render_results(frame, list_area, ui_state, theme);
+    let text = ui_state.text();
+    render_header(frame, header_area, ui_state, query_mode, overrides, theme);
+    render_input(frame, input_area, ui_state, diagnostic, theme);
+    match &ui_state.theme_editor {
+        Some(editor) => render_theme_editor(frame, list_area, editor, theme, text),
+        None if ui_state.preview.is_open() => {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(list_area);
+            render_results(frame, columns[0], ui_state, theme);
+            render_preview(frame, columns[1], &ui_state.preview, theme, text);
+        }
+        None => render_results(frame, list_area, ui_state, theme),
+    }
+    if let Some(menu) = &ui_state.actions_menu {
+        render_actions_menu(frame, list_area, menu, theme, text);
+    }
+    if let Some(editor) = &ui_state.launch_options {
+        render_launch_options(frame, list_area, editor, theme, text);
+    }
+    if let Some(editor) = &ui_state.alias_editor {
+        render_alias_editor(frame, list_area, editor, theme, text);
+    }
     render_footer(frame, footer_area, ui_state, theme);
+    downgrade_buffer(frame.buffer_mut(), colors.resolve());
 }
 
-fn render_header(frame: &mut Frame, area: Rect, ui_state: &TuiState, theme: Theme) {
+fn render_header(
+    frame: &mut Frame,
+    area: Rect,
+    ui_state: &TuiState,
+    query_mode: QueryMode,
+    overrides: ProviderOverrides,
+    theme: Theme,
+) {
+    let text = ui_state.text();
     let layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
         .split(area);
 
-    let left = Line::from(vec![
+    let mut left = Line::from(vec![
         Span::styled(
             "egg",
             Style::default()
@@ -422,17 +1154,44 @@ fn render_header(frame: &mut Frame, area: Rect, ui_state: &TuiState, theme: Them
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(
-            "  search".to_string(),
+            format!("  {}", text.header_search),
             Style::default().fg(theme.dim),
         ),
     ]);
+    if let Some(label) = query_mode.label(text) {
+        left.spans.push(Span::styled(
+            format!(" · {label}"),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    // Badges for providers switched with F3-F5, so a missing source is never a mystery.
+    for provider in SearchProvider::ALL {
+        let Some(enabled) = overrides.get(provider) else {
+            continue;
+        };
+        let state = if enabled {
+            text.provider_on
+        } else {
+            text.provider_off
+        };
+        left.spans.push(Span::raw(" "));
+        left.spans.push(Span::styled(
+            format!(" {} {state} ", provider.label(text)),
+            Style::default()
+                .fg(theme.highlight_fg)
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
     let left_widget = Paragraph::new(left).style(Style::default().bg(theme.background));
     frame.render_widget(left_widget, layout[0]);
 
     let label = if ui_state.input.trim().is_empty() {
-        "recent"
+        text.header_home
     } else {
-        "results"
+        text.header_results
     };
     let right_text = format!("{label}: {}", ui_state.results.len());
     let right = Paragraph::new(Line::from(Span::styled(
@@ -444,34 +1203,67 @@ fn render_header(frame: &mut Frame, area: Rect, ui_state: &TuiState, theme: Them
     frame.render_widget(right, layout[1]);
 }
 
-fn render_input(frame: &mut Frame, area: Rect, ui_state: &mut TuiState, theme: Theme) {
+/// Draws the search box, with the first query diagnostic on its bottom border.
+fn render_input(
+    frame: &mut Frame,
+    area: Rect,
+    ui_state: &mut TuiState,
+    diagnostic: Option<Diagnostic>,
+    theme: Theme,
+) {
     let input_padding = 1u16;
     let input_width = area
         .width
         .saturating_sub(2 + input_padding.saturating_mul(2)) as usize;
     let (visible_input, cursor_x) = slice_input(&ui_state.input, ui_state.cursor, input_width);
-    let input_span = if ui_state.input.is_empty() {
-        Span::styled("Type to search...", Style::default().fg(theme.dim))
+    let text = ui_state.text();
+    let mut input_spans = if ui_state.input.is_empty() {
+        vec![Span::styled(
+            text.placeholder,
+            Style::default().fg(theme.dim),
+        )]
     } else {
-        Span::styled(visible_input, Style::default().fg(theme.text))
+        vec![Span::styled(visible_input, Style::default().fg(theme.text))]
     };
+    // Ghost text fills the room left after the input, so it never scrolls it.
+    let room = input_width.saturating_sub(ui_state.input.chars().count() + 1);
+    if let Some(suffix) = completion_suffix(ui_state).filter(|_| room > 0) {
+        let ghost: String = suffix.chars().take(room).collect();
+        input_spans.push(Span::styled(ghost, Style::default().fg(theme.dim)));
+    }
 
-    let input = Paragraph::new(Line::from(input_span))
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.accent))
         .style(Style::default().bg(theme.surface))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(theme.accent))
-                .style(Style::default().bg(theme.surface))
-                .padding(Padding::horizontal(input_padding))
-                .title(Span::styled(
-                    " Search ",
-                    Style::default()
-                        .fg(theme.accent)
-                        .add_modifier(Modifier::BOLD),
-                )),
+        .padding(Padding::horizontal(input_padding))
+        .title(Span::styled(
+            format!(" {} ", text.search_title),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+    if let Some(diagnostic) = diagnostic {
+        let style = if diagnostic.is_error() {
+            Style::default()
+                .fg(Color::Rgb(240, 128, 128))
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.dim)
+        };
+        block = block.title(
+            Title::from(Span::styled(
+                format!(" {} ", diagnostic.message(text)),
+                style,
+            ))
+            .position(Position::Bottom)
+            .alignment(Alignment::Right),
         );
+    }
+    let input = Paragraph::new(Line::from(input_spans))
+        .style(Style::default().bg(theme.surface))
+        .block(block);
     frame.render_widget(input, area);
 
     let cursor_x = area.x + 1 + input_padding + cursor_x as u16;
@@ -483,21 +1275,32 @@ fn render_input(frame: &mut Frame, area: Rect, ui_state: &mut TuiState, theme: T
 }
 
 fn render_results(frame: &mut Frame, area: Rect, ui_state: &mut TuiState, theme: Theme) {
-    let block = Block::default()
+    ui_state.page_size = (area.height.saturating_sub(2) / RESULT_HEIGHT).max(1) as usize;
+    if ui_state.split.enabled && !ui_state.results.is_empty() {
+        render_split_results(frame, area, ui_state, theme);
+        return;
+    }
+
+    let text = ui_state.text();
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(theme.border))
         .style(Style::default().bg(theme.surface))
         .title(Span::styled(
-            " Results ",
+            if ui_state.searching {
+                format!(" {} · {} ", text.results_title, text.searching)
+            } else {
+                format!(" {} ", text.results_title)
+            },
             Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
         ));
 
     if ui_state.results.is_empty() {
         let message = if ui_state.input.trim().is_empty() {
-            "No recent items. Type to search."
+            text.no_recent_items
         } else {
-            "No results. Try another query."
+            text.no_results
         };
         let empty = Paragraph::new(message)
             .style(Style::default().fg(theme.dim).bg(theme.surface))
@@ -506,28 +1309,31 @@ fn render_results(frame: &mut Frame, area: Rect, ui_state: &mut TuiState, theme:
         frame.render_widget(empty, area);
         return;
     }
+    if let Some(position) = scroll_position(
+        ui_state.list_state.selected(),
+        ui_state.results.len(),
+        ui_state.page_size,
+        theme,
+    ) {
+        block = block.title(position);
+    }
 
-    let items: Vec<ListItem> = ui_state
-        .results
-        .iter()
-        .map(|result| {
-            let title = Line::from(Span::styled(
-                result.title.clone(),
-                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
-            ));
-            let (type_label, type_color) = result_type_info(&result.action_id, theme);
-            let mut subtitle_spans = Vec::new();
-            subtitle_spans.push(Span::styled(type_label, Style::default().fg(type_color)));
-            if !result.subtitle.trim().is_empty() {
-                subtitle_spans.push(Span::styled(
-                    format!(" {}", result.subtitle),
-                    Style::default().fg(theme.dim),
-                ));
-            }
-            let subtitle = Line::from(subtitle_spans);
-            ListItem::new(vec![title, subtitle])
-        })
-        .collect();
+    let (items, list_state) = if ui_state.dashboard.is_active() {
+        dashboard_rows(ui_state, area.width, theme)
+    } else if ui_state.groups.enabled {
+        grouped_rows(ui_state, area.width, theme)
+    } else {
+        let items = ui_state
+            .results
+            .iter()
+            .enumerate()
+            .map(|(index, result)| {
+                let marks = RowMarks::new(ui_state, index);
+                result_item(result, marks, ui_state.full_paths, area.width, theme, text)
+            })
+            .collect();
+        (items, &mut ui_state.list_state)
+    };
 
     let list = List::new(items)
         .block(block)
@@ -538,21 +1344,318 @@ fn render_results(frame: &mut Frame, area: Rect, ui_state: &mut TuiState, theme:
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("> ");
-    frame.render_stateful_widget(list, area, &mut ui_state.list_state);
+    frame.render_stateful_widget(list, area, list_state);
 }
 
-fn result_type_info(action_id: &str, theme: Theme) -> (&'static str, Color) {
-    match action_id {
-        "app" => ("app", theme.accent),
-        "uwp" => ("uwp", Color::Rgb(126, 211, 158)),
-        "bookmark" => ("bookmark", Color::Rgb(122, 199, 242)),
-        "url" => ("url", Color::Rgb(238, 185, 110)),
-        "search" => ("search", Color::Rgb(190, 168, 255)),
-        _ => ("Other", theme.dim),
+/// `12/45` on the bottom border, once there are more results than fit; just
+/// the count while nothing in the list is selected.
+fn scroll_position(
+    selected: Option<usize>,
+    len: usize,
+    page_size: usize,
+    theme: Theme,
+) -> Option<Title<'static>> {
+    if len <= page_size {
+        return None;
     }
+    let position = match selected {
+        Some(index) => format!(" {}/{len} ", index + 1),
+        None => format!(" {len} "),
+    };
+    Some(
+        Title::from(Span::styled(position, Style::default().fg(theme.dim)))
+            .position(Position::Bottom)
+            .alignment(Alignment::Right),
+    )
 }
 
-fn render_footer(frame: &mut Frame, area: Rect, ui_state: &TuiState, theme: Theme) {
+/// File results are never cached: Everything and Windows Search answer from their own
+/// live indexes.
+/// Runs a `file:` query on the pipeline's blocking task, so waiting out
+/// `windows_search_timeout_ms` never freezes the screen.
+fn run_file_search(query: &str, config: &AppConfig) -> SearchOutcome {
+    let (results, pending_actions, failure) = match search_files(query.to_string(), config) {
+        Ok((results, pending_actions)) => (results, pending_actions, None),
+        Err(err) => (
+            Vec::new(),
+            HashMap::new(),
+            Some(fill(
+                messages(config.language).file_search_unavailable,
+                &[&err],
+            )),
+        ),
+    };
+    SearchOutcome {
+        results,
+        pending_actions,
+        failure,
+    }
+}
+
+/// Windows Search also backs up Everything when that fails.
+#[cfg(windows)]
+fn search_files(
+    query: String,
+    config: &AppConfig,
+) -> Result<(Vec<SearchResult>, HashMap<String, PendingAction>), String> {
+    let mut error = None;
+    if config.enable_everything {
+        match everything::search(
+            &query,
+            config.max_results,
+            config.everything_dll_path.as_deref(),
+        ) {
+            Ok(paths) => return Ok(core::file_results(&paths)),
+            Err(err) => error = Some(err),
+        }
+    }
+    if config.enable_windows_search {
+        let limit = config.max_results;
+        return provider::with_timeout(
+            "Windows Search",
+            Duration::from_millis(config.windows_search_timeout_ms),
+            move || windows_search::search(&query, limit),
+        )
+        .map(|items| core::indexed_results(&items));
+    }
+    Err(error.unwrap_or_default())
+}
+
+#[cfg(not(windows))]
+fn search_files(
+    _: String,
+    _: &AppConfig,
+) -> Result<(Vec<SearchResult>, HashMap<String, PendingAction>), String> {
+    Err("Everything and Windows Search only run on Windows".to_string())
+}
+
+/// Window results are never cached: windows open, close and retitle all the time.
+fn refresh_window_results(ui_state: &mut TuiState, search: &str, config: &AppConfig) {
+    match list_windows() {
+        Ok(windows) => {
+            let (results, pending_actions) = core::window_results(&windows, search, config);
+            ui_state.results = results;
+            ui_state.pending_actions = pending_actions;
+        }
+        Err(err) => {
+            ui_state.results.clear();
+            ui_state.pending_actions.clear();
+            let message = fill(ui_state.text().window_list_unavailable, &[&err]);
+            set_status_message(ui_state, message);
+        }
+    }
+    reset_selection(ui_state);
+}
+
+/// Process results are never cached either: processes come and go all the time.
+fn refresh_process_results(ui_state: &mut TuiState, search: &str, config: &AppConfig) {
+    match list_processes() {
+        Ok(processes) => {
+            let (results, pending_actions) = core::process_results(&processes, search, config);
+            ui_state.results = results;
+            ui_state.pending_actions = pending_actions;
+        }
+        Err(err) => {
+            ui_state.results.clear();
+            ui_state.pending_actions.clear();
+            let message = fill(ui_state.text().process_list_unavailable, &[&err]);
+            set_status_message(ui_state, message);
+        }
+    }
+    reset_selection(ui_state);
+}
+
+/// Plugin results are never cached: plugins often answer from live data. The plugin
+/// runs on the pipeline's blocking task and is ended when it runs over its timeout
+/// or a newer query supersedes it.
+fn run_plugin_search(
+    plugin: &PluginManifest,
+    search: &str,
+    config: &AppConfig,
+    token: &GenerationToken,
+) -> SearchOutcome {
+    let timeout = Duration::from_millis(config.plugin_timeout_ms);
+    let (results, pending_actions, failure) = match plugins::query(plugin, search, timeout, token) {
+        Ok(replies) => {
+            let (results, pending_actions) = core::plugin_results(plugin, &replies);
+            (results, pending_actions, None)
+        }
+        Err(err) => (
+            Vec::new(),
+            HashMap::new(),
+            Some(fill(messages(config.language).plugin_unavailable, &[&err])),
+        ),
+    };
+    SearchOutcome {
+        results,
+        pending_actions,
+        failure,
+    }
+}
+
+/// What a result row shows besides the result itself.
+#[derive(Debug, Clone, Copy, Default)]
+struct RowMarks {
+    pinned: bool,
+    marked: bool,
+    /// The `n` of the Alt+n shortcut that launches the result.
+    shortcut: Option<usize>,
+}
+
+impl RowMarks {
+    fn new(ui_state: &TuiState, index: usize) -> Self {
+        let id = &ui_state.results[index].id;
+        Self {
+            pinned: ui_state.pinned.contains(id),
+            marked: is_marked(&ui_state.marked, id),
+            shortcut: shortcut_number(index),
+        }
+    }
+}
+
+/// Results launched directly with Alt+1 to Alt+9.
+const LAUNCH_SHORTCUTS: usize = 9;
+
+/// The `n` of Alt+n for the result at `index`.
+fn shortcut_number(index: usize) -> Option<usize> {
+    (index < LAUNCH_SHORTCUTS).then_some(index + 1)
+}
+
+/// The result position Alt+1 to Alt+9 launch.
+fn launch_shortcut(key: KeyEvent) -> Option<usize> {
+    if key.modifiers != KeyModifiers::ALT {
+        return None;
+    }
+    let KeyCode::Char(digit @ '1'..='9') = key.code else {
+        return None;
+    };
+    Some(digit as usize - '1' as usize)
+}
+
+/// Alt+n: launches the nth result without moving the selection there first.
+fn launch_nth(ui_state: &mut TuiState, app_state: &AppState, index: usize) {
+    if ui_state.searching {
+        finish_pending_search(ui_state, app_state);
+    }
+    if index >= ui_state.results.len() {
+        return;
+    }
+    ui_state.list_state.select(Some(index));
+    launch_selected(ui_state, app_state, ActionVariant::Open);
+}
+
+/// Renders a result for a list `width` columns wide (borders included).
+fn result_item(
+    result: &SearchResult,
+    marks: RowMarks,
+    full_paths: bool,
+    width: u16,
+    theme: Theme,
+    text: &Messages,
+) -> ListItem<'static> {
+    let mut title_spans = Vec::new();
+    if marks.marked {
+        title_spans.push(Span::styled(MARK_MARKER, Style::default().fg(theme.accent)));
+    }
+    if marks.pinned {
+        title_spans.push(Span::styled(PIN_MARKER, Style::default().fg(theme.accent)));
+    }
+    title_spans.push(Span::styled(
+        result.title.clone(),
+        Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+    ));
+    if let Some(source) = result.match_source {
+        let label = match source {
+            MatchSource::Typo => format!("  {}", text.typo_match),
+            _ => format!("  via {}", source.label()),
+        };
+        title_spans.push(Span::styled(
+            label,
+            Style::default()
+                .fg(theme.dim)
+                .add_modifier(Modifier::ITALIC),
+        ));
+    }
+    if let Some(number) = marks.shortcut {
+        // Right-aligned, where it fits: borders, highlight symbol and a gap.
+        let label = format!("Alt+{number}");
+        let used = Line::from(title_spans.clone()).width() + Span::raw(&label).width();
+        if let Some(gap) = usize::from(width)
+            .checked_sub(4 + used)
+            .filter(|gap| *gap > 0)
+        {
+            title_spans.push(Span::raw(" ".repeat(gap)));
+            title_spans.push(Span::styled(label, Style::default().fg(theme.dim)));
+        }
+    }
+    let title = Line::from(title_spans);
+    let (type_label, type_color) = result_type_info(&result.action_id, theme, text);
+    let mut subtitle_spans = Vec::new();
+    subtitle_spans.push(Span::styled(type_label, Style::default().fg(type_color)));
+    if !result.subtitle.trim().is_empty() {
+        // Borders, highlight symbol, type label and its trailing space.
+        let room = usize::from(width).saturating_sub(4 + Span::raw(type_label).width() + 1);
+        let subtitle = if full_paths || !shows_path(&result.action_id) {
+            result.subtitle.clone()
+        } else {
+            compact_path(&result.subtitle)
+        };
+        subtitle_spans.push(Span::styled(
+            format!(" {}", truncate_middle(&subtitle, room)),
+            Style::default().fg(theme.dim),
+        ));
+    }
+    let subtitle = Line::from(subtitle_spans);
+    ListItem::new(vec![title, subtitle])
+}
+
+const PIN_MARKER: &str = "★ ";
+/// Lines per result in the list: the title and the subtitle.
+const RESULT_HEIGHT: u16 = 2;
+
+/// Pads `label` with spaces to `width` terminal columns; wide characters count twice.
+fn pad_label(label: &str, width: usize) -> String {
+    let room = width.saturating_sub(Span::raw(label).width());
+    format!("{label}{}", " ".repeat(room))
+}
+
+/// Result kinds whose subtitle is a raw path or URL.
+fn shows_path(action_id: &str) -> bool {
+    matches!(action_id, "app" | "uwp" | "repo" | "file" | "url")
+}
+
+fn result_type_info(action_id: &str, theme: Theme, text: &Messages) -> (&'static str, Color) {
+    match action_id {
+        "app" => (text.kind_app, theme.accent),
+        "uwp" => (text.kind_uwp, Color::Rgb(126, 211, 158)),
+        "pwa" => (text.kind_pwa, Color::Rgb(110, 200, 230)),
+        "uninstall" => (text.kind_uninstall, Color::Rgb(230, 120, 120)),
+        "repo" => (text.kind_repo, Color::Rgb(240, 120, 80)),
+        "bookmark" => (text.kind_bookmark, Color::Rgb(122, 199, 242)),
+        "url" => (text.kind_url, Color::Rgb(238, 185, 110)),
+        "search" => (text.kind_search, Color::Rgb(190, 168, 255)),
+        "file" => (text.kind_file, Color::Rgb(214, 160, 214)),
+        "command" => (text.kind_command, Color::Rgb(240, 128, 128)),
+        "system" => (text.kind_system, Color::Rgb(255, 160, 90)),
+        "settings" => (text.kind_settings, Color::Rgb(160, 200, 120)),
+        "workspace" => (text.kind_workspace, Color::Rgb(255, 196, 120)),
+        "snippet" => (text.kind_snippet, Color::Rgb(200, 180, 150)),
+        "note" => (text.kind_note, Color::Rgb(230, 220, 140)),
+        "todo" => (text.kind_todo, Color::Rgb(150, 220, 170)),
+        "builtin" => ("egg", theme.accent),
+        "suggest" => (text.kind_suggest, theme.accent),
+        "help" => (text.kind_help, theme.dim),
+        "plugin" => (text.kind_plugin, Color::Rgb(150, 214, 200)),
+        "emoji" => (text.kind_emoji, Color::Rgb(250, 210, 100)),
+        "window" => (text.kind_window, Color::Rgb(140, 190, 250)),
+        "process" => (text.kind_process, Color::Rgb(240, 130, 120)),
+        _ => (text.kind_other, theme.dim),
+    }
+}
+
+const ADMIN_HINT_KEY: &str = "Ctrl+Enter";
+
+fn render_footer(frame: &mut Frame, area: Rect, ui_state: &TuiState, theme: Theme) {
     if let Some(message) = ui_state.status_message.as_deref() {
         let footer_widget = Paragraph::new(Line::from(Span::styled(
             message,
@@ -571,18 +1674,111 @@ fn render_footer(frame: &mut Frame, area: Rect, ui_state: &TuiState, theme: Them
         .fg(theme.accent)
         .add_modifier(Modifier::BOLD);
     let hint_style = Style::default().fg(theme.dim);
-    let footer = Line::from(vec![
-        Span::styled("Enter", key_style),
-        Span::styled(": run  ", hint_style),
-        Span::styled("Esc", key_style),
-        Span::styled(": quit  ", hint_style),
-        Span::styled("Up/Down", key_style),
-        Span::styled(": move  ", hint_style),
-        Span::styled("Ctrl+W", key_style),
-        Span::styled(": delete  ", hint_style),
-        Span::styled("Ctrl+O", key_style),
-        Span::styled(": settings", hint_style),
-    ]);
+    let text = ui_state.text();
+    let mut hints = if ui_state.session_offer.is_some() {
+        vec![
+            ("Ctrl+R", text.hint_restore_session),
+            (text.any_key, text.hint_dismiss),
+        ]
+    } else if ui_state.launch_options.is_some() {
+        vec![
+            ("Enter", text.hint_next),
+            ("Esc", text.hint_cancel),
+            ("Shift+Tab", text.hint_back),
+        ]
+    } else if ui_state.alias_editor.is_some() {
+        vec![("Enter", text.hint_save), ("Esc", text.hint_cancel)]
+    } else if ui_state.actions_menu.is_some() {
+        vec![
+            ("Enter", text.hint_run),
+            ("Esc", text.hint_back),
+            ("Up/Down", text.hint_move),
+        ]
+    } else if ui_state.theme_editor.is_some() {
+        vec![
+            ("Enter", text.hint_save),
+            ("Esc", text.hint_discard),
+            ("Up/Down", text.hint_slot),
+            ("Ctrl+R", text.hint_reset_slot),
+        ]
+    } else if ui_state.split.enabled {
+        vec![
+            ("Enter", text.hint_run),
+            ("Esc", text.hint_quit),
+            ("Up/Down", text.hint_move),
+            ("Tab", text.hint_pane),
+            ("Shift+Enter", text.hint_actions),
+            ("Ctrl+W", text.hint_delete),
+            ("Ctrl+O", text.hint_settings),
+            ("Ctrl+T", text.hint_theme),
+        ]
+    } else {
+        vec![
+            ("Enter", text.hint_run),
+            ("Esc", text.hint_quit),
+            ("Up/Down", text.hint_move),
+            ("Tab", text.hint_actions),
+            ("Ctrl+W", text.hint_delete),
+            ("Ctrl+O", text.hint_settings),
+            ("Ctrl+T", text.hint_theme),
+        ]
+    };
+    // Terminals do not report a held Ctrl on its own, so show the elevated launch
+    // whenever the selected result supports it.
+    let searching_results = ui_state.actions_menu.is_none()
+        && ui_state.launch_options.is_none()
+        && ui_state.alias_editor.is_none()
+        && ui_state.theme_editor.is_none()
+        && ui_state.session_offer.is_none();
+    if searching_results
+        && ui_state
+            .answer
+            .as_ref()
+            .is_some_and(|answer| answer.value.is_some())
+    {
+        hints.insert(1.min(hints.len()), ("Ctrl+Y", text.hint_copy_answer));
+    }
+    if searching_results && ui_state.completion.is_some() {
+        hints.retain(|(key, _)| *key != "Tab");
+        hints.insert(1.min(hints.len()), ("Tab", text.hint_complete));
+    }
+    if searching_results && !ui_state.undo.is_empty() {
+        hints.insert(3.min(hints.len()), ("Ctrl+Z", text.hint_undo));
+    }
+    if searching_results && selected_can_elevate(ui_state) {
+        hints.insert(1, (ADMIN_HINT_KEY, text.hint_run_as_admin));
+    }
+    let run_marked = fill(text.hint_run_marked, &[&ui_state.marked.len().to_string()]);
+    if searching_results && !ui_state.marked.is_empty() {
+        hints.retain(|(key, _)| !matches!(*key, "Enter" | "Esc"));
+        hints.insert(0, ("Enter", &run_marked));
+        hints.insert(1, ("Esc", text.hint_clear_marks));
+    }
+    // Drop trailing hints that would not fit instead of wrapping out of the one-line footer.
+    let mut spans = Vec::new();
+    let mut width = 0usize;
+    for (key, hint) in hints {
+        let separator = if spans.is_empty() { 0 } else { 2 };
+        let entry_width = Span::raw(key).width() + Span::raw(hint).width() + 2;
+        if width + separator + entry_width > area.width as usize {
+            break;
+        }
+        if separator > 0 {
+            spans.push(Span::styled("  ", hint_style));
+        }
+        let style = if key == ADMIN_HINT_KEY {
+            Style::default()
+                .fg(theme.highlight_fg)
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            key_style
+        };
+        spans.push(Span::styled(key, style));
+        spans.push(Span::styled(format!(": {hint}"), hint_style));
+        width += separator + entry_width;
+    }
+    let footer = Line::from(spans);
     let footer_widget = Paragraph::new(footer)
         .wrap(Wrap { trim: true })
         .alignment(Alignment::Center)
@@ -613,25 +1809,26 @@ fn key_matches_blacklist_hotkey(key: KeyEvent, app_state: &AppState) -> bool {
 }
 
 fn add_selected_to_blacklist(ui_state: &mut TuiState, app_state: &AppState) {
+    let text = ui_state.text();
     let Some(index) = ui_state.list_state.selected() else {
-        set_status_message(ui_state, "No selection to blacklist.");
+        set_status_message(ui_state, text.nothing_to_blacklist);
         return;
     };
     let Some(result) = ui_state.results.get(index).cloned() else {
-        set_status_message(ui_state, "No selection to blacklist.");
+        set_status_message(ui_state, text.nothing_to_blacklist);
         return;
     };
     let Some(action) = ui_state.pending_actions.get(&result.id).cloned() else {
-        set_status_message(ui_state, "Unable to resolve selection.");
+        set_status_message(ui_state, text.selection_unresolved);
         return;
     };
     let PendingAction::Application(app) = action else {
-        set_status_message(ui_state, "Only apps can be blacklisted.");
+        set_status_message(ui_state, text.cannot_blacklist);
         return;
     };
     let entry = app.path.trim();
     if entry.is_empty() {
-        set_status_message(ui_state, "Selected app has no path.");
+        set_status_message(ui_state, text.app_without_path);
         return;
     }
     let entry = entry.to_string();
@@ -644,128 +1841,211 @@ fn add_selected_to_blacklist(ui_state: &mut TuiState, app_state: &AppState) {
         .iter()
         .any(|item| item.eq_ignore_ascii_case(&entry))
     {
-        set_status_message(ui_state, format!("Already in blacklist: {app_name}"));
+        set_status_message(ui_state, fill(text.already_blacklisted, &[&app_name]));
         return;
     }
     config.system_tool_exclusions.push(entry.clone());
     if config.save().is_err() {
-        set_status_message(ui_state, "Failed to save settings.");
+        set_status_message(ui_state, text.settings_not_saved);
         return;
     }
     drop(config);
 
     if let Ok(mut guard) = app_state.app_index.lock() {
-        guard.retain(|item| !item.path.eq_ignore_ascii_case(&entry));
+        let before = guard.clone();
+        Arc::make_mut(&mut guard).retain(|item| !item.path.eq_ignore_ascii_case(&entry));
+        let diff = IndexDiff::between(&before, &guard);
+        drop(guard);
+        app_state.invalidate_changes(&diff);
+    }
+
+    ui_state.undo.push(UndoEntry::Blacklist {
+        path: entry,
+        app_name: app_name.clone(),
+        recent: take_recent(app_state, &result_id),
+    });
+
+    refresh_app_index(app_state);
+    refresh_results(ui_state, app_state);
+    set_status_message(ui_state, fill(text.blacklisted, &[&app_name]));
+}
+
+/// Hides the selected app or bookmark from results by its id. Unlike the blacklist
+/// this works for bookmarks too and leaves the app index alone.
+fn hide_selected(ui_state: &mut TuiState, app_state: &AppState) {
+    let text = ui_state.text();
+    let Some(result) = ui_state
+        .list_state
+        .selected()
+        .and_then(|index| ui_state.results.get(index))
+        .cloned()
+    else {
+        set_status_message(ui_state, text.nothing_to_hide);
+        return;
+    };
+    if !matches!(
+        ui_state.pending_actions.get(&result.id),
+        Some(PendingAction::Application(_) | PendingAction::Bookmark(_))
+    ) {
+        set_status_message(ui_state, text.cannot_hide);
+        return;
     }
 
-    if let Ok(mut recent_guard) = app_state.recent_actions.lock() {
-        recent_guard.retain(|item| item.result.id != result_id);
+    let mut config = app_state.config.lock().unwrap();
+    if !config.hidden_results.contains(&result.id) {
+        config.hidden_results.push(result.id.clone());
     }
+    if config.save().is_err() {
+        set_status_message(ui_state, text.settings_not_saved);
+        return;
+    }
+    drop(config);
 
+    ui_state.undo.push(UndoEntry::Hide {
+        result_id: result.id.clone(),
+        title: result.title.clone(),
+        recent: take_recent(app_state, &result.id),
+    });
     if let Ok(mut cache_guard) = app_state.search_cache.lock() {
-        cache_guard.clear();
+        cache_guard.invalidate(CacheProvider::Config);
     }
-    refresh_app_index(app_state);
     refresh_results(ui_state, app_state);
-    set_status_message(ui_state, format!("Added to blacklist: {app_name}"));
+    set_status_message(ui_state, fill(text.hidden, &[&result.title]));
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-struct HotkeySpec {
-    modifiers: KeyModifiers,
-    code: KeyCode,
+/// Removes `result_id` from the recent list, returning it with its position for undo.
+fn take_recent(app_state: &AppState, result_id: &str) -> Option<(usize, Box<RecentEntry>)> {
+    let mut recent_guard = app_state.recent_actions.lock().ok()?;
+    let (index, entry) = recent_guard.take(result_id)?;
+    if let Err(err) = cache::save_recent_actions(&recent_guard) {
+        warn!("failed to save recent actions: {err}");
+    }
+    Some((index, Box::new(entry)))
 }
 
-fn parse_hotkey(input: &str) -> Option<HotkeySpec> {
-    let mut modifiers = KeyModifiers::empty();
-    let mut code = None;
+/// The builtin command bound to `key`, if any.
+fn shortcut_command(key: KeyEvent) -> Option<BuiltinCommand> {
+    BuiltinCommand::ALL.into_iter().find(|command| {
+        command
+            .shortcut()
+            .and_then(parse_hotkey)
+            .is_some_and(|spec| hotkey_matches(key, &spec))
+    })
+}
 
-    for token in input.split('+').map(|value| value.trim()) {
-        if token.is_empty() {
-            continue;
+/// Runs a command picked from the `egg` palette, then clears the query.
+fn run_builtin_command(command: BuiltinCommand, ui_state: &mut TuiState, app_state: &AppState) {
+    perform_builtin_command(command, ui_state, app_state);
+    if command == BuiltinCommand::Quit {
+        return;
+    }
+    ui_state.input.clear();
+    ui_state.cursor = 0;
+    refresh_results(ui_state, app_state);
+}
+
+fn perform_builtin_command(command: BuiltinCommand, ui_state: &mut TuiState, app_state: &AppState) {
+    match command {
+        BuiltinCommand::Quit => ui_state.should_quit = true,
+        BuiltinCommand::Reindex => {
+            let (sender, receiver) = mpsc::channel();
+            reindex_apps(app_state, move |step| {
+                let _ = sender.send(step);
+            });
+            ui_state.reindex_progress = Some(receiver);
+            refresh_bookmark_index(app_state);
+            set_status_message(ui_state, ui_state.text().reindexing);
         }
-        let token_upper = token.to_ascii_uppercase();
-        match token_upper.as_str() {
-            "ALT" => modifiers.insert(KeyModifiers::ALT),
-            "CTRL" | "CONTROL" => modifiers.insert(KeyModifiers::CONTROL),
-            "SHIFT" => modifiers.insert(KeyModifiers::SHIFT),
-            _ => {
-                if code.is_some() {
-                    return None;
+        BuiltinCommand::Reload => match AppConfig::try_load_checked() {
+            Ok((config, issues)) => {
+                ui_state.language = config.language;
+                logging::set_debug(config.debug_mode);
+                *app_state.config.lock().unwrap() = config;
+                if let Ok(mut cache_guard) = app_state.search_cache.lock() {
+                    cache_guard.invalidate(CacheProvider::Config);
                 }
-                code = parse_key_code(&token_upper);
+                let text = ui_state.text();
+                let message = if issues.is_empty() {
+                    text.settings_reloaded.to_string()
+                } else {
+                    let described: Vec<String> =
+                        issues.iter().map(|issue| issue.describe(text)).collect();
+                    fill(text.settings_reloaded_with_issues, &[&described.join(" ")])
+                };
+                set_status_message(ui_state, message);
+            }
+            Err(err) => {
+                let message = fill(ui_state.text().settings_not_reloaded, &[&err]);
+                set_status_message(ui_state, message);
             }
+        },
+        BuiltinCommand::Settings => {
+            open_settings_in_editor(app_state);
+            set_status_message(ui_state, ui_state.text().settings_opened);
         }
-    }
-
-    code.map(|code| HotkeySpec { modifiers, code })
-}
-
-fn parse_key_code(token: &str) -> Option<KeyCode> {
-    if token.len() == 1 {
-        let ch = token.chars().next().unwrap();
-        let normalized = ch.to_ascii_lowercase();
-        if normalized.is_ascii_alphanumeric() || normalized == ' ' {
-            return Some(KeyCode::Char(normalized));
+        BuiltinCommand::Stats => {
+            let cached_searches = app_state.search_cache.lock().map_or(0, |cache| cache.len());
+            let memory_limit_mb = app_state.config.lock().unwrap().memory_limit_mb;
+            let mut status = app_state.resources.lock().unwrap().clone();
+            // The guard polls every few seconds; read memory fresh so the first look is right.
+            if let Some(usage) = current_usage() {
+                status.usage = usage;
+            }
+            set_status_message(ui_state, status.summary(cached_searches, memory_limit_mb));
         }
-    }
-
-    match token {
-        "SPACE" => Some(KeyCode::Char(' ')),
-        "ENTER" | "RETURN" => Some(KeyCode::Enter),
-        "TAB" => Some(KeyCode::Tab),
-        "ESC" | "ESCAPE" => Some(KeyCode::Esc),
-        "BACKSPACE" => Some(KeyCode::Backspace),
-        "LEFT" => Some(KeyCode::Left),
-        "RIGHT" => Some(KeyCode::Right),
-        "UP" => Some(KeyCode::Up),
-        "DOWN" => Some(KeyCode::Down),
-        _ => None,
+        BuiltinCommand::Theme => open_theme_editor(ui_state, app_state),
+        BuiltinCommand::FullPaths => toggle_full_paths(ui_state, app_state),
+        BuiltinCommand::ToggleApps => toggle_provider(ui_state, app_state, SearchProvider::Apps),
+        BuiltinCommand::ToggleBookmarks => {
+            toggle_provider(ui_state, app_state, SearchProvider::Bookmarks)
+        }
+        BuiltinCommand::ToggleFiles => toggle_provider(ui_state, app_state, SearchProvider::Files),
+        BuiltinCommand::Undo => undo_last(ui_state, app_state),
+        BuiltinCommand::Profile => next_ranking_profile(ui_state, app_state),
     }
 }
 
-fn hotkey_matches(event: KeyEvent, spec: &HotkeySpec) -> bool {
-    let mut event_mods = event.modifiers;
-    let mut spec_mods = spec.modifiers;
-    let mut event_code = event.code;
-    let mut spec_code = spec.code;
-
-    if let (KeyCode::Char(event_char), KeyCode::Char(spec_char)) = (event.code, spec.code) {
-        event_mods.remove(KeyModifiers::SHIFT);
-        spec_mods.remove(KeyModifiers::SHIFT);
-        event_code = KeyCode::Char(event_char.to_ascii_lowercase());
-        spec_code = KeyCode::Char(spec_char.to_ascii_lowercase());
+fn next_ranking_profile(ui_state: &mut TuiState, app_state: &AppState) {
+    let mut config = app_state.config.lock().unwrap();
+    config.ranking_profile = config.ranking_profile.next();
+    let profile = config.ranking_profile;
+    let saved = config.save();
+    drop(config);
+    let text = ui_state.text();
+    match saved {
+        Ok(()) => set_status_message(ui_state, fill(text.ranking_profile, &[profile.label()])),
+        Err(_) => set_status_message(ui_state, text.settings_not_saved),
     }
-
-    event_mods == spec_mods && event_code == spec_code
 }
 
-fn refresh_app_index(app_state: &AppState) {
+fn refresh_bookmark_index(app_state: &AppState) {
     let refresh_state = app_state.clone();
     tokio::spawn(async move {
-        let exclusions = {
+        let (browsers, language) = {
             let config = refresh_state.config.lock().unwrap();
-            config.system_tool_exclusions.clone()
+            (config.bookmark_browsers.clone(), config.language)
         };
-        let refreshed = build_index(exclusions).await;
-        if refreshed.is_empty() {
+        let Ok(bookmarks) =
+            tokio::task::spawn_blocking(move || bookmarks::load_bookmarks(&browsers, language))
+                .await
+        else {
             return;
-        }
+        };
+        crate::store_bookmark_index(&refresh_state, bookmarks);
+    });
+}
 
-        let mut updated = false;
-        if let Ok(mut guard) = refresh_state.app_index.lock() {
-            if *guard != refreshed {
-                *guard = refreshed.clone();
-                updated = true;
-            }
-        }
+fn refresh_app_index(app_state: &AppState) {
+    reindex_apps(app_state, |_| {});
+}
 
-        if updated {
-            let _ = cache::save_app_index(&refreshed);
-            if let Ok(mut cache_guard) = refresh_state.search_cache.lock() {
-                cache_guard.clear();
-            }
-        }
+/// Rebuilds the app index in the background, reporting each step to `progress`.
+fn reindex_apps(app_state: &AppState, progress: impl Fn(IndexProgress) + Send + Sync + 'static) {
+    let refresh_state = app_state.clone();
+    tokio::spawn(async move {
+        let options = IndexOptions::from_config(&refresh_state.config.lock().unwrap());
+        let refreshed = build_index_with_progress(options, progress).await;
+        crate::store_app_index(&refresh_state, refreshed);
     });
 }
 
@@ -799,3 +2079,959 @@ fn slice_chars(input: &str, start: usize, end: usize) -> String {
     }
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, time::Duration};
+
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use ratatui::style::Color;
+
+    use super::{poll_reindex_progress, session::SessionSnapshot, session_outcome};
+    use crate::{
+        config::{ColorSupport, SuggestionEngine},
+        history::unix_now,
+        indexer::IndexProgress,
+        locale::Language,
+        models::{ActionVariant, ProcessInfo, SearchResult},
+        search_core::process_results,
+        state::{AppState, CacheProvider, PendingAction},
+        testing::{fixture_state, push_recent, TuiDriver},
+    };
+
+    #[test]
+    fn typing_filters_results_and_selects_first() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+
+        let titles = driver.result_titles();
+        assert_eq!(titles.first().map(String::as_str), Some("Google Chrome"));
+        assert_eq!(driver.selected_title().as_deref(), Some("Google Chrome"));
+        assert!(titles.last().unwrap().contains("Google"));
+    }
+
+    #[test]
+    fn remembered_queries_complete_as_ghost_text() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver
+            .app_state
+            .query_history
+            .lock()
+            .unwrap()
+            .record("google chrome");
+        driver.type_text("goo");
+        // Typed `goo` plus the ghost `gle chrome`; the result is titled `Google Chrome`.
+        assert!(driver.screen_contains("google chrome"));
+        assert_eq!(driver.ui_state.completion.as_deref(), Some("google chrome"));
+
+        driver.press(KeyCode::Left);
+        driver.render();
+        assert_eq!(driver.ui_state.completion, None);
+        driver.press(KeyCode::End);
+        driver.render();
+        driver.press(KeyCode::Tab);
+        assert_eq!(driver.ui_state.input, "google chrome");
+        assert_eq!(driver.ui_state.cursor, 13);
+        assert!(driver.ui_state.actions_menu.is_none());
+        driver.render();
+        assert_eq!(driver.ui_state.completion, None);
+    }
+
+    #[test]
+    fn alt_up_and_down_step_through_remembered_queries() {
+        let state = fixture_state();
+        state.config.lock().unwrap().dashboard_sections.clear();
+        let mut driver = TuiDriver::new(state);
+        driver
+            .app_state
+            .query_history
+            .lock()
+            .unwrap()
+            .restore(vec!["google chrome".to_string(), "notepad".to_string()]);
+        driver.type_text("vis");
+        for expected in ["google chrome", "notepad", "notepad"] {
+            driver.press_with(KeyCode::Up, KeyModifiers::ALT);
+            assert_eq!(driver.ui_state.input, expected);
+        }
+        assert_eq!(driver.selected_title().as_deref(), Some("Notepad"));
+        driver.press_with(KeyCode::Down, KeyModifiers::ALT);
+        assert_eq!(driver.ui_state.input, "google chrome");
+        driver.press_with(KeyCode::Down, KeyModifiers::ALT);
+        assert_eq!(driver.ui_state.input, "vis");
+        driver.press_with(KeyCode::Down, KeyModifiers::ALT);
+        assert_eq!(driver.ui_state.input, "vis");
+
+        // Without results, as on an empty home screen, plain Up recalls too; editing
+        // the recalled query ends the browse.
+        for _ in 0..3 {
+            driver.press(KeyCode::Backspace);
+        }
+        assert!(driver.ui_state.results.is_empty());
+        driver.press(KeyCode::Up);
+        assert_eq!(driver.ui_state.input, "google chrome");
+        driver.press(KeyCode::Backspace);
+        driver.press_with(KeyCode::Down, KeyModifiers::ALT);
+        assert_eq!(driver.ui_state.input, "google chrom");
+        driver.press(KeyCode::Down);
+        assert_eq!(driver.ui_state.input, "google chrom");
+    }
+
+    #[test]
+    fn alt_digits_launch_the_nth_result() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("no");
+        assert!(driver.screen_contains("Alt+1"));
+        assert!(driver.screen_contains("Alt+2"));
+        let second = driver.result_titles()[1].clone();
+
+        driver.press_with(KeyCode::Char('9'), KeyModifiers::ALT);
+        assert!(!driver.ui_state.should_quit);
+        assert_eq!(driver.ui_state.input, "no");
+        driver.press_with(KeyCode::Char('2'), KeyModifiers::ALT);
+        assert!(driver.ui_state.should_quit);
+        assert_eq!(
+            driver.ui_state.pending_result.map(|result| result.title),
+            Some(second)
+        );
+    }
+
+    #[test]
+    fn marked_results_launch_together() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("notepad");
+        driver.press_with(KeyCode::Char(' '), KeyModifiers::CONTROL);
+        assert_eq!(driver.ui_state.input, "notepad");
+        driver.press_with(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        driver.type_text("github");
+        driver.press_with(KeyCode::Char(' '), KeyModifiers::CONTROL);
+        assert!(driver.screen_contains("✓ GitHub"));
+        driver.press_with(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        driver.type_text("egg quit");
+        driver.press_with(KeyCode::Char(' '), KeyModifiers::CONTROL);
+        assert_eq!(driver.ui_state.marked.len(), 2);
+
+        // Esc drops the marks before it quits.
+        driver.press(KeyCode::Esc);
+        assert!(driver.ui_state.marked.is_empty());
+        assert!(!driver.ui_state.should_quit);
+
+        driver.press_with(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        driver.press_with(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        driver.type_text("notepad");
+        driver.press_with(KeyCode::Char(' '), KeyModifiers::CONTROL);
+        driver.press_with(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        driver.type_text("calculator");
+        driver.press_with(KeyCode::Char(' '), KeyModifiers::CONTROL);
+        driver.press(KeyCode::Enter);
+        assert!(driver.ui_state.should_quit);
+        assert!(driver.ui_state.pending_action.is_none());
+        let titles: Vec<&str> = driver
+            .ui_state
+            .pending_batch
+            .iter()
+            .map(|(result, _)| result.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Notepad", "Calculator"]);
+    }
+
+    #[test]
+    fn function_keys_switch_providers_for_the_session() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        assert!(driver
+            .result_titles()
+            .contains(&"Google Chrome".to_string()));
+
+        driver.press(KeyCode::F(3));
+        assert!(!driver
+            .result_titles()
+            .contains(&"Google Chrome".to_string()));
+        assert!(driver.screen_contains(" apps off "));
+        assert!(driver.app_state.config.lock().unwrap().enable_app_results);
+
+        driver.press(KeyCode::F(3));
+        assert!(driver
+            .result_titles()
+            .contains(&"Google Chrome".to_string()));
+        assert!(!driver.screen_contains(" apps off "));
+
+        driver.press(KeyCode::F(5));
+        assert_eq!(
+            driver.ui_state.status_message.as_deref(),
+            Some(
+                "File search is not set up; enable Everything or Windows Search in settings.json."
+            )
+        );
+    }
+
+    #[test]
+    fn enter_returns_selected_action_and_quits() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("notepad");
+        driver.press(KeyCode::Enter);
+
+        assert!(driver.ui_state.should_quit);
+        let Some(PendingAction::Application(app)) = driver.ui_state.pending_action.clone() else {
+            panic!("expected an application action");
+        };
+        assert_eq!(app.name, "Notepad");
+        assert_eq!(
+            driver
+                .ui_state
+                .pending_result
+                .as_ref()
+                .map(|r| r.title.as_str()),
+            Some("Notepad")
+        );
+    }
+
+    #[test]
+    fn selection_wraps_in_both_directions() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("no");
+        let len = driver.result_titles().len();
+        assert!(len > 1);
+
+        driver.press(KeyCode::Up);
+        assert_eq!(driver.ui_state.list_state.selected(), Some(len - 1));
+        driver.press(KeyCode::Down);
+        assert_eq!(driver.ui_state.list_state.selected(), Some(0));
+        driver.press_with(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        assert_eq!(driver.ui_state.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn ctrl_w_deletes_previous_word_and_refreshes() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("visual zzzz");
+        assert!(
+            driver
+                .ui_state
+                .results
+                .iter()
+                .all(|result| result.action_id == "search"),
+            "only web searches remain"
+        );
+
+        driver.press_with(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(driver.ui_state.input, "visual ");
+        assert_eq!(
+            driver.result_titles().first().map(String::as_str),
+            Some("Visual Studio Code")
+        );
+    }
+
+    #[test]
+    fn empty_query_shows_recent_entries() {
+        let state = fixture_state();
+        let app = state.app_index.lock().unwrap()[0].clone();
+        push_recent(
+            &state,
+            SearchResult {
+                id: format!("app-{}", app.id),
+                title: app.name.clone(),
+                subtitle: app.path.clone(),
+                score: 0,
+                action_id: "app".to_string(),
+                match_source: None,
+            },
+            PendingAction::Application(app),
+        );
+
+        let mut driver = TuiDriver::new(state);
+        assert_eq!(driver.result_titles(), vec!["Google Chrome".to_string()]);
+        assert!(driver.screen_contains("home: 1"));
+        assert!(driver.screen_contains("Recent"));
+    }
+
+    #[test]
+    fn hidden_results_leave_the_dashboard_and_only_items_can_be_hidden() {
+        let state = fixture_state();
+        let app = state.app_index.lock().unwrap()[0].clone();
+        push_recent(
+            &state,
+            SearchResult {
+                id: format!("app-{}", app.id),
+                title: app.name.clone(),
+                subtitle: app.path.clone(),
+                score: 0,
+                action_id: "app".to_string(),
+                match_source: None,
+            },
+            PendingAction::Application(app),
+        );
+        state.config.lock().unwrap().hidden_results = vec!["app-shell:chrome".to_string()];
+
+        let mut driver = TuiDriver::new(state);
+        assert!(driver.result_titles().is_empty());
+
+        driver.type_text("zzz");
+        driver.press_with(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(
+            driver.ui_state.status_message.as_deref(),
+            Some("Only apps and bookmarks can be hidden.")
+        );
+    }
+
+    #[test]
+    fn dashboard_adds_frequent_apps_below_recents() {
+        let state = fixture_state();
+        let app = state.app_index.lock().unwrap()[0].clone();
+        {
+            let mut history = state.launch_history.lock().unwrap();
+            let history = std::sync::Arc::make_mut(&mut history);
+            let now = unix_now();
+            history.record("app-shell:chrome", now);
+            history.record("app-shell:notepad", now);
+            history.record("app-shell:notepad", now);
+        }
+        push_recent(
+            &state,
+            SearchResult {
+                id: format!("app-{}", app.id),
+                title: app.name.clone(),
+                subtitle: app.path.clone(),
+                score: 0,
+                action_id: "app".to_string(),
+                match_source: None,
+            },
+            PendingAction::Application(app),
+        );
+
+        let mut driver = TuiDriver::new(state);
+        // Chrome is already listed under Recent, so Frequent only adds Notepad.
+        assert_eq!(
+            driver.result_titles(),
+            vec!["Google Chrome".to_string(), "Notepad".to_string()]
+        );
+        assert!(driver.screen_contains("Frequent apps"));
+
+        driver.press(KeyCode::Down);
+        assert_eq!(driver.selected_title().as_deref(), Some("Notepad"));
+    }
+
+    #[test]
+    fn escape_quits_without_action() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("code");
+        driver.press(KeyCode::Esc);
+        assert!(driver.ui_state.should_quit);
+        assert!(driver.ui_state.pending_action.is_none());
+    }
+
+    #[test]
+    fn blacklist_hotkey_rejects_bookmarks() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("github");
+        assert_eq!(driver.selected_title().as_deref(), Some("GitHub"));
+
+        driver.press_with(KeyCode::Char('b'), KeyModifiers::CONTROL);
+        assert_eq!(
+            driver.ui_state.status_message.as_deref(),
+            Some("Only apps can be blacklisted.")
+        );
+        assert!(driver.screen_contains("Only apps can be blacklisted."));
+    }
+
+    #[test]
+    fn reindex_progress_shows_in_the_status_line() {
+        let mut driver = TuiDriver::new(fixture_state());
+        let (sender, receiver) = mpsc::channel();
+        driver.ui_state.reindex_progress = Some(receiver);
+        sender.send(IndexProgress::Started).unwrap();
+        sender
+            .send(IndexProgress::Source {
+                source: "shell apps",
+                count: 120,
+                done: 3,
+                sources: 12,
+            })
+            .unwrap();
+        poll_reindex_progress(&mut driver.ui_state);
+        assert_eq!(
+            driver.ui_state.status_message.as_deref(),
+            Some("Reindexing 3/12: shell apps (120)")
+        );
+
+        sender
+            .send(IndexProgress::Finished {
+                total: 480,
+                duration: Duration::from_millis(1_250),
+            })
+            .unwrap();
+        poll_reindex_progress(&mut driver.ui_state);
+        assert_eq!(
+            driver.ui_state.status_message.as_deref(),
+            Some("Reindexed 480 apps in 1.2s.")
+        );
+        assert!(driver.ui_state.reindex_progress.is_none());
+    }
+
+    #[test]
+    fn pinned_results_show_a_marker() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("no");
+        assert!(!driver.screen_contains("★"));
+
+        driver.app_state.config.lock().unwrap().pinned_results =
+            vec!["bookmark-Edge 默认:notes".to_string()];
+        driver
+            .app_state
+            .search_cache
+            .lock()
+            .unwrap()
+            .invalidate(CacheProvider::Config);
+        driver.press(KeyCode::Backspace);
+        driver.type_text("o");
+        assert_eq!(driver.selected_title().as_deref(), Some("Meeting Notes"));
+        assert!(driver.screen_contains("★ Meeting Notes"));
+
+        driver.type_text(" zzz");
+        driver.press_with(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(
+            driver.ui_state.status_message.as_deref(),
+            Some("Only apps and bookmarks can be pinned.")
+        );
+    }
+
+    #[test]
+    fn egg_palette_lists_shortcuts_and_runs_ui_commands() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("egg");
+        assert!(driver.screen_contains("egg theme · Ctrl+T"));
+        assert!(driver.screen_contains("egg apps · F3"));
+
+        driver.type_text(" theme");
+        assert_eq!(driver.selected_title().as_deref(), Some("Edit Theme"));
+        driver.press(KeyCode::Enter);
+        assert!(driver.ui_state.theme_editor.is_some());
+        assert!(driver.ui_state.input.is_empty());
+        assert!(!driver.ui_state.should_quit);
+    }
+
+    #[test]
+    fn theme_editor_previews_and_discards_edits() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.app_state.config.lock().unwrap().theme.colors = ColorSupport::Truecolor;
+        driver.press_with(KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert!(driver.screen_contains(" Theme "));
+        assert!(driver.screen_contains("background"));
+
+        // Replace the background slot with a new color, one keystroke at a time.
+        for _ in 0..7 {
+            driver.press(KeyCode::Backspace);
+        }
+        driver.type_text("#000080");
+        let editor = driver.ui_state.theme_editor.as_ref().unwrap();
+        assert_eq!(editor.draft.background, "#000080");
+        assert_eq!(editor.theme().background, Color::Rgb(0, 0, 128));
+
+        driver.press(KeyCode::Down);
+        driver.type_text("zz");
+        assert!(driver.screen_contains("Not a color"));
+
+        driver.press(KeyCode::Esc);
+        assert!(driver.ui_state.theme_editor.is_none());
+        assert!(!driver.ui_state.should_quit);
+        // The slot was never set, so it still follows the preset.
+        assert!(driver
+            .app_state
+            .config
+            .lock()
+            .unwrap()
+            .theme
+            .background
+            .is_empty());
+    }
+
+    #[test]
+    fn renders_placeholder_and_result_rows() {
+        let mut driver = TuiDriver::new(AppState::new());
+        assert!(driver.screen_contains("Type to search..."));
+        assert!(driver.screen_contains("No recent items. Type to search."));
+
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("rust");
+        let screen = driver.render();
+        assert!(screen
+            .iter()
+            .any(|line| line.contains("> Rust Documentation")));
+        assert!(screen.iter().any(|line| line.contains("bookmark")));
+    }
+
+    #[test]
+    fn subtitles_switch_between_compact_and_full_paths() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        assert!(driver.screen_contains("chrome.exe · Application"));
+
+        driver.app_state.config.lock().unwrap().full_path_subtitles = true;
+        assert!(driver.screen_contains(r"C:\Program Files\Google\Chrome\Application\chrome.exe"));
+
+        // Narrow terminals cut the middle of the path but keep the file name.
+        let state = fixture_state();
+        state.config.lock().unwrap().full_path_subtitles = true;
+        let mut driver = TuiDriver::with_size(state, 40, 20);
+        driver.type_text("chrome");
+        assert!(driver.screen_contains(r"…\chrome.exe"));
+        assert!(!driver.screen_contains(r"Google\Chrome"));
+    }
+
+    #[test]
+    fn debug_mode_explains_the_selected_score() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        assert!(!driver.screen_contains(" Score "));
+
+        driver.app_state.config.lock().unwrap().debug_mode = true;
+        assert!(driver.screen_contains(" Score "));
+        assert!(driver.screen_contains("\"chrome\" → name \"Google Chrome\": fuzzy"));
+        assert!(driver.screen_contains("total "));
+    }
+
+    #[test]
+    fn header_shows_the_prefix_mode() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("b ");
+        assert!(driver.screen_contains("egg  search · bookmarks"));
+        driver.type_text("chrome");
+        assert!(driver
+            .result_titles()
+            .iter()
+            .all(|title| title != "Google Chrome"));
+
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        assert!(!driver.screen_contains("search ·"));
+    }
+
+    #[test]
+    fn picking_a_suggestion_replaces_the_query() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrmoe");
+        assert!(driver.screen_contains("did you mean"));
+        driver.press(KeyCode::Enter);
+        assert!(!driver.ui_state.should_quit);
+        assert_eq!(driver.ui_state.input, "chrome");
+        assert_eq!(driver.ui_state.cursor, 6);
+        assert_eq!(driver.selected_title().as_deref(), Some("Google Chrome"));
+    }
+
+    #[test]
+    fn question_mark_lists_operators_and_bad_prefixes_are_flagged() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("?");
+        assert_eq!(driver.selected_title().as_deref(), Some("a <text>"));
+        driver.press(KeyCode::Down);
+        driver.press(KeyCode::Enter);
+        assert!(!driver.ui_state.should_quit);
+        assert_eq!(driver.ui_state.input, "b ");
+
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("xy: chrome");
+        assert!(driver.screen_contains("unknown prefix 'xy:'"));
+    }
+
+    #[test]
+    fn session_outcome_reports_misses_and_launch_position() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("qqqzzz");
+        let outcome = session_outcome(&driver.ui_state).unwrap();
+        assert_eq!(outcome.local_results, 0);
+        assert!(outcome.launched.is_none());
+
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("no");
+        driver.press(KeyCode::Down);
+        driver.press(KeyCode::Enter);
+        let outcome = session_outcome(&driver.ui_state).unwrap();
+        assert!(outcome.local_results > 1);
+        assert_eq!(outcome.launched.map(|(position, ..)| position), Some(1));
+
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("> dir");
+        assert!(session_outcome(&driver.ui_state).is_none());
+    }
+
+    #[test]
+    fn tab_opens_actions_menu_and_enter_picks_a_variant() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        driver.press(KeyCode::Tab);
+        assert!(driver.screen_contains("Run as administrator"));
+        assert!(driver.screen_contains("Copy path"));
+
+        driver.press(KeyCode::Down);
+        driver.press(KeyCode::Enter);
+        assert!(driver.ui_state.should_quit);
+        assert_eq!(driver.ui_state.pending_variant, ActionVariant::RunAsAdmin);
+        assert_eq!(
+            driver
+                .ui_state
+                .pending_result
+                .as_ref()
+                .map(|result| result.title.as_str()),
+            Some("Google Chrome")
+        );
+    }
+
+    #[test]
+    fn ctrl_enter_runs_elevated_only_where_supported() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("calculator");
+        assert!(!driver.screen_contains("Ctrl+Enter"));
+        driver.press_with(KeyCode::Enter, KeyModifiers::CONTROL);
+        assert!(!driver.ui_state.should_quit);
+
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        assert!(driver.screen_contains("Ctrl+Enter: run as admin"));
+        driver.press_with(KeyCode::Enter, KeyModifiers::CONTROL);
+        assert!(driver.ui_state.should_quit);
+        assert_eq!(driver.ui_state.pending_variant, ActionVariant::RunAsAdmin);
+    }
+
+    #[test]
+    fn escape_closes_actions_menu_without_quitting() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        driver.press_with(KeyCode::Enter, KeyModifiers::SHIFT);
+        assert!(driver.ui_state.actions_menu.is_some());
+
+        driver.press(KeyCode::Esc);
+        assert!(driver.ui_state.actions_menu.is_none());
+        assert!(!driver.ui_state.should_quit);
+    }
+
+    #[test]
+    fn actions_menu_edits_launch_options_in_two_steps() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        driver.press(KeyCode::Tab);
+        driver.press(KeyCode::Up);
+        driver.press(KeyCode::Enter);
+        assert!(!driver.ui_state.should_quit);
+        assert!(driver.screen_contains("Launch options · Google Chrome"));
+
+        driver.type_text("--incognito");
+        assert!(driver.screen_contains("--incognito_"));
+        driver.press(KeyCode::Enter);
+        driver.type_text(r"C:\Temp");
+        assert!(driver.screen_contains(r"C:\Temp_"));
+        assert!(driver.screen_contains("Enter: save"));
+
+        driver.press(KeyCode::Esc);
+        assert!(driver.ui_state.launch_options.is_none());
+        assert!(!driver.ui_state.should_quit);
+        assert!(driver
+            .app_state
+            .config
+            .lock()
+            .unwrap()
+            .launch_overrides
+            .is_empty());
+    }
+
+    #[test]
+    fn actions_menu_opens_alias_prompt() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("chrome");
+        driver.press(KeyCode::Tab);
+        driver.press(KeyCode::Up);
+        driver.press(KeyCode::Up);
+        driver.press(KeyCode::Enter);
+        assert!(driver.screen_contains("Alias · Google Chrome"));
+
+        driver.type_text("g c");
+        assert!(driver.screen_contains("gc_"));
+        driver.press(KeyCode::Esc);
+        assert!(driver.ui_state.alias_editor.is_none());
+        assert!(!driver.ui_state.should_quit);
+        assert!(driver.app_state.config.lock().unwrap().aliases.is_empty());
+    }
+
+    #[test]
+    fn known_page_titles_are_shown_when_enabled() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.ui_state.page_titles.cache.insert(
+            "https://example.com".to_string(),
+            Some("Example Domain".to_string()),
+        );
+        driver.type_text("example.com");
+        assert!(!driver.screen_contains("Example Domain"));
+
+        driver.app_state.config.lock().unwrap().fetch_page_titles = true;
+        assert!(driver.screen_contains("Example Domain · example.com"));
+    }
+
+    #[test]
+    fn web_suggestions_are_listed_right_under_the_web_search_row() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.app_state.config.lock().unwrap().web_suggestions = true;
+        driver.ui_state.web_suggestions.cache.insert(
+            (SuggestionEngine::DuckDuckGo, "zzqx".to_string()),
+            vec!["zzqx meaning".to_string()],
+        );
+        driver.type_text("zzqx");
+        assert!(driver.screen_contains("DuckDuckGo suggestion"));
+        let results = &driver.ui_state.results;
+        let web_row = results
+            .iter()
+            .rposition(|result| result.id.starts_with("search-"))
+            .unwrap();
+        let row = &results[web_row + 1];
+        assert_eq!(row.title, "zzqx meaning");
+        assert!(matches!(
+            &driver.ui_state.pending_actions[&row.id],
+            PendingAction::Search(url) if url == "https://duckduckgo.com/?q=zzqx%20meaning"
+        ));
+    }
+
+    #[test]
+    fn ctrl_z_reports_when_there_is_nothing_to_undo() {
+        let mut driver = TuiDriver::new(fixture_state());
+        assert!(!driver.screen_contains("Ctrl+Z"));
+        driver.press_with(KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert_eq!(
+            driver.ui_state.status_message.as_deref(),
+            Some("Nothing to undo.")
+        );
+    }
+
+    #[test]
+    fn unfinished_session_is_restored_with_ctrl_r() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("no");
+        driver.press(KeyCode::Down);
+        driver.press(KeyCode::Tab);
+        driver.press(KeyCode::Up);
+        driver.press(KeyCode::Enter);
+        driver.type_text("--new-window");
+        let snapshot = SessionSnapshot::capture(&driver.ui_state);
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SessionSnapshot>(&json).unwrap(),
+            snapshot
+        );
+
+        let mut restored = TuiDriver::new(fixture_state());
+        restored.ui_state.session_offer = Some(snapshot.clone());
+        assert!(restored.screen_contains("Ctrl+R: restore the session that crashed"));
+        restored.press_with(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert!(restored.ui_state.session_offer.is_none());
+        assert_eq!(restored.ui_state.input, "no");
+        assert_eq!(restored.ui_state.list_state.selected(), Some(1));
+        assert_eq!(restored.ui_state.launch_options, snapshot.launch_options);
+        assert!(restored.screen_contains("--new-window_"));
+
+        let mut dismissed = TuiDriver::new(fixture_state());
+        dismissed.ui_state.session_offer = Some(snapshot);
+        dismissed.type_text("x");
+        assert!(dismissed.ui_state.session_offer.is_none());
+        assert_eq!(dismissed.ui_state.input, "x");
+    }
+
+    #[test]
+    fn file_results_open_a_preview_on_wide_terminals() {
+        let file_result = |driver: &mut TuiDriver| {
+            let result = SearchResult {
+                id: "file-notes".to_string(),
+                title: "notes.txt".to_string(),
+                subtitle: "C:/missing/notes.txt".to_string(),
+                score: 0,
+                action_id: "file".to_string(),
+                match_source: None,
+            };
+            driver.ui_state.pending_actions.insert(
+                result.id.clone(),
+                PendingAction::File("C:/missing/notes.txt".to_string()),
+            );
+            driver.ui_state.results = vec![result];
+            driver.ui_state.list_state.select(Some(0));
+        };
+
+        let mut wide = TuiDriver::with_size(fixture_state(), 120, 30);
+        file_result(&mut wide);
+        assert!(wide.screen_contains(" notes.txt "));
+        assert!(wide.screen_contains("Cannot preview:"));
+        assert!(wide.ui_state.preview.is_open());
+
+        let mut narrow = TuiDriver::new(fixture_state());
+        file_result(&mut narrow);
+        assert!(!narrow.screen_contains("Cannot preview:"));
+        assert!(!narrow.ui_state.preview.is_open());
+    }
+
+    #[test]
+    fn builtin_quit_runs_in_process_without_pending_action() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("egg quit");
+        assert_eq!(driver.selected_title().as_deref(), Some("Quit egg"));
+        driver.press(KeyCode::Enter);
+        assert!(driver.ui_state.should_quit);
+        assert!(driver.ui_state.pending_action.is_none());
+    }
+
+    #[test]
+    fn chinese_language_setting_translates_the_screen() {
+        let state = fixture_state();
+        state.config.lock().unwrap().language = Language::Zh;
+        let mut driver = TuiDriver::new(state);
+        assert!(driver.screen_contains("输入以搜索"));
+
+        driver.type_text("egg quit");
+        assert_eq!(driver.selected_title().as_deref(), Some("退出"));
+        assert!(driver.screen_contains("结果"));
+        driver.press_with(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(
+            driver.ui_state.status_message.as_deref(),
+            Some("只能隐藏应用和收藏夹。")
+        );
+    }
+
+    #[test]
+    fn conversions_show_an_answer_card_above_the_results() {
+        let mut driver = TuiDriver::new(fixture_state());
+        driver.type_text("15kg to lb");
+        assert!(driver.screen_contains("15 kg = 33.0693 lb"));
+        assert!(driver.screen_contains("Ctrl+Y: copy answer"));
+
+        driver.type_text(" x");
+        assert!(!driver.screen_contains("Answer"));
+
+        driver.press_with(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        driver.app_state.config.lock().unwrap().instant_answers = false;
+        assert!(!driver.screen_contains("33.0693"));
+    }
+
+    #[test]
+    fn ending_an_elevated_process_takes_a_second_enter() {
+        let mut driver = TuiDriver::new(fixture_state());
+        let processes = [ProcessInfo {
+            pid: 96,
+            name: "svchost.exe".to_string(),
+            elevated: true,
+        }];
+        let config = driver.app_state.config.lock().unwrap().clone();
+        let (results, pending_actions) = process_results(&processes, "", &config);
+        driver.ui_state.results = results;
+        driver.ui_state.pending_actions = pending_actions;
+        driver.ui_state.list_state.select(Some(0));
+
+        driver.press(KeyCode::Enter);
+        assert!(driver.ui_state.pending_action.is_none());
+        assert!(driver.screen_contains("svchost.exe runs as administrator"));
+        // Any other key calls it off.
+        driver.press(KeyCode::Down);
+        driver.press(KeyCode::Enter);
+        assert!(driver.ui_state.pending_action.is_none());
+
+        driver.press(KeyCode::Enter);
+        assert!(driver.ui_state.should_quit);
+        assert!(matches!(
+            driver.ui_state.pending_action,
+            Some(PendingAction::KillProcess { pid: 96, .. })
+        ));
+    }
+
+    #[test]
+    fn page_keys_scroll_long_result_lists() {
+        let mut driver = TuiDriver::with_size(fixture_state(), 80, 20);
+        driver.ui_state.results = (1..=25)
+            .map(|number| SearchResult {
+                id: format!("result-{number}"),
+                title: format!("Result {number:02}"),
+                subtitle: String::new(),
+                score: 0,
+                action_id: "app".to_string(),
+                match_source: None,
+            })
+            .collect();
+        driver.ui_state.list_state.select(Some(0));
+        assert!(driver.screen_contains(" 1/25 "));
+        let page = driver.ui_state.page_size;
+        assert!(page > 1 && page < 25);
+
+        driver.press(KeyCode::PageDown);
+        let title = format!("Result {:02}", page + 1);
+        assert_eq!(driver.selected_title().as_deref(), Some(title.as_str()));
+        assert!(driver.screen_contains(&title));
+        assert!(driver.screen_contains(&format!(" {}/25 ", page + 1)));
+
+        // Paging stops at either end instead of wrapping around.
+        for _ in 0..5 {
+            driver.press(KeyCode::PageDown);
+        }
+        assert_eq!(driver.selected_title().as_deref(), Some("Result 25"));
+        assert!(driver.screen_contains("Result 25"));
+        for _ in 0..5 {
+            driver.press(KeyCode::PageUp);
+        }
+        assert_eq!(driver.selected_title().as_deref(), Some("Result 01"));
+    }
+
+    #[test]
+    fn split_panes_switch_with_tab_and_launch_selection() {
+        let state = fixture_state();
+        state.config.lock().unwrap().split_panes = true;
+        let mut driver = TuiDriver::with_size(state, 120, 30);
+        driver.type_text("no");
+        let screen = driver.render();
+        assert!(screen
+            .iter()
+            .any(|line| line.contains("Apps") && line.contains("Bookmarks & Files")));
+
+        let first = driver.selected_title().unwrap();
+        driver.press(KeyCode::Down);
+        let second = driver.selected_title().unwrap();
+        assert_ne!(first, second);
+        assert!(["Notepad", "Notion"].contains(&second.as_str()));
+
+        driver.press(KeyCode::Tab);
+        assert_eq!(driver.selected_title().as_deref(), Some("Meeting Notes"));
+        driver.press(KeyCode::Tab);
+        assert_eq!(driver.selected_title(), Some(second));
+
+        driver.press(KeyCode::Tab);
+        driver.press(KeyCode::Enter);
+        assert!(matches!(
+            driver.ui_state.pending_action,
+            Some(PendingAction::Bookmark(_))
+        ));
+    }
+
+    #[test]
+    fn grouped_results_list_a_header_above_each_category() {
+        let state = fixture_state();
+        state.config.lock().unwrap().group_results = true;
+        let mut driver = TuiDriver::with_size(state, 100, 30);
+        driver.type_text("no");
+        let screen = driver.render();
+        let row = |text: &str| {
+            screen
+                .iter()
+                .position(|line| line.trim_matches(|ch: char| !ch.is_alphanumeric()) == text)
+        };
+        let (apps, bookmarks, web) = (row("Applications"), row("Bookmarks"), row("Web"));
+        assert!(apps.is_some() && bookmarks.is_some() && web.is_some());
+        assert!(apps < bookmarks && bookmarks < web);
+
+        // Headers take no selection: Down moves from result to result.
+        let titles = driver.result_titles();
+        let notes = titles.iter().position(|title| title == "Meeting Notes");
+        for _ in 0..notes.unwrap() {
+            driver.press(KeyCode::Down);
+        }
+        assert_eq!(driver.selected_title().as_deref(), Some("Meeting Notes"));
+    }
+
+    #[test]
+    fn split_panes_fall_back_on_narrow_terminals() {
+        let state = fixture_state();
+        state.config.lock().unwrap().split_panes = true;
+        let mut driver = TuiDriver::new(state);
+        driver.type_text("no");
+        assert!(!driver.screen_contains("Bookmarks & Files"));
+        assert!(!driver.ui_state.split.enabled);
+    }
+}
@@ -0,0 +1,172 @@
+//! Secondary actions for the selected result, opened with Tab or Shift+Enter.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState},
+};
+
+use super::{
+    alias_editor::open_alias_editor, confirmed, finish_pending_search,
+    launch_options::open_launch_options, set_status_message, TuiState,
+};
+use crate::{
+    execute::action_variants,
+    locale::Messages,
+    models::{ActionVariant, SearchResult},
+    state::{AppState, PendingAction},
+    theme::Theme,
+};
+
+pub(crate) struct ActionsMenu {
+    result: SearchResult,
+    action: PendingAction,
+    variants: Vec<ActionVariant>,
+    selected: usize,
+}
+
+impl ActionsMenu {
+    fn select(&mut self, delta: isize) {
+        let len = self.variants.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+    }
+}
+
+pub(super) fn open_actions_menu(ui_state: &mut TuiState, app_state: &AppState) {
+    if ui_state.searching {
+        finish_pending_search(ui_state, app_state);
+    }
+    let Some(result) = ui_state
+        .list_state
+        .selected()
+        .and_then(|index| ui_state.results.get(index))
+        .cloned()
+    else {
+        return;
+    };
+    let Some(action) = ui_state.pending_actions.get(&result.id).cloned() else {
+        return;
+    };
+    let variants = action_variants(&action);
+    if variants.len() < 2 {
+        set_status_message(ui_state, ui_state.text().no_other_actions);
+        return;
+    }
+    ui_state.actions_menu = Some(ActionsMenu {
+        result,
+        action,
+        variants,
+        selected: 0,
+    });
+}
+
+pub(super) fn handle_actions_menu_key_event(
+    key: KeyEvent,
+    ui_state: &mut TuiState,
+    app_state: &AppState,
+) {
+    let Some(menu) = ui_state.actions_menu.as_mut() else {
+        return;
+    };
+
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('c') => ui_state.should_quit = true,
+            KeyCode::Char('n') => menu.select(1),
+            KeyCode::Char('p') => menu.select(-1),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Tab | KeyCode::BackTab => ui_state.actions_menu = None,
+        KeyCode::Up => menu.select(-1),
+        KeyCode::Down => menu.select(1),
+        KeyCode::Enter => {
+            let Some(menu) = ui_state.actions_menu.take() else {
+                return;
+            };
+            let variant = menu.variants[menu.selected];
+            match (variant, &menu.action) {
+                (ActionVariant::EditLaunchOptions, PendingAction::Application(app)) => {
+                    open_launch_options(ui_state, app_state, app);
+                    return;
+                }
+                (ActionVariant::EditAlias, PendingAction::Application(app)) => {
+                    open_alias_editor(ui_state, app_state, app);
+                    return;
+                }
+                (ActionVariant::EditLaunchOptions | ActionVariant::EditAlias, _) => return,
+                _ => {}
+            }
+            if !confirmed(ui_state, &menu.result, &menu.action) {
+                return;
+            }
+            ui_state.pending_variant = variant;
+            ui_state.pending_action = Some(menu.action);
+            ui_state.pending_result = Some(menu.result);
+            ui_state.should_quit = true;
+        }
+        _ => {}
+    }
+}
+
+/// Draws the menu as a popup over the bottom-right of `area`.
+pub(super) fn render_actions_menu(
+    frame: &mut Frame,
+    area: Rect,
+    menu: &ActionsMenu,
+    theme: Theme,
+    text: &Messages,
+) {
+    let title = format!(" {} ", menu.result.title);
+    let content_width = menu
+        .variants
+        .iter()
+        .map(|variant| Span::raw(variant.label(text)).width() + 2)
+        .chain(Some(Span::raw(title.as_str()).width()))
+        .max()
+        .unwrap_or_default() as u16;
+    let width = (content_width + 2).min(area.width);
+    let height = (menu.variants.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + area.width - width,
+        y: area.y + area.height - height,
+        width,
+        height,
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.surface))
+        .title(Span::styled(
+            title,
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        ));
+    let items: Vec<ListItem> = menu
+        .variants
+        .iter()
+        .map(|variant| {
+            ListItem::new(Span::styled(
+                variant.label(text),
+                Style::default().fg(theme.text),
+            ))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight_fg)
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(menu.selected));
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut list_state);
+}
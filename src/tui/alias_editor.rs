@@ -0,0 +1,172 @@
+//! Prompt for an app's alias, opened from the actions menu. An app has at most one
+//! alias here; command aliases are edited in `settings.json`.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+use super::{pad_label, set_status_message, TuiState, UndoEntry};
+use crate::{
+    config::AliasTarget,
+    locale::{fill, Messages},
+    models::ApplicationInfo,
+    state::{AppState, CacheProvider},
+    theme::Theme,
+};
+
+const MAX_ALIAS_LEN: usize = 32;
+
+pub(crate) struct AliasEditor {
+    app_id: String,
+    app_name: String,
+    alias: String,
+}
+
+/// Opens the prompt prefilled with the app's current alias.
+pub(super) fn open_alias_editor(
+    ui_state: &mut TuiState,
+    app_state: &AppState,
+    app: &ApplicationInfo,
+) {
+    let config = app_state.config.lock().unwrap();
+    let alias = config
+        .aliases
+        .iter()
+        .find(|(_, target)| **target == AliasTarget::App(app.id.clone()))
+        .map(|(name, _)| name.clone())
+        .unwrap_or_default();
+    ui_state.alias_editor = Some(AliasEditor {
+        app_id: app.id.clone(),
+        app_name: app.name.clone(),
+        alias,
+    });
+}
+
+pub(super) fn handle_alias_editor_key_event(
+    key: KeyEvent,
+    ui_state: &mut TuiState,
+    app_state: &AppState,
+) {
+    let Some(editor) = ui_state.alias_editor.as_mut() else {
+        return;
+    };
+
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('c') => ui_state.should_quit = true,
+            KeyCode::Char('u') => editor.alias.clear(),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc => {
+            ui_state.alias_editor = None;
+            set_status_message(ui_state, ui_state.text().alias_unchanged);
+        }
+        KeyCode::Enter => save_alias(ui_state, app_state),
+        KeyCode::Backspace => {
+            editor.alias.pop();
+        }
+        // Aliases are typed as the whole query, so they stay one word.
+        KeyCode::Char(ch)
+            if !ch.is_whitespace() && editor.alias.chars().count() < MAX_ALIAS_LEN =>
+        {
+            editor.alias.push(ch);
+        }
+        _ => {}
+    }
+}
+
+/// Replaces the app's alias. A name already used by something else moves to this app.
+fn save_alias(ui_state: &mut TuiState, app_state: &AppState) {
+    let Some(editor) = ui_state.alias_editor.take() else {
+        return;
+    };
+    let text = ui_state.text();
+    let target = AliasTarget::App(editor.app_id.clone());
+    let alias = editor.alias.trim().to_string();
+
+    let mut config = app_state.config.lock().unwrap();
+    let before = config.aliases.clone();
+    config
+        .aliases
+        .retain(|name, existing| *existing != target && !name.trim().eq_ignore_ascii_case(&alias));
+    if !alias.is_empty() {
+        config.aliases.insert(alias.clone(), target);
+    }
+    if config.aliases == before {
+        drop(config);
+        set_status_message(ui_state, text.alias_unchanged);
+        return;
+    }
+    let saved = config.save();
+    drop(config);
+    if saved.is_err() {
+        set_status_message(ui_state, text.settings_not_saved);
+        return;
+    }
+    if let Ok(mut cache_guard) = app_state.search_cache.lock() {
+        cache_guard.invalidate(CacheProvider::Config);
+    }
+    ui_state.undo.push(UndoEntry::Aliases {
+        app_name: editor.app_name.clone(),
+        previous: before,
+    });
+    let message = if alias.is_empty() {
+        fill(text.alias_removed, &[&editor.app_name])
+    } else {
+        fill(text.alias_saved, &[&alias, &editor.app_name])
+    };
+    set_status_message(ui_state, message);
+}
+
+/// Draws the prompt as a popup across the bottom of `area`.
+pub(super) fn render_alias_editor(
+    frame: &mut Frame,
+    area: Rect,
+    editor: &AliasEditor,
+    theme: Theme,
+    text: &Messages,
+) {
+    let height = 5.min(area.height);
+    let popup = Rect {
+        x: area.x,
+        y: area.y + area.height - height,
+        width: area.width,
+        height,
+    };
+    let prompt = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled(
+                pad_label(text.alias_label, 14),
+                Style::default().fg(theme.accent),
+            ),
+            Span::styled(
+                format!("{}_", editor.alias),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::default(),
+        Line::from(Span::styled(
+            text.alias_keys,
+            Style::default().fg(theme.dim),
+        )),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.accent))
+            .style(Style::default().bg(theme.surface))
+            .title(Span::styled(
+                format!(" {} ", fill(text.alias_title, &[&editor.app_name])),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            )),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(prompt, popup);
+}
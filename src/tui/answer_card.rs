@@ -0,0 +1,100 @@
+//! The instant answer card above the results: what the input converts to, with
+//! Ctrl+Y copying it. The answer is worked out again every frame, so currency
+//! answers appear once their rates arrive and `now in ...` keeps time.
+
+use chrono::Utc;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+
+use super::{set_status_message, TuiState};
+use crate::{
+    answers::{Answer, AnswerContext},
+    config::AppConfig,
+    locale::{fill, Messages},
+    theme::Theme,
+};
+
+#[cfg(unix)]
+use crate::unix_utils::copy_to_clipboard;
+#[cfg(windows)]
+use crate::windows_utils::copy_to_clipboard;
+
+/// Borders plus the answer and note lines.
+pub(crate) const CARD_HEIGHT: u16 = 4;
+
+/// Updates `ui_state.answer` for the current input.
+pub(crate) fn sync_answer(ui_state: &mut TuiState, config: &AppConfig) {
+    if !config.instant_answers || ui_state.theme_editor.is_some() {
+        ui_state.answer = None;
+        return;
+    }
+    let context = AnswerContext {
+        text: ui_state.text(),
+        now: Utc::now(),
+        fetch_rates: config.fetch_exchange_rates,
+    };
+    ui_state.answer = ui_state.answers.answer(&ui_state.input, &context);
+}
+
+pub(crate) fn render_answer_card(
+    frame: &mut Frame,
+    area: Rect,
+    answer: &Answer,
+    theme: Theme,
+    text: &Messages,
+) {
+    let headline = match &answer.value {
+        Some(value) => Line::from(vec![
+            Span::styled(
+                format!("{} = ", answer.question),
+                Style::default().fg(theme.text),
+            ),
+            Span::styled(
+                value.as_str(),
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        None => Line::from(Span::styled(
+            answer.question.as_str(),
+            Style::default().fg(theme.text),
+        )),
+    };
+    let note = Line::from(Span::styled(
+        answer.note.as_deref().unwrap_or_default(),
+        Style::default().fg(theme.dim),
+    ));
+    let card = Paragraph::new(vec![headline, note])
+        .style(Style::default().bg(theme.surface))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.accent))
+                .style(Style::default().bg(theme.surface))
+                .title(Span::styled(
+                    format!(" {} ", text.answer_title),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                )),
+        );
+    frame.render_widget(card, area);
+}
+
+/// Ctrl+Y: puts the answer on the clipboard.
+pub(crate) fn copy_answer(ui_state: &mut TuiState) {
+    let Some(value) = ui_state
+        .answer
+        .as_ref()
+        .and_then(|answer| answer.value.clone())
+    else {
+        return;
+    };
+    let text = ui_state.text();
+    match copy_to_clipboard(&value) {
+        Ok(()) => set_status_message(ui_state, fill(text.answer_copied, &[&value])),
+        Err(err) => set_status_message(ui_state, fill(text.answer_not_copied, &[&err])),
+    }
+}
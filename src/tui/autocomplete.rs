@@ -0,0 +1,103 @@
+//! Remembered queries in the search box. Ghost text after the input completes it
+//! to the newest remembered query it starts; it shows only with the cursor at the
+//! end of the input, where Right or Tab accepts it. Alt+Up and Alt+Down (plain Up
+//! and Down while there are no results) step through the queries like shell
+//! history. Queries are remembered when they lead to a launch.
+
+use super::{refresh_results, TuiState};
+use crate::{cache, state::AppState};
+
+/// Updates `ui_state.completion` for the current input.
+pub(crate) fn sync_completion(ui_state: &mut TuiState, app_state: &AppState, enabled: bool) {
+    let at_end = ui_state.cursor == ui_state.input.chars().count();
+    ui_state.completion = if enabled && at_end && ui_state.theme_editor.is_none() {
+        let history = app_state.query_history.lock().unwrap();
+        history.complete(&ui_state.input).map(str::to_string)
+    } else {
+        None
+    };
+}
+
+/// What the completion adds after the input.
+pub(crate) fn completion_suffix(ui_state: &TuiState) -> Option<String> {
+    let completion = ui_state.completion.as_ref()?;
+    Some(
+        completion
+            .chars()
+            .skip(ui_state.input.chars().count())
+            .collect(),
+    )
+}
+
+/// Right or Tab: replaces the input with the completion. Returns `false`, leaving
+/// the key to its usual job, when there is none.
+pub(crate) fn accept_completion(ui_state: &mut TuiState, app_state: &AppState) -> bool {
+    let Some(completion) = ui_state.completion.take() else {
+        return false;
+    };
+    ui_state.cursor = completion.chars().count();
+    ui_state.input = completion;
+    refresh_results(ui_state, app_state);
+    true
+}
+
+/// Where Alt+Up and Alt+Down are in the remembered queries.
+#[derive(Debug)]
+pub(crate) struct HistoryBrowse {
+    /// 0 is the newest query.
+    position: usize,
+    /// The input before browsing, put back when stepping past the newest query.
+    draft: String,
+}
+
+/// Steps to an older (`delta > 0`) or newer remembered query and puts it in the
+/// input. Editing the recalled query ends the browse; the next step starts over
+/// from the newest query.
+pub(crate) fn recall_query(ui_state: &mut TuiState, app_state: &AppState, delta: isize) {
+    let browse = ui_state
+        .history_browse
+        .take()
+        .filter(|browse| recalled(app_state, browse.position).as_deref() == Some(&ui_state.input));
+    let draft = browse
+        .as_ref()
+        .map_or_else(|| ui_state.input.clone(), |browse| browse.draft.clone());
+    let next = match browse.as_ref().map(|browse| browse.position) {
+        None if delta < 0 => return,
+        None => Some(0),
+        Some(position) if delta > 0 => Some(position + 1),
+        Some(position) => position.checked_sub(1),
+    };
+    let input = match next {
+        // Down from the newest query: back to what was typed.
+        None => draft,
+        Some(position) => match recalled(app_state, position) {
+            Some(query) => {
+                ui_state.history_browse = Some(HistoryBrowse { position, draft });
+                query
+            }
+            // Up from the oldest query, or with none remembered: stay put.
+            None => {
+                ui_state.history_browse = browse;
+                return;
+            }
+        },
+    };
+    ui_state.cursor = input.chars().count();
+    ui_state.input = input;
+    refresh_results(ui_state, app_state);
+}
+
+fn recalled(app_state: &AppState, position: usize) -> Option<String> {
+    let history = app_state.query_history.lock().unwrap();
+    let query = history.items().nth(position).cloned();
+    query
+}
+
+/// Remembers the query of a launch and saves the history.
+pub(crate) fn remember_query(app_state: &AppState, query: &str) {
+    let mut history = app_state.query_history.lock().unwrap();
+    history.record(query);
+    if let Err(err) = cache::save_query_history(&history) {
+        log::warn!("failed to save query history: {err}");
+    }
+}
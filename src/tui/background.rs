@@ -0,0 +1,70 @@
+//! Work the event loop hands to blocking tasks, such as reading a previewed
+//! file or fetching a page title. What they load is sent back over a channel
+//! and picked up on a later frame.
+
+use std::{
+    sync::mpsc::{self, Receiver, Sender, TryIter},
+    time::Duration,
+};
+
+pub(crate) struct BackgroundLoader<T> {
+    /// Set once the event loop runs inside the tokio runtime; until then, as in
+    /// tests, nothing is loaded in the background.
+    pub(crate) enabled: bool,
+    loaded_tx: Sender<T>,
+    loaded_rx: Receiver<T>,
+}
+
+impl<T> Default for BackgroundLoader<T> {
+    fn default() -> Self {
+        let (loaded_tx, loaded_rx) = mpsc::channel();
+        Self {
+            enabled: false,
+            loaded_tx,
+            loaded_rx,
+        }
+    }
+}
+
+impl<T: Send + 'static> BackgroundLoader<T> {
+    /// Runs `load` on a blocking task; returns whether it was started.
+    pub(crate) fn load(&self, load: impl FnOnce() -> T + Send + 'static) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let loaded_tx = self.loaded_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let _ = loaded_tx.send(load());
+        });
+        true
+    }
+
+    /// Runs `load` on a blocking task once `delay` has passed, unless `wanted`
+    /// says by then that it is no longer needed.
+    pub(crate) fn load_after(
+        &self,
+        delay: Duration,
+        wanted: impl FnOnce() -> bool + Send + 'static,
+        load: impl FnOnce() -> T + Send + 'static,
+    ) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let loaded_tx = self.loaded_tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if wanted() {
+                let _ = tokio::task::spawn_blocking(move || {
+                    let _ = loaded_tx.send(load());
+                })
+                .await;
+            }
+        });
+        true
+    }
+
+    /// What finished loading since the last call.
+    pub(crate) fn loaded(&self) -> TryIter<'_, T> {
+        self.loaded_rx.try_iter()
+    }
+}
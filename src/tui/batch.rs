@@ -0,0 +1,87 @@
+//! Marking several results with Ctrl+Space and launching them together with
+//! Enter, e.g. three project bookmarks at once. Marks stay while the query
+//! changes, so results of different searches can be collected; Esc clears them.
+
+use super::{finish_pending_search, set_status_message, TuiState};
+use crate::{
+    locale::fill,
+    models::SearchResult,
+    state::{AppState, PendingAction},
+};
+
+/// Shown before the title of a marked result.
+pub(crate) const MARK_MARKER: &str = "✓ ";
+
+/// Actions that make sense to run together: opening things. Commands that change
+/// the system, end processes or only act inside egg are left out.
+fn markable(action: &PendingAction) -> bool {
+    matches!(
+        action,
+        PendingAction::Application(_)
+            | PendingAction::Bookmark(_)
+            | PendingAction::Url(_)
+            | PendingAction::File(_)
+            | PendingAction::Path { .. }
+    )
+}
+
+pub(crate) fn is_marked(marked: &[(SearchResult, PendingAction)], id: &str) -> bool {
+    marked.iter().any(|(result, _)| result.id == id)
+}
+
+/// Ctrl+Space: marks the selected result, or unmarks it.
+pub(crate) fn toggle_mark(ui_state: &mut TuiState, app_state: &AppState) {
+    if ui_state.searching {
+        finish_pending_search(ui_state, app_state);
+    }
+    let text = ui_state.text();
+    let Some(result) = ui_state
+        .list_state
+        .selected()
+        .and_then(|index| ui_state.results.get(index))
+        .cloned()
+    else {
+        return;
+    };
+    if let Some(position) = ui_state
+        .marked
+        .iter()
+        .position(|(marked, _)| marked.id == result.id)
+    {
+        ui_state.marked.remove(position);
+    } else {
+        match ui_state.pending_actions.get(&result.id) {
+            Some(action) if markable(action) => {
+                ui_state.marked.push((result, action.clone()));
+            }
+            _ => {
+                set_status_message(ui_state, text.cannot_mark);
+                return;
+            }
+        }
+    }
+    let count = ui_state.marked.len().to_string();
+    set_status_message(ui_state, fill(text.marked_count, &[&count]));
+}
+
+/// Esc with marks: drops them instead of quitting. Returns `false` when there
+/// were none.
+pub(crate) fn clear_marks(ui_state: &mut TuiState) -> bool {
+    if ui_state.marked.is_empty() {
+        return false;
+    }
+    ui_state.marked.clear();
+    set_status_message(ui_state, ui_state.text().marks_cleared);
+    true
+}
+
+/// Enter with marks: quits with every marked result to launch, in the order
+/// they were marked. Returns `false` when there were none.
+pub(crate) fn launch_marked(ui_state: &mut TuiState) -> bool {
+    if ui_state.marked.is_empty() {
+        return false;
+    }
+    ui_state.pending_batch = std::mem::take(&mut ui_state.marked);
+    ui_state.should_quit = true;
+    true
+}
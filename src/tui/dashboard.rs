@@ -0,0 +1,128 @@
+//! Empty-query dashboard: the configured `dashboard_sections` stacked in one list
+//! with a header row above each non-empty section.
+//!
+//! Headers are display-only, so `TuiState::results` and `list_state` keep indexing
+//! results exactly as they do for a search; only rendering maps between the two.
+
+use std::collections::HashMap;
+
+use ratatui::widgets::{ListItem, ListState};
+
+use crate::{
+    config::DashboardSection, models::SearchResult, search_core as core, state::AppState,
+    theme::Theme,
+};
+
+use super::{reset_selection, result_groups::rows_with_headers, TuiState};
+
+/// Most-used apps listed under "Frequent apps".
+const FREQUENT_LIMIT: usize = 5;
+
+struct Section {
+    kind: DashboardSection,
+    /// Index into `TuiState::results` of the section's first row.
+    start: usize,
+}
+
+#[derive(Default)]
+pub(crate) struct Dashboard {
+    sections: Vec<Section>,
+    /// Scroll state for the list including header rows.
+    view: ListState,
+}
+
+impl Dashboard {
+    pub(crate) fn is_active(&self) -> bool {
+        !self.sections.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.sections.clear();
+        self.view = ListState::default();
+    }
+}
+
+pub(crate) fn load_dashboard(ui_state: &mut TuiState, app_state: &AppState) {
+    let (section_kinds, hidden) = {
+        let config = app_state.config.lock().unwrap();
+        (
+            config.dashboard_sections.clone(),
+            config.hidden_results.clone(),
+        )
+    };
+    let mut results: Vec<SearchResult> = Vec::new();
+    let mut pending_actions = HashMap::new();
+    let mut sections = Vec::new();
+
+    for kind in section_kinds {
+        let start = results.len();
+        let (candidates, actions, limit) = match kind {
+            DashboardSection::Recent => {
+                let recent_guard = app_state.recent_actions.lock().unwrap();
+                let (candidates, actions) = recent_guard
+                    .items()
+                    .map(|entry| {
+                        (
+                            entry.result.clone(),
+                            (entry.result.id.clone(), entry.action.clone()),
+                        )
+                    })
+                    .unzip();
+                (candidates, actions, usize::MAX)
+            }
+            DashboardSection::Frequent => {
+                let app_index = app_state.app_index.lock().unwrap();
+                let history = app_state.launch_history.lock().unwrap();
+                // Ask for extra rows so items already shown higher up can be skipped.
+                let (candidates, actions) =
+                    core::frequent_apps(&app_index, &history, FREQUENT_LIMIT + results.len());
+                (candidates, actions, FREQUENT_LIMIT)
+            }
+        };
+        for result in candidates {
+            if results.len() - start >= limit {
+                break;
+            }
+            if pending_actions.contains_key(&result.id) || hidden.contains(&result.id) {
+                continue;
+            }
+            if let Some(action) = actions.get(&result.id) {
+                pending_actions.insert(result.id.clone(), action.clone());
+                results.push(result);
+            }
+        }
+        if results.len() > start {
+            sections.push(Section { kind, start });
+        }
+    }
+
+    ui_state.results = results;
+    ui_state.pending_actions = pending_actions;
+    reset_selection(ui_state);
+    ui_state.dashboard.sections = sections;
+}
+
+/// List rows with section headers interleaved, and the view state selecting the
+/// row of the selected result.
+pub(crate) fn dashboard_rows(
+    ui_state: &mut TuiState,
+    width: u16,
+    theme: Theme,
+) -> (Vec<ListItem<'static>>, &mut ListState) {
+    let text = ui_state.text();
+    let sections = &ui_state.dashboard.sections;
+    let (items, selected_row) = rows_with_headers(
+        ui_state,
+        |index| {
+            sections
+                .iter()
+                .find(|section| section.start == index)
+                .map(|section| section.kind.title(text))
+        },
+        width,
+        theme,
+    );
+    let view = &mut ui_state.dashboard.view;
+    view.select(selected_row);
+    (items, view)
+}
@@ -0,0 +1,232 @@
+//! Two-step prompt for an app's launch arguments and start folder, opened from the
+//! actions menu. Saved values go to `launch_overrides` and apply from the next launch.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{pad_label, set_status_message, TuiState, UndoEntry};
+use crate::{
+    config::LaunchOverride,
+    locale::{fill, Messages},
+    models::ApplicationInfo,
+    state::AppState,
+    theme::Theme,
+};
+
+const MAX_INPUT_LEN: usize = 260;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Step {
+    Arguments,
+    WorkingDirectory,
+}
+
+impl Step {
+    fn label(self, text: &Messages) -> &'static str {
+        match self {
+            Self::Arguments => text.arguments_label,
+            Self::WorkingDirectory => text.start_folder_label,
+        }
+    }
+}
+
+/// Serializable so a half-filled prompt survives a crash (see `session.rs`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LaunchOptionsEditor {
+    app_id: String,
+    app_name: String,
+    step: Step,
+    arguments: String,
+    working_directory: String,
+}
+
+impl LaunchOptionsEditor {
+    fn input_mut(&mut self) -> &mut String {
+        match self.step {
+            Step::Arguments => &mut self.arguments,
+            Step::WorkingDirectory => &mut self.working_directory,
+        }
+    }
+}
+
+/// Opens the prompt prefilled with the saved override, or else the indexed values.
+pub(super) fn open_launch_options(
+    ui_state: &mut TuiState,
+    app_state: &AppState,
+    app: &ApplicationInfo,
+) {
+    let config = app_state.config.lock().unwrap();
+    let (arguments, working_directory) = match config.launch_overrides.get(&app.id) {
+        Some(launch) => (&launch.arguments, &launch.working_directory),
+        None => (&app.arguments, &app.working_directory),
+    };
+    ui_state.launch_options = Some(LaunchOptionsEditor {
+        app_id: app.id.clone(),
+        app_name: app.name.clone(),
+        step: Step::Arguments,
+        arguments: arguments.clone().unwrap_or_default(),
+        working_directory: working_directory.clone().unwrap_or_default(),
+    });
+}
+
+pub(super) fn handle_launch_options_key_event(
+    key: KeyEvent,
+    ui_state: &mut TuiState,
+    app_state: &AppState,
+) {
+    let Some(editor) = ui_state.launch_options.as_mut() else {
+        return;
+    };
+
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('c') => ui_state.should_quit = true,
+            KeyCode::Char('u') => editor.input_mut().clear(),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc => {
+            ui_state.launch_options = None;
+            set_status_message(ui_state, ui_state.text().launch_options_unchanged);
+        }
+        KeyCode::Enter | KeyCode::Tab if editor.step == Step::Arguments => {
+            editor.step = Step::WorkingDirectory;
+        }
+        KeyCode::BackTab if editor.step == Step::WorkingDirectory => {
+            editor.step = Step::Arguments;
+        }
+        KeyCode::Enter => save_launch_options(ui_state, app_state),
+        KeyCode::Backspace => {
+            editor.input_mut().pop();
+        }
+        KeyCode::Char(ch) => {
+            let input = editor.input_mut();
+            if input.chars().count() < MAX_INPUT_LEN {
+                input.push(ch);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn save_launch_options(ui_state: &mut TuiState, app_state: &AppState) {
+    let Some(editor) = ui_state.launch_options.take() else {
+        return;
+    };
+    let text = ui_state.text();
+    // Cleared fields are kept as empty strings, so an app can be launched without
+    // the arguments or start folder it was indexed with.
+    let launch = LaunchOverride {
+        arguments: Some(editor.arguments.trim().to_string()),
+        working_directory: Some(editor.working_directory.trim().to_string()),
+    };
+    let indexed = app_state
+        .app_index
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|app| app.id == editor.app_id)
+        .map(|app| LaunchOverride {
+            arguments: Some(app.arguments.clone().unwrap_or_default()),
+            working_directory: Some(app.working_directory.clone().unwrap_or_default()),
+        });
+    let mut config = app_state.config.lock().unwrap();
+    let unchanged = match config.launch_overrides.get(&editor.app_id) {
+        Some(current) => *current == launch,
+        None => indexed.as_ref() == Some(&launch),
+    };
+    if unchanged {
+        drop(config);
+        set_status_message(ui_state, text.launch_options_unchanged);
+        return;
+    }
+    let previous = config
+        .launch_overrides
+        .insert(editor.app_id.clone(), launch);
+    let saved = config.save();
+    drop(config);
+    match saved {
+        Ok(()) => {
+            set_status_message(
+                ui_state,
+                fill(text.launch_options_saved, &[&editor.app_name]),
+            );
+            ui_state.undo.push(UndoEntry::LaunchOptions {
+                app_id: editor.app_id,
+                app_name: editor.app_name,
+                previous,
+            });
+        }
+        Err(_) => set_status_message(ui_state, text.settings_not_saved),
+    }
+}
+
+/// Draws the prompt as a popup across the bottom of `area`.
+pub(super) fn render_launch_options(
+    frame: &mut Frame,
+    area: Rect,
+    editor: &LaunchOptionsEditor,
+    theme: Theme,
+    text: &Messages,
+) {
+    let height = 6.min(area.height);
+    let popup = Rect {
+        x: area.x,
+        y: area.y + area.height - height,
+        width: area.width,
+        height,
+    };
+
+    let field = |step: Step, value: &str| {
+        let active = editor.step == step;
+        let value_style = if active {
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.dim)
+        };
+        Line::from(vec![
+            Span::styled(
+                pad_label(step.label(text), 14),
+                Style::default().fg(if active { theme.accent } else { theme.dim }),
+            ),
+            Span::styled(
+                if active {
+                    format!("{value}_")
+                } else {
+                    value.to_string()
+                },
+                value_style,
+            ),
+        ])
+    };
+    let hint = match editor.step {
+        Step::Arguments => text.arguments_keys,
+        Step::WorkingDirectory => text.start_folder_keys,
+    };
+    let prompt = Paragraph::new(vec![
+        field(Step::Arguments, &editor.arguments),
+        field(Step::WorkingDirectory, &editor.working_directory),
+        Line::default(),
+        Line::from(Span::styled(hint, Style::default().fg(theme.dim))),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.accent))
+            .style(Style::default().bg(theme.surface))
+            .title(Span::styled(
+                format!(" {} ", fill(text.launch_options_title, &[&editor.app_name])),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            )),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(prompt, popup);
+}
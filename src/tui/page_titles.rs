@@ -0,0 +1,81 @@
+//! Shows the page title under the selected URL or bookmark result when
+//! `fetch_page_titles` is on. Titles are fetched on a blocking task and added to
+//! every result for the same page once they arrive; nothing is fetched without a
+//! runtime (as in tests).
+
+use std::collections::HashSet;
+
+use log::debug;
+
+use super::{background::BackgroundLoader, TuiState};
+use crate::{
+    page_titles::{fetch_title, page_url, TitleCache},
+    state::PendingAction,
+};
+
+#[derive(Default)]
+pub(crate) struct PageTitles {
+    pub(crate) cache: TitleCache,
+    /// Pages being fetched.
+    pending: HashSet<String>,
+    pub(crate) loader: BackgroundLoader<(String, Option<String>)>,
+}
+
+fn action_page(action: &PendingAction) -> Option<String> {
+    match action {
+        PendingAction::Url(url) => page_url(url),
+        PendingAction::Bookmark(bookmark) => page_url(&bookmark.url),
+        _ => None,
+    }
+}
+
+/// Picks up finished fetches, starts one for the selected result if its page is
+/// new, and puts known titles in front of result subtitles.
+pub(crate) fn sync_page_titles(ui_state: &mut TuiState, enabled: bool) {
+    if !enabled {
+        return;
+    }
+    let titles = &mut ui_state.page_titles;
+    for (url, title) in titles.loader.loaded() {
+        titles.pending.remove(&url);
+        titles.cache.insert(url, title);
+    }
+
+    let selected = ui_state
+        .list_state
+        .selected()
+        .and_then(|index| ui_state.results.get(index))
+        .and_then(|result| ui_state.pending_actions.get(&result.id))
+        .and_then(action_page);
+    if let Some(url) = selected {
+        if titles.loader.enabled
+            && titles.cache.get(&url).is_none()
+            && titles.pending.insert(url.clone())
+        {
+            titles.loader.load(move || {
+                let title = fetch_title(&url).unwrap_or_else(|err| {
+                    debug!("no title for {url}: {err}");
+                    None
+                });
+                (url, title)
+            });
+        }
+    }
+
+    for result in &mut ui_state.results {
+        let Some(url) = ui_state
+            .pending_actions
+            .get(&result.id)
+            .and_then(action_page)
+        else {
+            continue;
+        };
+        if let Some(Some(title)) = titles.cache.get(&url) {
+            // Results are only rebuilt by a new search, so the title may be there already.
+            let prefix = format!("{title} · ");
+            if !result.subtitle.starts_with(&prefix) {
+                result.subtitle.insert_str(0, &prefix);
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Preview pane beside the result list for file and folder results. Files are read
+//! on a blocking task, so moving through results never waits on the disk; without
+//! a runtime (as in tests) they are read inline.
+
+use std::path::Path;
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+
+use super::{background::BackgroundLoader, TuiState};
+use crate::{
+    file_preview::{format_size, load_preview, FilePreview, TokenKind},
+    locale::{fill, Messages},
+    recent_documents::is_recent_document,
+    state::PendingAction,
+    theme::Theme,
+};
+
+/// Narrower result areas keep the whole width for the list.
+pub(crate) const MIN_WIDTH: u16 = 100;
+
+#[derive(Default)]
+pub(crate) struct PreviewPane {
+    /// The file shown, or being loaded.
+    path: Option<String>,
+    content: Option<FilePreview>,
+    pub(crate) loader: BackgroundLoader<(String, FilePreview)>,
+}
+
+impl PreviewPane {
+    pub(crate) fn is_open(&self) -> bool {
+        self.path.is_some()
+    }
+}
+
+/// The file or folder behind the selected result, if it is one.
+fn selected_path(ui_state: &TuiState) -> Option<String> {
+    let result = ui_state.results.get(ui_state.list_state.selected()?)?;
+    match ui_state.pending_actions.get(&result.id)? {
+        PendingAction::File(path) | PendingAction::Path { path, .. } => Some(path.clone()),
+        PendingAction::Application(app) if is_recent_document(app) => Some(app.path.clone()),
+        _ => None,
+    }
+}
+
+/// Follows the selection: starts loading a newly selected file and picks up loads
+/// that finished. A load for a file no longer selected is dropped. With `enabled`
+/// false the pane closes.
+pub(crate) fn sync_preview(ui_state: &mut TuiState, enabled: bool) {
+    let wanted = enabled.then(|| selected_path(ui_state)).flatten();
+    let pane = &mut ui_state.preview;
+    for (path, content) in pane.loader.loaded() {
+        if pane.path.as_ref() == Some(&path) {
+            pane.content = Some(content);
+        }
+    }
+    if pane.path == wanted {
+        return;
+    }
+    pane.path = wanted.clone();
+    pane.content = None;
+    let Some(path) = wanted else {
+        return;
+    };
+    if !pane.loader.enabled {
+        pane.content = Some(load_preview(Path::new(&path)));
+        return;
+    }
+    pane.loader.load(move || {
+        let content = load_preview(Path::new(&path));
+        (path, content)
+    });
+}
+
+pub(crate) fn render_preview(
+    frame: &mut Frame,
+    area: Rect,
+    pane: &PreviewPane,
+    theme: Theme,
+    messages: &Messages,
+) {
+    let Some(path) = pane.path.as_deref() else {
+        return;
+    };
+    let name = path
+        .trim_end_matches(['\\', '/'])
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or(path);
+    let dim = Style::default().fg(theme.dim);
+    let text = Style::default().fg(theme.text);
+
+    let mut lines = Vec::new();
+    match &pane.content {
+        None => lines.push(Line::from(Span::styled(messages.preview_loading, dim))),
+        Some(FilePreview::Text {
+            lines: text_lines,
+            truncated,
+            language,
+        }) => {
+            let gutter = text_lines.len().max(1).to_string().len();
+            for (index, line) in text_lines.iter().enumerate() {
+                let mut spans = vec![Span::styled(
+                    format!("{:>gutter$} ", index + 1),
+                    Style::default().fg(theme.border),
+                )];
+                match language {
+                    Some(language) => {
+                        for (kind, token) in language.highlight(line) {
+                            spans.push(Span::styled(token.to_string(), token_style(kind, theme)));
+                        }
+                    }
+                    None => spans.push(Span::styled(line.clone(), text)),
+                }
+                lines.push(Line::from(spans));
+            }
+            if *truncated {
+                lines.push(Line::from(Span::styled("...", dim)));
+            }
+        }
+        Some(FilePreview::Image {
+            format,
+            width,
+            height,
+            size,
+        }) => {
+            lines.push(Line::from(Span::styled(
+                fill(messages.preview_image, &[format]),
+                text.add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::from(Span::styled(
+                format!("{width} x {height} px"),
+                text,
+            )));
+            lines.push(Line::from(Span::styled(format_size(*size), dim)));
+        }
+        Some(FilePreview::Folder { entries, total }) => {
+            for entry in entries {
+                let style = if entry.ends_with('\\') {
+                    Style::default().fg(theme.accent)
+                } else {
+                    text
+                };
+                lines.push(Line::from(Span::styled(entry.clone(), style)));
+            }
+            if *total > entries.len() {
+                lines.push(Line::from(Span::styled(
+                    fill(
+                        messages.preview_more_entries,
+                        &[&(total - entries.len()).to_string()],
+                    ),
+                    dim,
+                )));
+            } else if entries.is_empty() {
+                lines.push(Line::from(Span::styled(messages.preview_empty_folder, dim)));
+            }
+        }
+        Some(FilePreview::Binary { size }) => {
+            lines.push(Line::from(Span::styled(
+                fill(messages.preview_unsupported, &[&format_size(*size)]),
+                dim,
+            )));
+        }
+        Some(FilePreview::Unavailable(reason)) => {
+            lines.push(Line::from(Span::styled(
+                fill(messages.preview_failed, &[reason]),
+                dim,
+            )));
+        }
+    }
+
+    let preview = Paragraph::new(lines)
+        .style(Style::default().bg(theme.surface))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.border))
+                .style(Style::default().bg(theme.surface))
+                .title(Span::styled(
+                    format!(" {name} "),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                )),
+        );
+    frame.render_widget(preview, area);
+}
+
+fn token_style(kind: TokenKind, theme: Theme) -> Style {
+    match kind {
+        TokenKind::Plain => Style::default().fg(theme.text),
+        TokenKind::Keyword => Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD),
+        TokenKind::String => Style::default().fg(Color::Rgb(238, 185, 110)),
+        TokenKind::Number => Style::default().fg(Color::Rgb(190, 168, 255)),
+        TokenKind::Comment => Style::default()
+            .fg(theme.dim)
+            .add_modifier(Modifier::ITALIC),
+    }
+}
@@ -0,0 +1,87 @@
+//! Optional grouped list (`group_results`): a header row above each category of
+//! results, which `search_core` has already gathered together.
+//!
+//! As on the dashboard, headers are display-only, so `TuiState::results` and
+//! `list_state` keep indexing results and Up/Down skip the headers.
+
+use ratatui::{prelude::*, widgets::ListItem, widgets::ListState};
+
+use crate::{search_core::result_category, theme::Theme};
+
+use super::{result_item, RowMarks, TuiState};
+
+#[derive(Default)]
+pub(crate) struct ResultGroups {
+    /// Recomputed every frame from the setting and the layout.
+    pub(crate) enabled: bool,
+    /// Scroll state for the list including header rows.
+    view: ListState,
+}
+
+impl ResultGroups {
+    pub(crate) fn forget_selection(&mut self) {
+        self.view = ListState::default();
+    }
+}
+
+/// List rows with a header wherever the category changes, and the view state
+/// selecting the row of the selected result.
+pub(crate) fn grouped_rows(
+    ui_state: &mut TuiState,
+    width: u16,
+    theme: Theme,
+) -> (Vec<ListItem<'static>>, &mut ListState) {
+    let text = ui_state.text();
+    let results = &ui_state.results;
+    let (items, selected_row) = rows_with_headers(
+        ui_state,
+        |index| {
+            let category = result_category(&results[index].action_id);
+            let previous = index
+                .checked_sub(1)
+                .map(|previous| result_category(&results[previous].action_id));
+            (previous != Some(category)).then(|| category.title(text))
+        },
+        width,
+        theme,
+    );
+    let view = &mut ui_state.groups.view;
+    view.select(selected_row);
+    (items, view)
+}
+
+/// Rows for every result with `header_at(index)` above it when it returns one,
+/// and the row of the selected result.
+pub(crate) fn rows_with_headers(
+    ui_state: &TuiState,
+    header_at: impl Fn(usize) -> Option<&'static str>,
+    width: u16,
+    theme: Theme,
+) -> (Vec<ListItem<'static>>, Option<usize>) {
+    let text = ui_state.text();
+    let header_style = Style::default()
+        .fg(theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let mut items = Vec::new();
+    let mut selected_row = None;
+    for (index, result) in ui_state.results.iter().enumerate() {
+        if let Some(header) = header_at(index) {
+            items.push(ListItem::new(Line::from(Span::styled(
+                header,
+                header_style,
+            ))));
+        }
+        if ui_state.list_state.selected() == Some(index) {
+            selected_row = Some(items.len());
+        }
+        items.push(result_item(
+            result,
+            RowMarks::new(ui_state, index),
+            ui_state.full_paths,
+            width,
+            theme,
+            text,
+        ));
+    }
+    (items, selected_row)
+}
@@ -0,0 +1,53 @@
+//! Score breakdown of the selected result, shown under the result list with
+//! `debug_mode` so surprising rankings can be understood and reported.
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+
+use super::TuiState;
+use crate::{search_core::explain_score, state::AppState, theme::Theme};
+
+/// Taller breakdowns are cut off rather than squeezing the result list.
+const MAX_HEIGHT: u16 = 10;
+
+/// Lines describing how the selected result was scored.
+pub(crate) fn score_details(ui_state: &TuiState, app_state: &AppState) -> Option<Vec<String>> {
+    let result = ui_state.results.get(ui_state.list_state.selected()?)?;
+    let action = ui_state.pending_actions.get(&result.id)?;
+    let config = app_state.search_config();
+    let history = app_state.launch_history.lock().unwrap();
+    let lines = match explain_score(&ui_state.input, action, &history, &config) {
+        Some(breakdown) => breakdown.lines(),
+        None if result.score == i64::MIN => vec!["always listed last".to_string()],
+        None => vec![format!("fixed score {}", result.score)],
+    };
+    Some(lines)
+}
+
+/// Height of the pane for `lines`, borders included.
+pub(crate) fn details_height(lines: &[String]) -> u16 {
+    (lines.len() as u16 + 2).min(MAX_HEIGHT)
+}
+
+pub(crate) fn render_score_details(frame: &mut Frame, area: Rect, lines: &[String], theme: Theme) {
+    let text: Vec<Line> = lines
+        .iter()
+        .map(|line| Line::from(Span::styled(line.as_str(), Style::default().fg(theme.text))))
+        .collect();
+    let details = Paragraph::new(text)
+        .style(Style::default().bg(theme.surface))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.border))
+                .style(Style::default().bg(theme.surface))
+                .title(Span::styled(
+                    " Score ",
+                    Style::default().fg(theme.dim).add_modifier(Modifier::BOLD),
+                )),
+        );
+    frame.render_widget(details, area);
+}
@@ -0,0 +1,186 @@
+//! Background search: keystrokes submit queries, a tokio task debounces them by
+//! `query_delay_ms`, runs the search off the render thread and sends back only
+//! results for the newest query. Each query takes a generation from
+//! `AppState::query_generations`; a search whose query was superseded stops
+//! scanning and its results are dropped. Besides the indexes, `file:` queries
+//! and plugin keywords go through here too, so a slow Everything, Windows Search
+//! or plugin never holds up the event loop.
+
+use std::{collections::HashMap, time::Duration};
+
+use tokio::sync::mpsc;
+
+use crate::{
+    models::SearchResult,
+    plugins::PluginManifest,
+    state::{AppState, GenerationToken, PendingAction, QueryGenerations},
+};
+
+use super::{run_file_search, run_plugin_search, run_search};
+
+/// What a query searches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SearchKind {
+    /// The app and bookmark indexes, through the search cache.
+    Index,
+    /// Everything or Windows Search, for the text of a `file:` query.
+    Files,
+    /// A plugin, for the text after its action keyword.
+    Plugin(PluginManifest),
+}
+
+impl SearchKind {
+    /// Runs `query`; index and plugin searches stop once `token` goes stale.
+    pub(crate) fn run(
+        &self,
+        query: &str,
+        app_state: &AppState,
+        token: &GenerationToken,
+    ) -> SearchOutcome {
+        match self {
+            Self::Index => {
+                let (results, pending_actions) = run_search(query, app_state, token);
+                SearchOutcome {
+                    results,
+                    pending_actions,
+                    failure: None,
+                }
+            }
+            Self::Files => run_file_search(query, &app_state.search_config()),
+            Self::Plugin(plugin) => {
+                run_plugin_search(plugin, query, &app_state.search_config(), token)
+            }
+        }
+    }
+}
+
+pub(crate) struct SearchOutcome {
+    pub(crate) results: Vec<SearchResult>,
+    pub(crate) pending_actions: HashMap<String, PendingAction>,
+    /// Why the search found nothing, for the status line.
+    pub(crate) failure: Option<String>,
+}
+
+struct SearchRequest {
+    token: GenerationToken,
+    kind: SearchKind,
+    query: String,
+}
+
+pub(crate) struct SearchResponse {
+    pub(crate) generation: u64,
+    pub(crate) outcome: SearchOutcome,
+}
+
+pub(crate) struct SearchPipeline {
+    requests: mpsc::UnboundedSender<SearchRequest>,
+    responses: mpsc::UnboundedReceiver<SearchResponse>,
+    generations: QueryGenerations,
+}
+
+impl SearchPipeline {
+    /// Starts the worker task. Must be called from within a tokio runtime.
+    pub(crate) fn spawn(app_state: AppState) -> Self {
+        let (request_tx, request_rx) = mpsc::unbounded_channel();
+        let (response_tx, response_rx) = mpsc::unbounded_channel();
+        let generations = app_state.query_generations.clone();
+        tokio::spawn(search_worker(app_state, request_rx, response_tx));
+        Self {
+            requests: request_tx,
+            responses: response_rx,
+            generations,
+        }
+    }
+
+    /// Queues `query`, superseding any search still waiting or running.
+    pub(crate) fn submit(&self, kind: SearchKind, query: String) -> u64 {
+        let token = self.generations.advance();
+        let generation = token.generation();
+        let _ = self.requests.send(SearchRequest { token, kind, query });
+        generation
+    }
+
+    /// Drops whatever is in flight, e.g. when the input was cleared or hit the cache.
+    pub(crate) fn cancel(&self) {
+        self.generations.cancel();
+    }
+
+    /// Returns the newest finished search, discarding stale ones.
+    pub(crate) fn try_recv(&mut self) -> Option<SearchResponse> {
+        let mut newest = None;
+        while let Ok(response) = self.responses.try_recv() {
+            if self.generations.is_current(response.generation) {
+                newest = Some(response);
+            }
+        }
+        newest
+    }
+}
+
+async fn search_worker(
+    app_state: AppState,
+    mut requests: mpsc::UnboundedReceiver<SearchRequest>,
+    responses: mpsc::UnboundedSender<SearchResponse>,
+) {
+    while let Some(mut request) = requests.recv().await {
+        let delay = Duration::from_millis(app_state.config.lock().unwrap().query_delay_ms);
+        // Wait until typing pauses for `delay`, always keeping the newest query.
+        loop {
+            match tokio::time::timeout(delay, requests.recv()).await {
+                Ok(Some(newer)) => request = newer,
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+        if request.token.is_stale() {
+            continue;
+        }
+
+        let state = app_state.clone();
+        let (kind, query, token) = (request.kind, request.query, request.token.clone());
+        let Ok(outcome) =
+            tokio::task::spawn_blocking(move || kind.run(&query, &state, &token)).await
+        else {
+            continue;
+        };
+        if !request.token.is_stale() {
+            let _ = responses.send(SearchResponse {
+                generation: request.token.generation(),
+                outcome,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::testing::fixture_state;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn delivers_only_the_newest_query() {
+        let state = fixture_state();
+        state.config.lock().unwrap().query_delay_ms = 20;
+        let mut pipeline = SearchPipeline::spawn(state);
+
+        pipeline.submit(SearchKind::Index, "no".to_string());
+        pipeline.submit(SearchKind::Index, "not".to_string());
+        let last = pipeline.submit(SearchKind::Index, "notepad".to_string());
+
+        let started = Instant::now();
+        let response = loop {
+            if let Some(response) = pipeline.try_recv() {
+                break response;
+            }
+            assert!(started.elapsed() < Duration::from_secs(5), "no results");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        };
+        assert_eq!(response.generation, last);
+        assert_eq!(response.outcome.results[0].title, "Notepad");
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(pipeline.try_recv().is_none());
+    }
+}
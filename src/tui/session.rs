@@ -0,0 +1,142 @@
+//! Crash recovery. While the TUI runs, a snapshot of the search box, selection and
+//! any open launch options prompt is written to the data directory; a clean exit
+//! removes it, so finding one at startup means the last session ended unexpectedly.
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use super::{finish_pending_search, refresh_results, LaunchOptionsEditor, TuiState};
+use crate::{cache, state::AppState};
+
+const SESSION_FILE: &str = "session.json";
+/// Snapshots are written at most this often, and only when something changed.
+const WRITE_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SessionSnapshot {
+    pub(crate) query: String,
+    /// Cursor position in characters.
+    pub(crate) cursor: usize,
+    pub(crate) selected: Option<usize>,
+    /// First visible row of the result list.
+    pub(crate) offset: usize,
+    #[serde(default)]
+    pub(crate) launch_options: Option<LaunchOptionsEditor>,
+}
+
+impl SessionSnapshot {
+    pub(crate) fn capture(ui_state: &TuiState) -> Self {
+        Self {
+            query: ui_state.input.clone(),
+            cursor: ui_state.cursor,
+            selected: ui_state.list_state.selected(),
+            offset: ui_state.list_state.offset(),
+            launch_options: ui_state.launch_options.clone(),
+        }
+    }
+
+    /// Whether restoring would bring back anything.
+    fn is_worth_offering(&self) -> bool {
+        !self.query.trim().is_empty() || self.launch_options.is_some()
+    }
+}
+
+/// The snapshot left behind by a session that did not exit cleanly.
+pub(crate) fn unfinished_session() -> Option<SessionSnapshot> {
+    let path = session_path()?;
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<SessionSnapshot>(&content) {
+        Ok(snapshot) => snapshot.is_worth_offering().then_some(snapshot),
+        Err(err) => {
+            warn!("failed to parse session snapshot {:?}: {err}", path);
+            None
+        }
+    }
+}
+
+/// Marks the session as cleanly finished.
+pub(crate) fn clear_session() {
+    if let Some(path) = session_path() {
+        if let Err(err) = fs::remove_file(&path) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!("failed to remove session snapshot {:?}: {err}", path);
+            }
+        }
+    }
+}
+
+/// Writes snapshots from the event loop.
+pub(crate) struct SessionRecorder {
+    last: Option<SessionSnapshot>,
+    last_write: Option<Instant>,
+}
+
+impl SessionRecorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            last: None,
+            last_write: None,
+        }
+    }
+
+    pub(crate) fn record(&mut self, ui_state: &TuiState) {
+        if self
+            .last_write
+            .is_some_and(|written| written.elapsed() < WRITE_INTERVAL)
+        {
+            return;
+        }
+        let snapshot = SessionSnapshot::capture(ui_state);
+        if self.last.as_ref() == Some(&snapshot) {
+            return;
+        }
+        self.last_write = Some(Instant::now());
+        match save_snapshot(&snapshot) {
+            Ok(()) => self.last = Some(snapshot),
+            Err(err) => warn!("failed to write session snapshot: {err}"),
+        }
+    }
+}
+
+/// Puts the query, selection and prompt from `snapshot` back. The search runs
+/// inline so the selection lands on the same row as before.
+pub(crate) fn restore_session(
+    ui_state: &mut TuiState,
+    app_state: &AppState,
+    snapshot: SessionSnapshot,
+) {
+    ui_state.cursor = snapshot.cursor.min(snapshot.query.chars().count());
+    ui_state.input = snapshot.query;
+    refresh_results(ui_state, app_state);
+    if ui_state.searching {
+        finish_pending_search(ui_state, app_state);
+    }
+    if let Some(selected) = snapshot.selected {
+        if selected < ui_state.results.len() {
+            ui_state.list_state.select(Some(selected));
+            *ui_state.list_state.offset_mut() = snapshot.offset.min(selected);
+        }
+    }
+    ui_state.launch_options = snapshot.launch_options;
+}
+
+fn save_snapshot(snapshot: &SessionSnapshot) -> Result<(), String> {
+    let Some(path) = session_path() else {
+        return Err("无法确定数据目录".into());
+    };
+    let payload = serde_json::to_string(snapshot).map_err(|err| err.to_string())?;
+    cache::write_atomically(&path, payload)?;
+    debug!("wrote session snapshot {:?}", path);
+    Ok(())
+}
+
+fn session_path() -> Option<PathBuf> {
+    let base = dirs::data_local_dir()?;
+    Some(base.join("egg").join("data").join(SESSION_FILE))
+}
@@ -0,0 +1,220 @@
+//! Optional side-by-side layout: apps on the left, bookmarks/files/web on the right.
+//!
+//! `TuiState::results` and `list_state` stay the single source of truth, so Enter
+//! launches exactly what it would in the single list; the panes only change how
+//! results are drawn and how Up/Down/PageUp/PageDown/Tab move the selection.
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{locale::Messages, models::SearchResult, theme::Theme};
+
+use super::{batch::is_marked, result_item, scroll_position, shortcut_number, RowMarks, TuiState};
+
+/// Narrower result areas fall back to the single list.
+pub(crate) const MIN_WIDTH: u16 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Pane {
+    Apps,
+    Links,
+}
+
+impl Pane {
+    fn index(self) -> usize {
+        match self {
+            Self::Apps => 0,
+            Self::Links => 1,
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Self::Apps => Self::Links,
+            Self::Links => Self::Apps,
+        }
+    }
+
+    fn title(self, text: &Messages) -> &'static str {
+        match self {
+            Self::Apps => text.apps_pane,
+            Self::Links => text.links_pane,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SplitPanes {
+    /// Recomputed every frame from the setting and the terminal width.
+    pub(crate) enabled: bool,
+    /// Scroll offset and last selected row of each pane.
+    states: [ListState; 2],
+}
+
+impl SplitPanes {
+    pub(crate) fn forget_selection(&mut self) {
+        self.states = Default::default();
+    }
+}
+
+pub(crate) fn pane_of(result: &SearchResult) -> Pane {
+    match result.action_id.as_str() {
+        "bookmark" | "file" | "url" | "search" => Pane::Links,
+        _ => Pane::Apps,
+    }
+}
+
+/// Global result indices shown in `pane`, in rank order.
+fn pane_indices(results: &[SearchResult], pane: Pane) -> Vec<usize> {
+    results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| pane_of(result) == pane)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn focused_pane(ui_state: &TuiState) -> Option<Pane> {
+    let selected = ui_state.list_state.selected()?;
+    ui_state.results.get(selected).map(pane_of)
+}
+
+pub(crate) fn move_selection_in_pane(ui_state: &mut TuiState, delta: isize) {
+    step_in_pane(ui_state, |position, len| {
+        (position as isize + delta).rem_euclid(len as isize) as usize
+    });
+}
+
+/// Like [`move_selection_in_pane`], but stops at either end of the pane.
+pub(crate) fn page_selection_in_pane(ui_state: &mut TuiState, delta: isize) {
+    step_in_pane(ui_state, |position, len| {
+        (position as isize + delta).clamp(0, len as isize - 1) as usize
+    });
+}
+
+/// Selects the row `next` picks from the focused pane's current row and length.
+fn step_in_pane(ui_state: &mut TuiState, next: impl FnOnce(usize, usize) -> usize) {
+    let Some(pane) = focused_pane(ui_state) else {
+        return;
+    };
+    let indices = pane_indices(&ui_state.results, pane);
+    let Some(position) = indices
+        .iter()
+        .position(|index| Some(*index) == ui_state.list_state.selected())
+    else {
+        return;
+    };
+    let next = next(position, indices.len());
+    ui_state.split.states[pane.index()].select(Some(next));
+    ui_state.list_state.select(Some(indices[next]));
+}
+
+/// Moves focus to the other pane, returning to the row it last had selected.
+pub(crate) fn switch_pane(ui_state: &mut TuiState) {
+    let Some(current) = focused_pane(ui_state) else {
+        return;
+    };
+    let current_indices = pane_indices(&ui_state.results, current);
+    let position = current_indices
+        .iter()
+        .position(|index| Some(*index) == ui_state.list_state.selected());
+    ui_state.split.states[current.index()].select(position);
+
+    let target = current.other();
+    let indices = pane_indices(&ui_state.results, target);
+    if indices.is_empty() {
+        return;
+    }
+    let remembered = ui_state.split.states[target.index()]
+        .selected()
+        .filter(|position| *position < indices.len())
+        .unwrap_or(0);
+    ui_state.list_state.select(Some(indices[remembered]));
+}
+
+pub(crate) fn render_split_results(
+    frame: &mut Frame,
+    area: Rect,
+    ui_state: &mut TuiState,
+    theme: Theme,
+) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let focused = focused_pane(ui_state);
+    for (pane, column) in [Pane::Apps, Pane::Links].into_iter().zip(columns.iter()) {
+        render_pane(frame, *column, ui_state, pane, focused == Some(pane), theme);
+    }
+}
+
+fn render_pane(
+    frame: &mut Frame,
+    area: Rect,
+    ui_state: &mut TuiState,
+    pane: Pane,
+    focused: bool,
+    theme: Theme,
+) {
+    let text = ui_state.text();
+    let border = if focused { theme.accent } else { theme.border };
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(border))
+        .style(Style::default().bg(theme.surface))
+        .title(Span::styled(
+            format!(" {} ", pane.title(text)),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        ));
+
+    let indices = pane_indices(&ui_state.results, pane);
+    if indices.is_empty() {
+        let empty = Paragraph::new(text.no_matches)
+            .style(Style::default().fg(theme.dim).bg(theme.surface))
+            .alignment(Alignment::Center)
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    let state = &mut ui_state.split.states[pane.index()];
+    if focused {
+        let position = indices
+            .iter()
+            .position(|index| Some(*index) == ui_state.list_state.selected());
+        state.select(position);
+    }
+    if let Some(position) =
+        scroll_position(state.selected(), indices.len(), ui_state.page_size, theme)
+    {
+        block = block.title(position);
+    }
+    let items: Vec<ListItem> = indices
+        .iter()
+        .map(|index| {
+            let result = &ui_state.results[*index];
+            let marks = RowMarks {
+                pinned: ui_state.pinned.contains(&result.id),
+                marked: is_marked(&ui_state.marked, &result.id),
+                shortcut: shortcut_number(*index),
+            };
+            result_item(result, marks, ui_state.full_paths, area.width, theme, text)
+        })
+        .collect();
+    let highlight = if focused {
+        Style::default()
+            .fg(theme.highlight_fg)
+            .bg(theme.highlight_bg)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.text)
+    };
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(highlight)
+        .highlight_symbol(if focused { "> " } else { "  " });
+    frame.render_stateful_widget(list, area, state);
+}
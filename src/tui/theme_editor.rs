@@ -0,0 +1,241 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use super::{set_status_message, TuiState};
+use crate::{
+    config::ThemeConfig,
+    locale::{fill, Messages},
+    state::AppState,
+    theme::{format_color, parse_color, Theme, ThemeSlot},
+};
+
+const MAX_INPUT_LEN: usize = 24;
+
+/// Draft theme being edited; rendering uses it for live preview until saved or discarded.
+pub(crate) struct ThemeEditor {
+    pub(crate) draft: ThemeConfig,
+    selected: usize,
+    pub(crate) input: String,
+    /// Input that did not parse as a color.
+    invalid: Option<String>,
+}
+
+impl ThemeEditor {
+    fn new(current: ThemeConfig) -> Self {
+        let mut editor = Self {
+            draft: current,
+            selected: 0,
+            input: String::new(),
+            invalid: None,
+        };
+        editor.input = editor.slot_text(editor.slot());
+        editor
+    }
+
+    pub(super) fn theme(&self) -> Theme {
+        Theme::from_config(&self.draft)
+    }
+
+    /// The slot's own color, or the preset's when the slot is not set.
+    fn slot_text(&self, slot: ThemeSlot) -> String {
+        match slot.value(&self.draft) {
+            "" => format_color(Theme::preset(self.draft.preset).color(slot)),
+            value => value.to_string(),
+        }
+    }
+
+    fn slot(&self) -> ThemeSlot {
+        ThemeSlot::ALL[self.selected]
+    }
+
+    fn select(&mut self, delta: isize) {
+        let len = ThemeSlot::ALL.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+        self.input = self.slot_text(self.slot());
+        self.invalid = None;
+    }
+
+    fn apply_input(&mut self) {
+        match parse_color(&self.input) {
+            Some(color) => {
+                *self.slot().value_mut(&mut self.draft) = format_color(color);
+                self.invalid = None;
+            }
+            None => {
+                self.invalid = Some(self.input.trim().to_string());
+            }
+        }
+    }
+
+    /// Hands the slot back to the preset.
+    fn reset_slot(&mut self) {
+        self.slot().value_mut(&mut self.draft).clear();
+        self.input = self.slot_text(self.slot());
+        self.invalid = None;
+    }
+
+    fn next_preset(&mut self) {
+        self.draft.preset = self.draft.preset.next();
+        self.input = self.slot_text(self.slot());
+        self.invalid = None;
+    }
+}
+
+pub(super) fn open_theme_editor(ui_state: &mut TuiState, app_state: &AppState) {
+    let current = app_state.config.lock().unwrap().theme.clone();
+    ui_state.theme_editor = Some(ThemeEditor::new(current));
+}
+
+pub(super) fn handle_theme_editor_key_event(
+    key: KeyEvent,
+    ui_state: &mut TuiState,
+    app_state: &AppState,
+) {
+    let Some(editor) = ui_state.theme_editor.as_mut() else {
+        return;
+    };
+
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('c') => ui_state.should_quit = true,
+            KeyCode::Char('s') => save_theme(ui_state, app_state),
+            KeyCode::Char('r') => editor.reset_slot(),
+            KeyCode::Char('t') => editor.next_preset(),
+            KeyCode::Char('n') => editor.select(1),
+            KeyCode::Char('p') => editor.select(-1),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc => {
+            ui_state.theme_editor = None;
+            set_status_message(ui_state, ui_state.text().theme_discarded);
+        }
+        KeyCode::Enter => save_theme(ui_state, app_state),
+        KeyCode::Up => editor.select(-1),
+        KeyCode::Down | KeyCode::Tab => editor.select(1),
+        KeyCode::Backspace => {
+            editor.input.pop();
+            editor.apply_input();
+        }
+        KeyCode::Char(ch) if editor.input.chars().count() < MAX_INPUT_LEN => {
+            editor.input.push(ch);
+            editor.apply_input();
+        }
+        _ => {}
+    }
+}
+
+fn save_theme(ui_state: &mut TuiState, app_state: &AppState) {
+    let Some(editor) = ui_state.theme_editor.take() else {
+        return;
+    };
+    let mut config = app_state.config.lock().unwrap();
+    config.theme = editor.draft;
+    let saved = config.save();
+    drop(config);
+    let text = ui_state.text();
+    match saved {
+        Ok(()) => set_status_message(ui_state, text.theme_saved),
+        Err(_) => set_status_message(ui_state, text.settings_not_saved),
+    }
+}
+
+pub(super) fn render_theme_editor(
+    frame: &mut Frame,
+    area: Rect,
+    editor: &ThemeEditor,
+    theme: Theme,
+    text: &Messages,
+) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(5)])
+        .split(area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(Span::styled(
+            format!(" {} · {} ", text.theme_title, editor.draft.preset.label()),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        ));
+
+    let items: Vec<ListItem> = ThemeSlot::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, slot)| {
+            let value = if index == editor.selected {
+                format!("{}_", editor.input)
+            } else {
+                editor.slot_text(*slot)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<13}", slot.label()),
+                    Style::default().fg(theme.text),
+                ),
+                Span::styled("      ", Style::default().bg(theme.color(*slot))),
+                Span::styled(format!("  {value}"), Style::default().fg(theme.dim)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight_fg)
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(editor.selected));
+    frame.render_stateful_widget(list, layout[0], &mut list_state);
+
+    let status = match &editor.invalid {
+        Some(input) => Line::from(Span::styled(
+            fill(text.not_a_color, &[input]),
+            Style::default().fg(theme.accent),
+        )),
+        None => Line::from(Span::styled(
+            text.theme_keys,
+            Style::default().fg(theme.dim),
+        )),
+    };
+    let preview = Paragraph::new(vec![
+        Line::from(Span::styled(
+            format!("  {}", text.sample_result),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("> {}", text.selected_result),
+            Style::default()
+                .fg(theme.highlight_fg)
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD),
+        )),
+        status,
+    ])
+    .style(Style::default().bg(theme.surface))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.surface))
+            .title(Span::styled(
+                format!(" {} ", text.theme_preview_title),
+                Style::default().fg(theme.dim),
+            )),
+    );
+    frame.render_widget(preview, layout[1]);
+}
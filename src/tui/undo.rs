@@ -0,0 +1,213 @@
+//! Ctrl+Z for changes made from the TUI that take something away: blacklisting an
+//! app, hiding or unpinning a result, changing an app's alias and saving or
+//! clearing an app's launch options. Each change keeps what it replaced, and
+//! undoing writes that back to `settings.json` and the recent list.
+
+use std::collections::HashMap;
+
+use log::warn;
+
+use super::{refresh_app_index, refresh_results, set_status_message, TuiState};
+use crate::{
+    cache,
+    config::{AliasTarget, LaunchOverride},
+    locale::{fill, Messages},
+    state::{AppState, CacheProvider, RecentEntry},
+};
+
+/// Older changes fall off the bottom of the stack.
+const MAX_UNDO: usize = 20;
+
+pub(crate) enum UndoEntry {
+    /// An app path added to `system_tool_exclusions`, with the recent entry (and its
+    /// position) that went with it.
+    Blacklist {
+        path: String,
+        app_name: String,
+        recent: Option<(usize, Box<RecentEntry>)>,
+    },
+    /// A result id added to `hidden_results`.
+    Hide {
+        result_id: String,
+        title: String,
+        recent: Option<(usize, Box<RecentEntry>)>,
+    },
+    /// A result id removed from `pinned_results`, and where it was in the list.
+    Pin {
+        result_id: String,
+        title: String,
+        index: usize,
+    },
+    /// The aliases as they were before an app's alias was changed, which may
+    /// also have taken the name from another app.
+    Aliases {
+        app_name: String,
+        previous: HashMap<String, AliasTarget>,
+    },
+    /// An app's launch options as they were before they were saved or cleared.
+    LaunchOptions {
+        app_id: String,
+        app_name: String,
+        previous: Option<LaunchOverride>,
+    },
+}
+
+impl UndoEntry {
+    fn describe(&self, text: &Messages) -> String {
+        match self {
+            Self::Blacklist { app_name, .. } => fill(text.unblacklisted, &[app_name]),
+            Self::Hide { title, .. } => fill(text.shown_again, &[title]),
+            Self::Pin { title, .. } => fill(text.pinned_again, &[title]),
+            Self::Aliases { app_name, .. } => fill(text.alias_restored, &[app_name]),
+            Self::LaunchOptions { app_name, .. } => fill(text.launch_options_restored, &[app_name]),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct UndoStack {
+    entries: Vec<UndoEntry>,
+}
+
+impl UndoStack {
+    pub(crate) fn push(&mut self, entry: UndoEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > MAX_UNDO {
+            self.entries.remove(0);
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Reverts the most recent change. If settings cannot be saved the change stays
+/// on the stack, so Ctrl+Z can be tried again.
+pub(crate) fn undo_last(ui_state: &mut TuiState, app_state: &AppState) {
+    let text = ui_state.text();
+    let Some(entry) = ui_state.undo.entries.pop() else {
+        set_status_message(ui_state, text.nothing_to_undo);
+        return;
+    };
+    if revert(&entry, app_state).is_err() {
+        ui_state.undo.entries.push(entry);
+        set_status_message(ui_state, text.settings_not_saved);
+        return;
+    }
+    match entry {
+        UndoEntry::Blacklist { .. } => {
+            refresh_app_index(app_state);
+            refresh_results(ui_state, app_state);
+        }
+        UndoEntry::Hide { .. } => refresh_results(ui_state, app_state),
+        UndoEntry::Pin { .. } => {
+            ui_state.pinned = app_state
+                .config
+                .lock()
+                .unwrap()
+                .pinned_results
+                .iter()
+                .cloned()
+                .collect();
+            refresh_results(ui_state, app_state);
+        }
+        UndoEntry::Aliases { .. } => refresh_results(ui_state, app_state),
+        UndoEntry::LaunchOptions { .. } => {}
+    }
+    set_status_message(ui_state, fill(text.undone, &[&entry.describe(text)]));
+}
+
+fn revert(entry: &UndoEntry, app_state: &AppState) -> Result<(), String> {
+    match entry {
+        UndoEntry::Blacklist { path, recent, .. } => {
+            let mut config = app_state.config.lock().unwrap();
+            config
+                .system_tool_exclusions
+                .retain(|item| !item.eq_ignore_ascii_case(path));
+            config.save()?;
+            drop(config);
+
+            put_back_recent(app_state, recent);
+            if let Ok(mut cache_guard) = app_state.search_cache.lock() {
+                cache_guard.invalidate(CacheProvider::Apps);
+            }
+            Ok(())
+        }
+        UndoEntry::Hide {
+            result_id, recent, ..
+        } => {
+            let mut config = app_state.config.lock().unwrap();
+            config.hidden_results.retain(|id| id != result_id);
+            config.save()?;
+            drop(config);
+
+            put_back_recent(app_state, recent);
+            if let Ok(mut cache_guard) = app_state.search_cache.lock() {
+                cache_guard.invalidate(CacheProvider::Config);
+            }
+            Ok(())
+        }
+        UndoEntry::Pin {
+            result_id, index, ..
+        } => {
+            let mut config = app_state.config.lock().unwrap();
+            if !config.pinned_results.contains(result_id) {
+                let index = (*index).min(config.pinned_results.len());
+                config.pinned_results.insert(index, result_id.clone());
+                if let Err(err) = config.save() {
+                    config.pinned_results.remove(index);
+                    return Err(err);
+                }
+            }
+            drop(config);
+
+            if let Ok(mut cache_guard) = app_state.search_cache.lock() {
+                cache_guard.invalidate(CacheProvider::Config);
+            }
+            Ok(())
+        }
+        UndoEntry::Aliases { previous, .. } => {
+            let mut config = app_state.config.lock().unwrap();
+            let changed = std::mem::replace(&mut config.aliases, previous.clone());
+            if let Err(err) = config.save() {
+                config.aliases = changed;
+                return Err(err);
+            }
+            drop(config);
+
+            if let Ok(mut cache_guard) = app_state.search_cache.lock() {
+                cache_guard.invalidate(CacheProvider::Config);
+            }
+            Ok(())
+        }
+        UndoEntry::LaunchOptions {
+            app_id, previous, ..
+        } => {
+            let mut config = app_state.config.lock().unwrap();
+            match previous {
+                Some(launch) => {
+                    config
+                        .launch_overrides
+                        .insert(app_id.clone(), launch.clone());
+                }
+                None => {
+                    config.launch_overrides.remove(app_id);
+                }
+            }
+            config.save()
+        }
+    }
+}
+
+fn put_back_recent(app_state: &AppState, recent: &Option<(usize, Box<RecentEntry>)>) {
+    let Some((index, entry)) = recent else {
+        return;
+    };
+    if let Ok(mut recent_guard) = app_state.recent_actions.lock() {
+        recent_guard.put_back(*index, RecentEntry::clone(entry));
+        if let Err(err) = cache::save_recent_actions(&recent_guard) {
+            warn!("failed to save recent actions: {err}");
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Lists query suggestions under the web search row when `web_suggestions` is
+//! on. Once typing pauses for [`DEBOUNCE`], the suggestions for the input are
+//! fetched on a blocking task and added below the row when they arrive; nothing
+//! is fetched without a runtime (as in tests).
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use log::debug;
+
+use super::{background::BackgroundLoader, TuiState};
+use crate::{
+    config::SuggestionEngine,
+    search_core::web_suggestion_result,
+    web_suggestions::{fetch_suggestions, SuggestionCache},
+};
+
+/// How long the input must stay the same before its suggestions are fetched.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+type Key = (SuggestionEngine, String);
+
+#[derive(Default)]
+pub(crate) struct WebSuggestions {
+    pub(crate) cache: SuggestionCache,
+    /// The newest query asked for; requests for older ones are dropped unsent.
+    requested: Arc<Mutex<Option<Key>>>,
+    pub(crate) loader: BackgroundLoader<(Key, Vec<String>)>,
+}
+
+/// Picks up finished fetches and lists the suggestions for `query` below the
+/// web search row, asking `engine` for them if they are new. `engine` is `None`
+/// while suggestions are off.
+pub(crate) fn sync_web_suggestions(
+    ui_state: &mut TuiState,
+    engine: Option<SuggestionEngine>,
+    query: &str,
+) {
+    let Some(engine) = engine else {
+        return;
+    };
+    let suggestions = &mut ui_state.web_suggestions;
+    for (key, listed) in suggestions.loader.loaded() {
+        suggestions.cache.insert(key, listed);
+    }
+    // The results may still be those of an earlier input.
+    if ui_state.searching || query.is_empty() {
+        return;
+    }
+    let Some(web_row) = ui_state
+        .results
+        .iter()
+        .rposition(|result| result.id.starts_with("search-"))
+    else {
+        return;
+    };
+    // Results are only rebuilt by a new search, so the suggestions may be there already.
+    if ui_state
+        .results
+        .iter()
+        .any(|result| result.id.starts_with("web-suggestion-"))
+    {
+        return;
+    }
+    let key = (engine, query.to_string());
+    let Some(listed) = suggestions.cache.get(&key).cloned() else {
+        request(suggestions, key);
+        return;
+    };
+
+    let text = ui_state.text();
+    let rows: Vec<_> = listed
+        .iter()
+        .enumerate()
+        .map(|(index, suggestion)| web_suggestion_result(engine, suggestion, index, text))
+        .collect();
+    let count = rows.len();
+    for (offset, (result, action)) in rows.into_iter().enumerate() {
+        ui_state.pending_actions.insert(result.id.clone(), action);
+        ui_state.results.insert(web_row + 1 + offset, result);
+    }
+    // Keep the same result selected.
+    if let Some(selected) = ui_state
+        .list_state
+        .selected()
+        .filter(|&index| index > web_row)
+    {
+        ui_state.list_state.select(Some(selected + count));
+    }
+}
+
+/// Fetches the suggestions for `key` after [`DEBOUNCE`], unless another query
+/// is asked for first.
+fn request(suggestions: &WebSuggestions, key: Key) {
+    if !suggestions.loader.enabled {
+        return;
+    }
+    {
+        let mut requested = suggestions.requested.lock().unwrap();
+        if requested.as_ref() == Some(&key) {
+            return;
+        }
+        *requested = Some(key.clone());
+    }
+    let requested = suggestions.requested.clone();
+    let wanted = key.clone();
+    suggestions.loader.load_after(
+        DEBOUNCE,
+        move || requested.lock().unwrap().as_ref() == Some(&wanted),
+        move || {
+            let listed = fetch_suggestions(key.0, &key.1).unwrap_or_else(|err| {
+                debug!("no suggestions for {}: {err}", key.1);
+                Vec::new()
+            });
+            (key, listed)
+        },
+    );
+}
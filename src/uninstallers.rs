@@ -0,0 +1,246 @@
+//! Uninstallers registered under the `Uninstall` registry keys, the list behind
+//! Programs and Features. The indexer otherwise skips uninstallers, so these are
+//! only indexed with `index_uninstallers` on, and only offered for queries that
+//! ask for one (`uninstall spotify`, `卸载 微信`).
+
+use std::collections::HashSet;
+
+#[cfg(windows)]
+use winreg::{enums::*, RegKey};
+
+use crate::{
+    models::{AppType, ApplicationInfo},
+    text_utils::build_pinyin_index,
+};
+
+/// Ids of uninstallers, followed by the registry key name.
+pub const UNINSTALL_ID_PREFIX: &str = "uninstall:";
+/// Query words that ask for an uninstaller.
+pub const UNINSTALL_WORDS: [&str; 4] = ["uninstall", "remove", "卸载", "删除"];
+
+#[cfg(windows)]
+const UNINSTALL_KEYS: [(HKEY, &str); 3] = [
+    (
+        HKEY_LOCAL_MACHINE,
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+    ),
+    (
+        HKEY_LOCAL_MACHINE,
+        r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+    ),
+    (
+        HKEY_CURRENT_USER,
+        r"Software\Microsoft\Windows\CurrentVersion\Uninstall",
+    ),
+];
+
+/// The values of one `Uninstall\<key>` entry that matter here.
+#[derive(Default)]
+struct UninstallKey {
+    name: String,
+    display_name: Option<String>,
+    uninstall_string: Option<String>,
+    publisher: Option<String>,
+    display_icon: Option<String>,
+    /// `SystemComponent = 1`, hidden from Programs and Features.
+    system_component: bool,
+    /// Updates and hotfixes, which name the product they belong to.
+    is_update: bool,
+}
+
+/// Uninstallers of everything Programs and Features lists, once per name.
+#[cfg(windows)]
+pub fn enumerate_uninstallers() -> Vec<ApplicationInfo> {
+    let mut keys = Vec::new();
+    for (hive, path) in UNINSTALL_KEYS {
+        let Ok(root) = RegKey::predef(hive).open_subkey(path) else {
+            continue;
+        };
+        for name in root.enum_keys().flatten() {
+            let Ok(key) = root.open_subkey(&name) else {
+                continue;
+            };
+            let text = |value: &str| key.get_value::<String, _>(value).ok();
+            let flag = |value: &str| key.get_value::<u32, _>(value).ok() == Some(1);
+            keys.push(UninstallKey {
+                display_name: text("DisplayName"),
+                uninstall_string: text("UninstallString"),
+                publisher: text("Publisher"),
+                display_icon: text("DisplayIcon"),
+                system_component: flag("SystemComponent"),
+                is_update: text("ParentKeyName").is_some()
+                    || text("ReleaseType")
+                        .is_some_and(|kind| matches!(kind.as_str(), "Update" | "Hotfix")),
+                name,
+            });
+        }
+    }
+    uninstallers(keys)
+}
+
+#[cfg(not(windows))]
+pub fn enumerate_uninstallers() -> Vec<ApplicationInfo> {
+    Vec::new()
+}
+
+pub fn is_uninstall_entry(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(UNINSTALL_ID_PREFIX)
+}
+
+/// Whether `tokens` ask for an uninstaller.
+pub fn asks_for_uninstaller(tokens: &[&str]) -> bool {
+    tokens.iter().any(|token| {
+        let token = token.to_lowercase();
+        UNINSTALL_WORDS.contains(&token.as_str())
+            || (token.chars().count() >= 3
+                && UNINSTALL_WORDS.iter().any(|word| word.starts_with(&token)))
+    })
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn uninstallers(keys: Vec<UninstallKey>) -> Vec<ApplicationInfo> {
+    let mut seen = HashSet::new();
+    keys.into_iter()
+        .filter_map(uninstaller)
+        .filter(|app| seen.insert(app.name.to_lowercase()))
+        .collect()
+}
+
+fn uninstaller(key: UninstallKey) -> Option<ApplicationInfo> {
+    if key.system_component || key.is_update {
+        return None;
+    }
+    let name = key.display_name?.trim().to_string();
+    let command = key.uninstall_string?;
+    let (program, arguments) = split_command_line(&command)?;
+    if name.is_empty() {
+        return None;
+    }
+    let publisher = key
+        .publisher
+        .filter(|publisher| !publisher.trim().is_empty());
+    let mut keywords = vec![name.clone()];
+    keywords.extend(UNINSTALL_WORDS.iter().map(|word| word.to_string()));
+    keywords.extend(publisher.clone());
+    // `DisplayIcon` is a file with an optional `,index`, the form `icons` reads.
+    let icon = key
+        .display_icon
+        .map(|icon| icon.trim().trim_matches('"').to_string())
+        .filter(|icon| !icon.is_empty())
+        .map(|icon| match icon.rsplit_once(',') {
+            Some((_, index)) if index.trim().parse::<i32>().is_ok() => icon,
+            _ => format!("{icon},0"),
+        });
+    Some(ApplicationInfo {
+        id: format!("{UNINSTALL_ID_PREFIX}{}", key.name.to_lowercase()),
+        pinyin_index: build_pinyin_index([name.as_str()]),
+        name,
+        path: program,
+        source_path: None,
+        app_type: AppType::Uninstaller,
+        description: Some(command.trim().to_string()),
+        keywords,
+        working_directory: None,
+        arguments,
+        icon,
+        folded: None,
+    })
+}
+
+/// Splits an `UninstallString` into the program and its arguments. Many are
+/// unquoted paths with spaces, so an unquoted one ends after `.exe`.
+fn split_command_line(command: &str) -> Option<(String, Option<String>)> {
+    let command = command.trim();
+    let (program, rest) = if let Some(quoted) = command.strip_prefix('"') {
+        quoted.split_once('"')?
+    } else if let Some(end) = command.to_ascii_lowercase().find(".exe") {
+        command.split_at(end + ".exe".len())
+    } else {
+        command.split_once(' ').unwrap_or((command, ""))
+    };
+    let program = program.trim();
+    if program.is_empty() {
+        return None;
+    }
+    let arguments = Some(rest.trim().to_string()).filter(|rest| !rest.is_empty());
+    Some((program.to_string(), arguments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str, display_name: &str, uninstall_string: &str) -> UninstallKey {
+        UninstallKey {
+            name: name.to_string(),
+            display_name: Some(display_name.to_string()),
+            uninstall_string: Some(uninstall_string.to_string()),
+            ..UninstallKey::default()
+        }
+    }
+
+    #[test]
+    fn uninstall_keys_become_uninstallers() {
+        let mut spotify = key(
+            "Spotify",
+            "Spotify",
+            r#""C:\Users\me\AppData\Roaming\Spotify\Spotify.exe" /uninstall"#,
+        );
+        spotify.display_icon = Some(r"C:\Users\me\AppData\Roaming\Spotify\Spotify.exe".into());
+        let mut update = key("KB5005565", "Security Update", "msiexec.exe /X{KB}");
+        update.is_update = true;
+        let mut hidden = key("Runtime", "VC++ Runtime", "msiexec.exe /X{VC}");
+        hidden.system_component = true;
+        let keys = vec![
+            spotify,
+            key(
+                "{1D8E6291-B0D5-35EC-8441-6616F567A0F7}",
+                "7-Zip 23.01",
+                r"C:\Program Files\7-Zip\Uninstall.exe",
+            ),
+            key(
+                "7-Zip",
+                "7-Zip 23.01",
+                r"C:\Program Files\7-Zip\Uninstall.exe",
+            ),
+            key(
+                "{GUID}",
+                "Office",
+                "MsiExec.exe /X{90160000-0011-0000-0000}",
+            ),
+            update,
+            hidden,
+        ];
+
+        let apps = uninstallers(keys);
+        let names: Vec<&str> = apps.iter().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, vec!["Spotify", "7-Zip 23.01", "Office"]);
+        assert_eq!(apps[0].id, "uninstall:spotify");
+        assert_eq!(apps[0].app_type, AppType::Uninstaller);
+        assert_eq!(
+            apps[0].path,
+            r"C:\Users\me\AppData\Roaming\Spotify\Spotify.exe"
+        );
+        assert_eq!(apps[0].arguments.as_deref(), Some("/uninstall"));
+        assert_eq!(
+            apps[0].icon.as_deref(),
+            Some(r"C:\Users\me\AppData\Roaming\Spotify\Spotify.exe,0")
+        );
+        assert_eq!(apps[1].path, r"C:\Program Files\7-Zip\Uninstall.exe");
+        assert_eq!(apps[1].arguments, None);
+        assert_eq!(apps[2].path, "MsiExec.exe");
+        assert_eq!(
+            apps[2].arguments.as_deref(),
+            Some("/X{90160000-0011-0000-0000}")
+        );
+    }
+
+    #[test]
+    fn only_uninstall_queries_ask_for_uninstallers() {
+        assert!(asks_for_uninstaller(&["uninstall", "spotify"]));
+        assert!(asks_for_uninstaller(&["unins", "spotify"]));
+        assert!(asks_for_uninstaller(&["卸载", "微信"]));
+        assert!(!asks_for_uninstaller(&["spotify"]));
+        assert!(!asks_for_uninstaller(&["re", "spotify"]));
+    }
+}
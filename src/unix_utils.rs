@@ -0,0 +1,235 @@
+//! Linux and macOS counterparts of `windows_utils`: system actions, the
+//! clipboard, the window switcher and the process list go through the
+//! desktop's own command-line tools.
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    models::{ProcessInfo, WindowInfo},
+    system_commands::SystemCommand,
+};
+
+/// Performs a built-in system action.
+pub(crate) fn run_system_command(command: SystemCommand) -> Result<(), String> {
+    let (program, args) = system_command_line(command);
+    let status = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|err| format!("无法运行 {program}: {err}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{program} 执行失败 ({status})"))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_command_line(command: SystemCommand) -> (&'static str, &'static [&'static str]) {
+    match command {
+        SystemCommand::Lock => ("pmset", &["displaysleepnow"]),
+        SystemCommand::Sleep => ("pmset", &["sleepnow"]),
+        SystemCommand::Shutdown => (
+            "osascript",
+            &["-e", "tell application \"System Events\" to shut down"],
+        ),
+        SystemCommand::Restart => (
+            "osascript",
+            &["-e", "tell application \"System Events\" to restart"],
+        ),
+        SystemCommand::SignOut => (
+            "osascript",
+            &["-e", "tell application \"System Events\" to log out"],
+        ),
+        SystemCommand::EmptyRecycleBin => (
+            "osascript",
+            &["-e", "tell application \"Finder\" to empty trash"],
+        ),
+        SystemCommand::OpenSettings => ("open", &["x-apple.systempreferences:"]),
+    }
+}
+
+/// systemd's `loginctl` and `systemctl`, which every mainstream desktop answers to.
+#[cfg(not(target_os = "macos"))]
+fn system_command_line(command: SystemCommand) -> (&'static str, &'static [&'static str]) {
+    match command {
+        SystemCommand::Lock => ("loginctl", &["lock-session"]),
+        SystemCommand::Sleep => ("systemctl", &["suspend"]),
+        SystemCommand::Shutdown => ("systemctl", &["poweroff"]),
+        SystemCommand::Restart => ("systemctl", &["reboot"]),
+        // logind reads an empty session id as the caller's own session.
+        SystemCommand::SignOut => ("loginctl", &["terminate-session", ""]),
+        SystemCommand::EmptyRecycleBin => ("gio", &["trash", "--empty"]),
+        SystemCommand::OpenSettings => ("gnome-control-center", &[]),
+    }
+}
+
+/// Places `text` on the clipboard through the first clipboard tool that runs.
+pub(crate) fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut last_error = String::from("没有可用的剪贴板工具");
+    for (program, args) in clipboard_tools() {
+        let child = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(err) => {
+                last_error = format!("无法运行 {program}: {err}");
+                continue;
+            }
+        };
+        let written = child
+            .stdin
+            .take()
+            .map(|mut stdin| stdin.write_all(text.as_bytes()));
+        match (written, child.wait()) {
+            (Some(Ok(())), Ok(status)) if status.success() => return Ok(()),
+            _ => last_error = format!("{program} 无法写入剪贴板"),
+        }
+    }
+    Err(format!("无法复制到剪贴板: {last_error}"))
+}
+
+#[cfg(target_os = "macos")]
+fn clipboard_tools() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("pbcopy", &[])]
+}
+
+#[cfg(not(target_os = "macos"))]
+fn clipboard_tools() -> &'static [(&'static str, &'static [&'static str])] {
+    &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ]
+}
+
+/// Top-level windows as listed by `wmctrl -lp`, which works on X11 desktops.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn list_windows() -> Result<Vec<WindowInfo>, String> {
+    let output = Command::new("wmctrl")
+        .arg("-lp")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|err| format!("无法运行 wmctrl: {err}"))?;
+    if !output.status.success() {
+        return Err(format!("wmctrl 执行失败 ({})", output.status));
+    }
+    // `0x03a00003  0 4242   host  Title`: id, desktop, pid, host, then the title.
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut rest = line;
+            let mut field = || {
+                rest = rest.trim_start();
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let (field, after) = rest.split_at(end);
+                rest = after;
+                field
+            };
+            let id = isize::from_str_radix(field().strip_prefix("0x")?, 16).ok()?;
+            let desktop = field();
+            let process_id = field();
+            field();
+            // Sticky panels and docks sit on desktop -1.
+            if desktop == "-1" {
+                return None;
+            }
+            let title = rest.trim().to_string();
+            let process = std::fs::read_to_string(format!("/proc/{process_id}/comm"))
+                .map(|name| name.trim().to_string())
+                .unwrap_or_default();
+            (!title.is_empty()).then_some(WindowInfo { id, title, process })
+        })
+        .collect())
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn list_windows() -> Result<Vec<WindowInfo>, String> {
+    Err("macOS 上暂不支持窗口切换".into())
+}
+
+/// Raises the window `id` from [`list_windows`], switching desktops if needed.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn focus_window(id: isize) -> Result<(), String> {
+    let status = Command::new("wmctrl")
+        .args(["-i", "-a", &format!("0x{id:08x}")])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|err| format!("无法运行 wmctrl: {err}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("窗口已关闭".into())
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn focus_window(_: isize) -> Result<(), String> {
+    Err("macOS 上暂不支持窗口切换".into())
+}
+
+/// Running processes from `ps`, by pid, leaving out egg itself. Root's
+/// processes count as elevated.
+pub(crate) fn list_processes() -> Result<Vec<ProcessInfo>, String> {
+    let own_pid = std::process::id();
+    Ok(run_ps(&["-A"])?
+        .into_iter()
+        .filter(|process| process.pid != own_pid)
+        .collect())
+}
+
+/// `ps` with `args`, read as `pid uid command` lines.
+fn run_ps(args: &[&str]) -> Result<Vec<ProcessInfo>, String> {
+    let output = Command::new("ps")
+        .args(args)
+        .args(["-o", "pid=", "-o", "uid=", "-o", "comm="])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|err| format!("无法运行 ps: {err}"))?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let uid: u32 = fields.next()?.parse().ok()?;
+            // macOS prints the full path; the command may contain spaces.
+            let command = fields.collect::<Vec<_>>().join(" ");
+            let name = command.rsplit('/').next().unwrap_or(&command).to_string();
+            (!name.is_empty()).then_some(ProcessInfo {
+                pid,
+                name,
+                elevated: uid == 0,
+            })
+        })
+        .collect())
+}
+
+/// Asks `pid` from [`list_processes`] to quit with `SIGTERM`, unless the pid
+/// now belongs to another program than `name`.
+pub(crate) fn kill_process(pid: u32, name: &str) -> Result<(), String> {
+    let pid_arg = pid.to_string();
+    let running = run_ps(&["-p", &pid_arg])?;
+    if !running.iter().any(|process| process.name == name) {
+        return Err(format!("{name} 已经退出"));
+    }
+    let pid = libc::pid_t::try_from(pid).map_err(|_| format!("无效的进程号 {pid}"))?;
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        let err = std::io::Error::last_os_error();
+        Err(format!("无法结束 {name}: {err}"))
+    }
+}
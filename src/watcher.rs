@@ -0,0 +1,272 @@
+//! Watches the Start Menu, browser bookmark files, `settings.json` and the plugins
+//! folder, and applies changes to the in-memory indexes and config while egg is
+//! running.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use log::{debug, info, warn};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::{
+    bookmarks::{self, BookmarkSource},
+    cache,
+    config::{config_path, AppConfig},
+    index_diff::IndexDiff,
+    indexer, plugins,
+    state::{AppState, CacheProvider},
+};
+
+/// Bursts of events (installers, browser saves) are batched over this window.
+const SETTLE_DELAY: Duration = Duration::from_millis(750);
+
+/// Starts watching; keep the returned watcher alive for as long as updates are wanted.
+pub fn spawn_index_watcher(state: Arc<AppState>) -> Option<RecommendedWatcher> {
+    let (tx, rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let mut watcher =
+        match notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) => {
+                let _ = tx.send(event.paths);
+            }
+            Err(err) => warn!("index watcher error: {err}"),
+        }) {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                warn!("failed to create index watcher: {err}");
+                return None;
+            }
+        };
+
+    let start_menu_roots = indexer::watched_start_menu_roots();
+    for root in &start_menu_roots {
+        if let Err(err) = watcher.watch(root, RecursiveMode::Recursive) {
+            warn!("failed to watch {:?}: {err}", root);
+        }
+    }
+    // Browsers replace the Bookmarks file on save, so watch its directory instead.
+    let browsers = state.config.lock().unwrap().bookmark_browsers.clone();
+    let sources = bookmarks::bookmark_sources(&browsers);
+    let profile_dirs: HashSet<PathBuf> = sources
+        .iter()
+        .filter_map(|source| source.path.parent().map(Path::to_path_buf))
+        .collect();
+    for dir in &profile_dirs {
+        if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            warn!("failed to watch {:?}: {err}", dir);
+        }
+    }
+    // Editors also save by replacing the file, so watch the config directory too.
+    let settings_path = config_path();
+    if let Some(dir) = settings_path.as_deref().and_then(Path::parent) {
+        if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            warn!("failed to watch {:?}: {err}", dir);
+        }
+    }
+    // Creating the folder shows up through the config directory.
+    let plugins_dir = plugins::plugins_dir();
+    if let Some(dir) = plugins_dir.as_deref().filter(|dir| dir.is_dir()) {
+        if let Err(err) = watcher.watch(dir, RecursiveMode::Recursive) {
+            warn!("failed to watch {:?}: {err}", dir);
+        }
+    }
+    info!(
+        "watching {} start menu roots, {} bookmark profiles, settings and plugins",
+        start_menu_roots.len(),
+        profile_dirs.len()
+    );
+
+    tokio::spawn(apply_changes(
+        state,
+        rx,
+        start_menu_roots,
+        sources,
+        settings_path,
+        plugins_dir,
+    ));
+    Some(watcher)
+}
+
+async fn apply_changes(
+    state: Arc<AppState>,
+    mut rx: mpsc::UnboundedReceiver<Vec<PathBuf>>,
+    start_menu_roots: Vec<PathBuf>,
+    sources: Vec<BookmarkSource>,
+    settings_path: Option<PathBuf>,
+    plugins_dir: Option<PathBuf>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut changed: HashSet<PathBuf> = first.into_iter().collect();
+        while let Ok(Some(more)) = tokio::time::timeout(SETTLE_DELAY, rx.recv()).await {
+            changed.extend(more);
+        }
+
+        let mut url_shortcuts = Vec::new();
+        let mut shell_changed = false;
+        let mut bookmark_labels = HashSet::new();
+        let mut settings_changed = false;
+        let mut plugins_changed = false;
+        for path in &changed {
+            if start_menu_roots.iter().any(|root| path.starts_with(root)) {
+                if has_extension(path, "url") {
+                    url_shortcuts.push(path.clone());
+                } else {
+                    // .lnk files and folder moves surface through AppsFolder.
+                    shell_changed = true;
+                }
+            } else if let Some(source) = sources.iter().find(|source| source.path == *path) {
+                bookmark_labels.insert(source.label.clone());
+            } else if settings_path.as_ref() == Some(path) {
+                settings_changed = true;
+            } else if plugins_dir
+                .as_ref()
+                .is_some_and(|dir| path.starts_with(dir))
+            {
+                plugins_changed = true;
+            }
+        }
+        if settings_changed {
+            reload_settings(&state);
+        }
+        if plugins_changed {
+            // Plugins are read along with the settings, so a change rereads them.
+            state
+                .search_cache
+                .lock()
+                .unwrap()
+                .invalidate(CacheProvider::Config);
+            debug!("plugins folder changed");
+        }
+        debug!(
+            "index watcher: {} url shortcuts, shell changed: {shell_changed}, {} bookmark profiles",
+            url_shortcuts.len(),
+            bookmark_labels.len()
+        );
+
+        let (exclusions, language) = {
+            let config = state.config.lock().unwrap();
+            (config.system_tool_exclusions.clone(), config.language)
+        };
+        let apps_before = (!url_shortcuts.is_empty() || shell_changed)
+            .then(|| state.app_index.lock().unwrap().clone());
+        let mut apps_changed = false;
+        if !url_shortcuts.is_empty() {
+            let updates: Vec<_> = url_shortcuts
+                .into_iter()
+                .map(|path| {
+                    let app = indexer::load_url_shortcut(&path);
+                    (path, app)
+                })
+                .collect();
+            let mut guard = state.app_index.lock().unwrap();
+            let apps = Arc::make_mut(&mut guard);
+            apps_changed |= indexer::merge_url_shortcuts(apps, updates, &exclusions);
+        }
+        if shell_changed {
+            let shell_apps = indexer::enumerate_shell_index().await;
+            if !shell_apps.is_empty() {
+                let mut guard = state.app_index.lock().unwrap();
+                let apps = Arc::make_mut(&mut guard);
+                apps_changed |= indexer::merge_shell_apps(apps, shell_apps, &exclusions);
+            }
+        }
+        if let Some(before) = apps_before.filter(|_| apps_changed) {
+            let snapshot = state.app_index.lock().unwrap().clone();
+            let _ = cache::save_app_index(&snapshot);
+            info!("index watcher updated apps ({} entries)", snapshot.len());
+            state.invalidate_changes(&IndexDiff::between(&before, &snapshot));
+        }
+
+        let bookmarks_before =
+            (!bookmark_labels.is_empty()).then(|| state.bookmark_index.lock().unwrap().clone());
+        for label in &bookmark_labels {
+            let entries: Vec<_> = sources
+                .iter()
+                .filter(|source| source.label == *label)
+                .flat_map(|source| {
+                    bookmarks::load_bookmark_file(&source.path, &source.label, language)
+                })
+                .collect();
+            let mut guard = state.bookmark_index.lock().unwrap();
+            bookmarks::replace_profile_entries(Arc::make_mut(&mut guard), label, entries);
+        }
+        if let Some(before) = bookmarks_before {
+            let snapshot = state.bookmark_index.lock().unwrap().clone();
+            state.invalidate_changes(&IndexDiff::between(&before, &snapshot));
+        }
+    }
+}
+
+/// Swaps in the config from disk. A file that is mid-edit and fails to parse keeps
+/// the current settings; saves egg made itself change nothing and are skipped.
+fn reload_settings(state: &AppState) {
+    let loaded = match AppConfig::try_load() {
+        Ok(config) => config,
+        Err(err) => {
+            warn!("settings not reloaded: {err}");
+            return;
+        }
+    };
+    if apply_settings(state, loaded) {
+        info!("settings reloaded from disk");
+    }
+}
+
+/// Replaces the config when it differs, invalidating config-dependent searches.
+fn apply_settings(state: &AppState, loaded: AppConfig) -> bool {
+    let mut config = state.config.lock().unwrap();
+    if serde_json::to_value(&*config).ok() == serde_json::to_value(&loaded).ok() {
+        return false;
+    }
+    *config = loaded;
+    drop(config);
+    if let Ok(mut cache_guard) = state.search_cache.lock() {
+        cache_guard.invalidate(CacheProvider::Config);
+    }
+    true
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CachedSearch;
+
+    #[test]
+    fn changed_settings_replace_config_and_drop_cached_searches() {
+        let state = AppState::new();
+        let mut cache = state.search_cache.lock().unwrap();
+        let stamp = cache.stamp();
+        cache.insert(
+            "query".to_string(),
+            CachedSearch {
+                query: "query".to_string(),
+                results: Vec::new(),
+                pending_actions: Default::default(),
+            },
+            vec![CacheProvider::Config],
+            stamp,
+        );
+        drop(cache);
+
+        assert!(!apply_settings(&state, AppConfig::default()));
+        assert!(state.search_cache.lock().unwrap().get("query").is_some());
+
+        let edited = AppConfig {
+            max_results: 7,
+            ..AppConfig::default()
+        };
+        assert!(apply_settings(&state, edited));
+        assert_eq!(state.config.lock().unwrap().max_results, 7);
+        assert!(state.search_cache.lock().unwrap().get("query").is_none());
+    }
+}
@@ -0,0 +1,226 @@
+//! Installed web apps (PWAs) of Chrome and Edge. Installing one puts a Start Menu
+//! shortcut to the browser's `chrome_proxy.exe` or `msedge_proxy.exe` with an
+//! `--app-id=`; egg starts the same proxy with the shortcut's arguments, so the
+//! app opens in its own window under the right browser profile.
+
+use std::{collections::HashSet, path::Path};
+#[cfg(windows)]
+use std::{fs, path::PathBuf};
+
+#[cfg(windows)]
+use log::warn;
+
+#[cfg(windows)]
+use crate::{
+    indexer::{watched_start_menu_roots, ComInitGuard},
+    windows_utils::read_shell_link,
+};
+use crate::{
+    models::{AppType, ApplicationInfo},
+    text_utils::build_pinyin_index,
+};
+
+/// Ids of web apps, followed by the browser and the app id.
+pub const WEB_APP_ID_PREFIX: &str = "pwa:";
+
+/// App proxies and the browsers they belong to.
+#[cfg_attr(not(windows), allow(dead_code))]
+const PROXIES: [(&str, &str); 2] = [("chrome_proxy.exe", "Chrome"), ("msedge_proxy.exe", "Edge")];
+
+/// Web apps behind the `.lnk` shortcuts anywhere under the Start Menu.
+#[cfg(windows)]
+pub fn enumerate_web_apps() -> Vec<ApplicationInfo> {
+    let mut links = Vec::new();
+    let mut stack: Vec<PathBuf> = watched_start_menu_roots();
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                stack.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"))
+            {
+                links.push(path);
+            }
+        }
+    }
+    links.sort();
+
+    let _com_guard = match unsafe { ComInitGuard::new() } {
+        Ok(guard) => guard,
+        Err(err) => {
+            warn!("web apps skipped, COM unavailable: {err}");
+            return Vec::new();
+        }
+    };
+    links
+        .iter()
+        .filter_map(|link| {
+            let shortcut = read_shell_link(link)?;
+            web_app(
+                link,
+                &shortcut.target,
+                &shortcut.arguments,
+                shortcut.working_directory,
+            )
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn enumerate_web_apps() -> Vec<ApplicationInfo> {
+    Vec::new()
+}
+
+pub fn is_web_app(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(WEB_APP_ID_PREFIX)
+}
+
+/// The web app a shortcut starts, or `None` unless it runs a browser's app proxy
+/// with an `--app-id`.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn web_app(
+    shortcut: &Path,
+    target: &str,
+    arguments: &str,
+    working_directory: Option<String>,
+) -> Option<ApplicationInfo> {
+    let program = target.rsplit(['\\', '/']).next()?;
+    let (_, browser) = PROXIES
+        .into_iter()
+        .find(|(proxy, _)| program.eq_ignore_ascii_case(proxy))?;
+    let app_id = argument(arguments, "app-id")?;
+    let name = shortcut.file_stem()?.to_str()?.trim().to_string();
+    if name.is_empty() {
+        return None;
+    }
+    let start_url = argument(arguments, "app-url");
+
+    let mut keywords = vec![name.clone(), "web app".to_string(), "pwa".to_string()];
+    if let Some(host) = start_url.and_then(url_host) {
+        keywords.push(host.to_string());
+    }
+    let description = match start_url {
+        Some(url) => format!("{browser} web app · {url}"),
+        None => format!("{browser} web app"),
+    };
+    Some(ApplicationInfo {
+        id: format!(
+            "{WEB_APP_ID_PREFIX}{}:{}",
+            browser.to_ascii_lowercase(),
+            app_id.to_ascii_lowercase()
+        ),
+        pinyin_index: build_pinyin_index([name.as_str()]),
+        name,
+        path: target.to_string(),
+        source_path: Some(shortcut.to_string_lossy().into_owned()),
+        app_type: AppType::Pwa,
+        description: Some(description),
+        keywords,
+        working_directory: working_directory.filter(|dir| !dir.trim().is_empty()),
+        arguments: Some(arguments.trim().to_string()),
+        icon: None,
+        folded: None,
+    })
+}
+
+/// The value of `--name=value` in a command line, with surrounding quotes removed.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn argument<'a>(arguments: &'a str, name: &str) -> Option<&'a str> {
+    let flag = format!("--{name}=");
+    let start = arguments.find(&flag)? + flag.len();
+    let rest = &arguments[start..];
+    let value = match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next()?,
+        None => rest.split_whitespace().next()?,
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://")?.1;
+    let host = rest.split(['/', '?', '#', ':']).next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// Drops the AppsFolder items for web apps found through their shortcuts. The
+/// shell lists them under an AppUserModelId ending in `_crx_<app id>`.
+pub fn drop_shell_duplicates(shell_apps: &mut Vec<ApplicationInfo>, web_apps: &[ApplicationInfo]) {
+    let app_ids: HashSet<&str> = web_apps
+        .iter()
+        .filter_map(|app| app.id.rsplit(':').next())
+        .collect();
+    shell_apps.retain(|app| {
+        let lower = app.path.to_ascii_lowercase();
+        !lower
+            .split("_crx_")
+            .skip(1)
+            .any(|rest| app_ids.contains(rest.split(['.', '!', '\\']).next().unwrap_or(rest)))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::app;
+
+    const EDGE_PROXY: &str = r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge_proxy.exe";
+
+    #[test]
+    fn proxy_shortcuts_become_web_apps() {
+        let arguments = "--profile-directory=Default --app-id=agimnkijcaahngcdmfeangaknmldooml \
+                         --app-url=https://www.youtube.com/?feature=ytca --app-launch-source=4";
+        let shortcut = Path::new("C:/Users/me/Start Menu/Programs/YouTube.lnk");
+        let youtube = web_app(shortcut, EDGE_PROXY, arguments, None).unwrap();
+        assert_eq!(youtube.id, "pwa:edge:agimnkijcaahngcdmfeangaknmldooml");
+        assert_eq!(youtube.name, "YouTube");
+        assert_eq!(youtube.app_type, AppType::Pwa);
+        assert_eq!(youtube.path, EDGE_PROXY);
+        assert_eq!(youtube.arguments.as_deref(), Some(arguments));
+        assert_eq!(
+            youtube.description.as_deref(),
+            Some("Edge web app · https://www.youtube.com/?feature=ytca")
+        );
+        assert!(youtube.keywords.contains(&"www.youtube.com".to_string()));
+
+        let chrome = web_app(
+            shortcut,
+            r"C:\Program Files\Google\Chrome\Application\chrome_proxy.exe",
+            "--profile-directory=\"Profile 1\" --app-id=\"mjoklplbddabcmpepnokjaffbmgbkkgg\"",
+            None,
+        )
+        .unwrap();
+        assert_eq!(chrome.id, "pwa:chrome:mjoklplbddabcmpepnokjaffbmgbkkgg");
+        assert_eq!(chrome.description.as_deref(), Some("Chrome web app"));
+
+        assert!(web_app(shortcut, r"C:\Tools\app.exe", arguments, None).is_none());
+        assert!(web_app(shortcut, EDGE_PROXY, "--profile-directory=Default", None).is_none());
+    }
+
+    #[test]
+    fn shell_entries_of_web_apps_are_dropped() {
+        let youtube = web_app(
+            Path::new("YouTube.lnk"),
+            EDGE_PROXY,
+            "--app-id=agimnkijcaahngcdmfeangaknmldooml",
+            None,
+        )
+        .unwrap();
+        let mut shell_apps = vec![
+            app(
+                "shell:msedge._crx_agimnkijcaahngcdmfeangaknmldooml",
+                "YouTube",
+                "MSEdge._crx_agimnkijcaahngcdmfeangaknmldooml",
+            ),
+            app("shell:chrome._crx_other", "Other", "Chrome._crx_other"),
+            app("shell:notepad", "Notepad", r"C:\Windows\notepad.exe"),
+        ];
+        drop_shell_duplicates(&mut shell_apps, &[youtube]);
+        let names: Vec<&str> = shell_apps.iter().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, vec!["Other", "Notepad"]);
+    }
+}
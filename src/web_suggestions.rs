@@ -0,0 +1,110 @@
+//! Query suggestions from a search engine's suggest endpoint, for
+//! `web_suggestions`. Google, Bing and DuckDuckGo all answer in the OpenSearch
+//! format, `["query", ["suggestion", ...]]`. Requests give up after a couple of
+//! seconds, and suggestions are kept for the session.
+
+use std::{io::Read, time::Duration};
+
+use serde_json::Value;
+
+use crate::{config::SuggestionEngine, session_cache::SessionCache};
+
+/// Suggestions listed under the web search row.
+pub const MAX_SUGGESTIONS: usize = 5;
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+fn endpoint(engine: SuggestionEngine) -> &'static str {
+    match engine {
+        SuggestionEngine::Google => {
+            "https://suggestqueries.google.com/complete/search?client=firefox&q="
+        }
+        SuggestionEngine::Bing => "https://api.bing.com/osjson.aspx?query=",
+        SuggestionEngine::DuckDuckGo => "https://duckduckgo.com/ac/?type=list&q=",
+    }
+}
+
+pub fn fetch_suggestions(engine: SuggestionEngine, query: &str) -> Result<Vec<String>, String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(TIMEOUT)
+        .user_agent(concat!("egg-cli/", env!("CARGO_PKG_VERSION")))
+        .build();
+    let url = format!("{}{}", endpoint(engine), urlencoding::encode(query));
+    let response = agent
+        .get(&url)
+        .call()
+        .map_err(|err| format!("无法获取搜索建议: {err}"))?;
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_end(&mut body)
+        .map_err(|err| format!("无法读取搜索建议: {err}"))?;
+    let body: Value =
+        serde_json::from_slice(&body).map_err(|err| format!("无法解析搜索建议: {err}"))?;
+    Ok(parse_suggestions(&body, query))
+}
+
+/// The suggestions of an OpenSearch response, up to [`MAX_SUGGESTIONS`] of them,
+/// leaving out the query itself and repeats.
+pub fn parse_suggestions(body: &Value, query: &str) -> Vec<String> {
+    let Some(listed) = body.get(1).and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    let mut suggestions: Vec<String> = Vec::new();
+    for suggestion in listed.iter().filter_map(Value::as_str) {
+        let suggestion = suggestion.trim();
+        if suggestion.is_empty()
+            || suggestion.eq_ignore_ascii_case(query.trim())
+            || suggestions
+                .iter()
+                .any(|kept| kept.eq_ignore_ascii_case(suggestion))
+        {
+            continue;
+        }
+        suggestions.push(suggestion.to_string());
+        if suggestions.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    suggestions
+}
+
+/// Suggestions fetched this session, keyed by engine and query. An empty list
+/// records a query with none or whose fetch failed, so it is not fetched again.
+pub type SuggestionCache = SessionCache<(SuggestionEngine, String), Vec<String>, 128>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions_skip_the_query_and_repeats() {
+        let body = serde_json::json!([
+            "rust",
+            [
+                "rust",
+                "Rust lang",
+                "rust game",
+                "rust lang",
+                " ",
+                "rustup",
+                7,
+                "rust book",
+                "rust-analyzer",
+                "rustc"
+            ]
+        ]);
+        assert_eq!(
+            parse_suggestions(&body, "Rust"),
+            vec![
+                "Rust lang",
+                "rust game",
+                "rustup",
+                "rust book",
+                "rust-analyzer"
+            ]
+        );
+        assert!(parse_suggestions(&serde_json::json!({"error": 1}), "rust").is_empty());
+    }
+}
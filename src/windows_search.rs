@@ -0,0 +1,223 @@
+//! Optional file search through the Windows Search index (the `SystemIndex` catalog).
+//!
+//! The query helper turns the user's text into Windows Search SQL, which is then run
+//! over OLE DB. Documents and emails come back without egg scanning anything itself.
+
+use std::{mem, ptr};
+
+use log::debug;
+use windows::{
+    core::{w, Interface, GUID, PCWSTR},
+    Win32::System::{
+        Com::{CoCreateInstance, CoTaskMemFree, CLSCTX_INPROC_SERVER},
+        Search::{
+            CSearchManager, IAccessor, ICommandText, IDBCreateCommand, IDBCreateSession,
+            IDBInitialize, IDataInitialize, IRowset, ISearchManager, DBACCESSOR_ROWDATA, DBBINDING,
+            DBMEMOWNER_CLIENTOWNED, DBPARAMIO_NOTPARAM, DBPART_LENGTH, DBPART_STATUS, DBPART_VALUE,
+            DBSTATUS_S_OK, DBSTATUS_S_TRUNCATED, DBTYPE_WSTR, HACCESSOR, MSDAINITIALIZE,
+        },
+    },
+};
+
+use crate::{indexer::ComInitGuard, models::IndexedItem};
+
+/// `DBGUID_DEFAULT`: lets the provider pick its own SQL dialect.
+const DBGUID_DEFAULT: GUID = GUID::from_u128(0xc8b521fb_5cf3_11ce_ade5_00aa0044773d);
+const SELECT_COLUMNS: PCWSTR = w!("System.ItemNameDisplay,System.ItemPathDisplay,System.ItemUrl");
+const COLUMN_COUNT: usize = 3;
+/// Longer values are truncated; paths past this length are rare in the index.
+const MAX_VALUE_CHARS: usize = 1024;
+const ROW_BATCH: usize = 32;
+
+#[repr(C)]
+struct Column {
+    status: u32,
+    length: usize,
+    value: [u16; MAX_VALUE_CHARS],
+}
+
+impl Column {
+    fn text(&self) -> String {
+        if self.status != DBSTATUS_S_OK.0 as u32 && self.status != DBSTATUS_S_TRUNCATED.0 as u32 {
+            return String::new();
+        }
+        let chars = (self.length / mem::size_of::<u16>()).min(MAX_VALUE_CHARS - 1);
+        String::from_utf16_lossy(&self.value[..chars])
+    }
+}
+
+type Row = [Column; COLUMN_COUNT];
+
+/// Runs `query` against the Windows Search index and returns up to `limit` items.
+///
+/// Blocks on the indexer service; callers should wrap it in [`crate::provider::with_timeout`].
+pub fn search(query: &str, limit: u32) -> Result<Vec<IndexedItem>, String> {
+    let _com = unsafe { ComInitGuard::new() }.map_err(|err| format!("无法初始化 COM: {err}"))?;
+    let (connection, sql) = build_query(query, limit)?;
+    let rowset =
+        open_rowset(&connection, &sql).map_err(|err| format!("Windows Search 查询失败: {err}"))?;
+    let items = read_rows(&rowset).map_err(|err| format!("无法读取 Windows Search 结果: {err}"))?;
+    debug!(
+        "windows search returned {} results for {query:?}",
+        items.len()
+    );
+    Ok(items)
+}
+
+/// Returns the OLE DB connection string and the SQL generated for `query`.
+fn build_query(query: &str, limit: u32) -> Result<(String, String), String> {
+    unsafe {
+        let manager: ISearchManager = CoCreateInstance(&CSearchManager, None, CLSCTX_INPROC_SERVER)
+            .map_err(|err| format!("Windows Search 不可用: {err}"))?;
+        let helper = manager
+            .GetCatalog(w!("SystemIndex"))
+            .and_then(|catalog| catalog.GetQueryHelper())
+            .map_err(|err| format!("无法打开 Windows Search 索引: {err}"))?;
+        let prepared = helper
+            .SetQuerySelectColumns(SELECT_COLUMNS)
+            .and_then(|_| helper.SetQueryMaxResults(limit.min(i32::MAX as u32) as i32));
+        prepared.map_err(|err| format!("无法设置 Windows Search 查询: {err}"))?;
+
+        let query_wide: Vec<u16> = query.encode_utf16().chain(Some(0)).collect();
+        let sql = helper
+            .GenerateSQLFromUserQuery(PCWSTR(query_wide.as_ptr()))
+            .map_err(|err| format!("无法生成 Windows Search 查询: {err}"))?;
+        let connection = helper
+            .ConnectionString()
+            .map_err(|err| format!("无法获取 Windows Search 连接: {err}"));
+        let sql_text = sql.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(sql.0 as *const _));
+        let connection = connection?;
+        let connection_text = connection.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(connection.0 as *const _));
+        Ok((connection_text, sql_text))
+    }
+}
+
+fn open_rowset(connection: &str, sql: &str) -> windows::core::Result<IRowset> {
+    let connection_wide: Vec<u16> = connection.encode_utf16().chain(Some(0)).collect();
+    let sql_wide: Vec<u16> = sql.encode_utf16().chain(Some(0)).collect();
+    unsafe {
+        let data_init: IDataInitialize =
+            CoCreateInstance(&MSDAINITIALIZE, None, CLSCTX_INPROC_SERVER)?;
+        let mut source = None;
+        data_init.GetDataSource(
+            None,
+            CLSCTX_INPROC_SERVER.0,
+            PCWSTR(connection_wide.as_ptr()),
+            &IDBInitialize::IID,
+            &mut source,
+        )?;
+        let source: IDBInitialize = source
+            .ok_or_else(|| windows::core::Error::from(windows::Win32::Foundation::E_FAIL))?
+            .cast()?;
+        source.Initialize()?;
+
+        let session = source
+            .cast::<IDBCreateSession>()?
+            .CreateSession(None, &IDBCreateCommand::IID)?
+            .cast::<IDBCreateCommand>()?;
+        let command: ICommandText = session.CreateCommand(None, &ICommandText::IID)?.cast()?;
+        command.SetCommandText(&DBGUID_DEFAULT, PCWSTR(sql_wide.as_ptr()))?;
+
+        let mut rowset = None;
+        command.Execute(None, &IRowset::IID, None, None, Some(&mut rowset))?;
+        rowset
+            .ok_or_else(|| windows::core::Error::from(windows::Win32::Foundation::E_FAIL))?
+            .cast()
+    }
+}
+
+fn read_rows(rowset: &IRowset) -> windows::core::Result<Vec<IndexedItem>> {
+    let bindings: Vec<DBBINDING> = (0..COLUMN_COUNT)
+        .map(|index| {
+            let base = index * mem::size_of::<Column>();
+            DBBINDING {
+                iOrdinal: index + 1,
+                obValue: base + mem::offset_of!(Column, value),
+                obLength: base + mem::offset_of!(Column, length),
+                obStatus: base + mem::offset_of!(Column, status),
+                dwPart: (DBPART_VALUE.0 | DBPART_LENGTH.0 | DBPART_STATUS.0) as u32,
+                dwMemOwner: DBMEMOWNER_CLIENTOWNED.0 as u32,
+                eParamIO: DBPARAMIO_NOTPARAM.0 as u32,
+                cbMaxLen: MAX_VALUE_CHARS * mem::size_of::<u16>(),
+                wType: DBTYPE_WSTR.0 as u16,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let accessor_owner: IAccessor = rowset.cast()?;
+    let mut accessor = HACCESSOR::default();
+    unsafe {
+        accessor_owner.CreateAccessor(
+            DBACCESSOR_ROWDATA.0 as u32,
+            bindings.len(),
+            bindings.as_ptr(),
+            mem::size_of::<Row>(),
+            &mut accessor,
+            None,
+        )?;
+    }
+
+    let result = fetch_rows(rowset, accessor);
+    unsafe {
+        let _ = accessor_owner.ReleaseAccessor(accessor, None);
+    }
+    result
+}
+
+fn fetch_rows(rowset: &IRowset, accessor: HACCESSOR) -> windows::core::Result<Vec<IndexedItem>> {
+    let mut items = Vec::new();
+    // SAFETY: `Row` is plain data; all-zero is a valid (empty) row.
+    let mut row: Box<Row> = Box::new(unsafe { mem::zeroed() });
+    let mut handles = [0usize; ROW_BATCH];
+    loop {
+        let mut obtained = 0usize;
+        let mut handles_ptr = handles.as_mut_ptr();
+        // Called through the vtable: the generated wrapper cannot pass a
+        // caller-allocated handle array.
+        let hr = unsafe {
+            (Interface::vtable(rowset).GetNextRows)(
+                Interface::as_raw(rowset),
+                0,
+                0,
+                ROW_BATCH as isize,
+                &mut obtained,
+                &mut handles_ptr,
+            )
+        };
+        hr.ok()?;
+        if obtained == 0 {
+            break;
+        }
+        for &handle in &handles[..obtained] {
+            unsafe {
+                if rowset
+                    .GetData(handle, accessor, ptr::addr_of_mut!(*row).cast())
+                    .is_ok()
+                {
+                    items.push(IndexedItem {
+                        name: row[0].text(),
+                        path: row[1].text(),
+                        url: row[2].text(),
+                    });
+                }
+            }
+        }
+        unsafe {
+            rowset.ReleaseRows(
+                obtained,
+                handles.as_ptr(),
+                ptr::null(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )?;
+        }
+        // DB_S_ENDOFROWSET: fewer rows than requested means the end was reached.
+        if obtained < ROW_BATCH {
+            break;
+        }
+    }
+    Ok(items)
+}
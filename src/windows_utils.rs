@@ -1,6 +1,556 @@
-use std::{ffi::OsStr, os::windows::ffi::OsStrExt};
+use std::{
+    ffi::{c_void, OsStr},
+    mem::size_of,
+    os::windows::ffi::OsStrExt,
+    path::Path,
+    ptr,
+};
+
+use windows::{
+    core::{Interface, PCWSTR, PWSTR},
+    Win32::{
+        Foundation::{CloseHandle, GlobalFree, BOOL, HANDLE, HWND, LPARAM, LUID, TRUE},
+        Graphics::{
+            Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED},
+            Gdi::{
+                DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO,
+                BITMAPINFOHEADER, DIB_RGB_COLORS, HBITMAP, HGDIOBJ,
+            },
+        },
+        Security::{
+            AdjustTokenPrivileges, GetTokenInformation, LookupPrivilegeValueW, TokenElevation,
+            LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED, SE_SHUTDOWN_NAME, TOKEN_ADJUST_PRIVILEGES,
+            TOKEN_ELEVATION, TOKEN_PRIVILEGES, TOKEN_QUERY,
+        },
+        Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES,
+        System::{
+            Com::{CoCreateInstance, CoTaskMemFree, IPersistFile, CLSCTX_INPROC_SERVER, STGM_READ},
+            Console::GetConsoleWindow,
+            DataExchange::{CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData},
+            Diagnostics::ToolHelp::{
+                CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+                TH32CS_SNAPPROCESS,
+            },
+            Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE},
+            Ole::CF_UNICODETEXT,
+            Power::SetSuspendState,
+            Shutdown::{
+                ExitWindowsEx, LockWorkStation, EWX_LOGOFF, EWX_POWEROFF, EWX_REBOOT, EWX_SHUTDOWN,
+                EXIT_WINDOWS_FLAGS, SHTDN_REASON_FLAG_PLANNED, SHTDN_REASON_MAJOR_OTHER,
+            },
+            Threading::{
+                GetCurrentProcess, OpenProcess, OpenProcessToken, QueryFullProcessImageNameW,
+                TerminateProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+                PROCESS_TERMINATE,
+            },
+        },
+        UI::{
+            Input::KeyboardAndMouse::{
+                SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+                KEYEVENTF_KEYUP, VK_MENU,
+            },
+            Shell::{
+                Common::ITEMIDLIST, ExtractIconExW, IShellLinkW, SHEmptyRecycleBinW,
+                SHGetFileInfoW, SHParseDisplayName, ShellLink, SHFILEINFOW, SHGFI_ICON,
+                SHGFI_LARGEICON, SHGFI_PIDL,
+            },
+            WindowsAndMessaging::{
+                DestroyIcon, EnumWindows, GetIconInfo, GetWindow, GetWindowLongW, GetWindowTextW,
+                GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, SetForegroundWindow,
+                ShowWindow, GWL_EXSTYLE, GW_OWNER, HICON, ICONINFO, SW_RESTORE, WS_EX_TOOLWINDOW,
+            },
+        },
+    },
+};
+use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+use crate::{
+    icons::encode_ico,
+    indexer::ComInitGuard,
+    models::{ProcessInfo, WindowInfo},
+    system_commands::SystemCommand,
+    wsl::WslDistro,
+};
+
+/// Where WSL registers each distribution, one subkey per distribution id.
+const LXSS_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Lxss";
+/// The `State` of a distribution that finished installing.
+const WSL_STATE_INSTALLED: u32 = 1;
 
 /// Converts an [`OsStr`] into a null-terminated wide string buffer suitable for Win32 APIs.
 pub(crate) fn os_str_to_wide(value: &OsStr) -> Vec<u16> {
     value.encode_wide().chain(Some(0)).collect()
 }
+
+/// Performs a built-in system action.
+pub(crate) fn run_system_command(command: SystemCommand) -> Result<(), String> {
+    match command {
+        SystemCommand::Lock => {
+            unsafe { LockWorkStation() }.map_err(|err| format!("无法锁定电脑: {err}"))
+        }
+        SystemCommand::Sleep => {
+            if unsafe { SetSuspendState(false, false, false) }.as_bool() {
+                Ok(())
+            } else {
+                Err("无法进入睡眠".into())
+            }
+        }
+        SystemCommand::Shutdown => exit_windows(EWX_SHUTDOWN | EWX_POWEROFF),
+        SystemCommand::Restart => exit_windows(EWX_REBOOT),
+        SystemCommand::SignOut => exit_windows(EWX_LOGOFF),
+        SystemCommand::EmptyRecycleBin => {
+            // Flags 0 keeps the standard Windows confirmation dialog.
+            unsafe { SHEmptyRecycleBinW(HWND::default(), PCWSTR::null(), 0) }
+                .map_err(|err| format!("无法清空回收站: {err}"))
+        }
+        SystemCommand::OpenSettings => open::that("ms-settings:").map_err(|err| err.to_string()),
+    }
+}
+
+/// Places `text` on the clipboard as Unicode text.
+pub(crate) fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let wide: Vec<u16> = text.encode_utf16().chain(Some(0)).collect();
+    unsafe {
+        OpenClipboard(HWND::default()).map_err(|err| format!("无法打开剪贴板: {err}"))?;
+        let result = (|| {
+            EmptyClipboard()?;
+            let memory = GlobalAlloc(GMEM_MOVEABLE, wide.len() * std::mem::size_of::<u16>())?;
+            let buffer = GlobalLock(memory) as *mut u16;
+            if buffer.is_null() {
+                let _ = GlobalFree(memory);
+                return Err(windows::core::Error::from_win32());
+            }
+            std::ptr::copy_nonoverlapping(wide.as_ptr(), buffer, wide.len());
+            let _ = GlobalUnlock(memory);
+            // Once set, the clipboard owns the memory.
+            if let Err(err) = SetClipboardData(u32::from(CF_UNICODETEXT.0), HANDLE(memory.0)) {
+                let _ = GlobalFree(memory);
+                return Err(err);
+            }
+            Ok(())
+        })();
+        let _ = CloseClipboard();
+        result.map_err(|err| format!("无法复制到剪贴板: {err}"))
+    }
+}
+
+/// What a `.lnk` shortcut starts.
+pub(crate) struct ShellLinkInfo {
+    pub target: String,
+    pub arguments: String,
+    pub working_directory: Option<String>,
+}
+
+/// Target path of a `.lnk` shortcut, or `None` when it does not point at a file
+/// system path. COM must be initialized on the calling thread.
+pub(crate) fn resolve_shell_link(path: &Path) -> Option<String> {
+    read_shell_link(path).map(|link| link.target)
+}
+
+/// Target, arguments and working directory of a `.lnk` shortcut, or `None` when
+/// it does not point at a file system path. COM must be initialized on the
+/// calling thread.
+pub(crate) fn read_shell_link(path: &Path) -> Option<ShellLinkInfo> {
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER).ok()?;
+        let file: IPersistFile = link.cast().ok()?;
+        let wide = os_str_to_wide(path.as_os_str());
+        file.Load(PCWSTR(wide.as_ptr()), STGM_READ).ok()?;
+        let mut buffer = [0u16; 1024];
+        link.GetPath(&mut buffer, ptr::null_mut(), 0).ok()?;
+        let target = wide_to_string(&buffer);
+        if target.trim().is_empty() {
+            return None;
+        }
+        // Web app shortcuts carry long argument lists, so this buffer is the
+        // documented maximum rather than MAX_PATH.
+        let mut arguments = vec![0u16; 32 * 1024];
+        let arguments = match link.GetArguments(&mut arguments) {
+            Ok(()) => wide_to_string(&arguments),
+            Err(_) => String::new(),
+        };
+        buffer.fill(0);
+        let working_directory = link
+            .GetWorkingDirectory(&mut buffer)
+            .ok()
+            .map(|()| wide_to_string(&buffer))
+            .filter(|dir| !dir.trim().is_empty());
+        Some(ShellLinkInfo {
+            target,
+            arguments,
+            working_directory,
+        })
+    }
+}
+
+/// The WSL distributions installed for the current user. Reading the registry is
+/// much faster than `wsl --list`, which starts the WSL service.
+pub(crate) fn wsl_distributions() -> Vec<WslDistro> {
+    let Ok(lxss) = RegKey::predef(HKEY_CURRENT_USER).open_subkey(LXSS_KEY) else {
+        return Vec::new();
+    };
+    let default: Option<String> = lxss.get_value("DefaultDistribution").ok();
+    lxss.enum_keys()
+        .flatten()
+        .filter_map(|id| {
+            let key = lxss.open_subkey(&id).ok()?;
+            let state = key
+                .get_value::<u32, _>("State")
+                .unwrap_or(WSL_STATE_INSTALLED);
+            if state != WSL_STATE_INSTALLED {
+                return None;
+            }
+            Some(WslDistro {
+                name: key.get_value("DistributionName").ok()?,
+                default: default
+                    .as_deref()
+                    .is_some_and(|default| default.eq_ignore_ascii_case(&id)),
+            })
+        })
+        .collect()
+}
+
+/// A NUL-terminated UTF-16 buffer as a string.
+fn wide_to_string(buffer: &[u16]) -> String {
+    let len = buffer
+        .iter()
+        .position(|&ch| ch == 0)
+        .unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+/// Top-level windows as Alt+Tab lists them, front to back: visible, titled,
+/// unowned and not tool windows. Cloaked windows (suspended Store apps, windows
+/// on other virtual desktops) and egg's own console are left out.
+pub(crate) fn list_windows() -> Result<Vec<WindowInfo>, String> {
+    let mut handles: Vec<HWND> = Vec::new();
+    unsafe {
+        EnumWindows(
+            Some(collect_window),
+            LPARAM(&mut handles as *mut Vec<HWND> as isize),
+        )
+    }
+    .map_err(|err| format!("无法列出窗口: {err}"))?;
+    let console = unsafe { GetConsoleWindow() };
+    Ok(handles
+        .into_iter()
+        .filter(|&hwnd| hwnd != console && is_switchable(hwnd))
+        .filter_map(|hwnd| {
+            Some(WindowInfo {
+                id: hwnd.0 as isize,
+                title: window_title(hwnd)?,
+                process: window_process(hwnd).unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+unsafe extern "system" fn collect_window(hwnd: HWND, handles: LPARAM) -> BOOL {
+    (*(handles.0 as *mut Vec<HWND>)).push(hwnd);
+    TRUE
+}
+
+fn is_switchable(hwnd: HWND) -> bool {
+    unsafe {
+        if !IsWindowVisible(hwnd).as_bool() || GetWindow(hwnd, GW_OWNER).is_ok() {
+            return false;
+        }
+        if GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0 {
+            return false;
+        }
+        let mut cloaked = 0u32;
+        let read = DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut u32 as *mut c_void,
+            size_of::<u32>() as u32,
+        );
+        read.is_err() || cloaked == 0
+    }
+}
+
+fn window_title(hwnd: HWND) -> Option<String> {
+    let mut buffer = [0u16; 512];
+    let len = unsafe { GetWindowTextW(hwnd, &mut buffer) }.max(0) as usize;
+    let title = String::from_utf16_lossy(&buffer[..len]);
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// File name of the program that owns `hwnd`, e.g. `chrome.exe`.
+fn window_process(hwnd: HWND) -> Option<String> {
+    let mut process_id = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut process_id)) };
+    process_file_name(process_id)
+}
+
+/// File name of the program `pid` runs, e.g. `chrome.exe`.
+fn process_file_name(pid: u32) -> Option<String> {
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+}
+
+/// Brings the window `id` from [`list_windows`] to the front, restoring it first
+/// when minimized.
+pub(crate) fn focus_window(id: isize) -> Result<(), String> {
+    let hwnd = HWND(id as *mut c_void);
+    unsafe {
+        if !IsWindow(hwnd).as_bool() {
+            return Err("窗口已关闭".into());
+        }
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+        // Only the process that received the last input may take the foreground;
+        // a synthetic Alt press makes that egg.
+        let alt = |flags| INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: VK_MENU,
+                    dwFlags: flags,
+                    ..Default::default()
+                },
+            },
+        };
+        let inputs = [alt(KEYBD_EVENT_FLAGS(0)), alt(KEYEVENTF_KEYUP)];
+        SendInput(&inputs, size_of::<INPUT>() as i32);
+        if SetForegroundWindow(hwnd).as_bool() {
+            Ok(())
+        } else {
+            Err("无法切换到该窗口".into())
+        }
+    }
+}
+
+/// Running processes, in the order Windows lists them, leaving out the idle
+/// process and egg itself.
+pub(crate) fn list_processes() -> Result<Vec<ProcessInfo>, String> {
+    let own_pid = std::process::id();
+    let mut processes = Vec::new();
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)
+            .map_err(|err| format!("无法列出进程: {err}"))?;
+        let mut entry = PROCESSENTRY32W {
+            dwSize: size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut next = Process32FirstW(snapshot, &mut entry);
+        while next.is_ok() {
+            let pid = entry.th32ProcessID;
+            if pid != 0 && pid != own_pid {
+                let len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&ch| ch == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                processes.push(ProcessInfo {
+                    pid,
+                    name: String::from_utf16_lossy(&entry.szExeFile[..len]),
+                    elevated: is_elevated(pid),
+                });
+            }
+            next = Process32NextW(snapshot, &mut entry);
+        }
+        let _ = CloseHandle(snapshot);
+    }
+    Ok(processes)
+}
+
+/// Whether `pid` runs with an elevated token. Processes egg may not even query,
+/// such as services and other users' programs, count as elevated.
+fn is_elevated(pid: u32) -> bool {
+    unsafe {
+        let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+            return true;
+        };
+        let mut token = HANDLE::default();
+        let opened = OpenProcessToken(process, TOKEN_QUERY, &mut token);
+        let _ = CloseHandle(process);
+        if opened.is_err() {
+            return true;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut len = 0u32;
+        let read = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut c_void),
+            size_of::<TOKEN_ELEVATION>() as u32,
+            &mut len,
+        );
+        let _ = CloseHandle(token);
+        read.is_err() || elevation.TokenIsElevated != 0
+    }
+}
+
+/// Ends `pid` from [`list_processes`], unless the pid now belongs to another
+/// program than `name`.
+pub(crate) fn kill_process(pid: u32, name: &str) -> Result<(), String> {
+    if process_file_name(pid).is_some_and(|running| !running.eq_ignore_ascii_case(name)) {
+        return Err(format!("{name} 已经退出"));
+    }
+    unsafe {
+        let process = OpenProcess(PROCESS_TERMINATE, false, pid)
+            .map_err(|err| format!("无法结束 {name}: {err}"))?;
+        let result = TerminateProcess(process, 1);
+        let _ = CloseHandle(process);
+        result.map_err(|err| format!("无法结束 {name}: {err}"))
+    }
+}
+
+fn exit_windows(flags: EXIT_WINDOWS_FLAGS) -> Result<(), String> {
+    if flags != EWX_LOGOFF {
+        enable_shutdown_privilege()?;
+    }
+    unsafe { ExitWindowsEx(flags, SHTDN_REASON_MAJOR_OTHER | SHTDN_REASON_FLAG_PLANNED) }
+        .map_err(|err| format!("无法关闭或重启系统: {err}"))
+}
+
+/// Shutdown and restart require `SeShutdownPrivilege` to be enabled on the process token.
+fn enable_shutdown_privilege() -> Result<(), String> {
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(
+            GetCurrentProcess(),
+            TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY,
+            &mut token,
+        )
+        .map_err(|err| format!("无法打开进程令牌: {err}"))?;
+
+        let mut luid = LUID::default();
+        let result = LookupPrivilegeValueW(PCWSTR::null(), SE_SHUTDOWN_NAME, &mut luid)
+            .and_then(|_| {
+                let privileges = TOKEN_PRIVILEGES {
+                    PrivilegeCount: 1,
+                    Privileges: [LUID_AND_ATTRIBUTES {
+                        Luid: luid,
+                        Attributes: SE_PRIVILEGE_ENABLED,
+                    }],
+                };
+                AdjustTokenPrivileges(token, false, Some(&privileges), 0, None, None)
+            })
+            .map_err(|err| format!("无法获取关机权限: {err}"));
+        let _ = CloseHandle(token);
+        result
+    }
+}
+
+/// The icon at `index` in an `.exe`, `.dll` or `.ico` file, as `.ico` file bytes.
+pub fn file_icon(file: &str, index: i32) -> Option<Vec<u8>> {
+    let wide = os_str_to_wide(OsStr::new(file));
+    let mut icon = HICON::default();
+    let found = unsafe { ExtractIconExW(PCWSTR(wide.as_ptr()), index, Some(&mut icon), None, 1) };
+    if found == 0 || icon.is_invalid() {
+        return None;
+    }
+    icon_bytes(icon)
+}
+
+/// The icon Explorer shows for a file, folder or `shell:AppsFolder\...` item, as
+/// `.ico` file bytes.
+pub fn shell_icon(path: &str) -> Option<Vec<u8>> {
+    let _com_guard = unsafe { ComInitGuard::new() }.ok()?;
+    let wide = os_str_to_wide(OsStr::new(path));
+    let mut pidl: *mut ITEMIDLIST = ptr::null_mut();
+    unsafe { SHParseDisplayName(PCWSTR(wide.as_ptr()), None, &mut pidl, 0, None) }.ok()?;
+    let mut info = SHFILEINFOW::default();
+    let found = unsafe {
+        SHGetFileInfoW(
+            PCWSTR(pidl as *const u16),
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            Some(&mut info),
+            size_of::<SHFILEINFOW>() as u32,
+            SHGFI_PIDL | SHGFI_ICON | SHGFI_LARGEICON,
+        )
+    };
+    unsafe { CoTaskMemFree(Some(pidl as *const c_void)) };
+    if found == 0 || info.hIcon.is_invalid() {
+        return None;
+    }
+    icon_bytes(info.hIcon)
+}
+
+/// Reads the icon's colour bitmap and destroys the icon.
+fn icon_bytes(icon: HICON) -> Option<Vec<u8>> {
+    let mut info = ICONINFO::default();
+    let read = unsafe { GetIconInfo(icon, &mut info) };
+    let _ = unsafe { DestroyIcon(icon) };
+    read.ok()?;
+    let pixels = bitmap_pixels(info.hbmColor);
+    for bitmap in [info.hbmColor, info.hbmMask] {
+        if !bitmap.is_invalid() {
+            let _ = unsafe { DeleteObject(HGDIOBJ(bitmap.0)) };
+        }
+    }
+    let (width, height, mut pixels) = pixels?;
+    // Icons from before alpha channels leave it at zero; show them opaque.
+    if pixels.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+        pixels.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 255);
+    }
+    Some(encode_ico(width, height, &pixels))
+}
+
+/// 32-bit BGRA rows, bottom row first.
+fn bitmap_pixels(bitmap: HBITMAP) -> Option<(u32, u32, Vec<u8>)> {
+    if bitmap.is_invalid() {
+        return None;
+    }
+    let mut header = BITMAP::default();
+    let size = size_of::<BITMAP>() as i32;
+    if unsafe {
+        GetObjectW(
+            HGDIOBJ(bitmap.0),
+            size,
+            Some(&mut header as *mut BITMAP as *mut c_void),
+        )
+    } == 0
+    {
+        return None;
+    }
+    let (width, height) = (header.bmWidth, header.bmHeight);
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+    let mut info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: height,
+            biPlanes: 1,
+            biBitCount: 32,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    let dc = unsafe { GetDC(None) };
+    let lines = unsafe {
+        GetDIBits(
+            dc,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr().cast()),
+            &mut info,
+            DIB_RGB_COLORS,
+        )
+    };
+    unsafe { ReleaseDC(None, dc) };
+    (lines == height).then_some((width as u32, height as u32, pixels))
+}
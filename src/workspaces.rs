@@ -0,0 +1,114 @@
+//! Workspaces: named groups of apps, bookmarks, links and files under
+//! `workspaces` in `settings.json`, launched together by typing the name in full.
+//! A member is written as a URL, an absolute path, an app's id or name, or a
+//! bookmark's title, and is looked up in that order.
+
+use crate::{
+    bookmarks::BookmarkEntry, config::AppConfig, models::ApplicationInfo, search_core::typed_path,
+    state::PendingAction,
+};
+
+/// The workspace `query` spells out in full, ignoring case.
+pub fn find_workspace<'a>(query: &str, config: &'a AppConfig) -> Option<(&'a str, &'a [String])> {
+    config
+        .workspaces
+        .iter()
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(query.trim()))
+        .map(|(name, members)| (name.as_str(), members.as_slice()))
+}
+
+/// A workspace's members as labels and actions, in order, and the members that
+/// match nothing.
+pub fn resolve_members<'a>(
+    members: &'a [String],
+    app_index: &[ApplicationInfo],
+    bookmark_index: &[BookmarkEntry],
+) -> (Vec<(String, PendingAction)>, Vec<&'a str>) {
+    let mut resolved = Vec::new();
+    let mut missing = Vec::new();
+    for member in members.iter().map(|member| member.trim()) {
+        match resolve_member(member, app_index, bookmark_index) {
+            Some(found) => resolved.push(found),
+            None if !member.is_empty() => missing.push(member),
+            None => {}
+        }
+    }
+    (resolved, missing)
+}
+
+fn resolve_member(
+    member: &str,
+    app_index: &[ApplicationInfo],
+    bookmark_index: &[BookmarkEntry],
+) -> Option<(String, PendingAction)> {
+    if member.starts_with("http://") || member.starts_with("https://") {
+        let host = member.split_once("://")?.1.split('/').next()?;
+        return Some((host.to_string(), PendingAction::Url(member.to_string())));
+    }
+    if let Some(path) = typed_path(member).or(member.starts_with('/').then_some(member)) {
+        let name = path
+            .trim_end_matches(['\\', '/'])
+            .rsplit(['\\', '/'])
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(path);
+        let action = PendingAction::Path {
+            path: path.to_string(),
+            reveal: false,
+        };
+        return Some((name.to_string(), action));
+    }
+    let app = app_index.iter().find(|app| app.id == member).or_else(|| {
+        app_index
+            .iter()
+            .find(|app| app.name.eq_ignore_ascii_case(member))
+    });
+    if let Some(app) = app {
+        return Some((app.name.clone(), PendingAction::Application(app.clone())));
+    }
+    let bookmark = bookmark_index
+        .iter()
+        .find(|bookmark| bookmark.id == member || bookmark.title.eq_ignore_ascii_case(member))?;
+    Some((
+        bookmark.title.clone(),
+        PendingAction::Bookmark(bookmark.clone()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixture_apps, fixture_bookmarks};
+
+    #[test]
+    fn members_resolve_to_apps_bookmarks_links_and_paths() {
+        let members: Vec<String> = [
+            "visual studio code",
+            "shell:notion",
+            "GitHub",
+            "https://jira.example.com/board",
+            r"D:\projects\egg",
+            "Teams",
+            " ",
+        ]
+        .map(String::from)
+        .to_vec();
+        let (resolved, missing) = resolve_members(&members, &fixture_apps(), &fixture_bookmarks());
+        let labels: Vec<&str> = resolved.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Visual Studio Code",
+                "Notion",
+                "GitHub",
+                "jira.example.com",
+                "egg"
+            ]
+        );
+        assert!(matches!(resolved[2].1, PendingAction::Bookmark(_)));
+        assert!(
+            matches!(&resolved[3].1, PendingAction::Url(url) if url == "https://jira.example.com/board")
+        );
+        assert_eq!(missing, vec!["Teams"]);
+    }
+}
@@ -0,0 +1,117 @@
+//! Windows Subsystem for Linux. Every installed distribution is indexed and opens
+//! a shell in its home folder, and `wsl <command>` runs a command in the default
+//! distribution through [`CommandShell::Wsl`](crate::config::CommandShell). The
+//! distributions are read from the registry by `windows_utils`.
+
+#[cfg(windows)]
+use crate::windows_utils::wsl_distributions;
+use crate::{
+    execute::quote_argument,
+    models::{AppType, ApplicationInfo},
+};
+
+/// Ids of WSL distributions, followed by the distribution's name.
+pub const WSL_ID_PREFIX: &str = "wsl:";
+/// Whether this platform has WSL, and so the `wsl ` prefix.
+pub const WSL_AVAILABLE: bool = cfg!(windows);
+const WSL_KEYWORD: &str = "wsl";
+
+/// An installed distribution.
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WslDistro {
+    pub name: String,
+    /// The one `wsl` without `--distribution` starts.
+    pub default: bool,
+}
+
+/// Returns the command when `query` starts with `wsl ` and has text after it.
+pub fn wsl_query(query: &str) -> Option<&str> {
+    let (head, rest) = query.trim_start().split_once(char::is_whitespace)?;
+    let command = rest.trim();
+    (head.eq_ignore_ascii_case(WSL_KEYWORD) && !command.is_empty()).then_some(command)
+}
+
+#[cfg(windows)]
+pub fn enumerate_wsl_distros() -> Vec<ApplicationInfo> {
+    distro_entries(wsl_distributions())
+}
+
+#[cfg(not(windows))]
+pub fn enumerate_wsl_distros() -> Vec<ApplicationInfo> {
+    Vec::new()
+}
+
+pub fn is_wsl_distro(app: &ApplicationInfo) -> bool {
+    app.id.starts_with(WSL_ID_PREFIX)
+}
+
+/// Index entries that open a shell in each distribution. Docker Desktop's own
+/// distributions only run its engine, so they are left out.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn distro_entries(distros: Vec<WslDistro>) -> Vec<ApplicationInfo> {
+    distros
+        .into_iter()
+        .filter(|distro| !distro.name.starts_with("docker-desktop"))
+        .map(|distro| ApplicationInfo {
+            id: format!("{WSL_ID_PREFIX}{}", distro.name.to_lowercase()),
+            path: "wsl.exe".to_string(),
+            source_path: None,
+            app_type: AppType::Win32,
+            description: Some(if distro.default {
+                "WSL distribution · default".to_string()
+            } else {
+                "WSL distribution".to_string()
+            }),
+            keywords: vec!["wsl".to_string(), "linux".to_string()],
+            pinyin_index: None,
+            working_directory: None,
+            arguments: Some(format!(
+                "--distribution {} --cd ~",
+                quote_argument(&distro.name)
+            )),
+            icon: None,
+            name: distro.name,
+            folded: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distributions_open_a_shell_and_wsl_runs_commands() {
+        assert_eq!(wsl_query("wsl  ls -la ~"), Some("ls -la ~"));
+        assert_eq!(wsl_query("WSL uname"), Some("uname"));
+        assert_eq!(wsl_query("wsl"), None);
+        assert_eq!(wsl_query("wslconfig /l"), None);
+
+        let distro = |name: &str, default: bool| WslDistro {
+            name: name.to_string(),
+            default,
+        };
+        let entries = distro_entries(vec![
+            distro("Ubuntu-22.04", true),
+            distro("docker-desktop", false),
+            distro("Debian", false),
+        ]);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "wsl:ubuntu-22.04");
+        assert_eq!(entries[0].name, "Ubuntu-22.04");
+        assert_eq!(entries[0].path, "wsl.exe");
+        assert_eq!(
+            entries[0].arguments,
+            Some(format!(
+                "--distribution {} --cd ~",
+                quote_argument("Ubuntu-22.04")
+            ))
+        );
+        assert_eq!(
+            entries[0].description.as_deref(),
+            Some("WSL distribution · default")
+        );
+        assert!(is_wsl_distro(&entries[1]));
+    }
+}